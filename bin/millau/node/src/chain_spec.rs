@@ -16,8 +16,8 @@
 
 use bp_millau::derive_account_from_rialto_id;
 use millau_runtime::{
-	AccountId, AuraConfig, BalancesConfig, BridgeWestendGrandpaConfig, GenesisConfig, GrandpaConfig, SessionConfig,
-	SessionKeys, Signature, SudoConfig, SystemConfig, WASM_BINARY,
+	AccountId, AuraConfig, BalancesConfig, BridgeLaneRegistryConfig, BridgeWestendGrandpaConfig, GenesisConfig,
+	GrandpaConfig, SessionConfig, SessionKeys, Signature, SudoConfig, SystemConfig, WASM_BINARY,
 };
 use sp_consensus_aura::sr25519::AuthorityId as AuraId;
 use sp_core::{sr25519, Pair, Public};
@@ -69,8 +69,8 @@ impl Alternative {
 	pub(crate) fn load(self) -> ChainSpec {
 		let properties = Some(
 			serde_json::json!({
-				"tokenDecimals": 9,
-				"tokenSymbol": "MLAU"
+				"tokenDecimals": bp_chain_registry::MILLAU.token_decimals,
+				"tokenSymbol": bp_chain_registry::MILLAU.token_symbol
 			})
 			.as_object()
 			.expect("Map given; qed")
@@ -194,13 +194,31 @@ fn testnet_genesis(
 		grandpa: GrandpaConfig {
 			authorities: Vec::new(),
 		},
-		sudo: SudoConfig { key: root_key },
+		sudo: SudoConfig { key: root_key.clone() },
 		session: SessionConfig {
 			keys: initial_authorities
 				.iter()
 				.map(|x| (x.0.clone(), x.0.clone(), session_keys(x.1.clone(), x.2.clone())))
 				.collect::<Vec<_>>(),
 		},
+		bridge_lane_registry: BridgeLaneRegistryConfig {
+			lanes: vec![
+				bp_messages::LaneId::Legacy([0, 0, 0, 0]),
+				bp_messages::LaneId::Legacy([0, 0, 0, 1]),
+			]
+			.into_iter()
+			.map(|lane| {
+				(
+					lane,
+					pallet_bridge_lane_registry::LaneInfo {
+						owner: root_key.clone(),
+						allowed_senders: pallet_bridge_lane_registry::AllowedSenders::Any,
+						minimal_fee: 0,
+					},
+				)
+			})
+			.collect(),
+		},
 		bridge_westend_grandpa: BridgeWestendGrandpaConfig {
 			// for our deployments to avoid multiple same-nonces transactions:
 			// //Alice is already used to initialize Rialto<->Millau bridge
@@ -40,10 +40,10 @@ use pallet_transaction_payment::{FeeDetails, Multiplier, RuntimeDispatchInfo};
 use sp_api::impl_runtime_apis;
 use sp_consensus_aura::sr25519::AuthorityId as AuraId;
 use sp_core::{crypto::KeyTypeId, OpaqueMetadata};
-use sp_runtime::traits::{Block as BlockT, IdentityLookup, NumberFor, OpaqueKeys};
+use sp_runtime::traits::{Block as BlockT, Convert, IdentityLookup, NumberFor, OpaqueKeys};
 use sp_runtime::{
 	create_runtime_str, generic, impl_opaque_keys,
-	transaction_validity::{TransactionSource, TransactionValidity},
+	transaction_validity::{TransactionLongevity, TransactionPriority, TransactionSource, TransactionValidity},
 	ApplyExtrinsicResult, FixedPointNumber, MultiSignature, MultiSigner, Perquintill,
 };
 use sp_std::prelude::*;
@@ -61,6 +61,7 @@ pub use frame_support::{
 
 pub use frame_system::Call as SystemCall;
 pub use pallet_balances::Call as BalancesCall;
+pub use pallet_bridge_chunked_dispatch::Call as ChunkedDispatchCall;
 pub use pallet_bridge_grandpa::Call as BridgeGrandpaRialtoCall;
 pub use pallet_bridge_grandpa::Call as BridgeGrandpaWestendCall;
 pub use pallet_bridge_messages::Call as MessagesCall;
@@ -151,7 +152,7 @@ parameter_types! {
 		read: 60_000_000, // ~0.06 ms = ~60 µs
 		write: 200_000_000, // ~0.2 ms = 200 µs
 	};
-	pub const SS58Prefix: u8 = 60;
+	pub const SS58Prefix: u8 = bp_chain_registry::MILLAU.ss58_prefix as u8;
 }
 
 impl frame_system::Config for Runtime {
@@ -209,6 +210,24 @@ impl pallet_randomness_collective_flip::Config for Runtime {}
 impl pallet_aura::Config for Runtime {
 	type AuthorityId = AuraId;
 }
+
+parameter_types! {
+	pub const MaxFailedBridgeMessages: u32 = 1024;
+	pub const BridgedMessagePayloadHashingByteWeight: Weight = 2_000;
+	pub const MaxDispatchedBridgeMessages: u32 = 4096;
+	pub const BridgedCallDepositPerConsumer: Balance = 1_000_000;
+	/// Account that is sending dispatch acknowledgements to Rialto.
+	pub AcknowledgementAccount: AccountId = bp_millau::AccountIdConverter::convert(
+		pallet_bridge_dispatch::acknowledgement_account_id(bp_runtime::MILLAU_CHAIN_ID),
+	);
+	/// Account that is dispatching dispatch acknowledgements from Rialto.
+	pub RialtoAcknowledgementAccount: AccountId = bp_millau::derive_account_from_rialto_id(
+		bp_runtime::SourceAccount::Account(bp_rialto::AccountIdConverter::convert(
+			pallet_bridge_dispatch::acknowledgement_account_id(bp_runtime::RIALTO_CHAIN_ID),
+		)),
+	);
+}
+
 impl pallet_bridge_dispatch::Config for Runtime {
 	type Event = Event;
 	type MessageId = (bp_messages::LaneId, bp_messages::MessageNonce);
@@ -219,6 +238,24 @@ impl pallet_bridge_dispatch::Config for Runtime {
 	type TargetChainAccountPublic = MultiSigner;
 	type TargetChainSignature = MultiSignature;
 	type AccountIdConverter = bp_millau::AccountIdConverter;
+	// only storage, guarded by consumer references, is charged. Other storage isn't filtered in this test runtime
+	type StorageDepositMeter =
+		pallet_bridge_dispatch::ConsumerReferencesDepositMeter<Runtime, Balances, BridgedCallDepositPerConsumer>;
+	// XCM messages are not supported by this runtime
+	type XcmExecutor = ();
+	type AcknowledgementSender = bridge_runtime_common::messages_acknowledgements::MessagesPalletAcknowledgementSender<
+		Runtime,
+		WithRialtoMessagesInstance,
+		AcknowledgementAccount,
+		crate::rialto_messages::ToRialtoAcknowledgementMessageBuilder,
+	>;
+	type AcknowledgementOrigin =
+		pallet_bridge_dispatch::EnsureAcknowledgementAccount<AccountId, RialtoAcknowledgementAccount, RialtoChainId>;
+	type MaxFailedMessages = MaxFailedBridgeMessages;
+	type PayloadHashingByteWeight = BridgedMessagePayloadHashingByteWeight;
+	type RetryDispatchFeePayment = bridge_runtime_common::messages::target::WithdrawRetryDispatchFee<Runtime, Balances>;
+	type MaxDispatchedMessages = MaxDispatchedBridgeMessages;
+	type DispatchAsOrigin = OriginCaller;
 }
 
 impl pallet_grandpa::Config for Runtime {
@@ -328,13 +365,32 @@ parameter_types! {
 	// Assuming the worst case of every header being finalized, we will keep headers for at least a
 	// week.
 	pub const HeadersToKeep: u32 = 7 * bp_millau::DAYS as u32;
+	// Number of obsolete headers to prune at the beginning of every block.
+	pub const MaxHeadersToPruneInBlock: u32 = 8;
+	// Number of authority set changes to keep.
+	pub const AuthoritySetChangesToKeep: u32 = 64;
+	// Number of previous authority sets, which justifications are still accepted.
+	pub const AuthoritySetsToKeep: u32 = 4;
+	// Rialto headers may be imported using ancestry proofs.
+	pub const AcceptRialtoAncestryProofs: bool = true;
+	// Westend headers may only be imported using finality proofs.
+	pub const AcceptWestendAncestryProofs: bool = false;
+	// Mandatory headers are rare (once per session), so single free header per block is enough.
+	pub const FreeMandatoryHeadersPerBlock: u32 = 1;
 }
 
 pub type RialtoGrandpaInstance = ();
 impl pallet_bridge_grandpa::Config for Runtime {
+	type Event = Event;
 	type BridgedChain = bp_rialto::Rialto;
 	type MaxRequests = MaxRequests;
 	type HeadersToKeep = HeadersToKeep;
+	type MaxHeadersToPruneInBlock = MaxHeadersToPruneInBlock;
+	type AuthoritySetChangesToKeep = AuthoritySetChangesToKeep;
+	type AuthoritySetsToKeep = AuthoritySetsToKeep;
+	type AcceptAncestryProofs = AcceptRialtoAncestryProofs;
+	type FreeMandatoryHeadersPerBlock = FreeMandatoryHeadersPerBlock;
+	type OnEquivocation = ();
 
 	// TODO [#391]: Use weights generated for the Millau runtime instead of Rialto ones.
 	type WeightInfo = pallet_bridge_grandpa::weights::RialtoWeight<Runtime>;
@@ -342,9 +398,16 @@ impl pallet_bridge_grandpa::Config for Runtime {
 
 pub type WestendGrandpaInstance = pallet_bridge_grandpa::Instance1;
 impl pallet_bridge_grandpa::Config<WestendGrandpaInstance> for Runtime {
+	type Event = Event;
 	type BridgedChain = bp_westend::Westend;
 	type MaxRequests = MaxRequests;
 	type HeadersToKeep = HeadersToKeep;
+	type MaxHeadersToPruneInBlock = MaxHeadersToPruneInBlock;
+	type AuthoritySetChangesToKeep = AuthoritySetChangesToKeep;
+	type AuthoritySetsToKeep = AuthoritySetsToKeep;
+	type AcceptAncestryProofs = AcceptWestendAncestryProofs;
+	type FreeMandatoryHeadersPerBlock = FreeMandatoryHeadersPerBlock;
+	type OnEquivocation = ();
 
 	// TODO [#391]: Use weights generated for the Millau runtime instead of Rialto ones.
 	type WeightInfo = pallet_bridge_grandpa::weights::RialtoWeight<Runtime>;
@@ -358,10 +421,22 @@ parameter_types! {
 		bp_millau::MAX_UNREWARDED_RELAYER_ENTRIES_AT_INBOUND_LANE;
 	pub const MaxUnconfirmedMessagesAtInboundLane: bp_messages::MessageNonce =
 		bp_millau::MAX_UNCONFIRMED_MESSAGES_AT_INBOUND_LANE;
+	pub const MaxConfirmedRelayerEntriesToPrune: bp_messages::MessageNonce = 8;
+	pub const MaxMessageEventsInDeliveryTransaction: bp_messages::MessageNonce = 16;
 	// `IdentityFee` is used by Millau => we may use weight directly
 	pub const GetDeliveryConfirmationTransactionFee: Balance =
 		bp_millau::MAX_SINGLE_MESSAGE_DELIVERY_CONFIRMATION_TX_WEIGHT as _;
 	pub const RootAccountForPayments: Option<AccountId> = None;
+	pub const TreasuryAccountForPayments: Option<AccountId> = None;
+	pub const BridgedMaxUnrewardedRelayerEntriesAtInboundLane: bp_messages::MessageNonce =
+		bp_rialto::MAX_UNREWARDED_RELAYER_ENTRIES_AT_INBOUND_LANE;
+	pub const StalledConfirmationPeriod: BlockNumber = bp_millau::HOURS;
+	// the bounty covers cost of the single delivery confirmation transaction for every message
+	pub const StalledConfirmationBountyPerMessage: Balance =
+		bp_millau::MAX_SINGLE_MESSAGE_DELIVERY_CONFIRMATION_TX_WEIGHT as _;
+	pub const MessagesUnsignedPriority: TransactionPriority = 0;
+	pub const MessagesUnsignedLongevity: TransactionLongevity = 32;
+	pub const MaxAllowedMessagesRelayers: u32 = 16;
   pub const BridgedChainId: bp_runtime::ChainId = bp_runtime::RIALTO_CHAIN_ID;
 }
 
@@ -376,6 +451,8 @@ impl pallet_bridge_messages::Config<WithRialtoMessagesInstance> for Runtime {
 	type MaxMessagesToPruneAtOnce = MaxMessagesToPruneAtOnce;
 	type MaxUnrewardedRelayerEntriesAtInboundLane = MaxUnrewardedRelayerEntriesAtInboundLane;
 	type MaxUnconfirmedMessagesAtInboundLane = MaxUnconfirmedMessagesAtInboundLane;
+	type MaxConfirmedRelayerEntriesToPrune = MaxConfirmedRelayerEntriesToPrune;
+	type MaxMessageEventsInDeliveryTransaction = MaxMessageEventsInDeliveryTransaction;
 
 	type OutboundPayload = crate::rialto_messages::ToRialtoMessagePayload;
 	type OutboundMessageFee = Balance;
@@ -383,34 +460,137 @@ impl pallet_bridge_messages::Config<WithRialtoMessagesInstance> for Runtime {
 	type InboundPayload = crate::rialto_messages::FromRialtoMessagePayload;
 	type InboundMessageFee = bp_rialto::Balance;
 	type InboundRelayer = bp_rialto::AccountId;
+	type InboundRelayerSigner = bp_rialto::AccountSigner;
+	type InboundRelayerSignature = bp_rialto::Signature;
 
 	type AccountIdConverter = bp_millau::AccountIdConverter;
 
 	type TargetHeaderChain = crate::rialto_messages::Rialto;
-	type LaneMessageVerifier = crate::rialto_messages::ToRialtoMessageVerifier;
-	type MessageDeliveryAndDispatchPayment = pallet_bridge_messages::instant_payments::InstantCurrencyPayments<
+	type LaneMessageVerifier =
+		pallet_bridge_lane_registry::LaneRegistryVerifier<Runtime, (), crate::rialto_messages::ToRialtoMessageVerifier>;
+	type MessageDeliveryAndDispatchPayment = pallet_bridge_messages::fee_split::FeeSplitPayments<
 		Runtime,
 		pallet_balances::Pallet<Runtime>,
-		GetDeliveryConfirmationTransactionFee,
-		RootAccountForPayments,
+		bridge_runtime_common::reward_split::ConfirmationRewardSplitPayments<
+			pallet_bridge_messages::instant_payments::InstantCurrencyPayments<
+				Runtime,
+				pallet_balances::Pallet<Runtime>,
+				GetDeliveryConfirmationTransactionFee,
+				RootAccountForPayments,
+			>,
+			crate::rialto_messages::ToRialtoConfirmationRewardShare,
+		>,
+		crate::rialto_messages::ToRialtoMessagesFeeSplit,
+		TreasuryAccountForPayments,
 	>;
 	type OnDeliveryConfirmed = ();
+	type BridgedMaxUnrewardedRelayerEntriesAtInboundLane = BridgedMaxUnrewardedRelayerEntriesAtInboundLane;
+	type StalledConfirmationPeriod = StalledConfirmationPeriod;
+	type StalledConfirmationBountyPerMessage = StalledConfirmationBountyPerMessage;
 
 	type SourceHeaderChain = crate::rialto_messages::Rialto;
 	type MessageDispatch = crate::rialto_messages::FromRialtoMessageDispatch;
+	type UnsignedPriority = MessagesUnsignedPriority;
+	type UnsignedLongevity = MessagesUnsignedLongevity;
+	type MaxAllowedRelayers = MaxAllowedMessagesRelayers;
 	type BridgedChainId = BridgedChainId;
 }
 
+parameter_types! {
+	pub const MaxCallChunks: u32 = 64;
+	pub const MaxCallChunkSize: u32 = 512 * 1024;
+	pub const MaxPendingCallsPerAccount: u32 = 4;
+	pub const PendingCallLifetime: BlockNumber = bp_millau::DAYS;
+	pub const CallChunkDeposit: Balance = 512 * 1024 * TransactionByteFee::get();
+	pub const CallChunkByteWeight: Weight = 4_000;
+}
+
+impl pallet_bridge_chunked_dispatch::Config for Runtime {
+	type Event = Event;
+	type Call = Call;
+	type CallFilter = ();
+	type MaxChunks = MaxCallChunks;
+	type MaxChunkSize = MaxCallChunkSize;
+	type MaxPendingCallsPerAccount = MaxPendingCallsPerAccount;
+	type PendingCallLifetime = PendingCallLifetime;
+	type ChunkDeposit = CallChunkDeposit;
+	type ByteWeight = CallChunkByteWeight;
+	type Currency = Balances;
+}
+
+parameter_types! {
+	pub const MaxLaneAllowedSenders: u32 = 16;
+	// reading or writing 32-byte account id
+	pub const LaneAllowedSenderWeight: Weight = 32 * 2_000;
+	pub LaneRegistryOwner: AccountId = Sudo::key();
+}
+
+impl pallet_bridge_lane_registry::Config for Runtime {
+	type Event = Event;
+	type RegistryOrigin = frame_system::EnsureRoot<AccountId>;
+	type Balance = Balance;
+	type MaxAllowedSenders = MaxLaneAllowedSenders;
+	type AllowedSenderWeight = LaneAllowedSenderWeight;
+}
+
+parameter_types! {
+	pub const RialtoChainId: bp_runtime::ChainId = bp_runtime::RIALTO_CHAIN_ID;
+	pub const ReconciliationPeriod: BlockNumber = bp_millau::HOURS;
+	pub const MaxReconciliationsPerBlock: u32 = 8;
+	/// Account that is sending reconciliation messages to Rialto.
+	pub ReconciliationAccount: AccountId = bp_millau::AccountIdConverter::convert(
+		pallet_bridge_fee_settlement::reconciliation_account_id(bp_runtime::MILLAU_CHAIN_ID),
+	);
+	/// Account that is dispatching reconciliation messages from Rialto.
+	pub RialtoReconciliationAccount: AccountId = bp_millau::derive_account_from_rialto_id(
+		bp_runtime::SourceAccount::Account(bp_rialto::AccountIdConverter::convert(
+			pallet_bridge_fee_settlement::reconciliation_account_id(bp_runtime::RIALTO_CHAIN_ID),
+		)),
+	);
+}
+
+/// Instance of the fee settlement pallet used to settle fees with Rialto chain.
+pub type WithRialtoFeeSettlementInstance = ();
+
+impl pallet_bridge_fee_settlement::Config<WithRialtoFeeSettlementInstance> for Runtime {
+	type Event = Event;
+	type Currency = Balances;
+	type AccountIdConverter = bp_millau::AccountIdConverter;
+	type HubChainId = RialtoChainId;
+	type SettlementOrigin =
+		pallet_bridge_fee_settlement::EnsureSettlementAccount<AccountId, RialtoReconciliationAccount>;
+	type ReconciliationSender = bridge_runtime_common::messages_forwarding::MessagesPalletReconciliationSender<
+		Runtime,
+		WithRialtoMessagesInstance,
+		ReconciliationAccount,
+		crate::rialto_messages::ToRialtoReconciliationMessageBuilder,
+	>;
+	type ReconciliationPeriod = ReconciliationPeriod;
+	type MaxReconciliationsPerBlock = MaxReconciliationsPerBlock;
+}
+
+/// Lanes of the messages pallet, that have been used before the lane registry has been added.
+pub struct ExistingRialtoLanes;
+
+impl frame_support::traits::Get<Vec<bp_messages::LaneId>> for ExistingRialtoLanes {
+	fn get() -> Vec<bp_messages::LaneId> {
+		use frame_support::IterableStorageMap;
+		pallet_bridge_messages::OutboundLanes::<WithRialtoMessagesInstance>::iter()
+			.map(|(lane, _)| lane)
+			.collect()
+	}
+}
+
 construct_runtime!(
 	pub enum Runtime where
 		Block = Block,
 		NodeBlock = opaque::Block,
 		UncheckedExtrinsic = UncheckedExtrinsic
 	{
-		BridgeRialtoMessages: pallet_bridge_messages::{Pallet, Call, Storage, Event<T>},
-		BridgeDispatch: pallet_bridge_dispatch::{Pallet, Event<T>},
-		BridgeRialtoGrandpa: pallet_bridge_grandpa::{Pallet, Call, Storage},
-		BridgeWestendGrandpa: pallet_bridge_grandpa::<Instance1>::{Pallet, Call, Config<T>, Storage},
+		BridgeRialtoMessages: pallet_bridge_messages::{Pallet, Call, Storage, Event<T>, ValidateUnsigned},
+		BridgeDispatch: pallet_bridge_dispatch::{Pallet, Call, Storage, Event<T>},
+		BridgeRialtoGrandpa: pallet_bridge_grandpa::{Pallet, Call, Storage, Event<T>},
+		BridgeWestendGrandpa: pallet_bridge_grandpa::<Instance1>::{Pallet, Call, Config<T>, Storage, Event<T>},
 		System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
 		RandomnessCollectiveFlip: pallet_randomness_collective_flip::{Pallet, Storage},
 		Timestamp: pallet_timestamp::{Pallet, Call, Storage, Inherent},
@@ -421,6 +601,9 @@ construct_runtime!(
 		Sudo: pallet_sudo::{Pallet, Call, Config<T>, Storage, Event<T>},
 		Session: pallet_session::{Pallet, Call, Storage, Event, Config<T>},
 		ShiftSessionManager: pallet_shift_session_manager::{Pallet},
+		BridgeChunkedDispatch: pallet_bridge_chunked_dispatch::{Pallet, Call, Storage, Event<T>},
+		BridgeLaneRegistry: pallet_bridge_lane_registry::{Pallet, Call, Config<T>, Storage, Event<T>},
+		BridgeRialtoFeeSettlement: pallet_bridge_fee_settlement::{Pallet, Call, Storage, Event<T>},
 	}
 );
 
@@ -434,6 +617,13 @@ pub type Block = generic::Block<Header, UncheckedExtrinsic>;
 pub type SignedBlock = generic::SignedBlock<Block>;
 /// BlockId type as expected by this runtime.
 pub type BlockId = generic::BlockId<Block>;
+bridge_runtime_common::declare_bridge_reject_obsolete_headers_and_messages!(
+	Runtime,
+	Call,
+	grandpa: [BridgeRialtoGrandpa => RialtoGrandpaInstance, BridgeWestendGrandpa => WestendGrandpaInstance],
+	messages: [BridgeRialtoMessages => WithRialtoMessagesInstance]
+);
+
 /// The SignedExtension to the basic transaction logic.
 pub type SignedExtra = (
 	frame_system::CheckSpecVersion<Runtime>,
@@ -443,6 +633,7 @@ pub type SignedExtra = (
 	frame_system::CheckNonce<Runtime>,
 	frame_system::CheckWeight<Runtime>,
 	pallet_transaction_payment::ChargeTransactionPayment<Runtime>,
+	BridgeRejectObsoleteHeadersAndMessages,
 );
 /// The payload being signed in transactions.
 pub type SignedPayload = generic::SignedPayload<Call, SignedExtra>;
@@ -450,9 +641,20 @@ pub type SignedPayload = generic::SignedPayload<Call, SignedExtra>;
 pub type UncheckedExtrinsic = generic::UncheckedExtrinsic<Address, Call, Signature, SignedExtra>;
 /// Extrinsic type that has already been checked.
 pub type CheckedExtrinsic = generic::CheckedExtrinsic<AccountId, Call, SignedExtra>;
+/// Migrations that are applied when the runtime is upgraded.
+pub type Migrations = (
+	pallet_bridge_grandpa::InitializeImportedHashesCapacity<Runtime, RialtoGrandpaInstance, HeadersToKeep>,
+	pallet_bridge_grandpa::InitializeAuthoritySetsHistory<Runtime, RialtoGrandpaInstance>,
+	pallet_bridge_grandpa::InitializeImportedHashesCapacity<Runtime, WestendGrandpaInstance, HeadersToKeep>,
+	pallet_bridge_grandpa::InitializeAuthoritySetsHistory<Runtime, WestendGrandpaInstance>,
+	pallet_bridge_messages::migrations::MigrateToV1<Runtime, WithRialtoMessagesInstance>,
+	pallet_bridge_messages::migrations::MigrateToV2<Runtime, WithRialtoMessagesInstance>,
+	pallet_bridge_lane_registry::RegisterExistingLanes<Runtime, (), ExistingRialtoLanes, LaneRegistryOwner>,
+	pallet_bridge_messages::CheckLanesInvariants<Runtime, WithRialtoMessagesInstance>,
+);
 /// Executive: handles dispatch to the various modules.
 pub type Executive =
-	frame_executive::Executive<Runtime, Block, frame_system::ChainContext<Runtime>, Runtime, AllPallets>;
+	frame_executive::Executive<Runtime, Block, frame_system::ChainContext<Runtime>, Runtime, AllPallets, Migrations>;
 
 impl_runtime_apis! {
 	impl sp_api::Core<Block> for Runtime {
@@ -592,6 +794,21 @@ impl_runtime_apis! {
 		fn is_known_header(hash: bp_rialto::Hash) -> bool {
 			BridgeRialtoGrandpa::is_known_header(hash)
 		}
+
+		fn is_known_and_finalized(hash: bp_rialto::Hash) -> Option<bp_rialto::BlockNumber> {
+			BridgeRialtoGrandpa::is_known_and_finalized(hash)
+		}
+
+		fn authority_set_changes() -> Vec<bp_header_chain::AuthoritySetChange<bp_rialto::Hash, bp_rialto::BlockNumber>> {
+			BridgeRialtoGrandpa::authority_set_changes()
+		}
+
+		fn verify_finality_proof(
+			header: Vec<u8>,
+			justification: Vec<u8>,
+		) -> Result<(), bp_header_chain::FinalityProofError> {
+			BridgeRialtoGrandpa::verify_encoded_finality_proof(&header, &justification)
+		}
 	}
 
 	impl bp_westend::WestendFinalityApi<Block> for Runtime {
@@ -603,6 +820,21 @@ impl_runtime_apis! {
 		fn is_known_header(hash: bp_westend::Hash) -> bool {
 			BridgeWestendGrandpa::is_known_header(hash)
 		}
+
+		fn is_known_and_finalized(hash: bp_westend::Hash) -> Option<bp_westend::BlockNumber> {
+			BridgeWestendGrandpa::is_known_and_finalized(hash)
+		}
+
+		fn authority_set_changes() -> Vec<bp_header_chain::AuthoritySetChange<bp_westend::Hash, bp_westend::BlockNumber>> {
+			BridgeWestendGrandpa::authority_set_changes()
+		}
+
+		fn verify_finality_proof(
+			header: Vec<u8>,
+			justification: Vec<u8>,
+		) -> Result<(), bp_header_chain::FinalityProofError> {
+			BridgeWestendGrandpa::verify_encoded_finality_proof(&header, &justification)
+		}
 	}
 
 	impl bp_rialto::ToRialtoOutboundLaneApi<Block, Balance, ToRialtoMessagePayload> for Runtime {
@@ -635,6 +867,38 @@ impl_runtime_apis! {
 		fn latest_generated_nonce(lane: bp_messages::LaneId) -> bp_messages::MessageNonce {
 			BridgeRialtoMessages::outbound_latest_generated_nonce(lane)
 		}
+
+		fn estimate_relayer_rewards(
+			lane: bp_messages::LaneId,
+			max_messages: bp_messages::MessageNonce,
+		) -> bp_messages::RelayerRewardsEstimation<Balance> {
+			bridge_runtime_common::messages_api::estimate_relayer_rewards::<
+				Runtime,
+				WithRialtoMessagesInstance,
+				WithRialtoMessageBridge,
+			>(lane, max_messages)
+		}
+
+		fn lane_statistics(lane: bp_messages::LaneId) -> bp_messages::LaneStatistics<Balance> {
+			BridgeRialtoMessages::lane_statistics(lane)
+		}
+
+		fn maximal_message_arguments_size() -> u32 {
+			bridge_runtime_common::messages::source::maximal_message_arguments_size(
+				bp_millau::max_extrinsic_size(),
+				bp_rialto::max_extrinsic_size(),
+			)
+		}
+	}
+
+	impl bp_messages::BridgesInfoApi<Block> for Runtime {
+		fn bridges() -> Vec<bp_messages::BridgeInfo> {
+			vec![bridge_runtime_common::messages_api::bridge_info::<
+				Runtime,
+				WithRialtoMessagesInstance,
+				RialtoGrandpaInstance,
+			>(pallet_bridge_lane_registry::Lanes::<Runtime>::iter_keys().collect())]
+		}
 	}
 
 	impl bp_rialto::FromRialtoInboundLaneApi<Block> for Runtime {
@@ -649,6 +913,24 @@ impl_runtime_apis! {
 		fn unrewarded_relayers_state(lane: bp_messages::LaneId) -> bp_messages::UnrewardedRelayersState {
 			BridgeRialtoMessages::inbound_unrewarded_relayers_state(lane)
 		}
+
+		fn dry_run_message_dispatch(
+			lane: bp_messages::LaneId,
+			payload: Vec<u8>,
+		) -> bp_runtime::messages::MessageDispatchResult {
+			bridge_runtime_common::messages_api::dry_run_message_dispatch::<
+				Runtime,
+				WithRialtoMessagesInstance,
+			>(lane, payload)
+		}
+	}
+
+	impl bp_rialto::FromRialtoDerivedAccountsApi<Block, AccountId> for Runtime {
+		fn derived_account_origin(
+			account_id: AccountId,
+		) -> Option<bp_message_dispatch::DerivedAccountOrigin<bp_rialto::AccountId>> {
+			BridgeDispatch::derived_account_origin(account_id)
+		}
 	}
 }
 
@@ -680,6 +962,8 @@ where
 mod tests {
 	use super::*;
 	use bridge_runtime_common::messages;
+	use frame_support::assert_ok;
+	use sp_runtime::traits::Header as HeaderT;
 
 	#[test]
 	fn ensure_millau_message_lane_weights_are_correct() {
@@ -719,4 +1003,298 @@ mod tests {
 			DbWeight::get(),
 		);
 	}
+
+	fn initialization_data<Header: HeaderT>(number: Header::Number) -> bp_header_chain::InitializationData<Header> {
+		bp_header_chain::InitializationData {
+			header: Header::new(
+				number,
+				Default::default(),
+				Default::default(),
+				Default::default(),
+				Default::default(),
+			),
+			authority_list: Vec::new(),
+			set_id: 0,
+			is_halted: false,
+			genesis_hash: Default::default(),
+		}
+	}
+
+	#[test]
+	fn bridges_with_rialto_and_westend_are_independent() {
+		let mut ext: sp_io::TestExternalities = SystemConfig::default().build_storage::<Runtime>().unwrap().into();
+		ext.execute_with(|| {
+			assert_ok!(BridgeRialtoGrandpa::initialize(
+				Origin::root(),
+				initialization_data::<bp_rialto::Header>(10),
+			));
+			assert_ok!(BridgeWestendGrandpa::initialize(
+				Origin::root(),
+				initialization_data::<bp_westend::Header>(20),
+			));
+
+			// every instance has its own storage
+			let rialto_header = BridgeRialtoGrandpa::best_finalized();
+			let westend_header = BridgeWestendGrandpa::best_finalized();
+			assert_eq!(rialto_header.number, 10);
+			assert_eq!(westend_header.number, 20);
+			assert!(!BridgeRialtoGrandpa::is_known_header(westend_header.hash()));
+			assert!(!BridgeWestendGrandpa::is_known_header(rialto_header.hash()));
+
+			// and the bridge info is reported using pallet names of the corresponding instances
+			let bridge_info = bridge_runtime_common::messages_api::bridge_info::<
+				Runtime,
+				WithRialtoMessagesInstance,
+				RialtoGrandpaInstance,
+			>(Vec::new());
+			assert_eq!(
+				bridge_info.messages_pallet_name,
+				bp_millau::WITH_RIALTO_MESSAGES_PALLET_NAME.as_bytes().to_vec(),
+			);
+			assert_eq!(
+				bridge_info.finality_pallet_name,
+				bp_millau::WITH_RIALTO_GRANDPA_PALLET_NAME.as_bytes().to_vec(),
+			);
+		});
+	}
+
+	#[test]
+	fn obsolete_bridge_transactions_are_rejected() {
+		use codec::Decode;
+		use frame_support::storage::StorageMap;
+		use sp_runtime::{traits::SignedExtension, transaction_validity::InvalidTransaction};
+
+		let validate = |call: Call| {
+			BridgeRejectObsoleteHeadersAndMessages.validate(&[0u8; 32].into(), &call, &Default::default(), 0)
+		};
+		let submit_finality_proof = |number| {
+			Call::BridgeRialtoGrandpa(BridgeGrandpaRialtoCall::submit_finality_proof(
+				initialization_data::<bp_rialto::Header>(number).header,
+				// justification is not verified by the extension
+				Decode::decode(&mut &[0u8; 64][..]).unwrap(),
+			))
+		};
+		let receive_messages_proof = |nonces_start, nonces_end, additional_lanes| {
+			Call::BridgeRialtoMessages(MessagesCall::receive_messages_proof(
+				[0u8; 32].into(),
+				messages::target::FromBridgedChainMessagesProof {
+					bridged_header_hash: Default::default(),
+					storage_proof: Vec::new(),
+					lane: bp_messages::LaneId::Legacy([0, 0, 0, 0]),
+					nonces_start,
+					nonces_end,
+					additional_lanes,
+				},
+				0,
+				0,
+			))
+		};
+
+		let mut ext: sp_io::TestExternalities = SystemConfig::default().build_storage::<Runtime>().unwrap().into();
+		ext.execute_with(|| {
+			assert_ok!(BridgeRialtoGrandpa::initialize(
+				Origin::root(),
+				initialization_data::<bp_rialto::Header>(10),
+			));
+			pallet_bridge_messages::InboundLanes::<Runtime, WithRialtoMessagesInstance>::insert(
+				bp_messages::LaneId::Legacy([0, 0, 0, 0]),
+				bp_messages::InboundLaneData {
+					relayers: Default::default(),
+					last_confirmed_nonce: 10,
+				},
+			);
+
+			// headers that are not newer than the best finalized header are rejected
+			assert_eq!(
+				validate(submit_finality_proof(9)),
+				Err(InvalidTransaction::Stale.into())
+			);
+			assert_eq!(
+				validate(submit_finality_proof(10)),
+				Err(InvalidTransaction::Stale.into())
+			);
+			assert!(validate(submit_finality_proof(11)).is_ok());
+
+			// messages that have already been delivered are rejected
+			assert_eq!(
+				validate(receive_messages_proof(5, 10, Vec::new())),
+				Err(InvalidTransaction::Stale.into()),
+			);
+			assert!(validate(receive_messages_proof(5, 11, Vec::new())).is_ok());
+			// proof without messages may be used to update the lane state
+			assert!(validate(receive_messages_proof(11, 10, Vec::new())).is_ok());
+			// proof is accepted if it delivers new messages at any lane
+			assert!(validate(receive_messages_proof(
+				5,
+				10,
+				vec![(bp_messages::LaneId::Legacy([0, 0, 0, 1]), 1, 1)]
+			))
+			.is_ok());
+
+			// other calls are not affected
+			assert!(validate(Call::System(SystemCall::remark(Vec::new()))).is_ok());
+		});
+	}
+
+	#[test]
+	fn fee_settlement_pallet_call_is_encoded_correctly() {
+		use codec::Encode;
+		use frame_support::weights::GetDispatchInfo;
+
+		let lane = bp_messages::LaneId::Legacy([0, 0, 0, 0]);
+		let call = Call::BridgeRialtoFeeSettlement(pallet_bridge_fee_settlement::Call::settle(lane, 100));
+		assert_eq!(
+			call.encode(),
+			pallet_bridge_fee_settlement::encode_settle_call(
+				bp_millau::WITH_RIALTO_FEE_SETTLEMENT_PALLET_INDEX,
+				lane,
+				100 as Balance
+			),
+		);
+		assert!(call.get_dispatch_info().weight <= bp_millau::FEE_SETTLEMENT_SETTLE_CALL_WEIGHT);
+	}
+
+	#[test]
+	fn reconciliation_messages_are_sent_to_rialto() {
+		use bp_message_dispatch::CallOrigin;
+		use codec::Decode;
+		use frame_support::traits::Hooks;
+
+		let lane = bp_messages::LaneId::Legacy([0, 0, 0, 0]);
+		let mut ext: sp_io::TestExternalities = SystemConfig::default().build_storage::<Runtime>().unwrap().into();
+		ext.execute_with(|| {
+			// reconciliation account owns the lane and is able to pay message fees
+			assert_ok!(BridgeLaneRegistry::register_lane(
+				Origin::root(),
+				lane,
+				pallet_bridge_lane_registry::LaneInfo {
+					owner: ReconciliationAccount::get(),
+					allowed_senders: pallet_bridge_lane_registry::AllowedSenders::Owner,
+					minimal_fee: 0,
+				},
+			));
+			let _ = Balances::deposit_creating(&ReconciliationAccount::get(), 1_000_000_000_000_000);
+
+			BridgeRialtoFeeSettlement::note_forwarding_fee(bp_runtime::RIALTO_CHAIN_ID, lane, 100);
+			BridgeRialtoFeeSettlement::on_initialize(ReconciliationPeriod::get());
+
+			assert_eq!(
+				BridgeRialtoFeeSettlement::pending_reconciliation(bp_runtime::RIALTO_CHAIN_ID, lane),
+				0
+			);
+			assert_eq!(
+				BridgeRialtoFeeSettlement::failed_reconciliation(bp_runtime::RIALTO_CHAIN_ID, lane),
+				0
+			);
+
+			// the message that calls `settle` at Rialto has been queued
+			let message = BridgeRialtoMessages::outbound_message_data(lane, 1).unwrap();
+			let payload = ToRialtoMessagePayload::decode(&mut &message.payload[..]).unwrap();
+			assert_eq!(payload.origin, CallOrigin::SourceAccount(ReconciliationAccount::get()));
+			assert_eq!(
+				payload.call,
+				pallet_bridge_fee_settlement::encode_settle_call(
+					bp_rialto::WITH_MILLAU_FEE_SETTLEMENT_PALLET_INDEX,
+					lane,
+					100 as bp_rialto::Balance
+				),
+			);
+			assert!(message.fee > 0);
+		});
+	}
+
+	#[test]
+	fn dispatch_acknowledgement_call_is_encoded_correctly() {
+		use codec::Encode;
+		use frame_support::weights::GetDispatchInfo;
+
+		let acknowledgement = bp_message_dispatch::DispatchAcknowledgement {
+			message_id: (bp_messages::LaneId::Legacy([0, 0, 0, 0]), 1),
+			dispatch_result: true,
+		};
+		let call = Call::BridgeDispatch(pallet_bridge_dispatch::Call::receive_dispatch_acknowledgement(
+			acknowledgement.clone(),
+		));
+		assert_eq!(
+			call.encode(),
+			pallet_bridge_dispatch::encode_receive_dispatch_acknowledgement_call(
+				bp_millau::WITH_RIALTO_DISPATCH_PALLET_INDEX,
+				acknowledgement,
+			),
+		);
+		assert!(call.get_dispatch_info().weight <= bp_millau::DISPATCH_ACKNOWLEDGEMENT_CALL_WEIGHT);
+	}
+
+	#[test]
+	fn dispatch_acknowledgements_are_sent_to_rialto() {
+		use bp_message_dispatch::{CallOrigin, DispatchAcknowledgement, MessagePayload};
+		use bp_messages::{
+			target_chain::{DispatchMessage, DispatchMessageData, MessageDispatch},
+			MessageKey,
+		};
+		use bp_runtime::messages::DispatchFeePayment;
+		use bridge_runtime_common::messages::target::{
+			FromBridgedChainEncodedMessageCall, FromBridgedChainMessagePayload,
+		};
+		use codec::{Decode, Encode};
+		use frame_support::weights::GetDispatchInfo;
+
+		let lane = bp_messages::LaneId::Legacy([0, 0, 0, 0]);
+		let mut ext: sp_io::TestExternalities = SystemConfig::default().build_storage::<Runtime>().unwrap().into();
+		ext.execute_with(|| {
+			// acknowledgement account owns the lane and is able to pay message fees
+			assert_ok!(BridgeLaneRegistry::register_lane(
+				Origin::root(),
+				lane,
+				pallet_bridge_lane_registry::LaneInfo {
+					owner: AcknowledgementAccount::get(),
+					allowed_senders: pallet_bridge_lane_registry::AllowedSenders::Owner,
+					minimal_fee: 0,
+				},
+			));
+			let _ = Balances::deposit_creating(&AcknowledgementAccount::get(), 1_000_000_000_000_000);
+
+			// message from Rialto requests dispatch acknowledgement
+			let call: Call = SystemCall::remark(vec![]).into();
+			let dispatch_result = rialto_messages::FromRialtoMessageDispatch::dispatch(
+				&[1u8; 32].into(),
+				DispatchMessage {
+					key: MessageKey {
+						lane_id: lane,
+						nonce: 1,
+					},
+					data: DispatchMessageData {
+						payload: Ok(FromBridgedChainMessagePayload::<WithRialtoMessageBridge>::V2 {
+							payload: MessagePayload {
+								spec_version: VERSION.spec_version,
+								weight: call.get_dispatch_info().weight,
+								origin: CallOrigin::SourceRoot,
+								dispatch_fee_payment: DispatchFeePayment::AtSourceChain,
+								call: FromBridgedChainEncodedMessageCall::new(call.encode()),
+							},
+							acknowledge_dispatch: true,
+						}),
+						fee: 1,
+					},
+				},
+			);
+			assert!(dispatch_result.dispatch_result);
+
+			// the message that calls `receive_dispatch_acknowledgement` at Rialto has been queued
+			let message = BridgeRialtoMessages::outbound_message_data(lane, 1).unwrap();
+			let payload = ToRialtoMessagePayload::decode(&mut &message.payload[..]).unwrap();
+			assert_eq!(payload.origin, CallOrigin::SourceAccount(AcknowledgementAccount::get()));
+			assert_eq!(
+				payload.call,
+				pallet_bridge_dispatch::encode_receive_dispatch_acknowledgement_call(
+					bp_rialto::WITH_MILLAU_DISPATCH_PALLET_INDEX,
+					DispatchAcknowledgement {
+						message_id: (lane, 1),
+						dispatch_result: true,
+					},
+				),
+			);
+			assert!(message.fee > 0);
+		});
+	}
 }
@@ -18,32 +18,47 @@
 
 use crate::Runtime;
 
+use bp_message_dispatch::{CallOrigin, DispatchAcknowledgement};
 use bp_messages::{
 	source_chain::TargetHeaderChain,
 	target_chain::{ProvedMessages, SourceHeaderChain},
 	InboundLaneData, LaneId, Message, MessageNonce, Parameter as MessagesParameter,
 };
-use bp_runtime::{ChainId, MILLAU_CHAIN_ID, RIALTO_CHAIN_ID};
-use bridge_runtime_common::messages::{self, MessageBridge, MessageTransaction};
+use bp_runtime::{messages::DispatchFeePayment, ChainId, StorageProofStats, MILLAU_CHAIN_ID, RIALTO_CHAIN_ID};
+use bridge_runtime_common::{
+	conversion_rate::ParameterConversionRate,
+	messages::{self, MessageBridge, MessageTransaction},
+	messages_acknowledgements::{AcknowledgementMessageBuilder, MessagesPalletMessageId},
+	messages_forwarding::ReconciliationMessageBuilder,
+};
 use codec::{Decode, Encode};
 use frame_support::{
 	parameter_types,
 	weights::{DispatchClass, Weight},
 	RuntimeDebug,
 };
-use sp_runtime::{traits::Saturating, FixedPointNumber, FixedU128};
+use pallet_bridge_messages::fee_split::FeeSplit;
+use sp_runtime::{traits::Saturating, FixedPointNumber, FixedU128, Perbill};
 use sp_std::{convert::TryFrom, ops::RangeInclusive};
 
 /// Initial value of `RialtoToMillauConversionRate` parameter.
 pub const INITIAL_RIALTO_TO_MILLAU_CONVERSION_RATE: FixedU128 = FixedU128::from_inner(FixedU128::DIV);
 /// Initial value of `RialtoFeeMultiplier` parameter.
 pub const INITIAL_RIALTO_FEE_MULTIPLIER: FixedU128 = FixedU128::from_inner(FixedU128::DIV);
+/// Initial value of `RialtoSpecVersion` parameter.
+pub const INITIAL_RIALTO_SPEC_VERSION: u32 = 1;
 
 parameter_types! {
 	/// Rialto to Millau conversion rate. Initially we treat both tokens as equal.
 	pub storage RialtoToMillauConversionRate: FixedU128 = INITIAL_RIALTO_TO_MILLAU_CONVERSION_RATE;
 	/// Fee multiplier value at Rialto chain.
 	pub storage RialtoFeeMultiplier: FixedU128 = INITIAL_RIALTO_FEE_MULTIPLIER;
+	/// Split of fees, collected from Millau -> Rialto messages, between relayers, treasury and burn.
+	pub storage ToRialtoMessagesFeeSplit: FeeSplit = FeeSplit::default();
+	/// Part of delivery rewards of Millau -> Rialto messages that is paid to the confirmation relayer.
+	pub storage ToRialtoConfirmationRewardShare: Perbill = Perbill::zero();
+	/// Spec version of the Rialto runtime. It is used by messages that are sent by the Millau runtime itself.
+	pub storage RialtoSpecVersion: u32 = INITIAL_RIALTO_SPEC_VERSION;
 }
 
 /// Message payload for Millau -> Rialto messages.
@@ -72,6 +87,68 @@ pub type FromRialtoMessageDispatch = messages::target::FromBridgedChainMessageDi
 	pallet_bridge_dispatch::DefaultInstance,
 >;
 
+/// Builder of Millau -> Rialto reconciliation messages, that are calling `settle` of the Rialto
+/// fee settlement pallet.
+#[derive(RuntimeDebug)]
+pub struct ToRialtoReconciliationMessageBuilder;
+
+impl ReconciliationMessageBuilder<bp_millau::Balance, ToRialtoMessagePayload, bp_millau::Balance>
+	for ToRialtoReconciliationMessageBuilder
+{
+	fn build_reconciliation_message(
+		lane: LaneId,
+		amount: bp_millau::Balance,
+	) -> Result<(ToRialtoMessagePayload, bp_millau::Balance), &'static str> {
+		let payload = ToRialtoMessagePayload {
+			spec_version: RialtoSpecVersion::get(),
+			weight: bp_rialto::FEE_SETTLEMENT_SETTLE_CALL_WEIGHT,
+			origin: CallOrigin::SourceAccount(crate::ReconciliationAccount::get()),
+			dispatch_fee_payment: DispatchFeePayment::AtSourceChain,
+			call: pallet_bridge_fee_settlement::encode_settle_call(
+				bp_rialto::WITH_MILLAU_FEE_SETTLEMENT_PALLET_INDEX,
+				lane,
+				bp_rialto::Balance::from(amount),
+			),
+		};
+		let fee = messages::source::estimate_message_dispatch_and_delivery_fee::<WithRialtoMessageBridge>(
+			&payload,
+			WithRialtoMessageBridge::RELAYER_FEE_PERCENT,
+		)?;
+
+		Ok((payload, fee))
+	}
+}
+
+/// Builder of Millau -> Rialto dispatch acknowledgement messages, that are calling
+/// `receive_dispatch_acknowledgement` of the Rialto dispatch pallet.
+#[derive(RuntimeDebug)]
+pub struct ToRialtoAcknowledgementMessageBuilder;
+
+impl AcknowledgementMessageBuilder<MessagesPalletMessageId, ToRialtoMessagePayload, bp_millau::Balance>
+	for ToRialtoAcknowledgementMessageBuilder
+{
+	fn build_acknowledgement_message(
+		acknowledgement: DispatchAcknowledgement<MessagesPalletMessageId>,
+	) -> Result<(ToRialtoMessagePayload, bp_millau::Balance), &'static str> {
+		let payload = ToRialtoMessagePayload {
+			spec_version: RialtoSpecVersion::get(),
+			weight: bp_rialto::DISPATCH_ACKNOWLEDGEMENT_CALL_WEIGHT,
+			origin: CallOrigin::SourceAccount(crate::AcknowledgementAccount::get()),
+			dispatch_fee_payment: DispatchFeePayment::AtSourceChain,
+			call: pallet_bridge_dispatch::encode_receive_dispatch_acknowledgement_call(
+				bp_rialto::WITH_MILLAU_DISPATCH_PALLET_INDEX,
+				acknowledgement,
+			),
+		};
+		let fee = messages::source::estimate_message_dispatch_and_delivery_fee::<WithRialtoMessageBridge>(
+			&payload,
+			WithRialtoMessageBridge::RELAYER_FEE_PERCENT,
+		)?;
+
+		Ok((payload, fee))
+	}
+}
+
 /// Millau <-> Rialto message bridge.
 #[derive(RuntimeDebug, Clone, Copy)]
 pub struct WithRialtoMessageBridge;
@@ -84,11 +161,29 @@ impl MessageBridge for WithRialtoMessageBridge {
 	type ThisChain = Millau;
 	type BridgedChain = Rialto;
 	type BridgedMessagesInstance = crate::WithRialtoMessagesInstance;
+	type ConversionRateOracle = ParameterConversionRate<RialtoToMillauConversionRate>;
 
-	fn bridged_balance_to_this_balance(bridged_balance: bp_rialto::Balance) -> bp_millau::Balance {
-		bp_millau::Balance::try_from(RialtoToMillauConversionRate::get().saturating_mul_int(bridged_balance))
+	fn bridged_balance_to_this_balance(
+		bridged_balance: bp_rialto::Balance,
+		bridged_to_this_conversion_rate: FixedU128,
+	) -> bp_millau::Balance {
+		bp_millau::Balance::try_from(bridged_to_this_conversion_rate.saturating_mul_int(bridged_balance))
 			.unwrap_or(bp_millau::Balance::MAX)
 	}
+
+	fn maximal_storage_proof_size() -> u32 {
+		Self::maximal_messages_storage_proof_size(bp_millau::MAX_UNCONFIRMED_MESSAGES_AT_INBOUND_LANE, 1)
+	}
+
+	fn maximal_messages_storage_proof_size(messages_count: MessageNonce, lanes_count: u32) -> u32 {
+		messages::target::maximal_messages_storage_proof_size(
+			messages_count,
+			lanes_count,
+			messages::target::maximal_incoming_message_size(bp_millau::max_extrinsic_size()),
+			bp_rialto::EXTRA_STORAGE_PROOF_SIZE,
+		)
+		.min(bp_millau::max_extrinsic_size())
+	}
 }
 
 /// Millau chain from message lane point of view.
@@ -177,6 +272,11 @@ impl messages::BridgedChainWithMessages for Rialto {
 		0..=upper_limit
 	}
 
+	fn is_dispatch_fee_payment_supported(_dispatch_fee_payment: DispatchFeePayment) -> bool {
+		// both source and target chain dispatch fee payment modes are supported by the Rialto runtime
+		true
+	}
+
 	fn estimate_delivery_transaction(
 		message_payload: &[u8],
 		include_pay_dispatch_fee_cost: bool,
@@ -244,9 +344,14 @@ impl SourceHeaderChain<bp_rialto::Balance> for Rialto {
 	// - hash of the header this proof has been created with;
 	// - the storage proof or one or several keys;
 	// - id of the lane we prove messages for;
-	// - inclusive range of messages nonces that are proved.
+	// - inclusive range of messages nonces that are proved;
+	// - additional lanes (with inclusive ranges of their messages nonces) that are proved.
 	type MessagesProof = FromRialtoMessagesProof;
 
+	fn proof_lanes_count(proof: &Self::MessagesProof) -> u32 {
+		proof.lanes_count()
+	}
+
 	fn verify_messages_proof(
 		proof: Self::MessagesProof,
 		messages_count: u32,
@@ -256,6 +361,17 @@ impl SourceHeaderChain<bp_rialto::Balance> for Rialto {
 			messages_count,
 		)
 	}
+	fn verify_messages_proof_with_stats(
+		proof: Self::MessagesProof,
+		messages_count: u32,
+	) -> Result<(ProvedMessages<Message<bp_rialto::Balance>>, Option<StorageProofStats>), Self::Error> {
+		messages::target::verify_messages_proof_with_stats::<
+			WithRialtoMessageBridge,
+			Runtime,
+			crate::RialtoGrandpaInstance,
+		>(proof, messages_count)
+		.map(|(proved_messages, stats)| (proved_messages, Some(stats)))
+	}
 }
 
 /// Millau -> Rialto message lane pallet parameters.
@@ -263,6 +379,12 @@ impl SourceHeaderChain<bp_rialto::Balance> for Rialto {
 pub enum MillauToRialtoMessagesParameter {
 	/// The conversion formula we use is: `MillauTokens = RialtoTokens * conversion_rate`.
 	RialtoToMillauConversionRate(FixedU128),
+	/// Split of collected message fees between relayers, treasury and burn.
+	FeeSplit(FeeSplit),
+	/// Part of delivery rewards that is paid to the confirmation relayer.
+	ConfirmationRewardShare(Perbill),
+	/// Spec version of the Rialto runtime.
+	RialtoSpecVersion(u32),
 }
 
 impl MessagesParameter for MillauToRialtoMessagesParameter {
@@ -271,6 +393,13 @@ impl MessagesParameter for MillauToRialtoMessagesParameter {
 			MillauToRialtoMessagesParameter::RialtoToMillauConversionRate(ref conversion_rate) => {
 				RialtoToMillauConversionRate::set(conversion_rate)
 			}
+			MillauToRialtoMessagesParameter::FeeSplit(ref fee_split) => ToRialtoMessagesFeeSplit::set(fee_split),
+			MillauToRialtoMessagesParameter::ConfirmationRewardShare(ref share) => {
+				ToRialtoConfirmationRewardShare::set(share)
+			}
+			MillauToRialtoMessagesParameter::RialtoSpecVersion(ref spec_version) => {
+				RialtoSpecVersion::set(spec_version)
+			}
 		}
 	}
 }
@@ -16,8 +16,8 @@
 
 use bp_rialto::derive_account_from_millau_id;
 use rialto_runtime::{
-	AccountId, BabeConfig, BalancesConfig, BridgeKovanConfig, BridgeRialtoPoaConfig, GenesisConfig, GrandpaConfig,
-	SessionConfig, SessionKeys, Signature, SudoConfig, SystemConfig, WASM_BINARY,
+	AccountId, BabeConfig, BalancesConfig, BridgeKovanConfig, BridgeLaneRegistryConfig, BridgeRialtoPoaConfig,
+	GenesisConfig, GrandpaConfig, SessionConfig, SessionKeys, Signature, SudoConfig, SystemConfig, WASM_BINARY,
 };
 use serde_json::json;
 use sp_consensus_babe::AuthorityId as BabeId;
@@ -70,8 +70,8 @@ impl Alternative {
 	pub(crate) fn load(self) -> ChainSpec {
 		let properties = Some(
 			json!({
-				"tokenDecimals": 9,
-				"tokenSymbol": "RLT"
+				"tokenDecimals": bp_chain_registry::RIALTO.token_decimals,
+				"tokenSymbol": bp_chain_registry::RIALTO.token_symbol
 			})
 			.as_object()
 			.expect("Map given; qed")
@@ -198,13 +198,31 @@ fn testnet_genesis(
 		grandpa: GrandpaConfig {
 			authorities: Vec::new(),
 		},
-		sudo: SudoConfig { key: root_key },
+		sudo: SudoConfig { key: root_key.clone() },
 		session: SessionConfig {
 			keys: initial_authorities
 				.iter()
 				.map(|x| (x.0.clone(), x.0.clone(), session_keys(x.1.clone(), x.2.clone())))
 				.collect::<Vec<_>>(),
 		},
+		bridge_lane_registry: BridgeLaneRegistryConfig {
+			lanes: vec![
+				bp_messages::LaneId::Legacy([0, 0, 0, 0]),
+				bp_messages::LaneId::Legacy([0, 0, 0, 1]),
+			]
+			.into_iter()
+			.map(|lane| {
+				(
+					lane,
+					pallet_bridge_lane_registry::LaneInfo {
+						owner: root_key.clone(),
+						allowed_senders: pallet_bridge_lane_registry::AllowedSenders::Any,
+						minimal_fee: 0,
+					},
+				)
+			})
+			.collect(),
+		},
 	}
 }
 
@@ -45,10 +45,10 @@ use pallet_grandpa::{fg_primitives, AuthorityId as GrandpaId, AuthorityList as G
 use pallet_transaction_payment::{FeeDetails, Multiplier, RuntimeDispatchInfo};
 use sp_api::impl_runtime_apis;
 use sp_core::{crypto::KeyTypeId, OpaqueMetadata};
-use sp_runtime::traits::{Block as BlockT, IdentityLookup, NumberFor, OpaqueKeys};
+use sp_runtime::traits::{Block as BlockT, Convert, IdentityLookup, NumberFor, OpaqueKeys};
 use sp_runtime::{
 	create_runtime_str, generic, impl_opaque_keys,
-	transaction_validity::{TransactionSource, TransactionValidity},
+	transaction_validity::{TransactionLongevity, TransactionPriority, TransactionSource, TransactionValidity},
 	ApplyExtrinsicResult, FixedPointNumber, MultiSignature, MultiSigner, Perquintill,
 };
 use sp_std::prelude::*;
@@ -66,6 +66,7 @@ pub use frame_support::{
 
 pub use frame_system::Call as SystemCall;
 pub use pallet_balances::Call as BalancesCall;
+pub use pallet_bridge_chunked_dispatch::Call as ChunkedDispatchCall;
 pub use pallet_bridge_currency_exchange::Call as BridgeCurrencyExchangeCall;
 pub use pallet_bridge_eth_poa::Call as BridgeEthPoACall;
 pub use pallet_bridge_grandpa::Call as BridgeGrandpaMillauCall;
@@ -157,7 +158,7 @@ parameter_types! {
 		read: 60_000_000, // ~0.06 ms = ~60 µs
 		write: 200_000_000, // ~0.2 ms = 200 µs
 	};
-	pub const SS58Prefix: u8 = 48;
+	pub const SS58Prefix: u8 = bp_chain_registry::RIALTO.ss58_prefix as u8;
 }
 
 impl frame_system::Config for Runtime {
@@ -284,6 +285,23 @@ impl pallet_bridge_currency_exchange::Config<KovanCurrencyExchange> for Runtime
 	type DepositInto = DepositInto;
 }
 
+parameter_types! {
+	pub const MaxFailedBridgeMessages: u32 = 1024;
+	pub const BridgedMessagePayloadHashingByteWeight: Weight = 2_000;
+	pub const MaxDispatchedBridgeMessages: u32 = 4096;
+	pub const BridgedCallDepositPerConsumer: Balance = 1_000_000;
+	/// Account that is sending dispatch acknowledgements to Millau.
+	pub AcknowledgementAccount: AccountId = bp_rialto::AccountIdConverter::convert(
+		pallet_bridge_dispatch::acknowledgement_account_id(bp_runtime::RIALTO_CHAIN_ID),
+	);
+	/// Account that is dispatching dispatch acknowledgements from Millau.
+	pub MillauAcknowledgementAccount: AccountId = bp_rialto::derive_account_from_millau_id(
+		bp_runtime::SourceAccount::Account(bp_millau::AccountIdConverter::convert(
+			pallet_bridge_dispatch::acknowledgement_account_id(bp_runtime::MILLAU_CHAIN_ID),
+		)),
+	);
+}
+
 impl pallet_bridge_dispatch::Config for Runtime {
 	type Event = Event;
 	type MessageId = (bp_messages::LaneId, bp_messages::MessageNonce);
@@ -294,6 +312,24 @@ impl pallet_bridge_dispatch::Config for Runtime {
 	type TargetChainAccountPublic = MultiSigner;
 	type TargetChainSignature = MultiSignature;
 	type AccountIdConverter = bp_rialto::AccountIdConverter;
+	// only storage, guarded by consumer references, is charged. Other storage isn't filtered in this test runtime
+	type StorageDepositMeter =
+		pallet_bridge_dispatch::ConsumerReferencesDepositMeter<Runtime, Balances, BridgedCallDepositPerConsumer>;
+	// XCM messages are not supported by this runtime
+	type XcmExecutor = ();
+	type AcknowledgementSender = bridge_runtime_common::messages_acknowledgements::MessagesPalletAcknowledgementSender<
+		Runtime,
+		WithMillauMessagesInstance,
+		AcknowledgementAccount,
+		crate::millau_messages::ToMillauAcknowledgementMessageBuilder,
+	>;
+	type AcknowledgementOrigin =
+		pallet_bridge_dispatch::EnsureAcknowledgementAccount<AccountId, MillauAcknowledgementAccount, MillauChainId>;
+	type MaxFailedMessages = MaxFailedBridgeMessages;
+	type PayloadHashingByteWeight = BridgedMessagePayloadHashingByteWeight;
+	type RetryDispatchFeePayment = bridge_runtime_common::messages::target::WithdrawRetryDispatchFee<Runtime, Balances>;
+	type MaxDispatchedMessages = MaxDispatchedBridgeMessages;
+	type DispatchAsOrigin = OriginCaller;
 }
 
 pub struct DepositInto;
@@ -470,11 +506,31 @@ parameter_types! {
 	pub const HeadersToKeep: u32 = 7 * bp_rialto::DAYS as u32;
 }
 
+parameter_types! {
+	/// Number of authority set changes to keep.
+	pub const AuthoritySetChangesToKeep: u32 = 64;
+	/// Number of previous authority sets, which justifications are still accepted.
+	pub const AuthoritySetsToKeep: u32 = 4;
+	/// Number of obsolete headers to prune at the beginning of every block.
+	pub const MaxHeadersToPruneInBlock: u32 = 8;
+	/// Millau headers may be imported using ancestry proofs.
+	pub const AcceptAncestryProofs: bool = true;
+	/// Mandatory headers are rare (once per session), so single free header per block is enough.
+	pub const FreeMandatoryHeadersPerBlock: u32 = 1;
+}
+
 pub type MillauGrandpaInstance = ();
 impl pallet_bridge_grandpa::Config for Runtime {
+	type Event = Event;
 	type BridgedChain = bp_millau::Millau;
 	type MaxRequests = MaxRequests;
 	type HeadersToKeep = HeadersToKeep;
+	type MaxHeadersToPruneInBlock = MaxHeadersToPruneInBlock;
+	type AuthoritySetChangesToKeep = AuthoritySetChangesToKeep;
+	type AuthoritySetsToKeep = AuthoritySetsToKeep;
+	type AcceptAncestryProofs = AcceptAncestryProofs;
+	type FreeMandatoryHeadersPerBlock = FreeMandatoryHeadersPerBlock;
+	type OnEquivocation = ();
 	type WeightInfo = pallet_bridge_grandpa::weights::RialtoWeight<Runtime>;
 }
 
@@ -486,10 +542,22 @@ parameter_types! {
 		bp_rialto::MAX_UNREWARDED_RELAYER_ENTRIES_AT_INBOUND_LANE;
 	pub const MaxUnconfirmedMessagesAtInboundLane: bp_messages::MessageNonce =
 		bp_rialto::MAX_UNCONFIRMED_MESSAGES_AT_INBOUND_LANE;
+	pub const MaxConfirmedRelayerEntriesToPrune: bp_messages::MessageNonce = 8;
+	pub const MaxMessageEventsInDeliveryTransaction: bp_messages::MessageNonce = 16;
 	// `IdentityFee` is used by Rialto => we may use weight directly
 	pub const GetDeliveryConfirmationTransactionFee: Balance =
 		bp_rialto::MAX_SINGLE_MESSAGE_DELIVERY_CONFIRMATION_TX_WEIGHT as _;
 	pub const RootAccountForPayments: Option<AccountId> = None;
+	pub const TreasuryAccountForPayments: Option<AccountId> = None;
+	pub const BridgedMaxUnrewardedRelayerEntriesAtInboundLane: bp_messages::MessageNonce =
+		bp_millau::MAX_UNREWARDED_RELAYER_ENTRIES_AT_INBOUND_LANE;
+	pub const StalledConfirmationPeriod: BlockNumber = bp_rialto::HOURS;
+	// the bounty covers cost of the single delivery confirmation transaction for every message
+	pub const StalledConfirmationBountyPerMessage: Balance =
+		bp_rialto::MAX_SINGLE_MESSAGE_DELIVERY_CONFIRMATION_TX_WEIGHT as _;
+	pub const MessagesUnsignedPriority: TransactionPriority = 0;
+	pub const MessagesUnsignedLongevity: TransactionLongevity = 32;
+	pub const MaxAllowedMessagesRelayers: u32 = 16;
   pub const BridgedChainId: bp_runtime::ChainId = bp_runtime::MILLAU_CHAIN_ID;
 }
 
@@ -503,6 +571,8 @@ impl pallet_bridge_messages::Config<WithMillauMessagesInstance> for Runtime {
 	type MaxMessagesToPruneAtOnce = MaxMessagesToPruneAtOnce;
 	type MaxUnrewardedRelayerEntriesAtInboundLane = MaxUnrewardedRelayerEntriesAtInboundLane;
 	type MaxUnconfirmedMessagesAtInboundLane = MaxUnconfirmedMessagesAtInboundLane;
+	type MaxConfirmedRelayerEntriesToPrune = MaxConfirmedRelayerEntriesToPrune;
+	type MaxMessageEventsInDeliveryTransaction = MaxMessageEventsInDeliveryTransaction;
 
 	type OutboundPayload = crate::millau_messages::ToMillauMessagePayload;
 	type OutboundMessageFee = Balance;
@@ -510,24 +580,127 @@ impl pallet_bridge_messages::Config<WithMillauMessagesInstance> for Runtime {
 	type InboundPayload = crate::millau_messages::FromMillauMessagePayload;
 	type InboundMessageFee = bp_millau::Balance;
 	type InboundRelayer = bp_millau::AccountId;
+	type InboundRelayerSigner = bp_millau::AccountSigner;
+	type InboundRelayerSignature = bp_millau::Signature;
 
 	type AccountIdConverter = bp_rialto::AccountIdConverter;
 
 	type TargetHeaderChain = crate::millau_messages::Millau;
-	type LaneMessageVerifier = crate::millau_messages::ToMillauMessageVerifier;
-	type MessageDeliveryAndDispatchPayment = pallet_bridge_messages::instant_payments::InstantCurrencyPayments<
+	type LaneMessageVerifier =
+		pallet_bridge_lane_registry::LaneRegistryVerifier<Runtime, (), crate::millau_messages::ToMillauMessageVerifier>;
+	type MessageDeliveryAndDispatchPayment = pallet_bridge_messages::fee_split::FeeSplitPayments<
 		Runtime,
 		pallet_balances::Pallet<Runtime>,
-		GetDeliveryConfirmationTransactionFee,
-		RootAccountForPayments,
+		bridge_runtime_common::reward_split::ConfirmationRewardSplitPayments<
+			pallet_bridge_messages::instant_payments::InstantCurrencyPayments<
+				Runtime,
+				pallet_balances::Pallet<Runtime>,
+				GetDeliveryConfirmationTransactionFee,
+				RootAccountForPayments,
+			>,
+			crate::millau_messages::ToMillauConfirmationRewardShare,
+		>,
+		crate::millau_messages::ToMillauMessagesFeeSplit,
+		TreasuryAccountForPayments,
 	>;
 	type OnDeliveryConfirmed = ();
+	type BridgedMaxUnrewardedRelayerEntriesAtInboundLane = BridgedMaxUnrewardedRelayerEntriesAtInboundLane;
+	type StalledConfirmationPeriod = StalledConfirmationPeriod;
+	type StalledConfirmationBountyPerMessage = StalledConfirmationBountyPerMessage;
 
 	type SourceHeaderChain = crate::millau_messages::Millau;
 	type MessageDispatch = crate::millau_messages::FromMillauMessageDispatch;
+	type UnsignedPriority = MessagesUnsignedPriority;
+	type UnsignedLongevity = MessagesUnsignedLongevity;
+	type MaxAllowedRelayers = MaxAllowedMessagesRelayers;
 	type BridgedChainId = BridgedChainId;
 }
 
+parameter_types! {
+	pub const MaxCallChunks: u32 = 64;
+	pub const MaxCallChunkSize: u32 = 512 * 1024;
+	pub const MaxPendingCallsPerAccount: u32 = 4;
+	pub const PendingCallLifetime: BlockNumber = bp_rialto::DAYS;
+	pub const CallChunkDeposit: Balance = 512 * 1024 * TransactionByteFee::get();
+	pub const CallChunkByteWeight: Weight = 4_000;
+}
+
+impl pallet_bridge_chunked_dispatch::Config for Runtime {
+	type Event = Event;
+	type Call = Call;
+	type CallFilter = ();
+	type MaxChunks = MaxCallChunks;
+	type MaxChunkSize = MaxCallChunkSize;
+	type MaxPendingCallsPerAccount = MaxPendingCallsPerAccount;
+	type PendingCallLifetime = PendingCallLifetime;
+	type ChunkDeposit = CallChunkDeposit;
+	type ByteWeight = CallChunkByteWeight;
+	type Currency = Balances;
+}
+
+parameter_types! {
+	pub const MaxLaneAllowedSenders: u32 = 16;
+	// reading or writing 32-byte account id
+	pub const LaneAllowedSenderWeight: Weight = 32 * 2_000;
+	pub LaneRegistryOwner: AccountId = Sudo::key();
+}
+
+impl pallet_bridge_lane_registry::Config for Runtime {
+	type Event = Event;
+	type RegistryOrigin = frame_system::EnsureRoot<AccountId>;
+	type Balance = Balance;
+	type MaxAllowedSenders = MaxLaneAllowedSenders;
+	type AllowedSenderWeight = LaneAllowedSenderWeight;
+}
+
+parameter_types! {
+	pub const MillauChainId: bp_runtime::ChainId = bp_runtime::MILLAU_CHAIN_ID;
+	pub const ReconciliationPeriod: BlockNumber = bp_rialto::HOURS;
+	pub const MaxReconciliationsPerBlock: u32 = 8;
+	/// Account that is sending reconciliation messages to Millau.
+	pub ReconciliationAccount: AccountId = bp_rialto::AccountIdConverter::convert(
+		pallet_bridge_fee_settlement::reconciliation_account_id(bp_runtime::RIALTO_CHAIN_ID),
+	);
+	/// Account that is dispatching reconciliation messages from Millau.
+	pub MillauReconciliationAccount: AccountId = bp_rialto::derive_account_from_millau_id(
+		bp_runtime::SourceAccount::Account(bp_millau::AccountIdConverter::convert(
+			pallet_bridge_fee_settlement::reconciliation_account_id(bp_runtime::MILLAU_CHAIN_ID),
+		)),
+	);
+}
+
+/// Instance of the fee settlement pallet used to settle fees with Millau chain.
+pub type WithMillauFeeSettlementInstance = ();
+
+impl pallet_bridge_fee_settlement::Config<WithMillauFeeSettlementInstance> for Runtime {
+	type Event = Event;
+	type Currency = Balances;
+	type AccountIdConverter = bp_rialto::AccountIdConverter;
+	type HubChainId = MillauChainId;
+	type SettlementOrigin =
+		pallet_bridge_fee_settlement::EnsureSettlementAccount<AccountId, MillauReconciliationAccount>;
+	type ReconciliationSender = bridge_runtime_common::messages_forwarding::MessagesPalletReconciliationSender<
+		Runtime,
+		WithMillauMessagesInstance,
+		ReconciliationAccount,
+		crate::millau_messages::ToMillauReconciliationMessageBuilder,
+	>;
+	type ReconciliationPeriod = ReconciliationPeriod;
+	type MaxReconciliationsPerBlock = MaxReconciliationsPerBlock;
+}
+
+/// Lanes of the messages pallet, that have been used before the lane registry has been added.
+pub struct ExistingMillauLanes;
+
+impl frame_support::traits::Get<Vec<bp_messages::LaneId>> for ExistingMillauLanes {
+	fn get() -> Vec<bp_messages::LaneId> {
+		use frame_support::IterableStorageMap;
+		pallet_bridge_messages::OutboundLanes::<WithMillauMessagesInstance>::iter()
+			.map(|(lane, _)| lane)
+			.collect()
+	}
+}
+
 construct_runtime!(
 	pub enum Runtime where
 		Block = Block,
@@ -557,9 +730,12 @@ construct_runtime!(
 		BridgeKovanCurrencyExchange: pallet_bridge_currency_exchange::<Instance2>::{Pallet, Call},
 
 		// Millau bridge modules.
-		BridgeMillauGrandpa: pallet_bridge_grandpa::{Pallet, Call, Storage},
-		BridgeDispatch: pallet_bridge_dispatch::{Pallet, Event<T>},
-		BridgeMillauMessages: pallet_bridge_messages::{Pallet, Call, Storage, Event<T>},
+		BridgeMillauGrandpa: pallet_bridge_grandpa::{Pallet, Call, Storage, Event<T>},
+		BridgeDispatch: pallet_bridge_dispatch::{Pallet, Call, Storage, Event<T>},
+		BridgeMillauMessages: pallet_bridge_messages::{Pallet, Call, Storage, Event<T>, ValidateUnsigned},
+		BridgeChunkedDispatch: pallet_bridge_chunked_dispatch::{Pallet, Call, Storage, Event<T>},
+		BridgeLaneRegistry: pallet_bridge_lane_registry::{Pallet, Call, Config<T>, Storage, Event<T>},
+		BridgeMillauFeeSettlement: pallet_bridge_fee_settlement::{Pallet, Call, Storage, Event<T>},
 	}
 );
 
@@ -573,6 +749,13 @@ pub type Block = generic::Block<Header, UncheckedExtrinsic>;
 pub type SignedBlock = generic::SignedBlock<Block>;
 /// BlockId type as expected by this runtime.
 pub type BlockId = generic::BlockId<Block>;
+bridge_runtime_common::declare_bridge_reject_obsolete_headers_and_messages!(
+	Runtime,
+	Call,
+	grandpa: [BridgeMillauGrandpa => MillauGrandpaInstance],
+	messages: [BridgeMillauMessages => WithMillauMessagesInstance]
+);
+
 /// The SignedExtension to the basic transaction logic.
 pub type SignedExtra = (
 	frame_system::CheckSpecVersion<Runtime>,
@@ -582,6 +765,7 @@ pub type SignedExtra = (
 	frame_system::CheckNonce<Runtime>,
 	frame_system::CheckWeight<Runtime>,
 	pallet_transaction_payment::ChargeTransactionPayment<Runtime>,
+	BridgeRejectObsoleteHeadersAndMessages,
 );
 /// The payload being signed in transactions.
 pub type SignedPayload = generic::SignedPayload<Call, SignedExtra>;
@@ -589,9 +773,18 @@ pub type SignedPayload = generic::SignedPayload<Call, SignedExtra>;
 pub type UncheckedExtrinsic = generic::UncheckedExtrinsic<Address, Call, Signature, SignedExtra>;
 /// Extrinsic type that has already been checked.
 pub type CheckedExtrinsic = generic::CheckedExtrinsic<AccountId, Call, SignedExtra>;
+/// Migrations that are applied when the runtime is upgraded.
+pub type Migrations = (
+	pallet_bridge_grandpa::InitializeImportedHashesCapacity<Runtime, MillauGrandpaInstance, HeadersToKeep>,
+	pallet_bridge_grandpa::InitializeAuthoritySetsHistory<Runtime, MillauGrandpaInstance>,
+	pallet_bridge_messages::migrations::MigrateToV1<Runtime, WithMillauMessagesInstance>,
+	pallet_bridge_messages::migrations::MigrateToV2<Runtime, WithMillauMessagesInstance>,
+	pallet_bridge_lane_registry::RegisterExistingLanes<Runtime, (), ExistingMillauLanes, LaneRegistryOwner>,
+	pallet_bridge_messages::CheckLanesInvariants<Runtime, WithMillauMessagesInstance>,
+);
 /// Executive: handles dispatch to the various modules.
 pub type Executive =
-	frame_executive::Executive<Runtime, Block, frame_system::ChainContext<Runtime>, Runtime, AllPallets>;
+	frame_executive::Executive<Runtime, Block, frame_system::ChainContext<Runtime>, Runtime, AllPallets, Migrations>;
 
 impl_runtime_apis! {
 	impl sp_api::Core<Block> for Runtime {
@@ -690,6 +883,21 @@ impl_runtime_apis! {
 		fn is_known_header(hash: bp_millau::Hash) -> bool {
 			BridgeMillauGrandpa::is_known_header(hash)
 		}
+
+		fn is_known_and_finalized(hash: bp_millau::Hash) -> Option<bp_millau::BlockNumber> {
+			BridgeMillauGrandpa::is_known_and_finalized(hash)
+		}
+
+		fn authority_set_changes() -> Vec<bp_header_chain::AuthoritySetChange<bp_millau::Hash, bp_millau::BlockNumber>> {
+			BridgeMillauGrandpa::authority_set_changes()
+		}
+
+		fn verify_finality_proof(
+			header: Vec<u8>,
+			justification: Vec<u8>,
+		) -> Result<(), bp_header_chain::FinalityProofError> {
+			BridgeMillauGrandpa::verify_encoded_finality_proof(&header, &justification)
+		}
 	}
 
 	impl bp_currency_exchange::RialtoCurrencyExchangeApi<Block, exchange::EthereumTransactionInclusionProof> for Runtime {
@@ -854,6 +1062,38 @@ impl_runtime_apis! {
 		fn latest_generated_nonce(lane: bp_messages::LaneId) -> bp_messages::MessageNonce {
 			BridgeMillauMessages::outbound_latest_generated_nonce(lane)
 		}
+
+		fn estimate_relayer_rewards(
+			lane: bp_messages::LaneId,
+			max_messages: bp_messages::MessageNonce,
+		) -> bp_messages::RelayerRewardsEstimation<Balance> {
+			bridge_runtime_common::messages_api::estimate_relayer_rewards::<
+				Runtime,
+				WithMillauMessagesInstance,
+				WithMillauMessageBridge,
+			>(lane, max_messages)
+		}
+
+		fn lane_statistics(lane: bp_messages::LaneId) -> bp_messages::LaneStatistics<Balance> {
+			BridgeMillauMessages::lane_statistics(lane)
+		}
+
+		fn maximal_message_arguments_size() -> u32 {
+			bridge_runtime_common::messages::source::maximal_message_arguments_size(
+				bp_rialto::max_extrinsic_size(),
+				bp_millau::max_extrinsic_size(),
+			)
+		}
+	}
+
+	impl bp_messages::BridgesInfoApi<Block> for Runtime {
+		fn bridges() -> Vec<bp_messages::BridgeInfo> {
+			vec![bridge_runtime_common::messages_api::bridge_info::<
+				Runtime,
+				WithMillauMessagesInstance,
+				MillauGrandpaInstance,
+			>(pallet_bridge_lane_registry::Lanes::<Runtime>::iter_keys().collect())]
+		}
 	}
 
 	impl bp_millau::FromMillauInboundLaneApi<Block> for Runtime {
@@ -868,6 +1108,24 @@ impl_runtime_apis! {
 		fn unrewarded_relayers_state(lane: bp_messages::LaneId) -> bp_messages::UnrewardedRelayersState {
 			BridgeMillauMessages::inbound_unrewarded_relayers_state(lane)
 		}
+
+		fn dry_run_message_dispatch(
+			lane: bp_messages::LaneId,
+			payload: Vec<u8>,
+		) -> bp_runtime::messages::MessageDispatchResult {
+			bridge_runtime_common::messages_api::dry_run_message_dispatch::<
+				Runtime,
+				WithMillauMessagesInstance,
+			>(lane, payload)
+		}
+	}
+
+	impl bp_millau::FromMillauDerivedAccountsApi<Block, AccountId> for Runtime {
+		fn derived_account_origin(
+			account_id: AccountId,
+		) -> Option<bp_message_dispatch::DerivedAccountOrigin<bp_millau::AccountId>> {
+			BridgeDispatch::derived_account_origin(account_id)
+		}
 	}
 
 	#[cfg(feature = "runtime-benchmarks")]
@@ -1096,10 +1354,14 @@ impl_runtime_apis! {
 						.any(|event| matches!(
 							event,
 							Event::BridgeDispatch(pallet_bridge_dispatch::Event::<Runtime, _>::MessageDispatched(
-								_, ([0, 0, 0, 0], nonce_from_event), _,
+								_, (bp_messages::LaneId::Legacy([0, 0, 0, 0]), nonce_from_event), _,
 							)) if nonce_from_event == nonce
 						))
 				}
+
+				fn pallet_parameter() -> Self::Parameter {
+					millau_messages::RialtoToMillauMessagesParameter::MillauToRialtoConversionRate(Default::default())
+				}
 			}
 
 			add_benchmark!(
@@ -1262,4 +1524,44 @@ mod tests {
 			additional_amount
 		});
 	}
+
+	#[test]
+	fn fee_settlement_pallet_call_is_encoded_correctly() {
+		use codec::Encode;
+		use frame_support::weights::GetDispatchInfo;
+
+		let lane = bp_messages::LaneId::Legacy([0, 0, 0, 0]);
+		let call = Call::BridgeMillauFeeSettlement(pallet_bridge_fee_settlement::Call::settle(lane, 100));
+		assert_eq!(
+			call.encode(),
+			pallet_bridge_fee_settlement::encode_settle_call(
+				bp_rialto::WITH_MILLAU_FEE_SETTLEMENT_PALLET_INDEX,
+				lane,
+				100 as Balance
+			),
+		);
+		assert!(call.get_dispatch_info().weight <= bp_rialto::FEE_SETTLEMENT_SETTLE_CALL_WEIGHT);
+	}
+
+	#[test]
+	fn dispatch_acknowledgement_call_is_encoded_correctly() {
+		use codec::Encode;
+		use frame_support::weights::GetDispatchInfo;
+
+		let acknowledgement = bp_message_dispatch::DispatchAcknowledgement {
+			message_id: (bp_messages::LaneId::Legacy([0, 0, 0, 0]), 1),
+			dispatch_result: true,
+		};
+		let call = Call::BridgeDispatch(pallet_bridge_dispatch::Call::receive_dispatch_acknowledgement(
+			acknowledgement.clone(),
+		));
+		assert_eq!(
+			call.encode(),
+			pallet_bridge_dispatch::encode_receive_dispatch_acknowledgement_call(
+				bp_rialto::WITH_MILLAU_DISPATCH_PALLET_INDEX,
+				acknowledgement,
+			),
+		);
+		assert!(call.get_dispatch_info().weight <= bp_rialto::DISPATCH_ACKNOWLEDGEMENT_CALL_WEIGHT);
+	}
 }
@@ -18,32 +18,47 @@
 
 use crate::Runtime;
 
+use bp_message_dispatch::{CallOrigin, DispatchAcknowledgement};
 use bp_messages::{
 	source_chain::TargetHeaderChain,
 	target_chain::{ProvedMessages, SourceHeaderChain},
 	InboundLaneData, LaneId, Message, MessageNonce, Parameter as MessagesParameter,
 };
-use bp_runtime::{ChainId, MILLAU_CHAIN_ID, RIALTO_CHAIN_ID};
-use bridge_runtime_common::messages::{self, MessageBridge, MessageTransaction};
+use bp_runtime::{messages::DispatchFeePayment, ChainId, StorageProofStats, MILLAU_CHAIN_ID, RIALTO_CHAIN_ID};
+use bridge_runtime_common::{
+	conversion_rate::ParameterConversionRate,
+	messages::{self, MessageBridge, MessageTransaction},
+	messages_acknowledgements::{AcknowledgementMessageBuilder, MessagesPalletMessageId},
+	messages_forwarding::ReconciliationMessageBuilder,
+};
 use codec::{Decode, Encode};
 use frame_support::{
 	parameter_types,
 	weights::{DispatchClass, Weight},
 	RuntimeDebug,
 };
-use sp_runtime::{traits::Saturating, FixedPointNumber, FixedU128};
+use pallet_bridge_messages::fee_split::FeeSplit;
+use sp_runtime::{traits::Saturating, FixedPointNumber, FixedU128, Perbill};
 use sp_std::{convert::TryFrom, ops::RangeInclusive};
 
 /// Initial value of `MillauToRialtoConversionRate` parameter.
 pub const INITIAL_MILLAU_TO_RIALTO_CONVERSION_RATE: FixedU128 = FixedU128::from_inner(FixedU128::DIV);
 /// Initial value of `MillauFeeMultiplier` parameter.
 pub const INITIAL_MILLAU_FEE_MULTIPLIER: FixedU128 = FixedU128::from_inner(FixedU128::DIV);
+/// Initial value of `MillauSpecVersion` parameter.
+pub const INITIAL_MILLAU_SPEC_VERSION: u32 = 1;
 
 parameter_types! {
 	/// Millau to Rialto conversion rate. Initially we treat both tokens as equal.
 	pub storage MillauToRialtoConversionRate: FixedU128 = INITIAL_MILLAU_TO_RIALTO_CONVERSION_RATE;
 	/// Fee multiplier value at Millau chain.
 	pub storage MillauFeeMultiplier: FixedU128 = INITIAL_MILLAU_FEE_MULTIPLIER;
+	/// Split of fees, collected from Rialto -> Millau messages, between relayers, treasury and burn.
+	pub storage ToMillauMessagesFeeSplit: FeeSplit = FeeSplit::default();
+	/// Part of delivery rewards of Rialto -> Millau messages that is paid to the confirmation relayer.
+	pub storage ToMillauConfirmationRewardShare: Perbill = Perbill::zero();
+	/// Spec version of the Millau runtime. It is used by messages that are sent by the Rialto runtime itself.
+	pub storage MillauSpecVersion: u32 = INITIAL_MILLAU_SPEC_VERSION;
 }
 
 /// Message payload for Rialto -> Millau messages.
@@ -72,6 +87,70 @@ pub type FromMillauMessagesProof = messages::target::FromBridgedChainMessagesPro
 /// Messages delivery proof for Rialto -> Millau messages.
 pub type ToMillauMessagesDeliveryProof = messages::source::FromBridgedChainMessagesDeliveryProof<bp_millau::Hash>;
 
+/// Builder of Rialto -> Millau reconciliation messages, that are calling `settle` of the Millau
+/// fee settlement pallet.
+#[derive(RuntimeDebug)]
+pub struct ToMillauReconciliationMessageBuilder;
+
+impl ReconciliationMessageBuilder<bp_rialto::Balance, ToMillauMessagePayload, bp_rialto::Balance>
+	for ToMillauReconciliationMessageBuilder
+{
+	fn build_reconciliation_message(
+		lane: LaneId,
+		amount: bp_rialto::Balance,
+	) -> Result<(ToMillauMessagePayload, bp_rialto::Balance), &'static str> {
+		let amount =
+			bp_millau::Balance::try_from(amount).map_err(|_| "Reconciliation amount overflows Millau balance")?;
+		let payload = ToMillauMessagePayload {
+			spec_version: MillauSpecVersion::get(),
+			weight: bp_millau::FEE_SETTLEMENT_SETTLE_CALL_WEIGHT,
+			origin: CallOrigin::SourceAccount(crate::ReconciliationAccount::get()),
+			dispatch_fee_payment: DispatchFeePayment::AtSourceChain,
+			call: pallet_bridge_fee_settlement::encode_settle_call(
+				bp_millau::WITH_RIALTO_FEE_SETTLEMENT_PALLET_INDEX,
+				lane,
+				amount,
+			),
+		};
+		let fee = messages::source::estimate_message_dispatch_and_delivery_fee::<WithMillauMessageBridge>(
+			&payload,
+			WithMillauMessageBridge::RELAYER_FEE_PERCENT,
+		)?;
+
+		Ok((payload, fee))
+	}
+}
+
+/// Builder of Rialto -> Millau dispatch acknowledgement messages, that are calling
+/// `receive_dispatch_acknowledgement` of the Millau dispatch pallet.
+#[derive(RuntimeDebug)]
+pub struct ToMillauAcknowledgementMessageBuilder;
+
+impl AcknowledgementMessageBuilder<MessagesPalletMessageId, ToMillauMessagePayload, bp_rialto::Balance>
+	for ToMillauAcknowledgementMessageBuilder
+{
+	fn build_acknowledgement_message(
+		acknowledgement: DispatchAcknowledgement<MessagesPalletMessageId>,
+	) -> Result<(ToMillauMessagePayload, bp_rialto::Balance), &'static str> {
+		let payload = ToMillauMessagePayload {
+			spec_version: MillauSpecVersion::get(),
+			weight: bp_millau::DISPATCH_ACKNOWLEDGEMENT_CALL_WEIGHT,
+			origin: CallOrigin::SourceAccount(crate::AcknowledgementAccount::get()),
+			dispatch_fee_payment: DispatchFeePayment::AtSourceChain,
+			call: pallet_bridge_dispatch::encode_receive_dispatch_acknowledgement_call(
+				bp_millau::WITH_RIALTO_DISPATCH_PALLET_INDEX,
+				acknowledgement,
+			),
+		};
+		let fee = messages::source::estimate_message_dispatch_and_delivery_fee::<WithMillauMessageBridge>(
+			&payload,
+			WithMillauMessageBridge::RELAYER_FEE_PERCENT,
+		)?;
+
+		Ok((payload, fee))
+	}
+}
+
 /// Millau <-> Rialto message bridge.
 #[derive(RuntimeDebug, Clone, Copy)]
 pub struct WithMillauMessageBridge;
@@ -84,11 +163,29 @@ impl MessageBridge for WithMillauMessageBridge {
 	type ThisChain = Rialto;
 	type BridgedChain = Millau;
 	type BridgedMessagesInstance = crate::WithMillauMessagesInstance;
+	type ConversionRateOracle = ParameterConversionRate<MillauToRialtoConversionRate>;
 
-	fn bridged_balance_to_this_balance(bridged_balance: bp_millau::Balance) -> bp_rialto::Balance {
-		bp_rialto::Balance::try_from(MillauToRialtoConversionRate::get().saturating_mul_int(bridged_balance))
+	fn bridged_balance_to_this_balance(
+		bridged_balance: bp_millau::Balance,
+		bridged_to_this_conversion_rate: FixedU128,
+	) -> bp_rialto::Balance {
+		bp_rialto::Balance::try_from(bridged_to_this_conversion_rate.saturating_mul_int(bridged_balance))
 			.unwrap_or(bp_rialto::Balance::MAX)
 	}
+
+	fn maximal_storage_proof_size() -> u32 {
+		Self::maximal_messages_storage_proof_size(bp_rialto::MAX_UNCONFIRMED_MESSAGES_AT_INBOUND_LANE, 1)
+	}
+
+	fn maximal_messages_storage_proof_size(messages_count: MessageNonce, lanes_count: u32) -> u32 {
+		messages::target::maximal_messages_storage_proof_size(
+			messages_count,
+			lanes_count,
+			messages::target::maximal_incoming_message_size(bp_rialto::max_extrinsic_size()),
+			bp_millau::EXTRA_STORAGE_PROOF_SIZE,
+		)
+		.min(bp_rialto::max_extrinsic_size())
+	}
 }
 
 /// Rialto chain from message lane point of view.
@@ -177,6 +274,11 @@ impl messages::BridgedChainWithMessages for Millau {
 		0..=upper_limit
 	}
 
+	fn is_dispatch_fee_payment_supported(_dispatch_fee_payment: DispatchFeePayment) -> bool {
+		// both source and target chain dispatch fee payment modes are supported by the Millau runtime
+		true
+	}
+
 	fn estimate_delivery_transaction(
 		message_payload: &[u8],
 		include_pay_dispatch_fee_cost: bool,
@@ -244,9 +346,14 @@ impl SourceHeaderChain<bp_millau::Balance> for Millau {
 	// - hash of the header this proof has been created with;
 	// - the storage proof of one or several keys;
 	// - id of the lane we prove messages for;
-	// - inclusive range of messages nonces that are proved.
+	// - inclusive range of messages nonces that are proved;
+	// - additional lanes (with inclusive ranges of their messages nonces) that are proved.
 	type MessagesProof = FromMillauMessagesProof;
 
+	fn proof_lanes_count(proof: &Self::MessagesProof) -> u32 {
+		proof.lanes_count()
+	}
+
 	fn verify_messages_proof(
 		proof: Self::MessagesProof,
 		messages_count: u32,
@@ -256,6 +363,17 @@ impl SourceHeaderChain<bp_millau::Balance> for Millau {
 			messages_count,
 		)
 	}
+	fn verify_messages_proof_with_stats(
+		proof: Self::MessagesProof,
+		messages_count: u32,
+	) -> Result<(ProvedMessages<Message<bp_millau::Balance>>, Option<StorageProofStats>), Self::Error> {
+		messages::target::verify_messages_proof_with_stats::<
+			WithMillauMessageBridge,
+			Runtime,
+			crate::MillauGrandpaInstance,
+		>(proof, messages_count)
+		.map(|(proved_messages, stats)| (proved_messages, Some(stats)))
+	}
 }
 
 /// Rialto -> Millau message lane pallet parameters.
@@ -263,6 +381,12 @@ impl SourceHeaderChain<bp_millau::Balance> for Millau {
 pub enum RialtoToMillauMessagesParameter {
 	/// The conversion formula we use is: `RialtoTokens = MillauTokens * conversion_rate`.
 	MillauToRialtoConversionRate(FixedU128),
+	/// Split of collected message fees between relayers, treasury and burn.
+	FeeSplit(FeeSplit),
+	/// Part of delivery rewards that is paid to the confirmation relayer.
+	ConfirmationRewardShare(Perbill),
+	/// Spec version of the Millau runtime.
+	MillauSpecVersion(u32),
 }
 
 impl MessagesParameter for RialtoToMillauMessagesParameter {
@@ -271,6 +395,13 @@ impl MessagesParameter for RialtoToMillauMessagesParameter {
 			RialtoToMillauMessagesParameter::MillauToRialtoConversionRate(ref conversion_rate) => {
 				MillauToRialtoConversionRate::set(conversion_rate)
 			}
+			RialtoToMillauMessagesParameter::FeeSplit(ref fee_split) => ToMillauMessagesFeeSplit::set(fee_split),
+			RialtoToMillauMessagesParameter::ConfirmationRewardShare(ref share) => {
+				ToMillauConfirmationRewardShare::set(share)
+			}
+			RialtoToMillauMessagesParameter::MillauSpecVersion(ref spec_version) => {
+				MillauSpecVersion::set(spec_version)
+			}
 		}
 	}
 }
@@ -279,7 +410,7 @@ impl MessagesParameter for RialtoToMillauMessagesParameter {
 mod tests {
 	use super::*;
 	use crate::{AccountId, Call, ExistentialDeposit, Runtime, SystemCall, SystemConfig, VERSION};
-	use bp_message_dispatch::CallOrigin;
+	use bp_message_dispatch::{CallOrigin, MessagePayload};
 	use bp_messages::{
 		target_chain::{DispatchMessage, DispatchMessageData, MessageDispatch},
 		MessageKey,
@@ -334,13 +465,15 @@ mod tests {
 						nonce: 0,
 					},
 					data: DispatchMessageData {
-						payload: Ok(FromBridgedChainMessagePayload::<WithMillauMessageBridge> {
-							spec_version: VERSION.spec_version,
-							weight: dispatch_weight,
-							origin: CallOrigin::SourceRoot,
-							dispatch_fee_payment: DispatchFeePayment::AtTargetChain,
-							call: FromBridgedChainEncodedMessageCall::new(call.encode()),
-						}),
+						payload: Ok(FromBridgedChainMessagePayload::<WithMillauMessageBridge>::V1(
+							MessagePayload {
+								spec_version: VERSION.spec_version,
+								weight: dispatch_weight,
+								origin: CallOrigin::SourceRoot,
+								dispatch_fee_payment: DispatchFeePayment::AtTargetChain,
+								call: FromBridgedChainEncodedMessageCall::new(call.encode()),
+							},
+						)),
 						fee: 1,
 					},
 				},
@@ -357,4 +490,183 @@ mod tests {
 			);
 		});
 	}
+
+	#[test]
+	fn reconciliation_message_from_millau_settles_lane_debt() {
+		let mut ext: sp_io::TestExternalities = SystemConfig::default().build_storage::<Runtime>().unwrap().into();
+		ext.execute_with(|| {
+			let lane = LaneId::Legacy([0, 0, 0, 0]);
+			let lane_pot = crate::BridgeMillauFeeSettlement::lane_pot_account_id(MILLAU_CHAIN_ID, lane);
+			let initial_amount = ExistentialDeposit::get();
+			let _ = <pallet_balances::Pallet<Runtime> as Currency<AccountId>>::deposit_creating(
+				&lane_pot,
+				initial_amount + 1_000,
+			);
+
+			// message is built the same way it is built by the Millau runtime
+			let millau_reconciliation_account = bp_millau::AccountIdConverter::convert(
+				pallet_bridge_fee_settlement::reconciliation_account_id(MILLAU_CHAIN_ID),
+			);
+			let dispatch_result = FromMillauMessageDispatch::dispatch(
+				&[1u8; 32].into(),
+				DispatchMessage {
+					key: MessageKey {
+						lane_id: lane,
+						nonce: 1,
+					},
+					data: DispatchMessageData {
+						payload: Ok(FromBridgedChainMessagePayload::<WithMillauMessageBridge>::V1(
+							MessagePayload {
+								spec_version: VERSION.spec_version,
+								weight: bp_rialto::FEE_SETTLEMENT_SETTLE_CALL_WEIGHT,
+								origin: CallOrigin::SourceAccount(millau_reconciliation_account),
+								dispatch_fee_payment: DispatchFeePayment::AtSourceChain,
+								call: FromBridgedChainEncodedMessageCall::new(
+									pallet_bridge_fee_settlement::encode_settle_call(
+										bp_rialto::WITH_MILLAU_FEE_SETTLEMENT_PALLET_INDEX,
+										lane,
+										1_000 as bp_rialto::Balance,
+									),
+								),
+							},
+						)),
+						fee: 1,
+					},
+				},
+			);
+
+			// debt has been paid from the lane pot to the account of Millau reconciliation account
+			assert!(dispatch_result.dispatch_result);
+			assert_eq!(
+				<pallet_balances::Pallet<Runtime> as Currency<AccountId>>::free_balance(&lane_pot),
+				initial_amount,
+			);
+			assert_eq!(
+				<pallet_balances::Pallet<Runtime> as Currency<AccountId>>::free_balance(
+					&crate::MillauReconciliationAccount::get()
+				),
+				1_000,
+			);
+			assert_eq!(crate::BridgeMillauFeeSettlement::outstanding_debt(lane), 0);
+		});
+	}
+
+	#[test]
+	fn acknowledgement_message_from_millau_is_received() {
+		let mut ext: sp_io::TestExternalities = SystemConfig::default().build_storage::<Runtime>().unwrap().into();
+		ext.execute_with(|| {
+			frame_system::Pallet::<Runtime>::set_block_number(1);
+
+			// message is built the same way it is built by the Millau runtime
+			let lane = LaneId::Legacy([0, 0, 0, 0]);
+			let millau_acknowledgement_account = bp_millau::AccountIdConverter::convert(
+				pallet_bridge_dispatch::acknowledgement_account_id(MILLAU_CHAIN_ID),
+			);
+			let dispatch_result = FromMillauMessageDispatch::dispatch(
+				&[1u8; 32].into(),
+				DispatchMessage {
+					key: MessageKey {
+						lane_id: lane,
+						nonce: 1,
+					},
+					data: DispatchMessageData {
+						payload: Ok(FromBridgedChainMessagePayload::<WithMillauMessageBridge>::V1(
+							MessagePayload {
+								spec_version: VERSION.spec_version,
+								weight: bp_rialto::DISPATCH_ACKNOWLEDGEMENT_CALL_WEIGHT,
+								origin: CallOrigin::SourceAccount(millau_acknowledgement_account),
+								dispatch_fee_payment: DispatchFeePayment::AtSourceChain,
+								call: FromBridgedChainEncodedMessageCall::new(
+									pallet_bridge_dispatch::encode_receive_dispatch_acknowledgement_call(
+										bp_rialto::WITH_MILLAU_DISPATCH_PALLET_INDEX,
+										DispatchAcknowledgement {
+											message_id: (lane, 42),
+											dispatch_result: true,
+										},
+									),
+								),
+							},
+						)),
+						fee: 1,
+					},
+				},
+			);
+
+			// acknowledgement of the Rialto -> Millau message has been received
+			assert!(dispatch_result.dispatch_result);
+			assert!(frame_system::Pallet::<Runtime>::events()
+				.iter()
+				.any(|record| record.event
+					== crate::Event::BridgeDispatch(
+						pallet_bridge_dispatch::Event::<Runtime>::DispatchAcknowledgementReceived(
+							MILLAU_CHAIN_ID,
+							(lane, 42),
+							true,
+						)
+					)));
+		});
+	}
+
+	#[test]
+	fn message_redelivered_after_inbound_lane_reset_is_not_dispatched_again() {
+		let mut ext: sp_io::TestExternalities = SystemConfig::default().build_storage::<Runtime>().unwrap().into();
+		ext.execute_with(|| {
+			frame_system::Pallet::<Runtime>::set_block_number(1);
+
+			let lane = LaneId::Legacy([0, 0, 0, 0]);
+			let deliver_remark = |lane_id, nonce| {
+				let call: Call = SystemCall::remark(vec![42]).into();
+				FromMillauMessageDispatch::dispatch(
+					&[1u8; 32].into(),
+					DispatchMessage {
+						key: MessageKey { lane_id, nonce },
+						data: DispatchMessageData {
+							payload: Ok(FromBridgedChainMessagePayload::<WithMillauMessageBridge>::V1(
+								MessagePayload {
+									spec_version: VERSION.spec_version,
+									weight: call.get_dispatch_info().weight,
+									origin: CallOrigin::SourceRoot,
+									dispatch_fee_payment: DispatchFeePayment::AtSourceChain,
+									call: FromBridgedChainEncodedMessageCall::new(call.encode()),
+								},
+							)),
+							fee: 1,
+						},
+					},
+				)
+			};
+
+			// first message of the lane is delivered and dispatched
+			assert!(deliver_remark(lane, 1).dispatch_result);
+
+			// inbound lane storage is reset (e.g. by the broken migration), so the messages pallet
+			// accepts the same message once again
+			pallet_bridge_messages::InboundLanes::<Runtime, WithMillauMessagesInstance>::remove(lane);
+			assert_eq!(crate::BridgeMillauMessages::inbound_latest_received_nonce(lane), 0);
+
+			// but the dispatch pallet refuses to dispatch it again
+			let dispatch_result = deliver_remark(lane, 1);
+			assert!(!dispatch_result.dispatch_result);
+			assert_eq!(
+				frame_system::Pallet::<Runtime>::events()
+					.pop()
+					.map(|record| record.event),
+				Some(crate::Event::BridgeDispatch(
+					pallet_bridge_dispatch::Event::<Runtime>::MessageAlreadyDispatched(MILLAU_CHAIN_ID, (lane, 1)),
+				)),
+			);
+
+			// while message with the same nonce from other lane is dispatched
+			assert!(deliver_remark(LaneId::Legacy([0, 0, 0, 1]), 1).dispatch_result);
+		});
+	}
+
+	#[test]
+	fn storage_proof_of_maximal_message_fits_into_extrinsic() {
+		let maximal_message_size = messages::target::maximal_incoming_message_size(bp_rialto::max_extrinsic_size());
+		let maximal_proof_size = WithMillauMessageBridge::maximal_messages_storage_proof_size(1, 1);
+		assert!(maximal_proof_size >= maximal_message_size + 2 * bp_millau::EXTRA_STORAGE_PROOF_SIZE);
+		assert!(maximal_proof_size < bp_rialto::max_extrinsic_size());
+		assert!(WithMillauMessageBridge::maximal_storage_proof_size() <= bp_rialto::max_extrinsic_size());
+	}
 }
@@ -0,0 +1,271 @@
+// Copyright 2019-2021 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Benchmarks of the messages proof verification.
+//!
+//! The proof is verified against the state root directly, so the numbers only include the cost
+//! of the storage proof verification and messages decoding. Every benchmark is parametrized by
+//! the number of messages in the proof, the size of every message payload and the shape of the
+//! proof (see `ProofShape`).
+//!
+//! Run with `cargo bench -p bridge-runtime-common`.
+
+use bp_messages::{LaneId, MessageData, MessageNonce, OutboundLaneData, DEFAULT_MESSAGE_PRIORITY};
+use bp_runtime::ChainId;
+use bridge_runtime_common::{
+	conversion_rate::ConversionRateOracle,
+	messages::{
+		target::{verify_messages_proof_with_state_root, FromBridgedChainMessagesProof},
+		BridgedChainWithMessages, ChainWithMessages, MessageBridge, MessageTransaction, ThisChainWithMessages,
+	},
+};
+use codec::Encode;
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use frame_support::weights::Weight;
+use sp_core::{Blake2Hasher, H256};
+use sp_runtime::FixedU128;
+use sp_std::{collections::btree_set::BTreeSet, ops::RangeInclusive};
+use sp_trie::{read_trie_value_with, trie_types::TrieDBMut, Layout, MemoryDB, Recorder, TrieMut};
+
+/// Lane that is used in benchmarks.
+const BENCH_LANE_ID: LaneId = LaneId::Legacy(*b"bnch");
+/// Number of unrelated storage entries in the populated Bridged chain state.
+const POPULATED_STATE_ENTRIES: u32 = 16_384;
+
+/// Numbers of messages in the proof.
+const MESSAGES_COUNTS: &[MessageNonce] = &[1, 16, 128];
+/// Sizes of every message payload.
+const MESSAGE_SIZES: &[usize] = &[128, 2 * 1024, 16 * 1024];
+
+/// Shape of the benchmarked proof.
+#[derive(Clone, Copy, Debug)]
+enum ProofShape {
+	/// The Bridged chain state only has proved values, so the proof is as compact as possible.
+	Compact,
+	/// Same as `Compact`, but the outbound lane state is also proved.
+	CompactWithLaneState,
+	/// The Bridged chain state has a lot of unrelated entries, so the proof includes branch nodes
+	/// with up to 16 children. That's what real chain proofs look like.
+	Populated,
+}
+
+impl ProofShape {
+	fn name(&self) -> &'static str {
+		match *self {
+			ProofShape::Compact => "compact",
+			ProofShape::CompactWithLaneState => "compact-with-lane-state",
+			ProofShape::Populated => "populated",
+		}
+	}
+}
+
+/// Bridge that is used in benchmarks. Only proof verification related methods are implemented.
+struct BenchBridge;
+
+impl MessageBridge for BenchBridge {
+	const RELAYER_FEE_PERCENT: u32 = 10;
+	const THIS_CHAIN_ID: ChainId = *b"this";
+	const BRIDGED_CHAIN_ID: ChainId = *b"brdg";
+
+	type ThisChain = BenchChain;
+	type BridgedChain = BenchChain;
+	type BridgedMessagesInstance = pallet_bridge_messages::DefaultInstance;
+	type ConversionRateOracle = BenchConversionRate;
+
+	fn bridged_balance_to_this_balance(bridged_balance: u64, _bridged_to_this_conversion_rate: FixedU128) -> u64 {
+		bridged_balance
+	}
+
+	fn maximal_storage_proof_size() -> u32 {
+		u32::MAX
+	}
+}
+
+struct BenchConversionRate;
+
+impl ConversionRateOracle for BenchConversionRate {
+	fn conversion_rate() -> Option<FixedU128> {
+		unreachable!()
+	}
+}
+
+struct BenchChain;
+
+impl ChainWithMessages for BenchChain {
+	type Hash = H256;
+	type AccountId = u64;
+	type Signer = u64;
+	type Signature = u64;
+	type Weight = Weight;
+	type Balance = u64;
+}
+
+impl ThisChainWithMessages for BenchChain {
+	type Call = ();
+
+	fn is_outbound_lane_enabled(_lane: &LaneId) -> bool {
+		unreachable!()
+	}
+
+	fn maximal_pending_messages_at_outbound_lane() -> MessageNonce {
+		unreachable!()
+	}
+
+	fn estimate_delivery_confirmation_transaction() -> MessageTransaction<Weight> {
+		unreachable!()
+	}
+
+	fn transaction_payment(_transaction: MessageTransaction<Weight>) -> u64 {
+		unreachable!()
+	}
+}
+
+impl BridgedChainWithMessages for BenchChain {
+	fn maximal_extrinsic_size() -> u32 {
+		unreachable!()
+	}
+
+	fn message_weight_limits(_message_payload: &[u8]) -> RangeInclusive<Weight> {
+		unreachable!()
+	}
+
+	fn is_dispatch_fee_payment_supported(_dispatch_fee_payment: bp_runtime::messages::DispatchFeePayment) -> bool {
+		unreachable!()
+	}
+
+	fn estimate_delivery_transaction(
+		_message_payload: &[u8],
+		_include_pay_dispatch_fee_cost: bool,
+		_message_dispatch_weight: Weight,
+	) -> MessageTransaction<Weight> {
+		unreachable!()
+	}
+
+	fn transaction_payment(_transaction: MessageTransaction<Weight>) -> u64 {
+		unreachable!()
+	}
+}
+
+/// Prepare messages proof and the state root it has been built at.
+fn prepare_proof(
+	messages_count: MessageNonce,
+	message_size: usize,
+	shape: ProofShape,
+) -> (FromBridgedChainMessagesProof<H256>, H256) {
+	let mut root = Default::default();
+	let mut mdb = MemoryDB::<Blake2Hasher>::default();
+	let mut proved_keys = Vec::with_capacity(messages_count as usize + 1);
+	{
+		let mut trie = TrieDBMut::<Blake2Hasher>::new(&mut mdb, &mut root);
+
+		for nonce in 1..=messages_count {
+			let storage_key = pallet_bridge_messages::storage_keys::message_key::<
+				pallet_bridge_messages::DefaultInstance,
+			>(&BENCH_LANE_ID, nonce);
+			let message_data = MessageData::<u64> {
+				payload: vec![42u8; message_size],
+				fee: 0,
+				priority: DEFAULT_MESSAGE_PRIORITY,
+			};
+			trie.insert(storage_key.0.as_ref(), &message_data.encode())
+				.expect("TrieMut::insert should not fail in benchmarks");
+			proved_keys.push(storage_key.0);
+		}
+
+		if let ProofShape::CompactWithLaneState | ProofShape::Populated = shape {
+			let storage_key = pallet_bridge_messages::storage_keys::outbound_lane_data_key::<
+				pallet_bridge_messages::DefaultInstance,
+			>(&BENCH_LANE_ID);
+			let outbound_lane_data = OutboundLaneData {
+				oldest_unpruned_nonce: 1,
+				latest_received_nonce: 0,
+				latest_generated_nonce: messages_count,
+			};
+			trie.insert(storage_key.0.as_ref(), &outbound_lane_data.encode())
+				.expect("TrieMut::insert should not fail in benchmarks");
+			proved_keys.push(storage_key.0);
+		}
+
+		if let ProofShape::Populated = shape {
+			for index in 0..POPULATED_STATE_ENTRIES {
+				let storage_key = sp_core::hashing::blake2_256(&index.encode());
+				trie.insert(&storage_key, &index.encode())
+					.expect("TrieMut::insert should not fail in benchmarks");
+			}
+		}
+	}
+
+	let mut proof_recorder = Recorder::<H256>::new();
+	for proved_key in &proved_keys {
+		read_trie_value_with::<Layout<Blake2Hasher>, _, _>(&mdb, &root, proved_key, &mut proof_recorder)
+			.expect("read_trie_value_with should not fail in benchmarks");
+	}
+	// the verifier rejects proofs with duplicate nodes
+	let storage_proof = proof_recorder
+		.drain()
+		.into_iter()
+		.map(|n| n.data.to_vec())
+		.collect::<BTreeSet<_>>()
+		.into_iter()
+		.collect();
+
+	(
+		FromBridgedChainMessagesProof {
+			bridged_header_hash: Default::default(),
+			storage_proof,
+			lane: BENCH_LANE_ID,
+			nonces_start: 1,
+			nonces_end: messages_count,
+			additional_lanes: Vec::new(),
+		},
+		root,
+	)
+}
+
+fn verify_messages_proof(c: &mut Criterion) {
+	for shape in &[
+		ProofShape::Compact,
+		ProofShape::CompactWithLaneState,
+		ProofShape::Populated,
+	] {
+		let mut group = c.benchmark_group(format!("verify_messages_proof/{}", shape.name()));
+		for messages_count in MESSAGES_COUNTS {
+			for message_size in MESSAGE_SIZES {
+				let (proof, state_root) = prepare_proof(*messages_count, *message_size, *shape);
+				let proof_size: usize = proof.storage_proof.iter().map(|node| node.len()).sum();
+				group.throughput(Throughput::Bytes(proof_size as u64));
+				group.bench_with_input(
+					BenchmarkId::new(format!("{}-messages", messages_count), message_size),
+					&proof,
+					|b, proof| {
+						b.iter(|| {
+							verify_messages_proof_with_state_root::<BenchBridge, Blake2Hasher>(
+								proof.clone(),
+								*messages_count as u32,
+								state_root,
+							)
+							.expect("prepared proof is valid")
+						})
+					},
+				);
+			}
+		}
+		group.finish();
+	}
+}
+
+criterion_group!(benches, verify_messages_proof);
+criterion_main!(benches);
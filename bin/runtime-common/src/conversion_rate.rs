@@ -0,0 +1,184 @@
+// Copyright 2019-2021 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Sources of the Bridged to This chain tokens conversion rate.
+//!
+//! The conversion rate is used to compute fee of messages, sent to the Bridged chain. The
+//! conversion formula is: `ThisTokens = BridgedTokens * conversion_rate`.
+
+use frame_support::traits::Get;
+use sp_runtime::{FixedPointNumber, FixedU128};
+use sp_std::{marker::PhantomData, vec::Vec};
+
+/// Oracle of the Bridged to This chain tokens conversion rate.
+///
+/// The oracle may be backed by the runtime storage parameter, a separate oracle pallet, rates
+/// submitted by relayers, a DEX time-weighted average price, or a combination of them.
+pub trait ConversionRateOracle {
+	/// Returns current conversion rate or `None` if the rate is unknown.
+	///
+	/// Messages are not accepted while the rate is unknown, because their fee can't be computed.
+	fn conversion_rate() -> Option<FixedU128>;
+}
+
+/// Set of conversion rate oracles.
+///
+/// It is implemented for tuples of up to 8 oracles.
+pub trait ConversionRateOracles {
+	/// Returns rates of all oracles that know the conversion rate.
+	fn conversion_rates() -> Vec<FixedU128>;
+}
+
+/// Conversion rate oracle that is backed by the value, stored in the runtime storage (or any
+/// other `Get` implementation).
+///
+/// The value is normally updated by the `update_pallet_parameter` call of the messages pallet.
+pub struct ParameterConversionRate<Rate>(PhantomData<Rate>);
+
+impl<Rate: Get<FixedU128>> ConversionRateOracle for ParameterConversionRate<Rate> {
+	fn conversion_rate() -> Option<FixedU128> {
+		Some(Rate::get())
+	}
+}
+
+/// Conversion rate oracle that returns median of rates, provided by multiple oracles.
+///
+/// Oracles that don't know the conversion rate are ignored. If there are two middle rates, their
+/// average is returned. If none of oracles knows the rate, `None` is returned.
+pub struct MedianConversionRate<Oracles>(PhantomData<Oracles>);
+
+impl<Oracles: ConversionRateOracles> ConversionRateOracle for MedianConversionRate<Oracles> {
+	fn conversion_rate() -> Option<FixedU128> {
+		median(Oracles::conversion_rates())
+	}
+}
+
+/// Returns median of given rates.
+fn median(mut rates: Vec<FixedU128>) -> Option<FixedU128> {
+	if rates.is_empty() {
+		return None;
+	}
+
+	rates.sort();
+	let middle = rates.len() / 2;
+	if rates.len() % 2 == 1 {
+		return Some(rates[middle]);
+	}
+
+	// (a + b) / 2 may overflow, so we're computing (a / 2 + b / 2 + (a % 2 + b % 2) / 2)
+	let (lower, upper) = (rates[middle - 1].into_inner(), rates[middle].into_inner());
+	Some(FixedU128::from_inner(
+		lower / 2 + upper / 2 + (lower % 2 + upper % 2) / 2,
+	))
+}
+
+macro_rules! impl_conversion_rate_oracles_for_tuples {
+	($($oracle:ident),+) => {
+		impl<$($oracle: ConversionRateOracle),+> ConversionRateOracles for ($($oracle,)+) {
+			fn conversion_rates() -> Vec<FixedU128> {
+				let mut rates = Vec::new();
+				$(rates.extend($oracle::conversion_rate());)+
+				rates
+			}
+		}
+	};
+}
+
+impl_conversion_rate_oracles_for_tuples!(A);
+impl_conversion_rate_oracles_for_tuples!(A, B);
+impl_conversion_rate_oracles_for_tuples!(A, B, C);
+impl_conversion_rate_oracles_for_tuples!(A, B, C, D);
+impl_conversion_rate_oracles_for_tuples!(A, B, C, D, E);
+impl_conversion_rate_oracles_for_tuples!(A, B, C, D, E, F);
+impl_conversion_rate_oracles_for_tuples!(A, B, C, D, E, F, G);
+impl_conversion_rate_oracles_for_tuples!(A, B, C, D, E, F, G, H);
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	macro_rules! declare_oracle {
+		($name:ident, $rate:expr) => {
+			struct $name;
+
+			impl ConversionRateOracle for $name {
+				fn conversion_rate() -> Option<FixedU128> {
+					$rate.map(FixedU128::saturating_from_integer)
+				}
+			}
+		};
+	}
+
+	declare_oracle!(Unknown, None::<u32>);
+	declare_oracle!(One, Some(1u32));
+	declare_oracle!(Two, Some(2u32));
+	declare_oracle!(Five, Some(5u32));
+	declare_oracle!(Hundred, Some(100u32));
+
+	#[test]
+	fn parameter_conversion_rate_returns_parameter_value() {
+		frame_support::parameter_types! {
+			const Rate: FixedU128 = FixedU128::from_inner(42);
+		}
+
+		assert_eq!(
+			ParameterConversionRate::<Rate>::conversion_rate(),
+			Some(FixedU128::from_inner(42)),
+		);
+	}
+
+	#[test]
+	fn median_conversion_rate_is_unknown_if_no_oracle_knows_the_rate() {
+		assert_eq!(MedianConversionRate::<(Unknown,)>::conversion_rate(), None);
+		assert_eq!(MedianConversionRate::<(Unknown, Unknown)>::conversion_rate(), None);
+	}
+
+	#[test]
+	fn median_conversion_rate_ignores_unknown_rates() {
+		assert_eq!(
+			MedianConversionRate::<(Unknown, Five, Unknown)>::conversion_rate(),
+			Some(FixedU128::saturating_from_integer(5)),
+		);
+	}
+
+	#[test]
+	fn median_conversion_rate_is_not_affected_by_outliers() {
+		assert_eq!(
+			MedianConversionRate::<(Hundred, Two, One)>::conversion_rate(),
+			Some(FixedU128::saturating_from_integer(2)),
+		);
+	}
+
+	#[test]
+	fn median_conversion_rate_of_even_number_of_rates_is_average_of_middle_rates() {
+		assert_eq!(
+			MedianConversionRate::<(One, Two)>::conversion_rate(),
+			Some(FixedU128::saturating_from_rational(3, 2)),
+		);
+		assert_eq!(
+			MedianConversionRate::<(Hundred, One, Five, Two)>::conversion_rate(),
+			Some(FixedU128::saturating_from_rational(7, 2)),
+		);
+	}
+
+	#[test]
+	fn median_of_large_rates_does_not_overflow() {
+		assert_eq!(
+			median(vec![FixedU128::from_inner(u128::MAX), FixedU128::from_inner(u128::MAX)]),
+			Some(FixedU128::from_inner(u128::MAX)),
+		);
+	}
+}
@@ -18,6 +18,11 @@
 
 #![cfg_attr(not(feature = "std"), no_std)]
 
+pub mod conversion_rate;
 pub mod messages;
+pub mod messages_acknowledgements;
 pub mod messages_api;
 pub mod messages_benchmarking;
+pub mod messages_forwarding;
+pub mod reject_obsolete_transactions;
+pub mod reward_split;
@@ -20,19 +20,21 @@
 //! pallet is used to dispatch incoming messages. Message identified by a tuple
 //! of to elements - message lane id and message nonce.
 
+use crate::conversion_rate::ConversionRateOracle;
+
 use bp_message_dispatch::MessageDispatch as _;
 use bp_messages::{
 	source_chain::{LaneMessageVerifier, Sender},
 	target_chain::{DispatchMessage, MessageDispatch, ProvedLaneMessages, ProvedMessages},
-	InboundLaneData, LaneId, Message, MessageData, MessageKey, MessageNonce, OutboundLaneData,
+	InboundLaneData, LaneId, Message, MessageData, MessageKey, MessageNonce, OutboundLaneData, UnrewardedRelayer,
 };
 use bp_runtime::{
 	messages::{DispatchFeePayment, MessageDispatchResult},
-	ChainId, Size, StorageProofChecker,
+	ChainId, Size, StorageProofChecker, StorageProofError, StorageProofStats,
 };
 use codec::{Decode, Encode};
 use frame_support::{
-	traits::{Currency, ExistenceRequirement, Instance},
+	traits::{Currency, ExistenceRequirement, Instance, WithdrawReasons},
 	weights::{Weight, WeightToFeePolynomial},
 	RuntimeDebug,
 };
@@ -60,9 +62,32 @@ pub trait MessageBridge {
 	type BridgedChain: BridgedChainWithMessages;
 	/// Instance of the `pallet-bridge-messages` pallet at the Bridged chain.
 	type BridgedMessagesInstance: Instance;
+	/// Oracle of the Bridged to This chain tokens conversion rate.
+	type ConversionRateOracle: ConversionRateOracle;
+
+	/// Convert Bridged chain balance into This chain balance, using given conversion rate.
+	///
+	/// The conversion formula is: `ThisTokens = BridgedTokens * bridged_to_this_conversion_rate`.
+	fn bridged_balance_to_this_balance(
+		bridged_balance: BalanceOf<BridgedChain<Self>>,
+		bridged_to_this_conversion_rate: FixedU128,
+	) -> BalanceOf<ThisChain<Self>>;
 
-	/// Convert Bridged chain balance into This chain balance.
-	fn bridged_balance_to_this_balance(bridged_balance: BalanceOf<BridgedChain<Self>>) -> BalanceOf<ThisChain<Self>>;
+	/// Maximal size (in bytes) of the Bridged chain storage proof that is accepted by This chain.
+	///
+	/// Messages and messages delivery proofs that are larger than this limit, are rejected before
+	/// any trie work is done. Valid proofs are bounded by the maximal size of This chain
+	/// extrinsic, so it is a reasonable limit.
+	fn maximal_storage_proof_size() -> u32;
+
+	/// Maximal size (in bytes) of the Bridged chain storage proof of `messages_count` messages,
+	/// sent over `lanes_count` lanes, that is accepted by This chain.
+	///
+	/// Messages proofs that are larger than this limit, are rejected before any trie work is done.
+	/// By default, it is the same as the `maximal_storage_proof_size`.
+	fn maximal_messages_storage_proof_size(_messages_count: MessageNonce, _lanes_count: u32) -> u32 {
+		Self::maximal_storage_proof_size()
+	}
 }
 
 /// Chain that has `pallet-bridge-messages` and `dispatch` modules.
@@ -129,6 +154,12 @@ pub trait BridgedChainWithMessages: ChainWithMessages {
 	/// return pure call dispatch weights range.
 	fn message_weight_limits(message_payload: &[u8]) -> RangeInclusive<Self::Weight>;
 
+	/// Returns true if the Bridged chain supports given dispatch fee payment mode.
+	///
+	/// Messages that are using unsupported mode are rejected at This chain, because otherwise
+	/// they would fail to dispatch at the Bridged chain.
+	fn is_dispatch_fee_payment_supported(dispatch_fee_payment: DispatchFeePayment) -> bool;
+
 	/// Estimate size and weight of single message delivery transaction at the Bridged chain.
 	fn estimate_delivery_transaction(
 		message_payload: &[u8],
@@ -154,6 +185,25 @@ pub(crate) type CallOf<C> = <C as ThisChainWithMessages>::Call;
 /// Raw storage proof type (just raw trie nodes).
 type RawStorageProof = Vec<Vec<u8>>;
 
+/// Error that is returned when the storage proof exceeds `MessageBridge::maximal_storage_proof_size()`.
+pub(crate) const PROOF_TOO_LARGE: &str = "Storage proof is too large";
+/// Error that is returned when the storage proof contains trie nodes that are not required to
+/// read proved values.
+pub(crate) const PROOF_HAS_UNUSED_NODES: &str = "Storage proof contains unused trie nodes";
+
+/// Returns true if size of the raw storage proof doesn't exceed given limit.
+fn is_storage_proof_size_acceptable(storage_proof: &[Vec<u8>], maximal_storage_proof_size: u32) -> bool {
+	let mut storage_proof_size = 0usize;
+	for node in storage_proof {
+		storage_proof_size = storage_proof_size.saturating_add(node.len());
+		if storage_proof_size > maximal_storage_proof_size as usize {
+			return false;
+		}
+	}
+
+	true
+}
+
 /// Compute fee of transaction at runtime where regular transaction payment pallet is being used.
 ///
 /// The value of `multiplier` parameter is the expected value of `pallet_transaction_payment::NextFeeMultiplier`
@@ -198,8 +248,9 @@ pub mod source {
 	/// Messages delivery proof from bridged chain:
 	///
 	/// - hash of finalized header;
-	/// - storage proof of inbound lane state;
-	/// - lane id.
+	/// - storage proof of inbound lane state or of selected unrewarded relayer entries;
+	/// - lane id;
+	/// - first nonces of selected unrewarded relayer entries.
 	#[derive(Clone, Decode, Encode, Eq, PartialEq, RuntimeDebug)]
 	pub struct FromBridgedChainMessagesDeliveryProof<BridgedHeaderHash> {
 		/// Hash of the bridge header the proof is for.
@@ -208,6 +259,12 @@ pub mod source {
 		pub storage_proof: RawStorageProof,
 		/// Lane id of which messages were delivered and the proof is for.
 		pub lane: LaneId,
+		/// First nonces of unrewarded relayer entries, included into the compact proof.
+		///
+		/// If it is `None`, the storage proof contains the whole inbound lane state. Otherwise it
+		/// only contains `InboundLaneRelayers` entries, starting at given nonces. Entries must
+		/// cover all messages that are not yet confirmed at this chain.
+		pub relayer_entries: Option<Vec<MessageNonce>>,
 	}
 
 	impl<BridgedHeaderHash> Size for FromBridgedChainMessagesDeliveryProof<BridgedHeaderHash> {
@@ -295,6 +352,28 @@ pub mod source {
 		super::target::maximal_incoming_message_size(BridgedChain::<B>::maximal_extrinsic_size())
 	}
 
+	/// Number of bytes of This chain `send_message` transaction that are reserved for everything
+	/// except the message call: signature, signed extensions and other `send_message` arguments.
+	pub const SEND_MESSAGE_TRANSACTION_SERVICE_BYTES: u32 = 1024;
+
+	/// Number of bytes of the encoded Bridged chain call that are used to encode everything except
+	/// call arguments: pallet index, call index and (at most 4 bytes) compact length of arguments.
+	pub const CALL_SERVICE_BYTES: u32 = 1 + 1 + 4;
+
+	/// Return maximal size of arguments of the Bridged chain call that may be sent in a This -> Bridged
+	/// chain message.
+	///
+	/// The message must fit into both This chain `send_message` transaction and Bridged chain message
+	/// delivery transaction, so the smallest of two limits is used.
+	pub fn maximal_message_arguments_size(
+		maximal_this_extrinsic_size: u32,
+		maximal_bridged_extrinsic_size: u32,
+	) -> u32 {
+		let maximal_this_call_size = maximal_this_extrinsic_size.saturating_sub(SEND_MESSAGE_TRANSACTION_SERVICE_BYTES);
+		let maximal_bridged_call_size = super::target::maximal_incoming_message_size(maximal_bridged_extrinsic_size);
+		sp_std::cmp::min(maximal_this_call_size, maximal_bridged_call_size).saturating_sub(CALL_SERVICE_BYTES)
+	}
+
 	/// Do basic Bridged-chain specific verification of This -> Bridged chain message.
 	///
 	/// Ok result from this function means that the delivery transaction with this message
@@ -308,6 +387,10 @@ pub mod source {
 			return Err("Incorrect message weight declared");
 		}
 
+		if !BridgedChain::<B>::is_dispatch_fee_payment_supported(payload.dispatch_fee_payment) {
+			return Err("Dispatch fee payment mode is not supported by the bridged chain");
+		}
+
 		// The maximal size of extrinsic at Substrate-based chain depends on the
 		// `frame_system::Config::MaximumBlockLength` and `frame_system::Config::AvailableBlockRatio`
 		// constants. This check is here to be sure that the lane won't stuck because message is too
@@ -354,8 +437,10 @@ pub mod source {
 		let confirmation_transaction_fee = ThisChain::<B>::transaction_payment(confirmation_transaction);
 
 		// minimal fee (in This tokens) is a sum of all required fees
-		let minimal_fee =
-			B::bridged_balance_to_this_balance(delivery_transaction_fee).checked_add(&confirmation_transaction_fee);
+		let bridged_to_this_conversion_rate =
+			B::ConversionRateOracle::conversion_rate().ok_or("Bridged chain tokens conversion rate is unknown")?;
+		let minimal_fee = B::bridged_balance_to_this_balance(delivery_transaction_fee, bridged_to_this_conversion_rate)
+			.checked_add(&confirmation_transaction_fee);
 
 		// before returning, add extra fee that is paid to the relayer (relayer interest)
 		minimal_fee
@@ -383,27 +468,81 @@ pub mod source {
 			bridged_header_hash,
 			storage_proof,
 			lane,
+			relayer_entries,
 		} = proof;
+		if !is_storage_proof_size_acceptable(&storage_proof, B::maximal_storage_proof_size()) {
+			return Err(PROOF_TOO_LARGE);
+		}
+
 		pallet_bridge_grandpa::Pallet::<ThisRuntime, GrandpaInstance>::parse_finalized_storage_proof(
 			bridged_header_hash.into(),
 			StorageProof::new(storage_proof),
 			|storage| {
-				// Messages delivery proof is just proof of single storage key read => any error
-				// is fatal.
-				let storage_inbound_lane_data_key =
-					pallet_bridge_messages::storage_keys::inbound_lane_data_key::<B::BridgedMessagesInstance>(&lane);
-				let raw_inbound_lane_data = storage
-					.read_value(storage_inbound_lane_data_key.0.as_ref())
-					.map_err(|_| "Failed to read inbound lane state from storage proof")?
-					.ok_or("Inbound lane state is missing from the messages proof")?;
-				let inbound_lane_data = InboundLaneData::decode(&mut &raw_inbound_lane_data[..])
-					.map_err(|_| "Failed to decode inbound lane state from the proof")?;
-
-				Ok((lane, inbound_lane_data))
+				let parsed_proof = match relayer_entries {
+					Some(relayer_entries) => {
+						read_relayer_entries_from_storage_proof::<B, _>(&storage, lane, relayer_entries)?
+					}
+					None => {
+						// Messages delivery proof is just proof of single storage key read => any error
+						// is fatal.
+						let storage_inbound_lane_data_key = pallet_bridge_messages::storage_keys::inbound_lane_data_key::<
+							B::BridgedMessagesInstance,
+						>(&lane);
+						let raw_inbound_lane_data = storage
+							.read_value(storage_inbound_lane_data_key.0.as_ref())
+							.map_err(|_| "Failed to read inbound lane state from storage proof")?
+							.ok_or("Inbound lane state is missing from the messages proof")?;
+						let inbound_lane_data = InboundLaneData::decode(&mut &raw_inbound_lane_data[..])
+							.map_err(|_| "Failed to decode inbound lane state from the proof")?;
+
+						(lane, inbound_lane_data)
+					}
+				};
+
+				// padded proofs are rejected, because their submitter hasn't paid for the padding
+				storage.ensure_no_unused_nodes().map_err(|_| PROOF_HAS_UNUSED_NODES)?;
+
+				Ok(parsed_proof)
 			},
 		)
 		.map_err(<&'static str>::from)?
 	}
+
+	/// Read selected unrewarded relayer entries from the compact messages delivery proof.
+	///
+	/// The returned inbound lane state only contains selected entries. Its `last_confirmed_nonce` is
+	/// set to the nonce that precedes the first entry, because we don't know the actual value.
+	fn read_relayer_entries_from_storage_proof<B: MessageBridge, H: Hasher>(
+		storage: &StorageProofChecker<H>,
+		lane: LaneId,
+		relayer_entries: Vec<MessageNonce>,
+	) -> Result<ParsedMessagesDeliveryProofFromBridgedChain<B>, &'static str> {
+		let first_entry_begin = *relayer_entries
+			.first()
+			.ok_or("Compact messages delivery proof has no relayer entries")?;
+		let relayers = relayer_entries
+			.into_iter()
+			.map(|begin| {
+				let storage_entry_key = pallet_bridge_messages::storage_keys::inbound_lane_relayer_entry_key::<
+					B::BridgedMessagesInstance,
+				>(&lane, begin);
+				let raw_entry = storage
+					.read_value(storage_entry_key.0.as_ref())
+					.map_err(|_| "Failed to read unrewarded relayer entry from storage proof")?
+					.ok_or("Unrewarded relayer entry is missing from the messages delivery proof")?;
+				UnrewardedRelayer::decode(&mut &raw_entry[..])
+					.map_err(|_| "Failed to decode unrewarded relayer entry from the proof")
+			})
+			.collect::<Result<_, _>>()?;
+
+		Ok((
+			lane,
+			InboundLaneData {
+				relayers,
+				last_confirmed_nonce: first_entry_begin.saturating_sub(1),
+			},
+		))
+	}
 }
 
 /// Sub-module that is declaring types required for processing Bridged -> This chain messages.
@@ -418,7 +557,9 @@ pub mod target {
 	>;
 
 	/// Decoded Bridged -> This message payload.
-	pub type FromBridgedChainMessagePayload<B> = bp_message_dispatch::MessagePayload<
+	///
+	/// Both legacy (unversioned) and versioned payloads are accepted.
+	pub type FromBridgedChainMessagePayload<B> = bp_message_dispatch::VersionedMessagePayload<
 		AccountIdOf<BridgedChain<B>>,
 		SignerOf<ThisChain<B>>,
 		SignatureOf<ThisChain<B>>,
@@ -430,7 +571,8 @@ pub mod target {
 	/// - hash of finalized header;
 	/// - storage proof of messages and (optionally) outbound lane state;
 	/// - lane id;
-	/// - nonces (inclusive range) of messages which are included in this proof.
+	/// - nonces (inclusive range) of messages which are included in this proof;
+	/// - additional lanes and nonces of their messages, if the proof covers several lanes.
 	#[derive(Clone, Decode, Encode, Eq, PartialEq, RuntimeDebug)]
 	pub struct FromBridgedChainMessagesProof<BridgedHeaderHash> {
 		/// Hash of the finalized bridged header the proof is for.
@@ -442,6 +584,26 @@ pub mod target {
 		pub nonces_start: MessageNonce,
 		/// Nonce of the last message being delivered.
 		pub nonces_end: MessageNonce,
+		/// Other lanes whose messages are delivered using the same storage proof. Every entry
+		/// is the lane id and the (inclusive) range of nonces of messages being delivered.
+		pub additional_lanes: Vec<(LaneId, MessageNonce, MessageNonce)>,
+	}
+
+	impl<BridgedHeaderHash> FromBridgedChainMessagesProof<BridgedHeaderHash> {
+		/// Return number of lanes covered by this proof.
+		pub fn lanes_count(&self) -> u32 {
+			u32::try_from(self.additional_lanes.len())
+				.unwrap_or(u32::MAX)
+				.saturating_add(1)
+		}
+
+		/// Return number of messages covered by this proof (at all lanes).
+		pub fn messages_count(&self) -> MessageNonce {
+			sp_std::iter::once((self.lane, self.nonces_start, self.nonces_end))
+				.chain(self.additional_lanes.iter().cloned())
+				.map(|(_, nonces_start, nonces_end)| nonces_end.saturating_add(1).saturating_sub(nonces_start))
+				.fold(0, |total, lane_messages| total.saturating_add(lane_messages))
+		}
 	}
 
 	impl<BridgedHeaderHash> Size for FromBridgedChainMessagesProof<BridgedHeaderHash> {
@@ -512,7 +674,18 @@ pub mod target {
 		fn dispatch_weight(
 			message: &DispatchMessage<Self::DispatchPayload, BalanceOf<BridgedChain<B>>>,
 		) -> frame_support::weights::Weight {
-			message.data.payload.as_ref().map(|payload| payload.weight).unwrap_or(0)
+			match message.data.payload {
+				Ok(ref payload) => payload.weight(),
+				Err(ref undecodable) => {
+					pallet_bridge_dispatch::Pallet::<ThisRuntime, ThisDispatchInstance>::undecodable_message_weight(
+						undecodable.payload.len(),
+					)
+				}
+			}
+		}
+
+		fn dispatch_overhead_weight() -> frame_support::weights::Weight {
+			pallet_bridge_dispatch::Pallet::<ThisRuntime, ThisDispatchInstance>::dispatched_message_recording_weight()
 		}
 
 		fn dispatch(
@@ -524,11 +697,9 @@ pub mod target {
 				B::BRIDGED_CHAIN_ID,
 				B::THIS_CHAIN_ID,
 				message_id,
-				message.data.payload.map_err(drop),
+				message.data.payload.map_err(|undecodable| undecodable.payload),
 				|dispatch_origin, dispatch_weight| {
-					let unadjusted_weight_fee = ThisRuntime::WeightToFee::calc(&dispatch_weight);
-					let fee_multiplier = pallet_transaction_payment::Pallet::<ThisRuntime>::next_fee_multiplier();
-					let adjusted_weight_fee = fee_multiplier.saturating_mul_int(unadjusted_weight_fee);
+					let adjusted_weight_fee = dispatch_fee::<ThisRuntime, BalanceOf<ThisChain<B>>>(dispatch_weight);
 					if !adjusted_weight_fee.is_zero() {
 						ThisCurrency::transfer(
 							dispatch_origin,
@@ -545,6 +716,50 @@ pub mod target {
 		}
 	}
 
+	/// Dispatch fee payment for retried failed messages, that withdraws the fee from the dispatch
+	/// origin account.
+	///
+	/// The fee is computed the same way as for regular messages. There's no relayer to reward, so
+	/// it is burned, like regular transaction fees.
+	#[derive(RuntimeDebug, Clone, Copy)]
+	pub struct WithdrawRetryDispatchFee<ThisRuntime, ThisCurrency> {
+		_marker: PhantomData<(ThisRuntime, ThisCurrency)>,
+	}
+
+	impl<ThisRuntime, ThisCurrency> pallet_bridge_dispatch::PayDispatchFee<ThisRuntime::AccountId>
+		for WithdrawRetryDispatchFee<ThisRuntime, ThisCurrency>
+	where
+		ThisRuntime: pallet_transaction_payment::Config,
+		<ThisRuntime as pallet_transaction_payment::Config>::OnChargeTransaction:
+			pallet_transaction_payment::OnChargeTransaction<ThisRuntime, Balance = ThisCurrency::Balance>,
+		ThisCurrency: Currency<ThisRuntime::AccountId>,
+		ThisCurrency::Balance: FixedPointOperand,
+	{
+		fn pay_dispatch_fee(account: &ThisRuntime::AccountId, weight: Weight) -> Result<(), ()> {
+			let fee = dispatch_fee::<ThisRuntime, ThisCurrency::Balance>(weight);
+			if fee.is_zero() {
+				return Ok(());
+			}
+
+			ThisCurrency::withdraw(account, fee, WithdrawReasons::FEE, ExistenceRequirement::AllowDeath)
+				.map(drop)
+				.map_err(drop)
+		}
+	}
+
+	/// Compute fee of dispatching the message with given weight at This chain.
+	fn dispatch_fee<ThisRuntime, Balance>(dispatch_weight: Weight) -> Balance
+	where
+		ThisRuntime: pallet_transaction_payment::Config,
+		<ThisRuntime as pallet_transaction_payment::Config>::OnChargeTransaction:
+			pallet_transaction_payment::OnChargeTransaction<ThisRuntime, Balance = Balance>,
+		Balance: FixedPointOperand,
+	{
+		let unadjusted_weight_fee = ThisRuntime::WeightToFee::calc(&dispatch_weight);
+		let fee_multiplier = pallet_transaction_payment::Pallet::<ThisRuntime>::next_fee_multiplier();
+		fee_multiplier.saturating_mul_int(unadjusted_weight_fee)
+	}
+
 	/// Return maximal dispatch weight of the message we're able to receive.
 	pub fn maximal_incoming_message_dispatch_weight(maximal_extrinsic_weight: Weight) -> Weight {
 		maximal_extrinsic_weight / 2
@@ -555,6 +770,24 @@ pub mod target {
 		maximal_extrinsic_size / 3 * 2
 	}
 
+	/// Return maximal size of the storage proof of `messages_count` messages, sent over `lanes_count`
+	/// lanes.
+	///
+	/// Every message takes at most `maximal_message_size` bytes in the proof. Every message and every
+	/// outbound lane state additionally brings at most `extra_storage_proof_size` bytes of trie nodes,
+	/// that are required to prove it.
+	pub fn maximal_messages_storage_proof_size(
+		messages_count: MessageNonce,
+		lanes_count: u32,
+		maximal_message_size: u32,
+		extra_storage_proof_size: u32,
+	) -> u32 {
+		let messages_count = u32::try_from(messages_count).unwrap_or(u32::MAX);
+		messages_count
+			.saturating_mul(maximal_message_size.saturating_add(extra_storage_proof_size))
+			.saturating_add(lanes_count.saturating_mul(extra_storage_proof_size))
+	}
+
 	/// Verify proof of Bridged -> This chain messages.
 	///
 	/// The `messages_count` argument verification (sane limits) is supposed to be made
@@ -570,7 +803,27 @@ pub mod target {
 		HashOf<BridgedChain<B>>:
 			Into<bp_runtime::HashOf<<ThisRuntime as pallet_bridge_grandpa::Config<GrandpaInstance>>::BridgedChain>>,
 	{
-		verify_messages_proof_with_parser::<B, _, _>(
+		verify_messages_proof_with_stats::<B, ThisRuntime, GrandpaInstance>(proof, messages_count)
+			.map(|(proved_messages, _)| proved_messages)
+	}
+
+	/// Verify proof of Bridged -> This chain messages and return proved messages along with
+	/// the storage proof statistics.
+	///
+	/// The `messages_count` argument verification (sane limits) is supposed to be made
+	/// outside of this function. This function only verifies that the proof declares exactly
+	/// `messages_count` messages.
+	pub fn verify_messages_proof_with_stats<B: MessageBridge, ThisRuntime, GrandpaInstance: 'static>(
+		proof: FromBridgedChainMessagesProof<HashOf<BridgedChain<B>>>,
+		messages_count: u32,
+	) -> Result<(ProvedMessages<Message<BalanceOf<BridgedChain<B>>>>, StorageProofStats), &'static str>
+	where
+		ThisRuntime: pallet_bridge_grandpa::Config<GrandpaInstance>,
+		ThisRuntime: pallet_bridge_messages::Config<B::BridgedMessagesInstance>,
+		HashOf<BridgedChain<B>>:
+			Into<bp_runtime::HashOf<<ThisRuntime as pallet_bridge_grandpa::Config<GrandpaInstance>>::BridgedChain>>,
+	{
+		verify_messages_proof_and_stats_with_parser::<B, _, _>(
 			proof,
 			messages_count,
 			|bridged_header_hash, bridged_storage_proof| {
@@ -589,13 +842,88 @@ pub mod target {
 		.map_err(Into::into)
 	}
 
+	/// Verify proof of Bridged -> This chain messages, when Bridged chain is a parachain.
+	///
+	/// The `bridged_header_hash` of the proof is the hash of the parachain head, finalized
+	/// by the relay chain and tracked by the parachains pallet. The storage proof is verified
+	/// against the state root of this head.
+	///
+	/// The `messages_count` argument verification (sane limits) is supposed to be made
+	/// outside of this function. This function only verifies that the proof declares exactly
+	/// `messages_count` messages.
+	pub fn verify_messages_proof_from_parachain<
+		B: MessageBridge,
+		BridgedParachain: bp_runtime::Chain,
+		ThisRuntime,
+		ParachainsInstance: 'static,
+	>(
+		para_id: pallet_bridge_parachains::ParaId,
+		proof: FromBridgedChainMessagesProof<HashOf<BridgedChain<B>>>,
+		messages_count: u32,
+	) -> Result<ProvedMessages<Message<BalanceOf<BridgedChain<B>>>>, &'static str>
+	where
+		ThisRuntime: pallet_bridge_parachains::Config<ParachainsInstance>,
+		HashOf<BridgedChain<B>>: Into<pallet_bridge_parachains::ParaHash>,
+	{
+		verify_messages_proof_with_parser::<B, _, _>(
+			proof,
+			messages_count,
+			|bridged_header_hash, bridged_storage_proof| {
+				pallet_bridge_parachains::Pallet::<ThisRuntime, ParachainsInstance>::parse_finalized_storage_proof::<
+					BridgedParachain,
+					_,
+				>(
+					para_id,
+					bridged_header_hash.into(),
+					StorageProof::new(bridged_storage_proof),
+					|storage_adapter| storage_adapter,
+				)
+				.map(|storage| StorageProofCheckerAdapter::<_, B> {
+					storage,
+					_dummy: Default::default(),
+				})
+				.map_err(|err| MessageProofError::Custom(err.into()))
+			},
+		)
+		.map_err(Into::into)
+	}
+
+	/// Verify proof of Bridged -> This chain messages against given Bridged chain state root.
+	///
+	/// Unlike `verify_messages_proof`, this function doesn't check that the state root belongs to
+	/// the finalized Bridged chain header. So it must never be used to accept messages - it only
+	/// exists to measure the cost of the proof verification itself (e.g. in benchmarks).
+	pub fn verify_messages_proof_with_state_root<B: MessageBridge, H: Hasher>(
+		proof: FromBridgedChainMessagesProof<HashOf<BridgedChain<B>>>,
+		messages_count: u32,
+		state_root: H::Out,
+	) -> Result<ProvedMessages<Message<BalanceOf<BridgedChain<B>>>>, &'static str> {
+		verify_messages_proof_with_parser::<B, _, _>(proof, messages_count, |_, bridged_storage_proof| {
+			StorageProofChecker::<H>::new(state_root, StorageProof::new(bridged_storage_proof))
+				.map(|storage| StorageProofCheckerAdapter::<_, B> {
+					storage,
+					_dummy: Default::default(),
+				})
+				.map_err(|err| match err {
+					StorageProofError::DuplicateNodesInProof => {
+						MessageProofError::Custom("Storage proof contains duplicate trie nodes")
+					}
+					_ => MessageProofError::Custom("Storage root is missing from the storage proof"),
+				})
+		})
+		.map_err(Into::into)
+	}
+
 	#[derive(Debug, PartialEq)]
 	pub(crate) enum MessageProofError {
 		Empty,
 		MessagesCountMismatch,
+		DuplicateLane,
 		MissingRequiredMessage,
 		FailedToDecodeMessage,
 		FailedToDecodeOutboundLaneState,
+		ProofTooLarge,
+		UnusedNodesInTheProof,
 		Custom(&'static str),
 	}
 
@@ -604,11 +932,14 @@ pub mod target {
 			match err {
 				MessageProofError::Empty => "Messages proof is empty",
 				MessageProofError::MessagesCountMismatch => "Declared messages count doesn't match actual value",
+				MessageProofError::DuplicateLane => "Lane is mentioned several times in the proof",
 				MessageProofError::MissingRequiredMessage => "Message is missing from the proof",
 				MessageProofError::FailedToDecodeMessage => "Failed to decode message from the proof",
 				MessageProofError::FailedToDecodeOutboundLaneState => {
 					"Failed to decode outbound lane data from the proof"
 				}
+				MessageProofError::ProofTooLarge => PROOF_TOO_LARGE,
+				MessageProofError::UnusedNodesInTheProof => PROOF_HAS_UNUSED_NODES,
 				MessageProofError::Custom(err) => err,
 			}
 		}
@@ -617,6 +948,9 @@ pub mod target {
 	pub(crate) trait MessageProofParser {
 		fn read_raw_outbound_lane_data(&self, lane_id: &LaneId) -> Option<Vec<u8>>;
 		fn read_raw_message(&self, message_key: &MessageKey) -> Option<Vec<u8>>;
+		/// Called after all required values have been read from the proof. Returns the proof
+		/// usage statistics.
+		fn ensure_no_unused_nodes(self) -> Result<StorageProofStats, MessageProofError>;
 	}
 
 	struct StorageProofCheckerAdapter<H: Hasher, B> {
@@ -644,6 +978,12 @@ pub mod target {
 			);
 			self.storage.read_value(storage_message_key.0.as_ref()).ok()?
 		}
+
+		fn ensure_no_unused_nodes(self) -> Result<StorageProofStats, MessageProofError> {
+			self.storage
+				.ensure_no_unused_nodes()
+				.map_err(|_| MessageProofError::UnusedNodesInTheProof)
+		}
 	}
 
 	/// Verify proof of Bridged -> This chain messages using given message proof parser.
@@ -652,6 +992,21 @@ pub mod target {
 		messages_count: u32,
 		build_parser: BuildParser,
 	) -> Result<ProvedMessages<Message<BalanceOf<BridgedChain<B>>>>, MessageProofError>
+	where
+		BuildParser: FnOnce(HashOf<BridgedChain<B>>, RawStorageProof) -> Result<Parser, MessageProofError>,
+		Parser: MessageProofParser,
+	{
+		verify_messages_proof_and_stats_with_parser::<B, _, _>(proof, messages_count, build_parser)
+			.map(|(proved_messages, _)| proved_messages)
+	}
+
+	/// Verify proof of Bridged -> This chain messages using given message proof parser. Returns
+	/// proved messages along with the storage proof statistics.
+	pub(crate) fn verify_messages_proof_and_stats_with_parser<B: MessageBridge, BuildParser, Parser>(
+		proof: FromBridgedChainMessagesProof<HashOf<BridgedChain<B>>>,
+		messages_count: u32,
+		build_parser: BuildParser,
+	) -> Result<(ProvedMessages<Message<BalanceOf<BridgedChain<B>>>>, StorageProofStats), MessageProofError>
 	where
 		BuildParser: FnOnce(HashOf<BridgedChain<B>>, RawStorageProof) -> Result<Parser, MessageProofError>,
 		Parser: MessageProofParser,
@@ -662,35 +1017,81 @@ pub mod target {
 			lane,
 			nonces_start,
 			nonces_end,
+			additional_lanes,
 		} = proof;
 
-		// receiving proofs where end < begin is ok (if proof includes outbound lane state)
-		let messages_in_the_proof = if let Some(nonces_difference) = nonces_end.checked_sub(nonces_start) {
-			// let's check that the user (relayer) has passed correct `messages_count`
-			// (this bounds maximal capacity of messages vec below)
-			let messages_in_the_proof = nonces_difference.saturating_add(1);
-			if messages_in_the_proof != MessageNonce::from(messages_count) {
-				return Err(MessageProofError::MessagesCountMismatch);
+		// reject large proofs before doing anything else with them
+		if !is_storage_proof_size_acceptable(&storage_proof, B::maximal_storage_proof_size()) {
+			return Err(MessageProofError::ProofTooLarge);
+		}
+
+		let mut lanes = Vec::with_capacity(additional_lanes.len().saturating_add(1));
+		lanes.push((lane, nonces_start, nonces_end));
+		lanes.extend(additional_lanes);
+
+		// let's check that the user (relayer) has passed correct `messages_count`
+		// (this bounds maximal capacity of messages vecs below)
+		let mut total_messages_in_the_proof: MessageNonce = 0;
+		for (_, nonces_start, nonces_end) in &lanes {
+			// receiving proofs where end < begin is ok (if proof includes outbound lane state)
+			if let Some(nonces_difference) = nonces_end.checked_sub(*nonces_start) {
+				total_messages_in_the_proof =
+					total_messages_in_the_proof.saturating_add(nonces_difference.saturating_add(1));
 			}
+		}
+		if total_messages_in_the_proof != MessageNonce::from(messages_count) {
+			return Err(MessageProofError::MessagesCountMismatch);
+		}
 
-			messages_in_the_proof
-		} else {
-			0
-		};
+		// proof may not be larger than it is required to prove declared number of messages
+		let lanes_count = u32::try_from(lanes.len()).unwrap_or(u32::MAX);
+		let maximal_storage_proof_size =
+			B::maximal_messages_storage_proof_size(total_messages_in_the_proof, lanes_count);
+		if !is_storage_proof_size_acceptable(&storage_proof, maximal_storage_proof_size) {
+			return Err(MessageProofError::ProofTooLarge);
+		}
 
 		let parser = build_parser(bridged_header_hash, storage_proof)?;
 
+		let mut proved_messages = ProvedMessages::new();
+		for (lane, nonces_start, nonces_end) in lanes {
+			// every lane may only be mentioned once in the proof
+			if proved_messages.contains_key(&lane) {
+				return Err(MessageProofError::DuplicateLane);
+			}
+
+			let proved_lane_messages = verify_lane_messages::<B, _>(&parser, lane, nonces_start, nonces_end)?;
+			proved_messages.insert(lane, proved_lane_messages);
+		}
+
+		// padded proofs are rejected, because their submitter hasn't paid for the padding
+		let stats = parser.ensure_no_unused_nodes()?;
+
+		Ok((proved_messages, stats))
+	}
+
+	/// Verify that the proof contains messages and (optionally) outbound lane state of the single lane.
+	fn verify_lane_messages<B: MessageBridge, Parser: MessageProofParser>(
+		parser: &Parser,
+		lane: LaneId,
+		nonces_start: MessageNonce,
+		nonces_end: MessageNonce,
+	) -> Result<ProvedLaneMessages<Message<BalanceOf<BridgedChain<B>>>>, MessageProofError> {
 		// Read messages first. All messages that are claimed to be in the proof must
 		// be in the proof. So any error in `read_value`, or even missing value is fatal.
 		//
 		// Mind that we allow proofs with no messages if outbound lane state is proved.
+		let messages_in_the_proof = nonces_end
+			.checked_sub(nonces_start)
+			.map(|nonces_difference| nonces_difference.saturating_add(1))
+			.unwrap_or(0);
 		let mut messages = Vec::with_capacity(messages_in_the_proof as _);
 		for nonce in nonces_start..=nonces_end {
 			let message_key = MessageKey { lane_id: lane, nonce };
 			let raw_message_data = parser
 				.read_raw_message(&message_key)
 				.ok_or(MessageProofError::MissingRequiredMessage)?;
-			let message_data = MessageData::<BalanceOf<BridgedChain<B>>>::decode(&mut &raw_message_data[..])
+			let message_data = MessageData::<BalanceOf<BridgedChain<B>>>::decode_any_version(&raw_message_data)
 				.map_err(|_| MessageProofError::FailedToDecodeMessage)?;
 			messages.push(Message {
 				key: message_key,
@@ -717,11 +1118,7 @@ pub mod target {
 			return Err(MessageProofError::Empty);
 		}
 
-		// We only support single lane messages in this schema
-		let mut proved_messages = ProvedMessages::new();
-		proved_messages.insert(lane, proved_lane_messages);
-
-		Ok(proved_messages)
+		Ok(proved_lane_messages)
 	}
 }
 
@@ -739,6 +1136,9 @@ mod tests {
 	const BRIDGED_CHAIN_TO_THIS_CHAIN_BALANCE_RATE: u32 = 6;
 	const BRIDGED_CHAIN_MAX_EXTRINSIC_WEIGHT: Weight = 2048;
 	const BRIDGED_CHAIN_MAX_EXTRINSIC_SIZE: u32 = 1024;
+	const MAXIMAL_STORAGE_PROOF_SIZE: u32 = 1024;
+	const MAXIMAL_PROVED_MESSAGE_SIZE: u32 = 64;
+	const EXTRA_STORAGE_PROOF_SIZE: u32 = 16;
 
 	/// Bridge that is deployed on ThisChain and allows sending/receiving messages to/from BridgedChain;
 	#[derive(Debug, PartialEq, Eq)]
@@ -752,9 +1152,27 @@ mod tests {
 		type ThisChain = ThisChain;
 		type BridgedChain = BridgedChain;
 		type BridgedMessagesInstance = pallet_bridge_messages::DefaultInstance;
+		type ConversionRateOracle = BridgedToThisConversionRate;
 
-		fn bridged_balance_to_this_balance(bridged_balance: BridgedChainBalance) -> ThisChainBalance {
-			ThisChainBalance(bridged_balance.0 * BRIDGED_CHAIN_TO_THIS_CHAIN_BALANCE_RATE as u32)
+		fn bridged_balance_to_this_balance(
+			bridged_balance: BridgedChainBalance,
+			bridged_to_this_conversion_rate: FixedU128,
+		) -> ThisChainBalance {
+			ThisChainBalance(bridged_to_this_conversion_rate.saturating_mul_int(bridged_balance.0))
+		}
+
+		fn maximal_storage_proof_size() -> u32 {
+			MAXIMAL_STORAGE_PROOF_SIZE
+		}
+
+		fn maximal_messages_storage_proof_size(messages_count: MessageNonce, lanes_count: u32) -> u32 {
+			target::maximal_messages_storage_proof_size(
+				messages_count,
+				lanes_count,
+				MAXIMAL_PROVED_MESSAGE_SIZE,
+				EXTRA_STORAGE_PROOF_SIZE,
+			)
+			.min(MAXIMAL_STORAGE_PROOF_SIZE)
 		}
 	}
 
@@ -770,10 +1188,29 @@ mod tests {
 		type ThisChain = BridgedChain;
 		type BridgedChain = ThisChain;
 		type BridgedMessagesInstance = pallet_bridge_messages::DefaultInstance;
+		type ConversionRateOracle = BridgedToThisConversionRate;
 
-		fn bridged_balance_to_this_balance(_this_balance: ThisChainBalance) -> BridgedChainBalance {
+		fn bridged_balance_to_this_balance(
+			_this_balance: ThisChainBalance,
+			_bridged_to_this_conversion_rate: FixedU128,
+		) -> BridgedChainBalance {
 			unreachable!()
 		}
+
+		fn maximal_storage_proof_size() -> u32 {
+			MAXIMAL_STORAGE_PROOF_SIZE
+		}
+	}
+
+	/// Conversion rate oracle of the test bridge.
+	struct BridgedToThisConversionRate;
+
+	impl ConversionRateOracle for BridgedToThisConversionRate {
+		fn conversion_rate() -> Option<FixedU128> {
+			Some(FixedU128::saturating_from_integer(
+				BRIDGED_CHAIN_TO_THIS_CHAIN_BALANCE_RATE,
+			))
+		}
 	}
 
 	#[derive(Debug, PartialEq, Decode, Encode, Clone)]
@@ -906,6 +1343,10 @@ mod tests {
 			unreachable!()
 		}
 
+		fn is_dispatch_fee_payment_supported(_dispatch_fee_payment: DispatchFeePayment) -> bool {
+			unreachable!()
+		}
+
 		fn estimate_delivery_transaction(
 			_message_payload: &[u8],
 			_include_pay_dispatch_fee_cost: bool,
@@ -960,6 +1401,10 @@ mod tests {
 			begin..=BRIDGED_CHAIN_MAX_EXTRINSIC_WEIGHT
 		}
 
+		fn is_dispatch_fee_payment_supported(dispatch_fee_payment: DispatchFeePayment) -> bool {
+			dispatch_fee_payment == DispatchFeePayment::AtSourceChain
+		}
+
 		fn estimate_delivery_transaction(
 			_message_payload: &[u8],
 			_include_pay_dispatch_fee_cost: bool,
@@ -998,7 +1443,7 @@ mod tests {
 				.unwrap();
 		assert_eq!(
 			message_on_this_chain,
-			target::FromBridgedChainMessagePayload::<OnThisChainBridge> {
+			bp_message_dispatch::MessagePayload {
 				spec_version: 1,
 				weight: 100,
 				origin: bp_message_dispatch::CallOrigin::SourceRoot,
@@ -1007,11 +1452,15 @@ mod tests {
 					ThisChainCall::Transfer.encode(),
 				),
 			}
+			.into()
+		);
+		assert_eq!(
+			Ok(ThisChainCall::Transfer),
+			message_on_this_chain.into_latest().unwrap().call.into()
 		);
-		assert_eq!(Ok(ThisChainCall::Transfer), message_on_this_chain.call.into());
 	}
 
-	const TEST_LANE_ID: &LaneId = b"test";
+	const TEST_LANE_ID: &LaneId = &LaneId::Legacy(*b"test");
 	const MAXIMAL_PENDING_MESSAGES_AT_TEST_LANE: MessageNonce = 32;
 
 	fn regular_outbound_message_payload() -> source::FromThisChainMessagePayload<OnThisChainBridge> {
@@ -1252,6 +1701,32 @@ mod tests {
 		);
 	}
 
+	#[test]
+	fn maximal_message_arguments_size_respects_both_chains_limits() {
+		// Bridged chain is the bottleneck: 30_000 / 3 * 2 - 6
+		assert_eq!(source::maximal_message_arguments_size(100_000, 30_000), 19_994);
+		// This chain is the bottleneck: 10_000 - 1024 - 6
+		assert_eq!(source::maximal_message_arguments_size(10_000, 30_000), 8_970);
+		// limits are too small to send anything
+		assert_eq!(source::maximal_message_arguments_size(1_024, 30_000), 0);
+	}
+
+	#[test]
+	fn verify_chain_message_rejects_message_with_unsupported_dispatch_fee_payment() {
+		assert_eq!(
+			source::verify_chain_message::<OnThisChainBridge>(&source::FromThisChainMessagePayload::<
+				OnThisChainBridge,
+			> {
+				spec_version: 1,
+				weight: 100,
+				origin: bp_message_dispatch::CallOrigin::SourceRoot,
+				dispatch_fee_payment: DispatchFeePayment::AtTargetChain,
+				call: vec![42],
+			},),
+			Err("Dispatch fee payment mode is not supported by the bridged chain"),
+		);
+	}
+
 	#[derive(Debug)]
 	struct TestMessageProofParser {
 		failing: bool,
@@ -1276,6 +1751,7 @@ mod tests {
 					MessageData::<BridgedChainBalance> {
 						payload: message_key.nonce.encode(),
 						fee: BridgedChainBalance(0),
+						priority: bp_messages::DEFAULT_MESSAGE_PRIORITY,
 					}
 					.encode(),
 				)
@@ -1283,6 +1759,10 @@ mod tests {
 				None
 			}
 		}
+
+		fn ensure_no_unused_nodes(self) -> Result<StorageProofStats, target::MessageProofError> {
+			Ok(StorageProofStats::default())
+		}
 	}
 
 	#[allow(clippy::reversed_empty_ranges)]
@@ -1297,6 +1777,17 @@ mod tests {
 			lane: Default::default(),
 			nonces_start: 1,
 			nonces_end,
+			additional_lanes: Vec::new(),
+		}
+	}
+
+	fn multi_lane_messages_proof(
+		nonces_end: MessageNonce,
+		additional_lanes: Vec<(LaneId, MessageNonce, MessageNonce)>,
+	) -> target::FromBridgedChainMessagesProof<()> {
+		target::FromBridgedChainMessagesProof {
+			additional_lanes,
+			..messages_proof(nonces_end)
 		}
 	}
 
@@ -1324,6 +1815,77 @@ mod tests {
 		);
 	}
 
+	#[test]
+	fn messages_proof_is_rejected_if_storage_proof_is_too_large() {
+		assert_eq!(
+			target::verify_messages_proof_with_parser::<OnThisChainBridge, _, TestMessageProofParser>(
+				target::FromBridgedChainMessagesProof {
+					storage_proof: vec![vec![42; MAXIMAL_STORAGE_PROOF_SIZE as usize / 2]; 3],
+					..messages_proof(10)
+				},
+				10,
+				|_, _| unreachable!(),
+			),
+			Err(target::MessageProofError::ProofTooLarge),
+		);
+	}
+
+	#[test]
+	fn messages_proof_is_rejected_if_storage_proof_is_too_large_for_declared_messages() {
+		let maximal_proof_size = MAXIMAL_PROVED_MESSAGE_SIZE + 2 * EXTRA_STORAGE_PROOF_SIZE;
+		assert_eq!(
+			target::verify_messages_proof_with_parser::<OnThisChainBridge, _, TestMessageProofParser>(
+				target::FromBridgedChainMessagesProof {
+					storage_proof: vec![vec![42; maximal_proof_size as usize + 1]],
+					..messages_proof(1)
+				},
+				1,
+				|_, _| unreachable!(),
+			),
+			Err(target::MessageProofError::ProofTooLarge),
+		);
+	}
+
+	#[test]
+	fn messages_proof_is_accepted_if_storage_proof_fits_declared_messages() {
+		let maximal_proof_size = MAXIMAL_PROVED_MESSAGE_SIZE + 2 * EXTRA_STORAGE_PROOF_SIZE;
+		assert!(maximal_proof_size < BRIDGED_CHAIN_MAX_EXTRINSIC_SIZE);
+		assert_eq!(
+			target::verify_messages_proof_with_parser::<OnThisChainBridge, _, _>(
+				target::FromBridgedChainMessagesProof {
+					storage_proof: vec![vec![42; maximal_proof_size as usize]],
+					..messages_proof(1)
+				},
+				1,
+				|_, _| Ok(TestMessageProofParser {
+					failing: false,
+					messages: 1..=1,
+					outbound_lane_data: None,
+				}),
+			)
+			.map(|messages| messages.len()),
+			Ok(1),
+		);
+	}
+
+	#[test]
+	fn maximal_messages_storage_proof_size_works() {
+		assert_eq!(target::maximal_messages_storage_proof_size(0, 1, 100, 10), 10);
+		assert_eq!(target::maximal_messages_storage_proof_size(1, 1, 100, 10), 120);
+		assert_eq!(target::maximal_messages_storage_proof_size(5, 2, 100, 10), 570);
+		assert_eq!(
+			target::maximal_messages_storage_proof_size(MessageNonce::MAX, 1, 100, 10),
+			u32::MAX
+		);
+	}
+
+	#[test]
+	fn storage_proof_size_is_checked_against_limit() {
+		assert!(is_storage_proof_size_acceptable(&[], 0));
+		assert!(is_storage_proof_size_acceptable(&[vec![42; 10], vec![42; 10]], 20));
+		assert!(!is_storage_proof_size_acceptable(&[vec![42; 10], vec![42; 11]], 20));
+	}
+
 	#[test]
 	fn message_proof_is_rejected_if_build_parser_fails() {
 		assert_eq!(
@@ -1455,7 +2017,8 @@ mod tests {
 						},
 						data: MessageData {
 							payload: 1u64.encode(),
-							fee: BridgedChainBalance(0)
+							fee: BridgedChainBalance(0),
+							priority: bp_messages::DEFAULT_MESSAGE_PRIORITY,
 						},
 					}],
 				},
@@ -1485,6 +2048,103 @@ mod tests {
 		);
 	}
 
+	#[test]
+	fn multi_lane_messages_proof_is_accepted() {
+		let proved_messages = target::verify_messages_proof_with_parser::<OnThisChainBridge, _, _>(
+			multi_lane_messages_proof(
+				2,
+				vec![
+					(LaneId::Legacy([0, 0, 0, 1]), 1, 1),
+					(LaneId::Legacy([0, 0, 0, 2]), 2, 1),
+				],
+			),
+			3,
+			|_, _| {
+				Ok(TestMessageProofParser {
+					failing: false,
+					messages: 1..=2,
+					outbound_lane_data: Some(OutboundLaneData::default()),
+				})
+			},
+		)
+		.unwrap();
+
+		assert_eq!(
+			proved_messages
+				.iter()
+				.map(|(lane, lane_data)| (*lane, lane_data.messages.len()))
+				.collect::<Vec<_>>(),
+			vec![
+				(LaneId::Legacy([0, 0, 0, 0]), 2),
+				(LaneId::Legacy([0, 0, 0, 1]), 1),
+				(LaneId::Legacy([0, 0, 0, 2]), 0)
+			],
+		);
+		assert!(proved_messages.values().all(|lane| lane.lane_state.is_some()));
+	}
+
+	#[test]
+	fn multi_lane_messages_proof_counts_messages_of_all_lanes() {
+		assert_eq!(messages_proof(0).messages_count(), 0);
+		assert_eq!(messages_proof(2).messages_count(), 2);
+		assert_eq!(
+			multi_lane_messages_proof(
+				2,
+				vec![
+					(LaneId::Legacy([0, 0, 0, 1]), 1, 1),
+					(LaneId::Legacy([0, 0, 0, 2]), 2, 1),
+					(LaneId::Legacy([0, 0, 0, 3]), 5, 7),
+				],
+			)
+			.messages_count(),
+			6,
+		);
+	}
+
+	#[test]
+	fn multi_lane_messages_proof_is_rejected_if_declared_messages_count_mismatches() {
+		assert_eq!(
+			target::verify_messages_proof_with_parser::<OnThisChainBridge, _, TestMessageProofParser>(
+				multi_lane_messages_proof(2, vec![(LaneId::Legacy([0, 0, 0, 1]), 1, 1)]),
+				2,
+				|_, _| unreachable!(),
+			),
+			Err(target::MessageProofError::MessagesCountMismatch),
+		);
+	}
+
+	#[test]
+	fn multi_lane_messages_proof_is_rejected_if_lane_is_duplicated() {
+		assert_eq!(
+			target::verify_messages_proof_with_parser::<OnThisChainBridge, _, _>(
+				multi_lane_messages_proof(1, vec![(LaneId::Legacy([0, 0, 0, 0]), 1, 1)]),
+				2,
+				|_, _| Ok(TestMessageProofParser {
+					failing: false,
+					messages: 1..=1,
+					outbound_lane_data: None,
+				}),
+			),
+			Err(target::MessageProofError::DuplicateLane),
+		);
+	}
+
+	#[test]
+	fn multi_lane_messages_proof_is_rejected_if_any_lane_is_empty() {
+		assert_eq!(
+			target::verify_messages_proof_with_parser::<OnThisChainBridge, _, _>(
+				multi_lane_messages_proof(1, vec![(LaneId::Legacy([0, 0, 0, 1]), 1, 0)]),
+				1,
+				|_, _| Ok(TestMessageProofParser {
+					failing: false,
+					messages: 1..=1,
+					outbound_lane_data: None,
+				}),
+			),
+			Err(target::MessageProofError::Empty),
+		);
+	}
+
 	#[test]
 	fn transaction_payment_works_with_zero_multiplier() {
 		use sp_runtime::traits::Zero;
@@ -1522,4 +2182,319 @@ mod tests {
 			100 + 50 * 10 + 777,
 		);
 	}
+
+	mod from_parachain {
+		use super::*;
+		use bp_runtime::Chain;
+		use frame_support::weights::Weight;
+		use pallet_bridge_parachains::{ParaHead, ParaId};
+		use sp_core::H256;
+		use sp_runtime::{
+			testing::Header as SubstrateHeader,
+			traits::{BlakeTwo256, Header as HeaderT, IdentityLookup},
+			DispatchError,
+		};
+		use sp_state_machine::{backend::Backend, InMemoryBackend};
+
+		type Block = frame_system::mocking::MockBlock<TestRuntime>;
+		type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<TestRuntime>;
+
+		const PARA_ID: ParaId = ParaId(42);
+		const PARAS_PALLET_NAME: &str = "Paras";
+
+		frame_support::construct_runtime! {
+			pub enum TestRuntime where
+				Block = Block,
+				NodeBlock = Block,
+				UncheckedExtrinsic = UncheckedExtrinsic,
+			{
+				System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
+				Grandpa: pallet_bridge_grandpa::{Pallet, Call, Event<T>},
+				Parachains: pallet_bridge_parachains::{Pallet, Call, Storage, Event<T>},
+			}
+		}
+
+		frame_support::parameter_types! {
+			pub const BlockHashCount: u64 = 250;
+		}
+
+		impl frame_system::Config for TestRuntime {
+			type Origin = Origin;
+			type Index = u64;
+			type Call = Call;
+			type BlockNumber = u64;
+			type Hash = H256;
+			type Hashing = BlakeTwo256;
+			type AccountId = u64;
+			type Lookup = IdentityLookup<Self::AccountId>;
+			type Header = SubstrateHeader;
+			type Event = Event;
+			type BlockHashCount = BlockHashCount;
+			type Version = ();
+			type PalletInfo = PalletInfo;
+			type AccountData = ();
+			type OnNewAccount = ();
+			type OnKilledAccount = ();
+			type BaseCallFilter = ();
+			type SystemWeightInfo = ();
+			type BlockWeights = ();
+			type BlockLength = ();
+			type DbWeight = ();
+			type SS58Prefix = ();
+			type OnSetCode = ();
+		}
+
+		frame_support::parameter_types! {
+			pub const MaxRequests: u32 = 16;
+			pub const HeadersToKeep: u32 = 5;
+			pub const MaxHeadersToPruneInBlock: u32 = 1;
+			pub const AuthoritySetChangesToKeep: u32 = 3;
+			pub const AuthoritySetsToKeep: u32 = 0;
+			pub const AcceptAncestryProofs: bool = false;
+			pub const FreeMandatoryHeadersPerBlock: u32 = 1;
+		}
+
+		impl pallet_bridge_grandpa::Config for TestRuntime {
+			type Event = Event;
+			type BridgedChain = TestBridgedChain;
+			type MaxRequests = MaxRequests;
+			type HeadersToKeep = HeadersToKeep;
+			type MaxHeadersToPruneInBlock = MaxHeadersToPruneInBlock;
+			type AuthoritySetChangesToKeep = AuthoritySetChangesToKeep;
+			type AuthoritySetsToKeep = AuthoritySetsToKeep;
+			type AcceptAncestryProofs = AcceptAncestryProofs;
+			type FreeMandatoryHeadersPerBlock = FreeMandatoryHeadersPerBlock;
+			type OnEquivocation = ();
+			type WeightInfo = ();
+		}
+
+		frame_support::parameter_types! {
+			pub const ParasPalletName: &'static str = PARAS_PALLET_NAME;
+			pub const HeadsToKeep: u32 = 2;
+			pub const MaxParaHeadSize: u32 = 1024;
+			pub const MaxStorageProofSize: u32 = 16 * 1024;
+			pub const StorageProofByteWeight: Weight = 1;
+			pub const ParaHeadByteWeight: Weight = 2;
+			pub TrackedParachains: Vec<ParaId> = vec![PARA_ID];
+		}
+
+		impl pallet_bridge_parachains::Config for TestRuntime {
+			type Event = Event;
+			type ParasPalletName = ParasPalletName;
+			type HeadsToKeep = HeadsToKeep;
+			type TrackedParachains = TrackedParachains;
+			type MaxParaHeadSize = MaxParaHeadSize;
+			type MaxStorageProofSize = MaxStorageProofSize;
+			type StorageProofByteWeight = StorageProofByteWeight;
+			type ParaHeadByteWeight = ParaHeadByteWeight;
+		}
+
+		/// Bridged relay chain. The bridged parachain is using the same primitives.
+		#[derive(Debug)]
+		struct TestBridgedChain;
+
+		impl Chain for TestBridgedChain {
+			type BlockNumber = u64;
+			type Hash = H256;
+			type Hasher = BlakeTwo256;
+			type Header = SubstrateHeader;
+		}
+
+		impl bp_header_chain::ChainWithGrandpa for TestBridgedChain {
+			const MAX_AUTHORITIES_COUNT: u32 = 16;
+			const REASONABLE_HEADERS_IN_JUSTIFICATON_ANCESTRY: u32 = 8;
+			const MAX_HEADER_SIZE: u32 = 256;
+		}
+
+		/// Bridge that is deployed on ThisChain and allows receiving messages from the parachain.
+		struct FromParachainBridge;
+
+		impl MessageBridge for FromParachainBridge {
+			const RELAYER_FEE_PERCENT: u32 = 10;
+			const THIS_CHAIN_ID: ChainId = *b"this";
+			const BRIDGED_CHAIN_ID: ChainId = *b"para";
+
+			type ThisChain = ThisChain;
+			type BridgedChain = BridgedParachain;
+			type BridgedMessagesInstance = pallet_bridge_messages::DefaultInstance;
+			type ConversionRateOracle = BridgedToThisConversionRate;
+
+			fn bridged_balance_to_this_balance(
+				_bridged_balance: BridgedChainBalance,
+				_bridged_to_this_conversion_rate: FixedU128,
+			) -> ThisChainBalance {
+				unreachable!()
+			}
+
+			fn maximal_storage_proof_size() -> u32 {
+				MAXIMAL_STORAGE_PROOF_SIZE
+			}
+		}
+
+		struct BridgedParachain;
+
+		impl ChainWithMessages for BridgedParachain {
+			type Hash = H256;
+			type AccountId = BridgedChainAccountId;
+			type Signer = BridgedChainSigner;
+			type Signature = BridgedChainSignature;
+			type Weight = Weight;
+			type Balance = BridgedChainBalance;
+		}
+
+		impl BridgedChainWithMessages for BridgedParachain {
+			fn maximal_extrinsic_size() -> u32 {
+				unreachable!()
+			}
+
+			fn message_weight_limits(_message_payload: &[u8]) -> RangeInclusive<Self::Weight> {
+				unreachable!()
+			}
+
+			fn is_dispatch_fee_payment_supported(_dispatch_fee_payment: DispatchFeePayment) -> bool {
+				unreachable!()
+			}
+
+			fn estimate_delivery_transaction(
+				_message_payload: &[u8],
+				_include_pay_dispatch_fee_cost: bool,
+				_message_dispatch_weight: WeightOf<Self>,
+			) -> MessageTransaction<WeightOf<Self>> {
+				unreachable!()
+			}
+
+			fn transaction_payment(_transaction: MessageTransaction<WeightOf<Self>>) -> BalanceOf<Self> {
+				unreachable!()
+			}
+		}
+
+		fn run_test<T>(test: impl FnOnce() -> T) -> T {
+			let t = frame_system::GenesisConfig::default()
+				.build_storage::<TestRuntime>()
+				.unwrap();
+			sp_io::TestExternalities::new(t).execute_with(test)
+		}
+
+		/// Storage of the bridged parachain with the single message.
+		fn parachain_storage() -> InMemoryBackend<BlakeTwo256> {
+			let message_key = pallet_bridge_messages::storage_keys::message_key::<
+				pallet_bridge_messages::DefaultInstance,
+			>(&Default::default(), 1);
+			let message_data = MessageData::<BridgedChainBalance> {
+				payload: vec![42],
+				fee: BridgedChainBalance(0),
+				priority: bp_messages::DEFAULT_MESSAGE_PRIORITY,
+			};
+			vec![(None, vec![(message_key.0, Some(message_data.encode()))])].into()
+		}
+
+		/// Import parachain head with given state root, using relay chain header that is imported
+		/// by the GRANDPA pallet.
+		///
+		/// Returns hash of the imported parachain head.
+		fn import_parachain_head(state_root: H256) -> H256 {
+			let mut para_header: SubstrateHeader = bp_test_utils::test_header(1);
+			para_header.set_state_root(state_root);
+			let para_head = ParaHead(para_header.encode());
+
+			let para_head_key = pallet_bridge_parachains::parachain_head_storage_key(PARAS_PALLET_NAME, PARA_ID).0;
+			let relay_storage: InMemoryBackend<BlakeTwo256> =
+				vec![(None, vec![(para_head_key.clone(), Some(para_head.encode()))])].into();
+			let mut relay_header: SubstrateHeader = bp_test_utils::test_header(0);
+			relay_header.set_state_root(relay_storage.storage_root(std::iter::empty()).0);
+			pallet_bridge_grandpa::Pallet::<TestRuntime>::initialize(
+				Origin::root(),
+				bp_header_chain::InitializationData {
+					header: relay_header.clone(),
+					authority_list: bp_test_utils::authority_list(),
+					set_id: 1,
+					is_halted: false,
+					genesis_hash: Default::default(),
+				},
+			)
+			.unwrap();
+
+			let para_heads_proof = sp_state_machine::prove_read(relay_storage, &[para_head_key])
+				.unwrap()
+				.iter_nodes()
+				.collect();
+			pallet_bridge_parachains::Pallet::<TestRuntime>::submit_parachain_heads(
+				Origin::signed(1),
+				relay_header.hash(),
+				vec![PARA_ID],
+				para_heads_proof,
+			)
+			.unwrap();
+
+			para_head.hash()
+		}
+
+		/// Proof of the single message, stored in the parachain storage.
+		fn parachain_messages_proof(para_head_hash: H256) -> target::FromBridgedChainMessagesProof<H256> {
+			let message_key = pallet_bridge_messages::storage_keys::message_key::<
+				pallet_bridge_messages::DefaultInstance,
+			>(&Default::default(), 1);
+			target::FromBridgedChainMessagesProof {
+				bridged_header_hash: para_head_hash,
+				storage_proof: sp_state_machine::prove_read(parachain_storage(), &[message_key.0])
+					.unwrap()
+					.iter_nodes()
+					.collect(),
+				lane: Default::default(),
+				nonces_start: 1,
+				nonces_end: 1,
+				additional_lanes: Vec::new(),
+			}
+		}
+
+		fn verify_messages_proof_from_parachain(
+			proof: target::FromBridgedChainMessagesProof<H256>,
+		) -> Result<ProvedMessages<Message<BridgedChainBalance>>, &'static str> {
+			target::verify_messages_proof_from_parachain::<FromParachainBridge, TestBridgedChain, TestRuntime, ()>(
+				PARA_ID, proof, 1,
+			)
+		}
+
+		#[test]
+		fn messages_proof_from_parachain_is_accepted() {
+			run_test(|| {
+				let para_head_hash = import_parachain_head(parachain_storage().storage_root(std::iter::empty()).0);
+
+				let proved_messages = verify_messages_proof_from_parachain(parachain_messages_proof(para_head_hash))
+					.unwrap()
+					.remove(&LaneId::default())
+					.unwrap();
+				assert_eq!(proved_messages.lane_state, None);
+				assert_eq!(proved_messages.messages.len(), 1);
+				assert_eq!(proved_messages.messages[0].key.nonce, 1);
+				assert_eq!(proved_messages.messages[0].data.payload, vec![42]);
+			})
+		}
+
+		#[test]
+		fn messages_proof_from_parachain_is_rejected_if_para_head_is_unknown() {
+			run_test(|| {
+				import_parachain_head(parachain_storage().storage_root(std::iter::empty()).0);
+
+				assert_eq!(
+					verify_messages_proof_from_parachain(parachain_messages_proof(H256::repeat_byte(42))),
+					Err(DispatchError::from(pallet_bridge_parachains::Error::<TestRuntime>::UnknownParaHead).into()),
+				);
+			})
+		}
+
+		#[test]
+		fn messages_proof_from_parachain_is_rejected_if_state_root_mismatches() {
+			run_test(|| {
+				let para_head_hash = import_parachain_head(H256::repeat_byte(42));
+
+				assert_eq!(
+					verify_messages_proof_from_parachain(parachain_messages_proof(para_head_hash)),
+					Err(
+						DispatchError::from(pallet_bridge_parachains::Error::<TestRuntime>::StorageRootMismatch).into()
+					),
+				);
+			})
+		}
+	}
 }
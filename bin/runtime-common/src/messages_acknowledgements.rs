@@ -0,0 +1,115 @@
+// Copyright 2019-2021 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Helpers for sending dispatch acknowledgements back to the source chain.
+//!
+//! When the message sender has requested acknowledgement, the dispatch pallet of the target chain
+//! passes the dispatch result to the `SendMessage` implementation. The `MessagesPalletAcknowledgementSender`
+//! queues it at the outbound lane of the messages pallet with the same id as the inbound lane, that has
+//! delivered the message. At the source chain, the acknowledgement is delivered as a call of the dispatch
+//! pallet (`receive_dispatch_acknowledgement`), dispatched on behalf of the acknowledgement account of the
+//! target chain (see `pallet_bridge_dispatch::acknowledgement_account_id`).
+
+use bp_message_dispatch::{DispatchAcknowledgement, SendMessage};
+use bp_messages::{source_chain::Sender, LaneId, MessageNonce};
+use bp_runtime::ChainId;
+use frame_support::{
+	traits::{Get, Instance},
+	weights::Weight,
+};
+use sp_std::marker::PhantomData;
+
+/// Message id that is used by the dispatch pallet, when messages are delivered by the messages pallet.
+pub type MessagesPalletMessageId = (LaneId, MessageNonce);
+
+/// Something that builds dispatch acknowledgement messages for the bridged chain.
+pub trait AcknowledgementMessageBuilder<MessageId, Payload, Fee> {
+	/// Build message that delivers the `acknowledgement` to the bridged chain. Returns message payload
+	/// and the delivery and dispatch fee of the message.
+	fn build_acknowledgement_message(
+		acknowledgement: DispatchAcknowledgement<MessageId>,
+	) -> Result<(Payload, Fee), &'static str>;
+}
+
+/// Acknowledgement sender that sends dispatch acknowledgements over the bridge, served by the messages
+/// pallet instance.
+///
+/// Acknowledgements are sent over the lane that has delivered the acknowledged message. They're sent on
+/// behalf of the `SenderAccount`, which is paying message fees. So this account must be funded and allowed
+/// to send messages over all lanes, where senders may request acknowledgements.
+pub struct MessagesPalletAcknowledgementSender<Runtime, MessagesPalletInstance, SenderAccount, Builder>(
+	PhantomData<(Runtime, MessagesPalletInstance, SenderAccount, Builder)>,
+);
+
+impl<Runtime, MessagesPalletInstance, SenderAccount, Builder> SendMessage<MessagesPalletMessageId>
+	for MessagesPalletAcknowledgementSender<Runtime, MessagesPalletInstance, SenderAccount, Builder>
+where
+	Runtime: pallet_bridge_messages::Config<MessagesPalletInstance>,
+	MessagesPalletInstance: Instance,
+	SenderAccount: Get<Runtime::AccountId>,
+	Builder:
+		AcknowledgementMessageBuilder<MessagesPalletMessageId, Runtime::OutboundPayload, Runtime::OutboundMessageFee>,
+{
+	fn send_message_weight() -> Weight {
+		// hashed lane identifiers have the largest encoded size, so we get the maximal payload size here
+		let largest_acknowledgement = DispatchAcknowledgement {
+			message_id: (LaneId::Hashed(Default::default()), MessageNonce::MAX),
+			dispatch_result: false,
+		};
+		Builder::build_acknowledgement_message(largest_acknowledgement)
+			.map(|(payload, _)| {
+				pallet_bridge_messages::send_message_weight::<Runtime, MessagesPalletInstance>(&payload)
+			})
+			.unwrap_or(0)
+	}
+
+	fn send_message(
+		source_chain: ChainId,
+		acknowledgement: DispatchAcknowledgement<MessagesPalletMessageId>,
+	) -> Result<(), ()> {
+		if source_chain != Runtime::BridgedChainId::get() {
+			log::trace!(
+				target: "runtime::bridge-messages",
+				"Failed to send dispatch acknowledgement to unknown chain {:?}",
+				source_chain,
+			);
+			return Err(());
+		}
+
+		let lane = acknowledgement.message_id.0;
+		let (payload, fee) = Builder::build_acknowledgement_message(acknowledgement).map_err(|error| {
+			log::trace!(
+				target: "runtime::bridge-messages",
+				"Failed to build dispatch acknowledgement message: {}",
+				error,
+			);
+		})?;
+		pallet_bridge_messages::Pallet::<Runtime, MessagesPalletInstance>::send_message(
+			Sender::Signed(SenderAccount::get()).into(),
+			lane,
+			payload,
+			fee,
+		)
+		.map(drop)
+		.map_err(|error| {
+			log::trace!(
+				target: "runtime::bridge-messages",
+				"Failed to send dispatch acknowledgement message: {:?}",
+				error.error,
+			);
+		})
+	}
+}
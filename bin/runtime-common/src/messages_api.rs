@@ -18,9 +18,14 @@
 
 use crate::messages::{source::FromThisChainMessagePayload, MessageBridge};
 
-use bp_messages::{LaneId, MessageDetails, MessageNonce};
+use bp_messages::{
+	target_chain::{DispatchMessage, DispatchMessageData, MessageDispatch},
+	BridgeInfo, LaneId, MessageDetails, MessageKey, MessageNonce, RelayerRewardsEstimation,
+};
+use bp_runtime::messages::MessageDispatchResult;
 use codec::Decode;
-use frame_support::traits::Instance;
+use frame_support::traits::{Get, Instance, PalletInfo};
+use sp_runtime::traits::{SaturatingAdd, Zero};
 use sp_std::vec::Vec;
 
 /// Implementation of the `To*OutboundLaneApi::message_details`.
@@ -46,7 +51,107 @@ where
 				size: message_data.payload.len() as _,
 				delivery_and_dispatch_fee: message_data.fee,
 				dispatch_fee_payment: decoded_payload.dispatch_fee_payment,
+				priority: message_data.priority,
 			})
 		})
 		.collect()
 }
+
+/// Implementation of the `To*OutboundLaneApi::estimate_relayer_rewards`.
+pub fn estimate_relayer_rewards<Runtime, MessagesPalletInstance, BridgeConfig>(
+	lane: LaneId,
+	max_messages: MessageNonce,
+) -> RelayerRewardsEstimation<Runtime::OutboundMessageFee>
+where
+	Runtime: pallet_bridge_messages::Config<MessagesPalletInstance>,
+	MessagesPalletInstance: Instance,
+	BridgeConfig: MessageBridge,
+{
+	let latest_received_nonce =
+		pallet_bridge_messages::Pallet::<Runtime, MessagesPalletInstance>::outbound_latest_received_nonce(lane);
+	let latest_generated_nonce =
+		pallet_bridge_messages::Pallet::<Runtime, MessagesPalletInstance>::outbound_latest_generated_nonce(lane);
+	let backlog = latest_generated_nonce.saturating_sub(latest_received_nonce);
+	let messages_to_estimate = sp_std::cmp::min(backlog, max_messages);
+
+	outbound_message_details::<Runtime, MessagesPalletInstance, BridgeConfig>(
+		lane,
+		latest_received_nonce.saturating_add(1),
+		latest_received_nonce.saturating_add(messages_to_estimate),
+	)
+	.into_iter()
+	.fold(
+		RelayerRewardsEstimation {
+			backlog,
+			..Default::default()
+		},
+		|mut estimation, details| {
+			estimation.messages += 1;
+			estimation.total_dispatch_weight = estimation.total_dispatch_weight.saturating_add(details.dispatch_weight);
+			estimation.total_size = estimation.total_size.saturating_add(details.size);
+			estimation.total_reward = estimation
+				.total_reward
+				.saturating_add(&details.delivery_and_dispatch_fee);
+			estimation
+		},
+	)
+}
+
+/// Implementation of the `From*InboundLaneApi::dry_run_message_dispatch`.
+///
+/// The message is dispatched as if it is the next message delivered over given lane by the
+/// relayer with default account id. Storage changes must be discarded after the call, so it
+/// must only be called from the runtime API.
+pub fn dry_run_message_dispatch<Runtime, MessagesPalletInstance>(
+	lane: LaneId,
+	payload: Vec<u8>,
+) -> MessageDispatchResult
+where
+	Runtime: pallet_bridge_messages::Config<MessagesPalletInstance>,
+	Runtime::InboundMessageFee: Zero,
+	MessagesPalletInstance: Instance,
+{
+	let nonce = pallet_bridge_messages::Pallet::<Runtime, MessagesPalletInstance>::inbound_latest_received_nonce(lane)
+		.saturating_add(1);
+	Runtime::MessageDispatch::dispatch(
+		&Default::default(),
+		DispatchMessage {
+			key: MessageKey { lane_id: lane, nonce },
+			data: DispatchMessageData {
+				payload: Decode::decode(&mut &payload[..]),
+				fee: Zero::zero(),
+			},
+		},
+	)
+}
+
+/// Returns information about the bridge, that may be used in the `BridgesInfoApi::bridges` implementation.
+pub fn bridge_info<Runtime, MessagesPalletInstance, GrandpaPalletInstance>(lanes: Vec<LaneId>) -> BridgeInfo
+where
+	Runtime:
+		pallet_bridge_messages::Config<MessagesPalletInstance> + pallet_bridge_grandpa::Config<GrandpaPalletInstance>,
+	MessagesPalletInstance: Instance,
+	GrandpaPalletInstance: 'static,
+{
+	BridgeInfo {
+		bridged_chain_id: <Runtime as pallet_bridge_messages::Config<MessagesPalletInstance>>::BridgedChainId::get(),
+		messages_pallet_name: pallet_name::<
+			<Runtime as pallet_bridge_messages::Config<MessagesPalletInstance>>::PalletInfo,
+			pallet_bridge_messages::Pallet<Runtime, MessagesPalletInstance>,
+		>(),
+		finality_pallet_name: pallet_name::<
+			<Runtime as pallet_bridge_messages::Config<MessagesPalletInstance>>::PalletInfo,
+			pallet_bridge_grandpa::Pallet<Runtime, GrandpaPalletInstance>,
+		>(),
+		operating_mode: pallet_bridge_messages::Pallet::<Runtime, MessagesPalletInstance>::operating_mode(),
+		is_finality_halted: pallet_bridge_grandpa::Pallet::<Runtime, GrandpaPalletInstance>::is_halted(),
+		lanes,
+	}
+}
+
+/// Returns name of the pallet in the runtime or empty vector if the pallet is not a part of the runtime.
+fn pallet_name<RuntimePalletInfo: PalletInfo, Pallet: 'static>() -> Vec<u8> {
+	RuntimePalletInfo::name::<Pallet>()
+		.map(|name| name.as_bytes().to_vec())
+		.unwrap_or_default()
+}
@@ -24,7 +24,7 @@ use crate::messages::{
 	BridgedChain, HashOf, MessageBridge, ThisChain,
 };
 
-use bp_messages::{LaneId, MessageData, MessageKey, MessagePayload};
+use bp_messages::{LaneId, MessageData, MessageKey, MessageNonce, MessagePayload, DEFAULT_MESSAGE_PRIORITY};
 use bp_runtime::ChainId;
 use codec::Encode;
 use ed25519_dalek::{PublicKey, SecretKey, Signer, KEYPAIR_LENGTH, SECRET_KEY_LENGTH};
@@ -32,8 +32,8 @@ use frame_support::weights::Weight;
 use pallet_bridge_messages::benchmarking::{MessageDeliveryProofParams, MessageProofParams, ProofSize};
 use sp_core::Hasher;
 use sp_runtime::traits::Header;
-use sp_std::prelude::*;
-use sp_trie::{record_all_keys, trie_types::TrieDBMut, Layout, MemoryDB, Recorder, TrieMut};
+use sp_std::{collections::btree_set::BTreeSet, prelude::*};
+use sp_trie::{read_trie_value_with, trie_types::TrieDBMut, Layout, MemoryDB, Recorder, TrieMut};
 
 /// Generate ed25519 signature to be used in `pallet_brdige_call_dispatch::CallOrigin::TargetAccount`.
 ///
@@ -79,7 +79,7 @@ pub fn prepare_message_proof<B, H, R, FI, MM, ML, MH>(
 	make_bridged_outbound_lane_data_key: ML,
 	make_bridged_header: MH,
 	message_dispatch_weight: Weight,
-	message_payload: MessagePayload,
+	mut message_payload: MessagePayload,
 ) -> (FromBridgedChainMessagesProof<HashOf<BridgedChain<B>>>, Weight)
 where
 	B: MessageBridge,
@@ -92,26 +92,35 @@ where
 	MH: Fn(H::Out) -> <R::BridgedChain as bp_runtime::Chain>::Header,
 {
 	// prepare Bridged chain storage with messages and (optionally) outbound lane state
-	let message_count = params
-		.message_nonces
-		.end()
-		.saturating_sub(*params.message_nonces.start())
-		+ 1;
+	let lanes = sp_std::iter::once((params.lane, params.message_nonces.clone()))
+		.chain(params.additional_lanes.iter().cloned())
+		.collect::<Vec<_>>();
+	let message_count = lanes
+		.iter()
+		.map(|(_, nonces)| nonces.end().saturating_sub(*nonces.start()) + 1)
+		.sum::<MessageNonce>();
 	let mut storage_keys = Vec::with_capacity(message_count as usize + 1);
+	if let ProofSize::HasLargeLeaf(size) = params.size {
+		// trailing bytes are ignored when the payload is decoded
+		if message_payload.len() < size as usize {
+			message_payload.resize(size as usize, 0);
+		}
+	}
 	let mut root = Default::default();
 	let mut mdb = MemoryDB::default();
 	{
 		let mut trie = TrieDBMut::<H>::new(&mut mdb, &mut root);
 
 		// insert messages
-		for nonce in params.message_nonces.clone() {
-			let message_key = MessageKey {
-				lane_id: params.lane,
-				nonce,
-			};
+		for (lane_id, nonce) in lanes
+			.iter()
+			.flat_map(|(lane_id, nonces)| nonces.clone().map(move |nonce| (*lane_id, nonce)))
+		{
+			let message_key = MessageKey { lane_id, nonce };
 			let message_data = MessageData {
 				fee: BalanceOf::<BridgedChain<B>>::from(0),
 				payload: message_payload.clone(),
+				priority: DEFAULT_MESSAGE_PRIORITY,
 			};
 			let storage_key = make_bridged_message_storage_key(message_key);
 			trie.insert(&storage_key, &message_data.encode())
@@ -129,14 +138,10 @@ where
 			storage_keys.push(storage_key);
 		}
 	}
-	root = grow_trie(root, &mut mdb, params.size);
+	root = grow_trie(root, &mut mdb, params.size, &storage_keys);
 
 	// generate storage proof to be delivered to This chain
-	let mut proof_recorder = Recorder::<H::Out>::new();
-	record_all_keys::<Layout<H>, _>(&mdb, &root, &mut proof_recorder)
-		.map_err(|_| "record_all_keys has failed")
-		.expect("record_all_keys should not fail in benchmarks");
-	let storage_proof = proof_recorder.drain().into_iter().map(|n| n.data.to_vec()).collect();
+	let storage_proof = record_proof(&mdb, &root, &storage_keys);
 
 	// prepare Bridged chain header and insert it into the Substrate pallet
 	let bridged_header = make_bridged_header(root);
@@ -150,6 +155,11 @@ where
 			lane: params.lane,
 			nonces_start: *params.message_nonces.start(),
 			nonces_end: *params.message_nonces.end(),
+			additional_lanes: params
+				.additional_lanes
+				.iter()
+				.map(|(lane, nonces)| (*lane, *nonces.start(), *nonces.end()))
+				.collect(),
 		},
 		message_dispatch_weight
 			.checked_mul(message_count)
@@ -182,14 +192,11 @@ where
 			.map_err(|_| "TrieMut::insert has failed")
 			.expect("TrieMut::insert should not fail in benchmarks");
 	}
-	root = grow_trie(root, &mut mdb, params.size);
+	let storage_keys = vec![storage_key];
+	root = grow_trie(root, &mut mdb, params.size, &storage_keys);
 
 	// generate storage proof to be delivered to This chain
-	let mut proof_recorder = Recorder::<H::Out>::new();
-	record_all_keys::<Layout<H>, _>(&mdb, &root, &mut proof_recorder)
-		.map_err(|_| "record_all_keys has failed")
-		.expect("record_all_keys should not fail in benchmarks");
-	let storage_proof = proof_recorder.drain().into_iter().map(|n| n.data.to_vec()).collect();
+	let storage_proof = record_proof(&mdb, &root, &storage_keys);
 
 	// prepare Bridged chain header and insert it into the Substrate pallet
 	let bridged_header = make_bridged_header(root);
@@ -200,38 +207,77 @@ where
 		bridged_header_hash: bridged_header_hash.into(),
 		storage_proof,
 		lane: params.lane,
+		relayer_entries: None,
 	}
 }
 
-/// Populate trie with dummy keys+values until trie has at least given size.
-fn grow_trie<H: Hasher>(mut root: H::Out, mdb: &mut MemoryDB<H>, trie_size: ProofSize) -> H::Out {
-	let (iterations, leaf_size, minimal_trie_size) = match trie_size {
-		ProofSize::Minimal(_) => return root,
-		ProofSize::HasLargeLeaf(size) => (1, size, size),
-		ProofSize::HasExtraNodes(size) => (8, 1, size),
+/// Populate trie with dummy keys+values until proof of given keys has at least given size.
+///
+/// Proofs with unused trie nodes are rejected by the verifier. So instead of adding unrelated
+/// keys, we're inserting dummy keys that diverge from the first proved key at different depths.
+/// Every such key adds a child to some branch node on the path to the proved key, so all extra
+/// proof bytes are required to read the proved key. The proof can't grow beyond the size that
+/// is achieved when every nibble of the proved key has all possible siblings.
+fn grow_trie<H: Hasher>(
+	mut root: H::Out,
+	mdb: &mut MemoryDB<H>,
+	trie_size: ProofSize,
+	proved_keys: &[Vec<u8>],
+) -> H::Out {
+	let minimal_proof_size = match trie_size {
+		ProofSize::HasExtraNodes(size) => size,
+		// large leaf is the message payload itself
+		ProofSize::Minimal(_) | ProofSize::HasLargeLeaf(_) => return root,
+	};
+	let proved_key = match proved_keys.first() {
+		Some(proved_key) => proved_key,
+		None => return root,
 	};
 
-	let mut key_index = 0;
-	loop {
-		// generate storage proof to be delivered to This chain
-		let mut proof_recorder = Recorder::<H::Out>::new();
-		record_all_keys::<Layout<H>, _>(mdb, &root, &mut proof_recorder)
-			.map_err(|_| "record_all_keys has failed")
-			.expect("record_all_keys should not fail in benchmarks");
-		let size: usize = proof_recorder.drain().into_iter().map(|n| n.data.len()).sum();
-		if size > minimal_trie_size as _ {
-			return root;
+	for diverging_byte in (0..proved_key.len()).rev() {
+		let size: usize = record_proof(mdb, &root, proved_keys).iter().map(|n| n.len()).sum();
+		if size > minimal_proof_size as _ {
+			break;
 		}
 
 		let mut trie = TrieDBMut::<H>::from_existing(mdb, &mut root)
 			.map_err(|_| "TrieDBMut::from_existing has failed")
 			.expect("TrieDBMut::from_existing should not fail in benchmarks");
-		for _ in 0..iterations {
-			trie.insert(&key_index.encode(), &vec![42u8; leaf_size as _])
-				.map_err(|_| "TrieMut::insert has failed")
-				.expect("TrieMut::insert should not fail in benchmarks");
-			key_index += 1;
+		for nibble in 1u8..16 {
+			// siblings at the low and at the high nibble of the diverging byte
+			for diverging_mask in &[nibble, nibble << 4] {
+				let mut dummy_key = proved_key.clone();
+				dummy_key[diverging_byte] ^= diverging_mask;
+				if proved_keys.contains(&dummy_key) {
+					continue;
+				}
+
+				trie.insert(&dummy_key, &[42u8])
+					.map_err(|_| "TrieMut::insert has failed")
+					.expect("TrieMut::insert should not fail in benchmarks");
+			}
 		}
 		trie.commit();
 	}
+
+	root
+}
+
+/// Record proof of given keys.
+fn record_proof<H: Hasher>(mdb: &MemoryDB<H>, root: &H::Out, proved_keys: &[Vec<u8>]) -> Vec<Vec<u8>> {
+	let mut proof_recorder = Recorder::<H::Out>::new();
+	for proved_key in proved_keys {
+		read_trie_value_with::<Layout<H>, _, _>(mdb, root, proved_key, &mut proof_recorder)
+			.map_err(|_| "read_trie_value_with has failed")
+			.expect("read_trie_value_with should not fail in benchmarks");
+	}
+
+	// the same node may be recorded several times, but the proof must not have duplicate nodes
+	proof_recorder
+		.drain()
+		.into_iter()
+		.map(|n| n.data.to_vec())
+		.collect::<BTreeSet<_>>()
+		.into_iter()
+		.collect()
 }
@@ -0,0 +1,526 @@
+// Copyright 2019-2021 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Helpers for forwarding messages, received over one bridge, to another bridge.
+//!
+//! If chain A has no direct bridge with chain C, but both are bridged with hub chain B, then
+//! messages from A to C may be sent through B. Such message is delivered to B wrapped into
+//! the `ForwardEnvelope`. When it is dispatched at B, it is sent over the B->C bridge, using
+//! lane and fee from the envelope.
+//!
+//! The fee for the next hop is paid at the hub chain by the forwarding account of the inbound
+//! lane (see `forwarding_account_id`). So lanes that are used for forwarding should only accept
+//! messages from trusted senders and the forwarding account must be funded by the lane owner.
+//! Paid fees may be reported to the `OnForwardingFeePaid` handler (e.g. to the fee settlement
+//! pallet), so that they're reimbursed by the originating chain. The fee settlement pallet at the
+//! hub chain may then use the `MessagesPalletReconciliationSender` to send reconciliation messages
+//! to originating chains.
+
+use bp_messages::{
+	source_chain::Sender,
+	target_chain::{DispatchMessage, DispatchMessageData, MessageDispatch},
+	LaneId, MessageKey,
+};
+use bp_runtime::{messages::MessageDispatchResult, ChainId};
+use codec::{Decode, Encode};
+use frame_support::{
+	traits::{Get, Instance},
+	weights::Weight,
+	RuntimeDebug,
+};
+use sp_core::{hashing::blake2_256, H256};
+use sp_runtime::traits::Convert;
+use sp_std::{marker::PhantomData, vec::Vec};
+
+/// A unique prefix for entropy when generating forwarding account IDs.
+pub const FORWARDING_ACCOUNT_DERIVATION_PREFIX: &[u8] = b"pallet-bridge/account-derivation/forwarding";
+
+/// Payload of the inbound message that may either be dispatched at this chain, or forwarded
+/// to the other bridge.
+#[derive(Encode, Decode, Clone, RuntimeDebug, PartialEq, Eq)]
+pub enum ForwardablePayload<Payload, Fee> {
+	/// The message is dispatched at this chain.
+	Dispatch(Payload),
+	/// The message is forwarded to the other bridge.
+	Forward(ForwardEnvelope<Fee>),
+}
+
+/// Envelope of the message that needs to be forwarded to the other bridge.
+#[derive(Encode, Decode, Clone, RuntimeDebug, PartialEq, Eq)]
+pub struct ForwardEnvelope<Fee> {
+	/// Identifier of the chain, where the message must be forwarded to.
+	pub target_chain_id: ChainId,
+	/// Lane of the next hop bridge.
+	pub lane: LaneId,
+	/// Delivery and dispatch fee of the next hop, paid at this chain.
+	pub fee: Fee,
+	/// Number of hops that the message has already made.
+	///
+	/// The value is only trusted at the first hop. When the message is forwarded, the hops
+	/// counter of the nested envelope (if any) is overwritten with the incremented value.
+	pub hops: u32,
+	/// Encoded payload of the next hop message.
+	pub payload: Vec<u8>,
+}
+
+/// Something that is able to forward messages to other bridges.
+pub trait MessageForwarder {
+	/// Type of the next hop message fee.
+	type Fee;
+
+	/// Return upper bound of the forwarding weight.
+	fn forward_weight(envelope: &ForwardEnvelope<Self::Fee>) -> Weight;
+
+	/// Forward message, received over the `inbound_lane`. Returns actual forwarding weight.
+	fn forward(inbound_lane: LaneId, envelope: ForwardEnvelope<Self::Fee>) -> Result<Weight, &'static str>;
+}
+
+/// Handler of fees that are paid by forwarding accounts.
+pub trait OnForwardingFeePaid<Fee> {
+	/// Called when the `fee` for forwarding message, received over the `inbound_lane` of the bridge
+	/// with `source_chain`, has been paid.
+	fn on_forwarding_fee_paid(source_chain: ChainId, inbound_lane: LaneId, fee: Fee);
+}
+
+impl<Fee> OnForwardingFeePaid<Fee> for () {
+	fn on_forwarding_fee_paid(_source_chain: ChainId, _inbound_lane: LaneId, _fee: Fee) {}
+}
+
+impl<T, I, Fee> OnForwardingFeePaid<Fee> for pallet_bridge_fee_settlement::Pallet<T, I>
+where
+	T: pallet_bridge_fee_settlement::Config<I>,
+	I: 'static,
+	Fee: Into<pallet_bridge_fee_settlement::BalanceOf<T, I>>,
+{
+	fn on_forwarding_fee_paid(source_chain: ChainId, inbound_lane: LaneId, fee: Fee) {
+		pallet_bridge_fee_settlement::Pallet::<T, I>::note_forwarding_fee(source_chain, inbound_lane, fee.into());
+	}
+}
+
+/// Something that builds reconciliation messages for the bridged chain.
+pub trait ReconciliationMessageBuilder<Balance, Payload, Fee> {
+	/// Build message that requests the bridged chain to pay `amount` from the pot of given lane. Returns
+	/// message payload and the delivery and dispatch fee of the message.
+	fn build_reconciliation_message(lane: LaneId, amount: Balance) -> Result<(Payload, Fee), &'static str>;
+}
+
+/// Reconciliation sender that sends reconciliation messages over the bridge, served by the messages
+/// pallet instance.
+///
+/// Messages are sent over the same lane, that has been used to receive forwarded messages. They're
+/// sent on behalf of the `SenderAccount`, which is paying message fees. So this account must be
+/// funded and allowed to send messages over lanes that are used for forwarding.
+pub struct MessagesPalletReconciliationSender<Runtime, MessagesPalletInstance, SenderAccount, Builder>(
+	PhantomData<(Runtime, MessagesPalletInstance, SenderAccount, Builder)>,
+);
+
+impl<Runtime, MessagesPalletInstance, SenderAccount, Builder, Balance>
+	pallet_bridge_fee_settlement::ReconciliationSender<Balance>
+	for MessagesPalletReconciliationSender<Runtime, MessagesPalletInstance, SenderAccount, Builder>
+where
+	Runtime: pallet_bridge_messages::Config<MessagesPalletInstance>,
+	MessagesPalletInstance: Instance,
+	SenderAccount: Get<Runtime::AccountId>,
+	Builder: ReconciliationMessageBuilder<Balance, Runtime::OutboundPayload, Runtime::OutboundMessageFee>,
+{
+	fn send_reconciliation(chain: ChainId, lane: LaneId, amount: Balance) -> Result<Weight, &'static str> {
+		if chain != Runtime::BridgedChainId::get() {
+			return Err("Reconciliation is targeting unknown chain");
+		}
+
+		let (payload, fee) = Builder::build_reconciliation_message(lane, amount)?;
+		let weight = pallet_bridge_messages::send_message_weight::<Runtime, MessagesPalletInstance>(&payload);
+		let post_info = pallet_bridge_messages::Pallet::<Runtime, MessagesPalletInstance>::send_message(
+			Sender::Signed(SenderAccount::get()).into(),
+			lane,
+			payload,
+			fee,
+		)
+		.map_err(|error| error.error.into())?;
+
+		Ok(post_info.actual_weight.unwrap_or(weight))
+	}
+}
+
+/// Derive the account ID that is paying fees for messages forwarded from the given lane of given bridge.
+pub fn forwarding_account_id(bridge_id: ChainId, inbound_lane: LaneId) -> H256 {
+	(FORWARDING_ACCOUNT_DERIVATION_PREFIX, bridge_id, inbound_lane)
+		.using_encoded(blake2_256)
+		.into()
+}
+
+/// Message dispatch that forwards messages with `ForwardablePayload::Forward` payload and dispatches
+/// all other messages using the `Dispatch`.
+///
+/// Messages that have made `MaxHops` hops are not forwarded.
+pub struct ForwardingMessageDispatch<Dispatch, Forwarder, MaxHops>(PhantomData<(Dispatch, Forwarder, MaxHops)>);
+
+impl<AccountId, Fee, Dispatch, Forwarder, MaxHops> MessageDispatch<AccountId, Fee>
+	for ForwardingMessageDispatch<Dispatch, Forwarder, MaxHops>
+where
+	Fee: Clone,
+	Dispatch: MessageDispatch<AccountId, Fee>,
+	Dispatch::DispatchPayload: Clone,
+	Forwarder: MessageForwarder,
+	Forwarder::Fee: Decode + Encode,
+	MaxHops: Get<u32>,
+{
+	type DispatchPayload = ForwardablePayload<Dispatch::DispatchPayload, Forwarder::Fee>;
+
+	fn dispatch_weight(message: &DispatchMessage<Self::DispatchPayload, Fee>) -> Weight {
+		match message.data.payload {
+			Ok(ForwardablePayload::Dispatch(ref payload)) => Dispatch::dispatch_weight(&DispatchMessage {
+				key: message.key.clone(),
+				data: DispatchMessageData {
+					payload: Ok(payload.clone()),
+					fee: message.data.fee.clone(),
+				},
+			}),
+			Ok(ForwardablePayload::Forward(ref envelope)) => Forwarder::forward_weight(envelope),
+			Err(_) => 0,
+		}
+	}
+
+	fn dispatch_overhead_weight() -> Weight {
+		Dispatch::dispatch_overhead_weight()
+	}
+
+	fn dispatch(
+		relayer_account: &AccountId,
+		message: DispatchMessage<Self::DispatchPayload, Fee>,
+	) -> MessageDispatchResult {
+		let DispatchMessage {
+			key,
+			data: DispatchMessageData { payload, fee },
+		} = message;
+		let payload = match payload {
+			Ok(ForwardablePayload::Dispatch(payload)) => Ok(payload),
+			Ok(ForwardablePayload::Forward(envelope)) => return forward::<Forwarder, MaxHops>(key, envelope),
+			Err(error) => Err(error),
+		};
+
+		Dispatch::dispatch(
+			relayer_account,
+			DispatchMessage {
+				key,
+				data: DispatchMessageData { payload, fee },
+			},
+		)
+	}
+}
+
+/// Forward message to the next hop bridge.
+fn forward<Forwarder, MaxHops>(key: MessageKey, mut envelope: ForwardEnvelope<Forwarder::Fee>) -> MessageDispatchResult
+where
+	Forwarder: MessageForwarder,
+	Forwarder::Fee: Decode + Encode,
+	MaxHops: Get<u32>,
+{
+	let forward_weight = Forwarder::forward_weight(&envelope);
+	let mut dispatch_result = MessageDispatchResult {
+		dispatch_result: false,
+		unspent_weight: forward_weight,
+		dispatch_fee_paid_during_dispatch: false,
+	};
+
+	if envelope.hops >= MaxHops::get() {
+		log::trace!(
+			target: "runtime::bridge-forwarding",
+			"Message {:?}: not forwarding message that has made {} hops",
+			key,
+			envelope.hops,
+		);
+		return dispatch_result;
+	}
+
+	envelope.payload = with_incremented_hops::<Forwarder::Fee>(envelope.hops, envelope.payload);
+	match Forwarder::forward(key.lane_id, envelope) {
+		Ok(actual_weight) => {
+			log::trace!(
+				target: "runtime::bridge-forwarding",
+				"Message {:?}: forwarded to the next hop bridge",
+				key,
+			);
+			dispatch_result.dispatch_result = true;
+			dispatch_result.unspent_weight = forward_weight.saturating_sub(actual_weight);
+		}
+		Err(error) => {
+			log::trace!(
+				target: "runtime::bridge-forwarding",
+				"Message {:?}: failed to forward to the next hop bridge: {}",
+				key,
+				error,
+			);
+		}
+	}
+
+	dispatch_result
+}
+
+/// If the next hop message payload is the `ForwardablePayload::Forward`, set hops counter of its
+/// envelope to `hops + 1`. Otherwise the payload is returned unchanged.
+///
+/// All bridges of the forwarding path are expected to use the same fee type. If the nested
+/// envelope can't be decoded, the next hop will be unable to decode it either, so it is never
+/// forwarded further.
+fn with_incremented_hops<Fee: Decode + Encode>(hops: u32, payload: Vec<u8>) -> Vec<u8> {
+	let mut input = &payload[..];
+	match NestedForwardablePayload::<Fee>::decode(&mut input) {
+		Ok(NestedForwardablePayload::Forward(mut nested_envelope)) if input.is_empty() => {
+			nested_envelope.hops = hops.saturating_add(1);
+			NestedForwardablePayload::Forward(nested_envelope).encode()
+		}
+		_ => payload,
+	}
+}
+
+/// The `ForwardablePayload` with only `Forward` variant, which may be decoded without knowing the
+/// type of dispatch payload.
+#[derive(Encode, Decode)]
+enum NestedForwardablePayload<Fee> {
+	#[codec(index = 1)]
+	Forward(ForwardEnvelope<Fee>),
+}
+
+/// Message forwarder that sends messages over the bridge, served by the messages pallet instance.
+///
+/// The next hop fee is paid by the forwarding account of the inbound lane at the bridge with
+/// `SourceChainId` and then it is reported to the `OnFeePaid`.
+pub struct MessagesPalletForwarder<Runtime, MessagesPalletInstance, SourceChainId, OnFeePaid = ()>(
+	PhantomData<(Runtime, MessagesPalletInstance, SourceChainId, OnFeePaid)>,
+);
+
+impl<Runtime, MessagesPalletInstance, SourceChainId, OnFeePaid> MessageForwarder
+	for MessagesPalletForwarder<Runtime, MessagesPalletInstance, SourceChainId, OnFeePaid>
+where
+	Runtime: pallet_bridge_messages::Config<MessagesPalletInstance>,
+	MessagesPalletInstance: Instance,
+	SourceChainId: Get<ChainId>,
+	OnFeePaid: OnForwardingFeePaid<Runtime::OutboundMessageFee>,
+{
+	type Fee = Runtime::OutboundMessageFee;
+
+	fn forward_weight(envelope: &ForwardEnvelope<Self::Fee>) -> Weight {
+		Runtime::OutboundPayload::decode(&mut &envelope.payload[..])
+			.map(|payload| pallet_bridge_messages::send_message_weight::<Runtime, MessagesPalletInstance>(&payload))
+			.unwrap_or(0)
+	}
+
+	fn forward(inbound_lane: LaneId, envelope: ForwardEnvelope<Self::Fee>) -> Result<Weight, &'static str> {
+		if envelope.target_chain_id != Runtime::BridgedChainId::get() {
+			return Err("Forwarded message is targeting unknown chain");
+		}
+
+		let payload = Runtime::OutboundPayload::decode(&mut &envelope.payload[..])
+			.map_err(|_| "Failed to decode forwarded message payload")?;
+		let weight = pallet_bridge_messages::send_message_weight::<Runtime, MessagesPalletInstance>(&payload);
+		let submitter = Runtime::AccountIdConverter::convert(forwarding_account_id(SourceChainId::get(), inbound_lane));
+		let post_info = pallet_bridge_messages::Pallet::<Runtime, MessagesPalletInstance>::send_message(
+			Sender::Signed(submitter).into(),
+			envelope.lane,
+			payload,
+			envelope.fee.clone(),
+		)
+		.map_err(|error| error.error.into())?;
+
+		OnFeePaid::on_forwarding_fee_paid(SourceChainId::get(), inbound_lane, envelope.fee);
+
+		Ok(post_info.actual_weight.unwrap_or(weight))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const MAX_HOPS: u32 = 2;
+	const FORWARD_WEIGHT: Weight = 100;
+	const ACTUAL_FORWARD_WEIGHT: Weight = 60;
+	const REJECTED_LANE: LaneId = LaneId::Legacy(*b"rjct");
+
+	frame_support::parameter_types! {
+		pub const MaxHops: u32 = MAX_HOPS;
+	}
+
+	struct TestDispatch;
+
+	impl MessageDispatch<u64, u64> for TestDispatch {
+		type DispatchPayload = Weight;
+
+		fn dispatch_weight(message: &DispatchMessage<Self::DispatchPayload, u64>) -> Weight {
+			message.data.payload.as_ref().copied().unwrap_or(0)
+		}
+
+		fn dispatch(_: &u64, message: DispatchMessage<Self::DispatchPayload, u64>) -> MessageDispatchResult {
+			MessageDispatchResult {
+				dispatch_result: message.data.payload.is_ok(),
+				unspent_weight: 0,
+				dispatch_fee_paid_during_dispatch: false,
+			}
+		}
+	}
+
+	std::thread_local! {
+		static FORWARDED_PAYLOAD: std::cell::RefCell<Option<Vec<u8>>> = std::cell::RefCell::new(None);
+	}
+
+	struct TestForwarder;
+
+	impl MessageForwarder for TestForwarder {
+		type Fee = u64;
+
+		fn forward_weight(_envelope: &ForwardEnvelope<Self::Fee>) -> Weight {
+			FORWARD_WEIGHT
+		}
+
+		fn forward(_inbound_lane: LaneId, envelope: ForwardEnvelope<Self::Fee>) -> Result<Weight, &'static str> {
+			if envelope.lane == REJECTED_LANE {
+				return Err("Rejected");
+			}
+
+			FORWARDED_PAYLOAD.with(|forwarded_payload| *forwarded_payload.borrow_mut() = Some(envelope.payload));
+			Ok(ACTUAL_FORWARD_WEIGHT)
+		}
+	}
+
+	type TestForwardingDispatch = ForwardingMessageDispatch<TestDispatch, TestForwarder, MaxHops>;
+
+	fn message(payload: ForwardablePayload<Weight, u64>) -> DispatchMessage<ForwardablePayload<Weight, u64>, u64> {
+		DispatchMessage {
+			key: MessageKey {
+				lane_id: LaneId::Legacy([0, 0, 0, 0]),
+				nonce: 1,
+			},
+			data: DispatchMessageData {
+				payload: Ok(payload),
+				fee: 0,
+			},
+		}
+	}
+
+	fn envelope(lane: LaneId, hops: u32) -> ForwardablePayload<Weight, u64> {
+		ForwardablePayload::Forward(ForwardEnvelope {
+			target_chain_id: *b"test",
+			lane,
+			fee: 100,
+			hops,
+			payload: vec![42],
+		})
+	}
+
+	#[test]
+	fn regular_message_is_dispatched() {
+		let message = message(ForwardablePayload::Dispatch(42));
+		assert_eq!(
+			<TestForwardingDispatch as MessageDispatch<u64, u64>>::dispatch_weight(&message),
+			42
+		);
+		assert_eq!(
+			<TestForwardingDispatch as MessageDispatch<u64, u64>>::dispatch(&0, message),
+			MessageDispatchResult {
+				dispatch_result: true,
+				unspent_weight: 0,
+				dispatch_fee_paid_during_dispatch: false,
+			},
+		);
+	}
+
+	#[test]
+	fn message_is_forwarded() {
+		let message = message(envelope(LaneId::Legacy([0, 0, 0, 1]), MAX_HOPS - 1));
+		assert_eq!(
+			<TestForwardingDispatch as MessageDispatch<u64, u64>>::dispatch_weight(&message),
+			FORWARD_WEIGHT
+		);
+		assert_eq!(
+			<TestForwardingDispatch as MessageDispatch<u64, u64>>::dispatch(&0, message),
+			MessageDispatchResult {
+				dispatch_result: true,
+				unspent_weight: FORWARD_WEIGHT - ACTUAL_FORWARD_WEIGHT,
+				dispatch_fee_paid_during_dispatch: false,
+			},
+		);
+	}
+
+	#[test]
+	fn message_that_has_made_too_many_hops_is_not_forwarded() {
+		assert_eq!(
+			<TestForwardingDispatch as MessageDispatch<u64, u64>>::dispatch(
+				&0,
+				message(envelope(LaneId::Legacy([0, 0, 0, 1]), MAX_HOPS))
+			),
+			MessageDispatchResult {
+				dispatch_result: false,
+				unspent_weight: FORWARD_WEIGHT,
+				dispatch_fee_paid_during_dispatch: false,
+			},
+		);
+	}
+
+	#[test]
+	fn message_rejected_by_forwarder_is_not_forwarded() {
+		assert_eq!(
+			<TestForwardingDispatch as MessageDispatch<u64, u64>>::dispatch(&0, message(envelope(REJECTED_LANE, 0))),
+			MessageDispatchResult {
+				dispatch_result: false,
+				unspent_weight: FORWARD_WEIGHT,
+				dispatch_fee_paid_during_dispatch: false,
+			},
+		);
+	}
+
+	#[test]
+	fn hops_of_nested_envelope_are_incremented() {
+		let nested_envelope = |hops| ForwardEnvelope {
+			target_chain_id: *b"next",
+			lane: LaneId::Legacy([0, 0, 0, 2]),
+			fee: 10u64,
+			hops,
+			payload: vec![42],
+		};
+		let message = message(ForwardablePayload::Forward(ForwardEnvelope {
+			target_chain_id: *b"test",
+			lane: LaneId::Legacy([0, 0, 0, 1]),
+			fee: 100,
+			hops: 0,
+			payload: ForwardablePayload::<Weight, u64>::Forward(nested_envelope(0)).encode(),
+		}));
+
+		assert!(<TestForwardingDispatch as MessageDispatch<u64, u64>>::dispatch(&0, message).dispatch_result);
+		assert_eq!(
+			FORWARDED_PAYLOAD.with(|forwarded_payload| forwarded_payload.borrow_mut().take()),
+			Some(ForwardablePayload::<Weight, u64>::Forward(nested_envelope(1)).encode()),
+		);
+	}
+
+	#[test]
+	fn nested_regular_payload_is_forwarded_unchanged() {
+		let payload = ForwardablePayload::<Weight, u64>::Dispatch(42).encode();
+		let message = message(ForwardablePayload::Forward(ForwardEnvelope {
+			target_chain_id: *b"test",
+			lane: LaneId::Legacy([0, 0, 0, 1]),
+			fee: 100,
+			hops: 0,
+			payload: payload.clone(),
+		}));
+
+		assert!(<TestForwardingDispatch as MessageDispatch<u64, u64>>::dispatch(&0, message).dispatch_result);
+		assert_eq!(
+			FORWARDED_PAYLOAD.with(|forwarded_payload| forwarded_payload.borrow_mut().take()),
+			Some(payload),
+		);
+	}
+}
@@ -0,0 +1,164 @@
+// Copyright 2019-2021 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Transaction extension that rejects obsolete bridge transactions.
+//!
+//! There may be several relayers, serving the same bridge. They are racing with each other, so
+//! the same bridged header or the same messages may be submitted by several relayers at once.
+//! Only the first transaction is useful - all others are no-op, but their submitters still pay
+//! for them. The extension, declared by the [`declare_bridge_reject_obsolete_headers_and_messages`]
+//! macro, rejects such transactions before they're included into the block.
+//!
+//! [`declare_bridge_reject_obsolete_headers_and_messages`]: crate::declare_bridge_reject_obsolete_headers_and_messages
+
+use crate::messages::target::FromBridgedChainMessagesProof;
+
+use frame_support::traits::Instance;
+use sp_runtime::{
+	traits::Header as HeaderT,
+	transaction_validity::{InvalidTransaction, TransactionValidity, ValidTransaction},
+};
+
+/// Validate transaction that submits finality proof of the bridged header.
+///
+/// The transaction is rejected if the pallet already has finalized header with the same or
+/// larger number.
+pub fn validate_finality_proof_submission<T: pallet_bridge_grandpa::Config<I>, I: 'static>(
+	finality_target: &pallet_bridge_grandpa::BridgedHeader<T, I>,
+) -> TransactionValidity {
+	let best_finalized_number = *pallet_bridge_grandpa::Pallet::<T, I>::best_finalized().number();
+	if *finality_target.number() <= best_finalized_number {
+		log::trace!(
+			target: "runtime::bridge",
+			"Rejecting obsolete bridged header: submitted {:?}, best finalized {:?}",
+			finality_target.number(),
+			best_finalized_number,
+		);
+
+		return InvalidTransaction::Stale.into();
+	}
+
+	Ok(ValidTransaction::default())
+}
+
+/// Validate transaction that delivers messages from the bridged chain.
+///
+/// The transaction is rejected if all messages of all lanes in the proof have already been
+/// delivered. Proofs that have no messages at some lane are never rejected, because they may be
+/// used to update state of this lane.
+pub fn validate_messages_delivery<T: pallet_bridge_messages::Config<I>, I: Instance, BridgedHeaderHash>(
+	proof: &FromBridgedChainMessagesProof<BridgedHeaderHash>,
+) -> TransactionValidity {
+	let is_obsolete = sp_std::iter::once((proof.lane, proof.nonces_start, proof.nonces_end))
+		.chain(proof.additional_lanes.iter().cloned())
+		.all(|(lane, nonces_start, nonces_end)| {
+			nonces_start <= nonces_end
+				&& nonces_end <= pallet_bridge_messages::Pallet::<T, I>::inbound_latest_received_nonce(lane)
+		});
+	if is_obsolete {
+		log::trace!(
+			target: "runtime::bridge",
+			"Rejecting obsolete messages delivery: lane {:?}, messages {}..={}",
+			proof.lane,
+			proof.nonces_start,
+			proof.nonces_end,
+		);
+
+		return InvalidTransaction::Stale.into();
+	}
+
+	Ok(ValidTransaction::default())
+}
+
+/// Declares the `BridgeRejectObsoleteHeadersAndMessages` signed extension for the runtime.
+///
+/// The extension rejects `submit_finality_proof` calls of listed GRANDPA pallets that are
+/// submitting already known headers, and `receive_messages_proof` calls of listed messages
+/// pallets that are delivering already delivered messages. Messages pallets must be using
+/// [`FromBridgedChainMessagesProof`] proofs. All other calls are passed through.
+///
+/// The runtime must depend on `pallet-bridge-grandpa` and `pallet-bridge-messages` crates.
+///
+/// Example:
+///
+/// ```ignore
+/// bridge_runtime_common::declare_bridge_reject_obsolete_headers_and_messages!(
+/// 	Runtime,
+/// 	Call,
+/// 	grandpa: [BridgeRialtoGrandpa => RialtoGrandpaInstance],
+/// 	messages: [BridgeRialtoMessages => WithRialtoMessagesInstance]
+/// );
+/// ```
+#[macro_export]
+macro_rules! declare_bridge_reject_obsolete_headers_and_messages {
+	(
+		$runtime:ident,
+		$call:ident,
+		grandpa: [$($grandpa_pallet:ident => $grandpa_instance:ty),*],
+		messages: [$($messages_pallet:ident => $messages_instance:ty),*]
+	) => {
+		/// Transaction extension that rejects obsolete bridge transactions.
+		#[derive(Clone, codec::Decode, codec::Encode, Eq, PartialEq, frame_support::RuntimeDebug)]
+		pub struct BridgeRejectObsoleteHeadersAndMessages;
+
+		impl sp_runtime::traits::SignedExtension for BridgeRejectObsoleteHeadersAndMessages {
+			const IDENTIFIER: &'static str = "BridgeRejectObsoleteHeadersAndMessages";
+			type AccountId = <$runtime as frame_system::Config>::AccountId;
+			type Call = $call;
+			type AdditionalSigned = ();
+			type Pre = ();
+
+			fn additional_signed(
+				&self,
+			) -> sp_std::result::Result<(), sp_runtime::transaction_validity::TransactionValidityError> {
+				Ok(())
+			}
+
+			fn validate(
+				&self,
+				_who: &Self::AccountId,
+				call: &Self::Call,
+				_info: &sp_runtime::traits::DispatchInfoOf<Self::Call>,
+				_len: usize,
+			) -> sp_runtime::transaction_validity::TransactionValidity {
+				match *call {
+					$(
+						$call::$grandpa_pallet(pallet_bridge_grandpa::Call::submit_finality_proof(
+							ref finality_target,
+							_,
+						)) => $crate::reject_obsolete_transactions::validate_finality_proof_submission::<
+							$runtime,
+							$grandpa_instance,
+						>(finality_target),
+					)*
+					$(
+						$call::$messages_pallet(pallet_bridge_messages::Call::receive_messages_proof(
+							_,
+							ref proof,
+							_,
+							_,
+						)) => $crate::reject_obsolete_transactions::validate_messages_delivery::<
+							$runtime,
+							$messages_instance,
+							_,
+						>(proof),
+					)*
+					_ => Ok(sp_runtime::transaction_validity::ValidTransaction::default()),
+				}
+			}
+		}
+	};
+}
@@ -0,0 +1,199 @@
+// Copyright 2019-2021 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Implementation of `MessageDeliveryAndDispatchPayment` trait that shares delivery rewards with
+//! the confirmation relayer.
+//!
+//! The confirmation relayer is normally only reimbursed with the confirmation fee, which barely
+//! covers the cost of the confirmation transaction. So confirmations are submitted by delivery
+//! relayers that want to receive their own rewards, or by altruistic relayers. The wrapper,
+//! declared here, moves the configured part of every delivery relayer reward to the relayer that
+//! has submitted the delivery confirmation.
+
+use bp_messages::source_chain::{MessageDeliveryAndDispatchPayment, RelayerRewards, RelayersRewards, Sender};
+use frame_support::traits::Get;
+use sp_runtime::{
+	traits::{AtLeast32BitUnsigned, Zero},
+	Perbill,
+};
+use sp_std::marker::PhantomData;
+
+/// Message payments that move `GetConfirmationRewardShare` part of delivery relayers rewards to
+/// the confirmation relayer, and pay resulting rewards using wrapped `Payments` implementation.
+///
+/// `GetConfirmationRewardShare` is expected to return the share that may be changed by the pallet
+/// owner (e.g. it may be backed by the runtime storage and updated using the messages pallet
+/// parameter). Rewards of the confirmation relayer for messages that it has delivered itself, are
+/// not affected.
+pub struct ConfirmationRewardSplitPayments<Payments, GetConfirmationRewardShare>(
+	PhantomData<(Payments, GetConfirmationRewardShare)>,
+);
+
+impl<AccountId, Balance, Payments, GetConfirmationRewardShare> MessageDeliveryAndDispatchPayment<AccountId, Balance>
+	for ConfirmationRewardSplitPayments<Payments, GetConfirmationRewardShare>
+where
+	AccountId: Clone + Ord,
+	Balance: AtLeast32BitUnsigned + Copy,
+	Payments: MessageDeliveryAndDispatchPayment<AccountId, Balance>,
+	GetConfirmationRewardShare: Get<Perbill>,
+{
+	type Error = Payments::Error;
+
+	fn initialize(relayer_fund_account: &AccountId) -> usize {
+		Payments::initialize(relayer_fund_account)
+	}
+
+	fn pay_delivery_and_dispatch_fee(
+		submitter: &Sender<AccountId>,
+		fee: &Balance,
+		relayer_fund_account: &AccountId,
+	) -> Result<(), Self::Error> {
+		Payments::pay_delivery_and_dispatch_fee(submitter, fee, relayer_fund_account)
+	}
+
+	fn refund_delivery_and_dispatch_fee(
+		refund_to: &Sender<AccountId>,
+		fee: &Balance,
+		relayer_fund_account: &AccountId,
+	) -> Result<(), Self::Error> {
+		Payments::refund_delivery_and_dispatch_fee(refund_to, fee, relayer_fund_account)
+	}
+
+	fn pay_relayers_rewards(
+		confirmation_relayer: &AccountId,
+		relayers_rewards: RelayersRewards<AccountId, Balance>,
+		relayer_fund_account: &AccountId,
+	) -> Balance {
+		let relayers_rewards = split_confirmation_reward(
+			confirmation_relayer,
+			relayers_rewards,
+			GetConfirmationRewardShare::get(),
+		);
+		Payments::pay_relayers_rewards(confirmation_relayer, relayers_rewards, relayer_fund_account)
+	}
+}
+
+/// Move `confirmation_reward_share` of delivery relayers rewards to the confirmation relayer.
+fn split_confirmation_reward<AccountId, Balance>(
+	confirmation_relayer: &AccountId,
+	mut relayers_rewards: RelayersRewards<AccountId, Balance>,
+	confirmation_reward_share: Perbill,
+) -> RelayersRewards<AccountId, Balance>
+where
+	AccountId: Clone + Ord,
+	Balance: AtLeast32BitUnsigned + Copy,
+{
+	if confirmation_reward_share.is_zero() {
+		return relayers_rewards;
+	}
+
+	let mut confirmation_reward = Balance::zero();
+	for (relayer, reward) in relayers_rewards.iter_mut() {
+		if relayer == confirmation_relayer {
+			continue;
+		}
+
+		let confirmation_part = confirmation_reward_share.mul_floor(reward.reward);
+		reward.reward = reward.reward.saturating_sub(confirmation_part);
+		confirmation_reward = confirmation_reward.saturating_add(confirmation_part);
+	}
+
+	if !confirmation_reward.is_zero() {
+		let confirmation_relayer_reward =
+			relayers_rewards
+				.entry(confirmation_relayer.clone())
+				.or_insert_with(|| RelayerRewards {
+					reward: Zero::zero(),
+					messages: 0,
+				});
+		confirmation_relayer_reward.reward = confirmation_relayer_reward.reward.saturating_add(confirmation_reward);
+	}
+
+	relayers_rewards
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const RELAYER_1: u64 = 1;
+	const RELAYER_2: u64 = 2;
+	const RELAYER_3: u64 = 3;
+
+	fn relayers_rewards() -> RelayersRewards<u64, u64> {
+		vec![
+			(
+				RELAYER_1,
+				RelayerRewards {
+					reward: 100,
+					messages: 2,
+				},
+			),
+			(
+				RELAYER_2,
+				RelayerRewards {
+					reward: 200,
+					messages: 3,
+				},
+			),
+		]
+		.into_iter()
+		.collect()
+	}
+
+	fn rewards_of(relayers_rewards: &RelayersRewards<u64, u64>) -> Vec<(u64, u64, u64)> {
+		relayers_rewards
+			.iter()
+			.map(|(relayer, reward)| (*relayer, reward.reward, reward.messages))
+			.collect()
+	}
+
+	#[test]
+	fn rewards_are_not_changed_if_confirmation_reward_share_is_zero() {
+		let relayers_rewards = split_confirmation_reward(&RELAYER_3, relayers_rewards(), Perbill::zero());
+		assert_eq!(
+			rewards_of(&relayers_rewards),
+			vec![(RELAYER_1, 100, 2), (RELAYER_2, 200, 3)]
+		);
+	}
+
+	#[test]
+	fn confirmation_relayer_receives_share_of_other_relayers_rewards() {
+		let relayers_rewards = split_confirmation_reward(&RELAYER_2, relayers_rewards(), Perbill::from_percent(10));
+		assert_eq!(
+			rewards_of(&relayers_rewards),
+			vec![(RELAYER_1, 90, 2), (RELAYER_2, 210, 3)]
+		);
+	}
+
+	#[test]
+	fn confirmation_relayer_that_has_not_delivered_messages_receives_share_of_rewards() {
+		let relayers_rewards = split_confirmation_reward(&RELAYER_3, relayers_rewards(), Perbill::from_percent(10));
+		assert_eq!(
+			rewards_of(&relayers_rewards),
+			vec![(RELAYER_1, 90, 2), (RELAYER_2, 180, 3), (RELAYER_3, 30, 0)],
+		);
+	}
+
+	#[test]
+	fn confirmation_relayer_may_receive_all_rewards() {
+		let relayers_rewards = split_confirmation_reward(&RELAYER_3, relayers_rewards(), Perbill::one());
+		assert_eq!(
+			rewards_of(&relayers_rewards),
+			vec![(RELAYER_1, 0, 2), (RELAYER_2, 0, 3), (RELAYER_3, 300, 0)],
+		);
+	}
+}
@@ -0,0 +1,557 @@
+// Copyright 2019-2021 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Runtime module that maintains registry of applications, multiplexing their messages over
+//! shared lanes.
+//!
+//! Messages of shared lanes are not dispatched as calls. Instead, every message payload is
+//! tagged with the 2-byte application identifier (see `bp_messages::AppPayload`) and the
+//! `AppPayloadDispatch`, which must be used as the `MessageDispatch` of the messages pallet
+//! instance, passes the payload to the handler of this application.
+//!
+//! The registry maps application identifiers to hashes of their payload schemas. The payload is
+//! only passed to the handler if the application is registered and the handler understands the
+//! registered schema. So all consumers are interpreting payloads the same way.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use bp_messages::{
+	target_chain::{DispatchMessage, MessageDispatch},
+	AppId, AppPayload, MessageKey,
+};
+use bp_runtime::messages::MessageDispatchResult;
+use codec::{Decode, Encode};
+use frame_support::{traits::Get, weights::Weight, RuntimeDebug};
+use sp_std::{marker::PhantomData, prelude::*};
+
+#[cfg(feature = "std")]
+use serde::{Deserialize, Serialize};
+
+#[cfg(test)]
+mod mock;
+
+/// Registered application information.
+#[derive(Encode, Decode, Clone, RuntimeDebug, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct AppInfo<AccountId, Hash> {
+	/// Application owner account. The owner may update the payload schema.
+	pub owner: AccountId,
+	/// Hash of the application payload schema.
+	pub schema_hash: Hash,
+}
+
+/// Application information type used by the pallet.
+pub type AppInfoOf<T> = AppInfo<<T as frame_system::Config>::AccountId, <T as frame_system::Config>::Hash>;
+
+/// Handler of payloads of the single application.
+pub trait AppPayloadHandler<Hash> {
+	/// Identifier of the application, which payloads are handled.
+	const APP_ID: AppId;
+
+	/// Returns hash of the payload schema that the handler understands.
+	fn schema_hash() -> Hash;
+	/// Returns weight of the payload handling.
+	fn handling_weight(payload: &[u8]) -> Weight;
+	/// Handle the application payload. Returns true if the payload has been handled successfully.
+	fn handle(message_key: &MessageKey, payload: Vec<u8>) -> bool;
+}
+
+/// Set of application payload handlers.
+///
+/// It is implemented for tuples of up to 8 handlers. If there are several handlers of the same
+/// application, the first one is used.
+pub trait AppPayloadHandlers<Hash> {
+	/// Returns hash of the payload schema that the handler of given application understands, or
+	/// `None` if there's no such handler.
+	fn schema_hash(app_id: AppId) -> Option<Hash>;
+	/// Returns weight of the payload handling by the handler of given application.
+	fn handling_weight(app_id: AppId, payload: &[u8]) -> Option<Weight>;
+	/// Handle the payload of given application.
+	fn handle(app_id: AppId, message_key: &MessageKey, payload: Vec<u8>) -> Option<bool>;
+}
+
+macro_rules! impl_app_payload_handlers_for_tuples {
+	($($handler:ident),+) => {
+		impl<Hash, $($handler: AppPayloadHandler<Hash>),+> AppPayloadHandlers<Hash> for ($($handler,)+) {
+			fn schema_hash(app_id: AppId) -> Option<Hash> {
+				$(if app_id == $handler::APP_ID {
+					return Some($handler::schema_hash());
+				})+
+				None
+			}
+
+			fn handling_weight(app_id: AppId, payload: &[u8]) -> Option<Weight> {
+				$(if app_id == $handler::APP_ID {
+					return Some($handler::handling_weight(payload));
+				})+
+				None
+			}
+
+			fn handle(app_id: AppId, message_key: &MessageKey, payload: Vec<u8>) -> Option<bool> {
+				$(if app_id == $handler::APP_ID {
+					return Some($handler::handle(message_key, payload));
+				})+
+				None
+			}
+		}
+	};
+}
+
+impl_app_payload_handlers_for_tuples!(A);
+impl_app_payload_handlers_for_tuples!(A, B);
+impl_app_payload_handlers_for_tuples!(A, B, C);
+impl_app_payload_handlers_for_tuples!(A, B, C, D);
+impl_app_payload_handlers_for_tuples!(A, B, C, D, E);
+impl_app_payload_handlers_for_tuples!(A, B, C, D, E, F);
+impl_app_payload_handlers_for_tuples!(A, B, C, D, E, F, G);
+impl_app_payload_handlers_for_tuples!(A, B, C, D, E, F, G, H);
+
+pub use pallet::*;
+
+// comes from #[pallet::event]
+#[allow(clippy::unused_unit)]
+#[frame_support::pallet]
+pub mod pallet {
+	use super::*;
+	use frame_support::pallet_prelude::*;
+	use frame_system::pallet_prelude::*;
+
+	#[pallet::config]
+	pub trait Config<I: 'static = ()>: frame_system::Config {
+		/// The overarching event type.
+		type Event: From<Event<Self, I>> + IsType<<Self as frame_system::Config>::Event>;
+		/// Origin that is allowed to register and deregister applications.
+		type RegistryOrigin: EnsureOrigin<Self::Origin>;
+	}
+
+	#[pallet::pallet]
+	#[pallet::generate_store(pub(super) trait Store)]
+	pub struct Pallet<T, I = ()>(PhantomData<(T, I)>);
+
+	#[pallet::hooks]
+	impl<T: Config<I>, I: 'static> Hooks<BlockNumberFor<T>> for Pallet<T, I> {}
+
+	#[pallet::call]
+	impl<T: Config<I>, I: 'static> Pallet<T, I> {
+		/// Register new application.
+		///
+		/// May only be called by the registry origin.
+		#[pallet::weight(T::DbWeight::get().reads_writes(1, 1))]
+		pub fn register_app(origin: OriginFor<T>, app_id: AppId, app_info: AppInfoOf<T>) -> DispatchResultWithPostInfo {
+			T::RegistryOrigin::ensure_origin(origin)?;
+			ensure!(
+				!Apps::<T, I>::contains_key(&app_id),
+				Error::<T, I>::AppAlreadyRegistered
+			);
+
+			log::trace!(target: "runtime::bridge-app-registry", "Registered application {:?}: {:?}", app_id, app_info);
+
+			Apps::<T, I>::insert(&app_id, app_info);
+			Self::deposit_event(Event::AppRegistered(app_id));
+
+			Ok(().into())
+		}
+
+		/// Update payload schema of the registered application.
+		///
+		/// May be called by the registry origin or by the application owner. Payloads of the
+		/// application are rejected until handlers, that understand the new schema, are deployed.
+		#[pallet::weight(T::DbWeight::get().reads_writes(1, 1))]
+		pub fn update_schema(origin: OriginFor<T>, app_id: AppId, schema_hash: T::Hash) -> DispatchResultWithPostInfo {
+			let mut app_info = Apps::<T, I>::get(&app_id).ok_or(Error::<T, I>::UnknownApp)?;
+			if let Err(origin) = T::RegistryOrigin::try_origin(origin) {
+				let submitter = ensure_signed(origin)?;
+				ensure!(submitter == app_info.owner, Error::<T, I>::NotAppOwner);
+			}
+
+			app_info.schema_hash = schema_hash;
+
+			log::trace!(target: "runtime::bridge-app-registry", "Updated application {:?}: {:?}", app_id, app_info);
+
+			Apps::<T, I>::insert(&app_id, app_info);
+			Self::deposit_event(Event::SchemaUpdated(app_id, schema_hash));
+
+			Ok(().into())
+		}
+
+		/// Deregister the application. Payloads of the application are rejected after this call.
+		///
+		/// May only be called by the registry origin.
+		#[pallet::weight(T::DbWeight::get().reads_writes(1, 1))]
+		pub fn deregister_app(origin: OriginFor<T>, app_id: AppId) -> DispatchResultWithPostInfo {
+			T::RegistryOrigin::ensure_origin(origin)?;
+			ensure!(Apps::<T, I>::contains_key(&app_id), Error::<T, I>::UnknownApp);
+
+			log::trace!(target: "runtime::bridge-app-registry", "Deregistered application {:?}", app_id);
+
+			Apps::<T, I>::remove(&app_id);
+			Self::deposit_event(Event::AppDeregistered(app_id));
+
+			Ok(().into())
+		}
+	}
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	#[pallet::metadata(T::Hash = "Hash")]
+	pub enum Event<T: Config<I>, I: 'static = ()> {
+		/// Application has been registered.
+		AppRegistered(AppId),
+		/// Payload schema of the application has been updated.
+		SchemaUpdated(AppId, T::Hash),
+		/// Application has been deregistered.
+		AppDeregistered(AppId),
+		/// Message payload has been passed to the application handler. The last field is the
+		/// result of payload handling.
+		PayloadDispatched(MessageKey, AppId, bool),
+		/// Message payload has been rejected, because the application is not registered, or there's
+		/// no handler that understands the registered schema.
+		PayloadRejected(MessageKey, AppId),
+	}
+
+	#[pallet::error]
+	pub enum Error<T, I = ()> {
+		/// The application is already registered.
+		AppAlreadyRegistered,
+		/// The application is not registered.
+		UnknownApp,
+		/// The call origin is not the application owner.
+		NotAppOwner,
+	}
+
+	/// Registered applications.
+	#[pallet::storage]
+	#[pallet::getter(fn app_info)]
+	pub type Apps<T: Config<I>, I: 'static = ()> = StorageMap<_, Blake2_128Concat, AppId, AppInfoOf<T>>;
+
+	#[pallet::genesis_config]
+	pub struct GenesisConfig<T: Config<I>, I: 'static = ()> {
+		/// Applications that are registered at genesis.
+		pub apps: Vec<(AppId, AppInfoOf<T>)>,
+	}
+
+	#[cfg(feature = "std")]
+	impl<T: Config<I>, I: 'static> Default for GenesisConfig<T, I> {
+		fn default() -> Self {
+			Self { apps: Vec::new() }
+		}
+	}
+
+	#[pallet::genesis_build]
+	impl<T: Config<I>, I: 'static> GenesisBuild<T, I> for GenesisConfig<T, I> {
+		fn build(&self) {
+			for (app_id, app_info) in &self.apps {
+				Apps::<T, I>::insert(app_id, app_info);
+			}
+		}
+	}
+}
+
+/// Weight of the registry lookup, performed for every dispatched payload.
+fn registry_lookup_weight<T: frame_system::Config>() -> Weight {
+	T::DbWeight::get().reads_writes(1, 1)
+}
+
+/// Message dispatcher that passes application payloads to handlers of registered applications.
+///
+/// Payloads that can't be decoded, payloads of unregistered applications and payloads of
+/// applications without handler that understands the registered schema are rejected.
+#[derive(RuntimeDebug)]
+pub struct AppPayloadDispatch<T, I, Handlers>(PhantomData<(T, I, Handlers)>);
+
+impl<T, I, Handlers, Fee> MessageDispatch<T::AccountId, Fee> for AppPayloadDispatch<T, I, Handlers>
+where
+	T: Config<I>,
+	I: 'static,
+	Handlers: AppPayloadHandlers<T::Hash>,
+{
+	type DispatchPayload = AppPayload;
+
+	fn dispatch_weight(message: &DispatchMessage<Self::DispatchPayload, Fee>) -> Weight {
+		let handling_weight = match message.data.payload {
+			Ok(ref payload) => Handlers::handling_weight(payload.app_id, &payload.payload).unwrap_or(0),
+			Err(_) => 0,
+		};
+		registry_lookup_weight::<T>().saturating_add(handling_weight)
+	}
+
+	fn dispatch(
+		_relayer_account: &T::AccountId,
+		message: DispatchMessage<Self::DispatchPayload, Fee>,
+	) -> MessageDispatchResult {
+		let dispatch_weight = Self::dispatch_weight(&message);
+		let mut dispatch_result = MessageDispatchResult {
+			dispatch_result: false,
+			unspent_weight: dispatch_weight.saturating_sub(registry_lookup_weight::<T>()),
+			dispatch_fee_paid_during_dispatch: false,
+		};
+
+		let AppPayload { app_id, payload } = match message.data.payload {
+			Ok(payload) => payload,
+			Err(_) => {
+				log::trace!(
+					target: "runtime::bridge-app-registry",
+					"Rejecting message {:?}: failed to decode application payload",
+					message.key,
+				);
+				return dispatch_result;
+			}
+		};
+
+		let registered_schema_hash = Pallet::<T, I>::app_info(app_id).map(|app_info| app_info.schema_hash);
+		if registered_schema_hash.is_none() || registered_schema_hash != Handlers::schema_hash(app_id) {
+			log::trace!(
+				target: "runtime::bridge-app-registry",
+				"Rejecting message {:?}: application {:?} is not registered, or its handler doesn't understand \
+				the registered schema {:?}",
+				message.key,
+				app_id,
+				registered_schema_hash,
+			);
+			Pallet::<T, I>::deposit_event(Event::PayloadRejected(message.key, app_id));
+			return dispatch_result;
+		}
+
+		dispatch_result.dispatch_result = Handlers::handle(app_id, &message.key, payload).unwrap_or(false);
+		dispatch_result.unspent_weight = 0;
+		Pallet::<T, I>::deposit_event(Event::PayloadDispatched(
+			message.key,
+			app_id,
+			dispatch_result.dispatch_result,
+		));
+
+		dispatch_result
+	}
+}
+
+impl<T: Config<I>, I: 'static> From<Error<T, I>> for bp_runtime::BridgeError {
+	fn from(error: Error<T, I>) -> Self {
+		match error {
+			Error::AppAlreadyRegistered => Self::InvalidState,
+			Error::UnknownApp => Self::UnknownEntity,
+			Error::NotAppOwner => Self::NotAllowed,
+			// hidden variant that is never constructed
+			_ => Self::InvalidState,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::mock::{run_test, AppRegistry, Event as TestEvent, Origin, TestRuntime, OWNER};
+	use bp_messages::{
+		target_chain::{DispatchMessageData, UndecodablePayload},
+		LaneId,
+	};
+	use frame_support::{assert_noop, assert_ok};
+	use sp_core::H256;
+	use sp_runtime::DispatchError;
+
+	const TEST_APP_ID: AppId = *b"ts";
+	const TEST_LANE_ID: LaneId = LaneId::Legacy(*b"test");
+	const TEST_HANDLING_WEIGHT: Weight = 100;
+
+	/// Handler that understands `schema_hash(1)` schema and fails to handle empty payloads.
+	struct TestHandler;
+
+	impl AppPayloadHandler<H256> for TestHandler {
+		const APP_ID: AppId = TEST_APP_ID;
+
+		fn schema_hash() -> H256 {
+			schema_hash(1)
+		}
+
+		fn handling_weight(_payload: &[u8]) -> Weight {
+			TEST_HANDLING_WEIGHT
+		}
+
+		fn handle(_message_key: &MessageKey, payload: Vec<u8>) -> bool {
+			!payload.is_empty()
+		}
+	}
+
+	type TestDispatch = AppPayloadDispatch<TestRuntime, (), (TestHandler,)>;
+
+	fn schema_hash(byte: u8) -> H256 {
+		H256::repeat_byte(byte)
+	}
+
+	fn app_info(schema: u8) -> AppInfoOf<TestRuntime> {
+		AppInfo {
+			owner: OWNER,
+			schema_hash: schema_hash(schema),
+		}
+	}
+
+	fn message_key() -> MessageKey {
+		MessageKey {
+			lane_id: TEST_LANE_ID,
+			nonce: 1,
+		}
+	}
+
+	fn message(app_id: AppId, payload: Vec<u8>) -> DispatchMessage<AppPayload, u64> {
+		DispatchMessage {
+			key: message_key(),
+			data: DispatchMessageData {
+				payload: Ok(AppPayload { app_id, payload }),
+				fee: 0,
+			},
+		}
+	}
+
+	fn app_registry_events() -> Vec<Event<TestRuntime>> {
+		frame_system::Pallet::<TestRuntime>::events()
+			.into_iter()
+			.filter_map(|record| match record.event {
+				TestEvent::AppRegistry(event) => Some(event),
+				_ => None,
+			})
+			.collect()
+	}
+
+	#[test]
+	fn app_is_registered_by_registry_origin() {
+		run_test(|| {
+			assert_noop!(
+				AppRegistry::register_app(Origin::signed(OWNER), TEST_APP_ID, app_info(1)),
+				DispatchError::BadOrigin,
+			);
+
+			assert_ok!(AppRegistry::register_app(Origin::root(), TEST_APP_ID, app_info(1)));
+			assert_eq!(AppRegistry::app_info(TEST_APP_ID), Some(app_info(1)));
+			assert_eq!(app_registry_events(), vec![Event::AppRegistered(TEST_APP_ID)]);
+
+			assert_noop!(
+				AppRegistry::register_app(Origin::root(), TEST_APP_ID, app_info(2)),
+				Error::<TestRuntime, ()>::AppAlreadyRegistered,
+			);
+		});
+	}
+
+	#[test]
+	fn schema_is_updated_by_app_owner() {
+		run_test(|| {
+			assert_noop!(
+				AppRegistry::update_schema(Origin::signed(OWNER), TEST_APP_ID, schema_hash(2)),
+				Error::<TestRuntime, ()>::UnknownApp,
+			);
+
+			assert_ok!(AppRegistry::register_app(Origin::root(), TEST_APP_ID, app_info(1)));
+			assert_noop!(
+				AppRegistry::update_schema(Origin::signed(OWNER + 1), TEST_APP_ID, schema_hash(2)),
+				Error::<TestRuntime, ()>::NotAppOwner,
+			);
+
+			assert_ok!(AppRegistry::update_schema(
+				Origin::signed(OWNER),
+				TEST_APP_ID,
+				schema_hash(2)
+			));
+			assert_eq!(AppRegistry::app_info(TEST_APP_ID), Some(app_info(2)));
+		});
+	}
+
+	#[test]
+	fn app_is_deregistered_by_registry_origin() {
+		run_test(|| {
+			assert_ok!(AppRegistry::register_app(Origin::root(), TEST_APP_ID, app_info(1)));
+			assert_noop!(
+				AppRegistry::deregister_app(Origin::signed(OWNER), TEST_APP_ID),
+				DispatchError::BadOrigin,
+			);
+
+			assert_ok!(AppRegistry::deregister_app(Origin::root(), TEST_APP_ID));
+			assert_eq!(AppRegistry::app_info(TEST_APP_ID), None);
+		});
+	}
+
+	#[test]
+	fn payload_is_passed_to_handler_of_registered_app() {
+		run_test(|| {
+			assert_ok!(AppRegistry::register_app(Origin::root(), TEST_APP_ID, app_info(1)));
+
+			let message = message(TEST_APP_ID, vec![42]);
+			assert_eq!(
+				TestDispatch::dispatch_weight(&message),
+				registry_lookup_weight::<TestRuntime>() + TEST_HANDLING_WEIGHT,
+			);
+
+			let dispatch_result = TestDispatch::dispatch(&OWNER, message);
+			assert!(dispatch_result.dispatch_result);
+			assert_eq!(dispatch_result.unspent_weight, 0);
+			assert_eq!(
+				app_registry_events().last(),
+				Some(&Event::PayloadDispatched(message_key(), TEST_APP_ID, true)),
+			);
+
+			assert!(!TestDispatch::dispatch(&OWNER, self::message(TEST_APP_ID, vec![])).dispatch_result);
+		});
+	}
+
+	#[test]
+	fn payload_of_unregistered_app_is_rejected() {
+		run_test(|| {
+			let dispatch_result = TestDispatch::dispatch(&OWNER, message(TEST_APP_ID, vec![42]));
+			assert!(!dispatch_result.dispatch_result);
+			assert_eq!(dispatch_result.unspent_weight, TEST_HANDLING_WEIGHT);
+			assert_eq!(
+				app_registry_events(),
+				vec![Event::PayloadRejected(message_key(), TEST_APP_ID)],
+			);
+		});
+	}
+
+	#[test]
+	fn payload_is_rejected_if_handler_does_not_understand_registered_schema() {
+		run_test(|| {
+			assert_ok!(AppRegistry::register_app(Origin::root(), TEST_APP_ID, app_info(2)));
+			assert!(!TestDispatch::dispatch(&OWNER, message(TEST_APP_ID, vec![42])).dispatch_result);
+		});
+	}
+
+	#[test]
+	fn payload_of_app_without_handler_is_rejected() {
+		run_test(|| {
+			assert_ok!(AppRegistry::register_app(Origin::root(), *b"xx", app_info(1)));
+			let message = message(*b"xx", vec![42]);
+			assert_eq!(
+				TestDispatch::dispatch_weight(&message),
+				registry_lookup_weight::<TestRuntime>()
+			);
+			assert!(!TestDispatch::dispatch(&OWNER, message).dispatch_result);
+		});
+	}
+
+	#[test]
+	fn undecodable_payload_is_rejected() {
+		run_test(|| {
+			let message = DispatchMessage {
+				key: message_key(),
+				data: DispatchMessageData {
+					payload: Err(UndecodablePayload {
+						payload: vec![42],
+						error: codec::Error::from("test"),
+					}),
+					fee: 0u64,
+				},
+			};
+			assert!(!TestDispatch::dispatch(&OWNER, message).dispatch_result);
+			assert!(app_registry_events().is_empty());
+		});
+	}
+}
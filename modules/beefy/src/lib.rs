@@ -0,0 +1,1029 @@
+// Copyright 2021 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Substrate BEEFY Pallet
+//!
+//! This pallet is an on-chain BEEFY light client for Substrate based chains.
+//!
+//! BEEFY validators of the bridged chain are signing commitments to the MMR root of the bridged
+//! chain, which has been finalized by GRANDPA. Commitment signatures are ECDSA signatures, so
+//! verifying the commitment is much cheaper than verifying GRANDPA justification. The pallet
+//! verifies signatures of submitted commitments against the tracked validator set and stores MMR
+//! roots of the last `T::CommitmentsToKeep` imported commitments. These roots may then be used to
+//! verify MMR proofs of the bridged chain headers.
+//!
+//! The next BEEFY validator set is committed to by the MMR leaves of the bridged chain. Anyone
+//! may enact the next validator set using the `change_validator_set` call, by providing the MMR
+//! leaf and its inclusion proof against the MMR root of already imported commitment (i.e. the
+//! root that has been signed by the current validator set).
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use bp_runtime::{BlockNumberOf, Chain, HashOf};
+use codec::{Decode, Encode};
+use frame_support::{ensure, weights::Weight, RuntimeDebug};
+use frame_system::{ensure_signed, RawOrigin};
+use sp_core::{ecdsa, H256};
+use sp_runtime::traits::BadOrigin;
+use sp_std::prelude::*;
+
+pub mod mmr;
+
+#[cfg(test)]
+mod mock;
+
+// Re-export in crate namespace for `construct_runtime!`
+pub use pallet::*;
+
+/// Block number of the bridged chain.
+pub type BridgedBlockNumber<T, I> = BlockNumberOf<<T as Config<I>>::BridgedChain>;
+/// Block hash of the bridged chain.
+pub type BridgedBlockHash<T, I> = HashOf<<T as Config<I>>::BridgedChain>;
+/// Leaf of the bridged chain MMR.
+pub type BridgedMmrLeaf<T, I> = MmrLeaf<BridgedBlockNumber<T, I>, BridgedBlockHash<T, I>>;
+/// Root of the bridged chain MMR.
+pub type MmrRootHash = H256;
+/// Identifier of the BEEFY validator set.
+pub type ValidatorSetId = u64;
+
+/// BEEFY validator set.
+#[derive(Encode, Decode, Clone, Default, RuntimeDebug, PartialEq, Eq)]
+pub struct ValidatorSet {
+	/// Identifier of the validator set.
+	pub id: ValidatorSetId,
+	/// Public keys of validators.
+	pub validators: Vec<ecdsa::Public>,
+}
+
+/// Commitment that is signed by BEEFY validators.
+#[derive(Encode, Decode, Clone, RuntimeDebug, PartialEq, Eq)]
+pub struct Commitment<BlockNumber> {
+	/// Root of the MMR at the `block_number`.
+	pub payload: MmrRootHash,
+	/// Number of the bridged chain block, the commitment is made for.
+	pub block_number: BlockNumber,
+	/// Identifier of the validator set that has signed the commitment.
+	pub validator_set_id: ValidatorSetId,
+}
+
+/// Commitment along with validators signatures.
+#[derive(Encode, Decode, Clone, RuntimeDebug, PartialEq, Eq)]
+pub struct SignedCommitment<BlockNumber> {
+	/// The commitment.
+	pub commitment: Commitment<BlockNumber>,
+	/// Signatures of validators, ordered by validator index. `None` if the validator has not
+	/// signed the commitment.
+	pub signatures: Vec<Option<ecdsa::Signature>>,
+}
+
+/// The next BEEFY validator set, committed to by the MMR leaf.
+#[derive(Encode, Decode, Clone, Default, RuntimeDebug, PartialEq, Eq)]
+pub struct NextValidatorSet {
+	/// Identifier of the next validator set.
+	pub id: ValidatorSetId,
+	/// Number of validators in the next validator set.
+	pub len: u32,
+	/// Merkle root of the next validator set public keys (see `mmr::validators_merkle_root`).
+	pub root: H256,
+}
+
+/// Leaf of the bridged chain MMR.
+#[derive(Encode, Decode, Clone, RuntimeDebug, PartialEq, Eq)]
+pub struct MmrLeaf<BlockNumber, Hash> {
+	/// Version of the leaf format.
+	pub version: u8,
+	/// Number and hash of the parent of the block, the leaf has been added at.
+	pub parent_number_and_hash: (BlockNumber, Hash),
+	/// The next BEEFY validator set.
+	pub beefy_next_authority_set: NextValidatorSet,
+	/// Merkle root of the parachain heads, included in the block.
+	pub parachain_heads: H256,
+}
+
+/// Proof of the leaf inclusion into the bridged chain MMR.
+#[derive(Encode, Decode, Clone, RuntimeDebug, PartialEq, Eq)]
+pub struct MmrLeafProof {
+	/// Index of the proved leaf.
+	pub leaf_index: u64,
+	/// Number of leaves in the MMR.
+	pub leaf_count: u64,
+	/// Proof items (MMR nodes), required to compute the MMR root.
+	pub items: Vec<MmrRootHash>,
+}
+
+/// Data that is used to initialize the pallet.
+#[derive(Encode, Decode, Clone, RuntimeDebug, PartialEq, Eq)]
+pub struct InitializationData<BlockNumber> {
+	/// Number of the bridged chain block, the pallet starts from. Only commitments for later
+	/// blocks are accepted.
+	pub best_block_number: BlockNumber,
+	/// Validator set that is signing commitments after `best_block_number`.
+	pub validator_set: ValidatorSet,
+	/// Should the pallet block transaction immediately after initialization.
+	pub is_halted: bool,
+}
+
+/// Returns number of valid signatures that are required to accept commitment, signed by the
+/// validator set of given size.
+pub fn signatures_required(validators_count: usize) -> usize {
+	validators_count - validators_count.saturating_sub(1) / 3
+}
+
+// comes from #[pallet::event]
+#[allow(clippy::unused_unit)]
+#[frame_support::pallet]
+pub mod pallet {
+	use super::*;
+	use frame_support::pallet_prelude::*;
+	use frame_system::pallet_prelude::*;
+
+	#[pallet::config]
+	pub trait Config<I: 'static = ()>: frame_system::Config {
+		/// The overarching event type.
+		type Event: From<Event<Self, I>> + IsType<<Self as frame_system::Config>::Event>;
+		/// The chain we are bridging to here.
+		type BridgedChain: Chain;
+		/// The upper bound on the number of requests allowed by the pallet.
+		///
+		/// A request refers to an action which writes to storage.
+		///
+		/// Once this bound is reached the pallet will not allow any dispatchables to be called
+		/// until the request count has decreased.
+		#[pallet::constant]
+		type MaxRequests: Get<u32>;
+		/// Maximal number of MMR roots to keep in the storage.
+		#[pallet::constant]
+		type CommitmentsToKeep: Get<u32>;
+		/// Maximal number of validators in the BEEFY validator set.
+		#[pallet::constant]
+		type MaxValidators: Get<u32>;
+		/// Weight of verifying single commitment signature.
+		#[pallet::constant]
+		type SignatureVerificationWeight: Get<Weight>;
+	}
+
+	#[pallet::pallet]
+	pub struct Pallet<T, I = ()>(PhantomData<(T, I)>);
+
+	#[pallet::hooks]
+	impl<T: Config<I>, I: 'static> Hooks<BlockNumberFor<T>> for Pallet<T, I> {
+		fn on_initialize(_n: T::BlockNumber) -> frame_support::weights::Weight {
+			<RequestCount<T, I>>::mutate(|count| *count = count.saturating_sub(1));
+			T::DbWeight::get().reads_writes(1, 1)
+		}
+	}
+
+	#[pallet::call]
+	impl<T: Config<I>, I: 'static> Pallet<T, I> {
+		/// Verify and import the commitment, signed by the current BEEFY validator set.
+		///
+		/// The commitment must be signed by more than 2/3 of validators and must be made for
+		/// the block that is after the best known block.
+		#[pallet::weight(submit_commitment_weight::<T, I>(signed_commitment))]
+		pub fn submit_commitment(
+			origin: OriginFor<T>,
+			signed_commitment: SignedCommitment<BridgedBlockNumber<T, I>>,
+		) -> DispatchResultWithPostInfo {
+			ensure_operational::<T, I>()?;
+			let _ = ensure_signed(origin)?;
+
+			ensure!(
+				Self::request_count() < T::MaxRequests::get(),
+				<Error<T, I>>::TooManyRequests
+			);
+
+			verify_commitment::<T, I>(&signed_commitment)?;
+
+			let Commitment {
+				payload, block_number, ..
+			} = signed_commitment.commitment;
+			<RequestCount<T, I>>::mutate(|count| *count += 1);
+			insert_mmr_root::<T, I>(block_number, payload);
+			Self::deposit_event(Event::CommitmentImported(block_number, payload));
+
+			log::info!(
+				target: "runtime::bridge-beefy",
+				"Succesfully imported commitment for block {:?} with MMR root {:?}",
+				block_number,
+				payload,
+			);
+
+			Ok(().into())
+		}
+
+		/// Bootstrap the pallet with the initial validator set.
+		///
+		/// This function is only allowed to be called from a trusted origin and writes to storage
+		/// with practically no checks in terms of the validity of the data.
+		#[pallet::weight((T::DbWeight::get().reads_writes(1, 4), DispatchClass::Operational))]
+		pub fn initialize(
+			origin: OriginFor<T>,
+			init_data: InitializationData<BridgedBlockNumber<T, I>>,
+		) -> DispatchResultWithPostInfo {
+			ensure_owner_or_root::<T, I>(origin)?;
+
+			let init_allowed = !<BestBlockNumber<T, I>>::exists();
+			ensure!(init_allowed, <Error<T, I>>::AlreadyInitialized);
+			ensure_valid_validator_set::<T, I>(&init_data.validator_set)?;
+			initialize_bridge::<T, I>(init_data.clone());
+
+			log::info!(
+				target: "runtime::bridge-beefy",
+				"Pallet has been initialized with the following parameters: {:?}",
+				init_data
+			);
+
+			Ok(().into())
+		}
+
+		/// Enact the next BEEFY validator set.
+		///
+		/// The next validator set must be committed to by the `mmr_leaf`, which must be included
+		/// into the MMR with the root, imported from the commitment for the `block_number`. The
+		/// identifier of the new set must be the next identifier after the current set identifier.
+		#[pallet::weight(T::DbWeight::get().reads_writes(4, 1))]
+		pub fn change_validator_set(
+			origin: OriginFor<T>,
+			block_number: BridgedBlockNumber<T, I>,
+			mmr_leaf: BridgedMmrLeaf<T, I>,
+			mmr_proof: MmrLeafProof,
+			validator_set: ValidatorSet,
+		) -> DispatchResultWithPostInfo {
+			ensure_operational::<T, I>()?;
+			let _ = ensure_signed(origin)?;
+			ensure!(<BestBlockNumber<T, I>>::exists(), <Error<T, I>>::NotInitialized);
+			ensure!(
+				validator_set.id == <CurrentValidatorSet<T, I>>::get().id + 1,
+				<Error<T, I>>::InvalidValidatorSetId
+			);
+			ensure_valid_validator_set::<T, I>(&validator_set)?;
+			verify_next_validator_set::<T, I>(block_number, &mmr_leaf, &mmr_proof, &validator_set)?;
+
+			let validator_set_id = validator_set.id;
+			<CurrentValidatorSet<T, I>>::put(validator_set);
+			Self::deposit_event(Event::ValidatorSetChanged(validator_set_id));
+
+			log::info!(
+				target: "runtime::bridge-beefy",
+				"Enacted BEEFY validator set {}",
+				validator_set_id,
+			);
+
+			Ok(().into())
+		}
+
+		/// Propose new `PalletOwner`, or remove the current owner.
+		///
+		/// The proposed owner only becomes `PalletOwner` after calling `accept_owner`. The owner
+		/// is removed immediately, because root is still able to manage the pallet.
+		///
+		/// May only be called either by root, or by `PalletOwner`.
+		#[pallet::weight((T::DbWeight::get().reads_writes(1, 2), DispatchClass::Operational))]
+		pub fn propose_owner(origin: OriginFor<T>, new_owner: Option<T::AccountId>) -> DispatchResultWithPostInfo {
+			ensure_owner_or_root::<T, I>(origin)?;
+			match new_owner {
+				Some(new_owner) => {
+					PendingPalletOwner::<T, I>::put(&new_owner);
+					log::info!(target: "runtime::bridge-beefy", "Proposing pallet Owner: {:?}", new_owner);
+					Self::deposit_event(Event::OwnerProposed(new_owner));
+				}
+				None => {
+					PalletOwner::<T, I>::kill();
+					PendingPalletOwner::<T, I>::kill();
+					log::info!(target: "runtime::bridge-beefy", "Removed Owner of pallet.");
+					Self::deposit_event(Event::OwnerChanged(None));
+				}
+			}
+
+			Ok(().into())
+		}
+
+		/// Accept the pallet ownership, proposed by the `propose_owner` call.
+		///
+		/// May only be called by the proposed owner.
+		#[pallet::weight((T::DbWeight::get().reads_writes(1, 2), DispatchClass::Operational))]
+		pub fn accept_owner(origin: OriginFor<T>) -> DispatchResultWithPostInfo {
+			let new_owner = ensure_signed(origin)?;
+			ensure!(
+				PendingPalletOwner::<T, I>::get().as_ref() == Some(&new_owner),
+				<Error<T, I>>::NotPendingOwner,
+			);
+
+			PalletOwner::<T, I>::put(&new_owner);
+			PendingPalletOwner::<T, I>::kill();
+			log::info!(target: "runtime::bridge-beefy", "Setting pallet Owner to: {:?}", new_owner);
+			Self::deposit_event(Event::OwnerChanged(Some(new_owner)));
+
+			Ok(().into())
+		}
+
+		/// Halt or resume all pallet operations.
+		///
+		/// May only be called either by root, or by `PalletOwner`.
+		#[pallet::weight((T::DbWeight::get().reads_writes(1, 1), DispatchClass::Operational))]
+		pub fn set_operational(origin: OriginFor<T>, operational: bool) -> DispatchResultWithPostInfo {
+			ensure_owner_or_root::<T, I>(origin)?;
+			<IsHalted<T, I>>::put(!operational);
+
+			if operational {
+				log::info!(target: "runtime::bridge-beefy", "Resuming pallet operations.");
+			} else {
+				log::warn!(target: "runtime::bridge-beefy", "Stopping pallet operations.");
+			}
+
+			Ok(().into())
+		}
+	}
+
+	#[pallet::event]
+	#[pallet::metadata(BridgedBlockNumber<T, I> = "BridgedBlockNumber", T::AccountId = "AccountId")]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config<I>, I: 'static = ()> {
+		/// Commitment has been imported. \[block_number, mmr_root\]
+		CommitmentImported(BridgedBlockNumber<T, I>, MmrRootHash),
+		/// New BEEFY validator set has been enacted. \[validator_set_id\]
+		ValidatorSetChanged(ValidatorSetId),
+		/// New pallet owner has been proposed. \[proposed_owner\]
+		OwnerProposed(T::AccountId),
+		/// Pallet owner has been changed or removed. \[new_owner\]
+		OwnerChanged(Option<T::AccountId>),
+	}
+
+	/// The current number of requests which have written to storage.
+	///
+	/// If the `RequestCount` hits `MaxRequests`, no more calls will be allowed to the pallet until
+	/// the request capacity is increased.
+	///
+	/// The `RequestCount` is decreased by one at the beginning of every block. This is to ensure
+	/// that the pallet can always make progress.
+	#[pallet::storage]
+	#[pallet::getter(fn request_count)]
+	pub(super) type RequestCount<T: Config<I>, I: 'static = ()> = StorageValue<_, u32, ValueQuery>;
+
+	/// Number of the best block, known to the pallet.
+	#[pallet::storage]
+	pub(super) type BestBlockNumber<T: Config<I>, I: 'static = ()> = StorageValue<_, BridgedBlockNumber<T, I>>;
+
+	/// The current BEEFY validator set.
+	#[pallet::storage]
+	pub(super) type CurrentValidatorSet<T: Config<I>, I: 'static = ()> = StorageValue<_, ValidatorSet, ValueQuery>;
+
+	/// MMR roots from imported commitments, mapped by the bridged chain block number.
+	#[pallet::storage]
+	pub(super) type ImportedMmrRoots<T: Config<I>, I: 'static = ()> =
+		StorageMap<_, Identity, BridgedBlockNumber<T, I>, MmrRootHash>;
+
+	/// A ring buffer of block numbers of imported commitments. Ordered by the insertion time.
+	#[pallet::storage]
+	pub(super) type ImportedBlockNumbers<T: Config<I>, I: 'static = ()> =
+		StorageMap<_, Identity, u32, BridgedBlockNumber<T, I>>;
+
+	/// Current ring buffer position.
+	#[pallet::storage]
+	pub(super) type ImportedBlockNumbersPointer<T: Config<I>, I: 'static = ()> = StorageValue<_, u32, ValueQuery>;
+
+	/// Optional pallet owner.
+	///
+	/// Pallet owner has a right to halt all pallet operations and then resume them.
+	#[pallet::storage]
+	pub(super) type PalletOwner<T: Config<I>, I: 'static = ()> = StorageValue<_, T::AccountId, OptionQuery>;
+
+	/// Account that has been proposed as the new pallet owner, but hasn't accepted the
+	/// ownership yet.
+	#[pallet::storage]
+	pub(super) type PendingPalletOwner<T: Config<I>, I: 'static = ()> = StorageValue<_, T::AccountId, OptionQuery>;
+
+	/// If true, all pallet transactions are failed immediately.
+	#[pallet::storage]
+	pub(super) type IsHalted<T: Config<I>, I: 'static = ()> = StorageValue<_, bool, ValueQuery>;
+
+	#[pallet::genesis_config]
+	pub struct GenesisConfig<T: Config<I>, I: 'static = ()> {
+		/// Optional module owner account.
+		pub owner: Option<T::AccountId>,
+		/// Optional module initialization data.
+		pub init_data: Option<InitializationData<BridgedBlockNumber<T, I>>>,
+	}
+
+	#[cfg(feature = "std")]
+	impl<T: Config<I>, I: 'static> Default for GenesisConfig<T, I> {
+		fn default() -> Self {
+			Self {
+				owner: None,
+				init_data: None,
+			}
+		}
+	}
+
+	#[pallet::genesis_build]
+	impl<T: Config<I>, I: 'static> GenesisBuild<T, I> for GenesisConfig<T, I> {
+		fn build(&self) {
+			if let Some(ref owner) = self.owner {
+				<PalletOwner<T, I>>::put(owner);
+			}
+
+			if let Some(init_data) = self.init_data.clone() {
+				initialize_bridge::<T, I>(init_data);
+			} else {
+				// Since the bridge hasn't been initialized we shouldn't allow anyone to perform
+				// transactions.
+				<IsHalted<T, I>>::put(true);
+			}
+		}
+	}
+
+	#[pallet::error]
+	pub enum Error<T, I = ()> {
+		/// There are too many requests for the current window to handle.
+		TooManyRequests,
+		/// The pallet is not yet initialized.
+		NotInitialized,
+		/// The pallet has already been initialized.
+		AlreadyInitialized,
+		/// All pallet operations are halted.
+		Halted,
+		/// The commitment is made for the block that is not after the best known block.
+		OldCommitment,
+		/// The commitment is signed by the validator set, other than the current set.
+		InvalidValidatorSetId,
+		/// The number of signatures doesn't match the number of validators.
+		InvalidSignaturesCount,
+		/// One of the commitment signatures is invalid.
+		InvalidSignature,
+		/// The commitment is signed by less than required number of validators.
+		NotEnoughSignatures,
+		/// The validator set is empty or has too many validators.
+		InvalidValidatorSet,
+		/// The caller has not been proposed as the new pallet owner.
+		NotPendingOwner,
+		/// There's no imported MMR root for the given block.
+		UnknownMmrRoot,
+		/// The MMR leaf is not included into the MMR with the imported root.
+		InvalidMmrLeafProof,
+		/// The validator set doesn't match the next validator set from the MMR leaf.
+		InvalidNextValidatorSet,
+	}
+
+	/// Returns weight of the `submit_commitment` call with given commitment.
+	pub(crate) fn submit_commitment_weight<T: Config<I>, I: 'static>(
+		signed_commitment: &SignedCommitment<BridgedBlockNumber<T, I>>,
+	) -> Weight {
+		let signatures = signed_commitment.signatures.iter().filter(|s| s.is_some()).count() as Weight;
+		T::DbWeight::get()
+			.reads_writes(5, 5)
+			.saturating_add(T::SignatureVerificationWeight::get().saturating_mul(signatures))
+	}
+
+	/// Verify that the commitment is signed by the current validator set and may be imported.
+	pub(crate) fn verify_commitment<T: Config<I>, I: 'static>(
+		signed_commitment: &SignedCommitment<BridgedBlockNumber<T, I>>,
+	) -> Result<(), sp_runtime::DispatchError> {
+		let commitment = &signed_commitment.commitment;
+		let best_block_number = <BestBlockNumber<T, I>>::get().ok_or(<Error<T, I>>::NotInitialized)?;
+		ensure!(
+			commitment.block_number > best_block_number,
+			<Error<T, I>>::OldCommitment
+		);
+
+		let validator_set = <CurrentValidatorSet<T, I>>::get();
+		ensure!(
+			commitment.validator_set_id == validator_set.id,
+			<Error<T, I>>::InvalidValidatorSetId
+		);
+		ensure!(
+			signed_commitment.signatures.len() == validator_set.validators.len(),
+			<Error<T, I>>::InvalidSignaturesCount
+		);
+
+		let commitment_hash = sp_io::hashing::keccak_256(&commitment.encode());
+		let mut valid_signatures = 0;
+		for (signature, validator) in signed_commitment.signatures.iter().zip(validator_set.validators.iter()) {
+			if let Some(signature) = signature {
+				ensure!(
+					verify_signature(signature, &commitment_hash, validator),
+					<Error<T, I>>::InvalidSignature
+				);
+				valid_signatures += 1;
+			}
+		}
+
+		ensure!(
+			valid_signatures >= signatures_required(validator_set.validators.len()),
+			<Error<T, I>>::NotEnoughSignatures
+		);
+
+		Ok(())
+	}
+
+	/// Verify that the validator set is the next validator set, committed to by the MMR leaf, and
+	/// that the leaf is included into the MMR with imported root.
+	pub(crate) fn verify_next_validator_set<T: Config<I>, I: 'static>(
+		block_number: BridgedBlockNumber<T, I>,
+		mmr_leaf: &BridgedMmrLeaf<T, I>,
+		mmr_proof: &MmrLeafProof,
+		validator_set: &ValidatorSet,
+	) -> Result<(), sp_runtime::DispatchError> {
+		let mmr_root = <ImportedMmrRoots<T, I>>::get(block_number).ok_or(<Error<T, I>>::UnknownMmrRoot)?;
+		let leaf_hash = sp_io::hashing::keccak_256(&mmr_leaf.encode()).into();
+		ensure!(
+			mmr::verify_leaf_proof(mmr_root, leaf_hash, mmr_proof),
+			<Error<T, I>>::InvalidMmrLeafProof
+		);
+
+		let next_validator_set = &mmr_leaf.beefy_next_authority_set;
+		ensure!(
+			next_validator_set.id == validator_set.id
+				&& next_validator_set.len as usize == validator_set.validators.len()
+				&& next_validator_set.root == mmr::validators_merkle_root(&validator_set.validators),
+			<Error<T, I>>::InvalidNextValidatorSet
+		);
+
+		Ok(())
+	}
+
+	/// Verify ECDSA signature of the commitment hash.
+	pub(crate) fn verify_signature(
+		signature: &ecdsa::Signature,
+		commitment_hash: &[u8; 32],
+		validator: &ecdsa::Public,
+	) -> bool {
+		sp_io::crypto::secp256k1_ecdsa_recover_compressed(&signature.0, commitment_hash)
+			.map(|recovered| recovered[..] == validator.0[..])
+			.unwrap_or(false)
+	}
+
+	/// Import MMR root of the verified commitment, pruning the oldest root if required.
+	pub(crate) fn insert_mmr_root<T: Config<I>, I: 'static>(
+		block_number: BridgedBlockNumber<T, I>,
+		mmr_root: MmrRootHash,
+	) {
+		let index = <ImportedBlockNumbersPointer<T, I>>::get();
+		let pruning = <ImportedBlockNumbers<T, I>>::try_get(index);
+		<BestBlockNumber<T, I>>::put(block_number);
+		<ImportedMmrRoots<T, I>>::insert(block_number, mmr_root);
+		<ImportedBlockNumbers<T, I>>::insert(index, block_number);
+
+		// Update ring buffer pointer and remove old MMR root.
+		<ImportedBlockNumbersPointer<T, I>>::put((index + 1) % T::CommitmentsToKeep::get());
+		if let Ok(block_number) = pruning {
+			log::debug!(target: "runtime::bridge-beefy", "Pruning old MMR root of block: {:?}.", block_number);
+			<ImportedMmrRoots<T, I>>::remove(block_number);
+		}
+	}
+
+	/// Ensure that the validator set may be used by the pallet.
+	pub(crate) fn ensure_valid_validator_set<T: Config<I>, I: 'static>(
+		validator_set: &ValidatorSet,
+	) -> Result<(), sp_runtime::DispatchError> {
+		ensure!(
+			!validator_set.validators.is_empty() && validator_set.validators.len() <= T::MaxValidators::get() as usize,
+			<Error<T, I>>::InvalidValidatorSet
+		);
+
+		Ok(())
+	}
+
+	/// Since this writes to storage with no real checks this should only be used in functions that
+	/// were called by a trusted origin.
+	pub(crate) fn initialize_bridge<T: Config<I>, I: 'static>(
+		init_params: InitializationData<BridgedBlockNumber<T, I>>,
+	) {
+		let InitializationData {
+			best_block_number,
+			validator_set,
+			is_halted,
+		} = init_params;
+
+		<BestBlockNumber<T, I>>::put(best_block_number);
+		<CurrentValidatorSet<T, I>>::put(validator_set);
+		<ImportedBlockNumbersPointer<T, I>>::put(0);
+		<IsHalted<T, I>>::put(is_halted);
+	}
+
+	/// Ensure that the origin is either root, or `PalletOwner`.
+	fn ensure_owner_or_root<T: Config<I>, I: 'static>(origin: T::Origin) -> Result<(), BadOrigin> {
+		match origin.into() {
+			Ok(RawOrigin::Root) => Ok(()),
+			Ok(RawOrigin::Signed(ref signer)) if Some(signer) == <PalletOwner<T, I>>::get().as_ref() => Ok(()),
+			_ => Err(BadOrigin),
+		}
+	}
+
+	/// Ensure that the pallet is in operational mode (not halted).
+	fn ensure_operational<T: Config<I>, I: 'static>() -> Result<(), Error<T, I>> {
+		if <IsHalted<T, I>>::get() {
+			Err(<Error<T, I>>::Halted)
+		} else {
+			Ok(())
+		}
+	}
+}
+
+impl<T: Config<I>, I: 'static> Pallet<T, I> {
+	/// Returns number of the best block, known to the pallet.
+	pub fn best_block_number() -> Option<BridgedBlockNumber<T, I>> {
+		<BestBlockNumber<T, I>>::get()
+	}
+
+	/// Returns MMR root from the imported commitment for given block, if it is still in the
+	/// storage.
+	pub fn mmr_root(block_number: BridgedBlockNumber<T, I>) -> Option<MmrRootHash> {
+		<ImportedMmrRoots<T, I>>::get(block_number)
+	}
+
+	/// Returns the current BEEFY validator set.
+	pub fn validator_set() -> ValidatorSet {
+		<CurrentValidatorSet<T, I>>::get()
+	}
+
+	/// Returns true if all pallet operations are halted.
+	pub fn is_halted() -> bool {
+		<IsHalted<T, I>>::get()
+	}
+}
+
+impl<T: Config<I>, I: 'static> From<Error<T, I>> for bp_runtime::BridgeError {
+	fn from(error: Error<T, I>) -> Self {
+		match error {
+			Error::TooManyRequests => Self::TooManyRequests,
+			Error::NotInitialized => Self::NotInitialized,
+			Error::AlreadyInitialized => Self::AlreadyInitialized,
+			Error::Halted => Self::Halted,
+			Error::OldCommitment => Self::ObsoleteHeader,
+			Error::InvalidValidatorSetId => Self::InvalidFinalityProof,
+			Error::InvalidSignaturesCount => Self::InvalidFinalityProof,
+			Error::InvalidSignature => Self::InvalidFinalityProof,
+			Error::NotEnoughSignatures => Self::InvalidFinalityProof,
+			Error::InvalidValidatorSet => Self::InvalidAuthoritySet,
+			Error::NotPendingOwner => Self::NotAllowed,
+			Error::UnknownMmrRoot => Self::UnknownHeader,
+			Error::InvalidMmrLeafProof => Self::InvalidFinalityProof,
+			Error::InvalidNextValidatorSet => Self::InvalidAuthoritySet,
+			// hidden variant that is never constructed
+			_ => Self::InvalidState,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::mock::{run_test, sign_commitment, validator_set, Origin, TestNumber, TestRuntime};
+	use frame_support::{assert_noop, assert_ok};
+	use sp_runtime::DispatchError;
+
+	fn initialize_bridge() {
+		assert_ok!(Pallet::<TestRuntime>::initialize(
+			Origin::root(),
+			InitializationData {
+				best_block_number: 0,
+				validator_set: validator_set(0, 4),
+				is_halted: false,
+			},
+		));
+	}
+
+	fn commitment(block_number: TestNumber) -> Commitment<TestNumber> {
+		Commitment {
+			payload: MmrRootHash::repeat_byte(block_number as u8),
+			block_number,
+			validator_set_id: 0,
+		}
+	}
+
+	fn submit_commitment(block_number: TestNumber) -> frame_support::dispatch::DispatchResultWithPostInfo {
+		Pallet::<TestRuntime>::submit_commitment(Origin::signed(1), sign_commitment(commitment(block_number), 4))
+	}
+
+	fn mmr_leaf(next_validator_set: &ValidatorSet) -> BridgedMmrLeaf<TestRuntime, ()> {
+		MmrLeaf {
+			version: 0,
+			parent_number_and_hash: (0, Default::default()),
+			beefy_next_authority_set: NextValidatorSet {
+				id: next_validator_set.id,
+				len: next_validator_set.validators.len() as u32,
+				root: mmr::validators_merkle_root(&next_validator_set.validators),
+			},
+			parachain_heads: Default::default(),
+		}
+	}
+
+	/// Import commitment to the MMR that only has the given leaf.
+	fn import_mmr_leaf(block_number: TestNumber, leaf: &BridgedMmrLeaf<TestRuntime, ()>) {
+		let mut commitment = commitment(block_number);
+		commitment.payload = sp_io::hashing::keccak_256(&leaf.encode()).into();
+		assert_ok!(Pallet::<TestRuntime>::submit_commitment(
+			Origin::signed(1),
+			sign_commitment(commitment, 4),
+		));
+	}
+
+	fn single_leaf_proof() -> MmrLeafProof {
+		MmrLeafProof {
+			leaf_index: 0,
+			leaf_count: 1,
+			items: vec![],
+		}
+	}
+
+	fn next_block() {
+		use frame_support::traits::OnInitialize;
+
+		let current_number = frame_system::Pallet::<TestRuntime>::block_number();
+		frame_system::Pallet::<TestRuntime>::set_block_number(current_number + 1);
+		let _ = Pallet::<TestRuntime>::on_initialize(current_number);
+	}
+
+	#[test]
+	fn signatures_required_is_more_than_two_thirds() {
+		assert_eq!(signatures_required(1), 1);
+		assert_eq!(signatures_required(3), 3);
+		assert_eq!(signatures_required(4), 3);
+		assert_eq!(signatures_required(7), 5);
+		assert_eq!(signatures_required(100), 67);
+	}
+
+	#[test]
+	fn only_root_or_owner_may_initialize_pallet() {
+		run_test(|| {
+			let init_data = InitializationData {
+				best_block_number: 0,
+				validator_set: validator_set(0, 4),
+				is_halted: false,
+			};
+			assert_noop!(
+				Pallet::<TestRuntime>::initialize(Origin::signed(1), init_data.clone()),
+				DispatchError::BadOrigin,
+			);
+			assert_ok!(Pallet::<TestRuntime>::initialize(Origin::root(), init_data.clone()));
+			assert_noop!(
+				Pallet::<TestRuntime>::initialize(Origin::root(), init_data),
+				Error::<TestRuntime>::AlreadyInitialized,
+			);
+		})
+	}
+
+	#[test]
+	fn pallet_rejects_empty_validator_set() {
+		run_test(|| {
+			assert_noop!(
+				Pallet::<TestRuntime>::initialize(
+					Origin::root(),
+					InitializationData {
+						best_block_number: 0,
+						validator_set: validator_set(0, 0),
+						is_halted: false,
+					},
+				),
+				Error::<TestRuntime>::InvalidValidatorSet,
+			);
+		})
+	}
+
+	#[test]
+	fn pallet_rejects_commitments_if_not_initialized() {
+		run_test(|| {
+			assert_noop!(submit_commitment(1), Error::<TestRuntime>::NotInitialized);
+		})
+	}
+
+	#[test]
+	fn pallet_imports_commitment_signed_by_current_validator_set() {
+		run_test(|| {
+			initialize_bridge();
+
+			assert_ok!(submit_commitment(1));
+			assert_eq!(Pallet::<TestRuntime>::best_block_number(), Some(1));
+			assert_eq!(Pallet::<TestRuntime>::mmr_root(1), Some(MmrRootHash::repeat_byte(1)));
+		})
+	}
+
+	#[test]
+	fn pallet_imports_commitment_with_missing_minority_signatures() {
+		run_test(|| {
+			initialize_bridge();
+
+			let mut signed_commitment = sign_commitment(commitment(1), 4);
+			signed_commitment.signatures[3] = None;
+			assert_ok!(Pallet::<TestRuntime>::submit_commitment(
+				Origin::signed(1),
+				signed_commitment.clone(),
+			));
+
+			let mut signed_commitment = sign_commitment(commitment(2), 4);
+			signed_commitment.signatures[2] = None;
+			signed_commitment.signatures[3] = None;
+			assert_noop!(
+				Pallet::<TestRuntime>::submit_commitment(Origin::signed(1), signed_commitment),
+				Error::<TestRuntime>::NotEnoughSignatures,
+			);
+		})
+	}
+
+	#[test]
+	fn pallet_rejects_invalid_commitments() {
+		run_test(|| {
+			initialize_bridge();
+			assert_ok!(submit_commitment(2));
+
+			// commitment for old block
+			assert_noop!(submit_commitment(2), Error::<TestRuntime>::OldCommitment);
+
+			// commitment signed by other validator set
+			let mut commitment = commitment(3);
+			commitment.validator_set_id = 1;
+			assert_noop!(
+				Pallet::<TestRuntime>::submit_commitment(Origin::signed(1), sign_commitment(commitment, 4)),
+				Error::<TestRuntime>::InvalidValidatorSetId,
+			);
+
+			// signature of other commitment
+			let mut signed_commitment = sign_commitment(self::commitment(3), 4);
+			signed_commitment.signatures[0] = sign_commitment(self::commitment(4), 4).signatures[0].clone();
+			assert_noop!(
+				Pallet::<TestRuntime>::submit_commitment(Origin::signed(1), signed_commitment),
+				Error::<TestRuntime>::InvalidSignature,
+			);
+
+			// too few signatures
+			let mut signed_commitment = sign_commitment(self::commitment(3), 4);
+			signed_commitment.signatures.pop();
+			assert_noop!(
+				Pallet::<TestRuntime>::submit_commitment(Origin::signed(1), signed_commitment),
+				Error::<TestRuntime>::InvalidSignaturesCount,
+			);
+		})
+	}
+
+	#[test]
+	fn pallet_rejects_commitments_if_halted() {
+		run_test(|| {
+			initialize_bridge();
+			assert_ok!(Pallet::<TestRuntime>::set_operational(Origin::root(), false));
+			assert_noop!(submit_commitment(1), Error::<TestRuntime>::Halted);
+
+			assert_ok!(Pallet::<TestRuntime>::set_operational(Origin::root(), true));
+			assert_ok!(submit_commitment(1));
+		})
+	}
+
+	#[test]
+	fn commitments_are_rate_limited() {
+		run_test(|| {
+			initialize_bridge();
+
+			assert_ok!(submit_commitment(1));
+			assert_ok!(submit_commitment(2));
+			assert_noop!(submit_commitment(3), Error::<TestRuntime>::TooManyRequests);
+
+			next_block();
+			assert_ok!(submit_commitment(3));
+		})
+	}
+
+	#[test]
+	fn old_mmr_roots_are_pruned() {
+		run_test(|| {
+			initialize_bridge();
+
+			for block_number in 1..=4 {
+				assert_ok!(submit_commitment(block_number));
+				next_block();
+				next_block();
+			}
+
+			assert_eq!(Pallet::<TestRuntime>::mmr_root(1), None);
+			assert!((2..=4).all(|block_number| Pallet::<TestRuntime>::mmr_root(block_number).is_some()));
+		})
+	}
+
+	#[test]
+	fn validator_set_is_changed_using_mmr_leaf() {
+		run_test(|| {
+			initialize_bridge();
+			let next_validator_set = validator_set(1, 7);
+			let leaf = mmr_leaf(&next_validator_set);
+			import_mmr_leaf(1, &leaf);
+
+			assert_ok!(Pallet::<TestRuntime>::change_validator_set(
+				Origin::signed(2),
+				1,
+				leaf,
+				single_leaf_proof(),
+				next_validator_set,
+			));
+			assert_eq!(Pallet::<TestRuntime>::validator_set(), validator_set(1, 7));
+
+			// commitments of the previous set are rejected
+			assert_noop!(submit_commitment(2), Error::<TestRuntime>::InvalidValidatorSetId);
+
+			let mut commitment = commitment(2);
+			commitment.validator_set_id = 1;
+			assert_ok!(Pallet::<TestRuntime>::submit_commitment(
+				Origin::signed(1),
+				sign_commitment(commitment, 7),
+			));
+		})
+	}
+
+	#[test]
+	fn validator_set_change_is_rejected_if_not_committed_to_by_imported_mmr_leaf() {
+		run_test(|| {
+			initialize_bridge();
+			let next_validator_set = validator_set(1, 7);
+			let leaf = mmr_leaf(&next_validator_set);
+			import_mmr_leaf(1, &leaf);
+
+			let change_validator_set = |block_number, leaf, proof, validator_set| {
+				Pallet::<TestRuntime>::change_validator_set(Origin::signed(2), block_number, leaf, proof, validator_set)
+			};
+
+			// no MMR root for the block
+			assert_noop!(
+				change_validator_set(2, leaf.clone(), single_leaf_proof(), next_validator_set.clone()),
+				Error::<TestRuntime>::UnknownMmrRoot,
+			);
+
+			// leaf is not in the MMR
+			let mut other_leaf = leaf.clone();
+			other_leaf.version = 1;
+			assert_noop!(
+				change_validator_set(1, other_leaf, single_leaf_proof(), next_validator_set.clone()),
+				Error::<TestRuntime>::InvalidMmrLeafProof,
+			);
+
+			// invalid proof
+			let mut invalid_proof = single_leaf_proof();
+			invalid_proof.items.push(Default::default());
+			assert_noop!(
+				change_validator_set(1, leaf.clone(), invalid_proof, next_validator_set.clone()),
+				Error::<TestRuntime>::InvalidMmrLeafProof,
+			);
+
+			// validator set is not the set from the leaf
+			assert_noop!(
+				change_validator_set(1, leaf.clone(), single_leaf_proof(), validator_set(1, 6)),
+				Error::<TestRuntime>::InvalidNextValidatorSet,
+			);
+
+			// validator set with wrong identifier
+			assert_noop!(
+				change_validator_set(1, leaf, single_leaf_proof(), validator_set(2, 7)),
+				Error::<TestRuntime>::InvalidValidatorSetId,
+			);
+		})
+	}
+
+	#[test]
+	fn validator_set_may_not_be_changed_by_root() {
+		run_test(|| {
+			initialize_bridge();
+			let next_validator_set = validator_set(1, 7);
+			let leaf = mmr_leaf(&next_validator_set);
+			import_mmr_leaf(1, &leaf);
+
+			assert_noop!(
+				Pallet::<TestRuntime>::change_validator_set(
+					Origin::root(),
+					1,
+					leaf,
+					single_leaf_proof(),
+					next_validator_set,
+				),
+				DispatchError::BadOrigin,
+			);
+		})
+	}
+
+	#[test]
+	fn ownership_may_only_be_accepted_by_proposed_owner() {
+		run_test(|| {
+			assert_ok!(Pallet::<TestRuntime>::propose_owner(Origin::root(), Some(1)));
+			assert_noop!(
+				Pallet::<TestRuntime>::accept_owner(Origin::signed(2)),
+				Error::<TestRuntime>::NotPendingOwner,
+			);
+			assert_eq!(PalletOwner::<TestRuntime>::get(), None);
+
+			assert_ok!(Pallet::<TestRuntime>::accept_owner(Origin::signed(1)));
+			assert_eq!(PalletOwner::<TestRuntime>::get(), Some(1));
+			assert_eq!(PendingPalletOwner::<TestRuntime>::get(), None);
+
+			assert_ok!(Pallet::<TestRuntime>::propose_owner(Origin::signed(1), None));
+			assert_eq!(PalletOwner::<TestRuntime>::get(), None);
+		})
+	}
+}
@@ -0,0 +1,330 @@
+// Copyright 2021 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Verification of the bridged chain MMR leaf proofs and validator set merkle roots.
+//!
+//! The MMR layout (node positions, peaks and proof items order) matches the layout of the
+//! `ckb-merkle-mountain-range` crate, which is used by the MMR pallet of the bridged chain.
+//! All nodes are merged using the Keccak-256 hash function.
+
+use crate::{MmrLeafProof, MmrRootHash};
+
+use sp_core::{ecdsa, H256};
+use sp_io::hashing::keccak_256;
+use sp_std::prelude::*;
+
+/// Maximal number of items in the valid MMR leaf proof.
+///
+/// The proof contains at most one item for every level of the tallest mountain and one item for
+/// every peak, and there are at most 64 levels and 64 peaks in the MMR with `u64` positions.
+pub const MAX_MMR_PROOF_ITEMS: usize = 128;
+
+/// Maximal number of leaves in the MMR, supported by the verifier.
+///
+/// It is much larger than number of blocks any chain will ever have and it guarantees that node
+/// positions computations never overflow.
+pub const MAX_MMR_LEAVES: u64 = 1 << 48;
+
+/// Returns merkle root of the given validators public keys.
+///
+/// Leaves of the tree are Keccak-256 hashes of the validators public keys. If there's an odd
+/// number of nodes at some level, the last node is promoted to the next level.
+pub fn validators_merkle_root(validators: &[ecdsa::Public]) -> H256 {
+	let mut nodes = validators
+		.iter()
+		.map(|validator| keccak_256(&validator.0[..]))
+		.collect::<Vec<_>>();
+	while nodes.len() > 1 {
+		nodes = nodes
+			.chunks(2)
+			.map(|pair| match pair {
+				[left, right] => merge(left, right),
+				[single] => *single,
+				_ => unreachable!("chunks(2) never yields more than 2 items; qed"),
+			})
+			.collect();
+	}
+
+	nodes.pop().map(H256::from).unwrap_or_default()
+}
+
+/// Returns true if the leaf with given hash is included in the MMR with given root.
+pub fn verify_leaf_proof(root: MmrRootHash, leaf_hash: H256, proof: &MmrLeafProof) -> bool {
+	if proof.leaf_index >= proof.leaf_count
+		|| proof.leaf_count > MAX_MMR_LEAVES
+		|| proof.items.len() > MAX_MMR_PROOF_ITEMS
+	{
+		return false;
+	}
+
+	let mmr_size = match leaf_index_to_mmr_size(proof.leaf_count - 1) {
+		Some(mmr_size) => mmr_size,
+		None => return false,
+	};
+	let leaf_position = match leaf_index_to_mmr_size(proof.leaf_index) {
+		Some(mmr_size) => mmr_size - u64::from((proof.leaf_index + 1).trailing_zeros()) - 1,
+		None => return false,
+	};
+
+	let mut items = proof.items.iter().map(|item| item.to_fixed_bytes());
+	let mut peaks = Vec::new();
+	let mut leaf_peak_found = false;
+	for peak_position in get_peaks(mmr_size) {
+		if leaf_peak_found {
+			// all peaks to the right of the leaf peak are bagged into single proof item
+			match items.next() {
+				Some(rhs_peaks) => peaks.push(rhs_peaks),
+				None => return false,
+			}
+			break;
+		} else if leaf_position <= peak_position {
+			leaf_peak_found = true;
+			match calculate_peak_root(leaf_hash.to_fixed_bytes(), leaf_position, peak_position, &mut items) {
+				Some(peak) => peaks.push(peak),
+				None => return false,
+			}
+		} else {
+			match items.next() {
+				Some(peak) => peaks.push(peak),
+				None => return false,
+			}
+		}
+	}
+
+	// all proof items must be used
+	if !leaf_peak_found || items.next().is_some() {
+		return false;
+	}
+
+	// bag peaks, starting from the rightmost one
+	while peaks.len() > 1 {
+		let right_peak = peaks.pop().expect("peaks.len() > 1; qed");
+		let left_peak = peaks.pop().expect("peaks.len() > 1; qed");
+		peaks.push(merge(&right_peak, &left_peak));
+	}
+
+	peaks.pop().map(H256::from) == Some(root)
+}
+
+/// Compute root of the mountain with given peak, using leaf hash and the proof items.
+fn calculate_peak_root(
+	leaf_hash: [u8; 32],
+	leaf_position: u64,
+	peak_position: u64,
+	items: &mut impl Iterator<Item = [u8; 32]>,
+) -> Option<[u8; 32]> {
+	let mut hash = leaf_hash;
+	let mut position = leaf_position;
+	let mut height = 0;
+	while position < peak_position {
+		let sibling = items.next()?;
+		if position_height(position + 1) > height {
+			// we're the right child
+			hash = merge(&sibling, &hash);
+			position += 1;
+		} else {
+			// we're the left child
+			hash = merge(&hash, &sibling);
+			position += parent_offset(height);
+		}
+		height += 1;
+	}
+
+	if position == peak_position {
+		Some(hash)
+	} else {
+		None
+	}
+}
+
+/// Returns positions of all MMR peaks, ordered from left to right.
+fn get_peaks(mmr_size: u64) -> Vec<u64> {
+	let (mut height, mut position) = left_peak_height_position(mmr_size);
+	let mut peaks = vec![position];
+	while height > 0 {
+		// move to the right sibling and then down, until we find the position within the MMR
+		position += sibling_offset(height);
+		while position > mmr_size - 1 {
+			if height == 0 {
+				return peaks;
+			}
+			position -= parent_offset(height - 1);
+			height -= 1;
+		}
+		peaks.push(position);
+	}
+
+	peaks
+}
+
+/// Returns height and position of the leftmost (and the tallest) MMR peak.
+fn left_peak_height_position(mmr_size: u64) -> (u32, u64) {
+	let left_position = |height: u32| (1u64 << (height + 1)) - 2;
+	let mut height = 1;
+	let mut previous_position = 0;
+	let mut position = left_position(height);
+	while position < mmr_size {
+		height += 1;
+		previous_position = position;
+		position = left_position(height);
+	}
+	(height - 1, previous_position)
+}
+
+/// Returns height of the node at given position.
+fn position_height(position: u64) -> u32 {
+	let bit_length = |number: u64| 64 - number.leading_zeros();
+	let all_ones = |number: u64| number != 0 && number.count_zeros() == number.leading_zeros();
+
+	let mut position = position + 1;
+	while !all_ones(position) {
+		position -= (1 << (bit_length(position) - 1)) - 1;
+	}
+	bit_length(position) - 1
+}
+
+/// Returns size of the MMR (number of nodes), when the leaf with given index is the last leaf.
+fn leaf_index_to_mmr_size(leaf_index: u64) -> Option<u64> {
+	let leaves_count = leaf_index.checked_add(1)?;
+	leaves_count
+		.checked_mul(2)
+		.map(|size| size - u64::from(leaves_count.count_ones()))
+}
+
+/// Returns offset of the sibling of the node at given height.
+fn sibling_offset(height: u32) -> u64 {
+	(2 << height) - 1
+}
+
+/// Returns offset of the parent of the left node at given height.
+fn parent_offset(height: u32) -> u64 {
+	2 << height
+}
+
+/// Merge two MMR (or merkle tree) nodes.
+fn merge(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+	let mut concat = [0u8; 64];
+	concat[..32].copy_from_slice(left);
+	concat[32..].copy_from_slice(right);
+	keccak_256(&concat)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn leaf(index: u8) -> [u8; 32] {
+		keccak_256(&[index])
+	}
+
+	fn proof(leaf_index: u64, leaf_count: u64, items: Vec<[u8; 32]>) -> MmrLeafProof {
+		MmrLeafProof {
+			leaf_index,
+			leaf_count,
+			items: items.into_iter().map(H256::from).collect(),
+		}
+	}
+
+	#[test]
+	fn peaks_are_computed_correctly() {
+		assert_eq!(get_peaks(1), vec![0]);
+		assert_eq!(get_peaks(3), vec![2]);
+		assert_eq!(get_peaks(4), vec![2, 3]);
+		assert_eq!(get_peaks(7), vec![6]);
+		assert_eq!(get_peaks(8), vec![6, 7]);
+		assert_eq!(get_peaks(10), vec![6, 9]);
+		assert_eq!(get_peaks(11), vec![6, 9, 10]);
+	}
+
+	#[test]
+	fn single_leaf_proof_is_verified() {
+		let root = H256::from(leaf(0));
+		assert!(verify_leaf_proof(root, leaf(0).into(), &proof(0, 1, vec![])));
+		assert!(!verify_leaf_proof(root, leaf(1).into(), &proof(0, 1, vec![])));
+		assert!(!verify_leaf_proof(root, leaf(0).into(), &proof(1, 1, vec![])));
+		assert!(!verify_leaf_proof(root, leaf(0).into(), &proof(0, 1, vec![leaf(1)])));
+	}
+
+	#[test]
+	fn leaf_proofs_are_verified() {
+		// MMR with 3 leaves has two peaks: (leaf0 + leaf1) at position 2 and leaf2 at position 3
+		let peak01 = merge(&leaf(0), &leaf(1));
+		let root = H256::from(merge(&leaf(2), &peak01));
+
+		assert!(verify_leaf_proof(
+			root,
+			leaf(0).into(),
+			&proof(0, 3, vec![leaf(1), leaf(2)])
+		));
+		assert!(verify_leaf_proof(
+			root,
+			leaf(1).into(),
+			&proof(1, 3, vec![leaf(0), leaf(2)])
+		));
+		assert!(verify_leaf_proof(root, leaf(2).into(), &proof(2, 3, vec![peak01])));
+
+		// wrong leaf index
+		assert!(!verify_leaf_proof(
+			root,
+			leaf(0).into(),
+			&proof(1, 3, vec![leaf(1), leaf(2)])
+		));
+		// missing proof items
+		assert!(!verify_leaf_proof(root, leaf(0).into(), &proof(0, 3, vec![leaf(1)])));
+		// wrong proof items
+		assert!(!verify_leaf_proof(root, leaf(2).into(), &proof(2, 3, vec![leaf(0)])));
+	}
+
+	#[test]
+	fn leaf_proof_with_bagged_rhs_peaks_is_verified() {
+		// MMR with 7 leaves has three peaks: at positions 6 (leaves 0..=3), 9 (leaves 4..=5) and 10 (leaf 6)
+		let peak01 = merge(&leaf(0), &leaf(1));
+		let peak23 = merge(&leaf(2), &leaf(3));
+		let peak0123 = merge(&peak01, &peak23);
+		let peak45 = merge(&leaf(4), &leaf(5));
+		let rhs_peaks = merge(&leaf(6), &peak45);
+		let root = H256::from(merge(&rhs_peaks, &peak0123));
+
+		assert!(verify_leaf_proof(
+			root,
+			leaf(2).into(),
+			&proof(2, 7, vec![leaf(3), peak01, rhs_peaks]),
+		));
+		assert!(verify_leaf_proof(
+			root,
+			leaf(5).into(),
+			&proof(5, 7, vec![peak0123, leaf(4), leaf(6)])
+		));
+	}
+
+	#[test]
+	fn validators_merkle_root_promotes_odd_nodes() {
+		let validators = (1..=3u8)
+			.map(|index| ecdsa::Public::from_raw([index; 33]))
+			.collect::<Vec<_>>();
+		let hashes = validators
+			.iter()
+			.map(|validator| keccak_256(&validator.0[..]))
+			.collect::<Vec<_>>();
+
+		assert_eq!(validators_merkle_root(&[]), H256::default());
+		assert_eq!(validators_merkle_root(&validators[..1]), H256::from(hashes[0]));
+		assert_eq!(
+			validators_merkle_root(&validators),
+			H256::from(merge(&merge(&hashes[0], &hashes[1]), &hashes[2])),
+		);
+	}
+}
@@ -0,0 +1,146 @@
+// Copyright 2021 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+// From construct_runtime macro
+#![allow(clippy::from_over_into)]
+
+use crate as beefy;
+use crate::{Commitment, SignedCommitment, ValidatorSet, ValidatorSetId};
+
+use bp_runtime::Chain;
+use codec::Encode;
+use frame_support::{construct_runtime, parameter_types, weights::Weight};
+use sp_core::ecdsa;
+use sp_runtime::{
+	testing::{Header, H256},
+	traits::{BlakeTwo256, IdentityLookup},
+	Perbill,
+};
+
+pub type AccountId = u64;
+pub type TestNumber = crate::BridgedBlockNumber<TestRuntime, ()>;
+
+type Block = frame_system::mocking::MockBlock<TestRuntime>;
+type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<TestRuntime>;
+
+construct_runtime! {
+	pub enum TestRuntime where
+		Block = Block,
+		NodeBlock = Block,
+		UncheckedExtrinsic = UncheckedExtrinsic,
+	{
+		System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
+		Beefy: beefy::{Pallet, Event<T>},
+	}
+}
+
+parameter_types! {
+	pub const BlockHashCount: u64 = 250;
+	pub const MaximumBlockWeight: Weight = 1024;
+	pub const MaximumBlockLength: u32 = 2 * 1024;
+	pub const AvailableBlockRatio: Perbill = Perbill::one();
+}
+
+impl frame_system::Config for TestRuntime {
+	type Origin = Origin;
+	type Index = u64;
+	type Call = Call;
+	type BlockNumber = u64;
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	type AccountId = AccountId;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = Header;
+	type Event = Event;
+	type BlockHashCount = BlockHashCount;
+	type Version = ();
+	type PalletInfo = PalletInfo;
+	type AccountData = ();
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+	type BaseCallFilter = ();
+	type SystemWeightInfo = ();
+	type DbWeight = ();
+	type BlockWeights = ();
+	type BlockLength = ();
+	type SS58Prefix = ();
+	type OnSetCode = ();
+}
+
+parameter_types! {
+	pub const MaxRequests: u32 = 2;
+	pub const CommitmentsToKeep: u32 = 3;
+	pub const MaxValidators: u32 = 16;
+	pub const SignatureVerificationWeight: Weight = 1;
+}
+
+impl beefy::Config for TestRuntime {
+	type Event = Event;
+	type BridgedChain = TestBridgedChain;
+	type MaxRequests = MaxRequests;
+	type CommitmentsToKeep = CommitmentsToKeep;
+	type MaxValidators = MaxValidators;
+	type SignatureVerificationWeight = SignatureVerificationWeight;
+}
+
+#[derive(Debug)]
+pub struct TestBridgedChain;
+
+impl Chain for TestBridgedChain {
+	type BlockNumber = <TestRuntime as frame_system::Config>::BlockNumber;
+	type Hash = <TestRuntime as frame_system::Config>::Hash;
+	type Hasher = <TestRuntime as frame_system::Config>::Hashing;
+	type Header = <TestRuntime as frame_system::Config>::Header;
+}
+
+pub fn run_test<T>(test: impl FnOnce() -> T) -> T {
+	sp_io::TestExternalities::new(Default::default()).execute_with(test)
+}
+
+/// Secret key of the validator with given index.
+fn validator_secret(index: usize) -> secp256k1::SecretKey {
+	secp256k1::SecretKey::parse(&[index as u8 + 1; 32]).expect("non-zero key is valid; qed")
+}
+
+/// Returns validator set with given id and given number of validators.
+pub fn validator_set(id: ValidatorSetId, validators_count: usize) -> ValidatorSet {
+	ValidatorSet {
+		id,
+		validators: (0..validators_count)
+			.map(|index| {
+				let public = secp256k1::PublicKey::from_secret_key(&validator_secret(index));
+				ecdsa::Public::from_raw(public.serialize_compressed())
+			})
+			.collect(),
+	}
+}
+
+/// Sign commitment by all validators of the set with given number of validators.
+pub fn sign_commitment(commitment: Commitment<TestNumber>, validators_count: usize) -> SignedCommitment<TestNumber> {
+	let commitment_hash = sp_io::hashing::keccak_256(&commitment.encode());
+	let message = secp256k1::Message::parse(&commitment_hash);
+	let signatures = (0..validators_count)
+		.map(|index| {
+			let (signature, recovery_id) = secp256k1::sign(&message, &validator_secret(index));
+			let mut raw_signature = [0u8; 65];
+			raw_signature[..64].copy_from_slice(&signature.serialize());
+			raw_signature[64] = recovery_id.serialize();
+			Some(ecdsa::Signature::from_raw(raw_signature))
+		})
+		.collect();
+
+	SignedCommitment { commitment, signatures }
+}
@@ -0,0 +1,897 @@
+// Copyright 2019-2021 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Runtime module that reassembles large calls, delivered in several chunks, and dispatches them.
+//!
+//! The size of bridge message is limited by the maximal size of the delivery transaction at the
+//! target chain. So calls with large arguments (e.g. large remarks or runtime code) can't be sent
+//! in a single message. Instead, the sender may split the encoded call into chunks and send every
+//! chunk in a separate message, dispatching the `submit_chunk` call of this pallet.
+//!
+//! All chunks must be submitted by the same account, in order. Once the last chunk is received,
+//! the pallet verifies that the reassembled call matches the declared call hash, decodes it and
+//! dispatches it using the origin of the account that has submitted chunks. The weight of the
+//! reassembled call is declared upfront and must be paid by the submitter of the last chunk.
+//!
+//! Every stored chunk requires the submitter to reserve `ChunkDeposit`. The deposit is returned
+//! when the call is dispatched, cancelled or removed after it has expired. The number of calls
+//! that may be reassembled at the same time for single account is limited by the
+//! `MaxPendingCallsPerAccount`.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::{Decode, Encode};
+use frame_support::{
+	dispatch::{Dispatchable, PostDispatchInfo},
+	traits::{Currency, Filter, ReservableCurrency},
+	weights::{extract_actual_weight, GetDispatchInfo, Pays, Weight},
+	RuntimeDebug,
+};
+use frame_system::RawOrigin;
+use sp_runtime::traits::{Hash, Saturating, Zero};
+use sp_std::prelude::*;
+
+#[cfg(test)]
+mod mock;
+
+/// Balance type used by the pallet.
+pub type BalanceOf<T, I> = <<T as Config<I>>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+
+/// The call that is being reassembled.
+#[derive(Encode, Decode, Clone, RuntimeDebug, PartialEq, Eq)]
+pub struct PendingCall<BlockNumber, Balance> {
+	/// Number of chunks the call has been split into.
+	pub chunks_count: u32,
+	/// Number of chunks that have been received so far.
+	pub received_chunks: u32,
+	/// Declared weight of the reassembled call.
+	pub call_weight: Weight,
+	/// Encoded call bytes that have been received so far.
+	pub encoded_call: Vec<u8>,
+	/// Deposit that is reserved for received chunks.
+	pub deposit: Balance,
+	/// Block after which the call may be removed from the storage by anyone.
+	pub expires_at: BlockNumber,
+}
+
+/// Pending call type used by the pallet.
+pub type PendingCallOf<T, I> = PendingCall<<T as frame_system::Config>::BlockNumber, BalanceOf<T, I>>;
+
+pub use pallet::*;
+
+// comes from #[pallet::event]
+#[allow(clippy::unused_unit)]
+#[frame_support::pallet]
+pub mod pallet {
+	use super::*;
+	use frame_support::pallet_prelude::*;
+	use frame_system::pallet_prelude::*;
+
+	#[pallet::config]
+	pub trait Config<I: 'static = ()>: frame_system::Config {
+		/// The overarching event type.
+		type Event: From<Event<Self, I>> + IsType<<Self as frame_system::Config>::Event>;
+		/// The overarching dispatch call type.
+		type Call: Parameter
+			+ GetDispatchInfo
+			+ Dispatchable<Origin = <Self as frame_system::Config>::Origin, PostInfo = PostDispatchInfo>;
+		/// Pre-dispatch filter for reassembled calls.
+		///
+		/// If this filter rejects the call, special event (`Event::CallRejected`) is emitted.
+		type CallFilter: Filter<<Self as Config<I>>::Call>;
+		/// Maximal number of chunks that the call may be split into.
+		#[pallet::constant]
+		type MaxChunks: Get<u32>;
+		/// Maximal size of single chunk.
+		#[pallet::constant]
+		type MaxChunkSize: Get<u32>;
+		/// Maximal number of calls that may be reassembled at the same time for single account.
+		#[pallet::constant]
+		type MaxPendingCallsPerAccount: Get<u32>;
+		/// Number of blocks after the last received chunk, after which the pending call may be
+		/// removed from the storage by anyone.
+		#[pallet::constant]
+		type PendingCallLifetime: Get<Self::BlockNumber>;
+		/// Deposit that is reserved for every stored chunk.
+		#[pallet::constant]
+		type ChunkDeposit: Get<BalanceOf<Self, I>>;
+		/// Weight of reading and writing single byte of the pending call.
+		#[pallet::constant]
+		type ByteWeight: Get<Weight>;
+		/// The currency used to reserve chunk deposits.
+		type Currency: ReservableCurrency<Self::AccountId>;
+	}
+
+	#[pallet::pallet]
+	#[pallet::generate_store(pub(super) trait Store)]
+	pub struct Pallet<T, I = ()>(PhantomData<(T, I)>);
+
+	#[pallet::hooks]
+	impl<T: Config<I>, I: 'static> Hooks<BlockNumberFor<T>> for Pallet<T, I> {}
+
+	#[pallet::call]
+	impl<T: Config<I>, I: 'static> Pallet<T, I> {
+		/// Submit chunk of the encoded call.
+		///
+		/// Method arguments are:
+		///
+		/// - `call_hash` - hash of the whole encoded call;
+		/// - `chunks_count` - the number of chunks the call has been split into;
+		/// - `chunk_index` - index of this chunk. Chunks must be submitted in order, starting from `0`;
+		/// - `chunk` - the chunk of the encoded call;
+		/// - `call_weight` - declared weight of the reassembled call.
+		///
+		/// The `call_hash`, `chunks_count` and `call_weight` must be the same for all chunks of the call.
+		/// When the last chunk is submitted, the call is reassembled and dispatched. Any problems with
+		/// the reassembled call are reported using events and the pending call is removed from the storage.
+		///
+		/// The `ChunkDeposit` is reserved for every chunk that is stored until the call is reassembled.
+		#[pallet::weight(submit_chunk_weight::<T, I>(
+			*chunks_count,
+			*chunk_index,
+			stored_call_size::<T, I>(*chunk_index, chunk.len()),
+			*call_weight,
+		))]
+		pub fn submit_chunk(
+			origin: OriginFor<T>,
+			call_hash: T::Hash,
+			chunks_count: u32,
+			chunk_index: u32,
+			chunk: Vec<u8>,
+			call_weight: Weight,
+		) -> DispatchResultWithPostInfo {
+			let submitter = ensure_signed(origin)?;
+
+			ensure!(
+				chunks_count != 0 && chunks_count <= T::MaxChunks::get(),
+				Error::<T, I>::InvalidChunksCount,
+			);
+			ensure!(
+				chunk.len() <= T::MaxChunkSize::get() as usize,
+				Error::<T, I>::ChunkTooLarge,
+			);
+
+			let mut pending_call = match PendingCalls::<T, I>::get(&submitter, call_hash) {
+				Some(pending_call) => {
+					ensure!(
+						pending_call.received_chunks == chunk_index
+							&& pending_call.chunks_count == chunks_count
+							&& pending_call.call_weight == call_weight,
+						Error::<T, I>::UnexpectedChunk,
+					);
+					pending_call
+				}
+				None => {
+					ensure!(chunk_index == 0, Error::<T, I>::UnexpectedChunk);
+					if chunks_count > 1 {
+						ensure!(
+							PendingCallsCount::<T, I>::get(&submitter) < T::MaxPendingCallsPerAccount::get(),
+							Error::<T, I>::TooManyPendingCalls,
+						);
+					}
+					PendingCall {
+						chunks_count,
+						received_chunks: 0,
+						call_weight,
+						encoded_call: Vec::new(),
+						deposit: Zero::zero(),
+						expires_at: Zero::zero(),
+					}
+				}
+			};
+
+			pending_call.received_chunks += 1;
+			pending_call.encoded_call.extend(chunk);
+
+			if pending_call.received_chunks != pending_call.chunks_count {
+				let chunk_deposit = T::ChunkDeposit::get();
+				T::Currency::reserve(&submitter, chunk_deposit).map_err(|_| Error::<T, I>::FailedToReserveDeposit)?;
+				pending_call.deposit = pending_call.deposit.saturating_add(chunk_deposit);
+				pending_call.expires_at =
+					frame_system::Pallet::<T>::block_number().saturating_add(T::PendingCallLifetime::get());
+				if chunk_index == 0 {
+					PendingCallsCount::<T, I>::mutate(&submitter, |count| *count += 1);
+				}
+
+				log::trace!(
+					target: "runtime::bridge-chunked-dispatch",
+					"Received chunk {} of {} of call {:?} from {:?}",
+					chunk_index + 1,
+					chunks_count,
+					call_hash,
+					submitter,
+				);
+
+				PendingCalls::<T, I>::insert(&submitter, call_hash, pending_call);
+				Self::deposit_event(Event::ChunkReceived(submitter, call_hash, chunk_index));
+				return Ok(().into());
+			}
+
+			let stored_call_size = pending_call.encoded_call.len();
+			Self::remove_pending_call(&submitter, call_hash, &pending_call);
+			let actual_call_weight = Self::dispatch_reassembled_call(submitter, call_hash, pending_call);
+
+			Ok(PostDispatchInfo {
+				actual_weight: Some(submit_chunk_weight::<T, I>(1, 0, stored_call_size, actual_call_weight)),
+				pays_fee: Pays::Yes,
+			})
+		}
+
+		/// Cancel reassembly of the call and remove all received chunks from the storage.
+		#[pallet::weight(remove_pending_call_weight::<T>())]
+		pub fn cancel_call(origin: OriginFor<T>, call_hash: T::Hash) -> DispatchResultWithPostInfo {
+			let submitter = ensure_signed(origin)?;
+			let pending_call = PendingCalls::<T, I>::get(&submitter, call_hash).ok_or(Error::<T, I>::UnknownCall)?;
+
+			Self::remove_pending_call(&submitter, call_hash, &pending_call);
+			Self::deposit_event(Event::CallCancelled(submitter, call_hash));
+
+			Ok(().into())
+		}
+
+		/// Remove expired pending call from the storage.
+		///
+		/// The call may be removed by anyone once `PendingCallLifetime` blocks have passed since
+		/// its last chunk has been received. The deposit is returned to the submitter.
+		#[pallet::weight(remove_pending_call_weight::<T>())]
+		pub fn remove_expired_call(
+			origin: OriginFor<T>,
+			submitter: T::AccountId,
+			call_hash: T::Hash,
+		) -> DispatchResultWithPostInfo {
+			ensure_signed(origin)?;
+			let pending_call = PendingCalls::<T, I>::get(&submitter, call_hash).ok_or(Error::<T, I>::UnknownCall)?;
+			ensure!(
+				frame_system::Pallet::<T>::block_number() > pending_call.expires_at,
+				Error::<T, I>::CallNotExpired,
+			);
+
+			Self::remove_pending_call(&submitter, call_hash, &pending_call);
+			Self::deposit_event(Event::CallExpired(submitter, call_hash));
+
+			Ok(().into())
+		}
+	}
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	#[pallet::metadata(T::AccountId = "AccountId", T::Hash = "Hash")]
+	pub enum Event<T: Config<I>, I: 'static = ()> {
+		/// Chunk of the call has been received. The last argument is the index of the chunk.
+		ChunkReceived(T::AccountId, T::Hash, u32),
+		/// Hash of the reassembled call doesn't match the declared hash.
+		CallHashMismatch(T::AccountId, T::Hash),
+		/// We have failed to decode reassembled call.
+		CallDecodeFailed(T::AccountId, T::Hash),
+		/// The reassembled call has been rejected by the call filter.
+		CallRejected(T::AccountId, T::Hash),
+		/// The weight of reassembled call is larger than declared weight.
+		/// Last two arguments are: expected and declared call weight.
+		CallWeightMismatch(T::AccountId, T::Hash, Weight, Weight),
+		/// The reassembled call has been dispatched with given result.
+		CallDispatched(T::AccountId, T::Hash, DispatchResult),
+		/// Reassembly of the call has been cancelled.
+		CallCancelled(T::AccountId, T::Hash),
+		/// Expired pending call has been removed from the storage.
+		CallExpired(T::AccountId, T::Hash),
+	}
+
+	#[pallet::error]
+	pub enum Error<T, I = ()> {
+		/// The call is split into zero or too many chunks.
+		InvalidChunksCount,
+		/// The chunk is too large.
+		ChunkTooLarge,
+		/// The chunk doesn't match the call that is being reassembled. Either chunk is submitted
+		/// out of order, or the call parameters (number of chunks or call weight) are different.
+		UnexpectedChunk,
+		/// There's no pending call with given hash.
+		UnknownCall,
+		/// The account already has maximal number of pending calls.
+		TooManyPendingCalls,
+		/// Failed to reserve deposit for the chunk.
+		FailedToReserveDeposit,
+		/// The pending call has not expired yet.
+		CallNotExpired,
+	}
+
+	/// Calls that are being reassembled, mapped by the submitter account and the call hash.
+	#[pallet::storage]
+	pub type PendingCalls<T: Config<I>, I: 'static = ()> =
+		StorageDoubleMap<_, Blake2_128Concat, T::AccountId, Identity, T::Hash, PendingCallOf<T, I>>;
+
+	/// Number of calls that are being reassembled, mapped by the submitter account.
+	#[pallet::storage]
+	pub type PendingCallsCount<T: Config<I>, I: 'static = ()> =
+		StorageMap<_, Blake2_128Concat, T::AccountId, u32, ValueQuery>;
+
+	impl<T: Config<I>, I: 'static> Pallet<T, I> {
+		/// Remove pending call from the storage and return its deposit to the submitter.
+		fn remove_pending_call(submitter: &T::AccountId, call_hash: T::Hash, pending_call: &PendingCallOf<T, I>) {
+			if pending_call.chunks_count > 1 {
+				PendingCallsCount::<T, I>::mutate_exists(submitter, |count| {
+					*count = count.map(|count| count.saturating_sub(1)).filter(|count| *count != 0);
+				});
+			}
+			PendingCalls::<T, I>::remove(submitter, call_hash);
+			T::Currency::unreserve(submitter, pending_call.deposit);
+		}
+
+		/// Verify and dispatch the reassembled call. Returns actual weight of the call.
+		fn dispatch_reassembled_call(
+			submitter: T::AccountId,
+			call_hash: T::Hash,
+			pending_call: PendingCallOf<T, I>,
+		) -> Weight {
+			if T::Hashing::hash(&pending_call.encoded_call) != call_hash {
+				log::trace!(
+					target: "runtime::bridge-chunked-dispatch",
+					"Reassembled call from {:?} doesn't match declared hash {:?}",
+					submitter,
+					call_hash,
+				);
+				Self::deposit_event(Event::CallHashMismatch(submitter, call_hash));
+				return 0;
+			}
+
+			let call = match <T as Config<I>>::Call::decode(&mut &pending_call.encoded_call[..]) {
+				Ok(call) => call,
+				Err(_) => {
+					log::trace!(
+						target: "runtime::bridge-chunked-dispatch",
+						"Failed to decode reassembled call {:?} from {:?}",
+						call_hash,
+						submitter,
+					);
+					Self::deposit_event(Event::CallDecodeFailed(submitter, call_hash));
+					return 0;
+				}
+			};
+
+			if !T::CallFilter::filter(&call) {
+				log::trace!(
+					target: "runtime::bridge-chunked-dispatch",
+					"Reassembled call {:?} from {:?} is rejected by filter",
+					call_hash,
+					submitter,
+				);
+				Self::deposit_event(Event::CallRejected(submitter, call_hash));
+				return 0;
+			}
+
+			let dispatch_info = call.get_dispatch_info();
+			if dispatch_info.weight > pending_call.call_weight {
+				log::trace!(
+					target: "runtime::bridge-chunked-dispatch",
+					"Reassembled call {:?} from {:?}: declared weight is too low. Expected at least {}, got {}",
+					call_hash,
+					submitter,
+					dispatch_info.weight,
+					pending_call.call_weight,
+				);
+				Self::deposit_event(Event::CallWeightMismatch(
+					submitter,
+					call_hash,
+					dispatch_info.weight,
+					pending_call.call_weight,
+				));
+				return 0;
+			}
+
+			let result = call.dispatch(RawOrigin::Signed(submitter.clone()).into());
+			let actual_call_weight = extract_actual_weight(&result, &dispatch_info);
+
+			log::trace!(
+				target: "runtime::bridge-chunked-dispatch",
+				"Reassembled call {:?} from {:?} has been dispatched. Weight: {} of {}. Result: {:?}",
+				call_hash,
+				submitter,
+				actual_call_weight,
+				pending_call.call_weight,
+				result,
+			);
+
+			Self::deposit_event(Event::CallDispatched(
+				submitter,
+				call_hash,
+				result.map(drop).map_err(|e| e.error),
+			));
+
+			actual_call_weight
+		}
+	}
+}
+
+/// Maximal size of the pending call after chunk with given index and size is received.
+fn stored_call_size<T: Config<I>, I: 'static>(chunk_index: u32, chunk_size: usize) -> usize {
+	(chunk_index as usize)
+		.saturating_mul(T::MaxChunkSize::get() as usize)
+		.saturating_add(chunk_size)
+}
+
+/// Weight of the `submit_chunk` call.
+///
+/// Every chunk pays for reading and writing the whole pending call, the pending calls counter and
+/// the submitter account. The last chunk also pays for the dispatch of the reassembled call.
+fn submit_chunk_weight<T: Config<I>, I: 'static>(
+	chunks_count: u32,
+	chunk_index: u32,
+	stored_call_size: usize,
+	call_weight: Weight,
+) -> Weight {
+	let storage_weight = T::DbWeight::get()
+		.reads_writes(3, 3)
+		.saturating_add(T::ByteWeight::get().saturating_mul(stored_call_size as Weight));
+	if chunk_index.saturating_add(1) == chunks_count {
+		storage_weight.saturating_add(call_weight)
+	} else {
+		storage_weight
+	}
+}
+
+/// Weight of removing the pending call from the storage.
+fn remove_pending_call_weight<T: frame_system::Config>() -> Weight {
+	T::DbWeight::get().reads_writes(3, 3)
+}
+
+impl<T: Config<I>, I: 'static> From<Error<T, I>> for bp_runtime::BridgeError {
+	fn from(error: Error<T, I>) -> Self {
+		match error {
+			Error::InvalidChunksCount => Self::InvalidArgument,
+			Error::ChunkTooLarge => Self::LimitExceeded,
+			Error::UnexpectedChunk => Self::InvalidState,
+			Error::UnknownCall => Self::UnknownEntity,
+			Error::TooManyPendingCalls => Self::LimitExceeded,
+			Error::FailedToReserveDeposit => Self::PaymentFailed,
+			Error::CallNotExpired => Self::InvalidState,
+			// hidden variant that is never constructed
+			_ => Self::InvalidState,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::mock::{
+		run_test, Balances, Call as TestCall, ChunkedDispatch, Event as TestEvent, Origin, TestRuntime, CALL_WEIGHT,
+		CHUNK_DEPOSIT, MAX_CHUNKS, MAX_CHUNK_SIZE, MAX_PENDING_CALLS_PER_ACCOUNT, PENDING_CALL_LIFETIME,
+		POOR_SUBMITTER, SUBMITTER, SUBMITTER_BALANCE,
+	};
+	use frame_support::{assert_noop, assert_ok};
+	use frame_system::{EventRecord, Phase};
+	use sp_core::H256;
+	use sp_runtime::traits::BlakeTwo256;
+
+	fn remark_call(size: usize) -> TestCall {
+		TestCall::System(frame_system::Call::remark(vec![42; size]))
+	}
+
+	fn submit_call_in_chunks(call: &TestCall, chunk_size: usize) -> H256 {
+		let encoded_call = call.encode();
+		let call_hash = BlakeTwo256::hash(&encoded_call);
+		let chunks = encoded_call.chunks(chunk_size).collect::<Vec<_>>();
+		for (chunk_index, chunk) in chunks.iter().enumerate() {
+			assert_ok!(ChunkedDispatch::submit_chunk(
+				Origin::signed(SUBMITTER),
+				call_hash,
+				chunks.len() as u32,
+				chunk_index as u32,
+				chunk.to_vec(),
+				CALL_WEIGHT,
+			));
+		}
+		call_hash
+	}
+
+	fn last_event() -> TestEvent {
+		frame_system::Pallet::<TestRuntime>::events()
+			.pop()
+			.map(|EventRecord { event, .. }| event)
+			.expect("at least one event is expected")
+	}
+
+	#[test]
+	fn call_is_dispatched_when_all_chunks_are_received() {
+		run_test(|| {
+			let call_hash = submit_call_in_chunks(&remark_call(200), 64);
+
+			assert_eq!(
+				frame_system::Pallet::<TestRuntime>::events()
+					.into_iter()
+					.filter(|EventRecord { event, .. }| matches!(
+						event,
+						TestEvent::ChunkedDispatch(Event::ChunkReceived(..))
+					))
+					.count(),
+				3,
+			);
+			assert_eq!(
+				last_event(),
+				TestEvent::ChunkedDispatch(Event::CallDispatched(SUBMITTER, call_hash, Ok(()))),
+			);
+			assert!(!PendingCalls::<TestRuntime>::contains_key(SUBMITTER, call_hash));
+		});
+	}
+
+	#[test]
+	fn call_split_into_single_chunk_is_dispatched() {
+		run_test(|| {
+			let call_hash = submit_call_in_chunks(&remark_call(16), MAX_CHUNK_SIZE as usize);
+
+			assert_eq!(
+				frame_system::Pallet::<TestRuntime>::events(),
+				vec![EventRecord {
+					phase: Phase::Initialization,
+					event: TestEvent::ChunkedDispatch(Event::CallDispatched(SUBMITTER, call_hash, Ok(()))),
+					topics: vec![],
+				}],
+			);
+		});
+	}
+
+	#[test]
+	fn chunk_is_rejected_if_chunks_count_is_invalid() {
+		run_test(|| {
+			assert_noop!(
+				ChunkedDispatch::submit_chunk(Origin::signed(SUBMITTER), Default::default(), 0, 0, vec![], 0),
+				Error::<TestRuntime, ()>::InvalidChunksCount,
+			);
+			assert_noop!(
+				ChunkedDispatch::submit_chunk(
+					Origin::signed(SUBMITTER),
+					Default::default(),
+					MAX_CHUNKS + 1,
+					0,
+					vec![],
+					0
+				),
+				Error::<TestRuntime, ()>::InvalidChunksCount,
+			);
+		});
+	}
+
+	#[test]
+	fn chunk_is_rejected_if_it_is_too_large() {
+		run_test(|| {
+			assert_noop!(
+				ChunkedDispatch::submit_chunk(
+					Origin::signed(SUBMITTER),
+					Default::default(),
+					2,
+					0,
+					vec![0; MAX_CHUNK_SIZE as usize + 1],
+					0
+				),
+				Error::<TestRuntime, ()>::ChunkTooLarge,
+			);
+		});
+	}
+
+	#[test]
+	fn chunk_is_rejected_if_submitted_out_of_order() {
+		run_test(|| {
+			assert_noop!(
+				ChunkedDispatch::submit_chunk(Origin::signed(SUBMITTER), Default::default(), 2, 1, vec![0], 0),
+				Error::<TestRuntime, ()>::UnexpectedChunk,
+			);
+
+			assert_ok!(ChunkedDispatch::submit_chunk(
+				Origin::signed(SUBMITTER),
+				Default::default(),
+				3,
+				0,
+				vec![0],
+				0
+			));
+			assert_noop!(
+				ChunkedDispatch::submit_chunk(Origin::signed(SUBMITTER), Default::default(), 3, 2, vec![0], 0),
+				Error::<TestRuntime, ()>::UnexpectedChunk,
+			);
+			assert_noop!(
+				ChunkedDispatch::submit_chunk(Origin::signed(SUBMITTER), Default::default(), 2, 1, vec![0], 0),
+				Error::<TestRuntime, ()>::UnexpectedChunk,
+			);
+		});
+	}
+
+	#[test]
+	fn chunks_of_different_submitters_are_not_mixed() {
+		run_test(|| {
+			assert_ok!(ChunkedDispatch::submit_chunk(
+				Origin::signed(SUBMITTER),
+				Default::default(),
+				2,
+				0,
+				vec![0],
+				0
+			));
+			assert_noop!(
+				ChunkedDispatch::submit_chunk(Origin::signed(SUBMITTER + 1), Default::default(), 2, 1, vec![0], 0),
+				Error::<TestRuntime, ()>::UnexpectedChunk,
+			);
+		});
+	}
+
+	#[test]
+	fn call_is_not_dispatched_if_hash_mismatches() {
+		run_test(|| {
+			let call_hash = Default::default();
+			assert_ok!(ChunkedDispatch::submit_chunk(
+				Origin::signed(SUBMITTER),
+				call_hash,
+				1,
+				0,
+				remark_call(16).encode(),
+				CALL_WEIGHT,
+			));
+
+			assert_eq!(
+				last_event(),
+				TestEvent::ChunkedDispatch(Event::CallHashMismatch(SUBMITTER, call_hash)),
+			);
+			assert!(!PendingCalls::<TestRuntime>::contains_key(SUBMITTER, call_hash));
+		});
+	}
+
+	#[test]
+	fn call_is_not_dispatched_if_decode_fails() {
+		run_test(|| {
+			let encoded_call = vec![0xFF; 16];
+			let call_hash = BlakeTwo256::hash(&encoded_call);
+			assert_ok!(ChunkedDispatch::submit_chunk(
+				Origin::signed(SUBMITTER),
+				call_hash,
+				1,
+				0,
+				encoded_call,
+				CALL_WEIGHT,
+			));
+
+			assert_eq!(
+				last_event(),
+				TestEvent::ChunkedDispatch(Event::CallDecodeFailed(SUBMITTER, call_hash)),
+			);
+		});
+	}
+
+	#[test]
+	fn call_is_not_dispatched_if_rejected_by_filter() {
+		run_test(|| {
+			let call = TestCall::System(frame_system::Call::fill_block(Default::default()));
+			let call_hash = submit_call_in_chunks(&call, MAX_CHUNK_SIZE as usize);
+
+			assert_eq!(
+				last_event(),
+				TestEvent::ChunkedDispatch(Event::CallRejected(SUBMITTER, call_hash)),
+			);
+		});
+	}
+
+	#[test]
+	fn call_is_not_dispatched_if_declared_weight_is_too_low() {
+		run_test(|| {
+			let encoded_call = remark_call(16).encode();
+			let call_hash = BlakeTwo256::hash(&encoded_call);
+			assert_ok!(ChunkedDispatch::submit_chunk(
+				Origin::signed(SUBMITTER),
+				call_hash,
+				1,
+				0,
+				encoded_call,
+				0,
+			));
+
+			assert!(matches!(
+				last_event(),
+				TestEvent::ChunkedDispatch(Event::CallWeightMismatch(SUBMITTER, hash, _, 0)) if hash == call_hash,
+			));
+		});
+	}
+
+	#[test]
+	fn pending_call_may_be_cancelled() {
+		run_test(|| {
+			let call_hash = Default::default();
+			assert_ok!(ChunkedDispatch::submit_chunk(
+				Origin::signed(SUBMITTER),
+				call_hash,
+				2,
+				0,
+				vec![0],
+				0
+			));
+			assert!(PendingCalls::<TestRuntime>::contains_key(SUBMITTER, call_hash));
+
+			assert_ok!(ChunkedDispatch::cancel_call(Origin::signed(SUBMITTER), call_hash));
+			assert!(!PendingCalls::<TestRuntime>::contains_key(SUBMITTER, call_hash));
+
+			assert_noop!(
+				ChunkedDispatch::cancel_call(Origin::signed(SUBMITTER), call_hash),
+				Error::<TestRuntime, ()>::UnknownCall,
+			);
+		});
+	}
+
+	#[test]
+	fn last_chunk_pays_for_call_dispatch() {
+		assert_eq!(submit_chunk_weight::<TestRuntime, ()>(2, 0, 0, CALL_WEIGHT), 0);
+		assert_eq!(
+			submit_chunk_weight::<TestRuntime, ()>(2, 1, 0, CALL_WEIGHT),
+			CALL_WEIGHT
+		);
+	}
+
+	#[test]
+	fn chunk_pays_for_stored_call_size() {
+		let first_chunk_size = stored_call_size::<TestRuntime, ()>(0, 16);
+		let last_chunk_size = stored_call_size::<TestRuntime, ()>(MAX_CHUNKS - 1, 16);
+		assert_eq!(first_chunk_size, 16);
+		assert_eq!(
+			last_chunk_size,
+			(MAX_CHUNKS as usize - 1) * MAX_CHUNK_SIZE as usize + 16
+		);
+		assert_eq!(
+			submit_chunk_weight::<TestRuntime, ()>(MAX_CHUNKS, 0, first_chunk_size, CALL_WEIGHT),
+			first_chunk_size as Weight,
+		);
+		assert_eq!(
+			submit_chunk_weight::<TestRuntime, ()>(MAX_CHUNKS, MAX_CHUNKS - 1, last_chunk_size, CALL_WEIGHT),
+			last_chunk_size as Weight + CALL_WEIGHT,
+		);
+	}
+
+	#[test]
+	fn deposit_is_reserved_for_stored_chunks_and_returned_when_call_is_dispatched() {
+		run_test(|| {
+			let encoded_call = remark_call(200).encode();
+			let call_hash = BlakeTwo256::hash(&encoded_call);
+			let chunks = encoded_call.chunks(64).collect::<Vec<_>>();
+			let chunks_count = chunks.len() as u32;
+			for (chunk_index, chunk) in chunks.iter().enumerate().take(chunks.len() - 1) {
+				assert_ok!(ChunkedDispatch::submit_chunk(
+					Origin::signed(SUBMITTER),
+					call_hash,
+					chunks_count,
+					chunk_index as u32,
+					chunk.to_vec(),
+					CALL_WEIGHT,
+				));
+				assert_eq!(
+					Balances::reserved_balance(SUBMITTER),
+					CHUNK_DEPOSIT * (chunk_index as u64 + 1)
+				);
+			}
+			assert_eq!(PendingCallsCount::<TestRuntime>::get(SUBMITTER), 1);
+
+			assert_ok!(ChunkedDispatch::submit_chunk(
+				Origin::signed(SUBMITTER),
+				call_hash,
+				chunks_count,
+				chunks_count - 1,
+				chunks[chunks.len() - 1].to_vec(),
+				CALL_WEIGHT,
+			));
+			assert_eq!(Balances::reserved_balance(SUBMITTER), 0);
+			assert_eq!(Balances::free_balance(SUBMITTER), SUBMITTER_BALANCE);
+			assert!(!PendingCallsCount::<TestRuntime>::contains_key(SUBMITTER));
+		});
+	}
+
+	#[test]
+	fn deposit_is_returned_when_call_is_cancelled() {
+		run_test(|| {
+			let call_hash = Default::default();
+			assert_ok!(ChunkedDispatch::submit_chunk(
+				Origin::signed(SUBMITTER),
+				call_hash,
+				2,
+				0,
+				vec![0],
+				0
+			));
+			assert_eq!(Balances::reserved_balance(SUBMITTER), CHUNK_DEPOSIT);
+
+			assert_ok!(ChunkedDispatch::cancel_call(Origin::signed(SUBMITTER), call_hash));
+			assert_eq!(Balances::reserved_balance(SUBMITTER), 0);
+			assert!(!PendingCallsCount::<TestRuntime>::contains_key(SUBMITTER));
+		});
+	}
+
+	#[test]
+	fn chunk_is_rejected_if_deposit_can_not_be_reserved() {
+		run_test(|| {
+			assert_noop!(
+				ChunkedDispatch::submit_chunk(Origin::signed(POOR_SUBMITTER), Default::default(), 2, 0, vec![0], 0),
+				Error::<TestRuntime, ()>::FailedToReserveDeposit,
+			);
+		});
+	}
+
+	#[test]
+	fn chunk_is_rejected_if_submitter_has_too_many_pending_calls() {
+		run_test(|| {
+			for index in 0..MAX_PENDING_CALLS_PER_ACCOUNT {
+				assert_ok!(ChunkedDispatch::submit_chunk(
+					Origin::signed(SUBMITTER),
+					H256::from_low_u64_be(index as u64),
+					2,
+					0,
+					vec![0],
+					0
+				));
+			}
+
+			assert_noop!(
+				ChunkedDispatch::submit_chunk(
+					Origin::signed(SUBMITTER),
+					H256::from_low_u64_be(MAX_PENDING_CALLS_PER_ACCOUNT as u64),
+					2,
+					0,
+					vec![0],
+					0
+				),
+				Error::<TestRuntime, ()>::TooManyPendingCalls,
+			);
+
+			// single-chunk calls are never stored, so they're not limited
+			submit_call_in_chunks(&remark_call(16), MAX_CHUNK_SIZE as usize);
+			assert_eq!(
+				PendingCallsCount::<TestRuntime>::get(SUBMITTER),
+				MAX_PENDING_CALLS_PER_ACCOUNT,
+			);
+		});
+	}
+
+	#[test]
+	fn expired_call_may_be_removed_by_anyone() {
+		run_test(|| {
+			let call_hash = Default::default();
+			assert_ok!(ChunkedDispatch::submit_chunk(
+				Origin::signed(SUBMITTER),
+				call_hash,
+				2,
+				0,
+				vec![0],
+				0
+			));
+
+			frame_system::Pallet::<TestRuntime>::set_block_number(1 + PENDING_CALL_LIFETIME);
+			assert_noop!(
+				ChunkedDispatch::remove_expired_call(Origin::signed(SUBMITTER + 1), SUBMITTER, call_hash),
+				Error::<TestRuntime, ()>::CallNotExpired,
+			);
+
+			frame_system::Pallet::<TestRuntime>::set_block_number(2 + PENDING_CALL_LIFETIME);
+			assert_ok!(ChunkedDispatch::remove_expired_call(
+				Origin::signed(SUBMITTER + 1),
+				SUBMITTER,
+				call_hash
+			));
+			assert_eq!(
+				last_event(),
+				TestEvent::ChunkedDispatch(Event::CallExpired(SUBMITTER, call_hash)),
+			);
+			assert!(!PendingCalls::<TestRuntime>::contains_key(SUBMITTER, call_hash));
+			assert_eq!(Balances::reserved_balance(SUBMITTER), 0);
+
+			assert_noop!(
+				ChunkedDispatch::remove_expired_call(Origin::signed(SUBMITTER + 1), SUBMITTER, call_hash),
+				Error::<TestRuntime, ()>::UnknownCall,
+			);
+		});
+	}
+}
@@ -0,0 +1,153 @@
+// Copyright 2019-2021 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+// From construct_runtime macro
+#![allow(clippy::from_over_into)]
+
+use crate as pallet_bridge_chunked_dispatch;
+
+use frame_support::{traits::Filter, weights::Weight};
+use sp_core::H256;
+use sp_runtime::{
+	testing::Header as SubstrateHeader,
+	traits::{BlakeTwo256, IdentityLookup},
+	Perbill,
+};
+
+pub type AccountId = u64;
+pub type Balance = u64;
+pub type Block = frame_system::mocking::MockBlock<TestRuntime>;
+pub type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<TestRuntime>;
+
+pub const SUBMITTER: AccountId = 1;
+pub const POOR_SUBMITTER: AccountId = 100;
+pub const SUBMITTER_BALANCE: Balance = 1_000;
+pub const CHUNK_DEPOSIT: Balance = 10;
+pub const PENDING_CALL_LIFETIME: u64 = 10;
+pub const MAX_PENDING_CALLS_PER_ACCOUNT: u32 = 2;
+pub const CALL_WEIGHT: Weight = 1_000_000_000;
+pub const MAX_CHUNKS: u32 = 8;
+pub const MAX_CHUNK_SIZE: u32 = 64;
+
+frame_support::construct_runtime! {
+	pub enum TestRuntime where
+		Block = Block,
+		NodeBlock = Block,
+		UncheckedExtrinsic = UncheckedExtrinsic,
+	{
+		System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
+		Balances: pallet_balances::{Pallet, Call, Event<T>},
+		ChunkedDispatch: pallet_bridge_chunked_dispatch::{Pallet, Call, Storage, Event<T>},
+	}
+}
+
+frame_support::parameter_types! {
+	pub const BlockHashCount: u64 = 250;
+	pub const MaximumBlockWeight: Weight = 1024;
+	pub const MaximumBlockLength: u32 = 2 * 1024;
+	pub const AvailableBlockRatio: Perbill = Perbill::one();
+}
+
+impl frame_system::Config for TestRuntime {
+	type Origin = Origin;
+	type Index = u64;
+	type Call = Call;
+	type BlockNumber = u64;
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	type AccountId = AccountId;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = SubstrateHeader;
+	type Event = Event;
+	type BlockHashCount = BlockHashCount;
+	type Version = ();
+	type PalletInfo = PalletInfo;
+	type AccountData = pallet_balances::AccountData<Balance>;
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+	type BaseCallFilter = ();
+	type SystemWeightInfo = ();
+	type BlockWeights = ();
+	type BlockLength = ();
+	type DbWeight = ();
+	type SS58Prefix = ();
+	type OnSetCode = ();
+}
+
+frame_support::parameter_types! {
+	pub const ExistentialDeposit: u64 = 1;
+	pub const MaxReserves: u32 = 50;
+}
+
+impl pallet_balances::Config for TestRuntime {
+	type MaxLocks = ();
+	type Balance = Balance;
+	type DustRemoval = ();
+	type Event = Event;
+	type ExistentialDeposit = ExistentialDeposit;
+	type AccountStore = frame_system::Pallet<TestRuntime>;
+	type WeightInfo = ();
+	type MaxReserves = MaxReserves;
+	type ReserveIdentifier = [u8; 8];
+}
+
+frame_support::parameter_types! {
+	pub const MaxChunks: u32 = MAX_CHUNKS;
+	pub const MaxChunkSize: u32 = MAX_CHUNK_SIZE;
+	pub const MaxPendingCallsPerAccount: u32 = MAX_PENDING_CALLS_PER_ACCOUNT;
+	pub const PendingCallLifetime: u64 = PENDING_CALL_LIFETIME;
+	pub const ChunkDeposit: Balance = CHUNK_DEPOSIT;
+	pub const ByteWeight: Weight = 1;
+}
+
+impl pallet_bridge_chunked_dispatch::Config for TestRuntime {
+	type Event = Event;
+	type Call = Call;
+	type CallFilter = TestCallFilter;
+	type MaxChunks = MaxChunks;
+	type MaxChunkSize = MaxChunkSize;
+	type MaxPendingCallsPerAccount = MaxPendingCallsPerAccount;
+	type PendingCallLifetime = PendingCallLifetime;
+	type ChunkDeposit = ChunkDeposit;
+	type ByteWeight = ByteWeight;
+	type Currency = pallet_balances::Pallet<TestRuntime>;
+}
+
+/// Call filter that rejects `System::fill_block` calls.
+pub struct TestCallFilter;
+
+impl Filter<Call> for TestCallFilter {
+	fn filter(call: &Call) -> bool {
+		!matches!(*call, Call::System(frame_system::Call::fill_block(_)))
+	}
+}
+
+/// Run pallet test.
+pub fn run_test<T>(test: impl FnOnce() -> T) -> T {
+	let mut t = frame_system::GenesisConfig::default()
+		.build_storage::<TestRuntime>()
+		.unwrap();
+	pallet_balances::GenesisConfig::<TestRuntime> {
+		balances: vec![(SUBMITTER, SUBMITTER_BALANCE), (SUBMITTER + 1, SUBMITTER_BALANCE)],
+	}
+	.assimilate_storage(&mut t)
+	.unwrap();
+	let mut ext = sp_io::TestExternalities::new(t);
+	ext.execute_with(|| {
+		frame_system::Pallet::<TestRuntime>::set_block_number(1);
+		test()
+	})
+}
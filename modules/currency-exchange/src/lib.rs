@@ -208,6 +208,24 @@ fn prepare_deposit_details<T: Config<I>, I: Instance>(
 	})
 }
 
+impl<T: Config<I>, I: Instance> From<Error<T, I>> for bp_runtime::BridgeError {
+	fn from(error: Error<T, I>) -> Self {
+		match error {
+			Error::InvalidTransaction => Self::InvalidStorageProof,
+			Error::InvalidAmount => Self::InvalidArgument,
+			Error::InvalidRecipient => Self::InvalidArgument,
+			Error::FailedToMapRecipients => Self::InvalidArgument,
+			Error::FailedToConvertCurrency => Self::InvalidArgument,
+			Error::DepositFailed => Self::PaymentFailed,
+			Error::DepositPartiallyFailed => Self::PaymentFailed,
+			Error::UnfinalizedTransaction => Self::UnknownHeader,
+			Error::AlreadyClaimed => Self::InvalidState,
+			// hidden variant that is never constructed
+			_ => Self::InvalidState,
+		}
+	}
+}
+
 #[cfg(test)]
 mod tests {
 	// From construct_runtime macro
@@ -20,13 +20,23 @@
 //! them and then dispatch as usual. To prevent compatibility issues, the Calls have
 //! to include a `spec_version`. This will be checked before dispatch. In the case of
 //! a successful dispatch an event is emitted.
+//!
+//! Messages may also carry versioned XCM instead of the runtime `Call`. Such messages are
+//! forwarded to the configured XCM executor.
+//!
+//! If the message sender has requested acknowledgement, the dispatch result is sent back to the
+//! source chain. There it is delivered as the `receive_dispatch_acknowledgement` call, dispatched
+//! on behalf of the acknowledgement account of this chain (see `acknowledgement_account_id`).
 
 #![cfg_attr(not(feature = "std"), no_std)]
 #![warn(missing_docs)]
 // Generated by `decl_event!`
 #![allow(clippy::unused_unit)]
 
-use bp_message_dispatch::{CallOrigin, MessageDispatch, MessagePayload, SpecVersion, Weight};
+use bp_message_dispatch::{
+	CallOrigin, DerivedAccountOrigin, DispatchAcknowledgement, MessageDispatch, MessagePayload, SendMessage,
+	SpecVersion, VersionedMessagePayload, Weight, XcmMessagePayload,
+};
 use bp_runtime::{
 	derive_account_id,
 	messages::{DispatchFeePayment, MessageDispatchResult},
@@ -34,16 +44,21 @@ use bp_runtime::{
 };
 use codec::{Decode, Encode};
 use frame_support::{
-	decl_event, decl_module, decl_storage,
-	dispatch::{Dispatchable, Parameter},
+	decl_error, decl_event, decl_module, decl_storage,
+	dispatch::{DispatchResultWithPostInfo, Dispatchable, Parameter},
 	ensure,
-	traits::{Filter, Get},
+	storage::{with_transaction, TransactionOutcome},
+	traits::{EnsureOrigin, Filter, Get, NamedReservableCurrency},
 	weights::{extract_actual_weight, GetDispatchInfo},
+	RuntimeDebug,
 };
-use frame_system::RawOrigin;
+use frame_system::{ensure_root, RawOrigin};
+use sp_core::{hashing::blake2_256, H256};
 use sp_runtime::{
-	traits::{BadOrigin, Convert, IdentifyAccount, MaybeDisplay, MaybeSerializeDeserialize, Member, Verify},
-	DispatchResult,
+	traits::{
+		BadOrigin, Convert, Hash, IdentifyAccount, MaybeDisplay, MaybeSerializeDeserialize, Member, Saturating, Verify,
+	},
+	DispatchError, DispatchResult,
 };
 use sp_std::{fmt::Debug, marker::PhantomData, prelude::*};
 
@@ -61,6 +76,9 @@ pub trait Config<I = DefaultInstance>: frame_system::Config {
 	type TargetChainAccountPublic: Parameter + IdentifyAccount<AccountId = Self::AccountId>;
 	/// Type of signature that may prove that the message has been signed by
 	/// owner of `TargetChainAccountPublic`.
+	///
+	/// Use `sp_runtime::MultiSignature` (with `sp_runtime::MultiSigner` public key) to accept
+	/// sr25519, ed25519 and ECDSA signatures.
 	type TargetChainSignature: Parameter + Verify<Signer = Self::TargetChainAccountPublic>;
 	/// The overarching dispatch call type.
 	type Call: Parameter
@@ -85,19 +103,262 @@ pub trait Config<I = DefaultInstance>: frame_system::Config {
 	///
 	/// Used when deriving target chain AccountIds from source chain AccountIds.
 	type AccountIdConverter: sp_runtime::traits::Convert<sp_core::hash::H256, Self::AccountId>;
+	/// Storage deposit meter for dispatched calls.
+	///
+	/// Storage deposits that are detected by the meter are charged from the dispatch origin
+	/// account. If the account is unable to pay, all changes made by the call are reverted and
+	/// special event (`Event::MessageStorageDepositPaymentFailed`) is emitted. The meter only
+	/// charges what it is able to observe, so calls creating storage that isn't detected by the
+	/// meter must be rejected by the `CallFilter`.
+	type StorageDepositMeter: StorageDepositMeter<Self::AccountId>;
+	/// Executor of XCM messages, delivered over the bridge.
+	///
+	/// The `CallFilter` isn't applied to XCM messages, so the executor is responsible for
+	/// filtering them. Use `()` if XCM messages must be rejected.
+	type XcmExecutor: XcmExecutor<Self::AccountId>;
+	/// Sender of dispatch acknowledgements.
+	///
+	/// If the message sender has requested acknowledgement, the dispatch result is sent back
+	/// to the source chain using this hook. Use `()` if acknowledgements are not supported.
+	type AcknowledgementSender: SendMessage<Self::MessageId>;
+	/// Origin of dispatch acknowledgements, received from the bridged chain.
+	///
+	/// Acknowledgements of messages, sent by this chain, are delivered as `receive_dispatch_acknowledgement`
+	/// calls. The origin should only accept calls, dispatched on behalf of the acknowledgement account of
+	/// the bridged chain (see `EnsureAcknowledgementAccount`). It returns id of the bridged chain.
+	type AcknowledgementOrigin: EnsureOrigin<<Self as frame_system::Config>::Origin, Success = ChainId>;
+	/// Maximal number of records in the `FailedMessages` map.
+	///
+	/// If it is zero, failed messages are not recorded at all. If the map is full, new failed
+	/// messages are not recorded (special event (`Event::FailedMessageDropped`) is emitted instead).
+	type MaxFailedMessages: Get<u32>;
+	/// Weight of hashing single byte of the message payload.
+	///
+	/// Payload is hashed when failed messages are recorded, so that the message may be retried later.
+	type PayloadHashingByteWeight: Get<Weight>;
+	/// Payment of the dispatch fee when the failed message is retried.
+	///
+	/// It is only used if the dispatch fee has not been paid when the message has been dispatched
+	/// for the first time. Use `()` if such messages must not be retried.
+	type RetryDispatchFeePayment: PayDispatchFee<Self::AccountId>;
+	/// Maximal number of recently dispatched messages that are tracked by the module.
+	///
+	/// Messages that are delivered again while they're still tracked, are rejected (special event
+	/// (`Event::MessageAlreadyDispatched`) is emitted instead of dispatch). This protects from double
+	/// dispatch if message nonces are reused, e.g. when lane storage is migrated by a runtime upgrade.
+	/// If it is zero, dispatched messages are not tracked at all. If this value is decreased, records
+	/// in the removed slots of the `DispatchedMessagesQueue` are never pruned.
+	type MaxDispatchedMessages: Get<u32>;
+	/// Origin that bridged calls may be dispatched with, if they're sent using the
+	/// `CallOrigin::SourceAccountAs` origin.
+	///
+	/// This is normally the `OriginCaller` of the runtime (same as the `PalletsOrigin` of the
+	/// `pallet_utility`). Origins that source chain accounts may use are configured using the
+	/// `set_dispatch_as_origin` call.
+	type DispatchAsOrigin: Parameter + Into<<Self as frame_system::Config>::Origin>;
+}
+
+/// A unique prefix for entropy when generating acknowledgement account IDs.
+pub const ACKNOWLEDGEMENT_ACCOUNT_DERIVATION_PREFIX: &[u8] = b"pallet-bridge/account-derivation/acknowledgement";
+/// Index of the `receive_dispatch_acknowledgement` call in the pallet calls enum.
+pub const RECEIVE_DISPATCH_ACKNOWLEDGEMENT_CALL_INDEX: u8 = 3;
+
+/// The record of message which dispatch has failed.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct FailedMessage<Hash> {
+	/// Id of the chain that has sent the message.
+	pub source_chain: ChainId,
+	/// Id of this chain, used by the message sender.
+	pub target_chain: ChainId,
+	/// Hash of the encoded message payload.
+	pub payload_hash: Hash,
+	/// Whether the dispatch fee of the message has already been paid.
+	///
+	/// If it is `false`, the fee is paid using `Config::RetryDispatchFeePayment` when the
+	/// message is retried.
+	pub dispatch_fee_paid: bool,
+}
+
+/// Payment of the message dispatch fee.
+pub trait PayDispatchFee<AccountId> {
+	/// Pay fee for dispatching message with given weight from the `account`.
+	fn pay_dispatch_fee(account: &AccountId, weight: Weight) -> Result<(), ()>;
+}
+
+/// Dispatch fee payment that always fails.
+impl<AccountId> PayDispatchFee<AccountId> for () {
+	fn pay_dispatch_fee(_account: &AccountId, _weight: Weight) -> Result<(), ()> {
+		Err(())
+	}
+}
+
+/// Meter of storage deposits, created by the dispatched call.
+pub trait StorageDepositMeter<AccountId> {
+	/// State snapshot that is taken right before the call is dispatched.
+	type Snapshot;
+
+	/// Take state snapshot right before the call is dispatched from the `account`.
+	fn snapshot(account: &AccountId) -> Self::Snapshot;
+
+	/// Compute storage deposit that has been created by the call since the `snapshot` has
+	/// been taken and charge it from the `account`.
+	///
+	/// Returns error if the account is unable to pay the deposit. In this case all changes
+	/// made by the call (and this method) are reverted.
+	fn charge_deposit(account: &AccountId, snapshot: Self::Snapshot) -> Result<(), ()>;
+}
+
+/// Storage deposit meter that doesn't charge anything.
+impl<AccountId> StorageDepositMeter<AccountId> for () {
+	type Snapshot = ();
+
+	fn snapshot(_account: &AccountId) -> Self::Snapshot {}
+
+	fn charge_deposit(_account: &AccountId, _snapshot: Self::Snapshot) -> Result<(), ()> {
+		Ok(())
+	}
+}
+
+/// Identifier of the deposit that is reserved by the `ConsumerReferencesDepositMeter`.
+pub const STORAGE_DEPOSIT_RESERVE_ID: [u8; 8] = *b"brdgdpst";
+
+/// Deposit meter that reserves `DepositPerConsumer` for every consumer reference, added to the
+/// dispatch origin account by the call.
+///
+/// This is not a storage size meter. It only sees storage items that pallets are guarding with
+/// the consumer reference of the account (locks, session keys, ...) and charges fixed deposit for
+/// every such item, whatever its size is. Storage that is created without adding consumer reference
+/// to the dispatch origin (e.g. items owned by other accounts or by the pallet itself) is not
+/// charged, so calls creating such storage must be rejected by the `CallFilter`. New accounts are
+/// paid by the existential deposit, so they are not metered. When the call removes consumer
+/// references, the deposit is returned to the account.
+pub struct ConsumerReferencesDepositMeter<T, Currency, DepositPerConsumer>(
+	PhantomData<(T, Currency, DepositPerConsumer)>,
+);
+
+impl<T, Currency, DepositPerConsumer> StorageDepositMeter<T::AccountId>
+	for ConsumerReferencesDepositMeter<T, Currency, DepositPerConsumer>
+where
+	T: frame_system::Config,
+	Currency: NamedReservableCurrency<T::AccountId, ReserveIdentifier = [u8; 8]>,
+	DepositPerConsumer: Get<Currency::Balance>,
+{
+	type Snapshot = frame_system::RefCount;
+
+	fn snapshot(account: &T::AccountId) -> Self::Snapshot {
+		frame_system::Pallet::<T>::consumers(account)
+	}
+
+	fn charge_deposit(account: &T::AccountId, snapshot: Self::Snapshot) -> Result<(), ()> {
+		let consumers = frame_system::Pallet::<T>::consumers(account);
+		if consumers > snapshot {
+			let deposit = DepositPerConsumer::get().saturating_mul((consumers - snapshot).into());
+			Currency::reserve_named(&STORAGE_DEPOSIT_RESERVE_ID, account, deposit).map_err(drop)?;
+		} else if consumers < snapshot {
+			let deposit = DepositPerConsumer::get().saturating_mul((snapshot - consumers).into());
+			Currency::unreserve_named(&STORAGE_DEPOSIT_RESERVE_ID, account, deposit);
+		}
+
+		Ok(())
+	}
+}
+
+/// Acknowledgement origin that only accepts signed origin of the `Account`.
+///
+/// The `Account` is the account that is used to dispatch acknowledgements from the `BridgedChainId`
+/// chain, derived from the acknowledgement account of that chain.
+pub struct EnsureAcknowledgementAccount<AccountId, Account, BridgedChainId>(
+	PhantomData<(AccountId, Account, BridgedChainId)>,
+);
+
+impl<O, AccountId, Account, BridgedChainId> EnsureOrigin<O>
+	for EnsureAcknowledgementAccount<AccountId, Account, BridgedChainId>
+where
+	O: Into<Result<RawOrigin<AccountId>, O>> + From<RawOrigin<AccountId>>,
+	AccountId: PartialEq,
+	Account: Get<AccountId>,
+	BridgedChainId: Get<ChainId>,
+{
+	type Success = ChainId;
+
+	fn try_origin(o: O) -> Result<Self::Success, O> {
+		o.into().and_then(|o| match o {
+			RawOrigin::Signed(ref who) if *who == Account::get() => Ok(BridgedChainId::get()),
+			r => Err(O::from(r)),
+		})
+	}
+
+	#[cfg(feature = "runtime-benchmarks")]
+	fn successful_origin() -> O {
+		O::from(RawOrigin::Signed(Account::get()))
+	}
+}
+
+/// Executor of XCM messages, delivered over the bridge.
+///
+/// Runtimes that have XCM support would implement it using their `XcmExecutor` and XCM weigher.
+pub trait XcmExecutor<AccountId> {
+	/// Compute weight of the encoded versioned XCM message.
+	///
+	/// Returns `None` if the message can't be decoded or weighed.
+	fn weight_of(message: &[u8]) -> Option<Weight>;
+
+	/// Execute the encoded versioned XCM message on behalf of the `origin` account.
+	///
+	/// Returns weight that has been actually used by the execution. It is returned in the
+	/// `Err` variant if the execution has failed.
+	fn execute(origin: AccountId, message: &[u8], weight_limit: Weight) -> Result<Weight, Weight>;
+}
+
+/// XCM executor that rejects all messages.
+impl<AccountId> XcmExecutor<AccountId> for () {
+	fn weight_of(_message: &[u8]) -> Option<Weight> {
+		None
+	}
+
+	fn execute(_origin: AccountId, _message: &[u8], _weight_limit: Weight) -> Result<Weight, Weight> {
+		Err(0)
+	}
 }
 
 decl_storage! {
-	trait Store for Pallet<T: Config<I>, I: Instance = DefaultInstance> as Dispatch {}
+	trait Store for Pallet<T: Config<I>, I: Instance = DefaultInstance> as Dispatch {
+		/// Messages which dispatch has failed. They may be retried using `retry_failed_message` call.
+		pub FailedMessages: map hasher(blake2_128_concat) T::MessageId => Option<FailedMessage<T::Hash>>;
+		/// Number of records in the `FailedMessages` map.
+		pub FailedMessagesCount: u32;
+		/// Origins of derived accounts, that have been used to dispatch messages.
+		///
+		/// The record is inserted when the derived account is used for the first time and is never
+		/// removed.
+		pub DerivedAccounts get(fn derived_account_origin):
+			map hasher(blake2_128_concat) T::AccountId => Option<DerivedAccountOrigin<T::SourceChainAccountId>>;
+		/// Recently dispatched messages, identified by the source chain and message id.
+		///
+		/// The value is the index of the `DispatchedMessagesQueue` slot, occupied by the message.
+		pub DispatchedMessages: map hasher(blake2_128_concat) (ChainId, T::MessageId) => Option<u32>;
+		/// Ring buffer of recently dispatched messages. It is used to prune the `DispatchedMessages` map.
+		pub DispatchedMessagesQueue: map hasher(twox_64_concat) u32 => Option<(ChainId, T::MessageId)>;
+		/// Index of the next `DispatchedMessagesQueue` slot that will be (re)used.
+		pub DispatchedMessagesQueueHead: u32;
+		/// Origins that source chain accounts are allowed to dispatch calls with, when messages are
+		/// sent using the `CallOrigin::SourceAccountAs` origin.
+		pub DispatchAsOrigins get(fn dispatch_as_origin):
+			double_map hasher(blake2_128_concat) ChainId, hasher(blake2_128_concat) T::SourceChainAccountId
+			=> Option<T::DispatchAsOrigin>;
+	}
 }
 
 decl_event!(
 	pub enum Event<T, I = DefaultInstance> where
 		<T as Config<I>>::MessageId,
 		AccountId = <T as frame_system::Config>::AccountId,
+		SourceChainAccountId = <T as Config<I>>::SourceChainAccountId,
 	{
 		/// Message has been rejected before reaching dispatch.
 		MessageRejected(ChainId, MessageId),
+		/// Message has been rejected, because it has already been dispatched.
+		MessageAlreadyDispatched(ChainId, MessageId),
 		/// Message has been rejected by dispatcher because of spec version mismatch.
 		/// Last two arguments are: expected and passed spec version.
 		MessageVersionSpecMismatch(ChainId, MessageId, SpecVersion, SpecVersion),
@@ -106,61 +367,283 @@ decl_event!(
 		MessageWeightMismatch(ChainId, MessageId, Weight, Weight),
 		/// Message signature mismatch.
 		MessageSignatureMismatch(ChainId, MessageId),
+		/// The source chain account isn't allowed to dispatch calls with any origin of this chain.
+		MessageDispatchAsOriginNotAllowed(ChainId, MessageId),
 		/// We have failed to decode Call from the message.
 		MessageCallDecodeFailed(ChainId, MessageId),
 		/// The call from the message has been rejected by the call filter.
 		MessageCallRejected(ChainId, MessageId),
+		/// The XCM executor has failed to decode or weigh XCM message.
+		MessageXcmRejected(ChainId, MessageId),
 		/// The origin account has failed to pay fee for dispatching the message.
 		MessageDispatchPaymentFailed(ChainId, MessageId, AccountId, Weight),
 		/// Message has been dispatched with given result.
 		MessageDispatched(ChainId, MessageId, DispatchResult),
+		/// The origin account has failed to pay storage deposit, created by the message call.
+		/// All changes made by the call have been reverted.
+		MessageStorageDepositPaymentFailed(ChainId, MessageId, AccountId),
+		/// Message dispatch has failed and the message has been recorded in the failed messages map.
+		FailedMessageStored(ChainId, MessageId),
+		/// Message dispatch has failed, but the failed messages map is full.
+		FailedMessageDropped(ChainId, MessageId),
+		/// Dispatch of the failed message has been retried. Last argument is the dispatch result.
+		FailedMessageRetried(ChainId, MessageId, bool),
+		/// Failed message has been removed from the failed messages map without dispatch.
+		FailedMessageDiscarded(ChainId, MessageId),
+		/// Dispatch result has been queued for delivery to the source chain.
+		DispatchAcknowledgementSent(ChainId, MessageId),
+		/// We have failed to send dispatch result back to the source chain.
+		DispatchAcknowledgementFailed(ChainId, MessageId),
+		/// Dispatch result of the message, sent by this chain, has been received from the bridged chain.
+		/// Last argument is the dispatch result.
+		DispatchAcknowledgementReceived(ChainId, MessageId, bool),
+		/// Origin that the source chain account may dispatch calls with, has been changed.
+		DispatchAsOriginUpdated(ChainId, SourceChainAccountId),
 		/// Phantom member, never used. Needed to handle multiple pallet instances.
 		_Dummy(PhantomData<I>),
 	}
 );
 
+decl_error! {
+	pub enum Error for Pallet<T: Config<I>, I: Instance> {
+		/// There's no failed message with given id.
+		FailedMessageNotFound,
+		/// The payload doesn't match payload of the failed message.
+		FailedMessagePayloadMismatch,
+		/// Declared dispatch weight is lower than the weight of the message.
+		DispatchWeightTooLow,
+	}
+}
+
 decl_module! {
 	/// Call Dispatch FRAME Pallet.
 	pub struct Module<T: Config<I>, I: Instance = DefaultInstance> for enum Call where origin: T::Origin {
 		/// Deposit one of this module's events by using the default implementation.
 		fn deposit_event() = default;
+
+		type Error = Error<T, I>;
+
+		/// Retry dispatch of the message which dispatch has previously failed.
+		///
+		/// The `payload` must be the encoded (legacy or versioned) payload of the failed message. The `dispatch_weight`
+		/// must be at least equal to the dispatch weight of the message. The dispatch fee is not
+		/// charged again if it has been paid when the message has been dispatched for the first time.
+		/// Otherwise it is paid using `Config::RetryDispatchFeePayment`.
+		#[weight = retry_failed_message_weight::<T, I>(payload.len(), *dispatch_weight)]
+		pub fn retry_failed_message(
+			origin,
+			id: T::MessageId,
+			payload: Vec<u8>,
+			dispatch_weight: Weight,
+		) -> DispatchResultWithPostInfo {
+			ensure_root(origin)?;
+
+			let failed_message = FailedMessages::<T, I>::get(&id).ok_or(Error::<T, I>::FailedMessageNotFound)?;
+			let payload_len = payload.len();
+			let message = <Self as MessageDispatch<T::AccountId, T::MessageId>>::Message::decode(&mut &payload[..])
+				.map_err(|_| Error::<T, I>::FailedMessagePayloadMismatch)?;
+			ensure!(
+				T::Hashing::hash_of(&message) == failed_message.payload_hash,
+				Error::<T, I>::FailedMessagePayloadMismatch,
+			);
+			ensure!(
+				<Self as MessageDispatch<T::AccountId, T::MessageId>>::dispatch_weight(&message) <= dispatch_weight,
+				Error::<T, I>::DispatchWeightTooLow,
+			);
+
+			// if the dispatch fails again, the message will be stored again
+			FailedMessages::<T, I>::remove(&id);
+			FailedMessagesCount::<I>::mutate(|count| *count = count.saturating_sub(1));
+			// the message has already been recorded as dispatched, but we want to retry it
+			DispatchedMessages::<T, I>::remove((failed_message.source_chain, id.clone()));
+
+			let dispatch_result = <Self as MessageDispatch<T::AccountId, T::MessageId>>::dispatch(
+				failed_message.source_chain,
+				failed_message.target_chain,
+				id.clone(),
+				Ok(message),
+				|account, weight| {
+					if failed_message.dispatch_fee_paid {
+						Ok(())
+					} else {
+						T::RetryDispatchFeePayment::pay_dispatch_fee(account, weight)
+					}
+				},
+			);
+			Self::deposit_event(RawEvent::FailedMessageRetried(
+				failed_message.source_chain,
+				id,
+				dispatch_result.dispatch_result,
+			));
+
+			let actual_weight = retry_failed_message_weight::<T, I>(payload_len, dispatch_weight)
+				.saturating_sub(dispatch_result.unspent_weight);
+			Ok(Some(actual_weight).into())
+		}
+
+		/// Allow source chain account to dispatch calls with given origin of this chain.
+		///
+		/// The origin is only used if message is sent using the `CallOrigin::SourceAccountAs` origin.
+		/// If `dispatch_as` is `None`, the account is no longer allowed to use any origin.
+		#[weight = T::DbWeight::get().writes(1)]
+		pub fn set_dispatch_as_origin(
+			origin,
+			source_chain: ChainId,
+			source_account: T::SourceChainAccountId,
+			dispatch_as: Option<T::DispatchAsOrigin>,
+		) {
+			ensure_root(origin)?;
+
+			match dispatch_as {
+				Some(dispatch_as) => DispatchAsOrigins::<T, I>::insert(source_chain, &source_account, dispatch_as),
+				None => DispatchAsOrigins::<T, I>::remove(source_chain, &source_account),
+			}
+			Self::deposit_event(RawEvent::DispatchAsOriginUpdated(source_chain, source_account));
+		}
+
+		/// Remove the failed message record without dispatching the message.
+		///
+		/// May be used to free slots in the failed messages map, when it is full.
+		#[weight = T::DbWeight::get().reads_writes(2, 2)]
+		pub fn discard_failed_message(origin, id: T::MessageId) {
+			ensure_root(origin)?;
+
+			let failed_message = FailedMessages::<T, I>::take(&id).ok_or(Error::<T, I>::FailedMessageNotFound)?;
+			FailedMessagesCount::<I>::mutate(|count| *count = count.saturating_sub(1));
+			Self::deposit_event(RawEvent::FailedMessageDiscarded(failed_message.source_chain, id));
+		}
+
+		/// Receive dispatch result of the message, sent by this chain to the bridged chain.
+		///
+		/// The call is sent back by the bridged chain if the message sender has requested acknowledgement.
+		#[weight = T::DbWeight::get().writes(1)]
+		pub fn receive_dispatch_acknowledgement(origin, acknowledgement: DispatchAcknowledgement<T::MessageId>) {
+			let bridged_chain = T::AcknowledgementOrigin::ensure_origin(origin)?;
+
+			log::trace!(
+				target: "runtime::bridge-dispatch",
+				"Message {:?}/{:?}: dispatch result has been received: {}",
+				bridged_chain,
+				acknowledgement.message_id,
+				acknowledgement.dispatch_result,
+			);
+			Self::deposit_event(RawEvent::DispatchAcknowledgementReceived(
+				bridged_chain,
+				acknowledgement.message_id,
+				acknowledgement.dispatch_result,
+			));
+		}
 	}
 }
 
-impl<T: Config<I>, I: Instance> MessageDispatch<T::AccountId, T::MessageId> for Pallet<T, I> {
-	type Message =
-		MessagePayload<T::SourceChainAccountId, T::TargetChainAccountPublic, T::TargetChainSignature, T::EncodedCall>;
+impl<T: Config<I>, I: Instance> Pallet<T, I> {
+	/// Record the message which dispatch has failed, so that it may be retried later.
+	///
+	/// Does nothing if failed messages are not recorded (`payload_hash` is `None`).
+	fn store_failed_message(
+		source_chain: ChainId,
+		target_chain: ChainId,
+		id: T::MessageId,
+		payload_hash: Option<T::Hash>,
+		dispatch_fee_paid: bool,
+	) {
+		let payload_hash = match payload_hash {
+			Some(payload_hash) => payload_hash,
+			None => return,
+		};
 
-	fn dispatch_weight(message: &Self::Message) -> Weight {
-		message.weight
+		let failed_messages_count = FailedMessagesCount::<I>::get();
+		if failed_messages_count >= T::MaxFailedMessages::get() {
+			log::trace!(
+				target: "runtime::bridge-dispatch",
+				"Message {:?}/{:?}: failed messages map is full. The message is dropped",
+				source_chain,
+				id,
+			);
+			Self::deposit_event(RawEvent::FailedMessageDropped(source_chain, id));
+			return;
+		}
+
+		FailedMessages::<T, I>::insert(
+			&id,
+			FailedMessage {
+				source_chain,
+				target_chain,
+				payload_hash,
+				dispatch_fee_paid,
+			},
+		);
+		FailedMessagesCount::<I>::put(failed_messages_count + 1);
+		Self::deposit_event(RawEvent::FailedMessageStored(source_chain, id));
 	}
 
-	fn dispatch<P: FnOnce(&T::AccountId, Weight) -> Result<(), ()>>(
+	/// Returns weight of checking and recording the dispatched message.
+	pub fn dispatched_message_recording_weight() -> Weight {
+		let db_weight = T::DbWeight::get();
+		if T::MaxDispatchedMessages::get() == 0 {
+			return db_weight.reads(1);
+		}
+
+		// `DispatchedMessages` check, `DispatchedMessagesQueueHead` + `DispatchedMessagesQueue` reads,
+		// pruned message read and removal, queue and `DispatchedMessages` inserts, head update
+		db_weight.reads_writes(4, 4)
+	}
+
+	/// Returns weight of recording the message, which payload (of given size) has failed to decode.
+	pub fn undecodable_message_weight(payload_size: usize) -> Weight {
+		if T::MaxFailedMessages::get() == 0 {
+			return 0;
+		}
+
+		failed_message_recording_weight::<T, I>(payload_size)
+	}
+
+	/// Returns true if the message has been recently dispatched.
+	fn is_dispatched_message(source_chain: ChainId, id: &T::MessageId) -> bool {
+		DispatchedMessages::<T, I>::contains_key((source_chain, id.clone()))
+	}
+
+	/// Remember that the message has been dispatched.
+	///
+	/// If the dispatched messages queue is full, the oldest record is pruned.
+	fn record_dispatched_message(source_chain: ChainId, id: T::MessageId) {
+		let max_dispatched_messages = T::MaxDispatchedMessages::get();
+		if max_dispatched_messages == 0 {
+			return;
+		}
+
+		// the limit may have been decreased by the runtime upgrade
+		let mut slot = DispatchedMessagesQueueHead::<I>::get();
+		if slot >= max_dispatched_messages {
+			slot = 0;
+		}
+
+		// the pruned message may have been dispatched again (retried) and now occupies another slot
+		if let Some(pruned_message) = DispatchedMessagesQueue::<T, I>::get(slot) {
+			if DispatchedMessages::<T, I>::get(&pruned_message) == Some(slot) {
+				DispatchedMessages::<T, I>::remove(&pruned_message);
+			}
+		}
+
+		DispatchedMessagesQueue::<T, I>::insert(slot, (source_chain, id.clone()));
+		DispatchedMessages::<T, I>::insert((source_chain, id), slot);
+		DispatchedMessagesQueueHead::<I>::put((slot + 1) % max_dispatched_messages);
+	}
+
+	/// Dispatch runtime call, delivered over the bridge.
+	fn dispatch_call<P: FnOnce(&T::AccountId, Weight) -> Result<(), ()>>(
 		source_chain: ChainId,
 		target_chain: ChainId,
 		id: T::MessageId,
-		message: Result<Self::Message, ()>,
+		message: MessagePayload<
+			T::SourceChainAccountId,
+			T::TargetChainAccountPublic,
+			T::TargetChainSignature,
+			T::EncodedCall,
+		>,
+		payload_hash: Option<T::Hash>,
 		pay_dispatch_fee: P,
 	) -> MessageDispatchResult {
-		// emit special even if message has been rejected by external component
-		let message = match message {
-			Ok(message) => message,
-			Err(_) => {
-				log::trace!(
-					target: "runtime::bridge-dispatch",
-					"Message {:?}/{:?}: rejected before actual dispatch",
-					source_chain,
-					id,
-				);
-				Self::deposit_event(RawEvent::MessageRejected(source_chain, id));
-				return MessageDispatchResult {
-					dispatch_result: false,
-					unspent_weight: 0,
-					dispatch_fee_paid_during_dispatch: false,
-				};
-			}
-		};
-
 		// verify spec version
 		// (we want it to be the same, because otherwise we may decode Call improperly)
 		let mut dispatch_result = MessageDispatchResult {
@@ -168,6 +651,8 @@ impl<T: Config<I>, I: Instance> MessageDispatch<T::AccountId, T::MessageId> for
 			unspent_weight: message.weight,
 			dispatch_fee_paid_during_dispatch: false,
 		};
+		// the dispatch fee is either paid at the source chain, or right before dispatch
+		let pay_dispatch_fee_at_target_chain = message.dispatch_fee_payment == DispatchFeePayment::AtTargetChain;
 		let expected_version = <T as frame_system::Config>::Version::get().spec_version;
 		if message.spec_version != expected_version {
 			log::trace!(
@@ -196,18 +681,25 @@ impl<T: Config<I>, I: Instance> MessageDispatch<T::AccountId, T::MessageId> for
 					source_chain,
 					id,
 				);
-				Self::deposit_event(RawEvent::MessageCallDecodeFailed(source_chain, id));
+				Self::deposit_event(RawEvent::MessageCallDecodeFailed(source_chain, id.clone()));
+				Self::store_failed_message(
+					source_chain,
+					target_chain,
+					id,
+					payload_hash,
+					!pay_dispatch_fee_at_target_chain,
+				);
 				return dispatch_result;
 			}
 		};
 
 		// prepare dispatch origin
-		let origin_account = match message.origin {
+		let (origin_account, derived_from, dispatch_as) = match message.origin {
 			CallOrigin::SourceRoot => {
 				let hex_id = derive_account_id::<T::SourceChainAccountId>(source_chain, SourceAccount::Root);
 				let target_id = T::AccountIdConverter::convert(hex_id);
 				log::trace!(target: "runtime::bridge-dispatch", "Root Account: {:?}", &target_id);
-				target_id
+				(target_id, Some(SourceAccount::Root), None)
 			}
 			CallOrigin::TargetAccount(source_account_id, target_public, target_signature) => {
 				let digest = account_ownership_digest(
@@ -233,13 +725,50 @@ impl<T: Config<I>, I: Instance> MessageDispatch<T::AccountId, T::MessageId> for
 				}
 
 				log::trace!(target: "runtime::bridge-dispatch", "Target Account: {:?}", &target_account);
-				target_account
+				(target_account, None, None)
 			}
 			CallOrigin::SourceAccount(source_account_id) => {
-				let hex_id = derive_account_id(source_chain, SourceAccount::Account(source_account_id));
+				let hex_id = derive_account_id(source_chain, SourceAccount::Account(source_account_id.clone()));
 				let target_id = T::AccountIdConverter::convert(hex_id);
 				log::trace!(target: "runtime::bridge-dispatch", "Source Account: {:?}", &target_id);
-				target_id
+				(target_id, Some(SourceAccount::Account(source_account_id)), None)
+			}
+			CallOrigin::SourceAccountAs(source_account_id) => {
+				let dispatch_as = match DispatchAsOrigins::<T, I>::get(source_chain, &source_account_id) {
+					Some(dispatch_as) => dispatch_as,
+					None => {
+						log::trace!(
+							target: "runtime::bridge-dispatch",
+							"Message {:?}/{:?}: source account {:?} isn't allowed to dispatch calls as any origin",
+							source_chain,
+							id,
+							source_account_id,
+						);
+						Self::deposit_event(RawEvent::MessageDispatchAsOriginNotAllowed(source_chain, id.clone()));
+						Self::store_failed_message(
+							source_chain,
+							target_chain,
+							id,
+							payload_hash,
+							!pay_dispatch_fee_at_target_chain,
+						);
+						return dispatch_result;
+					}
+				};
+
+				let hex_id = derive_account_id(source_chain, SourceAccount::Account(source_account_id.clone()));
+				let target_id = T::AccountIdConverter::convert(hex_id);
+				log::trace!(
+					target: "runtime::bridge-dispatch",
+					"Source Account: {:?}, dispatch as: {:?}",
+					&target_id,
+					&dispatch_as,
+				);
+				(
+					target_id,
+					Some(SourceAccount::Account(source_account_id)),
+					Some(dispatch_as),
+				)
 			}
 		};
 
@@ -280,7 +809,6 @@ impl<T: Config<I>, I: Instance> MessageDispatch<T::AccountId, T::MessageId> for
 		}
 
 		// pay dispatch fee right before dispatch
-		let pay_dispatch_fee_at_target_chain = message.dispatch_fee_payment == DispatchFeePayment::AtTargetChain;
 		if pay_dispatch_fee_at_target_chain && pay_dispatch_fee(&origin_account, message.weight).is_err() {
 			log::trace!(
 				target: "runtime::bridge-dispatch",
@@ -291,23 +819,57 @@ impl<T: Config<I>, I: Instance> MessageDispatch<T::AccountId, T::MessageId> for
 			);
 			Self::deposit_event(RawEvent::MessageDispatchPaymentFailed(
 				source_chain,
-				id,
+				id.clone(),
 				origin_account,
 				message.weight,
 			));
+			Self::store_failed_message(source_chain, target_chain, id, payload_hash, false);
 			return dispatch_result;
 		}
 		dispatch_result.dispatch_fee_paid_during_dispatch = pay_dispatch_fee_at_target_chain;
 
-		// finally dispatch message
-		let origin = RawOrigin::Signed(origin_account).into();
+		// remember who controls the derived account
+		if let Some(source_account) = derived_from {
+			Self::register_derived_account(&origin_account, source_chain, source_account);
+		}
+
+		// finally dispatch message, charging storage deposit from the origin account
+		let origin = match dispatch_as {
+			Some(dispatch_as) => dispatch_as.into(),
+			None => RawOrigin::Signed(origin_account.clone()).into(),
+		};
 
 		log::trace!(target: "runtime::bridge-dispatch", "Message being dispatched is: {:.4096?}", &call);
-		let result = call.dispatch(origin);
+		let (result, is_deposit_paid) = with_transaction(|| {
+			let snapshot = T::StorageDepositMeter::snapshot(&origin_account);
+			let result = call.dispatch(origin);
+			match T::StorageDepositMeter::charge_deposit(&origin_account, snapshot) {
+				Ok(()) => TransactionOutcome::Commit((result, true)),
+				Err(()) => TransactionOutcome::Rollback((result, false)),
+			}
+		});
 		let actual_call_weight = extract_actual_weight(&result, &dispatch_info);
-		dispatch_result.dispatch_result = result.is_ok();
 		dispatch_result.unspent_weight = message.weight.saturating_sub(actual_call_weight);
 
+		if !is_deposit_paid {
+			log::trace!(
+				target: "runtime::bridge-dispatch",
+				"Message {:?}/{:?}: {:?} has failed to pay storage deposit. Call changes are reverted",
+				source_chain,
+				id,
+				origin_account,
+			);
+			Self::deposit_event(RawEvent::MessageStorageDepositPaymentFailed(
+				source_chain,
+				id.clone(),
+				origin_account,
+			));
+			Self::store_failed_message(source_chain, target_chain, id, payload_hash, true);
+			return dispatch_result;
+		}
+
+		dispatch_result.dispatch_result = result.is_ok();
+
 		log::trace!(
 			target: "runtime::bridge-dispatch",
 			"Message {:?}/{:?} has been dispatched. Weight: {} of {}. Result: {:?}. Call dispatch result: {:?}",
@@ -321,41 +883,353 @@ impl<T: Config<I>, I: Instance> MessageDispatch<T::AccountId, T::MessageId> for
 
 		Self::deposit_event(RawEvent::MessageDispatched(
 			source_chain,
-			id,
+			id.clone(),
 			result.map(drop).map_err(|e| e.error),
 		));
+		if !dispatch_result.dispatch_result {
+			Self::store_failed_message(source_chain, target_chain, id, payload_hash, true);
+		}
 
 		dispatch_result
 	}
-}
 
-/// Check if the message is allowed to be dispatched on the target chain given the sender's origin
-/// on the source chain.
-///
-/// For example, if a message is sent from a "regular" account on the source chain it will not be
-/// allowed to be dispatched as Root on the target chain. This is a useful check to do on the source
-/// chain _before_ sending a message whose dispatch will be rejected on the target chain.
-pub fn verify_message_origin<SourceChainAccountId, TargetChainAccountPublic, TargetChainSignature, Call>(
-	sender_origin: &RawOrigin<SourceChainAccountId>,
-	message: &MessagePayload<SourceChainAccountId, TargetChainAccountPublic, TargetChainSignature, Call>,
-) -> Result<Option<SourceChainAccountId>, BadOrigin>
-where
-	SourceChainAccountId: PartialEq + Clone,
-{
-	match message.origin {
-		CallOrigin::SourceRoot => {
-			ensure!(sender_origin == &RawOrigin::Root, BadOrigin);
-			Ok(None)
-		}
-		CallOrigin::TargetAccount(ref source_account_id, _, _) => {
-			ensure!(
-				sender_origin == &RawOrigin::Signed(source_account_id.clone()),
-				BadOrigin
+	/// Execute XCM message, delivered over the bridge.
+	fn dispatch_xcm<P: FnOnce(&T::AccountId, Weight) -> Result<(), ()>>(
+		source_chain: ChainId,
+		target_chain: ChainId,
+		id: T::MessageId,
+		message: XcmMessagePayload<T::SourceChainAccountId>,
+		payload_hash: Option<T::Hash>,
+		pay_dispatch_fee: P,
+	) -> MessageDispatchResult {
+		let mut dispatch_result = MessageDispatchResult {
+			dispatch_result: false,
+			unspent_weight: message.weight,
+			dispatch_fee_paid_during_dispatch: false,
+		};
+		// the dispatch fee is either paid at the source chain, or right before dispatch
+		let pay_dispatch_fee_at_target_chain = message.dispatch_fee_payment == DispatchFeePayment::AtTargetChain;
+
+		// weigh the message
+		let expected_weight = match T::XcmExecutor::weight_of(&message.xcm) {
+			Some(expected_weight) => expected_weight,
+			None => {
+				log::trace!(
+					target: "runtime::bridge-dispatch",
+					"Failed to decode or weigh XCM from message {:?}/{:?}",
+					source_chain,
+					id,
+				);
+				Self::deposit_event(RawEvent::MessageXcmRejected(source_chain, id.clone()));
+				Self::store_failed_message(
+					source_chain,
+					target_chain,
+					id,
+					payload_hash,
+					!pay_dispatch_fee_at_target_chain,
+				);
+				return dispatch_result;
+			}
+		};
+
+		// verify weight
+		if message.weight < expected_weight {
+			log::trace!(
+				target: "runtime::bridge-dispatch",
+				"Message {:?}/{:?}: passed XCM weight is too low. Expected at least {:?}, got {:?}",
+				source_chain,
+				id,
+				expected_weight,
+				message.weight,
 			);
-			Ok(Some(source_account_id.clone()))
+			Self::deposit_event(RawEvent::MessageWeightMismatch(
+				source_chain,
+				id,
+				expected_weight,
+				message.weight,
+			));
+			return dispatch_result;
 		}
-		CallOrigin::SourceAccount(ref source_account_id) => {
-			ensure!(
+
+		// pay dispatch fee right before execution
+		let origin_account = T::AccountIdConverter::convert(derive_account_id(source_chain, message.origin.clone()));
+		if pay_dispatch_fee_at_target_chain && pay_dispatch_fee(&origin_account, message.weight).is_err() {
+			log::trace!(
+				target: "runtime::bridge-dispatch",
+				"Failed to pay dispatch fee for executing XCM message {:?}/{:?} with weight {}",
+				source_chain,
+				id,
+				message.weight,
+			);
+			Self::deposit_event(RawEvent::MessageDispatchPaymentFailed(
+				source_chain,
+				id.clone(),
+				origin_account,
+				message.weight,
+			));
+			Self::store_failed_message(source_chain, target_chain, id, payload_hash, false);
+			return dispatch_result;
+		}
+		dispatch_result.dispatch_fee_paid_during_dispatch = pay_dispatch_fee_at_target_chain;
+
+		// remember who controls the derived account
+		Self::register_derived_account(&origin_account, source_chain, message.origin);
+
+		// finally execute message
+		let result = T::XcmExecutor::execute(origin_account, &message.xcm, message.weight);
+		let actual_weight = match result {
+			Ok(actual_weight) | Err(actual_weight) => actual_weight,
+		};
+		dispatch_result.unspent_weight = message.weight.saturating_sub(actual_weight);
+		dispatch_result.dispatch_result = result.is_ok();
+
+		log::trace!(
+			target: "runtime::bridge-dispatch",
+			"XCM message {:?}/{:?} has been executed. Weight: {} of {}. Result: {:?}",
+			source_chain,
+			id,
+			actual_weight,
+			message.weight,
+			dispatch_result,
+		);
+
+		Self::deposit_event(RawEvent::MessageDispatched(
+			source_chain,
+			id.clone(),
+			result
+				.map(drop)
+				.map_err(|_| DispatchError::Other("XCM message execution has failed")),
+		));
+		if !dispatch_result.dispatch_result {
+			Self::store_failed_message(source_chain, target_chain, id, payload_hash, true);
+		}
+
+		dispatch_result
+	}
+
+	/// Send dispatch result back to the source chain.
+	fn send_dispatch_acknowledgement(source_chain: ChainId, id: T::MessageId, dispatch_result: bool) {
+		let acknowledgement = DispatchAcknowledgement {
+			message_id: id.clone(),
+			dispatch_result,
+		};
+		match T::AcknowledgementSender::send_message(source_chain, acknowledgement) {
+			Ok(()) => {
+				log::trace!(
+					target: "runtime::bridge-dispatch",
+					"Message {:?}/{:?}: dispatch result has been sent to the source chain",
+					source_chain,
+					id,
+				);
+				Self::deposit_event(RawEvent::DispatchAcknowledgementSent(source_chain, id));
+			}
+			Err(()) => {
+				log::trace!(
+					target: "runtime::bridge-dispatch",
+					"Message {:?}/{:?}: failed to send dispatch result to the source chain",
+					source_chain,
+					id,
+				);
+				Self::deposit_event(RawEvent::DispatchAcknowledgementFailed(source_chain, id));
+			}
+		}
+	}
+
+	/// Remember origin of the derived account, if it is used for the first time.
+	fn register_derived_account(
+		account: &T::AccountId,
+		source_chain: ChainId,
+		source_account: SourceAccount<T::SourceChainAccountId>,
+	) {
+		if DerivedAccounts::<T, I>::contains_key(account) {
+			return;
+		}
+
+		DerivedAccounts::<T, I>::insert(
+			account,
+			DerivedAccountOrigin {
+				source_chain,
+				source_account,
+			},
+		);
+	}
+}
+
+impl<T: Config<I>, I: Instance> MessageDispatch<T::AccountId, T::MessageId> for Pallet<T, I> {
+	type Message = VersionedMessagePayload<
+		T::SourceChainAccountId,
+		T::TargetChainAccountPublic,
+		T::TargetChainSignature,
+		T::EncodedCall,
+	>;
+
+	fn dispatch_weight(message: &Self::Message) -> Weight {
+		let mut weight = message.weight();
+		if message.acknowledge_dispatch() {
+			weight = weight.saturating_add(T::AcknowledgementSender::send_message_weight());
+		}
+		if T::MaxFailedMessages::get() != 0 {
+			weight = weight.saturating_add(failed_message_recording_weight::<T, I>(message.encoded_size()));
+		}
+		weight
+	}
+
+	fn dispatch<P: FnOnce(&T::AccountId, Weight) -> Result<(), ()>>(
+		source_chain: ChainId,
+		target_chain: ChainId,
+		id: T::MessageId,
+		message: Result<Self::Message, Vec<u8>>,
+		pay_dispatch_fee: P,
+	) -> MessageDispatchResult {
+		// emit special even if message has been rejected by external component
+		let message = match message {
+			Ok(message) => message,
+			Err(payload) => {
+				log::trace!(
+					target: "runtime::bridge-dispatch",
+					"Message {:?}/{:?}: rejected before actual dispatch",
+					source_chain,
+					id,
+				);
+				Self::deposit_event(RawEvent::MessageRejected(source_chain, id.clone()));
+
+				// remember payload hash, so that the message may be retried (e.g. after runtime upgrade)
+				if T::MaxFailedMessages::get() != 0 && !Self::is_dispatched_message(source_chain, &id) {
+					Self::record_dispatched_message(source_chain, id.clone());
+					Self::store_failed_message(source_chain, target_chain, id, Some(T::Hashing::hash(&payload)), false);
+				}
+
+				return MessageDispatchResult {
+					dispatch_result: false,
+					unspent_weight: 0,
+					dispatch_fee_paid_during_dispatch: false,
+				};
+			}
+		};
+
+		// reject message if it has already been dispatched (e.g. when lane storage has been migrated
+		// and the message has been delivered again)
+		if Self::is_dispatched_message(source_chain, &id) {
+			log::trace!(
+				target: "runtime::bridge-dispatch",
+				"Message {:?}/{:?}: has already been dispatched",
+				source_chain,
+				id,
+			);
+			Self::deposit_event(RawEvent::MessageAlreadyDispatched(source_chain, id));
+			return MessageDispatchResult {
+				dispatch_result: false,
+				unspent_weight: Self::dispatch_weight(&message),
+				dispatch_fee_paid_during_dispatch: false,
+			};
+		}
+		Self::record_dispatched_message(source_chain, id.clone());
+
+		// remember payload hash, so that the message may be retried if its dispatch fails
+		let payload_hash = if T::MaxFailedMessages::get() != 0 {
+			Some(T::Hashing::hash_of(&message))
+		} else {
+			None
+		};
+
+		// all payload versions are converted into the latest version before dispatch
+		let acknowledge_dispatch = message.acknowledge_dispatch();
+		let dispatch_result = match message.into_latest() {
+			Ok(message) => Self::dispatch_call(
+				source_chain,
+				target_chain,
+				id.clone(),
+				message,
+				payload_hash,
+				pay_dispatch_fee,
+			),
+			Err(message) => Self::dispatch_xcm(
+				source_chain,
+				target_chain,
+				id.clone(),
+				message,
+				payload_hash,
+				pay_dispatch_fee,
+			),
+		};
+
+		// let the source chain know whether the message has been dispatched successfully
+		if acknowledge_dispatch {
+			Self::send_dispatch_acknowledgement(source_chain, id, dispatch_result.dispatch_result);
+		}
+
+		dispatch_result
+	}
+}
+
+/// Derive the account ID that is sending dispatch acknowledgements from the given chain.
+pub fn acknowledgement_account_id(chain: ChainId) -> H256 {
+	(ACKNOWLEDGEMENT_ACCOUNT_DERIVATION_PREFIX, chain)
+		.using_encoded(blake2_256)
+		.into()
+}
+
+/// Encode the `receive_dispatch_acknowledgement` call of the pallet, deployed at the `pallet_index` of the
+/// (bridged) runtime.
+///
+/// The `acknowledgement` must use the message id type of the bridged runtime.
+pub fn encode_receive_dispatch_acknowledgement_call<MessageId: Encode>(
+	pallet_index: u8,
+	acknowledgement: DispatchAcknowledgement<MessageId>,
+) -> Vec<u8> {
+	(
+		pallet_index,
+		RECEIVE_DISPATCH_ACKNOWLEDGEMENT_CALL_INDEX,
+		acknowledgement,
+	)
+		.encode()
+}
+
+/// Weight of hashing the message payload of given size and recording the failed message.
+fn failed_message_recording_weight<T: Config<I>, I: Instance>(payload_size: usize) -> Weight {
+	T::PayloadHashingByteWeight::get()
+		.saturating_mul(payload_size as Weight)
+		.saturating_add(T::DbWeight::get().reads_writes(1, 2))
+}
+
+/// Weight of the `retry_failed_message` call.
+///
+/// The `dispatch_weight` covers hashing and recording the message if it fails again. On top of that, the
+/// payload is hashed to verify it against the failed message record.
+fn retry_failed_message_weight<T: Config<I>, I: Instance>(payload_size: usize, dispatch_weight: Weight) -> Weight {
+	T::DbWeight::get()
+		.reads_writes(2, 2)
+		.saturating_add(T::PayloadHashingByteWeight::get().saturating_mul(payload_size as Weight))
+		.saturating_add(dispatch_weight)
+}
+
+/// Check if the message is allowed to be dispatched on the target chain given the sender's origin
+/// on the source chain.
+///
+/// For example, if a message is sent from a "regular" account on the source chain it will not be
+/// allowed to be dispatched as Root on the target chain. This is a useful check to do on the source
+/// chain _before_ sending a message whose dispatch will be rejected on the target chain.
+pub fn verify_message_origin<SourceChainAccountId, TargetChainAccountPublic, TargetChainSignature, Call>(
+	sender_origin: &RawOrigin<SourceChainAccountId>,
+	message: &MessagePayload<SourceChainAccountId, TargetChainAccountPublic, TargetChainSignature, Call>,
+) -> Result<Option<SourceChainAccountId>, BadOrigin>
+where
+	SourceChainAccountId: PartialEq + Clone,
+{
+	match message.origin {
+		CallOrigin::SourceRoot => {
+			ensure!(sender_origin == &RawOrigin::Root, BadOrigin);
+			Ok(None)
+		}
+		CallOrigin::TargetAccount(ref source_account_id, _, _) => {
+			ensure!(
+				sender_origin == &RawOrigin::Signed(source_account_id.clone()),
+				BadOrigin
+			);
+			Ok(Some(source_account_id.clone()))
+		}
+		CallOrigin::SourceAccount(ref source_account_id) | CallOrigin::SourceAccountAs(ref source_account_id) => {
+			ensure!(
 				sender_origin == &RawOrigin::Signed(source_account_id.clone()) || sender_origin == &RawOrigin::Root,
 				BadOrigin
 			);
@@ -391,26 +1265,40 @@ where
 	proof
 }
 
+impl<T: Config<I>, I: Instance> From<Error<T, I>> for bp_runtime::BridgeError {
+	fn from(error: Error<T, I>) -> Self {
+		match error {
+			Error::FailedMessageNotFound => Self::UnknownEntity,
+			Error::FailedMessagePayloadMismatch => Self::InvalidArgument,
+			Error::DispatchWeightTooLow => Self::InvalidArgument,
+			// hidden variant that is never constructed
+			_ => Self::InvalidState,
+		}
+	}
+}
+
 #[cfg(test)]
 mod tests {
 	// From construct_runtime macro
 	#![allow(clippy::from_over_into)]
 
 	use super::*;
-	use frame_support::{parameter_types, weights::Weight};
+	use frame_support::{assert_noop, assert_ok, parameter_types, weights::Weight};
 	use frame_system::{EventRecord, Phase};
-	use sp_core::H256;
+	use sp_core::{ecdsa, ed25519, sr25519, Pair, H256};
 	use sp_runtime::{
 		testing::Header,
 		traits::{BlakeTwo256, IdentityLookup},
-		Perbill,
+		MultiSignature, MultiSigner, Perbill,
 	};
 
 	type AccountId = u64;
 	type MessageId = [u8; 4];
+	type TestMessagePayload = MessagePayload<AccountId, TestAccountPublic, TestSignature, EncodedCall>;
 
 	const SOURCE_CHAIN_ID: ChainId = *b"srce";
 	const TARGET_CHAIN_ID: ChainId = *b"trgt";
+	const ACKNOWLEDGEMENT_ACCOUNT: AccountId = 100;
 
 	#[derive(Debug, Encode, Decode, Clone, PartialEq, Eq)]
 	pub struct TestAccountPublic(AccountId);
@@ -454,7 +1342,8 @@ mod tests {
 			UncheckedExtrinsic = UncheckedExtrinsic,
 		{
 			System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
-			Dispatch: call_dispatch::{Pallet, Call, Event<T>},
+			Dispatch: call_dispatch::{Pallet, Call, Storage, Event<T>},
+			Balances: pallet_balances::{Pallet, Call, Event<T>},
 		}
 	}
 
@@ -463,6 +1352,14 @@ mod tests {
 		pub const MaximumBlockWeight: Weight = 1024;
 		pub const MaximumBlockLength: u32 = 2 * 1024;
 		pub const AvailableBlockRatio: Perbill = Perbill::one();
+		pub storage MaxFailedMessages: u32 = 0;
+		pub storage PayloadHashingByteWeight: Weight = 0;
+		pub storage MaxDispatchedMessages: u32 = 0;
+		pub const ExistentialDeposit: u64 = 1;
+		pub const MaxReserves: u32 = 50;
+		pub const DepositPerConsumer: u64 = 10;
+		pub const AcknowledgementAccount: AccountId = ACKNOWLEDGEMENT_ACCOUNT;
+		pub const BridgedChainId: ChainId = SOURCE_CHAIN_ID;
 	}
 
 	impl frame_system::Config for TestRuntime {
@@ -479,7 +1376,7 @@ mod tests {
 		type BlockHashCount = BlockHashCount;
 		type Version = ();
 		type PalletInfo = PalletInfo;
-		type AccountData = ();
+		type AccountData = pallet_balances::AccountData<u64>;
 		type OnNewAccount = ();
 		type OnKilledAccount = ();
 		type BaseCallFilter = ();
@@ -491,6 +1388,18 @@ mod tests {
 		type OnSetCode = ();
 	}
 
+	impl pallet_balances::Config for TestRuntime {
+		type MaxLocks = ();
+		type Balance = u64;
+		type DustRemoval = ();
+		type Event = Event;
+		type ExistentialDeposit = ExistentialDeposit;
+		type AccountStore = frame_system::Pallet<TestRuntime>;
+		type WeightInfo = ();
+		type MaxReserves = MaxReserves;
+		type ReserveIdentifier = [u8; 8];
+	}
+
 	impl Config for TestRuntime {
 		type Event = Event;
 		type MessageId = MessageId;
@@ -501,6 +1410,15 @@ mod tests {
 		type CallFilter = TestCallFilter;
 		type EncodedCall = EncodedCall;
 		type AccountIdConverter = AccountIdConverter;
+		type StorageDepositMeter = TestStorageDepositMeter;
+		type XcmExecutor = TestXcmExecutor;
+		type AcknowledgementSender = TestAcknowledgementSender;
+		type AcknowledgementOrigin = EnsureAcknowledgementAccount<AccountId, AcknowledgementAccount, BridgedChainId>;
+		type MaxFailedMessages = MaxFailedMessages;
+		type PayloadHashingByteWeight = PayloadHashingByteWeight;
+		type RetryDispatchFeePayment = TestRetryDispatchFeePayment;
+		type MaxDispatchedMessages = MaxDispatchedMessages;
+		type DispatchAsOrigin = OriginCaller;
 	}
 
 	#[derive(Decode, Encode)]
@@ -520,6 +1438,98 @@ mod tests {
 		}
 	}
 
+	/// Account that is unable to pay storage deposit.
+	const ACCOUNT_WITHOUT_DEPOSIT_FUNDS: AccountId = 2;
+
+	/// Storage deposit meter that treats every event, deposited by the call, as new storage item.
+	pub struct TestStorageDepositMeter;
+
+	impl StorageDepositMeter<AccountId> for TestStorageDepositMeter {
+		type Snapshot = usize;
+
+		fn snapshot(_account: &AccountId) -> Self::Snapshot {
+			System::events().len()
+		}
+
+		fn charge_deposit(account: &AccountId, snapshot: Self::Snapshot) -> Result<(), ()> {
+			let created_items = System::events().len().saturating_sub(snapshot);
+			if created_items != 0 && *account == ACCOUNT_WITHOUT_DEPOSIT_FUNDS {
+				return Err(());
+			}
+
+			Ok(())
+		}
+	}
+
+	/// XCM executor that treats every byte of the message as a single unit of weight. Messages
+	/// that start with zero byte are failing.
+	pub struct TestXcmExecutor;
+
+	impl XcmExecutor<AccountId> for TestXcmExecutor {
+		fn weight_of(message: &[u8]) -> Option<Weight> {
+			if message.is_empty() {
+				return None;
+			}
+
+			Some(message.len() as Weight)
+		}
+
+		fn execute(_origin: AccountId, message: &[u8], _weight_limit: Weight) -> Result<Weight, Weight> {
+			let weight = message.len() as Weight;
+			if message[0] == 0 {
+				return Err(weight);
+			}
+
+			Ok(weight)
+		}
+	}
+
+	/// Account that is unable to pay dispatch fee when failed message is retried.
+	const ACCOUNT_WITHOUT_RETRY_FEE_FUNDS: AccountId = 3;
+
+	/// Dispatch fee payment that remembers accounts that have paid the fee.
+	pub struct TestRetryDispatchFeePayment;
+
+	impl TestRetryDispatchFeePayment {
+		fn is_fee_paid(account: AccountId) -> bool {
+			frame_support::storage::unhashed::exists(&(b":retry-fee-paid:", account).encode())
+		}
+	}
+
+	impl PayDispatchFee<AccountId> for TestRetryDispatchFeePayment {
+		fn pay_dispatch_fee(account: &AccountId, _weight: Weight) -> Result<(), ()> {
+			if *account == ACCOUNT_WITHOUT_RETRY_FEE_FUNDS {
+				return Err(());
+			}
+
+			frame_support::storage::unhashed::put(&(b":retry-fee-paid:", account).encode(), &true);
+			Ok(())
+		}
+	}
+
+	/// Weight of sending dispatch acknowledgement.
+	const TEST_ACKNOWLEDGEMENT_WEIGHT: Weight = 100;
+
+	/// Acknowledgement sender that remembers the last sent acknowledgement.
+	pub struct TestAcknowledgementSender;
+
+	impl TestAcknowledgementSender {
+		fn last_sent_acknowledgement() -> Option<(ChainId, DispatchAcknowledgement<MessageId>)> {
+			frame_support::storage::unhashed::get(b":sent-acknowledgement:")
+		}
+	}
+
+	impl SendMessage<MessageId> for TestAcknowledgementSender {
+		fn send_message_weight() -> Weight {
+			TEST_ACKNOWLEDGEMENT_WEIGHT
+		}
+
+		fn send_message(source_chain: ChainId, acknowledgement: DispatchAcknowledgement<MessageId>) -> Result<(), ()> {
+			frame_support::storage::unhashed::put(b":sent-acknowledgement:", &(source_chain, acknowledgement));
+			Ok(())
+		}
+	}
+
 	const TEST_SPEC_VERSION: SpecVersion = 0;
 	const TEST_WEIGHT: Weight = 1_000_000_000;
 
@@ -533,7 +1543,7 @@ mod tests {
 	fn prepare_message(
 		origin: CallOrigin<AccountId, TestAccountPublic, TestSignature>,
 		call: Call,
-	) -> <Pallet<TestRuntime> as MessageDispatch<AccountId, <TestRuntime as Config>::MessageId>>::Message {
+	) -> TestMessagePayload {
 		MessagePayload {
 			spec_version: TEST_SPEC_VERSION,
 			weight: TEST_WEIGHT,
@@ -543,26 +1553,34 @@ mod tests {
 		}
 	}
 
-	fn prepare_root_message(
-		call: Call,
-	) -> <Pallet<TestRuntime> as MessageDispatch<AccountId, <TestRuntime as Config>::MessageId>>::Message {
+	fn prepare_root_message(call: Call) -> TestMessagePayload {
 		prepare_message(CallOrigin::SourceRoot, call)
 	}
 
-	fn prepare_target_message(
-		call: Call,
-	) -> <Pallet<TestRuntime> as MessageDispatch<AccountId, <TestRuntime as Config>::MessageId>>::Message {
+	fn prepare_target_message(call: Call) -> TestMessagePayload {
 		let origin = CallOrigin::TargetAccount(1, TestAccountPublic(1), TestSignature(1));
 		prepare_message(origin, call)
 	}
 
-	fn prepare_source_message(
-		call: Call,
-	) -> <Pallet<TestRuntime> as MessageDispatch<AccountId, <TestRuntime as Config>::MessageId>>::Message {
+	fn prepare_source_message(call: Call) -> TestMessagePayload {
 		let origin = CallOrigin::SourceAccount(1);
 		prepare_message(origin, call)
 	}
 
+	fn prepare_source_as_message(call: Call) -> TestMessagePayload {
+		let origin = CallOrigin::SourceAccountAs(1);
+		prepare_message(origin, call)
+	}
+
+	fn prepare_xcm_message(xcm: Vec<u8>) -> XcmMessagePayload<AccountId> {
+		XcmMessagePayload {
+			weight: TEST_WEIGHT,
+			origin: SourceAccount::Account(1),
+			dispatch_fee_payment: DispatchFeePayment::AtTargetChain,
+			xcm,
+		}
+	}
+
 	#[test]
 	fn should_fail_on_spec_version_mismatch() {
 		new_test_ext().execute_with(|| {
@@ -575,7 +1593,13 @@ mod tests {
 			message.spec_version = BAD_SPEC_VERSION;
 
 			System::set_block_number(1);
-			let result = Dispatch::dispatch(SOURCE_CHAIN_ID, TARGET_CHAIN_ID, id, Ok(message), |_, _| unreachable!());
+			let result = Dispatch::dispatch(
+				SOURCE_CHAIN_ID,
+				TARGET_CHAIN_ID,
+				id,
+				Ok(message.into()),
+				|_, _| unreachable!(),
+			);
 			assert_eq!(result.unspent_weight, weight);
 			assert!(!result.dispatch_result);
 
@@ -609,7 +1633,13 @@ mod tests {
 			);
 
 			System::set_block_number(1);
-			let result = Dispatch::dispatch(SOURCE_CHAIN_ID, TARGET_CHAIN_ID, id, Ok(message), |_, _| unreachable!());
+			let result = Dispatch::dispatch(
+				SOURCE_CHAIN_ID,
+				TARGET_CHAIN_ID,
+				id,
+				Ok(message.into()),
+				|_, _| unreachable!(),
+			);
 			assert_eq!(result.unspent_weight, 7);
 			assert!(!result.dispatch_result);
 
@@ -642,7 +1672,13 @@ mod tests {
 			let weight = message.weight;
 
 			System::set_block_number(1);
-			let result = Dispatch::dispatch(SOURCE_CHAIN_ID, TARGET_CHAIN_ID, id, Ok(message), |_, _| unreachable!());
+			let result = Dispatch::dispatch(
+				SOURCE_CHAIN_ID,
+				TARGET_CHAIN_ID,
+				id,
+				Ok(message.into()),
+				|_, _| unreachable!(),
+			);
 			assert_eq!(result.unspent_weight, weight);
 			assert!(!result.dispatch_result);
 
@@ -660,13 +1696,71 @@ mod tests {
 		});
 	}
 
+	/// Returns account ownership digest of the test vector.
+	fn target_account_test_vector_digest() -> Vec<u8> {
+		let digest = account_ownership_digest(&42u32, 1u64, 1u32, SOURCE_CHAIN_ID, TARGET_CHAIN_ID);
+		assert_eq!(
+			digest,
+			vec![
+				0x2a, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, b's',
+				b'r', b'c', b'e', b't', b'r', b'g', b't',
+			],
+		);
+		digest
+	}
+
+	/// Sign test vector digest with given pair and verify the signature using `MultiSignature`.
+	fn verify_target_account_test_vector<P: Pair>(pair: P) -> bool
+	where
+		MultiSigner: From<P::Public>,
+		MultiSignature: From<P::Signature>,
+	{
+		let digest = target_account_test_vector_digest();
+		let target_account = MultiSigner::from(pair.public()).into_account();
+		MultiSignature::from(pair.sign(&digest)).verify(&digest[..], &target_account)
+	}
+
+	#[test]
+	fn target_account_ownership_may_be_proved_with_any_multi_signature_scheme() {
+		assert!(verify_target_account_test_vector(sr25519::Pair::from_seed(&[1; 32])));
+		assert!(verify_target_account_test_vector(ed25519::Pair::from_seed(&[1; 32])));
+		assert!(verify_target_account_test_vector(ecdsa::Pair::from_seed(&[1; 32])));
+	}
+
+	#[test]
+	fn target_account_ownership_proof_is_scheme_specific() {
+		// the same seed produces different accounts for different schemes, so signature of one
+		// scheme can't be used to prove ownership of account of another scheme
+		let digest = target_account_test_vector_digest();
+		let sr25519_pair = sr25519::Pair::from_seed(&[1; 32]);
+		let ed25519_pair = ed25519::Pair::from_seed(&[1; 32]);
+		let ecdsa_pair = ecdsa::Pair::from_seed(&[1; 32]);
+
+		let sr25519_account = MultiSigner::from(sr25519_pair.public()).into_account();
+		let ed25519_signature = MultiSignature::from(ed25519_pair.sign(&digest));
+		let ecdsa_signature = MultiSignature::from(ecdsa_pair.sign(&digest));
+		assert!(!ed25519_signature.verify(&digest[..], &sr25519_account));
+		assert!(!ecdsa_signature.verify(&digest[..], &sr25519_account));
+
+		// and the signature of other digest (e.g. with different spec version) is rejected
+		let ecdsa_account = MultiSigner::from(ecdsa_pair.public()).into_account();
+		let other_digest = account_ownership_digest(&42u32, 1u64, 2u32, SOURCE_CHAIN_ID, TARGET_CHAIN_ID);
+		assert!(!ecdsa_signature.verify(&other_digest[..], &ecdsa_account));
+	}
+
 	#[test]
 	fn should_emit_event_for_rejected_messages() {
 		new_test_ext().execute_with(|| {
 			let id = [0; 4];
 
 			System::set_block_number(1);
-			Dispatch::dispatch(SOURCE_CHAIN_ID, TARGET_CHAIN_ID, id, Err(()), |_, _| unreachable!());
+			Dispatch::dispatch(
+				SOURCE_CHAIN_ID,
+				TARGET_CHAIN_ID,
+				id,
+				Err(vec![42]),
+				|_, _| unreachable!(),
+			);
 
 			assert_eq!(
 				System::events(),
@@ -693,7 +1787,13 @@ mod tests {
 			message.call.0 = vec![];
 
 			System::set_block_number(1);
-			let result = Dispatch::dispatch(SOURCE_CHAIN_ID, TARGET_CHAIN_ID, id, Ok(message), |_, _| unreachable!());
+			let result = Dispatch::dispatch(
+				SOURCE_CHAIN_ID,
+				TARGET_CHAIN_ID,
+				id,
+				Ok(message.into()),
+				|_, _| unreachable!(),
+			);
 			assert_eq!(result.unspent_weight, weight);
 			assert!(!result.dispatch_result);
 
@@ -722,7 +1822,13 @@ mod tests {
 			message.weight = weight;
 
 			System::set_block_number(1);
-			let result = Dispatch::dispatch(SOURCE_CHAIN_ID, TARGET_CHAIN_ID, id, Ok(message), |_, _| unreachable!());
+			let result = Dispatch::dispatch(
+				SOURCE_CHAIN_ID,
+				TARGET_CHAIN_ID,
+				id,
+				Ok(message.into()),
+				|_, _| unreachable!(),
+			);
 			assert_eq!(result.unspent_weight, weight);
 			assert!(!result.dispatch_result);
 
@@ -751,7 +1857,7 @@ mod tests {
 			message.dispatch_fee_payment = DispatchFeePayment::AtTargetChain;
 
 			System::set_block_number(1);
-			let result = Dispatch::dispatch(SOURCE_CHAIN_ID, TARGET_CHAIN_ID, id, Ok(message), |_, _| Err(()));
+			let result = Dispatch::dispatch(SOURCE_CHAIN_ID, TARGET_CHAIN_ID, id, Ok(message.into()), |_, _| Err(()));
 			assert_eq!(result.unspent_weight, weight);
 			assert!(!result.dispatch_result);
 
@@ -784,7 +1890,7 @@ mod tests {
 			message.dispatch_fee_payment = DispatchFeePayment::AtTargetChain;
 
 			System::set_block_number(1);
-			let result = Dispatch::dispatch(SOURCE_CHAIN_ID, TARGET_CHAIN_ID, id, Ok(message), |_, _| Ok(()));
+			let result = Dispatch::dispatch(SOURCE_CHAIN_ID, TARGET_CHAIN_ID, id, Ok(message.into()), |_, _| Ok(()));
 			assert!(result.dispatch_fee_paid_during_dispatch);
 			assert!(result.dispatch_result);
 
@@ -812,7 +1918,13 @@ mod tests {
 			let message = prepare_target_message(call);
 
 			System::set_block_number(1);
-			let result = Dispatch::dispatch(SOURCE_CHAIN_ID, TARGET_CHAIN_ID, id, Ok(message), |_, _| unreachable!());
+			let result = Dispatch::dispatch(
+				SOURCE_CHAIN_ID,
+				TARGET_CHAIN_ID,
+				id,
+				Ok(message.into()),
+				|_, _| unreachable!(),
+			);
 			assert!(!result.dispatch_fee_paid_during_dispatch);
 			assert!(!result.dispatch_result);
 
@@ -838,7 +1950,13 @@ mod tests {
 			let message = prepare_root_message(Call::System(<frame_system::Call<TestRuntime>>::remark(vec![1, 2, 3])));
 
 			System::set_block_number(1);
-			let result = Dispatch::dispatch(SOURCE_CHAIN_ID, TARGET_CHAIN_ID, id, Ok(message), |_, _| unreachable!());
+			let result = Dispatch::dispatch(
+				SOURCE_CHAIN_ID,
+				TARGET_CHAIN_ID,
+				id,
+				Ok(message.into()),
+				|_, _| unreachable!(),
+			);
 			assert!(!result.dispatch_fee_paid_during_dispatch);
 			assert!(result.dispatch_result);
 
@@ -866,7 +1984,13 @@ mod tests {
 			let message = prepare_target_message(call);
 
 			System::set_block_number(1);
-			let result = Dispatch::dispatch(SOURCE_CHAIN_ID, TARGET_CHAIN_ID, id, Ok(message), |_, _| unreachable!());
+			let result = Dispatch::dispatch(
+				SOURCE_CHAIN_ID,
+				TARGET_CHAIN_ID,
+				id,
+				Ok(message.into()),
+				|_, _| unreachable!(),
+			);
 			assert!(!result.dispatch_fee_paid_during_dispatch);
 			assert!(result.dispatch_result);
 
@@ -894,7 +2018,13 @@ mod tests {
 			let message = prepare_source_message(call);
 
 			System::set_block_number(1);
-			let result = Dispatch::dispatch(SOURCE_CHAIN_ID, TARGET_CHAIN_ID, id, Ok(message), |_, _| unreachable!());
+			let result = Dispatch::dispatch(
+				SOURCE_CHAIN_ID,
+				TARGET_CHAIN_ID,
+				id,
+				Ok(message.into()),
+				|_, _| unreachable!(),
+			);
 			assert!(!result.dispatch_fee_paid_during_dispatch);
 			assert!(result.dispatch_result);
 
@@ -914,12 +2044,168 @@ mod tests {
 	}
 
 	#[test]
-	fn origin_is_checked_when_verifying_sending_message_using_source_root_account() {
-		let call = Call::System(<frame_system::Call<TestRuntime>>::remark(vec![]));
-		let message = prepare_root_message(call);
+	fn should_dispatch_bridge_message_as_allowed_origin() {
+		new_test_ext().execute_with(|| {
+			let id = [0; 4];
 
-		// When message is sent by Root, CallOrigin::SourceRoot is allowed
-		assert!(matches!(verify_message_origin(&RawOrigin::Root, &message), Ok(None)));
+			// `set_heap_pages` may only be called by Root
+			let call = Call::System(<frame_system::Call<TestRuntime>>::set_heap_pages(1));
+			let message = prepare_source_as_message(call);
+
+			assert_ok!(Dispatch::set_dispatch_as_origin(
+				Origin::root(),
+				SOURCE_CHAIN_ID,
+				1,
+				Some(OriginCaller::system(RawOrigin::Root)),
+			));
+
+			System::set_block_number(1);
+			let result = Dispatch::dispatch(
+				SOURCE_CHAIN_ID,
+				TARGET_CHAIN_ID,
+				id,
+				Ok(message.into()),
+				|_, _| unreachable!(),
+			);
+			assert!(!result.dispatch_fee_paid_during_dispatch);
+			assert!(result.dispatch_result);
+
+			assert_eq!(
+				System::events(),
+				vec![EventRecord {
+					phase: Phase::Initialization,
+					event: Event::Dispatch(call_dispatch::Event::<TestRuntime>::MessageDispatched(
+						SOURCE_CHAIN_ID,
+						id,
+						Ok(())
+					)),
+					topics: vec![],
+				}],
+			);
+		})
+	}
+
+	#[test]
+	fn should_reject_bridge_message_if_dispatch_as_origin_is_not_allowed() {
+		new_test_ext().execute_with(|| {
+			let id = [0; 4];
+
+			let call = Call::System(<frame_system::Call<TestRuntime>>::remark(vec![]));
+			let message = prepare_source_as_message(call);
+
+			// the account is only allowed to use the origin when messages are coming from another chain
+			assert_ok!(Dispatch::set_dispatch_as_origin(
+				Origin::root(),
+				TARGET_CHAIN_ID,
+				1,
+				Some(OriginCaller::system(RawOrigin::Root)),
+			));
+
+			System::set_block_number(1);
+			let result = Dispatch::dispatch(
+				SOURCE_CHAIN_ID,
+				TARGET_CHAIN_ID,
+				id,
+				Ok(message.into()),
+				|_, _| unreachable!(),
+			);
+			assert!(!result.dispatch_result);
+
+			assert_eq!(
+				System::events(),
+				vec![EventRecord {
+					phase: Phase::Initialization,
+					event: Event::Dispatch(call_dispatch::Event::<TestRuntime>::MessageDispatchAsOriginNotAllowed(
+						SOURCE_CHAIN_ID,
+						id
+					)),
+					topics: vec![],
+				}],
+			);
+		})
+	}
+
+	#[test]
+	fn dispatch_as_origin_may_only_be_changed_by_root() {
+		new_test_ext().execute_with(|| {
+			let dispatch_as = Some(OriginCaller::system(RawOrigin::Root));
+			assert_noop!(
+				Dispatch::set_dispatch_as_origin(Origin::signed(1), SOURCE_CHAIN_ID, 1, dispatch_as.clone()),
+				BadOrigin,
+			);
+
+			assert_ok!(Dispatch::set_dispatch_as_origin(
+				Origin::root(),
+				SOURCE_CHAIN_ID,
+				1,
+				dispatch_as.clone(),
+			));
+			assert_eq!(Dispatch::dispatch_as_origin(SOURCE_CHAIN_ID, 1), dispatch_as);
+
+			assert_ok!(Dispatch::set_dispatch_as_origin(
+				Origin::root(),
+				SOURCE_CHAIN_ID,
+				1,
+				None
+			));
+			assert_eq!(Dispatch::dispatch_as_origin(SOURCE_CHAIN_ID, 1), None);
+		})
+	}
+
+	#[test]
+	fn should_register_derived_accounts() {
+		new_test_ext().execute_with(|| {
+			let call = Call::System(<frame_system::Call<TestRuntime>>::remark(vec![]));
+			let messages = [
+				prepare_root_message(call.clone()),
+				prepare_source_message(call.clone()),
+				prepare_target_message(call),
+			];
+			for message in messages.iter().cloned() {
+				let result = Dispatch::dispatch(
+					SOURCE_CHAIN_ID,
+					TARGET_CHAIN_ID,
+					[0; 4],
+					Ok(message.into()),
+					|_, _| unreachable!(),
+				);
+				assert!(result.dispatch_result);
+			}
+
+			let root_account =
+				AccountIdConverter::convert(derive_account_id::<AccountId>(SOURCE_CHAIN_ID, SourceAccount::Root));
+			assert_eq!(
+				Dispatch::derived_account_origin(root_account),
+				Some(DerivedAccountOrigin {
+					source_chain: SOURCE_CHAIN_ID,
+					source_account: SourceAccount::Root,
+				}),
+			);
+
+			let source_account = AccountIdConverter::convert(derive_account_id::<AccountId>(
+				SOURCE_CHAIN_ID,
+				SourceAccount::Account(1),
+			));
+			assert_eq!(
+				Dispatch::derived_account_origin(source_account),
+				Some(DerivedAccountOrigin {
+					source_chain: SOURCE_CHAIN_ID,
+					source_account: SourceAccount::Account(1),
+				}),
+			);
+
+			// target accounts are not derived
+			assert_eq!(Dispatch::derived_account_origin(1), None);
+		});
+	}
+
+	#[test]
+	fn origin_is_checked_when_verifying_sending_message_using_source_root_account() {
+		let call = Call::System(<frame_system::Call<TestRuntime>>::remark(vec![]));
+		let message = prepare_root_message(call);
+
+		// When message is sent by Root, CallOrigin::SourceRoot is allowed
+		assert!(matches!(verify_message_origin(&RawOrigin::Root, &message), Ok(None)));
 
 		// when message is sent by some real account, CallOrigin::SourceRoot is not allowed
 		assert!(matches!(
@@ -973,4 +2259,892 @@ mod tests {
 		// The Root account is allowed to assume any expected origin account
 		assert!(matches!(verify_message_origin(&RawOrigin::Root, &message), Ok(Some(1))));
 	}
+
+	#[test]
+	fn origin_is_checked_when_verifying_sending_message_using_source_account_as_origin() {
+		let call = Call::System(<frame_system::Call<TestRuntime>>::remark(vec![]));
+		let message = prepare_source_as_message(call);
+
+		assert!(matches!(
+			verify_message_origin(&RawOrigin::Signed(1), &message),
+			Ok(Some(1))
+		));
+		assert!(matches!(
+			verify_message_origin(&RawOrigin::Signed(2), &message),
+			Err(BadOrigin)
+		));
+		assert!(matches!(verify_message_origin(&RawOrigin::Root, &message), Ok(Some(1))));
+	}
+
+	#[test]
+	fn should_revert_call_if_origin_fails_to_pay_storage_deposit() {
+		new_test_ext().execute_with(|| {
+			let id = [0; 4];
+
+			let call = Call::System(<frame_system::Call<TestRuntime>>::remark_with_event(vec![1, 2, 3]));
+			let origin = CallOrigin::TargetAccount(
+				ACCOUNT_WITHOUT_DEPOSIT_FUNDS,
+				TestAccountPublic(ACCOUNT_WITHOUT_DEPOSIT_FUNDS),
+				TestSignature(ACCOUNT_WITHOUT_DEPOSIT_FUNDS),
+			);
+			let message = prepare_message(origin, call);
+
+			System::set_block_number(1);
+			let result = Dispatch::dispatch(
+				SOURCE_CHAIN_ID,
+				TARGET_CHAIN_ID,
+				id,
+				Ok(message.into()),
+				|_, _| unreachable!(),
+			);
+			assert!(!result.dispatch_result);
+
+			assert_eq!(
+				System::events(),
+				vec![EventRecord {
+					phase: Phase::Initialization,
+					event: Event::Dispatch(call_dispatch::Event::<TestRuntime>::MessageStorageDepositPaymentFailed(
+						SOURCE_CHAIN_ID,
+						id,
+						ACCOUNT_WITHOUT_DEPOSIT_FUNDS,
+					)),
+					topics: vec![],
+				}],
+			);
+		})
+	}
+
+	#[test]
+	fn should_dispatch_call_if_origin_pays_storage_deposit() {
+		new_test_ext().execute_with(|| {
+			let id = [0; 4];
+
+			let call = Call::System(<frame_system::Call<TestRuntime>>::remark_with_event(vec![1, 2, 3]));
+			let message = prepare_target_message(call);
+
+			System::set_block_number(1);
+			let result = Dispatch::dispatch(
+				SOURCE_CHAIN_ID,
+				TARGET_CHAIN_ID,
+				id,
+				Ok(message.into()),
+				|_, _| unreachable!(),
+			);
+			assert!(result.dispatch_result);
+
+			assert_eq!(
+				System::events().pop().map(|record| record.event),
+				Some(Event::Dispatch(call_dispatch::Event::<TestRuntime>::MessageDispatched(
+					SOURCE_CHAIN_ID,
+					id,
+					Ok(())
+				))),
+			);
+		})
+	}
+
+	#[test]
+	fn should_not_store_failed_message_if_disabled() {
+		new_test_ext().execute_with(|| {
+			let id = [0; 4];
+
+			let call = Call::System(<frame_system::Call<TestRuntime>>::set_heap_pages(1));
+			let message = prepare_target_message(call);
+
+			System::set_block_number(1);
+			let result = Dispatch::dispatch(
+				SOURCE_CHAIN_ID,
+				TARGET_CHAIN_ID,
+				id,
+				Ok(message.into()),
+				|_, _| unreachable!(),
+			);
+			assert!(!result.dispatch_result);
+
+			assert_eq!(FailedMessages::<TestRuntime>::get(id), None);
+			assert_eq!(FailedMessagesCount::<DefaultInstance>::get(), 0);
+		})
+	}
+
+	#[test]
+	fn should_store_failed_message_if_enabled() {
+		new_test_ext().execute_with(|| {
+			MaxFailedMessages::set(&1);
+			let id = [0; 4];
+
+			let call = Call::System(<frame_system::Call<TestRuntime>>::set_heap_pages(1));
+			let message = VersionedMessagePayload::from(prepare_target_message(call));
+			let payload_hash = BlakeTwo256::hash_of(&message);
+
+			System::set_block_number(1);
+			let result = Dispatch::dispatch(SOURCE_CHAIN_ID, TARGET_CHAIN_ID, id, Ok(message), |_, _| unreachable!());
+			assert!(!result.dispatch_result);
+
+			assert_eq!(
+				FailedMessages::<TestRuntime>::get(id),
+				Some(FailedMessage {
+					source_chain: SOURCE_CHAIN_ID,
+					target_chain: TARGET_CHAIN_ID,
+					payload_hash,
+					dispatch_fee_paid: true,
+				}),
+			);
+			assert_eq!(FailedMessagesCount::<DefaultInstance>::get(), 1);
+			assert_eq!(
+				System::events().pop().map(|record| record.event),
+				Some(Event::Dispatch(
+					call_dispatch::Event::<TestRuntime>::FailedMessageStored(SOURCE_CHAIN_ID, id,)
+				)),
+			);
+		})
+	}
+
+	#[test]
+	fn should_store_undecodable_message_if_enabled() {
+		new_test_ext().execute_with(|| {
+			MaxFailedMessages::set(&1);
+			MaxDispatchedMessages::set(&1);
+			let id = [0; 4];
+			let payload = vec![1, 2, 3];
+
+			System::set_block_number(1);
+			let result = Dispatch::dispatch(
+				SOURCE_CHAIN_ID,
+				TARGET_CHAIN_ID,
+				id,
+				Err(payload.clone()),
+				|_, _| unreachable!(),
+			);
+			assert!(!result.dispatch_result);
+
+			assert_eq!(
+				FailedMessages::<TestRuntime>::get(id),
+				Some(FailedMessage {
+					source_chain: SOURCE_CHAIN_ID,
+					target_chain: TARGET_CHAIN_ID,
+					payload_hash: BlakeTwo256::hash(&payload),
+					dispatch_fee_paid: false,
+				}),
+			);
+			assert_eq!(FailedMessagesCount::<DefaultInstance>::get(), 1);
+			assert_eq!(
+				System::events().pop().map(|record| record.event),
+				Some(Event::Dispatch(
+					call_dispatch::Event::<TestRuntime>::FailedMessageStored(SOURCE_CHAIN_ID, id)
+				)),
+			);
+
+			// the same message isn't recorded twice
+			Dispatch::dispatch(
+				SOURCE_CHAIN_ID,
+				TARGET_CHAIN_ID,
+				id,
+				Err(payload),
+				|_, _| unreachable!(),
+			);
+			assert_eq!(FailedMessagesCount::<DefaultInstance>::get(), 1);
+		})
+	}
+
+	#[test]
+	fn should_not_store_undecodable_message_if_disabled() {
+		new_test_ext().execute_with(|| {
+			let id = [0; 4];
+
+			System::set_block_number(1);
+			Dispatch::dispatch(
+				SOURCE_CHAIN_ID,
+				TARGET_CHAIN_ID,
+				id,
+				Err(vec![42]),
+				|_, _| unreachable!(),
+			);
+
+			assert_eq!(FailedMessages::<TestRuntime>::get(id), None);
+			assert_eq!(FailedMessagesCount::<DefaultInstance>::get(), 0);
+			assert_eq!(Dispatch::undecodable_message_weight(1), 0);
+		})
+	}
+
+	#[test]
+	fn should_drop_failed_message_if_failed_messages_map_is_full() {
+		new_test_ext().execute_with(|| {
+			MaxFailedMessages::set(&1);
+
+			System::set_block_number(1);
+			for id in &[[0; 4], [1; 4]] {
+				let call = Call::System(<frame_system::Call<TestRuntime>>::set_heap_pages(1));
+				let message = prepare_target_message(call);
+				Dispatch::dispatch(
+					SOURCE_CHAIN_ID,
+					TARGET_CHAIN_ID,
+					*id,
+					Ok(message.into()),
+					|_, _| unreachable!(),
+				);
+			}
+
+			assert!(FailedMessages::<TestRuntime>::get([0; 4]).is_some());
+			assert_eq!(FailedMessages::<TestRuntime>::get([1; 4]), None);
+			assert_eq!(FailedMessagesCount::<DefaultInstance>::get(), 1);
+			assert_eq!(
+				System::events().pop().map(|record| record.event),
+				Some(Event::Dispatch(
+					call_dispatch::Event::<TestRuntime>::FailedMessageDropped(SOURCE_CHAIN_ID, [1; 4],)
+				)),
+			);
+		})
+	}
+
+	#[test]
+	fn should_retry_failed_message() {
+		new_test_ext().execute_with(|| {
+			MaxFailedMessages::set(&1);
+			let id = [0; 4];
+
+			let call = Call::System(<frame_system::Call<TestRuntime>>::remark(vec![1, 2, 3]));
+			let mut message = prepare_target_message(call);
+			message.dispatch_fee_payment = DispatchFeePayment::AtTargetChain;
+			let payload = message.encode();
+
+			System::set_block_number(1);
+			let result = Dispatch::dispatch(SOURCE_CHAIN_ID, TARGET_CHAIN_ID, id, Ok(message.into()), |_, _| Err(()));
+			assert!(!result.dispatch_result);
+			assert!(FailedMessages::<TestRuntime>::get(id).is_some());
+
+			assert_eq!(
+				FailedMessages::<TestRuntime>::get(id).map(|failed_message| failed_message.dispatch_fee_paid),
+				Some(false),
+			);
+
+			assert_ok!(Dispatch::retry_failed_message(Origin::root(), id, payload, TEST_WEIGHT));
+
+			assert!(TestRetryDispatchFeePayment::is_fee_paid(1));
+			assert_eq!(FailedMessages::<TestRuntime>::get(id), None);
+			assert_eq!(FailedMessagesCount::<DefaultInstance>::get(), 0);
+			assert_eq!(
+				System::events().pop().map(|record| record.event),
+				Some(Event::Dispatch(
+					call_dispatch::Event::<TestRuntime>::FailedMessageRetried(SOURCE_CHAIN_ID, id, true,)
+				)),
+			);
+		})
+	}
+
+	#[test]
+	fn should_not_charge_dispatch_fee_again_when_retrying_failed_message() {
+		new_test_ext().execute_with(|| {
+			MaxFailedMessages::set(&1);
+			let id = [0; 4];
+
+			// the call is dispatched, but fails because of bad origin
+			let call = Call::System(<frame_system::Call<TestRuntime>>::set_heap_pages(1));
+			let mut message = prepare_target_message(call);
+			message.dispatch_fee_payment = DispatchFeePayment::AtTargetChain;
+			let payload = message.encode();
+
+			System::set_block_number(1);
+			let result = Dispatch::dispatch(SOURCE_CHAIN_ID, TARGET_CHAIN_ID, id, Ok(message.into()), |_, _| Ok(()));
+			assert!(!result.dispatch_result);
+			assert!(result.dispatch_fee_paid_during_dispatch);
+			assert_eq!(
+				FailedMessages::<TestRuntime>::get(id).map(|failed_message| failed_message.dispatch_fee_paid),
+				Some(true),
+			);
+
+			assert_ok!(Dispatch::retry_failed_message(Origin::root(), id, payload, TEST_WEIGHT));
+
+			assert!(!TestRetryDispatchFeePayment::is_fee_paid(1));
+			assert_eq!(
+				FailedMessages::<TestRuntime>::get(id).map(|failed_message| failed_message.dispatch_fee_paid),
+				Some(true),
+			);
+		})
+	}
+
+	#[test]
+	fn should_store_failed_message_again_if_retry_dispatch_fee_is_not_paid() {
+		new_test_ext().execute_with(|| {
+			MaxFailedMessages::set(&1);
+			let id = [0; 4];
+
+			let call = Call::System(<frame_system::Call<TestRuntime>>::remark(vec![1, 2, 3]));
+			let origin = CallOrigin::TargetAccount(
+				ACCOUNT_WITHOUT_RETRY_FEE_FUNDS,
+				TestAccountPublic(ACCOUNT_WITHOUT_RETRY_FEE_FUNDS),
+				TestSignature(ACCOUNT_WITHOUT_RETRY_FEE_FUNDS),
+			);
+			let mut message = prepare_message(origin, call);
+			message.dispatch_fee_payment = DispatchFeePayment::AtTargetChain;
+			let payload = message.encode();
+
+			System::set_block_number(1);
+			Dispatch::dispatch(SOURCE_CHAIN_ID, TARGET_CHAIN_ID, id, Ok(message.into()), |_, _| Err(()));
+
+			assert_ok!(Dispatch::retry_failed_message(Origin::root(), id, payload, TEST_WEIGHT));
+
+			assert_eq!(
+				FailedMessages::<TestRuntime>::get(id).map(|failed_message| failed_message.dispatch_fee_paid),
+				Some(false),
+			);
+			assert_eq!(FailedMessagesCount::<DefaultInstance>::get(), 1);
+			assert_eq!(
+				System::events().pop().map(|record| record.event),
+				Some(Event::Dispatch(
+					call_dispatch::Event::<TestRuntime>::FailedMessageRetried(SOURCE_CHAIN_ID, id, false,)
+				)),
+			);
+		})
+	}
+
+	#[test]
+	fn should_discard_failed_message() {
+		new_test_ext().execute_with(|| {
+			MaxFailedMessages::set(&1);
+			let id = [0; 4];
+
+			let call = Call::System(<frame_system::Call<TestRuntime>>::set_heap_pages(1));
+			let message = prepare_target_message(call);
+
+			System::set_block_number(1);
+			Dispatch::dispatch(
+				SOURCE_CHAIN_ID,
+				TARGET_CHAIN_ID,
+				id,
+				Ok(message.into()),
+				|_, _| unreachable!(),
+			);
+
+			assert_noop!(Dispatch::discard_failed_message(Origin::signed(1), id), BadOrigin);
+			assert_noop!(
+				Dispatch::discard_failed_message(Origin::root(), [1; 4]),
+				Error::<TestRuntime, DefaultInstance>::FailedMessageNotFound,
+			);
+
+			assert_ok!(Dispatch::discard_failed_message(Origin::root(), id));
+			assert_eq!(FailedMessages::<TestRuntime>::get(id), None);
+			assert_eq!(FailedMessagesCount::<DefaultInstance>::get(), 0);
+			assert_eq!(
+				System::events().pop().map(|record| record.event),
+				Some(Event::Dispatch(
+					call_dispatch::Event::<TestRuntime>::FailedMessageDiscarded(SOURCE_CHAIN_ID, id)
+				)),
+			);
+		})
+	}
+
+	#[test]
+	fn dispatch_weight_includes_failed_message_recording_weight() {
+		new_test_ext().execute_with(|| {
+			let call = Call::System(<frame_system::Call<TestRuntime>>::remark(vec![1, 2, 3]));
+			let message = VersionedMessagePayload::from(prepare_root_message(call));
+			PayloadHashingByteWeight::set(&10);
+			assert_eq!(Dispatch::dispatch_weight(&message), TEST_WEIGHT);
+
+			MaxFailedMessages::set(&1);
+			assert_eq!(
+				Dispatch::dispatch_weight(&message),
+				TEST_WEIGHT + 10 * message.encoded_size() as Weight,
+			);
+		})
+	}
+
+	#[test]
+	fn should_reject_invalid_failed_message_retry() {
+		new_test_ext().execute_with(|| {
+			MaxFailedMessages::set(&1);
+			let id = [0; 4];
+
+			let call = Call::System(<frame_system::Call<TestRuntime>>::set_heap_pages(1));
+			let message = prepare_target_message(call);
+			let payload = message.encode();
+
+			System::set_block_number(1);
+			Dispatch::dispatch(
+				SOURCE_CHAIN_ID,
+				TARGET_CHAIN_ID,
+				id,
+				Ok(message.into()),
+				|_, _| unreachable!(),
+			);
+
+			assert_noop!(
+				Dispatch::retry_failed_message(Origin::signed(1), id, payload.clone(), TEST_WEIGHT),
+				BadOrigin,
+			);
+			assert_noop!(
+				Dispatch::retry_failed_message(Origin::root(), [1; 4], payload.clone(), TEST_WEIGHT),
+				Error::<TestRuntime, DefaultInstance>::FailedMessageNotFound,
+			);
+			assert_noop!(
+				Dispatch::retry_failed_message(Origin::root(), id, vec![42], TEST_WEIGHT),
+				Error::<TestRuntime, DefaultInstance>::FailedMessagePayloadMismatch,
+			);
+			assert_noop!(
+				Dispatch::retry_failed_message(Origin::root(), id, payload, TEST_WEIGHT - 1),
+				Error::<TestRuntime, DefaultInstance>::DispatchWeightTooLow,
+			);
+		})
+	}
+
+	#[test]
+	fn should_dispatch_legacy_and_versioned_payloads() {
+		new_test_ext().execute_with(|| {
+			let call = Call::System(<frame_system::Call<TestRuntime>>::remark(vec![1, 2, 3]));
+			let legacy_payload = prepare_root_message(call.clone()).encode();
+			let versioned_payload = VersionedMessagePayload::from(prepare_root_message(call)).encode();
+
+			System::set_block_number(1);
+			for (id, payload) in vec![([0; 4], legacy_payload), ([1; 4], versioned_payload)] {
+				let message = Decode::decode(&mut &payload[..]).map_err(drop);
+				let result = Dispatch::dispatch(SOURCE_CHAIN_ID, TARGET_CHAIN_ID, id, message, |_, _| unreachable!());
+				assert!(result.dispatch_result);
+			}
+		})
+	}
+
+	#[test]
+	fn should_execute_xcm_message() {
+		new_test_ext().execute_with(|| {
+			let id = [0; 4];
+			let message = prepare_xcm_message(vec![1; 10]);
+
+			System::set_block_number(1);
+			let result = Dispatch::dispatch(
+				SOURCE_CHAIN_ID,
+				TARGET_CHAIN_ID,
+				id,
+				Ok(VersionedMessagePayload::Xcm(message)),
+				|_, _| Ok(()),
+			);
+			assert!(result.dispatch_result);
+			assert!(result.dispatch_fee_paid_during_dispatch);
+			assert_eq!(result.unspent_weight, TEST_WEIGHT - 10);
+
+			let origin_account = AccountIdConverter::convert(derive_account_id::<AccountId>(
+				SOURCE_CHAIN_ID,
+				SourceAccount::Account(1),
+			));
+			assert_eq!(
+				Dispatch::derived_account_origin(origin_account),
+				Some(DerivedAccountOrigin {
+					source_chain: SOURCE_CHAIN_ID,
+					source_account: SourceAccount::Account(1),
+				}),
+			);
+			assert_eq!(
+				System::events(),
+				vec![EventRecord {
+					phase: Phase::Initialization,
+					event: Event::Dispatch(call_dispatch::Event::<TestRuntime>::MessageDispatched(
+						SOURCE_CHAIN_ID,
+						id,
+						Ok(())
+					)),
+					topics: vec![],
+				}],
+			);
+		})
+	}
+
+	#[test]
+	fn should_return_dispatch_failed_flag_if_xcm_execution_failed() {
+		new_test_ext().execute_with(|| {
+			let id = [0; 4];
+			let message = prepare_xcm_message(vec![0; 10]);
+
+			System::set_block_number(1);
+			let result = Dispatch::dispatch(
+				SOURCE_CHAIN_ID,
+				TARGET_CHAIN_ID,
+				id,
+				Ok(VersionedMessagePayload::Xcm(message)),
+				|_, _| Ok(()),
+			);
+			assert!(!result.dispatch_result);
+			assert_eq!(result.unspent_weight, TEST_WEIGHT - 10);
+		})
+	}
+
+	#[test]
+	fn should_reject_xcm_message_that_cant_be_weighed() {
+		new_test_ext().execute_with(|| {
+			let id = [0; 4];
+			let message = prepare_xcm_message(vec![]);
+
+			System::set_block_number(1);
+			let result = Dispatch::dispatch(
+				SOURCE_CHAIN_ID,
+				TARGET_CHAIN_ID,
+				id,
+				Ok(VersionedMessagePayload::Xcm(message)),
+				|_, _| unreachable!(),
+			);
+			assert!(!result.dispatch_result);
+			assert_eq!(result.unspent_weight, TEST_WEIGHT);
+
+			assert_eq!(
+				System::events(),
+				vec![EventRecord {
+					phase: Phase::Initialization,
+					event: Event::Dispatch(call_dispatch::Event::<TestRuntime>::MessageXcmRejected(
+						SOURCE_CHAIN_ID,
+						id
+					)),
+					topics: vec![],
+				}],
+			);
+		})
+	}
+
+	#[test]
+	fn should_fail_on_xcm_weight_mismatch() {
+		new_test_ext().execute_with(|| {
+			let id = [0; 4];
+			let mut message = prepare_xcm_message(vec![1; 10]);
+			message.weight = 7;
+
+			System::set_block_number(1);
+			let result = Dispatch::dispatch(
+				SOURCE_CHAIN_ID,
+				TARGET_CHAIN_ID,
+				id,
+				Ok(VersionedMessagePayload::Xcm(message)),
+				|_, _| unreachable!(),
+			);
+			assert!(!result.dispatch_result);
+			assert_eq!(result.unspent_weight, 7);
+
+			assert_eq!(
+				System::events(),
+				vec![EventRecord {
+					phase: Phase::Initialization,
+					event: Event::Dispatch(call_dispatch::Event::<TestRuntime>::MessageWeightMismatch(
+						SOURCE_CHAIN_ID,
+						id,
+						10,
+						7,
+					)),
+					topics: vec![],
+				}],
+			);
+		})
+	}
+
+	#[test]
+	fn should_send_dispatch_acknowledgement_if_requested() {
+		new_test_ext().execute_with(|| {
+			let id = [0; 4];
+			let call = Call::System(<frame_system::Call<TestRuntime>>::remark(vec![1, 2, 3]));
+			let message = VersionedMessagePayload::V2 {
+				payload: prepare_root_message(call),
+				acknowledge_dispatch: true,
+			};
+			assert_eq!(
+				Dispatch::dispatch_weight(&message),
+				TEST_WEIGHT + TEST_ACKNOWLEDGEMENT_WEIGHT
+			);
+
+			System::set_block_number(1);
+			let result = Dispatch::dispatch(SOURCE_CHAIN_ID, TARGET_CHAIN_ID, id, Ok(message), |_, _| unreachable!());
+			assert!(result.dispatch_result);
+
+			assert_eq!(
+				TestAcknowledgementSender::last_sent_acknowledgement(),
+				Some((
+					SOURCE_CHAIN_ID,
+					DispatchAcknowledgement {
+						message_id: id,
+						dispatch_result: true,
+					}
+				)),
+			);
+			assert_eq!(
+				System::events().pop().map(|record| record.event),
+				Some(Event::Dispatch(
+					call_dispatch::Event::<TestRuntime>::DispatchAcknowledgementSent(SOURCE_CHAIN_ID, id)
+				)),
+			);
+		})
+	}
+
+	#[test]
+	fn should_acknowledge_failed_dispatch() {
+		new_test_ext().execute_with(|| {
+			let id = [0; 4];
+			let call = Call::System(<frame_system::Call<TestRuntime>>::set_heap_pages(1));
+			let message = VersionedMessagePayload::V2 {
+				payload: prepare_target_message(call),
+				acknowledge_dispatch: true,
+			};
+
+			System::set_block_number(1);
+			let result = Dispatch::dispatch(SOURCE_CHAIN_ID, TARGET_CHAIN_ID, id, Ok(message), |_, _| unreachable!());
+			assert!(!result.dispatch_result);
+
+			assert_eq!(
+				TestAcknowledgementSender::last_sent_acknowledgement(),
+				Some((
+					SOURCE_CHAIN_ID,
+					DispatchAcknowledgement {
+						message_id: id,
+						dispatch_result: false,
+					}
+				)),
+			);
+		})
+	}
+
+	#[test]
+	fn should_not_send_dispatch_acknowledgement_if_not_requested() {
+		new_test_ext().execute_with(|| {
+			let id = [0; 4];
+			let call = Call::System(<frame_system::Call<TestRuntime>>::remark(vec![1, 2, 3]));
+			let message = VersionedMessagePayload::from(prepare_root_message(call));
+			assert_eq!(Dispatch::dispatch_weight(&message), TEST_WEIGHT);
+
+			System::set_block_number(1);
+			let result = Dispatch::dispatch(SOURCE_CHAIN_ID, TARGET_CHAIN_ID, id, Ok(message), |_, _| unreachable!());
+			assert!(result.dispatch_result);
+			assert_eq!(TestAcknowledgementSender::last_sent_acknowledgement(), None);
+		})
+	}
+
+	fn dispatch_remark(source_chain: ChainId, id: MessageId) -> MessageDispatchResult {
+		let call = Call::System(<frame_system::Call<TestRuntime>>::remark(vec![1, 2, 3]));
+		let message = VersionedMessagePayload::from(prepare_root_message(call));
+		Dispatch::dispatch(source_chain, TARGET_CHAIN_ID, id, Ok(message), |_, _| unreachable!())
+	}
+
+	#[test]
+	fn should_not_track_dispatched_messages_if_disabled() {
+		new_test_ext().execute_with(|| {
+			let id = [0; 4];
+
+			System::set_block_number(1);
+			assert!(dispatch_remark(SOURCE_CHAIN_ID, id).dispatch_result);
+			assert!(dispatch_remark(SOURCE_CHAIN_ID, id).dispatch_result);
+			assert_eq!(DispatchedMessages::<TestRuntime>::get((SOURCE_CHAIN_ID, id)), None);
+		})
+	}
+
+	#[test]
+	fn should_reject_already_dispatched_message() {
+		new_test_ext().execute_with(|| {
+			MaxDispatchedMessages::set(&4);
+			let id = [0; 4];
+
+			System::set_block_number(1);
+			assert!(dispatch_remark(SOURCE_CHAIN_ID, id).dispatch_result);
+
+			// lane storage is migrated and the same message is delivered again
+			let result = dispatch_remark(SOURCE_CHAIN_ID, id);
+			assert!(!result.dispatch_result);
+			assert_eq!(result.unspent_weight, TEST_WEIGHT);
+			assert!(!result.dispatch_fee_paid_during_dispatch);
+			assert_eq!(
+				System::events().pop().map(|record| record.event),
+				Some(Event::Dispatch(
+					call_dispatch::Event::<TestRuntime>::MessageAlreadyDispatched(SOURCE_CHAIN_ID, id)
+				)),
+			);
+		})
+	}
+
+	#[test]
+	fn should_dispatch_messages_with_same_id_from_different_chains() {
+		new_test_ext().execute_with(|| {
+			MaxDispatchedMessages::set(&4);
+			let id = [0; 4];
+
+			System::set_block_number(1);
+			assert!(dispatch_remark(SOURCE_CHAIN_ID, id).dispatch_result);
+			assert!(dispatch_remark(*b"othr", id).dispatch_result);
+		})
+	}
+
+	#[test]
+	fn should_prune_oldest_dispatched_messages() {
+		new_test_ext().execute_with(|| {
+			MaxDispatchedMessages::set(&2);
+
+			System::set_block_number(1);
+			assert!(dispatch_remark(SOURCE_CHAIN_ID, [1; 4]).dispatch_result);
+			assert!(dispatch_remark(SOURCE_CHAIN_ID, [2; 4]).dispatch_result);
+			assert!(dispatch_remark(SOURCE_CHAIN_ID, [3; 4]).dispatch_result);
+
+			// record of the first message has been pruned
+			assert_eq!(DispatchedMessages::<TestRuntime>::get((SOURCE_CHAIN_ID, [1; 4])), None);
+			assert_eq!(
+				DispatchedMessages::<TestRuntime>::get((SOURCE_CHAIN_ID, [2; 4])),
+				Some(1)
+			);
+			assert_eq!(
+				DispatchedMessages::<TestRuntime>::get((SOURCE_CHAIN_ID, [3; 4])),
+				Some(0)
+			);
+			assert_eq!(DispatchedMessagesQueueHead::<DefaultInstance>::get(), 1);
+
+			assert!(!dispatch_remark(SOURCE_CHAIN_ID, [3; 4]).dispatch_result);
+			assert!(dispatch_remark(SOURCE_CHAIN_ID, [1; 4]).dispatch_result);
+		})
+	}
+
+	#[test]
+	fn should_keep_rejecting_dispatched_messages_after_limit_is_decreased() {
+		new_test_ext().execute_with(|| {
+			MaxDispatchedMessages::set(&4);
+
+			System::set_block_number(1);
+			assert!(dispatch_remark(SOURCE_CHAIN_ID, [1; 4]).dispatch_result);
+			assert!(dispatch_remark(SOURCE_CHAIN_ID, [2; 4]).dispatch_result);
+			assert!(dispatch_remark(SOURCE_CHAIN_ID, [3; 4]).dispatch_result);
+
+			// runtime upgrade decreases the limit
+			MaxDispatchedMessages::set(&2);
+			assert!(dispatch_remark(SOURCE_CHAIN_ID, [4; 4]).dispatch_result);
+			assert_eq!(DispatchedMessages::<TestRuntime>::get((SOURCE_CHAIN_ID, [1; 4])), None);
+			assert_eq!(DispatchedMessagesQueueHead::<DefaultInstance>::get(), 1);
+
+			assert!(!dispatch_remark(SOURCE_CHAIN_ID, [2; 4]).dispatch_result);
+			assert!(!dispatch_remark(SOURCE_CHAIN_ID, [3; 4]).dispatch_result);
+			assert!(!dispatch_remark(SOURCE_CHAIN_ID, [4; 4]).dispatch_result);
+			assert!(dispatch_remark(SOURCE_CHAIN_ID, [1; 4]).dispatch_result);
+		})
+	}
+
+	#[test]
+	fn should_retry_failed_message_if_dispatched_messages_are_tracked() {
+		new_test_ext().execute_with(|| {
+			MaxFailedMessages::set(&1);
+			MaxDispatchedMessages::set(&2);
+			let id = [0; 4];
+
+			let call = Call::System(<frame_system::Call<TestRuntime>>::remark(vec![1, 2, 3]));
+			let mut message = prepare_target_message(call);
+			message.dispatch_fee_payment = DispatchFeePayment::AtTargetChain;
+			let payload = message.encode();
+
+			System::set_block_number(1);
+			let result = Dispatch::dispatch(SOURCE_CHAIN_ID, TARGET_CHAIN_ID, id, Ok(message.into()), |_, _| Err(()));
+			assert!(!result.dispatch_result);
+			assert_eq!(DispatchedMessages::<TestRuntime>::get((SOURCE_CHAIN_ID, id)), Some(0));
+
+			assert_ok!(Dispatch::retry_failed_message(Origin::root(), id, payload, TEST_WEIGHT));
+			assert_eq!(
+				System::events().pop().map(|record| record.event),
+				Some(Event::Dispatch(
+					call_dispatch::Event::<TestRuntime>::FailedMessageRetried(SOURCE_CHAIN_ID, id, true,)
+				)),
+			);
+
+			// the retried message occupies new slot and its old slot is not pruned
+			assert_eq!(DispatchedMessages::<TestRuntime>::get((SOURCE_CHAIN_ID, id)), Some(1));
+			assert!(dispatch_remark(SOURCE_CHAIN_ID, [1; 4]).dispatch_result);
+			assert_eq!(DispatchedMessages::<TestRuntime>::get((SOURCE_CHAIN_ID, id)), Some(1));
+			assert!(!dispatch_remark(SOURCE_CHAIN_ID, id).dispatch_result);
+		})
+	}
+
+	#[test]
+	fn consumer_references_deposit_meter_reserves_deposit_for_added_consumers() {
+		type Meter = ConsumerReferencesDepositMeter<TestRuntime, Balances, DepositPerConsumer>;
+
+		new_test_ext().execute_with(|| {
+			let account = 1;
+			let _ = <Balances as frame_support::traits::Currency<AccountId>>::deposit_creating(&account, 100);
+
+			let snapshot = Meter::snapshot(&account);
+			assert_ok!(System::inc_consumers(&account));
+			assert_ok!(System::inc_consumers(&account));
+			assert_eq!(Meter::charge_deposit(&account, snapshot), Ok(()));
+			assert_eq!(
+				Balances::reserved_balance_named(&STORAGE_DEPOSIT_RESERVE_ID, &account),
+				2 * DepositPerConsumer::get(),
+			);
+
+			let snapshot = Meter::snapshot(&account);
+			System::dec_consumers(&account);
+			assert_eq!(Meter::charge_deposit(&account, snapshot), Ok(()));
+			assert_eq!(
+				Balances::reserved_balance_named(&STORAGE_DEPOSIT_RESERVE_ID, &account),
+				DepositPerConsumer::get(),
+			);
+		})
+	}
+
+	#[test]
+	fn consumer_references_deposit_meter_fails_if_account_is_unable_to_pay_deposit() {
+		type Meter = ConsumerReferencesDepositMeter<TestRuntime, Balances, DepositPerConsumer>;
+
+		new_test_ext().execute_with(|| {
+			let account = 1;
+			let _ = <Balances as frame_support::traits::Currency<AccountId>>::deposit_creating(
+				&account,
+				DepositPerConsumer::get() - 1,
+			);
+
+			let snapshot = Meter::snapshot(&account);
+			assert_ok!(System::inc_consumers(&account));
+			assert_eq!(Meter::charge_deposit(&account, snapshot), Err(()));
+		})
+	}
+
+	#[test]
+	fn receive_dispatch_acknowledgement_works() {
+		new_test_ext().execute_with(|| {
+			System::set_block_number(1);
+			assert_ok!(Dispatch::receive_dispatch_acknowledgement(
+				Origin::signed(ACKNOWLEDGEMENT_ACCOUNT),
+				DispatchAcknowledgement {
+					message_id: [1; 4],
+					dispatch_result: true,
+				},
+			));
+
+			assert_eq!(
+				System::events().pop().map(|record| record.event),
+				Some(Event::Dispatch(
+					call_dispatch::Event::<TestRuntime>::DispatchAcknowledgementReceived(SOURCE_CHAIN_ID, [1; 4], true)
+				)),
+			);
+		})
+	}
+
+	#[test]
+	fn receive_dispatch_acknowledgement_rejects_unknown_origin() {
+		new_test_ext().execute_with(|| {
+			let acknowledgement = DispatchAcknowledgement {
+				message_id: [1; 4],
+				dispatch_result: true,
+			};
+			assert_noop!(
+				Dispatch::receive_dispatch_acknowledgement(Origin::root(), acknowledgement.clone()),
+				DispatchError::BadOrigin,
+			);
+			assert_noop!(
+				Dispatch::receive_dispatch_acknowledgement(
+					Origin::signed(ACKNOWLEDGEMENT_ACCOUNT + 1),
+					acknowledgement
+				),
+				DispatchError::BadOrigin,
+			);
+		})
+	}
+
+	#[test]
+	fn receive_dispatch_acknowledgement_call_is_encoded_correctly() {
+		let acknowledgement = DispatchAcknowledgement {
+			message_id: [1; 4],
+			dispatch_result: false,
+		};
+		let call = Call::Dispatch(call_dispatch::Call::<TestRuntime>::receive_dispatch_acknowledgement(
+			acknowledgement.clone(),
+		))
+		.encode();
+		assert_eq!(
+			encode_receive_dispatch_acknowledgement_call(call[0], acknowledgement),
+			call
+		);
+	}
 }
@@ -0,0 +1,502 @@
+// Copyright 2019-2021 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Runtime module that settles fees of forwarded messages between lane pots.
+//!
+//! When the hub chain forwards message to the next hop bridge, the next hop fee is paid by the
+//! hub from the pot of the inbound lane. The module, deployed at the hub, records this fee as the
+//! debt of the originating chain lane. Every `ReconciliationPeriod` blocks, the hub sends
+//! reconciliation messages to originating chains, requesting them to pay accumulated debts.
+//!
+//! The same module, deployed at the originating chain, receives reconciliation (the `settle` call,
+//! dispatched by the bridge) and pays the debt in the common asset (`Config::Currency`) from the
+//! lane pot to the hub account. Lane pots at originating chains are filled by lane owners from fees
+//! that are paid by users when sending messages, so end users only pay once - at the originating
+//! chain. If there are not enough funds in the pot, the rest of debt is paid on next reconciliation.
+//!
+//! Reconciliations that the hub has failed to send are moved to the separate set and retried after
+//! all other pending reconciliations are sent, so they never block reconciliations of other lanes.
+//!
+//! Reconciliation messages are sent by the hub on behalf of the reconciliation account (see
+//! `reconciliation_account_id`). At the originating chain, the `EnsureSettlementAccount` may be
+//! used as the settlement origin, accepting only calls dispatched on behalf of that account.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use bp_messages::LaneId;
+use bp_runtime::ChainId;
+use codec::Encode;
+use frame_support::{
+	traits::{Currency, EnsureOrigin, ExistenceRequirement, Get},
+	weights::Weight,
+};
+use frame_system::RawOrigin;
+use sp_core::{hashing::blake2_256, H256};
+use sp_runtime::traits::{Convert, Saturating, Zero};
+use sp_std::{marker::PhantomData, prelude::*};
+
+#[cfg(test)]
+mod mock;
+
+/// A unique prefix for entropy when generating lane pot account IDs.
+pub const LANE_POT_ACCOUNT_DERIVATION_PREFIX: &[u8] = b"pallet-bridge/account-derivation/lane-pot";
+/// A unique prefix for entropy when generating reconciliation account IDs.
+pub const RECONCILIATION_ACCOUNT_DERIVATION_PREFIX: &[u8] = b"pallet-bridge/account-derivation/reconciliation";
+/// Index of the `settle` call in the pallet calls enum.
+pub const SETTLE_CALL_INDEX: u8 = 0;
+
+/// Balance type of the common asset.
+pub type BalanceOf<T, I> = <<T as Config<I>>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
+
+/// Something that is able to send reconciliation messages to originating chains.
+pub trait ReconciliationSender<Balance> {
+	/// Send message to the `chain`, requesting it to pay `amount` from the pot of given lane.
+	///
+	/// Returns weight of the send operation.
+	fn send_reconciliation(chain: ChainId, lane: LaneId, amount: Balance) -> Result<Weight, &'static str>;
+}
+
+impl<Balance> ReconciliationSender<Balance> for () {
+	fn send_reconciliation(_chain: ChainId, _lane: LaneId, _amount: Balance) -> Result<Weight, &'static str> {
+		Err("Reconciliation messages are not supported")
+	}
+}
+
+/// Settlement origin that only accepts signed origin of the `Account`.
+///
+/// At the originating chain, the `Account` is the account that is used to dispatch reconciliation
+/// messages from the hub chain, derived from the hub reconciliation account.
+pub struct EnsureSettlementAccount<AccountId, Account>(PhantomData<(AccountId, Account)>);
+
+impl<O, AccountId, Account> EnsureOrigin<O> for EnsureSettlementAccount<AccountId, Account>
+where
+	O: Into<Result<RawOrigin<AccountId>, O>> + From<RawOrigin<AccountId>>,
+	AccountId: PartialEq + Clone,
+	Account: Get<AccountId>,
+{
+	type Success = AccountId;
+
+	fn try_origin(o: O) -> Result<Self::Success, O> {
+		o.into().and_then(|o| match o {
+			RawOrigin::Signed(ref who) if *who == Account::get() => Ok(who.clone()),
+			r => Err(O::from(r)),
+		})
+	}
+
+	#[cfg(feature = "runtime-benchmarks")]
+	fn successful_origin() -> O {
+		O::from(RawOrigin::Signed(Account::get()))
+	}
+}
+
+/// Derive the account ID that is sending reconciliation messages from the given hub chain.
+pub fn reconciliation_account_id(hub_chain: ChainId) -> H256 {
+	(RECONCILIATION_ACCOUNT_DERIVATION_PREFIX, hub_chain)
+		.using_encoded(blake2_256)
+		.into()
+}
+
+/// Encode the `settle` call of the pallet, deployed at the `pallet_index` of the (bridged) runtime.
+///
+/// The `amount` must be encoded using the balance type of the bridged runtime.
+pub fn encode_settle_call<Balance: Encode>(pallet_index: u8, lane: LaneId, amount: Balance) -> Vec<u8> {
+	(pallet_index, SETTLE_CALL_INDEX, lane, amount).encode()
+}
+
+pub use pallet::*;
+
+// comes from #[pallet::event]
+#[allow(clippy::unused_unit)]
+#[frame_support::pallet]
+pub mod pallet {
+	use super::*;
+	use frame_support::pallet_prelude::*;
+	use frame_system::pallet_prelude::*;
+
+	#[pallet::config]
+	pub trait Config<I: 'static = ()>: frame_system::Config {
+		/// The overarching event type.
+		type Event: From<Event<Self, I>> + IsType<<Self as frame_system::Config>::Event>;
+		/// The common asset that is used to settle fees.
+		type Currency: Currency<Self::AccountId>;
+		/// Converter from derived lane pot account ID to this chain account ID.
+		type AccountIdConverter: Convert<H256, Self::AccountId>;
+		/// Identifier of the hub chain, which is receiving fees that are settled by this pallet instance.
+		#[pallet::constant]
+		type HubChainId: Get<ChainId>;
+		/// Origin of reconciliation messages. Returns account that receives settled fees.
+		type SettlementOrigin: EnsureOrigin<Self::Origin, Success = Self::AccountId>;
+		/// Reconciliation messages sender.
+		type ReconciliationSender: ReconciliationSender<BalanceOf<Self, I>>;
+		/// Interval (in blocks) between reconciliation messages.
+		#[pallet::constant]
+		type ReconciliationPeriod: Get<Self::BlockNumber>;
+		/// Maximal number of reconciliation messages that are sent in single block.
+		#[pallet::constant]
+		type MaxReconciliationsPerBlock: Get<u32>;
+	}
+
+	#[pallet::pallet]
+	#[pallet::generate_store(pub(super) trait Store)]
+	pub struct Pallet<T, I = ()>(PhantomData<(T, I)>);
+
+	#[pallet::hooks]
+	impl<T: Config<I>, I: 'static> Hooks<BlockNumberFor<T>> for Pallet<T, I> {
+		fn on_initialize(block_number: T::BlockNumber) -> Weight {
+			let period = T::ReconciliationPeriod::get();
+			if period.is_zero() || !(block_number % period).is_zero() {
+				return 0;
+			}
+
+			send_reconciliations::<T, I>()
+		}
+	}
+
+	#[pallet::call]
+	impl<T: Config<I>, I: 'static> Pallet<T, I> {
+		/// Pay debt of the lane from the lane pot.
+		///
+		/// May only be called by the settlement origin (i.e. by the reconciliation message from
+		/// the hub chain). The `amount` is added to the outstanding debt of the lane and then the
+		/// debt is paid using funds of the lane pot.
+		#[pallet::weight(T::DbWeight::get().reads_writes(3, 3))]
+		pub fn settle(origin: OriginFor<T>, lane: LaneId, amount: BalanceOf<T, I>) -> DispatchResultWithPostInfo {
+			let receiver = T::SettlementOrigin::ensure_origin(origin)?;
+
+			let debt = OutstandingDebts::<T, I>::get(lane).saturating_add(amount);
+			let pot = Self::lane_pot_account_id(T::HubChainId::get(), lane);
+			let paid = sp_std::cmp::min(debt, T::Currency::free_balance(&pot));
+			if !paid.is_zero() {
+				T::Currency::transfer(&pot, &receiver, paid, ExistenceRequirement::AllowDeath)?;
+			}
+
+			let remaining = debt - paid;
+			if remaining.is_zero() {
+				OutstandingDebts::<T, I>::remove(lane);
+			} else {
+				OutstandingDebts::<T, I>::insert(lane, remaining);
+			}
+
+			log::trace!(
+				target: "runtime::bridge-fee-settlement",
+				"Settled debt of lane {:?}: paid={:?}, remaining={:?}",
+				lane,
+				paid,
+				remaining,
+			);
+
+			Self::deposit_event(Event::DebtSettled(lane, paid, remaining));
+
+			Ok(().into())
+		}
+	}
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	#[pallet::metadata(BalanceOf<T, I> = "Balance")]
+	pub enum Event<T: Config<I>, I: 'static = ()> {
+		/// Reconciliation message has been sent to the chain. \[chain, lane, amount\]
+		ReconciliationSent(ChainId, LaneId, BalanceOf<T, I>),
+		/// Debt of the lane has been (partially) paid. \[lane, paid, remaining\]
+		DebtSettled(LaneId, BalanceOf<T, I>, BalanceOf<T, I>),
+	}
+
+	/// Fees that have been paid by this (hub) chain on behalf of lanes of other chains and that
+	/// are not yet reconciled.
+	#[pallet::storage]
+	#[pallet::getter(fn pending_reconciliation)]
+	pub type PendingReconciliations<T: Config<I>, I: 'static = ()> =
+		StorageDoubleMap<_, Blake2_128Concat, ChainId, Blake2_128Concat, LaneId, BalanceOf<T, I>, ValueQuery>;
+
+	/// Reconciliations that this (hub) chain has failed to send. They're retried when there are no
+	/// other pending reconciliations.
+	#[pallet::storage]
+	#[pallet::getter(fn failed_reconciliation)]
+	pub type FailedReconciliations<T: Config<I>, I: 'static = ()> =
+		StorageDoubleMap<_, Blake2_128Concat, ChainId, Blake2_128Concat, LaneId, BalanceOf<T, I>, ValueQuery>;
+
+	/// Debts of this chain lanes to the hub chain that have not been paid because of insufficient
+	/// funds of lane pots.
+	#[pallet::storage]
+	#[pallet::getter(fn outstanding_debt)]
+	pub type OutstandingDebts<T: Config<I>, I: 'static = ()> =
+		StorageMap<_, Blake2_128Concat, LaneId, BalanceOf<T, I>, ValueQuery>;
+
+	impl<T: Config<I>, I: 'static> Pallet<T, I> {
+		/// Account of the pot that pays fees of messages, sent over the lane to the hub chain.
+		pub fn lane_pot_account_id(chain: ChainId, lane: LaneId) -> T::AccountId {
+			let encoded_id = (LANE_POT_ACCOUNT_DERIVATION_PREFIX, chain, lane).using_encoded(blake2_256);
+			T::AccountIdConverter::convert(encoded_id.into())
+		}
+
+		/// Record fee that has been paid by this (hub) chain on behalf of the lane of other chain.
+		pub fn note_forwarding_fee(chain: ChainId, lane: LaneId, fee: BalanceOf<T, I>) {
+			PendingReconciliations::<T, I>::mutate(chain, lane, |pending| *pending = pending.saturating_add(fee));
+		}
+	}
+}
+
+/// Send reconciliation messages for at most `MaxReconciliationsPerBlock` pending reconciliations.
+///
+/// New pending reconciliations are sent first. Reconciliations that we have failed to send are moved to
+/// the `FailedReconciliations` and are retried if there are no other pending reconciliations.
+///
+/// Returns weight that has been spent.
+fn send_reconciliations<T: Config<I>, I: 'static>() -> Weight {
+	let db_weight = T::DbWeight::get();
+	let max_reconciliations = T::MaxReconciliationsPerBlock::get() as usize;
+	let pending = PendingReconciliations::<T, I>::iter()
+		.take(max_reconciliations)
+		.collect::<Vec<_>>();
+	let failed = FailedReconciliations::<T, I>::iter()
+		.take(max_reconciliations - pending.len())
+		.collect::<Vec<_>>();
+
+	let mut spent_weight = db_weight.reads(pending.len() as u64 + failed.len() as u64 + 2);
+	for (chain, lane, amount) in pending {
+		PendingReconciliations::<T, I>::remove(chain, lane);
+		spent_weight = spent_weight.saturating_add(db_weight.writes(1));
+
+		match send_reconciliation::<T, I>(chain, lane, amount) {
+			Ok(send_weight) => spent_weight = spent_weight.saturating_add(send_weight),
+			Err(_) => {
+				FailedReconciliations::<T, I>::mutate(chain, lane, |failed_amount| {
+					*failed_amount = failed_amount.saturating_add(amount)
+				});
+				spent_weight = spent_weight.saturating_add(db_weight.reads_writes(1, 1));
+			}
+		}
+	}
+	for (chain, lane, amount) in failed {
+		if let Ok(send_weight) = send_reconciliation::<T, I>(chain, lane, amount) {
+			FailedReconciliations::<T, I>::remove(chain, lane);
+			spent_weight = spent_weight
+				.saturating_add(send_weight)
+				.saturating_add(db_weight.writes(1));
+		}
+	}
+
+	spent_weight
+}
+
+/// Send single reconciliation message.
+fn send_reconciliation<T: Config<I>, I: 'static>(
+	chain: ChainId,
+	lane: LaneId,
+	amount: BalanceOf<T, I>,
+) -> Result<Weight, &'static str> {
+	T::ReconciliationSender::send_reconciliation(chain, lane, amount)
+		.map(|send_weight| {
+			Pallet::<T, I>::deposit_event(Event::ReconciliationSent(chain, lane, amount));
+			send_weight
+		})
+		.map_err(|error| {
+			log::trace!(
+				target: "runtime::bridge-fee-settlement",
+				"Failed to send reconciliation of lane {:?} to chain {:?}: {}",
+				lane,
+				chain,
+				error,
+			);
+			error
+		})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::mock::{
+		run_test, Balances, Call as TestCall, Event as TestEvent, FeeSettlement, MaxReconciliationsPerBlock, Origin,
+		TestRuntime, FAILING_LANE, HUB_ACCOUNT, HUB_CHAIN_ID, RECONCILIATION_PERIOD,
+	};
+	use frame_support::{assert_noop, assert_ok, traits::Hooks};
+	use frame_system::{EventRecord, Phase};
+	use sp_runtime::DispatchError;
+
+	const TEST_LANE_ID: LaneId = LaneId::Legacy(*b"test");
+
+	fn fund_test_lane_pot(amount: u64) {
+		let _ = Balances::deposit_creating(&FeeSettlement::lane_pot_account_id(HUB_CHAIN_ID, TEST_LANE_ID), amount);
+	}
+
+	fn reconciliation_events() -> Vec<TestEvent> {
+		frame_system::Pallet::<TestRuntime>::events()
+			.into_iter()
+			.filter_map(|record| match record.event {
+				TestEvent::FeeSettlement(event) => Some(TestEvent::FeeSettlement(event)),
+				_ => None,
+			})
+			.collect()
+	}
+
+	#[test]
+	fn forwarding_fees_are_accumulated() {
+		run_test(|| {
+			FeeSettlement::note_forwarding_fee(HUB_CHAIN_ID, TEST_LANE_ID, 10);
+			FeeSettlement::note_forwarding_fee(HUB_CHAIN_ID, TEST_LANE_ID, 20);
+
+			assert_eq!(FeeSettlement::pending_reconciliation(HUB_CHAIN_ID, TEST_LANE_ID), 30);
+		});
+	}
+
+	#[test]
+	fn reconciliation_messages_are_only_sent_once_per_period() {
+		run_test(|| {
+			FeeSettlement::note_forwarding_fee(HUB_CHAIN_ID, TEST_LANE_ID, 30);
+
+			FeeSettlement::on_initialize(RECONCILIATION_PERIOD - 1);
+			assert_eq!(FeeSettlement::pending_reconciliation(HUB_CHAIN_ID, TEST_LANE_ID), 30);
+
+			FeeSettlement::on_initialize(RECONCILIATION_PERIOD);
+			assert_eq!(FeeSettlement::pending_reconciliation(HUB_CHAIN_ID, TEST_LANE_ID), 0);
+			assert_eq!(
+				reconciliation_events(),
+				vec![TestEvent::FeeSettlement(Event::ReconciliationSent(
+					HUB_CHAIN_ID,
+					TEST_LANE_ID,
+					30
+				))],
+			);
+		});
+	}
+
+	#[test]
+	fn failed_reconciliation_is_retried_in_next_period() {
+		run_test(|| {
+			FeeSettlement::note_forwarding_fee(HUB_CHAIN_ID, FAILING_LANE, 30);
+
+			FeeSettlement::on_initialize(RECONCILIATION_PERIOD);
+			assert_eq!(FeeSettlement::pending_reconciliation(HUB_CHAIN_ID, FAILING_LANE), 0);
+			assert_eq!(FeeSettlement::failed_reconciliation(HUB_CHAIN_ID, FAILING_LANE), 30);
+			assert_eq!(reconciliation_events(), vec![]);
+
+			FeeSettlement::on_initialize(2 * RECONCILIATION_PERIOD);
+			assert_eq!(FeeSettlement::failed_reconciliation(HUB_CHAIN_ID, FAILING_LANE), 30);
+			assert_eq!(reconciliation_events(), vec![]);
+		});
+	}
+
+	#[test]
+	fn failed_reconciliations_do_not_block_other_reconciliations() {
+		run_test(|| {
+			let failing_chains = [*b"hub1", *b"hub2", *b"hub3", *b"hub4"];
+			assert_eq!(failing_chains.len() as u32, MaxReconciliationsPerBlock::get());
+			for chain in failing_chains.iter() {
+				FeeSettlement::note_forwarding_fee(*chain, FAILING_LANE, 30);
+			}
+			FeeSettlement::note_forwarding_fee(HUB_CHAIN_ID, TEST_LANE_ID, 30);
+
+			FeeSettlement::on_initialize(RECONCILIATION_PERIOD);
+			FeeSettlement::on_initialize(2 * RECONCILIATION_PERIOD);
+
+			assert_eq!(FeeSettlement::pending_reconciliation(HUB_CHAIN_ID, TEST_LANE_ID), 0);
+			assert_eq!(
+				reconciliation_events(),
+				vec![TestEvent::FeeSettlement(Event::ReconciliationSent(
+					HUB_CHAIN_ID,
+					TEST_LANE_ID,
+					30
+				))],
+			);
+			for chain in failing_chains.iter() {
+				assert_eq!(FeeSettlement::failed_reconciliation(*chain, FAILING_LANE), 30);
+			}
+		});
+	}
+
+	#[test]
+	fn failed_reconciliation_is_retried_when_sender_succeeds() {
+		run_test(|| {
+			FailedReconciliations::<TestRuntime>::insert(HUB_CHAIN_ID, TEST_LANE_ID, 30);
+
+			FeeSettlement::on_initialize(RECONCILIATION_PERIOD);
+
+			assert_eq!(FeeSettlement::failed_reconciliation(HUB_CHAIN_ID, TEST_LANE_ID), 0);
+			assert_eq!(
+				reconciliation_events(),
+				vec![TestEvent::FeeSettlement(Event::ReconciliationSent(
+					HUB_CHAIN_ID,
+					TEST_LANE_ID,
+					30
+				))],
+			);
+		});
+	}
+
+	#[test]
+	fn settle_rejects_non_settlement_origin() {
+		run_test(|| {
+			assert_noop!(
+				FeeSettlement::settle(Origin::root(), TEST_LANE_ID, 10),
+				DispatchError::BadOrigin,
+			);
+			assert_noop!(
+				FeeSettlement::settle(Origin::signed(HUB_ACCOUNT + 1), TEST_LANE_ID, 10),
+				DispatchError::BadOrigin,
+			);
+		});
+	}
+
+	#[test]
+	fn settle_call_is_encoded_correctly() {
+		let pallet_index = TestCall::FeeSettlement(Call::settle(TEST_LANE_ID, 10)).encode()[0];
+		assert_eq!(
+			encode_settle_call(pallet_index, TEST_LANE_ID, 10u64),
+			TestCall::FeeSettlement(Call::settle(TEST_LANE_ID, 10)).encode(),
+		);
+	}
+
+	#[test]
+	fn settle_pays_debt_from_lane_pot() {
+		run_test(|| {
+			fund_test_lane_pot(100);
+
+			assert_ok!(FeeSettlement::settle(Origin::signed(HUB_ACCOUNT), TEST_LANE_ID, 30));
+
+			assert_eq!(Balances::free_balance(HUB_ACCOUNT), 30);
+			assert_eq!(
+				Balances::free_balance(FeeSettlement::lane_pot_account_id(HUB_CHAIN_ID, TEST_LANE_ID)),
+				70
+			);
+			assert_eq!(FeeSettlement::outstanding_debt(TEST_LANE_ID), 0);
+			assert_eq!(
+				frame_system::Pallet::<TestRuntime>::events().last(),
+				Some(&EventRecord {
+					phase: Phase::Initialization,
+					event: TestEvent::FeeSettlement(Event::DebtSettled(TEST_LANE_ID, 30, 0)),
+					topics: vec![],
+				}),
+			);
+		});
+	}
+
+	#[test]
+	fn settle_keeps_debt_that_is_not_covered_by_lane_pot() {
+		run_test(|| {
+			fund_test_lane_pot(20);
+
+			assert_ok!(FeeSettlement::settle(Origin::signed(HUB_ACCOUNT), TEST_LANE_ID, 30));
+			assert_eq!(Balances::free_balance(HUB_ACCOUNT), 20);
+			assert_eq!(FeeSettlement::outstanding_debt(TEST_LANE_ID), 10);
+
+			// the rest of debt is paid on next reconciliation
+			fund_test_lane_pot(100);
+			assert_ok!(FeeSettlement::settle(Origin::signed(HUB_ACCOUNT), TEST_LANE_ID, 5));
+			assert_eq!(Balances::free_balance(HUB_ACCOUNT), 35);
+			assert_eq!(FeeSettlement::outstanding_debt(TEST_LANE_ID), 0);
+		});
+	}
+}
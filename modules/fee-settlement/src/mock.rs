@@ -0,0 +1,152 @@
+// Copyright 2019-2021 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+// From construct_runtime macro
+#![allow(clippy::from_over_into)]
+
+use crate as pallet_bridge_fee_settlement;
+
+use bp_messages::LaneId;
+use bp_runtime::ChainId;
+use frame_support::weights::Weight;
+use sp_core::H256;
+use sp_runtime::{
+	testing::Header as SubstrateHeader,
+	traits::{BlakeTwo256, IdentityLookup},
+	Perbill,
+};
+
+pub type AccountId = u64;
+pub type Balance = u64;
+pub type Block = frame_system::mocking::MockBlock<TestRuntime>;
+pub type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<TestRuntime>;
+
+pub const HUB_ACCOUNT: AccountId = 1;
+pub const HUB_CHAIN_ID: ChainId = *b"hubc";
+pub const FAILING_LANE: LaneId = LaneId::Legacy(*b"fail");
+pub const RECONCILIATION_PERIOD: u64 = 10;
+
+frame_support::construct_runtime! {
+	pub enum TestRuntime where
+		Block = Block,
+		NodeBlock = Block,
+		UncheckedExtrinsic = UncheckedExtrinsic,
+	{
+		System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
+		Balances: pallet_balances::{Pallet, Call, Event<T>},
+		FeeSettlement: pallet_bridge_fee_settlement::{Pallet, Call, Storage, Event<T>},
+	}
+}
+
+frame_support::parameter_types! {
+	pub const BlockHashCount: u64 = 250;
+	pub const MaximumBlockWeight: Weight = 1024;
+	pub const MaximumBlockLength: u32 = 2 * 1024;
+	pub const AvailableBlockRatio: Perbill = Perbill::one();
+}
+
+impl frame_system::Config for TestRuntime {
+	type Origin = Origin;
+	type Index = u64;
+	type Call = Call;
+	type BlockNumber = u64;
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	type AccountId = AccountId;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = SubstrateHeader;
+	type Event = Event;
+	type BlockHashCount = BlockHashCount;
+	type Version = ();
+	type PalletInfo = PalletInfo;
+	type AccountData = pallet_balances::AccountData<Balance>;
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+	type BaseCallFilter = ();
+	type SystemWeightInfo = ();
+	type BlockWeights = ();
+	type BlockLength = ();
+	type DbWeight = ();
+	type SS58Prefix = ();
+	type OnSetCode = ();
+}
+
+frame_support::parameter_types! {
+	pub const ExistentialDeposit: u64 = 1;
+	pub const MaxReserves: u32 = 50;
+}
+
+impl pallet_balances::Config for TestRuntime {
+	type MaxLocks = ();
+	type Balance = Balance;
+	type DustRemoval = ();
+	type Event = Event;
+	type ExistentialDeposit = ExistentialDeposit;
+	type AccountStore = frame_system::Pallet<TestRuntime>;
+	type WeightInfo = ();
+	type MaxReserves = MaxReserves;
+	type ReserveIdentifier = [u8; 8];
+}
+
+frame_support::parameter_types! {
+	pub const HubChainId: ChainId = HUB_CHAIN_ID;
+	pub const HubAccount: AccountId = HUB_ACCOUNT;
+	pub const ReconciliationPeriod: u64 = RECONCILIATION_PERIOD;
+	pub const MaxReconciliationsPerBlock: u32 = 4;
+}
+
+impl pallet_bridge_fee_settlement::Config for TestRuntime {
+	type Event = Event;
+	type Currency = pallet_balances::Pallet<TestRuntime>;
+	type AccountIdConverter = TestAccountConverter;
+	type HubChainId = HubChainId;
+	type SettlementOrigin = pallet_bridge_fee_settlement::EnsureSettlementAccount<AccountId, HubAccount>;
+	type ReconciliationSender = TestReconciliationSender;
+	type ReconciliationPeriod = ReconciliationPeriod;
+	type MaxReconciliationsPerBlock = MaxReconciliationsPerBlock;
+}
+
+pub struct TestAccountConverter;
+
+impl sp_runtime::traits::Convert<H256, AccountId> for TestAccountConverter {
+	fn convert(hash: H256) -> AccountId {
+		hash.to_low_u64_ne()
+	}
+}
+
+pub struct TestReconciliationSender;
+
+impl pallet_bridge_fee_settlement::ReconciliationSender<Balance> for TestReconciliationSender {
+	fn send_reconciliation(_chain: ChainId, lane: LaneId, _amount: Balance) -> Result<Weight, &'static str> {
+		if lane == FAILING_LANE {
+			return Err("Failed to send reconciliation");
+		}
+
+		Ok(0)
+	}
+}
+
+/// Run pallet test.
+pub fn run_test<T>(test: impl FnOnce() -> T) -> T {
+	let t = frame_system::GenesisConfig::default()
+		.build_storage::<TestRuntime>()
+		.unwrap();
+	let mut ext = sp_io::TestExternalities::new(t);
+	ext.execute_with(|| {
+		frame_system::Pallet::<TestRuntime>::set_block_number(1);
+		test()
+	})
+}
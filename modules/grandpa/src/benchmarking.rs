@@ -38,11 +38,16 @@
 //!
 //! Note that the worst case scenario here would be a justification where each validator has it's
 //! own fork which is `SESSION_LENGTH` blocks long.
+//!
+//! The `report_equivocation` benchmark verifies equivocation proof with two precommits of the
+//! same authority and then halts the pallet (`HaltOnEquivocation` is the most expensive handler,
+//! provided by this crate).
 
 use crate::*;
 
 use bp_test_utils::{
-	accounts, make_justification_for_header, JustificationGeneratorParams, TEST_GRANDPA_ROUND, TEST_GRANDPA_SET_ID,
+	accounts, header_id, make_justification_for_header, signed_precommit, JustificationGeneratorParams,
+	TEST_GRANDPA_ROUND, TEST_GRANDPA_SET_ID,
 };
 use frame_benchmarking::{benchmarks_instance_pallet, whitelisted_caller};
 use frame_support::traits::Get;
@@ -51,16 +56,6 @@ use sp_finality_grandpa::AuthorityId;
 use sp_runtime::traits::Zero;
 use sp_std::vec::Vec;
 
-// The maximum number of vote ancestries to include in a justification.
-//
-// In practice this would be limited by the session length (number of blocks a single authority set
-// can produce) of a given chain.
-const MAX_VOTE_ANCESTRIES: u32 = 1000;
-
-// The maximum number of pre-commits to include in a justification. In practice this scales with the
-// number of validators.
-const MAX_VALIDATOR_SET_SIZE: u32 = 1024;
-
 /// Returns number of first header to be imported.
 ///
 /// Since we boostrap the pallet with `HeadersToKeep` already imported headers,
@@ -84,6 +79,7 @@ fn prepare_benchmark_data<T: Config<I>, I: 'static>(
 		authority_list,
 		set_id: TEST_GRANDPA_SET_ID,
 		is_halted: false,
+		genesis_hash: Default::default(),
 	};
 
 	bootstrap_bridge::<T, I>(init_data);
@@ -101,12 +97,43 @@ fn prepare_benchmark_data<T: Config<I>, I: 'static>(
 	(header, justification)
 }
 
+/// Prepare equivocation proof of the first authority to submit using `report_equivocation`.
+fn prepare_equivocation_proof<T: Config<I>, I: 'static>(
+) -> EquivocationProof<BridgedBlockHash<T, I>, BridgedBlockNumber<T, I>> {
+	// initialize the pallet with single authority
+	let _ = prepare_benchmark_data::<T, I>(1, 1);
+
+	let offender = accounts(1)[0];
+	let first = signed_precommit::<BridgedHeader<T, I>>(
+		&offender,
+		header_id::<BridgedHeader<T, I>>(1),
+		TEST_GRANDPA_ROUND,
+		TEST_GRANDPA_SET_ID,
+	);
+	let second = signed_precommit::<BridgedHeader<T, I>>(
+		&offender,
+		header_id::<BridgedHeader<T, I>>(2),
+		TEST_GRANDPA_ROUND,
+		TEST_GRANDPA_SET_ID,
+	);
+
+	EquivocationProof::new(
+		TEST_GRANDPA_SET_ID,
+		sp_finality_grandpa::Equivocation::Precommit(finality_grandpa::Equivocation {
+			round_number: TEST_GRANDPA_ROUND,
+			identity: offender.into(),
+			first: (first.precommit, first.signature),
+			second: (second.precommit, second.signature),
+		}),
+	)
+}
+
 benchmarks_instance_pallet! {
 	// This is the "gold standard" benchmark for this extrinsic, and it's what should be used to
 	// annotate the weight in the pallet.
 	submit_finality_proof {
-		let p in 1..MAX_VALIDATOR_SET_SIZE;
-		let v in 1..MAX_VOTE_ANCESTRIES;
+		let p in 1..T::BridgedChain::MAX_AUTHORITIES_COUNT;
+		let v in 1..T::BridgedChain::REASONABLE_HEADERS_IN_JUSTIFICATON_ANCESTRY;
 		let caller: T::AccountId = whitelisted_caller();
 		let (header, justification) = prepare_benchmark_data::<T, I>(p, v);
 	}: submit_finality_proof(RawOrigin::Signed(caller), header, justification)
@@ -117,4 +144,12 @@ benchmarks_instance_pallet! {
 		assert_eq!(<BestFinalized<T, I>>::get(), expected_hash);
 		assert!(<ImportedHeaders<T, I>>::contains_key(expected_hash));
 	}
+
+	report_equivocation {
+		let caller: T::AccountId = whitelisted_caller();
+		let equivocation_proof = prepare_equivocation_proof::<T, I>();
+	}: report_equivocation(RawOrigin::Signed(caller), Box::new(equivocation_proof))
+	verify {
+		assert!(<ReportedEquivocations<T, I>>::iter().next().is_some());
+	}
 }
@@ -31,6 +31,16 @@
 //! bug causing resulting in an equivocation. Such events are outside the scope of this pallet.
 //! Shall the fork occur on the bridged chain governance intervention will be required to
 //! re-initialize the bridge and track the right fork.
+//!
+//! Optionally (see `Config::AcceptAncestryProofs`), the pallet may also import headers without
+//! justifications, if they are ancestors of already imported headers. Such headers are kept in a
+//! separate ring buffer and never become the best finalized header.
+//!
+//! If the pallet sees that a previously imported header is not an ancestor of the newly imported
+//! header (i.e. there's another header with the same number on the chain of the new header), it
+//! emits the `ForkDetected` event and prunes the conflicting header, along with its imported
+//! ancestors that are known to be conflicting. This still requires governance intervention, but
+//! at least the fork doesn't go unnoticed.
 
 #![cfg_attr(not(feature = "std"), no_std)]
 // Runtime-generated enums
@@ -39,14 +49,15 @@
 use crate::weights::WeightInfo;
 
 use bp_header_chain::justification::GrandpaJustification;
-use bp_header_chain::InitializationData;
-use bp_runtime::{BlockNumberOf, Chain, HashOf, HasherOf, HeaderOf};
+use bp_header_chain::{ChainWithGrandpa, FinalityProofError, InitializationData};
+use bp_runtime::{BlockNumberOf, HashOf, HasherOf, HeaderOf};
+use codec::Decode;
 use finality_grandpa::voter_set::VoterSet;
-use frame_support::{ensure, fail};
+use frame_support::{ensure, fail, traits::Get, transactional, weights::Weight};
 use frame_system::{ensure_signed, RawOrigin};
-use sp_finality_grandpa::{ConsensusLog, GRANDPA_ENGINE_ID};
-use sp_runtime::traits::{BadOrigin, Header as HeaderT, Zero};
-use sp_std::convert::TryInto;
+use sp_finality_grandpa::{ConsensusLog, EquivocationProof, GRANDPA_ENGINE_ID};
+use sp_runtime::traits::{BadOrigin, Header as HeaderT, One, Zero};
+use sp_std::{boxed::Box, convert::TryInto, vec::Vec};
 
 #[cfg(test)]
 mod mock;
@@ -68,7 +79,119 @@ pub type BridgedBlockHash<T, I> = HashOf<<T as Config<I>>::BridgedChain>;
 pub type BridgedBlockHasher<T, I> = HasherOf<<T as Config<I>>::BridgedChain>;
 /// Header of the bridged chain.
 pub type BridgedHeader<T, I> = HeaderOf<<T as Config<I>>::BridgedChain>;
+/// Authority set change record of the bridged chain.
+pub type AuthoritySetChangeOf<T, I> =
+	bp_header_chain::AuthoritySetChange<BridgedBlockHash<T, I>, BridgedBlockNumber<T, I>>;
+/// Authority set, stored in the authority sets history.
+pub type HistoricalAuthoritySetOf<T, I> = bp_header_chain::HistoricalAuthoritySet<BridgedBlockNumber<T, I>>;
+
+/// Handler of the bridged chain GRANDPA authorities equivocations.
+pub trait OnEquivocation<Hash, Number> {
+	/// Called when valid equivocation proof of the current bridged chain authority has been
+	/// reported.
+	fn on_equivocation(proof: &EquivocationProof<Hash, Number>);
+}
+
+impl<Hash, Number> OnEquivocation<Hash, Number> for () {
+	fn on_equivocation(_proof: &EquivocationProof<Hash, Number>) {}
+}
+
+/// Equivocation handler that halts all pallet operations.
+///
+/// Operations may be resumed by the pallet owner or root, using the `set_operational` call.
+pub struct HaltOnEquivocation<T, I>(sp_std::marker::PhantomData<(T, I)>);
+
+impl<T: Config<I>, I: 'static> OnEquivocation<BridgedBlockHash<T, I>, BridgedBlockNumber<T, I>>
+	for HaltOnEquivocation<T, I>
+{
+	fn on_equivocation(proof: &EquivocationProof<BridgedBlockHash<T, I>, BridgedBlockNumber<T, I>>) {
+		log::warn!(
+			target: "runtime::bridge-grandpa",
+			"Stopping pallet operations because of equivocation of {:?} at round {} of set {}.",
+			proof.offender(),
+			proof.round(),
+			proof.set_id(),
+		);
+		<IsHalted<T, I>>::put(true);
+	}
+}
+
+/// Runtime upgrade that initializes capacity of the imported headers ring buffer of the pallet,
+/// deployed before the capacity has been tracked.
+///
+/// The `PreviousHeadersToKeep` must be the `HeadersToKeep` value of the runtime that is upgraded.
+/// If the new `HeadersToKeep` is lower, obsolete headers are pruned after the upgrade. The migration
+/// does nothing if the capacity is already initialized or the pallet is not initialized at all.
+pub struct InitializeImportedHashesCapacity<T, I, PreviousHeadersToKeep>(
+	sp_std::marker::PhantomData<(T, I, PreviousHeadersToKeep)>,
+);
+
+impl<T: Config<I>, I: 'static, PreviousHeadersToKeep: Get<u32>> frame_support::traits::OnRuntimeUpgrade
+	for InitializeImportedHashesCapacity<T, I, PreviousHeadersToKeep>
+{
+	fn on_runtime_upgrade() -> Weight {
+		if <ImportedHashesCapacity<T, I>>::exists() || !<BestFinalized<T, I>>::exists() {
+			return T::DbWeight::get().reads(2);
+		}
+
+		log::info!(
+			target: "runtime::bridge-grandpa",
+			"Initializing imported headers capacity with previous HeadersToKeep value: {}.",
+			PreviousHeadersToKeep::get(),
+		);
+		<ImportedHashesCapacity<T, I>>::put(PreviousHeadersToKeep::get());
+		T::DbWeight::get().reads_writes(2, 1)
+	}
+}
+
+/// Runtime upgrade that adds the current authority set to the authority sets history of the pallet,
+/// deployed before the history has been tracked.
+///
+/// The set is marked as enacted at the header that has enacted it, if this header is still in the
+/// `AuthoritySetChanges` ring buffer. Otherwise it is marked as enacted at the best finalized header,
+/// so headers that are finalized before the actual set enactment are never accepted. The migration does
+/// nothing if the history is disabled, the current set is already in the history or the pallet is not
+/// initialized at all.
+pub struct InitializeAuthoritySetsHistory<T, I>(sp_std::marker::PhantomData<(T, I)>);
+
+impl<T: Config<I>, I: 'static> frame_support::traits::OnRuntimeUpgrade for InitializeAuthoritySetsHistory<T, I> {
+	fn on_runtime_upgrade() -> Weight {
+		if T::AuthoritySetsToKeep::get() == 0 || !<BestFinalized<T, I>>::exists() {
+			return T::DbWeight::get().reads(1);
+		}
+
+		let current_set = <CurrentAuthoritySet<T, I>>::get();
+		if <AuthoritySetsHistory<T, I>>::contains_key(current_set.set_id) {
+			return T::DbWeight::get().reads(3);
+		}
+
+		let enacted_at = Pallet::<T, I>::authority_set_changes()
+			.into_iter()
+			.find(|change| change.set_id == current_set.set_id)
+			.map(|change| change.block_number)
+			.unwrap_or_else(|| *Pallet::<T, I>::best_finalized().number());
+
+		log::info!(
+			target: "runtime::bridge-grandpa",
+			"Adding current authority set {} to the authority sets history. Enacted at: {:?}.",
+			current_set.set_id,
+			enacted_at,
+		);
+		<AuthoritySetsHistory<T, I>>::insert(
+			current_set.set_id,
+			bp_header_chain::HistoricalAuthoritySet {
+				authorities: current_set.authorities,
+				enacted_at,
+				superseded_at: None,
+			},
+		);
+
+		T::DbWeight::get().reads_writes(6 + T::AuthoritySetChangesToKeep::get() as Weight, 1)
+	}
+}
 
+// comes from #[pallet::event]
+#[allow(clippy::unused_unit)]
 #[frame_support::pallet]
 pub mod pallet {
 	use super::*;
@@ -77,8 +200,11 @@ pub mod pallet {
 
 	#[pallet::config]
 	pub trait Config<I: 'static = ()>: frame_system::Config {
+		/// The overarching event type.
+		type Event: From<Event<Self, I>> + IsType<<Self as frame_system::Config>::Event>;
+
 		/// The chain we are bridging to here.
-		type BridgedChain: Chain;
+		type BridgedChain: ChainWithGrandpa;
 
 		/// The upper bound on the number of requests allowed by the pallet.
 		///
@@ -97,6 +223,48 @@ pub mod pallet {
 		#[pallet::constant]
 		type HeadersToKeep: Get<u32>;
 
+		/// Maximal number of obsolete headers that are pruned at the beginning of every block.
+		///
+		/// Headers become obsolete when `HeadersToKeep` is decreased by the runtime upgrade. They
+		/// are pruned lazily, either at the beginning of the block, or by the `prune_headers` call.
+		#[pallet::constant]
+		type MaxHeadersToPruneInBlock: Get<u32>;
+
+		/// Maximal number of authority set changes to keep in the storage.
+		///
+		/// Older changes are pruned when new change is enacted. The history is only kept for
+		/// debugging and for verifying proofs against historical headers, so it doesn't affect
+		/// the finality verification itself.
+		#[pallet::constant]
+		type AuthoritySetChangesToKeep: Get<u32>;
+
+		/// Maximal number of previous authority sets to keep in the storage.
+		///
+		/// Justifications, generated by these sets, may still be verified by the pallet (see
+		/// `submit_historical_finality_proof`). If it is zero, only justifications of the current
+		/// authority set are accepted.
+		#[pallet::constant]
+		type AuthoritySetsToKeep: Get<u32>;
+
+		/// If true, the pallet accepts headers without justifications, if they are proved to be
+		/// ancestors of already imported headers (see `submit_ancestry_proof`).
+		///
+		/// Ancestors of finalized headers are finalized too, so this allows verifying messages
+		/// and storage proofs against any header between finalized checkpoints.
+		#[pallet::constant]
+		type AcceptAncestryProofs: Get<bool>;
+
+		/// Maximal number of mandatory headers that may be submitted for free in a single block.
+		///
+		/// Mandatory headers must be imported to keep the bridge alive, so honest relayers are
+		/// not paying for them. All mandatory headers above this limit are paid as usual, so
+		/// malicious relayers can't use free submissions to spam the chain.
+		#[pallet::constant]
+		type FreeMandatoryHeadersPerBlock: Get<u32>;
+
+		/// Handler of reported equivocations of the bridged chain GRANDPA authorities.
+		type OnEquivocation: crate::OnEquivocation<BridgedBlockHash<Self, I>, BridgedBlockNumber<Self, I>>;
+
 		/// Weights gathered through benchmarking.
 		type WeightInfo: WeightInfo;
 	}
@@ -108,10 +276,13 @@ pub mod pallet {
 	impl<T: Config<I>, I: 'static> Hooks<BlockNumberFor<T>> for Pallet<T, I> {
 		fn on_initialize(_n: T::BlockNumber) -> frame_support::weights::Weight {
 			<RequestCount<T, I>>::mutate(|count| *count = count.saturating_sub(1));
+			<FreeMandatoryHeadersCount<T, I>>::kill();
+			let pruned_headers = prune_obsolete_headers::<T, I>(T::MaxHeadersToPruneInBlock::get());
 
 			(0_u64)
 				.saturating_add(T::DbWeight::get().reads(1))
-				.saturating_add(T::DbWeight::get().writes(1))
+				.saturating_add(T::DbWeight::get().writes(2))
+				.saturating_add(prune_obsolete_headers_weight::<T, I>(pruned_headers))
 		}
 	}
 
@@ -124,10 +295,10 @@ pub mod pallet {
 		///
 		/// If successful in verification, it will write the target header to the underlying storage
 		/// pallet.
-		#[pallet::weight(T::WeightInfo::submit_finality_proof(
-			justification.commit.precommits.len().try_into().unwrap_or(u32::MAX),
-			justification.votes_ancestries.len().try_into().unwrap_or(u32::MAX),
-		))]
+		///
+		/// The call is free if the header is mandatory (i.e. it enacts new authority set), unless
+		/// `T::FreeMandatoryHeadersPerBlock` free headers have already been imported in this block.
+		#[pallet::weight(submit_finality_proof_weight::<T, I>(justification))]
 		pub fn submit_finality_proof(
 			origin: OriginFor<T>,
 			finality_target: BridgedHeader<T, I>,
@@ -141,33 +312,10 @@ pub mod pallet {
 				<Error<T, I>>::TooManyRequests
 			);
 
-			let (hash, number) = (finality_target.hash(), finality_target.number());
-			log::trace!(target: "runtime::bridge-grandpa", "Going to try and finalize header {:?}", finality_target);
-
-			let best_finalized = match <ImportedHeaders<T, I>>::get(<BestFinalized<T, I>>::get()) {
-				Some(best_finalized) => best_finalized,
-				None => {
-					log::error!(
-						target: "runtime::bridge-grandpa",
-						"Cannot finalize header {:?} because pallet is not yet initialized",
-						finality_target,
-					);
-					fail!(<Error<T, I>>::NotInitialized);
-				}
-			};
-
-			// We do a quick check here to ensure that our header chain is making progress and isn't
-			// "travelling back in time" (which could be indicative of something bad, e.g a hard-fork).
-			ensure!(best_finalized.number() < number, <Error<T, I>>::OldHeader);
-
-			let authority_set = <CurrentAuthoritySet<T, I>>::get();
-			let set_id = authority_set.set_id;
-			verify_justification::<T, I>(&justification, hash, *number, authority_set)?;
-
-			let _enacted = try_enact_authority_change::<T, I>(&finality_target, set_id)?;
-			<RequestCount<T, I>>::mutate(|count| *count += 1);
-			insert_header::<T, I>(finality_target, hash);
-			log::info!(target: "runtime::bridge-grandpa", "Succesfully imported finalized header with hash {:?}!", hash);
+			let is_mandatory = import_finality_proof::<T, I>(finality_target, justification)?;
+			if is_mandatory && try_use_free_mandatory_header_slot::<T, I>() {
+				return Ok(Pays::No.into());
+			}
 
 			Ok(().into())
 		}
@@ -181,7 +329,7 @@ pub mod pallet {
 		/// This function is only allowed to be called from a trusted origin and writes to storage
 		/// with practically no checks in terms of the validity of the data. It is important that
 		/// you ensure that valid data is being passed in.
-		#[pallet::weight((T::DbWeight::get().reads_writes(2, 5), DispatchClass::Operational))]
+		#[pallet::weight((T::DbWeight::get().reads_writes(2, 6), DispatchClass::Operational))]
 		pub fn initialize(
 			origin: OriginFor<T>,
 			init_data: super::InitializationData<BridgedHeader<T, I>>,
@@ -201,20 +349,27 @@ pub mod pallet {
 			Ok(().into())
 		}
 
-		/// Change `PalletOwner`.
+		/// Propose new `PalletOwner`, or remove the current owner.
+		///
+		/// The proposed owner only becomes `PalletOwner` after calling `accept_owner`, so the
+		/// ownership can't be transferred to an inaccessible account. The owner is removed
+		/// immediately, because root is still able to manage the pallet.
 		///
 		/// May only be called either by root, or by `PalletOwner`.
-		#[pallet::weight((T::DbWeight::get().reads_writes(1, 1), DispatchClass::Operational))]
-		pub fn set_owner(origin: OriginFor<T>, new_owner: Option<T::AccountId>) -> DispatchResultWithPostInfo {
+		#[pallet::weight((T::DbWeight::get().reads_writes(1, 2), DispatchClass::Operational))]
+		pub fn propose_owner(origin: OriginFor<T>, new_owner: Option<T::AccountId>) -> DispatchResultWithPostInfo {
 			ensure_owner_or_root::<T, I>(origin)?;
 			match new_owner {
 				Some(new_owner) => {
-					PalletOwner::<T, I>::put(&new_owner);
-					log::info!(target: "runtime::bridge-grandpa", "Setting pallet Owner to: {:?}", new_owner);
+					PendingPalletOwner::<T, I>::put(&new_owner);
+					log::info!(target: "runtime::bridge-grandpa", "Proposing pallet Owner: {:?}", new_owner);
+					Self::deposit_event(Event::OwnerProposed(new_owner));
 				}
 				None => {
 					PalletOwner::<T, I>::kill();
+					PendingPalletOwner::<T, I>::kill();
 					log::info!(target: "runtime::bridge-grandpa", "Removed Owner of pallet.");
+					Self::deposit_event(Event::OwnerChanged(None));
 				}
 			}
 
@@ -237,6 +392,269 @@ pub mod pallet {
 
 			Ok(().into())
 		}
+
+		/// Report equivocation of the bridged chain GRANDPA authority.
+		///
+		/// The offender must be a member of the current authority set, known to the pallet. If the
+		/// proof is valid, the `OnEquivocation` handler is called. Equivocations may be reported
+		/// even if the pallet is halted. Every equivocation (identified by the set id, round and
+		/// offender) may only be reported once.
+		#[pallet::weight(T::WeightInfo::report_equivocation())]
+		pub fn report_equivocation(
+			origin: OriginFor<T>,
+			equivocation_proof: Box<EquivocationProof<BridgedBlockHash<T, I>, BridgedBlockNumber<T, I>>>,
+		) -> DispatchResultWithPostInfo {
+			let _ = ensure_signed(origin)?;
+
+			let equivocation_key = (equivocation_proof.round(), equivocation_proof.offender().clone());
+			ensure!(
+				!<ReportedEquivocations<T, I>>::contains_key(equivocation_proof.set_id(), &equivocation_key),
+				<Error<T, I>>::DuplicateEquivocationReport
+			);
+
+			let authority_set = <CurrentAuthoritySet<T, I>>::get();
+			ensure!(
+				equivocation_proof.set_id() == authority_set.set_id,
+				<Error<T, I>>::InvalidEquivocationSetId
+			);
+			ensure!(
+				authority_set
+					.authorities
+					.iter()
+					.any(|(authority, _)| authority == equivocation_proof.offender()),
+				<Error<T, I>>::UnknownEquivocationOffender
+			);
+			ensure!(
+				sp_finality_grandpa::check_equivocation_proof((*equivocation_proof).clone()),
+				<Error<T, I>>::InvalidEquivocationProof
+			);
+
+			log::warn!(
+				target: "runtime::bridge-grandpa",
+				"Received valid equivocation proof of {:?} at round {} of set {}",
+				equivocation_proof.offender(),
+				equivocation_proof.round(),
+				equivocation_proof.set_id(),
+			);
+
+			<ReportedEquivocations<T, I>>::insert(equivocation_proof.set_id(), equivocation_key, ());
+			T::OnEquivocation::on_equivocation(&equivocation_proof);
+			Self::deposit_event(Event::EquivocationReported(
+				equivocation_proof.set_id(),
+				equivocation_proof.round(),
+				equivocation_proof.offender().clone(),
+			));
+
+			Ok(().into())
+		}
+
+		/// Verify and import several headers, finalized according to given finality proofs.
+		///
+		/// Headers are imported in order, exactly as if they were submitted using separate
+		/// `submit_finality_proof` calls. So they must be ordered by number and every header must
+		/// be finalized by the authority set, enacted by previous headers. If any of finality proofs
+		/// is invalid, the whole call fails and no headers are imported.
+		///
+		/// Every header is counted as a separate request by the rate limiter. Unlike the
+		/// `submit_finality_proof` call, this call is always paid.
+		#[pallet::weight(finality_proofs.iter().fold(0 as Weight, |weight, (_, justification)| {
+			weight.saturating_add(submit_finality_proof_weight::<T, I>(justification))
+		}))]
+		#[transactional]
+		pub fn submit_finality_proofs(
+			origin: OriginFor<T>,
+			finality_proofs: Vec<(BridgedHeader<T, I>, GrandpaJustification<BridgedHeader<T, I>>)>,
+		) -> DispatchResultWithPostInfo {
+			ensure_operational::<T, I>()?;
+			let _ = ensure_signed(origin)?;
+
+			ensure!(!finality_proofs.is_empty(), <Error<T, I>>::EmptyBatch);
+			let proofs_count: u32 = finality_proofs.len().try_into().unwrap_or(u32::MAX);
+			ensure!(
+				Self::request_count().saturating_add(proofs_count) <= T::MaxRequests::get(),
+				<Error<T, I>>::TooManyRequests
+			);
+
+			for (finality_target, justification) in finality_proofs {
+				import_finality_proof::<T, I>(finality_target, justification)?;
+			}
+
+			Ok(().into())
+		}
+
+		/// Enable or disable the mandatory-headers-only mode.
+		///
+		/// In this mode, only headers that are changing the GRANDPA authority set are accepted.
+		///
+		/// May only be called either by root, or by `PalletOwner`.
+		#[pallet::weight((T::DbWeight::get().reads_writes(1, 1), DispatchClass::Operational))]
+		pub fn set_mandatory_headers_only(
+			origin: OriginFor<T>,
+			mandatory_headers_only: bool,
+		) -> DispatchResultWithPostInfo {
+			ensure_owner_or_root::<T, I>(origin)?;
+			<MandatoryHeadersOnly<T, I>>::put(mandatory_headers_only);
+
+			if mandatory_headers_only {
+				log::info!(target: "runtime::bridge-grandpa", "Accepting only mandatory headers.");
+			} else {
+				log::info!(target: "runtime::bridge-grandpa", "Accepting all finalized headers.");
+			}
+
+			Ok(().into())
+		}
+
+		/// Import headers that are ancestors of already imported header.
+		///
+		/// The `ancestry` must start with the parent of the `descendant` header and every next
+		/// header must be the parent of the previous one. Headers are imported without
+		/// justifications and don't change the best finalized header of the pallet. The call
+		/// is only available if `T::AcceptAncestryProofs` is true.
+		///
+		/// The whole call is counted as a single request by the rate limiter.
+		#[pallet::weight(T::DbWeight::get().reads_writes(
+			2 + 4 * ancestry.len() as Weight,
+			1 + 6 * ancestry.len() as Weight,
+		))]
+		pub fn submit_ancestry_proof(
+			origin: OriginFor<T>,
+			descendant: BridgedBlockHash<T, I>,
+			ancestry: Vec<BridgedHeader<T, I>>,
+		) -> DispatchResultWithPostInfo {
+			ensure_operational::<T, I>()?;
+			let _ = ensure_signed(origin)?;
+
+			ensure!(T::AcceptAncestryProofs::get(), <Error<T, I>>::AncestryProofsDisabled);
+			ensure!(
+				Self::request_count() < T::MaxRequests::get(),
+				<Error<T, I>>::TooManyRequests
+			);
+
+			let hashes = verify_ancestry_proof::<T, I>(descendant, &ancestry)?;
+			let ancestry_len = hashes.len();
+			<RequestCount<T, I>>::mutate(|count| *count += 1);
+			let canonical_hashes = ancestry
+				.iter()
+				.map(|header| *header.number())
+				.zip(hashes.iter().cloned())
+				.collect::<Vec<_>>();
+			for (header, hash) in ancestry.into_iter().zip(hashes) {
+				if let Some(conflicting_hash) = <ImportedHashesByNumber<T, I>>::get(header.number()) {
+					prune_fork::<T, I>(conflicting_hash, hash, &canonical_hashes);
+				}
+				insert_ancestor_header::<T, I>(header, hash);
+			}
+
+			log::info!(
+				target: "runtime::bridge-grandpa",
+				"Succesfully imported {} ancestors of header {:?}",
+				ancestry_len,
+				descendant,
+			);
+
+			Ok(().into())
+		}
+
+		/// Prune at most `max_headers` obsolete headers.
+		///
+		/// Headers become obsolete when `T::HeadersToKeep` is decreased. Obsolete headers are also
+		/// pruned at the beginning of every block, so this call is only needed to speed up the
+		/// pruning. Anyone may call it.
+		#[pallet::weight(prune_obsolete_headers_weight::<T, I>(*max_headers))]
+		pub fn prune_headers(origin: OriginFor<T>, max_headers: u32) -> DispatchResultWithPostInfo {
+			let _ = ensure_signed(origin)?;
+
+			let pruned_headers = prune_obsolete_headers::<T, I>(max_headers);
+			log::info!(
+				target: "runtime::bridge-grandpa",
+				"Pruned {} obsolete headers",
+				pruned_headers,
+			);
+
+			Ok(Some(prune_obsolete_headers_weight::<T, I>(pruned_headers)).into())
+		}
+
+		/// Verify and import a header, finalized by one of previous authority sets.
+		///
+		/// The header must be finalized by the authority set with given id, which must still be
+		/// in the pallet history (see `T::AuthoritySetsToKeep`). So the header number must be
+		/// between numbers of headers that have enacted this set and the next set. This allows
+		/// importing headers that have been finalized right before the authority set change, but
+		/// haven't been submitted before the change has been imported.
+		///
+		/// The header is imported without changing the best finalized header of the pallet and it
+		/// must not conflict with already imported headers. The call is always paid.
+		#[pallet::weight(submit_finality_proof_weight::<T, I>(justification))]
+		pub fn submit_historical_finality_proof(
+			origin: OriginFor<T>,
+			finality_target: BridgedHeader<T, I>,
+			justification: GrandpaJustification<BridgedHeader<T, I>>,
+			authority_set_id: sp_finality_grandpa::SetId,
+		) -> DispatchResultWithPostInfo {
+			ensure_operational::<T, I>()?;
+			let _ = ensure_signed(origin)?;
+
+			ensure!(
+				Self::request_count() < T::MaxRequests::get(),
+				<Error<T, I>>::TooManyRequests
+			);
+
+			let hash = finality_target.hash();
+			check_historical_finality_proof::<T, I>(&finality_target, &justification, authority_set_id)?;
+			<RequestCount<T, I>>::mutate(|count| *count += 1);
+			insert_ancestor_header::<T, I>(finality_target, hash);
+
+			log::info!(
+				target: "runtime::bridge-grandpa",
+				"Succesfully imported header {:?}, finalized by authority set {}",
+				hash,
+				authority_set_id,
+			);
+
+			Ok(().into())
+		}
+
+		/// Accept the pallet ownership, proposed by the `propose_owner` call.
+		///
+		/// May only be called by the proposed owner.
+		#[pallet::weight((T::DbWeight::get().reads_writes(1, 2), DispatchClass::Operational))]
+		pub fn accept_owner(origin: OriginFor<T>) -> DispatchResultWithPostInfo {
+			let new_owner = ensure_signed(origin)?;
+			ensure!(
+				PendingPalletOwner::<T, I>::get().as_ref() == Some(&new_owner),
+				<Error<T, I>>::NotPendingOwner,
+			);
+
+			PalletOwner::<T, I>::put(&new_owner);
+			PendingPalletOwner::<T, I>::kill();
+			log::info!(target: "runtime::bridge-grandpa", "Setting pallet Owner to: {:?}", new_owner);
+			Self::deposit_event(Event::OwnerChanged(Some(new_owner)));
+
+			Ok(().into())
+		}
+	}
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	#[pallet::metadata(BridgedBlockHash<T, I> = "BridgedHash", T::AccountId = "AccountId")]
+	pub enum Event<T: Config<I>, I: 'static = ()> {
+		/// Equivocation of the bridged chain GRANDPA authority has been reported.
+		/// \[set_id, round, offender\]
+		EquivocationReported(
+			sp_finality_grandpa::SetId,
+			sp_finality_grandpa::RoundNumber,
+			sp_finality_grandpa::AuthorityId,
+		),
+		/// Previously imported header is not an ancestor of the newly imported header. The
+		/// conflicting header has been pruned.
+		/// \[conflicting_hash, imported_hash\]
+		ForkDetected(BridgedBlockHash<T, I>, BridgedBlockHash<T, I>),
+		/// New pallet owner has been proposed. It needs to accept the ownership.
+		/// \[proposed_owner\]
+		OwnerProposed(T::AccountId),
+		/// Pallet owner has been changed or removed.
+		/// \[new_owner\]
+		OwnerChanged(Option<T::AccountId>),
 	}
 
 	/// The current number of requests which have written to storage.
@@ -254,7 +672,17 @@ pub mod pallet {
 	#[pallet::storage]
 	pub(super) type InitialHash<T: Config<I>, I: 'static = ()> = StorageValue<_, BridgedBlockHash<T, I>, ValueQuery>;
 
+	/// Hash of the bridged chain genesis block, provided at initialization.
+	#[pallet::storage]
+	#[pallet::getter(fn bridged_genesis_hash)]
+	pub(super) type BridgedGenesisHash<T: Config<I>, I: 'static = ()> =
+		StorageValue<_, BridgedBlockHash<T, I>, OptionQuery>;
+
 	/// Hash of the best finalized header.
+	///
+	/// When the pallet is in the mandatory-headers-only mode, this is the last header that has
+	/// changed the authority set, so it may be far behind the actual best finalized header of
+	/// the bridged chain.
 	#[pallet::storage]
 	pub(super) type BestFinalized<T: Config<I>, I: 'static = ()> = StorageValue<_, BridgedBlockHash<T, I>, ValueQuery>;
 
@@ -267,6 +695,37 @@ pub mod pallet {
 	#[pallet::storage]
 	pub(super) type ImportedHashesPointer<T: Config<I>, I: 'static = ()> = StorageValue<_, u32, ValueQuery>;
 
+	/// Number of ring buffer slots that may be occupied by imported hashes.
+	///
+	/// It is the largest `HeadersToKeep` value that has been used since the ring buffers were
+	/// last pruned. Slots above the current `HeadersToKeep` value are obsolete and are pruned lazily.
+	#[pallet::storage]
+	pub(super) type ImportedHashesCapacity<T: Config<I>, I: 'static = ()> = StorageValue<_, u32, ValueQuery>;
+
+	/// A ring buffer of hashes of headers, imported using ancestry proofs. Ordered by the
+	/// insertion time.
+	///
+	/// Ancestors are pruned separately from finalized headers, so that importing them never
+	/// prunes the best finalized header.
+	#[pallet::storage]
+	pub(super) type ImportedAncestorHashes<T: Config<I>, I: 'static = ()> =
+		StorageMap<_, Identity, u32, BridgedBlockHash<T, I>>;
+
+	/// Current ancestors ring buffer position.
+	#[pallet::storage]
+	pub(super) type ImportedAncestorHashesPointer<T: Config<I>, I: 'static = ()> = StorageValue<_, u32, ValueQuery>;
+
+	/// Hashes of imported headers, indexed by header number.
+	///
+	/// Used to detect imported headers that are conflicting with newly imported headers.
+	#[pallet::storage]
+	pub(super) type ImportedHashesByNumber<T: Config<I>, I: 'static = ()> =
+		StorageMap<_, Identity, BridgedBlockNumber<T, I>, BridgedBlockHash<T, I>>;
+
+	/// Number of mandatory headers that have been imported for free in the current block.
+	#[pallet::storage]
+	pub(super) type FreeMandatoryHeadersCount<T: Config<I>, I: 'static = ()> = StorageValue<_, u32, ValueQuery>;
+
 	/// Headers which have been imported into the pallet.
 	#[pallet::storage]
 	pub(super) type ImportedHeaders<T: Config<I>, I: 'static = ()> =
@@ -277,6 +736,37 @@ pub mod pallet {
 	pub(super) type CurrentAuthoritySet<T: Config<I>, I: 'static = ()> =
 		StorageValue<_, bp_header_chain::AuthoritySet, ValueQuery>;
 
+	/// A ring buffer of enacted authority set changes. Ordered by the insertion time.
+	#[pallet::storage]
+	pub(super) type AuthoritySetChanges<T: Config<I>, I: 'static = ()> =
+		StorageMap<_, Identity, u32, AuthoritySetChangeOf<T, I>>;
+
+	/// Current authority set changes ring buffer position.
+	#[pallet::storage]
+	pub(super) type AuthoritySetChangesPointer<T: Config<I>, I: 'static = ()> = StorageValue<_, u32, ValueQuery>;
+
+	/// Recent GRANDPA authority sets (including the current set), keyed by the set id.
+	///
+	/// Previous sets are pruned when new set is enacted, so at most `T::AuthoritySetsToKeep`
+	/// previous sets are kept.
+	#[pallet::storage]
+	pub(super) type AuthoritySetsHistory<T: Config<I>, I: 'static = ()> =
+		StorageMap<_, Identity, sp_finality_grandpa::SetId, HistoricalAuthoritySetOf<T, I>>;
+
+	/// Equivocations that have already been reported, keyed by the set id and (round, offender).
+	///
+	/// Only valid equivocations of the bridged chain authorities are stored here, so entries are
+	/// never pruned.
+	#[pallet::storage]
+	pub(super) type ReportedEquivocations<T: Config<I>, I: 'static = ()> = StorageDoubleMap<
+		_,
+		Identity,
+		sp_finality_grandpa::SetId,
+		Blake2_128Concat,
+		(sp_finality_grandpa::RoundNumber, sp_finality_grandpa::AuthorityId),
+		(),
+	>;
+
 	/// Optional pallet owner.
 	///
 	/// Pallet owner has a right to halt all pallet operations and then resume it. If it is
@@ -286,10 +776,23 @@ pub mod pallet {
 	#[pallet::storage]
 	pub(super) type PalletOwner<T: Config<I>, I: 'static = ()> = StorageValue<_, T::AccountId, OptionQuery>;
 
+	/// Account that has been proposed as the new pallet owner, but hasn't accepted the
+	/// ownership yet.
+	#[pallet::storage]
+	pub(super) type PendingPalletOwner<T: Config<I>, I: 'static = ()> = StorageValue<_, T::AccountId, OptionQuery>;
+
 	/// If true, all pallet transactions are failed immediately.
 	#[pallet::storage]
 	pub(super) type IsHalted<T: Config<I>, I: 'static = ()> = StorageValue<_, bool, ValueQuery>;
 
+	/// If true, only mandatory headers (headers that are changing the GRANDPA authority set)
+	/// are accepted by the pallet.
+	///
+	/// This mode is useful for bridges that only need finality of rare messages (e.g. governance
+	/// messages), because it minimizes the number of headers that are submitted to the chain.
+	#[pallet::storage]
+	pub(super) type MandatoryHeadersOnly<T: Config<I>, I: 'static = ()> = StorageValue<_, bool, ValueQuery>;
+
 	#[pallet::genesis_config]
 	pub struct GenesisConfig<T: Config<I>, I: 'static = ()> {
 		/// Optional module owner account.
@@ -347,42 +850,209 @@ pub mod pallet {
 		AlreadyInitialized,
 		/// All pallet operations are halted.
 		Halted,
+		/// The batch of finality proofs is empty.
+		EmptyBatch,
 		/// The storage proof doesn't contains storage root. So it is invalid for given header.
 		StorageRootMismatch,
+		/// The equivocation proof has been generated by authority set, other than the current set.
+		InvalidEquivocationSetId,
+		/// The equivocation offender is not a member of the current authority set.
+		UnknownEquivocationOffender,
+		/// The equivocation proof is invalid.
+		InvalidEquivocationProof,
+		/// The equivocation has already been reported.
+		DuplicateEquivocationReport,
+		/// The pallet only accepts mandatory headers and the header doesn't change the authority set.
+		NotMandatoryHeader,
+		/// The pallet doesn't accept ancestry proofs.
+		AncestryProofsDisabled,
+		/// The ancestry proof is empty, too large, contains already imported headers or its
+		/// headers are not linked to the descendant header.
+		InvalidAncestryProof,
+		/// The caller has not been proposed as the new pallet owner.
+		NotPendingOwner,
+		/// The authority set is not in the pallet history, or it is the current authority set.
+		UnknownHistoricalAuthoritySet,
+		/// The header hasn't been finalized by the given historical authority set.
+		HeaderNotFinalizedBySet,
+		/// The header has already been imported or it conflicts with already imported headers.
+		ConflictingHeader,
+		/// The storage proof contains duplicate trie nodes.
+		DuplicateNodesInStorageProof,
 	}
 
-	/// Check the given header for a GRANDPA scheduled authority set change. If a change
-	/// is found it will be enacted immediately.
-	///
-	/// This function does not support forced changes, or scheduled changes with delays
-	/// since these types of changes are indicative of abnormal behavior from GRANDPA.
+	/// Returns weight of the `submit_finality_proof` call with given justification.
+	pub(crate) fn submit_finality_proof_weight<T: Config<I>, I: 'static>(
+		justification: &GrandpaJustification<BridgedHeader<T, I>>,
+	) -> Weight {
+		T::WeightInfo::submit_finality_proof(
+			justification.commit.precommits.len().try_into().unwrap_or(u32::MAX),
+			justification.votes_ancestries.len().try_into().unwrap_or(u32::MAX),
+		)
+	}
+
+	/// Verify that the header is finalized according to the given finality proof and import it.
 	///
-	/// Returned value will indicate if a change was enacted or not.
-	pub(crate) fn try_enact_authority_change<T: Config<I>, I: 'static>(
-		header: &BridgedHeader<T, I>,
-		current_set_id: sp_finality_grandpa::SetId,
+	/// The rate limiter is not checked here, but the request is counted. Returns true if the
+	/// imported header is mandatory (i.e. it has enacted new authority set).
+	pub(crate) fn import_finality_proof<T: Config<I>, I: 'static>(
+		finality_target: BridgedHeader<T, I>,
+		justification: GrandpaJustification<BridgedHeader<T, I>>,
 	) -> Result<bool, sp_runtime::DispatchError> {
-		let mut change_enacted = false;
+		let hash = finality_target.hash();
+		log::trace!(target: "runtime::bridge-grandpa", "Going to try and finalize header {:?}", finality_target);
+
+		let set_id = check_finality_proof::<T, I>(&finality_target, &justification)?;
+		let enacted = try_enact_authority_change::<T, I>(&finality_target, set_id)?;
+		<RequestCount<T, I>>::mutate(|count| *count += 1);
+
+		// the only imported header that we may check is the header with parent number
+		let parent = (*finality_target.number() - One::one(), *finality_target.parent_hash());
+		let conflicting_hash = <ImportedHashesByNumber<T, I>>::get(parent.0);
+		insert_header::<T, I>(finality_target, hash);
+		if let Some(conflicting_hash) = conflicting_hash {
+			prune_fork::<T, I>(conflicting_hash, hash, &[parent]);
+		}
+		log::info!(target: "runtime::bridge-grandpa", "Succesfully imported finalized header with hash {:?}!", hash);
 
-		// We don't support forced changes - at that point governance intervention is required.
+		Ok(enacted)
+	}
+
+	/// Try to use one of free mandatory header slots of the current block.
+	///
+	/// Returns false if all free slots have already been used.
+	pub(crate) fn try_use_free_mandatory_header_slot<T: Config<I>, I: 'static>() -> bool {
+		<FreeMandatoryHeadersCount<T, I>>::mutate(|count| {
+			if *count >= T::FreeMandatoryHeadersPerBlock::get() {
+				return false;
+			}
+
+			*count += 1;
+			true
+		})
+	}
+
+	/// Verify that the header is finalized according to the given finality proof and may be
+	/// imported.
+	///
+	/// Returns identifier of the authority set that has finalized the header.
+	pub(crate) fn check_finality_proof<T: Config<I>, I: 'static>(
+		finality_target: &BridgedHeader<T, I>,
+		justification: &GrandpaJustification<BridgedHeader<T, I>>,
+	) -> Result<sp_finality_grandpa::SetId, Error<T, I>> {
+		let (hash, number) = (finality_target.hash(), finality_target.number());
+
+		let best_finalized = match <ImportedHeaders<T, I>>::get(<BestFinalized<T, I>>::get()) {
+			Some(best_finalized) => best_finalized,
+			None => {
+				log::error!(
+					target: "runtime::bridge-grandpa",
+					"Cannot finalize header {:?} because pallet is not yet initialized",
+					finality_target,
+				);
+				fail!(<Error<T, I>>::NotInitialized);
+			}
+		};
+
+		// We do a quick check here to ensure that our header chain is making progress and isn't
+		// "travelling back in time" (which could be indicative of something bad, e.g a hard-fork).
+		ensure!(best_finalized.number() < number, <Error<T, I>>::OldHeader);
 		ensure!(
-			super::find_forced_change(header).is_none(),
-			<Error<T, I>>::UnsupportedScheduledChange
+			!<MandatoryHeadersOnly<T, I>>::get() || super::find_scheduled_change(&finality_target).is_some(),
+			<Error<T, I>>::NotMandatoryHeader
 		);
 
-		if let Some(change) = super::find_scheduled_change(header) {
-			// GRANDPA only includes a `delay` for forced changes, so this isn't valid.
-			ensure!(change.delay == Zero::zero(), <Error<T, I>>::UnsupportedScheduledChange);
+		let authority_set = <CurrentAuthoritySet<T, I>>::get();
+		let set_id = authority_set.set_id;
+		verify_justification::<T, I>(justification, hash, *number, authority_set)?;
 
-			// TODO [#788]: Stop manually increasing the `set_id` here.
-			let next_authorities = bp_header_chain::AuthoritySet {
-				authorities: change.next_authorities,
-				set_id: current_set_id + 1,
-			};
+		Ok(set_id)
+	}
+
+	/// Verify that the header is finalized by the given historical authority set and may be
+	/// imported.
+	pub(crate) fn check_historical_finality_proof<T: Config<I>, I: 'static>(
+		finality_target: &BridgedHeader<T, I>,
+		justification: &GrandpaJustification<BridgedHeader<T, I>>,
+		authority_set_id: sp_finality_grandpa::SetId,
+	) -> Result<(), Error<T, I>> {
+		let (hash, number) = (finality_target.hash(), *finality_target.number());
+
+		let authority_set =
+			<AuthoritySetsHistory<T, I>>::get(authority_set_id).ok_or(<Error<T, I>>::UnknownHistoricalAuthoritySet)?;
+		let superseded_at = authority_set
+			.superseded_at
+			.ok_or(<Error<T, I>>::UnknownHistoricalAuthoritySet)?;
+
+		// the header that has enacted the next set is finalized by this set, but it is already
+		// imported, so we're only accepting headers between two set changes
+		ensure!(
+			authority_set.enacted_at < number && number < superseded_at,
+			<Error<T, I>>::HeaderNotFinalizedBySet
+		);
+		ensure!(
+			super::find_scheduled_change(finality_target).is_none()
+				&& super::find_forced_change(finality_target).is_none(),
+			<Error<T, I>>::UnsupportedScheduledChange
+		);
+		ensure!(!<MandatoryHeadersOnly<T, I>>::get(), <Error<T, I>>::NotMandatoryHeader);
+
+		// the header must be on the same chain with its imported neighbours
+		ensure!(
+			!<ImportedHashesByNumber<T, I>>::contains_key(number),
+			<Error<T, I>>::ConflictingHeader
+		);
+		if let Some(parent_hash) = <ImportedHashesByNumber<T, I>>::get(number - One::one()) {
+			ensure!(
+				parent_hash == *finality_target.parent_hash(),
+				<Error<T, I>>::ConflictingHeader
+			);
+		}
+		if let Some(child) =
+			<ImportedHashesByNumber<T, I>>::get(number + One::one()).and_then(<ImportedHeaders<T, I>>::get)
+		{
+			ensure!(*child.parent_hash() == hash, <Error<T, I>>::ConflictingHeader);
+		}
+
+		verify_justification::<T, I>(
+			justification,
+			hash,
+			number,
+			bp_header_chain::AuthoritySet::new(authority_set.authorities, authority_set_id),
+		)
+	}
+
+	/// Check the given header for a GRANDPA scheduled authority set change. If a change
+	/// is found it will be enacted immediately.
+	///
+	/// This function does not support forced changes, or scheduled changes with delays
+	/// since these types of changes are indicative of abnormal behavior from GRANDPA.
+	///
+	/// Returned value will indicate if a change was enacted or not.
+	pub(crate) fn try_enact_authority_change<T: Config<I>, I: 'static>(
+		header: &BridgedHeader<T, I>,
+		current_set_id: sp_finality_grandpa::SetId,
+	) -> Result<bool, sp_runtime::DispatchError> {
+		let mut change_enacted = false;
+
+		ensure_supported_authority_change::<T, I>(header)?;
+
+		if let Some(change) = super::find_scheduled_change(header) {
+			// TODO [#788]: Stop manually increasing the `set_id` here.
+			let next_authorities = bp_header_chain::AuthoritySet {
+				authorities: change.next_authorities,
+				set_id: current_set_id + 1,
+			};
 
 			// Since our header schedules a change and we know the delay is 0, it must also enact
 			// the change.
 			<CurrentAuthoritySet<T, I>>::put(&next_authorities);
+			insert_authority_set_change::<T, I>(next_authorities.set_id, header.hash(), *header.number());
+			insert_historical_authority_set::<T, I>(
+				next_authorities.set_id,
+				next_authorities.authorities.clone(),
+				*header.number(),
+			);
 			change_enacted = true;
 
 			log::info!(
@@ -397,6 +1067,25 @@ pub mod pallet {
 		Ok(change_enacted)
 	}
 
+	/// Ensure that the header doesn't signal authority set changes that are not supported by the
+	/// pallet.
+	pub(crate) fn ensure_supported_authority_change<T: Config<I>, I: 'static>(
+		header: &BridgedHeader<T, I>,
+	) -> Result<(), Error<T, I>> {
+		// We don't support forced changes - at that point governance intervention is required.
+		ensure!(
+			super::find_forced_change(header).is_none(),
+			<Error<T, I>>::UnsupportedScheduledChange
+		);
+
+		if let Some(change) = super::find_scheduled_change(header) {
+			// GRANDPA only includes a `delay` for forced changes, so this isn't valid.
+			ensure!(change.delay == Zero::zero(), <Error<T, I>>::UnsupportedScheduledChange);
+		}
+
+		Ok(())
+	}
+
 	/// Verify a GRANDPA justification (finality proof) for a given header.
 	///
 	/// Will use the GRANDPA current authorities known to the pallet.
@@ -408,7 +1097,7 @@ pub mod pallet {
 		hash: BridgedBlockHash<T, I>,
 		number: BridgedBlockNumber<T, I>,
 		authority_set: bp_header_chain::AuthoritySet,
-	) -> Result<(), sp_runtime::DispatchError> {
+	) -> Result<(), Error<T, I>> {
 		use bp_header_chain::justification::verify_justification;
 
 		let voter_set = VoterSet::new(authority_set.authorities).ok_or(<Error<T, I>>::InvalidAuthoritySet)?;
@@ -434,9 +1123,11 @@ pub mod pallet {
 	/// Note this function solely takes care of updating the storage and pruning old entries,
 	/// but does not verify the validity of such import.
 	pub(crate) fn insert_header<T: Config<I>, I: 'static>(header: BridgedHeader<T, I>, hash: BridgedBlockHash<T, I>) {
-		let index = <ImportedHashesPointer<T, I>>::get();
+		// the pointer may be out of bounds if `HeadersToKeep` has been decreased
+		let index = <ImportedHashesPointer<T, I>>::get() % T::HeadersToKeep::get();
 		let pruning = <ImportedHashes<T, I>>::try_get(index);
 		<BestFinalized<T, I>>::put(hash);
+		<ImportedHashesByNumber<T, I>>::insert(header.number(), hash);
 		<ImportedHeaders<T, I>>::insert(hash, header);
 		<ImportedHashes<T, I>>::insert(index, hash);
 
@@ -444,8 +1135,225 @@ pub mod pallet {
 		<ImportedHashesPointer<T, I>>::put((index + 1) % T::HeadersToKeep::get());
 		if let Ok(hash) = pruning {
 			log::debug!(target: "runtime::bridge-grandpa", "Pruning old header: {:?}.", hash);
-			<ImportedHeaders<T, I>>::remove(hash);
+			remove_header::<T, I>(hash);
+		}
+	}
+
+	/// Verify that the headers are ancestors of the already imported `descendant` header.
+	///
+	/// Returns hashes of the ancestry headers.
+	pub(crate) fn verify_ancestry_proof<T: Config<I>, I: 'static>(
+		descendant: BridgedBlockHash<T, I>,
+		ancestry: &[BridgedHeader<T, I>],
+	) -> Result<Vec<BridgedBlockHash<T, I>>, sp_runtime::DispatchError> {
+		// the ancestry proof that doesn't fit the ring buffer would prune its own headers
+		let max_ancestry_len = T::HeadersToKeep::get() as usize;
+		ensure!(
+			!ancestry.is_empty() && ancestry.len() <= max_ancestry_len,
+			<Error<T, I>>::InvalidAncestryProof
+		);
+
+		let descendant = <ImportedHeaders<T, I>>::get(descendant).ok_or(<Error<T, I>>::UnknownHeader)?;
+		let mut expected_hash = *descendant.parent_hash();
+		let mut hashes = Vec::with_capacity(ancestry.len());
+		for header in ancestry {
+			let hash = header.hash();
+			ensure!(hash == expected_hash, <Error<T, I>>::InvalidAncestryProof);
+			ensure!(
+				!<ImportedHeaders<T, I>>::contains_key(hash),
+				<Error<T, I>>::InvalidAncestryProof
+			);
+
+			expected_hash = *header.parent_hash();
+			hashes.push(hash);
+		}
+
+		Ok(hashes)
+	}
+
+	/// Import a previously verified ancestor header to the storage.
+	///
+	/// Unlike `insert_header`, this function doesn't update the best finalized header.
+	pub(crate) fn insert_ancestor_header<T: Config<I>, I: 'static>(
+		header: BridgedHeader<T, I>,
+		hash: BridgedBlockHash<T, I>,
+	) {
+		let index = <ImportedAncestorHashesPointer<T, I>>::get() % T::HeadersToKeep::get();
+		let pruning = <ImportedAncestorHashes<T, I>>::try_get(index);
+		<ImportedHashesByNumber<T, I>>::insert(header.number(), hash);
+		<ImportedHeaders<T, I>>::insert(hash, header);
+		<ImportedAncestorHashes<T, I>>::insert(index, hash);
+
+		// Update ring buffer pointer and remove old header.
+		<ImportedAncestorHashesPointer<T, I>>::put((index + 1) % T::HeadersToKeep::get());
+		if let Ok(hash) = pruning {
+			log::debug!(target: "runtime::bridge-grandpa", "Pruning old ancestor header: {:?}.", hash);
+			remove_header::<T, I>(hash);
+		}
+	}
+
+	/// Remove imported header from the storage, along with its `ImportedHashesByNumber` entry.
+	///
+	/// Ring buffers are not updated, so they may reference removed headers.
+	pub(crate) fn remove_header<T: Config<I>, I: 'static>(hash: BridgedBlockHash<T, I>) {
+		if let Some(header) = <ImportedHeaders<T, I>>::take(hash) {
+			<ImportedHashesByNumber<T, I>>::mutate_exists(header.number(), |indexed_hash| {
+				if *indexed_hash == Some(hash) {
+					*indexed_hash = None;
+				}
+			});
+		}
+	}
+
+	/// Prune imported header that is conflicting with the newly imported header and emit the
+	/// `ForkDetected` event.
+	///
+	/// The `canonical_hashes` are known (number, hash) pairs of the new header ancestors. Imported
+	/// ancestors of the conflicting header are also pruned, if they're conflicting with these
+	/// hashes. The best finalized header is never pruned. Returns number of pruned headers.
+	pub(crate) fn prune_fork<T: Config<I>, I: 'static>(
+		conflicting_hash: BridgedBlockHash<T, I>,
+		imported_hash: BridgedBlockHash<T, I>,
+		canonical_hashes: &[(BridgedBlockNumber<T, I>, BridgedBlockHash<T, I>)],
+	) -> u32 {
+		let best_finalized = <BestFinalized<T, I>>::get();
+		let mut pruned_headers = 0;
+		let mut hash = conflicting_hash;
+		while let Some(header) = <ImportedHeaders<T, I>>::get(hash) {
+			let is_conflicting = canonical_hashes
+				.iter()
+				.any(|(number, canonical_hash)| number == header.number() && *canonical_hash != hash);
+			if !is_conflicting || hash == best_finalized {
+				break;
+			}
+
+			log::warn!(
+				target: "runtime::bridge-grandpa",
+				"Pruning header {:?} that is conflicting with imported header {:?}.",
+				hash,
+				imported_hash,
+			);
+			remove_header::<T, I>(hash);
+			pruned_headers += 1;
+			hash = *header.parent_hash();
+		}
+
+		if pruned_headers != 0 {
+			Pallet::<T, I>::deposit_event(Event::ForkDetected(conflicting_hash, imported_hash));
+		}
+
+		pruned_headers
+	}
+
+	/// Prune at most `max_headers` ring buffer slots that are above the current `HeadersToKeep`
+	/// value, removing headers that are referenced by these slots.
+	///
+	/// The best finalized header is never removed, even if its slot is pruned. Returns number of
+	/// pruned slots.
+	pub(crate) fn prune_obsolete_headers<T: Config<I>, I: 'static>(max_headers: u32) -> u32 {
+		let headers_to_keep = T::HeadersToKeep::get();
+		let mut capacity = <ImportedHashesCapacity<T, I>>::get();
+		if capacity <= headers_to_keep {
+			if capacity < headers_to_keep {
+				<ImportedHashesCapacity<T, I>>::put(headers_to_keep);
+			}
+			return 0;
+		}
+
+		let best_finalized = <BestFinalized<T, I>>::get();
+		let mut pruned_headers = 0;
+		while capacity > headers_to_keep && pruned_headers < max_headers {
+			capacity -= 1;
+			pruned_headers += 1;
+
+			let pruned_hashes = <ImportedHashes<T, I>>::take(capacity)
+				.into_iter()
+				.chain(<ImportedAncestorHashes<T, I>>::take(capacity));
+			for hash in pruned_hashes {
+				if hash != best_finalized {
+					log::debug!(target: "runtime::bridge-grandpa", "Pruning obsolete header: {:?}.", hash);
+					remove_header::<T, I>(hash);
+				}
+			}
+		}
+
+		<ImportedHashesCapacity<T, I>>::put(capacity);
+		pruned_headers
+	}
+
+	/// Returns weight of pruning given number of obsolete ring buffer slots.
+	pub(crate) fn prune_obsolete_headers_weight<T: Config<I>, I: 'static>(pruned_headers: u32) -> Weight {
+		if pruned_headers == 0 {
+			return T::DbWeight::get().reads_writes(1, 1);
+		}
+
+		let pruned_headers = pruned_headers as Weight;
+		T::DbWeight::get().reads_writes(2 + 4 * pruned_headers, 1 + 6 * pruned_headers)
+	}
+
+	/// Remember enacted authority set change, pruning the oldest change if required.
+	pub(crate) fn insert_authority_set_change<T: Config<I>, I: 'static>(
+		set_id: sp_finality_grandpa::SetId,
+		block_hash: BridgedBlockHash<T, I>,
+		block_number: BridgedBlockNumber<T, I>,
+	) {
+		let changes_to_keep = T::AuthoritySetChangesToKeep::get();
+		if changes_to_keep == 0 {
+			return;
+		}
+
+		let index = <AuthoritySetChangesPointer<T, I>>::get();
+		<AuthoritySetChanges<T, I>>::insert(
+			index,
+			bp_header_chain::AuthoritySetChange {
+				set_id,
+				block_hash,
+				block_number,
+			},
+		);
+		<AuthoritySetChangesPointer<T, I>>::put((index + 1) % changes_to_keep);
+	}
+
+	/// Remember enacted authority set, marking the previous set as superseded and pruning the
+	/// oldest set if required.
+	pub(crate) fn insert_historical_authority_set<T: Config<I>, I: 'static>(
+		set_id: sp_finality_grandpa::SetId,
+		authorities: sp_finality_grandpa::AuthorityList,
+		enacted_at: BridgedBlockNumber<T, I>,
+	) {
+		let sets_to_keep = T::AuthoritySetsToKeep::get();
+		if let Some(previous_set_id) = set_id.checked_sub(1) {
+			if sets_to_keep != 0 {
+				<AuthoritySetsHistory<T, I>>::mutate(previous_set_id, |previous_set| {
+					if let Some(previous_set) = previous_set {
+						previous_set.superseded_at = Some(enacted_at);
+					}
+				});
+			}
+
+			// `AuthoritySetsToKeep` may have been decreased by the runtime upgrade, so there may be
+			// several sets to prune
+			let mut pruned_set_id = previous_set_id.checked_sub(sets_to_keep.into());
+			while let Some(set_id) = pruned_set_id {
+				if <AuthoritySetsHistory<T, I>>::take(set_id).is_none() {
+					break;
+				}
+				pruned_set_id = set_id.checked_sub(1);
+			}
+		}
+
+		if sets_to_keep == 0 {
+			return;
 		}
+
+		<AuthoritySetsHistory<T, I>>::insert(
+			set_id,
+			bp_header_chain::HistoricalAuthoritySet {
+				authorities,
+				enacted_at,
+				superseded_at: None,
+			},
+		);
 	}
 
 	/// Since this writes to storage with no real checks this should only be used in functions that
@@ -458,17 +1366,24 @@ pub mod pallet {
 			authority_list,
 			set_id,
 			is_halted,
+			genesis_hash,
 		} = init_params;
 
 		let initial_hash = header.hash();
 		<InitialHash<T, I>>::put(initial_hash);
+		let initial_number = *header.number();
 		<ImportedHashesPointer<T, I>>::put(0);
+		<ImportedHashesCapacity<T, I>>::put(T::HeadersToKeep::get());
 		insert_header::<T, I>(header, initial_hash);
 
-		let authority_set = bp_header_chain::AuthoritySet::new(authority_list, set_id);
+		let authority_set = bp_header_chain::AuthoritySet::new(authority_list.clone(), set_id);
 		<CurrentAuthoritySet<T, I>>::put(authority_set);
+		<AuthoritySetChangesPointer<T, I>>::put(0);
+		insert_authority_set_change::<T, I>(set_id, initial_hash, initial_number);
+		insert_historical_authority_set::<T, I>(set_id, authority_list, initial_number);
 
 		<IsHalted<T, I>>::put(is_halted);
+		<BridgedGenesisHash<T, I>>::put(genesis_hash);
 	}
 
 	#[cfg(feature = "runtime-benchmarks")]
@@ -518,6 +1433,8 @@ impl<T: Config<I>, I: 'static> Pallet<T, I> {
 	///
 	/// Returns a dummy header if there is no best header. This can only happen
 	/// if the pallet has not been initialized yet.
+	///
+	/// In the mandatory-headers-only mode, this is the last imported mandatory header.
 	pub fn best_finalized() -> BridgedHeader<T, I> {
 		let hash = <BestFinalized<T, I>>::get();
 		<ImportedHeaders<T, I>>::get(hash).unwrap_or_else(|| {
@@ -531,11 +1448,86 @@ impl<T: Config<I>, I: 'static> Pallet<T, I> {
 		})
 	}
 
+	/// Returns imported header with given hash, if it is still in the storage.
+	pub fn imported_header(hash: BridgedBlockHash<T, I>) -> Option<BridgedHeader<T, I>> {
+		<ImportedHeaders<T, I>>::get(hash)
+	}
+
 	/// Check if a particular header is known to the bridge pallet.
 	pub fn is_known_header(hash: BridgedBlockHash<T, I>) -> bool {
 		<ImportedHeaders<T, I>>::contains_key(hash)
 	}
 
+	/// Returns number of the header with given hash, if it is known to the pallet.
+	///
+	/// All headers that are known to the pallet are finalized: they are either imported using
+	/// finality proofs, or they are ancestors of such headers.
+	pub fn is_known_and_finalized(hash: BridgedBlockHash<T, I>) -> Option<BridgedBlockNumber<T, I>> {
+		<ImportedHeaders<T, I>>::get(hash).map(|header| *header.number())
+	}
+
+	/// Returns known authority set changes, ordered from the oldest to the newest.
+	///
+	/// Only last `T::AuthoritySetChangesToKeep` changes are returned.
+	pub fn authority_set_changes() -> Vec<AuthoritySetChangeOf<T, I>> {
+		let changes_to_keep = T::AuthoritySetChangesToKeep::get();
+		let pointer = <AuthoritySetChangesPointer<T, I>>::get();
+		(0..changes_to_keep)
+			.map(|offset| (pointer + offset) % changes_to_keep)
+			.filter_map(|index| <AuthoritySetChanges<T, I>>::get(index))
+			.collect()
+	}
+
+	/// Returns true if all pallet operations are halted.
+	pub fn is_halted() -> bool {
+		<IsHalted<T, I>>::get()
+	}
+
+	/// Returns true if the pallet only accepts mandatory headers.
+	pub fn is_mandatory_headers_only() -> bool {
+		<MandatoryHeadersOnly<T, I>>::get()
+	}
+
+	/// Verify that the header may be imported using given finality proof, without importing it.
+	///
+	/// Performs the same checks as the `submit_finality_proof` call, except the rate limiter check.
+	pub fn verify_finality_proof(
+		finality_target: &BridgedHeader<T, I>,
+		justification: &GrandpaJustification<BridgedHeader<T, I>>,
+	) -> Result<(), sp_runtime::DispatchError> {
+		Self::do_verify_finality_proof(finality_target, justification).map_err(Into::into)
+	}
+
+	/// Decode header and justification and verify that the header may be imported using this
+	/// justification, without importing it.
+	///
+	/// This is supposed to be used in implementations of the `<BridgedChain>FinalityApi` runtime API.
+	pub fn verify_encoded_finality_proof(
+		mut finality_target: &[u8],
+		mut justification: &[u8],
+	) -> Result<(), FinalityProofError> {
+		let finality_target =
+			BridgedHeader::<T, I>::decode(&mut finality_target).map_err(|_| FinalityProofError::InvalidEncoding)?;
+		let justification = GrandpaJustification::<BridgedHeader<T, I>>::decode(&mut justification)
+			.map_err(|_| FinalityProofError::InvalidEncoding)?;
+
+		Self::do_verify_finality_proof(&finality_target, &justification).map_err(|error| {
+			let reason = error.as_str().as_bytes().to_vec();
+			FinalityProofError::Rejected(error.into(), reason)
+		})
+	}
+
+	/// Verify that the header may be imported using given finality proof, returning the pallet
+	/// error if it may not.
+	fn do_verify_finality_proof(
+		finality_target: &BridgedHeader<T, I>,
+		justification: &GrandpaJustification<BridgedHeader<T, I>>,
+	) -> Result<(), Error<T, I>> {
+		ensure_operational::<T, I>()?;
+		check_finality_proof::<T, I>(finality_target, justification)?;
+		ensure_supported_authority_change::<T, I>(finality_target)
+	}
+
 	/// Verify that the passed storage proof is valid, given it is crafted using
 	/// known finalized header. If the proof is valid, then the `parse` callback
 	/// is called and the function returns its result.
@@ -545,8 +1537,11 @@ impl<T: Config<I>, I: 'static> Pallet<T, I> {
 		parse: impl FnOnce(bp_runtime::StorageProofChecker<BridgedBlockHasher<T, I>>) -> R,
 	) -> Result<R, sp_runtime::DispatchError> {
 		let header = <ImportedHeaders<T, I>>::get(hash).ok_or(Error::<T, I>::UnknownHeader)?;
-		let storage_proof_checker = bp_runtime::StorageProofChecker::new(*header.state_root(), storage_proof)
-			.map_err(|_| Error::<T, I>::StorageRootMismatch)?;
+		let storage_proof_checker =
+			bp_runtime::StorageProofChecker::new(*header.state_root(), storage_proof).map_err(|error| match error {
+				bp_runtime::StorageProofError::DuplicateNodesInProof => Error::<T, I>::DuplicateNodesInStorageProof,
+				_ => Error::<T, I>::StorageRootMismatch,
+			})?;
 
 		Ok(parse(storage_proof_checker))
 	}
@@ -594,20 +1589,54 @@ pub fn initialize_for_benchmarks<T: Config<I>, I: 'static>(header: BridgedHeader
 		authority_list: sp_std::vec::Vec::new(), // we don't verify any proofs in external benchmarks
 		set_id: 0,
 		is_halted: false,
+		genesis_hash: Default::default(),
 	});
 }
 
+impl<T: Config<I>, I: 'static> From<Error<T, I>> for bp_runtime::BridgeError {
+	fn from(error: Error<T, I>) -> Self {
+		match error {
+			Error::InvalidJustification => Self::InvalidFinalityProof,
+			Error::InvalidAuthoritySet => Self::InvalidAuthoritySet,
+			Error::TooManyRequests => Self::TooManyRequests,
+			Error::OldHeader => Self::ObsoleteHeader,
+			Error::UnknownHeader => Self::UnknownHeader,
+			Error::UnsupportedScheduledChange => Self::InvalidAuthoritySet,
+			Error::NotInitialized => Self::NotInitialized,
+			Error::AlreadyInitialized => Self::AlreadyInitialized,
+			Error::Halted => Self::Halted,
+			Error::EmptyBatch => Self::InvalidArgument,
+			Error::StorageRootMismatch => Self::InvalidStorageProof,
+			Error::InvalidEquivocationSetId => Self::InvalidArgument,
+			Error::UnknownEquivocationOffender => Self::UnknownEntity,
+			Error::InvalidEquivocationProof => Self::InvalidArgument,
+			Error::DuplicateEquivocationReport => Self::InvalidArgument,
+			Error::NotMandatoryHeader => Self::InvalidArgument,
+			Error::AncestryProofsDisabled => Self::NotAllowed,
+			Error::InvalidAncestryProof => Self::InvalidFinalityProof,
+			Error::NotPendingOwner => Self::NotAllowed,
+			Error::UnknownHistoricalAuthoritySet => Self::InvalidAuthoritySet,
+			Error::HeaderNotFinalizedBySet => Self::InvalidFinalityProof,
+			Error::ConflictingHeader => Self::InvalidArgument,
+			Error::DuplicateNodesInStorageProof => Self::InvalidStorageProof,
+			// hidden variant that is never constructed
+			_ => Self::InvalidState,
+		}
+	}
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
 	use crate::mock::{run_test, test_header, Origin, TestHash, TestHeader, TestNumber, TestRuntime};
+	use bp_runtime::BridgeError;
 	use bp_test_utils::{
-		authority_list, make_default_justification, make_justification_for_header, JustificationGeneratorParams, ALICE,
-		BOB,
+		authority_list, header_id, make_default_justification, make_justification_for_header, signed_precommit,
+		test_keyring, Account, JustificationGeneratorParams, ALICE, BOB, TEST_GRANDPA_ROUND, TEST_GRANDPA_SET_ID,
 	};
 	use codec::Encode;
 	use frame_support::weights::PostDispatchInfo;
-	use frame_support::{assert_err, assert_noop, assert_ok};
+	use frame_support::{assert_err, assert_noop, assert_ok, traits::OnRuntimeUpgrade};
 	use sp_runtime::{Digest, DigestItem, DispatchError};
 
 	fn initialize_substrate_bridge() {
@@ -624,6 +1653,7 @@ mod tests {
 			authority_list: authority_list(),
 			set_id: 1,
 			is_halted: false,
+			genesis_hash: Default::default(),
 		};
 
 		Pallet::<TestRuntime>::initialize(origin, init_data.clone()).map(|_| init_data)
@@ -700,6 +1730,10 @@ mod tests {
 				init_data.authority_list
 			);
 			assert!(!IsHalted::<TestRuntime>::get());
+			assert_eq!(
+				Pallet::<TestRuntime>::bridged_genesis_hash(),
+				Some(init_data.genesis_hash)
+			);
 		})
 	}
 
@@ -719,14 +1753,16 @@ mod tests {
 		run_test(|| {
 			PalletOwner::<TestRuntime>::put(2);
 
-			assert_ok!(Pallet::<TestRuntime>::set_owner(Origin::root(), Some(1)));
+			assert_ok!(Pallet::<TestRuntime>::propose_owner(Origin::root(), Some(1)));
+			assert_eq!(PalletOwner::<TestRuntime>::get(), Some(2));
+			assert_ok!(Pallet::<TestRuntime>::accept_owner(Origin::signed(1)));
 			assert_noop!(
 				Pallet::<TestRuntime>::set_operational(Origin::signed(2), false),
 				DispatchError::BadOrigin,
 			);
 			assert_ok!(Pallet::<TestRuntime>::set_operational(Origin::root(), false));
 
-			assert_ok!(Pallet::<TestRuntime>::set_owner(Origin::signed(1), None));
+			assert_ok!(Pallet::<TestRuntime>::propose_owner(Origin::signed(1), None));
 			assert_noop!(
 				Pallet::<TestRuntime>::set_operational(Origin::signed(1), true),
 				DispatchError::BadOrigin,
@@ -739,6 +1775,48 @@ mod tests {
 		});
 	}
 
+	#[test]
+	fn ownership_may_only_be_accepted_by_proposed_owner() {
+		run_test(|| {
+			frame_system::Pallet::<TestRuntime>::set_block_number(1);
+			PalletOwner::<TestRuntime>::put(2);
+
+			assert_noop!(
+				Pallet::<TestRuntime>::accept_owner(Origin::signed(1)),
+				Error::<TestRuntime>::NotPendingOwner,
+			);
+
+			assert_ok!(Pallet::<TestRuntime>::propose_owner(Origin::signed(2), Some(1)));
+			assert_noop!(
+				Pallet::<TestRuntime>::accept_owner(Origin::signed(3)),
+				Error::<TestRuntime>::NotPendingOwner,
+			);
+			assert_noop!(
+				Pallet::<TestRuntime>::accept_owner(Origin::root()),
+				DispatchError::BadOrigin
+			);
+
+			assert_ok!(Pallet::<TestRuntime>::accept_owner(Origin::signed(1)));
+			assert_eq!(PalletOwner::<TestRuntime>::get(), Some(1));
+			assert_eq!(PendingPalletOwner::<TestRuntime>::get(), None);
+			assert_noop!(
+				Pallet::<TestRuntime>::accept_owner(Origin::signed(1)),
+				Error::<TestRuntime>::NotPendingOwner,
+			);
+
+			assert_eq!(
+				frame_system::Pallet::<TestRuntime>::events()
+					.into_iter()
+					.map(|record| record.event)
+					.collect::<Vec<_>>(),
+				vec![
+					crate::mock::Event::Grandpa(Event::OwnerProposed(1)),
+					crate::mock::Event::Grandpa(Event::OwnerChanged(Some(1))),
+				],
+			);
+		});
+	}
+
 	#[test]
 	fn pallet_may_be_halted_by_root() {
 		run_test(|| {
@@ -781,6 +1859,80 @@ mod tests {
 		})
 	}
 
+	#[test]
+	fn mandatory_headers_only_mode_may_be_changed_by_owner_or_root() {
+		run_test(|| {
+			PalletOwner::<TestRuntime>::put(2);
+
+			assert_noop!(
+				Pallet::<TestRuntime>::set_mandatory_headers_only(Origin::signed(1), true),
+				DispatchError::BadOrigin,
+			);
+			assert_ok!(Pallet::<TestRuntime>::set_mandatory_headers_only(
+				Origin::signed(2),
+				true
+			));
+			assert!(Pallet::<TestRuntime>::is_mandatory_headers_only());
+			assert_ok!(Pallet::<TestRuntime>::set_mandatory_headers_only(Origin::root(), false));
+			assert!(!Pallet::<TestRuntime>::is_mandatory_headers_only());
+		});
+	}
+
+	#[test]
+	fn pallet_rejects_optional_headers_in_mandatory_headers_only_mode() {
+		run_test(|| {
+			initialize_substrate_bridge();
+			assert_ok!(Pallet::<TestRuntime>::set_mandatory_headers_only(Origin::root(), true));
+
+			assert_noop!(submit_finality_proof(1), Error::<TestRuntime>::NotMandatoryHeader);
+
+			let mut header = test_header(2);
+			header.digest = change_log(0);
+			let justification = make_default_justification(&header);
+			assert_ok!(Pallet::<TestRuntime>::submit_finality_proof(
+				Origin::signed(1),
+				header.clone(),
+				justification
+			));
+			assert_eq!(Pallet::<TestRuntime>::best_finalized(), header);
+
+			assert_ok!(Pallet::<TestRuntime>::set_mandatory_headers_only(Origin::root(), false));
+			let header = test_header(3);
+			let justification = make_justification_for_header(JustificationGeneratorParams {
+				header: header.clone(),
+				set_id: 2,
+				authorities: vec![(ALICE, 1), (BOB, 1)],
+				..Default::default()
+			});
+			assert_ok!(Pallet::<TestRuntime>::submit_finality_proof(
+				Origin::signed(1),
+				header,
+				justification
+			));
+		});
+	}
+
+	#[test]
+	fn is_known_and_finalized_returns_number_of_imported_header() {
+		run_test(|| {
+			initialize_substrate_bridge();
+			assert_ok!(submit_finality_proof(1));
+
+			assert_eq!(
+				Pallet::<TestRuntime>::is_known_and_finalized(test_header(0).hash()),
+				Some(0)
+			);
+			assert_eq!(
+				Pallet::<TestRuntime>::is_known_and_finalized(test_header(1).hash()),
+				Some(1)
+			);
+			assert_eq!(
+				Pallet::<TestRuntime>::is_known_and_finalized(test_header(2).hash()),
+				None
+			);
+		})
+	}
+
 	#[test]
 	fn pallet_rejects_header_if_not_initialized_yet() {
 		run_test(|| {
@@ -800,28 +1952,149 @@ mod tests {
 		})
 	}
 
+	fn finality_proof(header: u8) -> (TestHeader, GrandpaJustification<TestHeader>) {
+		let header = test_header(header.into());
+		let justification = make_default_justification(&header);
+		(header, justification)
+	}
+
 	#[test]
-	fn rejects_justification_that_skips_authority_set_transition() {
+	fn finality_proof_is_verified_without_importing_header() {
 		run_test(|| {
 			initialize_substrate_bridge();
 
 			let header = test_header(1);
+			let justification = make_default_justification(&header);
+			assert_ok!(Pallet::<TestRuntime>::verify_finality_proof(&header, &justification));
+			assert_eq!(
+				Pallet::<TestRuntime>::verify_encoded_finality_proof(&header.encode(), &justification.encode()),
+				Ok(()),
+			);
+
+			assert_eq!(<BestFinalized<TestRuntime>>::get(), test_header(0).hash());
+			assert!(!<ImportedHeaders<TestRuntime>>::contains_key(header.hash()));
+			assert_eq!(Pallet::<TestRuntime>::request_count(), 0);
+		})
+	}
+
+	#[test]
+	fn encoded_finality_proof_verification_reports_rejection_reason() {
+		run_test(|| {
+			initialize_substrate_bridge();
 
+			let header = test_header(1);
 			let params = JustificationGeneratorParams::<TestHeader> {
 				set_id: 2,
 				..Default::default()
 			};
 			let justification = make_justification_for_header(params);
 
-			assert_err!(
-				Pallet::<TestRuntime>::submit_finality_proof(Origin::signed(1), header, justification,),
-				<Error<TestRuntime>>::InvalidJustification
+			assert_eq!(
+				Pallet::<TestRuntime>::verify_encoded_finality_proof(&[], &justification.encode()),
+				Err(FinalityProofError::InvalidEncoding),
+			);
+			assert_eq!(
+				Pallet::<TestRuntime>::verify_encoded_finality_proof(&header.encode(), &justification.encode()),
+				Err(FinalityProofError::Rejected(
+					BridgeError::InvalidFinalityProof,
+					b"InvalidJustification".to_vec()
+				)),
+			);
+
+			let mut header = test_header(2);
+			header.digest = forced_change_log(0);
+			let justification = make_default_justification(&header);
+			assert_eq!(
+				Pallet::<TestRuntime>::verify_encoded_finality_proof(&header.encode(), &justification.encode()),
+				Err(FinalityProofError::Rejected(
+					BridgeError::InvalidAuthoritySet,
+					b"UnsupportedScheduledChange".to_vec()
+				)),
 			);
 		})
 	}
 
 	#[test]
-	fn does_not_import_header_with_invalid_finality_proof() {
+	fn succesfully_imports_batch_of_headers_with_valid_finality() {
+		run_test(|| {
+			initialize_substrate_bridge();
+
+			assert_ok!(Pallet::<TestRuntime>::submit_finality_proofs(
+				Origin::signed(1),
+				vec![finality_proof(1), finality_proof(2)],
+			));
+
+			let header = test_header(2);
+			assert_eq!(<BestFinalized<TestRuntime>>::get(), header.hash());
+			assert!(<ImportedHeaders<TestRuntime>>::contains_key(test_header(1).hash()));
+			assert!(<ImportedHeaders<TestRuntime>>::contains_key(header.hash()));
+			assert_eq!(Pallet::<TestRuntime>::request_count(), 2);
+		})
+	}
+
+	#[test]
+	fn batch_of_headers_is_not_imported_if_any_finality_proof_is_invalid() {
+		run_test(|| {
+			initialize_substrate_bridge();
+
+			let (header, mut justification) = finality_proof(2);
+			justification.round = 42;
+
+			assert_err!(
+				Pallet::<TestRuntime>::submit_finality_proofs(
+					Origin::signed(1),
+					vec![finality_proof(1), (header, justification)],
+				),
+				<Error<TestRuntime>>::InvalidJustification
+			);
+
+			assert_eq!(<BestFinalized<TestRuntime>>::get(), test_header(0).hash());
+			assert!(!<ImportedHeaders<TestRuntime>>::contains_key(test_header(1).hash()));
+			assert_eq!(Pallet::<TestRuntime>::request_count(), 0);
+		})
+	}
+
+	#[test]
+	fn batch_of_headers_is_rate_limited() {
+		run_test(|| {
+			initialize_substrate_bridge();
+
+			assert_noop!(
+				Pallet::<TestRuntime>::submit_finality_proofs(
+					Origin::signed(1),
+					vec![finality_proof(1), finality_proof(2), finality_proof(3)],
+				),
+				<Error<TestRuntime>>::TooManyRequests
+			);
+			assert_noop!(
+				Pallet::<TestRuntime>::submit_finality_proofs(Origin::signed(1), vec![]),
+				<Error<TestRuntime>>::EmptyBatch
+			);
+		})
+	}
+
+	#[test]
+	fn rejects_justification_that_skips_authority_set_transition() {
+		run_test(|| {
+			initialize_substrate_bridge();
+
+			let header = test_header(1);
+
+			let params = JustificationGeneratorParams::<TestHeader> {
+				set_id: 2,
+				..Default::default()
+			};
+			let justification = make_justification_for_header(params);
+
+			assert_err!(
+				Pallet::<TestRuntime>::submit_finality_proof(Origin::signed(1), header, justification,),
+				<Error<TestRuntime>>::InvalidJustification
+			);
+		})
+	}
+
+	#[test]
+	fn does_not_import_header_with_invalid_finality_proof() {
 		run_test(|| {
 			initialize_substrate_bridge();
 
@@ -847,6 +2120,7 @@ mod tests {
 				authority_list: invalid_authority_list,
 				set_id: 1,
 				is_halted: false,
+				genesis_hash: Default::default(),
 			};
 
 			assert_ok!(Pallet::<TestRuntime>::initialize(Origin::root(), init_data));
@@ -872,6 +2146,50 @@ mod tests {
 		})
 	}
 
+	fn mandatory_header(number: TestNumber, set_id: u64) -> (TestHeader, GrandpaJustification<TestHeader>) {
+		let mut header = test_header(number);
+		header.digest = change_log(0);
+		// the initial authority set is replaced with ALICE and BOB by every mandatory header
+		let mut params = JustificationGeneratorParams {
+			header: header.clone(),
+			..Default::default()
+		};
+		if set_id != TEST_GRANDPA_SET_ID {
+			params.set_id = set_id;
+			params.authorities = vec![(ALICE, 1), (BOB, 1)];
+		}
+		(header, make_justification_for_header(params))
+	}
+
+	#[test]
+	fn mandatory_headers_are_free_until_limit_is_reached() {
+		run_test(|| {
+			initialize_substrate_bridge();
+
+			// regular header is paid
+			let result = submit_finality_proof(1);
+			assert_eq!(result.unwrap().pays_fee, frame_support::weights::Pays::Yes);
+
+			// first mandatory header in the block is free
+			let (header, justification) = mandatory_header(2, 1);
+			let result = Pallet::<TestRuntime>::submit_finality_proof(Origin::signed(1), header, justification);
+			assert_eq!(result.unwrap().pays_fee, frame_support::weights::Pays::No);
+
+			// mandatory header is paid when all free slots of the block are used
+			next_block();
+			<FreeMandatoryHeadersCount<TestRuntime>>::put(1);
+			let (header, justification) = mandatory_header(3, 2);
+			let result = Pallet::<TestRuntime>::submit_finality_proof(Origin::signed(1), header, justification);
+			assert_eq!(result.unwrap().pays_fee, frame_support::weights::Pays::Yes);
+
+			// counter is reset in the next block
+			next_block();
+			let (header, justification) = mandatory_header(4, 3);
+			let result = Pallet::<TestRuntime>::submit_finality_proof(Origin::signed(1), header, justification);
+			assert_eq!(result.unwrap().pays_fee, frame_support::weights::Pays::No);
+		})
+	}
+
 	#[test]
 	fn importing_header_enacts_new_authority_set() {
 		run_test(|| {
@@ -904,6 +2222,334 @@ mod tests {
 				<CurrentAuthoritySet<TestRuntime>>::get(),
 				bp_header_chain::AuthoritySet::new(next_authorities, next_set_id),
 			);
+
+			// Make sure that the change has been recorded
+			assert_eq!(
+				Pallet::<TestRuntime>::authority_set_changes(),
+				vec![
+					bp_header_chain::AuthoritySetChange {
+						set_id: 1,
+						block_hash: test_header(0).hash(),
+						block_number: 0,
+					},
+					bp_header_chain::AuthoritySetChange {
+						set_id: next_set_id,
+						block_hash: header.hash(),
+						block_number: 2,
+					},
+				],
+			);
+		})
+	}
+
+	fn import_authority_set_change(number: TestNumber, set_id: sp_finality_grandpa::SetId) -> TestHeader {
+		// initial set is the test keyring and all following sets are [ALICE, BOB]
+		let authorities = if set_id == TEST_GRANDPA_SET_ID {
+			test_keyring()
+		} else {
+			vec![(ALICE, 1), (BOB, 1)]
+		};
+
+		let mut header = test_header(number);
+		header.digest = change_log(0);
+		let justification = make_justification_for_header(JustificationGeneratorParams {
+			header: header.clone(),
+			set_id,
+			authorities,
+			..Default::default()
+		});
+		assert_ok!(Pallet::<TestRuntime>::submit_finality_proof(
+			Origin::signed(1),
+			header.clone(),
+			justification
+		));
+
+		header
+	}
+
+	fn submit_historical_finality_proof(
+		header: TestHeader,
+		authority_set_id: sp_finality_grandpa::SetId,
+	) -> frame_support::dispatch::DispatchResultWithPostInfo {
+		let justification = make_default_justification(&header);
+		Pallet::<TestRuntime>::submit_historical_finality_proof(
+			Origin::signed(1),
+			header,
+			justification,
+			authority_set_id,
+		)
+	}
+
+	#[test]
+	fn enacted_authority_sets_are_stored_in_history() {
+		run_test(|| {
+			initialize_substrate_bridge();
+			assert_eq!(
+				<AuthoritySetsHistory<TestRuntime>>::get(1),
+				Some(bp_header_chain::HistoricalAuthoritySet {
+					authorities: authority_list(),
+					enacted_at: 0,
+					superseded_at: None,
+				}),
+			);
+
+			import_authority_set_change(2, 1);
+			assert_eq!(
+				<AuthoritySetsHistory<TestRuntime>>::get(1),
+				Some(bp_header_chain::HistoricalAuthoritySet {
+					authorities: authority_list(),
+					enacted_at: 0,
+					superseded_at: Some(2),
+				}),
+			);
+			assert_eq!(
+				<AuthoritySetsHistory<TestRuntime>>::get(2),
+				Some(bp_header_chain::HistoricalAuthoritySet {
+					authorities: vec![(ALICE.into(), 1), (BOB.into(), 1)],
+					enacted_at: 2,
+					superseded_at: None,
+				}),
+			);
+		})
+	}
+
+	#[test]
+	fn oldest_historical_authority_sets_are_pruned() {
+		run_test(|| {
+			initialize_substrate_bridge();
+			import_authority_set_change(2, 1);
+			import_authority_set_change(3, 2);
+			next_block();
+			import_authority_set_change(4, 3);
+
+			assert!(!<AuthoritySetsHistory<TestRuntime>>::contains_key(1));
+			assert!(<AuthoritySetsHistory<TestRuntime>>::contains_key(2));
+			assert!(<AuthoritySetsHistory<TestRuntime>>::contains_key(3));
+			assert!(<AuthoritySetsHistory<TestRuntime>>::contains_key(4));
+		})
+	}
+
+	#[test]
+	fn all_obsolete_historical_authority_sets_are_pruned_if_history_is_shrunk() {
+		run_test(|| {
+			crate::mock::AuthoritySetsToKeep::set(&3);
+			initialize_substrate_bridge();
+			import_authority_set_change(2, 1);
+			next_block();
+			import_authority_set_change(3, 2);
+			next_block();
+			import_authority_set_change(4, 3);
+			assert!((1..=4).all(|set_id| <AuthoritySetsHistory<TestRuntime>>::contains_key(set_id)));
+
+			crate::mock::AuthoritySetsToKeep::set(&1);
+			next_block();
+			import_authority_set_change(5, 4);
+
+			assert!((1..=3).all(|set_id| !<AuthoritySetsHistory<TestRuntime>>::contains_key(set_id)));
+			assert!(<AuthoritySetsHistory<TestRuntime>>::contains_key(4));
+			assert!(<AuthoritySetsHistory<TestRuntime>>::contains_key(5));
+		})
+	}
+
+	#[test]
+	fn all_historical_authority_sets_are_pruned_if_history_is_disabled() {
+		run_test(|| {
+			initialize_substrate_bridge();
+			import_authority_set_change(2, 1);
+			assert!(<AuthoritySetsHistory<TestRuntime>>::contains_key(1));
+			assert!(<AuthoritySetsHistory<TestRuntime>>::contains_key(2));
+
+			crate::mock::AuthoritySetsToKeep::set(&0);
+			next_block();
+			import_authority_set_change(3, 2);
+
+			assert!((1..=3).all(|set_id| !<AuthoritySetsHistory<TestRuntime>>::contains_key(set_id)));
+		})
+	}
+
+	#[test]
+	fn authority_sets_history_is_initialized_by_migration() {
+		run_test(|| {
+			type Migration = InitializeAuthoritySetsHistory<TestRuntime, ()>;
+
+			// migration does nothing if pallet is not initialized
+			Migration::on_runtime_upgrade();
+			assert_eq!(<AuthoritySetsHistory<TestRuntime>>::iter().count(), 0);
+
+			// the pallet has been deployed before the history has been tracked
+			initialize_substrate_bridge();
+			import_authority_set_change(2, 1);
+			<AuthoritySetsHistory<TestRuntime>>::remove(1);
+			<AuthoritySetsHistory<TestRuntime>>::remove(2);
+
+			// the set is enacted at the header, stored in the authority set changes
+			Migration::on_runtime_upgrade();
+			assert_eq!(
+				<AuthoritySetsHistory<TestRuntime>>::get(2),
+				Some(bp_header_chain::HistoricalAuthoritySet {
+					authorities: vec![(ALICE.into(), 1), (BOB.into(), 1)],
+					enacted_at: 2,
+					superseded_at: None,
+				}),
+			);
+			assert!(!<AuthoritySetsHistory<TestRuntime>>::contains_key(1));
+
+			// migration does nothing if the current set is already in the history
+			<AuthoritySetsHistory<TestRuntime>>::mutate(2, |set| set.as_mut().unwrap().enacted_at = 1);
+			Migration::on_runtime_upgrade();
+			assert_eq!(<AuthoritySetsHistory<TestRuntime>>::get(2).unwrap().enacted_at, 1);
+
+			// the set is enacted at the best finalized header if enactment is unknown
+			<AuthoritySetsHistory<TestRuntime>>::remove(2);
+			(0..<TestRuntime as Config>::AuthoritySetChangesToKeep::get())
+				.for_each(|index| <AuthoritySetChanges<TestRuntime>>::remove(index));
+			next_block();
+			let header = test_header(3);
+			let justification = make_justification_for_header(JustificationGeneratorParams {
+				header: header.clone(),
+				set_id: 2,
+				authorities: vec![(ALICE, 1), (BOB, 1)],
+				..Default::default()
+			});
+			assert_ok!(Pallet::<TestRuntime>::submit_finality_proof(
+				Origin::signed(1),
+				header,
+				justification
+			));
+			Migration::on_runtime_upgrade();
+			assert_eq!(<AuthoritySetsHistory<TestRuntime>>::get(2).unwrap().enacted_at, 3);
+		})
+	}
+
+	#[test]
+	fn authority_sets_are_not_stored_if_history_is_disabled() {
+		run_test(|| {
+			crate::mock::AuthoritySetsToKeep::set(&0);
+			initialize_substrate_bridge();
+			import_authority_set_change(2, 1);
+
+			assert!(!<AuthoritySetsHistory<TestRuntime>>::contains_key(1));
+			assert!(!<AuthoritySetsHistory<TestRuntime>>::contains_key(2));
+			assert_noop!(
+				submit_historical_finality_proof(test_header(1), 1),
+				<Error<TestRuntime>>::UnknownHistoricalAuthoritySet
+			);
+		})
+	}
+
+	#[test]
+	fn header_finalized_right_before_authority_set_change_may_be_imported() {
+		run_test(|| {
+			initialize_substrate_bridge();
+			let change_header = import_authority_set_change(3, 1);
+
+			// header 2 is finalized by the previous set, so it can't be imported as usual
+			assert_noop!(submit_finality_proof(2), <Error<TestRuntime>>::OldHeader);
+
+			// but it may be imported using the historical authority set
+			assert_ok!(submit_historical_finality_proof(test_header(2), 1));
+			assert!(<ImportedHeaders<TestRuntime>>::contains_key(test_header(2).hash()));
+			assert_eq!(<BestFinalized<TestRuntime>>::get(), change_header.hash());
+			assert_eq!(<RequestCount<TestRuntime>>::get(), 2);
+		})
+	}
+
+	#[test]
+	fn historical_finality_proof_is_rejected_if_set_is_unknown_or_current() {
+		run_test(|| {
+			initialize_substrate_bridge();
+			import_authority_set_change(3, 1);
+
+			assert_noop!(
+				submit_historical_finality_proof(test_header(2), 0),
+				<Error<TestRuntime>>::UnknownHistoricalAuthoritySet
+			);
+			assert_noop!(
+				submit_historical_finality_proof(test_header(4), 2),
+				<Error<TestRuntime>>::UnknownHistoricalAuthoritySet
+			);
+		})
+	}
+
+	#[test]
+	fn historical_finality_proof_is_rejected_if_header_is_not_finalized_by_set() {
+		run_test(|| {
+			initialize_substrate_bridge();
+			import_authority_set_change(3, 1);
+
+			assert_noop!(
+				submit_historical_finality_proof(test_header(4), 1),
+				<Error<TestRuntime>>::HeaderNotFinalizedBySet
+			);
+			assert_noop!(
+				submit_historical_finality_proof(test_header(3), 1),
+				<Error<TestRuntime>>::HeaderNotFinalizedBySet
+			);
+		})
+	}
+
+	#[test]
+	fn historical_finality_proof_is_rejected_if_header_conflicts_with_imported_headers() {
+		run_test(|| {
+			initialize_substrate_bridge();
+			import_authority_set_change(3, 1);
+			assert_ok!(submit_historical_finality_proof(test_header(1), 1));
+			next_block();
+
+			// header is already imported
+			assert_noop!(
+				submit_historical_finality_proof(test_header(1), 1),
+				<Error<TestRuntime>>::ConflictingHeader
+			);
+
+			// header is not a child of imported header
+			let mut fork_header = test_header(2);
+			fork_header.parent_hash = Default::default();
+			assert_noop!(
+				submit_historical_finality_proof(fork_header, 1),
+				<Error<TestRuntime>>::ConflictingHeader
+			);
+		})
+	}
+
+	#[test]
+	fn historical_finality_proof_is_rejected_if_justification_is_invalid() {
+		run_test(|| {
+			initialize_substrate_bridge();
+			import_authority_set_change(3, 1);
+
+			// justification is generated by the initial set, but we're claiming it is set 2
+			let header = test_header(2);
+			let justification = make_justification_for_header(JustificationGeneratorParams {
+				header: header.clone(),
+				set_id: 2,
+				authorities: vec![(ALICE, 1), (BOB, 1)],
+				..Default::default()
+			});
+			assert_noop!(
+				Pallet::<TestRuntime>::submit_historical_finality_proof(Origin::signed(1), header, justification, 1),
+				<Error<TestRuntime>>::InvalidJustification
+			);
+		})
+	}
+
+	#[test]
+	fn should_prune_authority_set_changes_over_changes_to_keep_parameter() {
+		run_test(|| {
+			initialize_substrate_bridge();
+			for set_id in 2..=4 {
+				let header = test_header(set_id);
+				insert_authority_set_change::<TestRuntime, ()>(set_id, header.hash(), set_id);
+			}
+
+			// the initial set change is pruned and the rest is ordered from the oldest to the newest
+			assert_eq!(
+				Pallet::<TestRuntime>::authority_set_changes()
+					.into_iter()
+					.map(|change| (change.set_id, change.block_number))
+					.collect::<Vec<_>>(),
+				vec![(2, 2), (3, 3), (4, 4)],
+			);
 		})
 	}
 
@@ -949,6 +2595,141 @@ mod tests {
 		})
 	}
 
+	fn precommit_equivocation(
+		offender: Account,
+		set_id: sp_finality_grandpa::SetId,
+	) -> Box<EquivocationProof<TestHash, TestNumber>> {
+		precommit_equivocation_of_headers(offender, set_id, 1, 2)
+	}
+
+	fn precommit_equivocation_of_headers(
+		offender: Account,
+		set_id: sp_finality_grandpa::SetId,
+		first_header: u8,
+		second_header: u8,
+	) -> Box<EquivocationProof<TestHash, TestNumber>> {
+		let first = signed_precommit::<TestHeader>(
+			&offender,
+			header_id::<TestHeader>(first_header),
+			TEST_GRANDPA_ROUND,
+			set_id,
+		);
+		let second = signed_precommit::<TestHeader>(
+			&offender,
+			header_id::<TestHeader>(second_header),
+			TEST_GRANDPA_ROUND,
+			set_id,
+		);
+
+		Box::new(EquivocationProof::new(
+			set_id,
+			sp_finality_grandpa::Equivocation::Precommit(finality_grandpa::Equivocation {
+				round_number: TEST_GRANDPA_ROUND,
+				identity: offender.into(),
+				first: (first.precommit, first.signature),
+				second: (second.precommit, second.signature),
+			}),
+		))
+	}
+
+	#[test]
+	fn valid_equivocation_report_halts_pallet() {
+		run_test(|| {
+			initialize_substrate_bridge();
+			frame_system::Pallet::<TestRuntime>::set_block_number(1);
+
+			assert_ok!(Pallet::<TestRuntime>::report_equivocation(
+				Origin::signed(1),
+				precommit_equivocation(ALICE, TEST_GRANDPA_SET_ID),
+			));
+
+			assert!(Pallet::<TestRuntime>::is_halted());
+			assert_eq!(
+				frame_system::Pallet::<TestRuntime>::events()
+					.into_iter()
+					.map(|record| record.event)
+					.collect::<Vec<_>>(),
+				vec![crate::mock::Event::Grandpa(Event::EquivocationReported(
+					TEST_GRANDPA_SET_ID,
+					TEST_GRANDPA_ROUND,
+					ALICE.into(),
+				))],
+			);
+		})
+	}
+
+	#[test]
+	fn duplicate_equivocation_report_is_rejected() {
+		run_test(|| {
+			initialize_substrate_bridge();
+
+			assert_ok!(Pallet::<TestRuntime>::report_equivocation(
+				Origin::signed(1),
+				precommit_equivocation(ALICE, TEST_GRANDPA_SET_ID),
+			));
+			// the same equivocation, reported with other votes, is also rejected
+			assert_noop!(
+				Pallet::<TestRuntime>::report_equivocation(
+					Origin::signed(2),
+					precommit_equivocation_of_headers(ALICE, TEST_GRANDPA_SET_ID, 1, 3),
+				),
+				<Error<TestRuntime>>::DuplicateEquivocationReport
+			);
+
+			// equivocation of other authority is accepted
+			assert_ok!(Pallet::<TestRuntime>::report_equivocation(
+				Origin::signed(1),
+				precommit_equivocation(BOB, TEST_GRANDPA_SET_ID),
+			));
+		})
+	}
+
+	#[test]
+	fn equivocation_report_of_other_authority_set_is_rejected() {
+		run_test(|| {
+			initialize_substrate_bridge();
+
+			assert_noop!(
+				Pallet::<TestRuntime>::report_equivocation(
+					Origin::signed(1),
+					precommit_equivocation(ALICE, TEST_GRANDPA_SET_ID + 1),
+				),
+				<Error<TestRuntime>>::InvalidEquivocationSetId
+			);
+		})
+	}
+
+	#[test]
+	fn equivocation_report_of_unknown_authority_is_rejected() {
+		run_test(|| {
+			initialize_substrate_bridge();
+
+			assert_noop!(
+				Pallet::<TestRuntime>::report_equivocation(
+					Origin::signed(1),
+					precommit_equivocation(Account(100), TEST_GRANDPA_SET_ID),
+				),
+				<Error<TestRuntime>>::UnknownEquivocationOffender
+			);
+		})
+	}
+
+	#[test]
+	fn invalid_equivocation_report_is_rejected() {
+		run_test(|| {
+			initialize_substrate_bridge();
+
+			// both votes are for the same block => it isn't an equivocation
+			assert_noop!(
+				Pallet::<TestRuntime>::report_equivocation(
+					Origin::signed(1),
+					precommit_equivocation_of_headers(ALICE, TEST_GRANDPA_SET_ID, 1, 1),
+				),
+				<Error<TestRuntime>>::InvalidEquivocationProof
+			);
+		})
+	}
+
 	#[test]
 	fn parse_finalized_storage_proof_rejects_proof_on_unknown_header() {
 		run_test(|| {
@@ -982,6 +2763,31 @@ mod tests {
 		});
 	}
 
+	#[test]
+	fn parse_finalized_storage_proof_rejects_proof_with_duplicate_nodes() {
+		run_test(|| {
+			let (state_root, storage_proof) = bp_runtime::craft_valid_storage_proof();
+			let mut storage_proof_nodes: Vec<_> = storage_proof.iter_nodes().collect();
+			storage_proof_nodes.push(storage_proof_nodes[0].clone());
+
+			let mut header = test_header(2);
+			header.set_state_root(state_root);
+
+			let hash = header.hash();
+			<BestFinalized<TestRuntime>>::put(hash);
+			<ImportedHeaders<TestRuntime>>::insert(hash, header);
+
+			assert_noop!(
+				Pallet::<TestRuntime>::parse_finalized_storage_proof(
+					hash,
+					sp_trie::StorageProof::new(storage_proof_nodes),
+					|_| (),
+				),
+				Error::<TestRuntime>::DuplicateNodesInStorageProof,
+			);
+		});
+	}
+
 	#[test]
 	fn rate_limiter_disallows_imports_once_limit_is_hit_in_single_block() {
 		run_test(|| {
@@ -1059,6 +2865,224 @@ mod tests {
 		})
 	}
 
+	/// Returns `count` headers, following the `descendant` header, ordered from the
+	/// child of `descendant` to the best header.
+	fn headers_chain(descendant: TestHeader, count: u64) -> Vec<TestHeader> {
+		let mut headers: Vec<TestHeader> = Vec::new();
+		for number in descendant.number + 1..=descendant.number + count {
+			let mut header = test_header(number);
+			header.set_parent_hash(headers.last().unwrap_or(&descendant).hash());
+			headers.push(header);
+		}
+		headers
+	}
+
+	#[test]
+	fn succesfully_imports_ancestry_proof() {
+		run_test(|| {
+			initialize_substrate_bridge();
+			let mut headers = headers_chain(test_header(0), 4);
+			let best_header = headers.pop().unwrap();
+			let justification = make_default_justification(&best_header);
+			assert_ok!(Pallet::<TestRuntime>::submit_finality_proof(
+				Origin::signed(1),
+				best_header.clone(),
+				justification,
+			));
+
+			headers.reverse();
+			assert_ok!(Pallet::<TestRuntime>::submit_ancestry_proof(
+				Origin::signed(1),
+				best_header.hash(),
+				headers.clone(),
+			));
+
+			assert!(headers
+				.iter()
+				.all(|header| Pallet::<TestRuntime>::is_known_header(header.hash())));
+			assert_eq!(Pallet::<TestRuntime>::best_finalized(), best_header);
+			assert_eq!(<RequestCount<TestRuntime>>::get(), 2);
+		})
+	}
+
+	#[test]
+	fn rejects_invalid_ancestry_proof() {
+		run_test(|| {
+			initialize_substrate_bridge();
+			let mut headers = headers_chain(test_header(0), 4);
+			let best_header = headers.pop().unwrap();
+			let justification = make_default_justification(&best_header);
+			assert_ok!(Pallet::<TestRuntime>::submit_finality_proof(
+				Origin::signed(1),
+				best_header.clone(),
+				justification,
+			));
+			headers.reverse();
+
+			// descendant is unknown
+			assert_noop!(
+				Pallet::<TestRuntime>::submit_ancestry_proof(
+					Origin::signed(1),
+					headers[0].hash(),
+					headers[1..].to_vec()
+				),
+				<Error<TestRuntime>>::UnknownHeader
+			);
+
+			// ancestry is empty
+			assert_noop!(
+				Pallet::<TestRuntime>::submit_ancestry_proof(Origin::signed(1), best_header.hash(), vec![]),
+				<Error<TestRuntime>>::InvalidAncestryProof
+			);
+
+			// headers are not linked
+			assert_noop!(
+				Pallet::<TestRuntime>::submit_ancestry_proof(
+					Origin::signed(1),
+					best_header.hash(),
+					vec![headers[0].clone(), headers[2].clone()],
+				),
+				<Error<TestRuntime>>::InvalidAncestryProof
+			);
+
+			// ancestry contains already imported header
+			assert_noop!(
+				Pallet::<TestRuntime>::submit_ancestry_proof(
+					Origin::signed(1),
+					best_header.hash(),
+					headers.iter().cloned().chain(std::iter::once(test_header(0))).collect(),
+				),
+				<Error<TestRuntime>>::InvalidAncestryProof
+			);
+		})
+	}
+
+	#[test]
+	fn rejects_ancestry_proof_if_disabled() {
+		run_test(|| {
+			initialize_substrate_bridge();
+			crate::mock::AcceptAncestryProofs::set(&false);
+
+			let genesis = test_header(0);
+			let header = headers_chain(genesis.clone(), 1).pop().unwrap();
+			assert_noop!(
+				Pallet::<TestRuntime>::submit_ancestry_proof(Origin::signed(1), header.hash(), vec![genesis]),
+				<Error<TestRuntime>>::AncestryProofsDisabled
+			);
+		})
+	}
+
+	fn fork_detected_events() -> Vec<Event<TestRuntime>> {
+		frame_system::Pallet::<TestRuntime>::events()
+			.into_iter()
+			.filter_map(|record| match record.event {
+				crate::mock::Event::Grandpa(event @ Event::ForkDetected(..)) => Some(event),
+				_ => None,
+			})
+			.collect()
+	}
+
+	/// Returns header with given number that is not a descendant of `test_header(0)`.
+	fn forked_header(number: TestNumber, parent: Option<&TestHeader>) -> TestHeader {
+		let mut header = test_header(number);
+		header.set_extrinsics_root(TestHash::repeat_byte(42));
+		header.set_parent_hash(
+			parent
+				.map(|parent| parent.hash())
+				.unwrap_or_else(|| test_header(0).hash()),
+		);
+		header
+	}
+
+	#[test]
+	fn fork_is_detected_when_finalized_header_conflicts_with_best_finalized_header() {
+		run_test(|| {
+			initialize_substrate_bridge();
+			let forked_header = forked_header(1, None);
+			let justification = make_default_justification(&forked_header);
+			assert_ok!(Pallet::<TestRuntime>::submit_finality_proof(
+				Origin::signed(1),
+				forked_header.clone(),
+				justification,
+			));
+
+			assert_ok!(submit_finality_proof(2));
+			assert!(!Pallet::<TestRuntime>::is_known_header(forked_header.hash()));
+			assert!(Pallet::<TestRuntime>::is_known_header(test_header(0).hash()));
+			assert_eq!(Pallet::<TestRuntime>::best_finalized(), test_header(2));
+			assert_eq!(
+				fork_detected_events(),
+				vec![Event::ForkDetected(forked_header.hash(), test_header(2).hash())],
+			);
+		})
+	}
+
+	#[test]
+	fn fork_is_not_detected_when_finalized_header_extends_best_finalized_header() {
+		run_test(|| {
+			initialize_substrate_bridge();
+			assert_ok!(submit_finality_proof(1));
+			assert_ok!(submit_finality_proof(2));
+
+			assert!(Pallet::<TestRuntime>::is_known_header(test_header(1).hash()));
+			assert!(fork_detected_events().is_empty());
+		})
+	}
+
+	#[test]
+	fn fork_is_detected_when_ancestry_conflicts_with_imported_headers() {
+		run_test(|| {
+			initialize_substrate_bridge();
+
+			// import forked headers 1' and 2'
+			let forked_header1 = forked_header(1, None);
+			let forked_header2 = forked_header(2, Some(&forked_header1));
+			let justification = make_default_justification(&forked_header2);
+			assert_ok!(Pallet::<TestRuntime>::submit_finality_proof(
+				Origin::signed(1),
+				forked_header2.clone(),
+				justification,
+			));
+			assert_ok!(Pallet::<TestRuntime>::submit_ancestry_proof(
+				Origin::signed(1),
+				forked_header2.hash(),
+				vec![forked_header1.clone()],
+			));
+			next_block();
+			next_block();
+
+			// import canonical headers 1..4
+			let mut headers = headers_chain(test_header(0), 4);
+			let best_header = headers.pop().unwrap();
+			let justification = make_default_justification(&best_header);
+			assert_ok!(Pallet::<TestRuntime>::submit_finality_proof(
+				Origin::signed(1),
+				best_header.clone(),
+				justification,
+			));
+			assert!(fork_detected_events().is_empty());
+
+			headers.reverse();
+			assert_ok!(Pallet::<TestRuntime>::submit_ancestry_proof(
+				Origin::signed(1),
+				best_header.hash(),
+				headers.clone(),
+			));
+
+			// the whole forked branch is pruned, but only single event is emitted
+			assert!(!Pallet::<TestRuntime>::is_known_header(forked_header1.hash()));
+			assert!(!Pallet::<TestRuntime>::is_known_header(forked_header2.hash()));
+			assert!(headers
+				.iter()
+				.all(|header| Pallet::<TestRuntime>::is_known_header(header.hash())));
+			assert!(Pallet::<TestRuntime>::is_known_header(test_header(0).hash()));
+			assert_eq!(
+				fork_detected_events(),
+				vec![Event::ForkDetected(forked_header2.hash(), headers[1].hash())],
+			);
+		})
+	}
+
 	#[test]
 	fn should_prune_headers_over_headers_to_keep_parameter() {
 		run_test(|| {
@@ -1084,4 +3108,86 @@ mod tests {
 			);
 		})
 	}
+
+	fn import_headers_and_shrink_ring_buffer() {
+		initialize_substrate_bridge();
+		for header in 1..=4 {
+			assert_ok!(submit_finality_proof(header));
+			next_block();
+		}
+
+		// slots 2..4 are now obsolete
+		crate::mock::HeadersToKeep::set(&2);
+	}
+
+	#[test]
+	fn prune_headers_call_prunes_obsolete_headers() {
+		run_test(|| {
+			import_headers_and_shrink_ring_buffer();
+
+			assert_ok!(Pallet::<TestRuntime>::prune_headers(Origin::signed(1), 10));
+			assert_eq!(<ImportedHashesCapacity<TestRuntime>>::get(), 2);
+			assert!(Pallet::<TestRuntime>::is_known_header(test_header(0).hash()));
+			assert!(Pallet::<TestRuntime>::is_known_header(test_header(1).hash()));
+			assert!(!Pallet::<TestRuntime>::is_known_header(test_header(2).hash()));
+			assert!(!Pallet::<TestRuntime>::is_known_header(test_header(3).hash()));
+			// best finalized header is never pruned
+			assert!(Pallet::<TestRuntime>::is_known_header(test_header(4).hash()));
+
+			// new headers are inserted into the shrunk ring buffer
+			assert_ok!(submit_finality_proof(5));
+			assert!(!Pallet::<TestRuntime>::is_known_header(test_header(0).hash()));
+			assert_eq!(<ImportedHashesPointer<TestRuntime>>::get(), 1);
+		})
+	}
+
+	#[test]
+	fn imported_hashes_capacity_is_initialized_by_migration() {
+		run_test(|| {
+			type Migration = InitializeImportedHashesCapacity<TestRuntime, (), crate::mock::HeadersToKeep>;
+
+			// migration does nothing if pallet is not initialized
+			Migration::on_runtime_upgrade();
+			assert!(!<ImportedHashesCapacity<TestRuntime>>::exists());
+
+			// the pallet has been deployed before the capacity has been tracked
+			initialize_substrate_bridge();
+			for header in 1..=4 {
+				assert_ok!(submit_finality_proof(header));
+				next_block();
+			}
+			<ImportedHashesCapacity<TestRuntime>>::kill();
+
+			// HeadersToKeep is decreased by the same runtime upgrade
+			Migration::on_runtime_upgrade();
+			crate::mock::HeadersToKeep::set(&2);
+			assert_eq!(<ImportedHashesCapacity<TestRuntime>>::get(), 5);
+
+			// obsolete headers are pruned after the upgrade
+			assert_ok!(Pallet::<TestRuntime>::prune_headers(Origin::signed(1), 10));
+			assert_eq!(<ImportedHashesCapacity<TestRuntime>>::get(), 2);
+			assert!(!Pallet::<TestRuntime>::is_known_header(test_header(2).hash()));
+			assert!(!Pallet::<TestRuntime>::is_known_header(test_header(3).hash()));
+
+			// migration does nothing if the capacity is already initialized
+			Migration::on_runtime_upgrade();
+			assert_eq!(<ImportedHashesCapacity<TestRuntime>>::get(), 2);
+		})
+	}
+
+	#[test]
+	fn obsolete_headers_are_pruned_at_the_beginning_of_block() {
+		run_test(|| {
+			import_headers_and_shrink_ring_buffer();
+
+			next_block();
+			assert!(Pallet::<TestRuntime>::is_known_header(test_header(3).hash()));
+			next_block();
+			assert!(!Pallet::<TestRuntime>::is_known_header(test_header(3).hash()));
+			assert!(Pallet::<TestRuntime>::is_known_header(test_header(2).hash()));
+			next_block();
+			assert!(!Pallet::<TestRuntime>::is_known_header(test_header(2).hash()));
+			assert_eq!(<ImportedHashesCapacity<TestRuntime>>::get(), 2);
+		})
+	}
 }
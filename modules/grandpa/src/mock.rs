@@ -42,7 +42,7 @@ construct_runtime! {
 		UncheckedExtrinsic = UncheckedExtrinsic,
 	{
 		System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
-		Grandpa: grandpa::{Pallet},
+		Grandpa: grandpa::{Pallet, Event<T>},
 	}
 }
 
@@ -63,7 +63,7 @@ impl frame_system::Config for TestRuntime {
 	type AccountId = AccountId;
 	type Lookup = IdentityLookup<Self::AccountId>;
 	type Header = Header;
-	type Event = ();
+	type Event = Event;
 	type BlockHashCount = BlockHashCount;
 	type Version = ();
 	type PalletInfo = PalletInfo;
@@ -81,15 +81,27 @@ impl frame_system::Config for TestRuntime {
 
 parameter_types! {
 	pub const MaxRequests: u32 = 2;
-	pub const HeadersToKeep: u32 = 5;
+	pub storage HeadersToKeep: u32 = 5;
+	pub const MaxHeadersToPruneInBlock: u32 = 1;
+	pub const AuthoritySetChangesToKeep: u32 = 3;
+	pub storage AuthoritySetsToKeep: u32 = 2;
 	pub const SessionLength: u64 = 5;
 	pub const NumValidators: u32 = 5;
+	pub storage AcceptAncestryProofs: bool = true;
+	pub const FreeMandatoryHeadersPerBlock: u32 = 1;
 }
 
 impl grandpa::Config for TestRuntime {
+	type Event = Event;
 	type BridgedChain = TestBridgedChain;
 	type MaxRequests = MaxRequests;
 	type HeadersToKeep = HeadersToKeep;
+	type MaxHeadersToPruneInBlock = MaxHeadersToPruneInBlock;
+	type AuthoritySetChangesToKeep = AuthoritySetChangesToKeep;
+	type AuthoritySetsToKeep = AuthoritySetsToKeep;
+	type AcceptAncestryProofs = AcceptAncestryProofs;
+	type FreeMandatoryHeadersPerBlock = FreeMandatoryHeadersPerBlock;
+	type OnEquivocation = grandpa::HaltOnEquivocation<TestRuntime, ()>;
 	type WeightInfo = ();
 }
 
@@ -103,6 +115,12 @@ impl Chain for TestBridgedChain {
 	type Header = <TestRuntime as frame_system::Config>::Header;
 }
 
+impl bp_header_chain::ChainWithGrandpa for TestBridgedChain {
+	const MAX_AUTHORITIES_COUNT: u32 = 16;
+	const REASONABLE_HEADERS_IN_JUSTIFICATON_ANCESTRY: u32 = 8;
+	const MAX_HEADER_SIZE: u32 = 256;
+}
+
 pub fn run_test<T>(test: impl FnOnce() -> T) -> T {
 	sp_io::TestExternalities::new(Default::default()).execute_with(test)
 }
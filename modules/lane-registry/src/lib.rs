@@ -0,0 +1,710 @@
+// Copyright 2019-2021 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Runtime module that maintains registry of permissioned outbound message lanes.
+//!
+//! Lanes of the messages pallet are identified by opaque 4-byte values and, by default,
+//! anyone may send messages over any lane. This pallet allows the registry origin (root or
+//! governance) to register lanes, assigning owner account, set of accounts that are allowed
+//! to send messages over the lane and minimal fee that must be paid by every message.
+//!
+//! The registry is consulted by the messages pallet through the `LaneRegistryVerifier`, which
+//! must be used as (or wrap) the `LaneMessageVerifier` of the messages pallet instance.
+//! Messages that are sent over unregistered lanes are rejected.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use bp_messages::{
+	source_chain::{LaneMessageVerifier, Sender},
+	LaneId, OutboundLaneData,
+};
+use codec::{Decode, Encode};
+use frame_support::{
+	traits::{Get, OnRuntimeUpgrade},
+	weights::Weight,
+	RuntimeDebug,
+};
+use sp_runtime::traits::Zero;
+use sp_std::{marker::PhantomData, prelude::*};
+
+#[cfg(feature = "std")]
+use serde::{Deserialize, Serialize};
+
+#[cfg(test)]
+mod mock;
+
+/// Error message that is used when message is sent over unregistered lane.
+pub const LANE_NOT_REGISTERED: &str = "The lane is not registered.";
+/// Error message that is used when message is sent by account that is not allowed to use the lane.
+pub const SENDER_NOT_ALLOWED: &str = "The sender is not allowed to send messages over the lane.";
+/// Error message that is used when message fee is below minimal fee of the lane.
+pub const TOO_LOW_FEE: &str = "Provided fee is below minimal fee of the lane.";
+
+/// Accounts that are allowed to send messages over the lane.
+#[derive(Encode, Decode, Clone, RuntimeDebug, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub enum AllowedSenders<AccountId> {
+	/// Any account may send messages over the lane.
+	Any,
+	/// Only lane owner may send messages over the lane.
+	Owner,
+	/// Lane owner and listed accounts may send messages over the lane.
+	Listed(Vec<AccountId>),
+}
+
+/// Registered lane information.
+#[derive(Encode, Decode, Clone, RuntimeDebug, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct LaneInfo<AccountId, Balance> {
+	/// Lane owner account. The owner may update lane parameters.
+	pub owner: AccountId,
+	/// Accounts that are allowed to send messages over the lane.
+	pub allowed_senders: AllowedSenders<AccountId>,
+	/// Minimal delivery and dispatch fee that must be paid by every message, sent over the lane.
+	pub minimal_fee: Balance,
+}
+
+impl<AccountId> AllowedSenders<AccountId> {
+	/// Returns number of explicitly allowed senders.
+	pub fn listed_count(&self) -> u32 {
+		match *self {
+			AllowedSenders::Listed(ref senders) => senders.len() as u32,
+			_ => 0,
+		}
+	}
+}
+
+impl<AccountId: PartialEq, Balance> LaneInfo<AccountId, Balance> {
+	/// Returns true if given sender is allowed to send messages over the lane.
+	///
+	/// Root is always allowed to send messages over registered lanes.
+	pub fn is_sender_allowed(&self, sender: &Sender<AccountId>) -> bool {
+		match *sender {
+			Sender::Root => true,
+			Sender::None => false,
+			Sender::Signed(ref sender) => match self.allowed_senders {
+				AllowedSenders::Any => true,
+				AllowedSenders::Owner => *sender == self.owner,
+				AllowedSenders::Listed(ref senders) => *sender == self.owner || senders.contains(sender),
+			},
+		}
+	}
+}
+
+/// Lane information type used by the pallet.
+pub type LaneInfoOf<T, I> = LaneInfo<<T as frame_system::Config>::AccountId, <T as Config<I>>::Balance>;
+
+/// Runtime upgrade that registers lanes, which have been used before the registry has been added
+/// to the runtime.
+///
+/// Every lane from `ExistingLanes` that is not yet registered, is registered with the `LaneOwner`
+/// owner, allowing any sender and with zero minimal fee, so it keeps working as before the upgrade.
+/// Already registered lanes are left unchanged.
+pub struct RegisterExistingLanes<T, I, ExistingLanes, LaneOwner>(PhantomData<(T, I, ExistingLanes, LaneOwner)>);
+
+impl<T, I, ExistingLanes, LaneOwner> OnRuntimeUpgrade for RegisterExistingLanes<T, I, ExistingLanes, LaneOwner>
+where
+	T: Config<I>,
+	I: 'static,
+	ExistingLanes: Get<Vec<LaneId>>,
+	LaneOwner: Get<T::AccountId>,
+{
+	fn on_runtime_upgrade() -> Weight {
+		let existing_lanes = ExistingLanes::get();
+		let mut registered_lanes = 0;
+		for lane in &existing_lanes {
+			if Lanes::<T, I>::contains_key(lane) {
+				continue;
+			}
+
+			Lanes::<T, I>::insert(
+				lane,
+				LaneInfo {
+					owner: LaneOwner::get(),
+					allowed_senders: AllowedSenders::Any,
+					minimal_fee: Zero::zero(),
+				},
+			);
+			registered_lanes += 1;
+		}
+
+		log::info!(
+			target: "runtime::bridge-lane-registry",
+			"Registered {} of {} existing lanes",
+			registered_lanes,
+			existing_lanes.len(),
+		);
+
+		// existing lanes are normally read from the storage, so there are two reads per lane
+		T::DbWeight::get().reads_writes(2 * existing_lanes.len() as Weight, registered_lanes)
+	}
+}
+
+pub use pallet::*;
+
+// comes from #[pallet::event]
+#[allow(clippy::unused_unit)]
+#[frame_support::pallet]
+pub mod pallet {
+	use super::*;
+	use frame_support::pallet_prelude::*;
+	use frame_system::pallet_prelude::*;
+	use sp_runtime::traits::AtLeast32BitUnsigned;
+
+	#[pallet::config]
+	pub trait Config<I: 'static = ()>: frame_system::Config {
+		/// The overarching event type.
+		type Event: From<Event<Self, I>> + IsType<<Self as frame_system::Config>::Event>;
+		/// Origin that is allowed to register and deregister lanes.
+		type RegistryOrigin: EnsureOrigin<Self::Origin>;
+		/// Type of the message fee. Must match the `OutboundMessageFee` of the messages pallet.
+		type Balance: Parameter + Member + AtLeast32BitUnsigned + Copy + MaybeSerializeDeserialize;
+		/// Maximal number of explicitly allowed senders of the single lane.
+		#[pallet::constant]
+		type MaxAllowedSenders: Get<u32>;
+		/// Weight of reading or writing single explicitly allowed sender of the lane.
+		#[pallet::constant]
+		type AllowedSenderWeight: Get<Weight>;
+	}
+
+	#[pallet::pallet]
+	#[pallet::generate_store(pub(super) trait Store)]
+	pub struct Pallet<T, I = ()>(PhantomData<(T, I)>);
+
+	#[pallet::hooks]
+	impl<T: Config<I>, I: 'static> Hooks<BlockNumberFor<T>> for Pallet<T, I> {}
+
+	#[pallet::call]
+	impl<T: Config<I>, I: 'static> Pallet<T, I> {
+		/// Register new lane.
+		///
+		/// May only be called by the registry origin.
+		#[pallet::weight(register_lane_weight::<T, I>(lane_info.allowed_senders.listed_count()))]
+		pub fn register_lane(
+			origin: OriginFor<T>,
+			lane: LaneId,
+			lane_info: LaneInfoOf<T, I>,
+		) -> DispatchResultWithPostInfo {
+			T::RegistryOrigin::ensure_origin(origin)?;
+			ensure!(
+				!Lanes::<T, I>::contains_key(&lane),
+				Error::<T, I>::LaneAlreadyRegistered
+			);
+			ensure_allowed_senders_count::<T, I>(&lane_info.allowed_senders)?;
+
+			log::trace!(target: "runtime::bridge-lane-registry", "Registered lane {:?}: {:?}", lane, lane_info);
+
+			Lanes::<T, I>::insert(&lane, lane_info);
+			Self::deposit_event(Event::LaneRegistered(lane));
+
+			Ok(().into())
+		}
+
+		/// Update parameters of the registered lane.
+		///
+		/// May be called by the registry origin or by the lane owner.
+		#[pallet::weight(update_lane_weight::<T, I>(T::MaxAllowedSenders::get(), allowed_senders.listed_count()))]
+		pub fn update_lane(
+			origin: OriginFor<T>,
+			lane: LaneId,
+			allowed_senders: AllowedSenders<T::AccountId>,
+			minimal_fee: T::Balance,
+		) -> DispatchResultWithPostInfo {
+			let mut lane_info = Lanes::<T, I>::get(&lane).ok_or(Error::<T, I>::UnknownLane)?;
+			if let Err(origin) = T::RegistryOrigin::try_origin(origin) {
+				let submitter = ensure_signed(origin)?;
+				ensure!(submitter == lane_info.owner, Error::<T, I>::NotLaneOwner);
+			}
+			ensure_allowed_senders_count::<T, I>(&allowed_senders)?;
+
+			// refund the difference between the maximal and actual number of previously allowed senders
+			let actual_weight =
+				update_lane_weight::<T, I>(lane_info.allowed_senders.listed_count(), allowed_senders.listed_count());
+
+			lane_info.allowed_senders = allowed_senders;
+			lane_info.minimal_fee = minimal_fee;
+
+			log::trace!(target: "runtime::bridge-lane-registry", "Updated lane {:?}: {:?}", lane, lane_info);
+
+			Lanes::<T, I>::insert(&lane, lane_info);
+			Self::deposit_event(Event::LaneUpdated(lane));
+
+			Ok(Some(actual_weight).into())
+		}
+
+		/// Deregister the lane. No messages may be sent over the lane after this call.
+		///
+		/// May only be called by the registry origin.
+		#[pallet::weight(T::DbWeight::get().reads_writes(1, 2))]
+		pub fn deregister_lane(origin: OriginFor<T>, lane: LaneId) -> DispatchResultWithPostInfo {
+			T::RegistryOrigin::ensure_origin(origin)?;
+			ensure!(Lanes::<T, I>::contains_key(&lane), Error::<T, I>::UnknownLane);
+
+			log::trace!(target: "runtime::bridge-lane-registry", "Deregistered lane {:?}", lane);
+
+			Lanes::<T, I>::remove(&lane);
+			Self::deposit_event(Event::LaneDeregistered(lane));
+
+			Ok(().into())
+		}
+	}
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config<I>, I: 'static = ()> {
+		/// Lane has been registered.
+		LaneRegistered(LaneId),
+		/// Lane parameters have been updated.
+		LaneUpdated(LaneId),
+		/// Lane has been deregistered.
+		LaneDeregistered(LaneId),
+	}
+
+	#[pallet::error]
+	pub enum Error<T, I = ()> {
+		/// The lane is already registered.
+		LaneAlreadyRegistered,
+		/// The lane is not registered.
+		UnknownLane,
+		/// The call origin is not the lane owner.
+		NotLaneOwner,
+		/// Too many allowed senders.
+		TooManyAllowedSenders,
+	}
+
+	/// Registered lanes.
+	#[pallet::storage]
+	#[pallet::getter(fn lane_info)]
+	pub type Lanes<T: Config<I>, I: 'static = ()> = StorageMap<_, Blake2_128Concat, LaneId, LaneInfoOf<T, I>>;
+
+	#[pallet::genesis_config]
+	pub struct GenesisConfig<T: Config<I>, I: 'static = ()> {
+		/// Lanes that are registered at genesis.
+		pub lanes: Vec<(LaneId, LaneInfoOf<T, I>)>,
+	}
+
+	#[cfg(feature = "std")]
+	impl<T: Config<I>, I: 'static> Default for GenesisConfig<T, I> {
+		fn default() -> Self {
+			Self { lanes: Vec::new() }
+		}
+	}
+
+	#[pallet::genesis_build]
+	impl<T: Config<I>, I: 'static> GenesisBuild<T, I> for GenesisConfig<T, I> {
+		fn build(&self) {
+			for (lane, lane_info) in &self.lanes {
+				Lanes::<T, I>::insert(lane, lane_info);
+			}
+		}
+	}
+}
+
+/// Weight of the `register_lane` call with given number of explicitly allowed senders.
+fn register_lane_weight<T: Config<I>, I: 'static>(allowed_senders: u32) -> Weight {
+	T::DbWeight::get()
+		.reads_writes(1, 2)
+		.saturating_add(T::AllowedSenderWeight::get().saturating_mul(allowed_senders as Weight))
+}
+
+/// Weight of the `update_lane` call, that replaces `old_allowed_senders` explicitly allowed senders
+/// with `new_allowed_senders` senders.
+fn update_lane_weight<T: Config<I>, I: 'static>(old_allowed_senders: u32, new_allowed_senders: u32) -> Weight {
+	T::DbWeight::get().reads_writes(1, 2).saturating_add(
+		T::AllowedSenderWeight::get()
+			.saturating_mul((old_allowed_senders as Weight).saturating_add(new_allowed_senders as Weight)),
+	)
+}
+
+/// Weight of reading the lane information by the `LaneRegistryVerifier`.
+fn read_lane_info_weight<T: Config<I>, I: 'static>() -> Weight {
+	T::DbWeight::get()
+		.reads(1)
+		.saturating_add(T::AllowedSenderWeight::get().saturating_mul(T::MaxAllowedSenders::get() as Weight))
+}
+
+/// Ensure that the number of explicitly allowed senders is below the limit.
+fn ensure_allowed_senders_count<T: Config<I>, I: 'static>(
+	allowed_senders: &AllowedSenders<T::AccountId>,
+) -> Result<(), Error<T, I>> {
+	match *allowed_senders {
+		AllowedSenders::Listed(ref senders) if senders.len() > T::MaxAllowedSenders::get() as usize => {
+			Err(Error::<T, I>::TooManyAllowedSenders)
+		}
+		_ => Ok(()),
+	}
+}
+
+/// Message verifier that checks that the lane is registered, the sender is allowed to use
+/// the lane and the message fee is not below minimal lane fee. If all checks pass, the message
+/// is passed to the wrapped `Verifier`.
+#[derive(RuntimeDebug)]
+pub struct LaneRegistryVerifier<T, I, Verifier>(PhantomData<(T, I, Verifier)>);
+
+impl<T, I, Verifier, Payload> LaneMessageVerifier<T::AccountId, Payload, T::Balance>
+	for LaneRegistryVerifier<T, I, Verifier>
+where
+	T: Config<I>,
+	I: 'static,
+	Verifier: LaneMessageVerifier<T::AccountId, Payload, T::Balance>,
+{
+	type Error = &'static str;
+
+	fn verify_message(
+		submitter: &Sender<T::AccountId>,
+		delivery_and_dispatch_fee: &T::Balance,
+		lane: &LaneId,
+		outbound_data: &OutboundLaneData,
+		payload: &Payload,
+	) -> Result<(), Self::Error> {
+		let lane_info = Lanes::<T, I>::get(lane).ok_or(LANE_NOT_REGISTERED)?;
+		if !lane_info.is_sender_allowed(submitter) {
+			return Err(SENDER_NOT_ALLOWED);
+		}
+		if *delivery_and_dispatch_fee < lane_info.minimal_fee {
+			return Err(TOO_LOW_FEE);
+		}
+
+		Verifier::verify_message(submitter, delivery_and_dispatch_fee, lane, outbound_data, payload).map_err(Into::into)
+	}
+
+	fn verify_message_weight() -> Weight {
+		read_lane_info_weight::<T, I>().saturating_add(Verifier::verify_message_weight())
+	}
+}
+
+impl<T: Config<I>, I: 'static> From<Error<T, I>> for bp_runtime::BridgeError {
+	fn from(error: Error<T, I>) -> Self {
+		match error {
+			Error::LaneAlreadyRegistered => Self::InvalidState,
+			Error::UnknownLane => Self::UnknownEntity,
+			Error::NotLaneOwner => Self::NotAllowed,
+			Error::TooManyAllowedSenders => Self::LimitExceeded,
+			// hidden variant that is never constructed
+			_ => Self::InvalidState,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::mock::{
+		run_test, Event as TestEvent, LaneRegistry, Origin, TestRuntime, ALLOWED_SENDER_WEIGHT, MAX_ALLOWED_SENDERS,
+		OWNER, SENDER,
+	};
+	use bp_messages::source_chain::ForbidOutboundMessages;
+	use frame_support::{assert_noop, assert_ok};
+	use frame_system::{EventRecord, Phase};
+	use sp_runtime::DispatchError;
+
+	const TEST_LANE_ID: LaneId = LaneId::Legacy(*b"test");
+	const MINIMAL_FEE: u64 = 100;
+
+	/// Verifier that accepts all messages.
+	struct AcceptAllVerifier;
+
+	impl LaneMessageVerifier<u64, (), u64> for AcceptAllVerifier {
+		type Error = &'static str;
+
+		fn verify_message(
+			_submitter: &Sender<u64>,
+			_delivery_and_dispatch_fee: &u64,
+			_lane: &LaneId,
+			_outbound_data: &OutboundLaneData,
+			_payload: &(),
+		) -> Result<(), Self::Error> {
+			Ok(())
+		}
+	}
+
+	type TestVerifier = LaneRegistryVerifier<TestRuntime, (), AcceptAllVerifier>;
+
+	fn test_lane_info(allowed_senders: AllowedSenders<u64>) -> LaneInfoOf<TestRuntime, ()> {
+		LaneInfo {
+			owner: OWNER,
+			allowed_senders,
+			minimal_fee: MINIMAL_FEE,
+		}
+	}
+
+	fn register_test_lane(allowed_senders: AllowedSenders<u64>) {
+		assert_ok!(LaneRegistry::register_lane(
+			Origin::root(),
+			TEST_LANE_ID,
+			test_lane_info(allowed_senders),
+		));
+	}
+
+	fn verify_message(sender: Sender<u64>, fee: u64) -> Result<(), &'static str> {
+		TestVerifier::verify_message(&sender, &fee, &TEST_LANE_ID, &Default::default(), &())
+	}
+
+	#[test]
+	fn register_lane_works() {
+		run_test(|| {
+			register_test_lane(AllowedSenders::Any);
+
+			assert_eq!(
+				LaneRegistry::lane_info(TEST_LANE_ID),
+				Some(test_lane_info(AllowedSenders::Any))
+			);
+			assert_eq!(
+				frame_system::Pallet::<TestRuntime>::events(),
+				vec![EventRecord {
+					phase: Phase::Initialization,
+					event: TestEvent::LaneRegistry(Event::LaneRegistered(TEST_LANE_ID)),
+					topics: vec![],
+				}],
+			);
+		});
+	}
+
+	#[test]
+	fn register_lane_rejects_non_registry_origin() {
+		run_test(|| {
+			assert_noop!(
+				LaneRegistry::register_lane(Origin::signed(OWNER), TEST_LANE_ID, test_lane_info(AllowedSenders::Any)),
+				DispatchError::BadOrigin,
+			);
+		});
+	}
+
+	#[test]
+	fn register_lane_rejects_already_registered_lane() {
+		run_test(|| {
+			register_test_lane(AllowedSenders::Any);
+
+			assert_noop!(
+				LaneRegistry::register_lane(Origin::root(), TEST_LANE_ID, test_lane_info(AllowedSenders::Owner)),
+				Error::<TestRuntime, ()>::LaneAlreadyRegistered,
+			);
+		});
+	}
+
+	#[test]
+	fn register_lane_rejects_too_many_allowed_senders() {
+		run_test(|| {
+			assert_noop!(
+				LaneRegistry::register_lane(
+					Origin::root(),
+					TEST_LANE_ID,
+					test_lane_info(AllowedSenders::Listed(vec![SENDER; MAX_ALLOWED_SENDERS as usize + 1])),
+				),
+				Error::<TestRuntime, ()>::TooManyAllowedSenders,
+			);
+		});
+	}
+
+	#[test]
+	fn update_lane_works_for_owner_and_registry_origin() {
+		run_test(|| {
+			register_test_lane(AllowedSenders::Any);
+
+			assert_ok!(LaneRegistry::update_lane(
+				Origin::signed(OWNER),
+				TEST_LANE_ID,
+				AllowedSenders::Owner,
+				MINIMAL_FEE * 2,
+			));
+			assert_eq!(
+				LaneRegistry::lane_info(TEST_LANE_ID)
+					.map(|lane_info| (lane_info.allowed_senders, lane_info.minimal_fee)),
+				Some((AllowedSenders::Owner, MINIMAL_FEE * 2)),
+			);
+
+			assert_ok!(LaneRegistry::update_lane(
+				Origin::root(),
+				TEST_LANE_ID,
+				AllowedSenders::Listed(vec![SENDER]),
+				MINIMAL_FEE,
+			));
+			assert_eq!(
+				LaneRegistry::lane_info(TEST_LANE_ID),
+				Some(test_lane_info(AllowedSenders::Listed(vec![SENDER]))),
+			);
+		});
+	}
+
+	#[test]
+	fn update_lane_refunds_weight_of_missing_allowed_senders() {
+		run_test(|| {
+			register_test_lane(AllowedSenders::Listed(vec![SENDER]));
+
+			let post_info = LaneRegistry::update_lane(
+				Origin::root(),
+				TEST_LANE_ID,
+				AllowedSenders::Listed(vec![SENDER, SENDER + 1]),
+				MINIMAL_FEE,
+			)
+			.unwrap();
+			assert_eq!(
+				post_info.actual_weight,
+				Some(update_lane_weight::<TestRuntime, ()>(1, 2))
+			);
+			assert!(
+				update_lane_weight::<TestRuntime, ()>(1, 2)
+					< update_lane_weight::<TestRuntime, ()>(MAX_ALLOWED_SENDERS, 2)
+			);
+		});
+	}
+
+	#[test]
+	fn update_lane_rejects_non_owner() {
+		run_test(|| {
+			register_test_lane(AllowedSenders::Any);
+
+			assert_noop!(
+				LaneRegistry::update_lane(Origin::signed(SENDER), TEST_LANE_ID, AllowedSenders::Any, 0),
+				Error::<TestRuntime, ()>::NotLaneOwner,
+			);
+		});
+	}
+
+	#[test]
+	fn update_lane_rejects_unknown_lane() {
+		run_test(|| {
+			assert_noop!(
+				LaneRegistry::update_lane(Origin::root(), TEST_LANE_ID, AllowedSenders::Any, 0),
+				Error::<TestRuntime, ()>::UnknownLane,
+			);
+		});
+	}
+
+	#[test]
+	fn deregister_lane_works() {
+		run_test(|| {
+			register_test_lane(AllowedSenders::Any);
+
+			assert_noop!(
+				LaneRegistry::deregister_lane(Origin::signed(OWNER), TEST_LANE_ID),
+				DispatchError::BadOrigin,
+			);
+			assert_ok!(LaneRegistry::deregister_lane(Origin::root(), TEST_LANE_ID));
+			assert_eq!(LaneRegistry::lane_info(TEST_LANE_ID), None);
+			assert_noop!(
+				LaneRegistry::deregister_lane(Origin::root(), TEST_LANE_ID),
+				Error::<TestRuntime, ()>::UnknownLane,
+			);
+		});
+	}
+
+	#[test]
+	fn verifier_rejects_messages_sent_over_unregistered_lane() {
+		run_test(|| {
+			assert_eq!(verify_message(Sender::Root, MINIMAL_FEE), Err(LANE_NOT_REGISTERED));
+		});
+	}
+
+	#[test]
+	fn verifier_checks_allowed_senders() {
+		run_test(|| {
+			register_test_lane(AllowedSenders::Owner);
+			assert_eq!(verify_message(Sender::Root, MINIMAL_FEE), Ok(()));
+			assert_eq!(verify_message(Sender::Signed(OWNER), MINIMAL_FEE), Ok(()));
+			assert_eq!(
+				verify_message(Sender::Signed(SENDER), MINIMAL_FEE),
+				Err(SENDER_NOT_ALLOWED)
+			);
+			assert_eq!(verify_message(Sender::None, MINIMAL_FEE), Err(SENDER_NOT_ALLOWED));
+
+			assert_ok!(LaneRegistry::update_lane(
+				Origin::root(),
+				TEST_LANE_ID,
+				AllowedSenders::Listed(vec![SENDER]),
+				MINIMAL_FEE,
+			));
+			assert_eq!(verify_message(Sender::Signed(OWNER), MINIMAL_FEE), Ok(()));
+			assert_eq!(verify_message(Sender::Signed(SENDER), MINIMAL_FEE), Ok(()));
+			assert_eq!(
+				verify_message(Sender::Signed(SENDER + 1), MINIMAL_FEE),
+				Err(SENDER_NOT_ALLOWED)
+			);
+
+			assert_ok!(LaneRegistry::update_lane(
+				Origin::root(),
+				TEST_LANE_ID,
+				AllowedSenders::Any,
+				MINIMAL_FEE,
+			));
+			assert_eq!(verify_message(Sender::Signed(SENDER + 1), MINIMAL_FEE), Ok(()));
+		});
+	}
+
+	#[test]
+	fn verifier_checks_minimal_fee() {
+		run_test(|| {
+			register_test_lane(AllowedSenders::Any);
+			assert_eq!(
+				verify_message(Sender::Signed(SENDER), MINIMAL_FEE - 1),
+				Err(TOO_LOW_FEE)
+			);
+			assert_eq!(verify_message(Sender::Signed(SENDER), MINIMAL_FEE), Ok(()));
+		});
+	}
+
+	#[test]
+	fn verifier_weight_includes_maximal_allowed_senders() {
+		assert_eq!(
+			TestVerifier::verify_message_weight(),
+			ALLOWED_SENDER_WEIGHT * MAX_ALLOWED_SENDERS as Weight,
+		);
+	}
+
+	#[test]
+	fn register_existing_lanes_registers_only_unknown_lanes() {
+		frame_support::parameter_types! {
+			pub ExistingLanes: Vec<LaneId> = vec![TEST_LANE_ID, LaneId::Legacy(*b"lan2")];
+			pub const ExistingLanesOwner: u64 = SENDER;
+		}
+
+		run_test(|| {
+			register_test_lane(AllowedSenders::Owner);
+
+			RegisterExistingLanes::<TestRuntime, (), ExistingLanes, ExistingLanesOwner>::on_runtime_upgrade();
+
+			assert_eq!(
+				LaneRegistry::lane_info(TEST_LANE_ID),
+				Some(test_lane_info(AllowedSenders::Owner))
+			);
+			assert_eq!(
+				LaneRegistry::lane_info(LaneId::Legacy(*b"lan2")),
+				Some(LaneInfo {
+					owner: SENDER,
+					allowed_senders: AllowedSenders::Any,
+					minimal_fee: 0,
+				}),
+			);
+		});
+	}
+
+	#[test]
+	fn verifier_calls_wrapped_verifier() {
+		run_test(|| {
+			register_test_lane(AllowedSenders::Any);
+			assert!(
+				LaneRegistryVerifier::<TestRuntime, (), ForbidOutboundMessages>::verify_message(
+					&Sender::Signed(SENDER),
+					&MINIMAL_FEE,
+					&TEST_LANE_ID,
+					&Default::default(),
+					&(),
+				)
+				.is_err()
+			);
+		});
+	}
+}
@@ -0,0 +1,322 @@
+// Copyright 2019-2021 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Runtime module that tracks liveness of the bridge.
+//!
+//! The source chain periodically sends heartbeat messages over the `HEARTBEAT_LANE_ID` lane
+//! (e.g. using the `pallet-bridge-message-scheduler`). At the target chain, these messages are
+//! intercepted by the `HeartbeatDispatch`, which records the delivery of every heartbeat in
+//! the `BridgeLiveness` storage value. All other messages are passed to the wrapped dispatcher.
+//!
+//! Other pallets may use `Pallet::is_bridge_live` to check that the bridge is still operating
+//! before relying on the bridged data. The pallet also emits the `BridgeStale` event when no
+//! heartbeats have been delivered for `MaxHeartbeatAge` blocks, and the `BridgeRecovered`
+//! event when the next heartbeat is delivered.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use bp_messages::{
+	target_chain::{DispatchMessage, MessageDispatch},
+	MessageNonce, HEARTBEAT_LANE_ID,
+};
+use bp_runtime::messages::MessageDispatchResult;
+use codec::{Decode, Encode};
+use frame_support::{traits::Get, weights::Weight, RuntimeDebug};
+use sp_runtime::traits::Saturating;
+use sp_std::marker::PhantomData;
+
+#[cfg(test)]
+mod mock;
+
+/// Information about the last delivered heartbeat message.
+#[derive(Encode, Decode, Clone, RuntimeDebug, PartialEq, Eq)]
+pub struct HeartbeatInfo<BlockNumber> {
+	/// Nonce of the heartbeat message.
+	pub nonce: MessageNonce,
+	/// Number of the block, where the heartbeat message has been delivered.
+	pub delivered_at: BlockNumber,
+}
+
+pub use pallet::*;
+
+// comes from #[pallet::event]
+#[allow(clippy::unused_unit)]
+#[frame_support::pallet]
+pub mod pallet {
+	use super::*;
+	use frame_support::pallet_prelude::*;
+	use frame_system::pallet_prelude::*;
+
+	#[pallet::config]
+	pub trait Config<I: 'static = ()>: frame_system::Config {
+		/// The overarching event type.
+		type Event: From<Event<Self, I>> + IsType<<Self as frame_system::Config>::Event>;
+		/// Number of blocks without heartbeats, after which the bridge is considered stale.
+		#[pallet::constant]
+		type MaxHeartbeatAge: Get<Self::BlockNumber>;
+	}
+
+	#[pallet::pallet]
+	#[pallet::generate_store(pub(super) trait Store)]
+	pub struct Pallet<T, I = ()>(PhantomData<(T, I)>);
+
+	#[pallet::hooks]
+	impl<T: Config<I>, I: 'static> Hooks<BlockNumberFor<T>> for Pallet<T, I> {
+		fn on_initialize(now: T::BlockNumber) -> Weight {
+			if IsStale::<T, I>::get() {
+				return T::DbWeight::get().reads(1);
+			}
+
+			match BridgeLiveness::<T, I>::get() {
+				Some(heartbeat) if now.saturating_sub(heartbeat.delivered_at) > T::MaxHeartbeatAge::get() => {
+					log::warn!(
+						target: "runtime::bridge-liveness",
+						"Bridge is stale. Last heartbeat {} has been delivered at block {:?}",
+						heartbeat.nonce,
+						heartbeat.delivered_at,
+					);
+
+					IsStale::<T, I>::put(true);
+					Self::deposit_event(Event::BridgeStale(heartbeat.delivered_at));
+					T::DbWeight::get().reads_writes(2, 1)
+				}
+				_ => T::DbWeight::get().reads(2),
+			}
+		}
+	}
+
+	#[pallet::call]
+	impl<T: Config<I>, I: 'static> Pallet<T, I> {}
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	#[pallet::metadata(T::BlockNumber = "BlockNumber")]
+	pub enum Event<T: Config<I>, I: 'static = ()> {
+		/// Heartbeat message with given nonce has been delivered.
+		HeartbeatReceived(MessageNonce),
+		/// No heartbeats have been delivered for too long. The payload is the number of block,
+		/// where the last heartbeat has been delivered.
+		BridgeStale(T::BlockNumber),
+		/// Heartbeat has been delivered after the bridge has been stale.
+		BridgeRecovered,
+	}
+
+	/// The last delivered heartbeat message.
+	#[pallet::storage]
+	#[pallet::getter(fn last_heartbeat)]
+	pub type BridgeLiveness<T: Config<I>, I: 'static = ()> = StorageValue<_, HeartbeatInfo<T::BlockNumber>>;
+
+	/// True if the `BridgeStale` event has been emitted and no heartbeats have been delivered since.
+	#[pallet::storage]
+	pub(super) type IsStale<T: Config<I>, I: 'static = ()> = StorageValue<_, bool, ValueQuery>;
+
+	impl<T: Config<I>, I: 'static> Pallet<T, I> {
+		/// Returns true if heartbeat message has been delivered during last `max_age` blocks.
+		///
+		/// Returns false if no heartbeats have been delivered yet.
+		pub fn is_bridge_live(max_age: T::BlockNumber) -> bool {
+			let now = frame_system::Pallet::<T>::block_number();
+			BridgeLiveness::<T, I>::get()
+				.map(|heartbeat| now.saturating_sub(heartbeat.delivered_at) <= max_age)
+				.unwrap_or(false)
+		}
+
+		/// Remember that the heartbeat message has been delivered at the current block.
+		pub(crate) fn note_heartbeat(nonce: MessageNonce) {
+			BridgeLiveness::<T, I>::put(HeartbeatInfo {
+				nonce,
+				delivered_at: frame_system::Pallet::<T>::block_number(),
+			});
+			Self::deposit_event(Event::HeartbeatReceived(nonce));
+
+			if IsStale::<T, I>::take() {
+				log::info!(
+					target: "runtime::bridge-liveness",
+					"Bridge has recovered. Heartbeat {} has been delivered",
+					nonce,
+				);
+
+				Self::deposit_event(Event::BridgeRecovered);
+			}
+		}
+	}
+}
+
+/// Weight of the heartbeat message dispatch.
+fn heartbeat_weight<T: frame_system::Config>() -> Weight {
+	T::DbWeight::get().reads_writes(1, 2)
+}
+
+/// Message dispatcher that records delivery of heartbeat messages and passes all other messages
+/// to the wrapped `Dispatch`.
+///
+/// Payload of heartbeat messages is ignored.
+#[derive(RuntimeDebug)]
+pub struct HeartbeatDispatch<T, I, Dispatch>(PhantomData<(T, I, Dispatch)>);
+
+impl<T, I, Dispatch, Fee> MessageDispatch<T::AccountId, Fee> for HeartbeatDispatch<T, I, Dispatch>
+where
+	T: Config<I>,
+	I: 'static,
+	Dispatch: MessageDispatch<T::AccountId, Fee>,
+{
+	type DispatchPayload = Dispatch::DispatchPayload;
+
+	fn dispatch_weight(message: &DispatchMessage<Self::DispatchPayload, Fee>) -> Weight {
+		if message.key.lane_id == HEARTBEAT_LANE_ID {
+			return heartbeat_weight::<T>();
+		}
+
+		Dispatch::dispatch_weight(message)
+	}
+
+	fn dispatch_overhead_weight() -> Weight {
+		Dispatch::dispatch_overhead_weight()
+	}
+
+	fn dispatch(
+		relayer_account: &T::AccountId,
+		message: DispatchMessage<Self::DispatchPayload, Fee>,
+	) -> MessageDispatchResult {
+		if message.key.lane_id != HEARTBEAT_LANE_ID {
+			return Dispatch::dispatch(relayer_account, message);
+		}
+
+		Pallet::<T, I>::note_heartbeat(message.key.nonce);
+		MessageDispatchResult {
+			dispatch_result: true,
+			unspent_weight: 0,
+			dispatch_fee_paid_during_dispatch: false,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::mock::{run_test, Event as TestEvent, TestDispatch, TestRuntime, MAX_HEARTBEAT_AGE};
+	use bp_messages::{target_chain::DispatchMessageData, LaneId, MessageKey};
+	use frame_support::traits::Hooks;
+
+	type Liveness = Pallet<TestRuntime>;
+	type TestHeartbeatDispatch = HeartbeatDispatch<TestRuntime, (), TestDispatch>;
+
+	const TEST_LANE_ID: LaneId = LaneId::Legacy(*b"test");
+
+	fn message(lane_id: LaneId, nonce: MessageNonce) -> DispatchMessage<u64, u64> {
+		DispatchMessage {
+			key: MessageKey { lane_id, nonce },
+			data: DispatchMessageData { payload: Ok(0), fee: 0 },
+		}
+	}
+
+	fn deliver_heartbeat(nonce: MessageNonce) -> MessageDispatchResult {
+		TestHeartbeatDispatch::dispatch(&1, message(HEARTBEAT_LANE_ID, nonce))
+	}
+
+	fn set_block_number(number: u64) {
+		frame_system::Pallet::<TestRuntime>::set_block_number(number);
+		Liveness::on_initialize(number);
+	}
+
+	fn liveness_events() -> Vec<Event<TestRuntime>> {
+		frame_system::Pallet::<TestRuntime>::events()
+			.into_iter()
+			.filter_map(|record| match record.event {
+				TestEvent::Liveness(event) => Some(event),
+				_ => None,
+			})
+			.collect()
+	}
+
+	#[test]
+	fn heartbeat_delivery_is_recorded() {
+		run_test(|| {
+			assert_eq!(
+				TestHeartbeatDispatch::dispatch_weight(&message(HEARTBEAT_LANE_ID, 1)),
+				heartbeat_weight::<TestRuntime>(),
+			);
+			assert!(deliver_heartbeat(1).dispatch_result);
+
+			assert_eq!(
+				Liveness::last_heartbeat(),
+				Some(HeartbeatInfo {
+					nonce: 1,
+					delivered_at: 1
+				}),
+			);
+			assert_eq!(liveness_events(), vec![Event::HeartbeatReceived(1)]);
+		});
+	}
+
+	#[test]
+	fn other_messages_are_passed_to_wrapped_dispatcher() {
+		run_test(|| {
+			// `TestDispatch` fails to dispatch all messages
+			assert_eq!(
+				TestHeartbeatDispatch::dispatch_weight(&message(TEST_LANE_ID, 1)),
+				TestDispatch::dispatch_weight(&message(TEST_LANE_ID, 1)),
+			);
+			assert!(!TestHeartbeatDispatch::dispatch(&1, message(TEST_LANE_ID, 1)).dispatch_result);
+
+			assert_eq!(Liveness::last_heartbeat(), None);
+			assert!(liveness_events().is_empty());
+		});
+	}
+
+	#[test]
+	fn is_bridge_live_checks_age_of_last_heartbeat() {
+		run_test(|| {
+			assert!(!Liveness::is_bridge_live(100));
+
+			deliver_heartbeat(1);
+			assert!(Liveness::is_bridge_live(0));
+
+			set_block_number(11);
+			assert!(Liveness::is_bridge_live(10));
+			assert!(!Liveness::is_bridge_live(9));
+		});
+	}
+
+	#[test]
+	fn stale_bridge_is_reported_once_until_next_heartbeat() {
+		run_test(|| {
+			deliver_heartbeat(1);
+
+			set_block_number(1 + MAX_HEARTBEAT_AGE);
+			assert_eq!(liveness_events(), vec![Event::HeartbeatReceived(1)]);
+
+			set_block_number(2 + MAX_HEARTBEAT_AGE);
+			set_block_number(3 + MAX_HEARTBEAT_AGE);
+			assert_eq!(
+				liveness_events(),
+				vec![Event::HeartbeatReceived(1), Event::BridgeStale(1)],
+			);
+
+			deliver_heartbeat(2);
+			assert_eq!(
+				liveness_events(),
+				vec![
+					Event::HeartbeatReceived(1),
+					Event::BridgeStale(1),
+					Event::HeartbeatReceived(2),
+					Event::BridgeRecovered,
+				],
+			);
+			assert!(!IsStale::<TestRuntime>::get());
+		});
+	}
+}
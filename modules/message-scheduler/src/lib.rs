@@ -0,0 +1,514 @@
+// Copyright 2019-2021 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Runtime module that sends configured bridge messages on a recurring schedule.
+//!
+//! Some applications need to send the same message over the bridge periodically - e.g.
+//! heartbeat messages or messages that are syncing some state with the bridged chain. The
+//! schedule origin (root or governance) may register such message with the period, fee of
+//! every message and the total budget that may be spent on the schedule fees. Messages are
+//! sent from the pot account in the `on_initialize` of the block where they're due.
+//!
+//! The schedule is stopped when its budget is exhausted or after `MaxFailures` consecutive
+//! failed sends. It is the runtime responsibility to keep the pot account funded.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use bp_messages::{source_chain::MessagesBridge, LaneId, MessageNonce};
+use codec::{Decode, Encode};
+use frame_support::{traits::Get, weights::Weight, RuntimeDebug};
+use sp_runtime::traits::{Saturating, Zero};
+use sp_std::prelude::*;
+
+#[cfg(test)]
+mod mock;
+
+/// Identifier of the message schedule.
+pub type ScheduleId = u32;
+
+/// Message that is sent over the bridge on a recurring schedule.
+#[derive(Encode, Decode, Clone, RuntimeDebug, PartialEq, Eq)]
+pub struct ScheduledMessage<BlockNumber, Balance, Payload> {
+	/// Lane to send message over.
+	pub lane: LaneId,
+	/// Message payload.
+	pub payload: Payload,
+	/// Delivery and dispatch fee that is paid for every sent message.
+	pub fee: Balance,
+	/// Number of blocks between two sends.
+	pub period: BlockNumber,
+	/// Part of the schedule budget that may still be spent on message fees.
+	pub remaining_budget: Balance,
+	/// Number of consecutive failed sends.
+	pub failures: u32,
+}
+
+/// Reason of the schedule stop.
+#[derive(Encode, Decode, Clone, Copy, RuntimeDebug, PartialEq, Eq)]
+pub enum StopReason {
+	/// Remaining budget is not enough to pay fee of the next message.
+	BudgetExhausted,
+	/// Too many consecutive sends have failed.
+	TooManyFailures,
+}
+
+/// Scheduled message type used by the pallet.
+pub type ScheduledMessageOf<T, I> =
+	ScheduledMessage<<T as frame_system::Config>::BlockNumber, <T as Config<I>>::Balance, <T as Config<I>>::Payload>;
+
+pub use pallet::*;
+
+// comes from #[pallet::event]
+#[allow(clippy::unused_unit)]
+#[frame_support::pallet]
+pub mod pallet {
+	use super::*;
+	use frame_support::pallet_prelude::*;
+	use frame_system::pallet_prelude::*;
+	use sp_runtime::traits::AtLeast32BitUnsigned;
+
+	#[pallet::config]
+	pub trait Config<I: 'static = ()>: frame_system::Config {
+		/// The overarching event type.
+		type Event: From<Event<Self, I>> + IsType<<Self as frame_system::Config>::Event>;
+		/// Origin that is allowed to schedule and cancel messages.
+		type ScheduleOrigin: EnsureOrigin<Self::Origin>;
+		/// Type of the message fee.
+		type Balance: Parameter + Member + AtLeast32BitUnsigned + Copy;
+		/// Type of the message payload.
+		type Payload: Parameter;
+		/// Messages bridge that is used to send scheduled messages.
+		type MessagesBridge: MessagesBridge<Self::AccountId, Self::Balance, Self::Payload>;
+		/// Account that sends scheduled messages and pays their fees.
+		type PotAccountId: Get<Self::AccountId>;
+		/// Maximal number of active schedules.
+		#[pallet::constant]
+		type MaxSchedules: Get<u32>;
+		/// Number of consecutive failed sends after which the schedule is stopped.
+		#[pallet::constant]
+		type MaxFailures: Get<u32>;
+		/// Weight of single `MessagesBridge::send_message` call.
+		#[pallet::constant]
+		type SendMessageWeight: Get<Weight>;
+	}
+
+	#[pallet::pallet]
+	#[pallet::generate_store(pub(super) trait Store)]
+	pub struct Pallet<T, I = ()>(PhantomData<(T, I)>);
+
+	#[pallet::hooks]
+	impl<T: Config<I>, I: 'static> Hooks<BlockNumberFor<T>> for Pallet<T, I> {
+		fn on_initialize(now: T::BlockNumber) -> Weight {
+			let due_schedules = Agenda::<T, I>::take(now);
+			let mut weight = T::DbWeight::get().reads_writes(1, 1);
+			for schedule_id in due_schedules {
+				weight = weight.saturating_add(Self::send_scheduled_message(now, schedule_id));
+			}
+			weight
+		}
+	}
+
+	#[pallet::call]
+	impl<T: Config<I>, I: 'static> Pallet<T, I> {
+		/// Schedule message that is sent every `period` blocks, starting from the `period`
+		/// blocks after the current block.
+		///
+		/// May only be called by the schedule origin.
+		#[pallet::weight(T::DbWeight::get().reads_writes(2, 4))]
+		pub fn schedule_message(
+			origin: OriginFor<T>,
+			lane: LaneId,
+			payload: T::Payload,
+			fee: T::Balance,
+			period: T::BlockNumber,
+			budget: T::Balance,
+		) -> DispatchResultWithPostInfo {
+			T::ScheduleOrigin::ensure_origin(origin)?;
+			ensure!(!period.is_zero(), Error::<T, I>::ZeroPeriod);
+			ensure!(budget >= fee, Error::<T, I>::InsufficientBudget);
+			let active_schedules = ActiveSchedules::<T, I>::get();
+			ensure!(
+				active_schedules < T::MaxSchedules::get(),
+				Error::<T, I>::TooManySchedules
+			);
+
+			let schedule_id = NextScheduleId::<T, I>::get();
+			let first_send_at = frame_system::Pallet::<T>::block_number().saturating_add(period);
+
+			log::trace!(
+				target: "runtime::bridge-message-scheduler",
+				"Scheduled message {} over lane {:?}. First send at: {:?}",
+				schedule_id,
+				lane,
+				first_send_at,
+			);
+
+			Schedules::<T, I>::insert(
+				schedule_id,
+				ScheduledMessage {
+					lane,
+					payload,
+					fee,
+					period,
+					remaining_budget: budget,
+					failures: 0,
+				},
+			);
+			Agenda::<T, I>::append(first_send_at, schedule_id);
+			ActiveSchedules::<T, I>::put(active_schedules + 1);
+			NextScheduleId::<T, I>::put(schedule_id.wrapping_add(1));
+			Self::deposit_event(Event::MessageScheduled(schedule_id));
+
+			Ok(().into())
+		}
+
+		/// Cancel the message schedule.
+		///
+		/// May only be called by the schedule origin.
+		#[pallet::weight(T::DbWeight::get().reads_writes(2, 2))]
+		pub fn cancel_schedule(origin: OriginFor<T>, schedule_id: ScheduleId) -> DispatchResultWithPostInfo {
+			T::ScheduleOrigin::ensure_origin(origin)?;
+			ensure!(
+				Schedules::<T, I>::contains_key(schedule_id),
+				Error::<T, I>::UnknownSchedule
+			);
+
+			log::trace!(target: "runtime::bridge-message-scheduler", "Cancelled schedule {}", schedule_id);
+
+			// the agenda entry is left as is and is ignored when it's due
+			remove_schedule::<T, I>(schedule_id);
+			Self::deposit_event(Event::ScheduleCancelled(schedule_id));
+
+			Ok(().into())
+		}
+	}
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config<I>, I: 'static = ()> {
+		/// Message has been scheduled.
+		MessageScheduled(ScheduleId),
+		/// Message schedule has been cancelled.
+		ScheduleCancelled(ScheduleId),
+		/// Scheduled message has been sent. The message has been assigned given nonce.
+		ScheduledMessageSent(ScheduleId, MessageNonce),
+		/// Failed to send scheduled message.
+		ScheduledMessageFailed(ScheduleId),
+		/// Message schedule has been stopped.
+		ScheduleStopped(ScheduleId, StopReason),
+	}
+
+	#[pallet::error]
+	pub enum Error<T, I = ()> {
+		/// The schedule period is zero.
+		ZeroPeriod,
+		/// The schedule budget is not enough to pay fee of the first message.
+		InsufficientBudget,
+		/// Too many active schedules.
+		TooManySchedules,
+		/// The schedule is unknown.
+		UnknownSchedule,
+	}
+
+	/// Active message schedules.
+	#[pallet::storage]
+	#[pallet::getter(fn schedule)]
+	pub type Schedules<T: Config<I>, I: 'static = ()> =
+		StorageMap<_, Twox64Concat, ScheduleId, ScheduledMessageOf<T, I>>;
+
+	/// Identifiers of schedules that are due at given block.
+	#[pallet::storage]
+	pub(super) type Agenda<T: Config<I>, I: 'static = ()> =
+		StorageMap<_, Twox64Concat, T::BlockNumber, Vec<ScheduleId>, ValueQuery>;
+
+	/// Number of active schedules.
+	#[pallet::storage]
+	pub(super) type ActiveSchedules<T: Config<I>, I: 'static = ()> = StorageValue<_, u32, ValueQuery>;
+
+	/// Identifier of the next schedule.
+	#[pallet::storage]
+	pub(super) type NextScheduleId<T: Config<I>, I: 'static = ()> = StorageValue<_, ScheduleId, ValueQuery>;
+
+	impl<T: Config<I>, I: 'static> Pallet<T, I> {
+		/// Send scheduled message and reschedule it (or stop the schedule). Returns weight of
+		/// the operation.
+		fn send_scheduled_message(now: T::BlockNumber, schedule_id: ScheduleId) -> Weight {
+			let db_weight = T::DbWeight::get();
+			let mut schedule = match Schedules::<T, I>::get(schedule_id) {
+				Some(schedule) => schedule,
+				None => return db_weight.reads(1),
+			};
+
+			let send_result = T::MessagesBridge::send_message(
+				T::PotAccountId::get(),
+				schedule.lane,
+				schedule.payload.clone(),
+				schedule.fee,
+			);
+			match send_result {
+				Ok(nonce) => {
+					schedule.remaining_budget = schedule.remaining_budget.saturating_sub(schedule.fee);
+					schedule.failures = 0;
+					Self::deposit_event(Event::ScheduledMessageSent(schedule_id, nonce));
+				}
+				Err(error) => {
+					log::trace!(
+						target: "runtime::bridge-message-scheduler",
+						"Failed to send scheduled message {}: {:?}",
+						schedule_id,
+						error,
+					);
+
+					schedule.failures = schedule.failures.saturating_add(1);
+					Self::deposit_event(Event::ScheduledMessageFailed(schedule_id));
+				}
+			}
+
+			let stop_reason = if schedule.failures >= T::MaxFailures::get() {
+				Some(StopReason::TooManyFailures)
+			} else if schedule.remaining_budget < schedule.fee {
+				Some(StopReason::BudgetExhausted)
+			} else {
+				None
+			};
+			match stop_reason {
+				Some(stop_reason) => {
+					log::trace!(
+						target: "runtime::bridge-message-scheduler",
+						"Stopped schedule {}: {:?}",
+						schedule_id,
+						stop_reason,
+					);
+
+					remove_schedule::<T, I>(schedule_id);
+					Self::deposit_event(Event::ScheduleStopped(schedule_id, stop_reason));
+				}
+				None => {
+					Agenda::<T, I>::append(now.saturating_add(schedule.period), schedule_id);
+					Schedules::<T, I>::insert(schedule_id, schedule);
+				}
+			}
+
+			db_weight.reads_writes(2, 2).saturating_add(T::SendMessageWeight::get())
+		}
+	}
+}
+
+/// Remove the schedule and decrease the number of active schedules.
+fn remove_schedule<T: Config<I>, I: 'static>(schedule_id: ScheduleId) {
+	Schedules::<T, I>::remove(schedule_id);
+	ActiveSchedules::<T, I>::mutate(|active_schedules| *active_schedules = active_schedules.saturating_sub(1));
+}
+
+impl<T: Config<I>, I: 'static> From<Error<T, I>> for bp_runtime::BridgeError {
+	fn from(error: Error<T, I>) -> Self {
+		match error {
+			Error::ZeroPeriod => Self::InvalidArgument,
+			Error::InsufficientBudget => Self::PaymentFailed,
+			Error::TooManySchedules => Self::LimitExceeded,
+			Error::UnknownSchedule => Self::UnknownEntity,
+			// hidden variant that is never constructed
+			_ => Self::InvalidState,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::mock::{
+		run_test, Event as TestEvent, MessageScheduler, Origin, TestRuntime, FAILING_PAYLOAD, MAX_FAILURES,
+		MAX_SCHEDULES, TEST_LANE_ID,
+	};
+	use frame_support::{assert_noop, assert_ok, traits::Hooks};
+	use sp_runtime::DispatchError;
+
+	const FEE: u64 = 10;
+	const PERIOD: u64 = 2;
+
+	fn schedule_message(payload: u64, budget: u64) -> ScheduleId {
+		let schedule_id = NextScheduleId::<TestRuntime>::get();
+		assert_ok!(MessageScheduler::schedule_message(
+			Origin::root(),
+			TEST_LANE_ID,
+			payload,
+			FEE,
+			PERIOD,
+			budget,
+		));
+		schedule_id
+	}
+
+	fn run_to_block(number: u64) {
+		let current = frame_system::Pallet::<TestRuntime>::block_number();
+		for block in current + 1..=number {
+			frame_system::Pallet::<TestRuntime>::set_block_number(block);
+			MessageScheduler::on_initialize(block);
+		}
+	}
+
+	fn scheduler_events() -> Vec<Event<TestRuntime>> {
+		frame_system::Pallet::<TestRuntime>::events()
+			.into_iter()
+			.filter_map(|record| match record.event {
+				TestEvent::MessageScheduler(event) => Some(event),
+				_ => None,
+			})
+			.collect()
+	}
+
+	#[test]
+	fn schedule_message_works() {
+		run_test(|| {
+			let schedule_id = schedule_message(42, FEE * 10);
+
+			assert_eq!(
+				MessageScheduler::schedule(schedule_id),
+				Some(ScheduledMessage {
+					lane: TEST_LANE_ID,
+					payload: 42,
+					fee: FEE,
+					period: PERIOD,
+					remaining_budget: FEE * 10,
+					failures: 0,
+				}),
+			);
+			assert_eq!(Agenda::<TestRuntime>::get(1 + PERIOD), vec![schedule_id]);
+			assert_eq!(ActiveSchedules::<TestRuntime>::get(), 1);
+			assert_eq!(scheduler_events(), vec![Event::MessageScheduled(schedule_id)]);
+		});
+	}
+
+	#[test]
+	fn schedule_message_rejects_invalid_schedules() {
+		run_test(|| {
+			assert_noop!(
+				MessageScheduler::schedule_message(Origin::signed(1), TEST_LANE_ID, 42, FEE, PERIOD, FEE),
+				DispatchError::BadOrigin,
+			);
+			assert_noop!(
+				MessageScheduler::schedule_message(Origin::root(), TEST_LANE_ID, 42, FEE, 0, FEE),
+				Error::<TestRuntime, ()>::ZeroPeriod,
+			);
+			assert_noop!(
+				MessageScheduler::schedule_message(Origin::root(), TEST_LANE_ID, 42, FEE, PERIOD, FEE - 1),
+				Error::<TestRuntime, ()>::InsufficientBudget,
+			);
+		});
+	}
+
+	#[test]
+	fn schedule_message_rejects_too_many_schedules() {
+		run_test(|| {
+			for _ in 0..MAX_SCHEDULES {
+				schedule_message(42, FEE);
+			}
+
+			assert_noop!(
+				MessageScheduler::schedule_message(Origin::root(), TEST_LANE_ID, 42, FEE, PERIOD, FEE),
+				Error::<TestRuntime, ()>::TooManySchedules,
+			);
+		});
+	}
+
+	#[test]
+	fn scheduled_message_is_sent_every_period() {
+		run_test(|| {
+			let schedule_id = schedule_message(42, FEE * 10);
+
+			run_to_block(1 + PERIOD - 1);
+			assert_eq!(scheduler_events(), vec![Event::MessageScheduled(schedule_id)]);
+
+			run_to_block(1 + PERIOD * 2);
+			assert_eq!(
+				scheduler_events(),
+				vec![
+					Event::MessageScheduled(schedule_id),
+					Event::ScheduledMessageSent(schedule_id, 42),
+					Event::ScheduledMessageSent(schedule_id, 42),
+				],
+			);
+			assert_eq!(
+				MessageScheduler::schedule(schedule_id).map(|schedule| schedule.remaining_budget),
+				Some(FEE * 8),
+			);
+			assert_eq!(Agenda::<TestRuntime>::get(1 + PERIOD * 3), vec![schedule_id]);
+		});
+	}
+
+	#[test]
+	fn schedule_is_stopped_when_budget_is_exhausted() {
+		run_test(|| {
+			let schedule_id = schedule_message(42, FEE * 2 + FEE / 2);
+
+			run_to_block(1 + PERIOD * 3);
+			assert_eq!(
+				scheduler_events(),
+				vec![
+					Event::MessageScheduled(schedule_id),
+					Event::ScheduledMessageSent(schedule_id, 42),
+					Event::ScheduledMessageSent(schedule_id, 42),
+					Event::ScheduleStopped(schedule_id, StopReason::BudgetExhausted),
+				],
+			);
+			assert_eq!(MessageScheduler::schedule(schedule_id), None);
+			assert_eq!(ActiveSchedules::<TestRuntime>::get(), 0);
+			assert!(Agenda::<TestRuntime>::get(1 + PERIOD * 3).is_empty());
+		});
+	}
+
+	#[test]
+	fn schedule_is_stopped_after_too_many_failures() {
+		run_test(|| {
+			let schedule_id = schedule_message(FAILING_PAYLOAD, FEE * 10);
+
+			run_to_block(1 + PERIOD * (MAX_FAILURES as u64 + 1));
+			let mut expected_events = vec![Event::MessageScheduled(schedule_id)];
+			expected_events.extend((0..MAX_FAILURES).map(|_| Event::ScheduledMessageFailed(schedule_id)));
+			expected_events.push(Event::ScheduleStopped(schedule_id, StopReason::TooManyFailures));
+			assert_eq!(scheduler_events(), expected_events);
+			assert_eq!(MessageScheduler::schedule(schedule_id), None);
+			assert_eq!(ActiveSchedules::<TestRuntime>::get(), 0);
+		});
+	}
+
+	#[test]
+	fn cancelled_message_is_not_sent() {
+		run_test(|| {
+			let schedule_id = schedule_message(42, FEE * 10);
+
+			assert_noop!(
+				MessageScheduler::cancel_schedule(Origin::signed(1), schedule_id),
+				DispatchError::BadOrigin,
+			);
+			assert_ok!(MessageScheduler::cancel_schedule(Origin::root(), schedule_id));
+			assert_noop!(
+				MessageScheduler::cancel_schedule(Origin::root(), schedule_id),
+				Error::<TestRuntime, ()>::UnknownSchedule,
+			);
+
+			run_to_block(1 + PERIOD * 2);
+			assert_eq!(
+				scheduler_events(),
+				vec![
+					Event::MessageScheduled(schedule_id),
+					Event::ScheduleCancelled(schedule_id),
+				],
+			);
+			assert_eq!(ActiveSchedules::<TestRuntime>::get(), 0);
+		});
+	}
+}
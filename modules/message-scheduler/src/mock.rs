@@ -0,0 +1,140 @@
+// Copyright 2019-2021 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+// From construct_runtime macro
+#![allow(clippy::from_over_into)]
+
+use crate as pallet_bridge_message_scheduler;
+
+use bp_messages::{source_chain::MessagesBridge, LaneId, MessageNonce};
+use frame_support::weights::Weight;
+use frame_system::EnsureRoot;
+use sp_core::H256;
+use sp_runtime::{
+	testing::Header as SubstrateHeader,
+	traits::{BlakeTwo256, IdentityLookup},
+	Perbill,
+};
+
+pub type AccountId = u64;
+pub type Balance = u64;
+pub type Payload = u64;
+pub type Block = frame_system::mocking::MockBlock<TestRuntime>;
+pub type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<TestRuntime>;
+
+pub const POT_ACCOUNT: AccountId = 100;
+pub const TEST_LANE_ID: LaneId = LaneId::Legacy(*b"test");
+pub const MAX_SCHEDULES: u32 = 4;
+pub const MAX_FAILURES: u32 = 3;
+/// Payload of the message that is never sent.
+pub const FAILING_PAYLOAD: Payload = 0;
+
+frame_support::construct_runtime! {
+	pub enum TestRuntime where
+		Block = Block,
+		NodeBlock = Block,
+		UncheckedExtrinsic = UncheckedExtrinsic,
+	{
+		System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
+		MessageScheduler: pallet_bridge_message_scheduler::{Pallet, Call, Storage, Event<T>},
+	}
+}
+
+frame_support::parameter_types! {
+	pub const BlockHashCount: u64 = 250;
+	pub const MaximumBlockWeight: Weight = 1024;
+	pub const MaximumBlockLength: u32 = 2 * 1024;
+	pub const AvailableBlockRatio: Perbill = Perbill::one();
+}
+
+impl frame_system::Config for TestRuntime {
+	type Origin = Origin;
+	type Index = u64;
+	type Call = Call;
+	type BlockNumber = u64;
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	type AccountId = AccountId;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = SubstrateHeader;
+	type Event = Event;
+	type BlockHashCount = BlockHashCount;
+	type Version = ();
+	type PalletInfo = PalletInfo;
+	type AccountData = ();
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+	type BaseCallFilter = ();
+	type SystemWeightInfo = ();
+	type BlockWeights = ();
+	type BlockLength = ();
+	type DbWeight = ();
+	type SS58Prefix = ();
+	type OnSetCode = ();
+}
+
+frame_support::parameter_types! {
+	pub const PotAccountId: AccountId = POT_ACCOUNT;
+	pub const MaxSchedules: u32 = MAX_SCHEDULES;
+	pub const MaxFailures: u32 = MAX_FAILURES;
+	pub const SendMessageWeight: Weight = 100;
+}
+
+impl pallet_bridge_message_scheduler::Config for TestRuntime {
+	type Event = Event;
+	type ScheduleOrigin = EnsureRoot<AccountId>;
+	type Balance = Balance;
+	type Payload = Payload;
+	type MessagesBridge = TestMessagesBridge;
+	type PotAccountId = PotAccountId;
+	type MaxSchedules = MaxSchedules;
+	type MaxFailures = MaxFailures;
+	type SendMessageWeight = SendMessageWeight;
+}
+
+/// Messages bridge that fails to send `FAILING_PAYLOAD` and returns payload as nonce of
+/// all other messages.
+pub struct TestMessagesBridge;
+
+impl MessagesBridge<AccountId, Balance, Payload> for TestMessagesBridge {
+	type Error = ();
+
+	fn send_message(
+		sender: AccountId,
+		lane: LaneId,
+		message: Payload,
+		_delivery_and_dispatch_fee: Balance,
+	) -> Result<MessageNonce, Self::Error> {
+		assert_eq!(sender, POT_ACCOUNT);
+		assert_eq!(lane, TEST_LANE_ID);
+		match message {
+			FAILING_PAYLOAD => Err(()),
+			_ => Ok(message),
+		}
+	}
+}
+
+/// Run pallet test.
+pub fn run_test<T>(test: impl FnOnce() -> T) -> T {
+	let t = frame_system::GenesisConfig::default()
+		.build_storage::<TestRuntime>()
+		.unwrap();
+	let mut ext = sp_io::TestExternalities::new(t);
+	ext.execute_with(|| {
+		frame_system::Pallet::<TestRuntime>::set_block_number(1);
+		test()
+	})
+}
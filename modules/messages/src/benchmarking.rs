@@ -24,7 +24,8 @@ use crate::{
 
 use bp_messages::{
 	source_chain::TargetHeaderChain, target_chain::SourceHeaderChain, DeliveredMessages, InboundLaneData, LaneId,
-	MessageData, MessageNonce, OutboundLaneData, UnrewardedRelayer, UnrewardedRelayersState,
+	MessageData, MessageNonce, OperatingMode, OutboundLaneData, UnrewardedRelayer, UnrewardedRelayersState,
+	DEFAULT_MESSAGE_PRIORITY,
 };
 use bp_runtime::messages::DispatchFeePayment;
 use frame_benchmarking::{account, benchmarks_instance};
@@ -72,6 +73,8 @@ pub struct MessageProofParams {
 	pub lane: LaneId,
 	/// Range of messages to include in the proof.
 	pub message_nonces: RangeInclusive<MessageNonce>,
+	/// Additional lanes (and ranges of their messages) to include in the proof.
+	pub additional_lanes: Vec<(LaneId, RangeInclusive<MessageNonce>)>,
 	/// If `Some`, the proof needs to include this outbound lane data.
 	pub outbound_lane_data: Option<OutboundLaneData>,
 	/// Proof size requirements.
@@ -121,6 +124,8 @@ pub trait Config<I: Instance>: crate::Config<I> {
 	) -> <Self::TargetHeaderChain as TargetHeaderChain<Self::OutboundPayload, Self::AccountId>>::MessagesDeliveryProof;
 	/// Returns true if message has been dispatched (either successfully or not).
 	fn is_message_dispatched(nonce: MessageNonce) -> bool;
+	/// Return pallet parameter to use in the `update_pallet_parameter` benchmark.
+	fn pallet_parameter() -> Self::Parameter;
 }
 
 benchmarks_instance! {
@@ -290,6 +295,7 @@ benchmarks_instance! {
 		let (proof, dispatch_weight) = T::prepare_message_proof(MessageProofParams {
 			lane: T::bench_lane_id(),
 			message_nonces: 21..=21,
+			additional_lanes: Vec::new(),
 			outbound_lane_data: None,
 			size: ProofSize::Minimal(EXPECTED_DEFAULT_MESSAGE_LENGTH),
 			dispatch_fee_payment: DispatchFeePayment::AtTargetChain,
@@ -324,6 +330,7 @@ benchmarks_instance! {
 		let (proof, dispatch_weight) = T::prepare_message_proof(MessageProofParams {
 			lane: T::bench_lane_id(),
 			message_nonces: 21..=22,
+			additional_lanes: Vec::new(),
 			outbound_lane_data: None,
 			size: ProofSize::Minimal(EXPECTED_DEFAULT_MESSAGE_LENGTH),
 			dispatch_fee_payment: DispatchFeePayment::AtTargetChain,
@@ -358,6 +365,7 @@ benchmarks_instance! {
 		let (proof, dispatch_weight) = T::prepare_message_proof(MessageProofParams {
 			lane: T::bench_lane_id(),
 			message_nonces: 21..=21,
+			additional_lanes: Vec::new(),
 			outbound_lane_data: Some(OutboundLaneData {
 				oldest_unpruned_nonce: 21,
 				latest_received_nonce: 20,
@@ -398,6 +406,7 @@ benchmarks_instance! {
 		let (proof, dispatch_weight) = T::prepare_message_proof(MessageProofParams {
 			lane: T::bench_lane_id(),
 			message_nonces: 21..=21,
+			additional_lanes: Vec::new(),
 			outbound_lane_data: None,
 			size: ProofSize::HasExtraNodes(1024),
 			dispatch_fee_payment: DispatchFeePayment::AtTargetChain,
@@ -432,6 +441,7 @@ benchmarks_instance! {
 		let (proof, dispatch_weight) = T::prepare_message_proof(MessageProofParams {
 			lane: T::bench_lane_id(),
 			message_nonces: 21..=21,
+			additional_lanes: Vec::new(),
 			outbound_lane_data: None,
 			size: ProofSize::HasExtraNodes(16 * 1024),
 			dispatch_fee_payment: DispatchFeePayment::AtTargetChain,
@@ -465,6 +475,7 @@ benchmarks_instance! {
 		let (proof, dispatch_weight) = T::prepare_message_proof(MessageProofParams {
 			lane: T::bench_lane_id(),
 			message_nonces: 21..=21,
+			additional_lanes: Vec::new(),
 			outbound_lane_data: None,
 			size: ProofSize::Minimal(EXPECTED_DEFAULT_MESSAGE_LENGTH),
 			dispatch_fee_payment: DispatchFeePayment::AtSourceChain,
@@ -478,6 +489,47 @@ benchmarks_instance! {
 		assert!(T::is_message_dispatched(21));
 	}
 
+	// Benchmark `receive_messages_proof` extrinsic with multiple lanes and following conditions:
+	// * proof does not include outbound lane states proofs;
+	// * proof includes single minimal-weight message for every lane;
+	// * inbound lane of the first lane already has state, so it needs to be read and decoded;
+	// * all messages are successfully dispatched;
+	// * all messages require all heavy checks done by dispatcher.
+	//
+	// The weight of additional lane in the proof is computed as
+	// `weight(receive_multiple_lanes_messages_proof(2)) - weight(receive_multiple_lanes_messages_proof(1))`
+	// minus weight of single message delivery.
+	receive_multiple_lanes_messages_proof {
+		let i in 1..16;
+
+		let relayer_id_on_source = T::bridged_relayer_id();
+		let relayer_id_on_target = account("relayer", 0, SEED);
+
+		// mark messages 1..=20 as delivered
+		receive_messages::<T, I>(20);
+
+		let (proof, dispatch_weight) = T::prepare_message_proof(MessageProofParams {
+			lane: T::bench_lane_id(),
+			message_nonces: 21..=21,
+			additional_lanes: (1..i).map(|index| (additional_bench_lane_id::<T, I>(index), 1..=1)).collect(),
+			outbound_lane_data: None,
+			size: ProofSize::Minimal(EXPECTED_DEFAULT_MESSAGE_LENGTH),
+			dispatch_fee_payment: DispatchFeePayment::AtTargetChain,
+		});
+	}: receive_messages_proof(RawOrigin::Signed(relayer_id_on_target), relayer_id_on_source, proof, i, dispatch_weight)
+	verify {
+		assert_eq!(
+			crate::Pallet::<T, I>::inbound_latest_received_nonce(T::bench_lane_id()),
+			21,
+		);
+		for index in 1..i {
+			assert_eq!(
+				crate::Pallet::<T, I>::inbound_latest_received_nonce(additional_bench_lane_id::<T, I>(index)),
+				1,
+			);
+		}
+	}
+
 	// Benchmark `receive_messages_delivery_proof` extrinsic with following conditions:
 	// * single relayer is rewarded for relaying single message;
 	// * relayer account does not exist (in practice it needs to exist in production environment).
@@ -603,6 +655,57 @@ benchmarks_instance! {
 		ensure_relayer_rewarded::<T, I>(&relayer2_id, &relayer2_balance);
 	}
 
+	// Benchmark `propose_owner` extrinsic with following conditions:
+	// * called by the current pallet owner;
+	// * the owner is removed, along with the pending owner (this is the worst case, because both
+	//   values are killed).
+	//
+	// Result of this benchmark is directly used by weight formula of the call.
+	propose_owner {
+		let owner: T::AccountId = account("owner", 0, SEED);
+		let pending_owner: T::AccountId = account("owner", 1, SEED);
+		crate::PalletOwner::<T, I>::put(&owner);
+		crate::PendingPalletOwner::<T, I>::put(&pending_owner);
+	}: propose_owner(RawOrigin::Signed(owner), None)
+	verify {
+		assert_eq!(crate::PalletOwner::<T, I>::get(), None);
+		assert_eq!(crate::PendingPalletOwner::<T, I>::get(), None);
+	}
+
+	// Benchmark `accept_owner` extrinsic, called by the proposed pallet owner.
+	//
+	// Result of this benchmark is directly used by weight formula of the call.
+	accept_owner {
+		let owner: T::AccountId = account("owner", 0, SEED);
+		let new_owner: T::AccountId = account("owner", 1, SEED);
+		crate::PalletOwner::<T, I>::put(&owner);
+		crate::PendingPalletOwner::<T, I>::put(&new_owner);
+	}: accept_owner(RawOrigin::Signed(new_owner.clone()))
+	verify {
+		assert_eq!(crate::PalletOwner::<T, I>::get(), Some(new_owner));
+		assert_eq!(crate::PendingPalletOwner::<T, I>::get(), None);
+	}
+
+	// Benchmark `set_operating_mode` extrinsic, called by the current pallet owner.
+	//
+	// Result of this benchmark is directly used by weight formula of the call.
+	set_operating_mode {
+		let owner: T::AccountId = account("owner", 0, SEED);
+		crate::PalletOwner::<T, I>::put(&owner);
+	}: set_operating_mode(RawOrigin::Signed(owner), OperatingMode::RejectingOutboundMessages)
+	verify {
+		assert_eq!(crate::PalletOperatingMode::<I>::get(), OperatingMode::RejectingOutboundMessages);
+	}
+
+	// Benchmark `update_pallet_parameter` extrinsic, called by the current pallet owner.
+	//
+	// Result of this benchmark is directly used by weight formula of the call.
+	update_pallet_parameter {
+		let owner: T::AccountId = account("owner", 0, SEED);
+		crate::PalletOwner::<T, I>::put(&owner);
+		let parameter = T::pallet_parameter();
+	}: update_pallet_parameter(RawOrigin::Signed(owner), parameter)
+
 	//
 	// Benchmarks for manual checks.
 	//
@@ -661,6 +764,7 @@ benchmarks_instance! {
 		let (proof, dispatch_weight) = T::prepare_message_proof(MessageProofParams {
 			lane: T::bench_lane_id(),
 			message_nonces: 21..=(20 + i as MessageNonce),
+			additional_lanes: Vec::new(),
 			outbound_lane_data: None,
 			size: ProofSize::Minimal(EXPECTED_DEFAULT_MESSAGE_LENGTH),
 			dispatch_fee_payment: DispatchFeePayment::AtTargetChain,
@@ -699,6 +803,7 @@ benchmarks_instance! {
 		let (proof, dispatch_weight) = T::prepare_message_proof(MessageProofParams {
 			lane: T::bench_lane_id(),
 			message_nonces: 21..=21,
+			additional_lanes: Vec::new(),
 			outbound_lane_data: None,
 			size: ProofSize::HasExtraNodes(i as _),
 			dispatch_fee_payment: DispatchFeePayment::AtTargetChain,
@@ -737,6 +842,7 @@ benchmarks_instance! {
 		let (proof, dispatch_weight) = T::prepare_message_proof(MessageProofParams {
 			lane: T::bench_lane_id(),
 			message_nonces: 21..=21,
+			additional_lanes: Vec::new(),
 			outbound_lane_data: None,
 			size: ProofSize::HasLargeLeaf(i as _),
 			dispatch_fee_payment: DispatchFeePayment::AtTargetChain,
@@ -777,6 +883,7 @@ benchmarks_instance! {
 		let (proof, dispatch_weight) = T::prepare_message_proof(MessageProofParams {
 			lane: T::bench_lane_id(),
 			message_nonces: 21..=20 + i as MessageNonce,
+			additional_lanes: Vec::new(),
 			outbound_lane_data: Some(OutboundLaneData {
 				oldest_unpruned_nonce: 21,
 				latest_received_nonce: 20,
@@ -898,11 +1005,22 @@ benchmarks_instance! {
 	}
 }
 
+/// Returns id of additional lane that is used in multi-lane benchmarks.
+fn additional_bench_lane_id<T: Config<I>, I: Instance>(index: u32) -> LaneId {
+	let mut lane_id = T::bench_lane_id();
+	match lane_id {
+		LaneId::Legacy(ref mut id) => id[0] = id[0].wrapping_add(index as u8),
+		LaneId::Hashed(ref mut hash) => hash.0[0] = hash.0[0].wrapping_add(index as u8),
+	}
+	lane_id
+}
+
 fn send_regular_message<T: Config<I>, I: Instance>() {
 	let mut outbound_lane = outbound_lane::<T, I>(T::bench_lane_id());
 	outbound_lane.send_message(MessageData {
 		payload: vec![],
 		fee: MESSAGE_FEE.into(),
+		priority: DEFAULT_MESSAGE_PRIORITY,
 	});
 }
 
@@ -911,6 +1029,7 @@ fn send_regular_message_with_payload<T: Config<I>, I: Instance>(payload: Vec<u8>
 	outbound_lane.send_message(MessageData {
 		payload,
 		fee: MESSAGE_FEE.into(),
+		priority: DEFAULT_MESSAGE_PRIORITY,
 	});
 }
 
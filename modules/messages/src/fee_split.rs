@@ -0,0 +1,290 @@
+// Copyright 2019-2021 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Implementation of `MessageDeliveryAndDispatchPayment` trait that splits collected message
+//! fees between relayers, treasury and burn.
+//!
+//! The split is applied to relayers rewards before they're paid by the wrapped payments
+//! implementation. So delivery and confirmation relayers are receiving the same proportion of the
+//! remaining rewards as they'd receive without the split.
+
+use bp_messages::source_chain::{MessageDeliveryAndDispatchPayment, RelayersRewards, Sender};
+use codec::{Decode, Encode};
+use frame_support::{
+	traits::{Currency as CurrencyT, ExistenceRequirement, Get, WithdrawReasons},
+	RuntimeDebug,
+};
+use num_traits::Zero;
+use sp_runtime::{traits::Saturating, Perbill};
+
+/// Split of collected message fees.
+///
+/// The rest of fees (`100% - treasury - burn`) is paid to relayers. If sum of `treasury` and
+/// `burn` is larger than 100%, then the `burn` part is decreased and relayers are receiving nothing.
+#[derive(Clone, Copy, Default, Encode, Decode, RuntimeDebug, PartialEq, Eq)]
+pub struct FeeSplit {
+	/// Part of fees that is transferred to the treasury account.
+	pub treasury: Perbill,
+	/// Part of fees that is burned.
+	pub burn: Perbill,
+}
+
+/// Message payments that take treasury and burn parts of relayers rewards, and pay the rest using
+/// wrapped `Payments` implementation.
+///
+/// `GetFeeSplit` is expected to return the split that may be changed by the pallet owner (e.g. it
+/// may be backed by the runtime storage and updated using the messages pallet parameter). If
+/// `TreasuryAccount` returns `None`, the treasury part is not taken.
+///
+/// NOTE The treasury and burn parts are withdrawn from the `relayers-fund` account using `Currency`.
+/// So this wrapper should only be used if the wrapped `Payments` implementation is also collecting
+/// fees in the same `Currency`.
+pub struct FeeSplitPayments<T, Currency, Payments, GetFeeSplit, TreasuryAccount> {
+	_phantom: sp_std::marker::PhantomData<(T, Currency, Payments, GetFeeSplit, TreasuryAccount)>,
+}
+
+impl<T, Currency, Payments, GetFeeSplit, TreasuryAccount>
+	MessageDeliveryAndDispatchPayment<T::AccountId, Currency::Balance>
+	for FeeSplitPayments<T, Currency, Payments, GetFeeSplit, TreasuryAccount>
+where
+	T: frame_system::Config,
+	Currency: CurrencyT<T::AccountId>,
+	Payments: MessageDeliveryAndDispatchPayment<T::AccountId, Currency::Balance>,
+	GetFeeSplit: Get<FeeSplit>,
+	TreasuryAccount: Get<Option<T::AccountId>>,
+{
+	type Error = Payments::Error;
+
+	fn initialize(relayer_fund_account: &T::AccountId) -> usize {
+		Payments::initialize(relayer_fund_account)
+	}
+
+	fn pay_delivery_and_dispatch_fee(
+		submitter: &Sender<T::AccountId>,
+		fee: &Currency::Balance,
+		relayer_fund_account: &T::AccountId,
+	) -> Result<(), Self::Error> {
+		Payments::pay_delivery_and_dispatch_fee(submitter, fee, relayer_fund_account)
+	}
+
+	fn refund_delivery_and_dispatch_fee(
+		refund_to: &Sender<T::AccountId>,
+		fee: &Currency::Balance,
+		relayer_fund_account: &T::AccountId,
+	) -> Result<(), Self::Error> {
+		Payments::refund_delivery_and_dispatch_fee(refund_to, fee, relayer_fund_account)
+	}
+
+	fn pay_relayers_rewards(
+		confirmation_relayer: &T::AccountId,
+		relayers_rewards: RelayersRewards<T::AccountId, Currency::Balance>,
+		relayer_fund_account: &T::AccountId,
+	) -> Currency::Balance {
+		let relayers_rewards = split_relayers_rewards::<Currency, _>(
+			relayers_rewards,
+			relayer_fund_account,
+			GetFeeSplit::get(),
+			TreasuryAccount::get(),
+		);
+		Payments::pay_relayers_rewards(confirmation_relayer, relayers_rewards, relayer_fund_account)
+	}
+}
+
+/// Take treasury and burn parts of relayers rewards. Returns remaining relayers rewards.
+fn split_relayers_rewards<Currency, AccountId>(
+	relayers_rewards: RelayersRewards<AccountId, Currency::Balance>,
+	relayer_fund_account: &AccountId,
+	fee_split: FeeSplit,
+	treasury_account: Option<AccountId>,
+) -> RelayersRewards<AccountId, Currency::Balance>
+where
+	AccountId: sp_std::fmt::Debug + Ord,
+	Currency: CurrencyT<AccountId>,
+{
+	let mut treasury_reward = Currency::Balance::zero();
+	let mut burned_fee = Currency::Balance::zero();
+	let relayers_rewards = relayers_rewards
+		.into_iter()
+		.map(|(relayer, mut reward)| {
+			let treasury_part = if treasury_account.is_some() {
+				fee_split.treasury.mul_floor(reward.reward)
+			} else {
+				Zero::zero()
+			};
+			let burn_part = sp_std::cmp::min(
+				fee_split.burn.mul_floor(reward.reward),
+				reward.reward.saturating_sub(treasury_part),
+			);
+			reward.reward = reward.reward.saturating_sub(treasury_part);
+			reward.reward = reward.reward.saturating_sub(burn_part);
+
+			treasury_reward = treasury_reward.saturating_add(treasury_part);
+			burned_fee = burned_fee.saturating_add(burn_part);
+			(relayer, reward)
+		})
+		.collect();
+
+	if let Some(treasury_account) = treasury_account {
+		if !treasury_reward.is_zero() {
+			let transfer_result = Currency::transfer(
+				relayer_fund_account,
+				&treasury_account,
+				treasury_reward,
+				// the relayer fund account must stay above ED (needs to be pre-funded)
+				ExistenceRequirement::KeepAlive,
+			);
+			if let Err(error) = transfer_result {
+				log::trace!(
+					target: "runtime::bridge-messages",
+					"Failed to transfer {:?} to treasury account {:?}: {:?}",
+					treasury_reward,
+					treasury_account,
+					error,
+				);
+			}
+		}
+	}
+
+	if !burned_fee.is_zero() {
+		// dropping negative imbalance decreases total issuance
+		let withdraw_result = Currency::withdraw(
+			relayer_fund_account,
+			burned_fee,
+			WithdrawReasons::FEE,
+			ExistenceRequirement::KeepAlive,
+		);
+		if let Err(error) = withdraw_result {
+			log::trace!(
+				target: "runtime::bridge-messages",
+				"Failed to burn {:?} of collected fees: {:?}",
+				burned_fee,
+				error,
+			);
+		}
+	}
+
+	relayers_rewards
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::instant_payments::InstantCurrencyPayments;
+	use crate::mock::{run_test, AccountId as TestAccountId, Balance as TestBalance, TestRuntime};
+	use bp_messages::source_chain::RelayerRewards;
+
+	type Balances = pallet_balances::Pallet<TestRuntime>;
+
+	const RELAYER_1: TestAccountId = 1;
+	const RELAYER_2: TestAccountId = 2;
+	const TREASURY_ACCOUNT: TestAccountId = 3;
+	const RELAYERS_FUND_ACCOUNT: TestAccountId = crate::mock::ENDOWED_ACCOUNT;
+
+	frame_support::parameter_types! {
+		pub const ConfirmationFee: TestBalance = 0;
+		pub const RootAccount: Option<TestAccountId> = None;
+		pub Treasury: Option<TestAccountId> = Some(TREASURY_ACCOUNT);
+		pub NoTreasury: Option<TestAccountId> = None;
+		pub TestFeeSplit: FeeSplit = FeeSplit {
+			treasury: Perbill::from_percent(10),
+			burn: Perbill::from_percent(20),
+		};
+		pub LargeFeeSplit: FeeSplit = FeeSplit {
+			treasury: Perbill::from_percent(60),
+			burn: Perbill::from_percent(60),
+		};
+	}
+
+	type Payments<GetFeeSplit, TreasuryAccount> = FeeSplitPayments<
+		TestRuntime,
+		Balances,
+		InstantCurrencyPayments<TestRuntime, Balances, ConfirmationFee, RootAccount>,
+		GetFeeSplit,
+		TreasuryAccount,
+	>;
+
+	fn relayers_rewards() -> RelayersRewards<TestAccountId, TestBalance> {
+		vec![
+			(
+				RELAYER_1,
+				RelayerRewards {
+					reward: 100,
+					messages: 2,
+				},
+			),
+			(
+				RELAYER_2,
+				RelayerRewards {
+					reward: 200,
+					messages: 3,
+				},
+			),
+		]
+		.into_iter()
+		.collect()
+	}
+
+	#[test]
+	fn fees_are_split_between_relayers_treasury_and_burn() {
+		run_test(|| {
+			let total_issuance = Balances::total_issuance();
+			let paid_rewards = Payments::<TestFeeSplit, Treasury>::pay_relayers_rewards(
+				&RELAYER_1,
+				relayers_rewards(),
+				&RELAYERS_FUND_ACCOUNT,
+			);
+
+			assert_eq!(paid_rewards, 210);
+			assert_eq!(Balances::free_balance(&RELAYER_1), 70);
+			assert_eq!(Balances::free_balance(&RELAYER_2), 140);
+			assert_eq!(Balances::free_balance(&TREASURY_ACCOUNT), 30);
+			assert_eq!(Balances::total_issuance(), total_issuance - 60);
+		});
+	}
+
+	#[test]
+	fn treasury_part_is_paid_to_relayers_if_there_is_no_treasury_account() {
+		run_test(|| {
+			let total_issuance = Balances::total_issuance();
+			Payments::<TestFeeSplit, NoTreasury>::pay_relayers_rewards(
+				&RELAYER_2,
+				relayers_rewards(),
+				&RELAYERS_FUND_ACCOUNT,
+			);
+
+			assert_eq!(Balances::free_balance(&RELAYER_1), 80);
+			assert_eq!(Balances::free_balance(&RELAYER_2), 160);
+			assert_eq!(Balances::total_issuance(), total_issuance - 60);
+		});
+	}
+
+	#[test]
+	fn burn_part_is_decreased_if_split_exceeds_collected_fees() {
+		run_test(|| {
+			let total_issuance = Balances::total_issuance();
+			Payments::<LargeFeeSplit, Treasury>::pay_relayers_rewards(
+				&RELAYER_1,
+				relayers_rewards(),
+				&RELAYERS_FUND_ACCOUNT,
+			);
+
+			assert_eq!(Balances::free_balance(&RELAYER_1), 0);
+			assert_eq!(Balances::free_balance(&RELAYER_2), 0);
+			assert_eq!(Balances::free_balance(&TREASURY_ACCOUNT), 180);
+			assert_eq!(Balances::total_issuance(), total_issuance - 120);
+		});
+	}
+}
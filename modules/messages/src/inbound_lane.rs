@@ -37,6 +37,11 @@ pub trait InboundLaneStorage {
 	fn max_unrewarded_relayer_entries(&self) -> MessageNonce;
 	/// Return maximal number of unconfirmed messages in inbound lane.
 	fn max_unconfirmed_messages(&self) -> MessageNonce;
+	/// Return maximal number of confirmed relayer entries that may be pruned when lane state is updated.
+	///
+	/// Remaining confirmed entries stay in the lane data (and so in delivery proofs) until they're pruned
+	/// later. The bridged chain ignores entries of messages that it has already confirmed.
+	fn max_confirmed_relayer_entries_to_prune(&self) -> MessageNonce;
 	/// Get lane data from the storage.
 	fn data(&self) -> InboundLaneData<Self::Relayer>;
 	/// Update lane data in the storage.
@@ -85,32 +90,34 @@ impl<S: InboundLaneStorage> InboundLane<S> {
 
 		let new_confirmed_nonce = outbound_lane_data.latest_received_nonce;
 		data.last_confirmed_nonce = new_confirmed_nonce;
-		// Firstly, remove all of the records where higher nonce <= new confirmed nonce
-		while data
-			.relayers
-			.front()
-			.map(|entry| entry.messages.end <= new_confirmed_nonce)
-			.unwrap_or(false)
-		{
-			data.relayers.pop_front();
-		}
-		// Secondly, update the next record with lower nonce equal to new confirmed nonce if needed.
-		// Note: There will be max. 1 record to update as we don't allow messages from relayers to overlap.
-		match data.relayers.front_mut() {
-			Some(entry) if entry.messages.begin < new_confirmed_nonce => {
-				entry.messages.dispatch_results = entry
-					.messages
-					.dispatch_results
-					.split_off((new_confirmed_nonce + 1 - entry.messages.begin) as _);
-				entry.messages.begin = new_confirmed_nonce + 1;
-			}
-			_ => {}
-		}
+		prune_confirmed_relayers(&mut data, self.storage.max_confirmed_relayer_entries_to_prune());
 
 		self.storage.set_data(data);
 		Some(outbound_lane_data.latest_received_nonce)
 	}
 
+	/// Returns nonce of the latest message that has been delivered to this lane.
+	pub fn last_delivered_nonce(&self) -> MessageNonce {
+		self.storage.data().last_delivered_nonce()
+	}
+
+	/// Prune at most `max_entries` relayer entries that have already been confirmed by the bridged chain.
+	///
+	/// Returns number of pruned entries.
+	pub fn prune_confirmed_relayers(&mut self, max_entries: MessageNonce) -> MessageNonce {
+		let mut data = self.storage.data();
+		let pruned_entries = prune_confirmed_relayers(&mut data, max_entries);
+		if pruned_entries != 0 {
+			self.storage.set_data(data);
+		}
+		pruned_entries
+	}
+
+	/// Returns true if lane has relayer entries that have already been confirmed by the bridged chain.
+	pub fn has_confirmed_relayers(&self) -> bool {
+		has_confirmed_relayers(&self.storage.data())
+	}
+
 	/// Receive new message.
 	pub fn receive_message<P: MessageDispatch<AccountId, S::MessageFee>, AccountId>(
 		&mut self,
@@ -125,7 +132,11 @@ impl<S: InboundLaneStorage> InboundLane<S> {
 			return ReceivalResult::InvalidNonce;
 		}
 
-		// if there are more unrewarded relayer entries than we may accept, reject this message
+		// if there are more unrewarded relayer entries than we may accept, reject this message. Entries that
+		// have been confirmed, but not yet pruned, are removed first
+		if data.relayers.len() as MessageNonce >= self.storage.max_unrewarded_relayer_entries() {
+			prune_confirmed_relayers(&mut data, MessageNonce::MAX);
+		}
 		if data.relayers.len() as MessageNonce >= self.storage.max_unrewarded_relayer_entries() {
 			return ReceivalResult::TooManyUnrewardedRelayers;
 		}
@@ -170,6 +181,44 @@ impl<S: InboundLaneStorage> InboundLane<S> {
 	}
 }
 
+/// Remove at most `max_entries` relayer entries where higher nonce <= confirmed nonce. If all such entries
+/// have been removed, update the next entry with lower nonce equal to confirmed nonce if needed.
+///
+/// Returns number of removed entries.
+fn prune_confirmed_relayers<RelayerId>(
+	data: &mut InboundLaneData<RelayerId>,
+	max_entries: MessageNonce,
+) -> MessageNonce {
+	let confirmed_nonce = data.last_confirmed_nonce;
+	let mut pruned_entries = 0;
+	while pruned_entries < max_entries && has_confirmed_relayers(data) {
+		data.relayers.pop_front();
+		pruned_entries += 1;
+	}
+
+	// Note: There will be max. 1 record to update as we don't allow messages from relayers to overlap.
+	match data.relayers.front_mut() {
+		Some(entry) if entry.messages.begin < confirmed_nonce && entry.messages.end > confirmed_nonce => {
+			entry.messages.dispatch_results = entry
+				.messages
+				.dispatch_results
+				.split_off((confirmed_nonce + 1 - entry.messages.begin) as _);
+			entry.messages.begin = confirmed_nonce + 1;
+		}
+		_ => {}
+	}
+
+	pruned_entries
+}
+
+/// Returns true if the oldest relayer entry has been completely confirmed.
+fn has_confirmed_relayers<RelayerId>(data: &InboundLaneData<RelayerId>) -> bool {
+	data.relayers
+		.front()
+		.map(|entry| entry.messages.end <= data.last_confirmed_nonce)
+		.unwrap_or(false)
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
@@ -316,6 +365,85 @@ mod tests {
 		});
 	}
 
+	#[test]
+	fn receive_status_update_prunes_limited_number_of_relayer_entries() {
+		run_test(|| {
+			let mut lane = inbound_lane::<TestRuntime, _>(TEST_LANE_ID);
+			let mut seed_storage_data = lane.storage.data();
+			seed_storage_data
+				.relayers
+				.push_back(unrewarded_relayer(1, 1, TEST_RELAYER_A));
+			seed_storage_data
+				.relayers
+				.push_back(unrewarded_relayer(2, 2, TEST_RELAYER_B));
+			seed_storage_data
+				.relayers
+				.push_back(unrewarded_relayer(3, 3, TEST_RELAYER_C));
+			seed_storage_data
+				.relayers
+				.push_back(unrewarded_relayer(4, 4, TEST_RELAYER_A));
+			lane.storage.set_data(seed_storage_data);
+
+			// only 2 entries are pruned by the state update
+			assert_eq!(
+				lane.receive_state_update(OutboundLaneData {
+					latest_received_nonce: 3,
+					..Default::default()
+				}),
+				Some(3),
+			);
+			assert_eq!(lane.storage.data().last_confirmed_nonce, 3);
+			assert_eq!(
+				lane.storage.data().relayers,
+				vec![
+					unrewarded_relayer(3, 3, TEST_RELAYER_C),
+					unrewarded_relayer(4, 4, TEST_RELAYER_A)
+				]
+			);
+			assert!(lane.has_confirmed_relayers());
+		});
+	}
+
+	#[test]
+	fn prune_confirmed_relayers_works() {
+		run_test(|| {
+			let mut lane = inbound_lane::<TestRuntime, _>(TEST_LANE_ID);
+			let mut seed_storage_data = lane.storage.data();
+			seed_storage_data.last_confirmed_nonce = 4;
+			seed_storage_data
+				.relayers
+				.push_back(unrewarded_relayer(1, 1, TEST_RELAYER_A));
+			seed_storage_data
+				.relayers
+				.push_back(unrewarded_relayer(2, 2, TEST_RELAYER_B));
+			seed_storage_data
+				.relayers
+				.push_back(unrewarded_relayer(3, 5, TEST_RELAYER_C));
+			lane.storage.set_data(seed_storage_data);
+			assert!(lane.has_confirmed_relayers());
+
+			// partially confirmed entry is not touched until all confirmed entries are pruned
+			assert_eq!(lane.prune_confirmed_relayers(1), 1);
+			assert!(lane.has_confirmed_relayers());
+			assert_eq!(
+				lane.storage.data().relayers,
+				vec![
+					unrewarded_relayer(2, 2, TEST_RELAYER_B),
+					unrewarded_relayer(3, 5, TEST_RELAYER_C)
+				]
+			);
+
+			assert_eq!(lane.prune_confirmed_relayers(1), 1);
+			assert!(!lane.has_confirmed_relayers());
+			assert_eq!(
+				lane.storage.data().relayers,
+				vec![unrewarded_relayer(5, 5, TEST_RELAYER_C)]
+			);
+
+			assert_eq!(lane.prune_confirmed_relayers(1), 0);
+		});
+	}
+
 	#[test]
 	fn fails_to_receive_message_with_incorrect_nonce() {
 		run_test(|| {
@@ -0,0 +1,372 @@
+// Copyright 2019-2021 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Implementation of `MessageDeliveryAndDispatchPayment` trait on top of `fungibles` traits.
+//!
+//! This may be used by chains that are charging message fees in some non-native asset (e.g.
+//! managed by `pallet-assets` or `orml-tokens`). The fee declared by the message submitter is
+//! still expressed in the native tokens of this chain, but every actual transfer is made in
+//! the `FeeAsset`. Fees are converted (using `FeeConversion`) when they're paid by the message
+//! submitter. Refunds and relayer rewards are paid pro rata from the collected assets.
+
+use crate::instant_payments::distribute_relayers_rewards;
+
+use bp_messages::{
+	source_chain::{MessageDeliveryAndDispatchPayment, RelayersRewards, Sender},
+	MessageNonce,
+};
+use codec::FullCodec;
+use frame_support::{
+	storage::types::{OptionQuery, StorageMap},
+	traits::{
+		tokens::fungibles::{Inspect, Transfer},
+		Get, StorageInstance,
+	},
+	Blake2_128Concat,
+};
+use num_traits::Zero;
+use sp_runtime::{
+	traits::{AtLeast32BitUnsigned, Saturating, UniqueSaturatedFrom, UniqueSaturatedInto},
+	FixedPointNumber, FixedU128,
+};
+use sp_std::fmt::Debug;
+
+/// Prefix of the `CollectedFees` map.
+pub struct CollectedFeesPrefix;
+
+impl StorageInstance for CollectedFeesPrefix {
+	fn pallet_prefix() -> &'static str {
+		"BridgeMessagesInstantAssetPayments"
+	}
+
+	const STORAGE_PREFIX: &'static str = "CollectedFees";
+}
+
+/// Fees (in this chain native tokens), that have been paid to the relayers fund account and are not
+/// yet paid to relayers or refunded. Map is keyed by the relayers fund account.
+///
+/// The fee asset balance of the relayers fund account (above the minimal balance) is split between
+/// relayers pro rata to these fees.
+pub type CollectedFees<AccountId, Balance> =
+	StorageMap<CollectedFeesPrefix, Blake2_128Concat, AccountId, Balance, OptionQuery>;
+
+/// Conversion of message fee (that is expressed in this chain native tokens) into the fee asset.
+pub trait FeeConversion<Balance, AssetBalance> {
+	/// Convert native tokens amount into the fee asset amount.
+	///
+	/// Returns `None` if conversion is impossible (e.g. there's no known conversion rate).
+	fn convert(fee: Balance) -> Option<AssetBalance>;
+}
+
+/// Instant message payments made in given `FeeAsset` of `Assets`.
+///
+/// The payment flow is the same as in the [`InstantCurrencyPayments`]. The only difference is
+/// that all amounts are transferred in the `FeeAsset`. The message fee is converted to the
+/// `FeeAsset` once, when it is paid by the submitter. Refunds and rewards are paid from the
+/// `relayers-fund` account pro rata to the (native) fees collected, so changes of the conversion
+/// rate never make the `relayers-fund` account pay more (or less) than it has collected.
+///
+/// [`InstantCurrencyPayments`]: crate::instant_payments::InstantCurrencyPayments
+pub struct InstantAssetPayments<T, Balance, Assets, FeeAsset, Conversion, GetConfirmationFee, RootAccount> {
+	_phantom: sp_std::marker::PhantomData<(
+		T,
+		Balance,
+		Assets,
+		FeeAsset,
+		Conversion,
+		GetConfirmationFee,
+		RootAccount,
+	)>,
+}
+
+impl<T, Balance, Assets, FeeAsset, Conversion, GetConfirmationFee, RootAccount>
+	MessageDeliveryAndDispatchPayment<T::AccountId, Balance>
+	for InstantAssetPayments<T, Balance, Assets, FeeAsset, Conversion, GetConfirmationFee, RootAccount>
+where
+	T: frame_system::Config,
+	Balance: AtLeast32BitUnsigned + FullCodec + Copy + Debug + From<MessageNonce>,
+	Assets: Transfer<T::AccountId>,
+	FeeAsset: Get<Assets::AssetId>,
+	Conversion: FeeConversion<Balance, Assets::Balance>,
+	GetConfirmationFee: Get<Balance>,
+	RootAccount: Get<Option<T::AccountId>>,
+{
+	type Error = &'static str;
+
+	fn initialize(relayer_fund_account: &T::AccountId) -> usize {
+		let fee_asset = FeeAsset::get();
+		let balance = Assets::balance(fee_asset, relayer_fund_account);
+		assert!(
+			!balance.is_zero() && balance >= Assets::minimum_balance(fee_asset),
+			"The relayer fund account ({:?}) must hold the fee asset for the message lanes pallet to work correctly.",
+			relayer_fund_account,
+		);
+		2
+	}
+
+	fn pay_delivery_and_dispatch_fee(
+		submitter: &Sender<T::AccountId>,
+		fee: &Balance,
+		relayer_fund_account: &T::AccountId,
+	) -> Result<(), Self::Error> {
+		let root_account = RootAccount::get();
+		let account = match submitter {
+			Sender::Signed(submitter) => submitter,
+			Sender::Root | Sender::None => root_account
+				.as_ref()
+				.ok_or("Sending messages using Root or None origin is disallowed.")?,
+		};
+
+		let asset_fee = Conversion::convert(*fee).ok_or("Failed to convert message fee to the fee asset.")?;
+		Assets::transfer(
+			FeeAsset::get(),
+			account,
+			relayer_fund_account,
+			asset_fee,
+			// it's fine for the submitter to go below Existential Deposit and die.
+			false,
+		)
+		.map_err(Into::<&'static str>::into)?;
+
+		CollectedFees::<T::AccountId, Balance>::mutate(relayer_fund_account, |collected_fees| {
+			*collected_fees = Some(collected_fees.unwrap_or_else(Zero::zero).saturating_add(*fee));
+		});
+
+		Ok(())
+	}
+
+	fn refund_delivery_and_dispatch_fee(
+		refund_to: &Sender<T::AccountId>,
+		fee: &Balance,
+		relayer_fund_account: &T::AccountId,
+	) -> Result<(), Self::Error> {
+		let root_account = RootAccount::get();
+		let account = match refund_to {
+			Sender::Signed(refund_to) => refund_to,
+			Sender::Root | Sender::None => root_account
+				.as_ref()
+				.ok_or("Refunding fees to Root or None origin is disallowed.")?,
+		};
+
+		let asset_fee =
+			collected_fee_share::<T::AccountId, Balance, Assets>(FeeAsset::get(), relayer_fund_account, *fee);
+		Assets::transfer(
+			FeeAsset::get(),
+			relayer_fund_account,
+			account,
+			asset_fee,
+			// the relayers fund account must always exist
+			true,
+		)
+		.map_err(Into::<&'static str>::into)?;
+
+		release_collected_fee::<T::AccountId, Balance>(relayer_fund_account, *fee);
+
+		Ok(())
+	}
+
+	fn pay_relayers_rewards(
+		confirmation_relayer: &T::AccountId,
+		relayers_rewards: RelayersRewards<T::AccountId, Balance>,
+		relayer_fund_account: &T::AccountId,
+	) -> Balance {
+		distribute_relayers_rewards(
+			confirmation_relayer,
+			relayers_rewards,
+			GetConfirmationFee::get(),
+			|relayer, reward| {
+				pay_relayer_reward::<_, Balance, Assets>(FeeAsset::get(), relayer_fund_account, relayer, reward)
+			},
+		)
+	}
+}
+
+/// Returns the fee asset amount that corresponds to the given part of fees, collected by the
+/// relayers fund account.
+fn collected_fee_share<AccountId, Balance, Assets>(
+	asset: Assets::AssetId,
+	relayer_fund_account: &AccountId,
+	fee: Balance,
+) -> Assets::Balance
+where
+	AccountId: FullCodec,
+	Balance: AtLeast32BitUnsigned + FullCodec + Copy,
+	Assets: Inspect<AccountId>,
+{
+	let collected_fees = CollectedFees::<AccountId, Balance>::get(relayer_fund_account).unwrap_or_else(Zero::zero);
+	// the minimal balance is pre-funded and must stay at the relayers fund account
+	let available_assets = Assets::balance(asset, relayer_fund_account).saturating_sub(Assets::minimum_balance(asset));
+	pro_rata_share(fee, collected_fees, available_assets)
+}
+
+/// Forget about the given part of fees, collected by the relayers fund account.
+fn release_collected_fee<AccountId, Balance>(relayer_fund_account: &AccountId, fee: Balance)
+where
+	AccountId: FullCodec,
+	Balance: AtLeast32BitUnsigned + FullCodec + Copy,
+{
+	CollectedFees::<AccountId, Balance>::mutate(relayer_fund_account, |collected_fees| {
+		let remaining_fees = collected_fees.unwrap_or_else(Zero::zero).saturating_sub(fee);
+		*collected_fees = if remaining_fees.is_zero() {
+			None
+		} else {
+			Some(remaining_fees)
+		};
+	});
+}
+
+/// Returns `fee / collected_fees` share of `available_assets`.
+fn pro_rata_share<Balance, AssetBalance>(
+	fee: Balance,
+	collected_fees: Balance,
+	available_assets: AssetBalance,
+) -> AssetBalance
+where
+	Balance: UniqueSaturatedInto<u128>,
+	AssetBalance: UniqueSaturatedInto<u128> + UniqueSaturatedFrom<u128>,
+{
+	let fee: u128 = fee.unique_saturated_into();
+	let collected_fees: u128 = collected_fees.unique_saturated_into();
+	if collected_fees == 0 {
+		return AssetBalance::unique_saturated_from(0u128);
+	}
+
+	AssetBalance::unique_saturated_from(
+		FixedU128::saturating_from_rational(sp_std::cmp::min(fee, collected_fees), collected_fees)
+			.saturating_mul_int(available_assets.unique_saturated_into()),
+	)
+}
+
+/// Transfer funds from relayers fund account to given relayer.
+///
+/// Returns the paid reward (zero if the transfer has failed).
+fn pay_relayer_reward<AccountId, Balance, Assets>(
+	asset: Assets::AssetId,
+	relayer_fund_account: &AccountId,
+	relayer_account: &AccountId,
+	reward: Balance,
+) -> Balance
+where
+	AccountId: Debug + FullCodec,
+	Balance: AtLeast32BitUnsigned + FullCodec + Copy + Debug,
+	Assets: Transfer<AccountId>,
+{
+	if reward.is_zero() {
+		return reward;
+	}
+
+	let asset_reward = collected_fee_share::<AccountId, Balance, Assets>(asset, relayer_fund_account, reward);
+	let pay_result = Assets::transfer(
+		asset,
+		relayer_fund_account,
+		relayer_account,
+		asset_reward,
+		// the relayer fund account must stay above ED (needs to be pre-funded)
+		true,
+	);
+
+	match pay_result {
+		Ok(_) => {
+			release_collected_fee::<AccountId, Balance>(relayer_fund_account, reward);
+			log::trace!(
+				target: "runtime::bridge-messages",
+				"Rewarded relayer {:?} with {:?} ({:?} in the fee asset)",
+				relayer_account,
+				reward,
+				asset_reward,
+			);
+			reward
+		}
+		Err(error) => {
+			log::trace!(
+				target: "runtime::bridge-messages",
+				"Failed to pay relayer {:?} reward {:?}: {:?}",
+				relayer_account,
+				reward,
+				error,
+			);
+			Zero::zero()
+		}
+	}
+}
+
+/// Fee conversion that is using constant multiplier (the `Get` that returns fixed-point
+/// number of fee asset units, equal to one native token).
+pub struct FixedRateFeeConversion<Rate>(sp_std::marker::PhantomData<Rate>);
+
+impl<Balance, AssetBalance, Rate> FeeConversion<Balance, AssetBalance> for FixedRateFeeConversion<Rate>
+where
+	Balance: Into<u128>,
+	AssetBalance: sp_std::convert::TryFrom<u128>,
+	Rate: Get<FixedU128>,
+{
+	fn convert(fee: Balance) -> Option<AssetBalance> {
+		Rate::get()
+			.checked_mul_int(fee.into())
+			.and_then(|asset_fee| AssetBalance::try_from(asset_fee).ok())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	frame_support::parameter_types! {
+		pub const Rate: FixedU128 = FixedU128::from_inner(1_500_000_000_000_000_000);
+	}
+
+	#[test]
+	fn fixed_rate_fee_conversion_works() {
+		assert_eq!(
+			<FixedRateFeeConversion<Rate> as FeeConversion<u64, u64>>::convert(100),
+			Some(150)
+		);
+		assert_eq!(
+			<FixedRateFeeConversion<Rate> as FeeConversion<u64, u32>>::convert(u64::MAX),
+			None
+		);
+	}
+
+	#[test]
+	fn pro_rata_share_works() {
+		assert_eq!(pro_rata_share(100u64, 400u64, 1_000u64), 250);
+		assert_eq!(pro_rata_share(400u64, 400u64, 1_000u64), 1_000);
+		assert_eq!(pro_rata_share(0u64, 400u64, 1_000u64), 0);
+	}
+
+	#[test]
+	fn pro_rata_share_never_exceeds_available_assets() {
+		assert_eq!(pro_rata_share(800u64, 400u64, 1_000u64), 1_000);
+		assert_eq!(pro_rata_share(100u64, 0u64, 1_000u64), 0);
+	}
+
+	#[test]
+	fn pro_rata_share_does_not_depend_on_conversion_rate_changes() {
+		// two messages with 100 fee each have been paid at different conversion rates: 150 + 50
+		let collected_fees = 200u64;
+		let available_assets = 200u64;
+
+		// the first message is rewarded
+		let first_reward = pro_rata_share(100u64, collected_fees, available_assets);
+		assert_eq!(first_reward, 100);
+
+		// and then the second one
+		assert_eq!(
+			pro_rata_share(100u64, collected_fees - 100, available_assets - first_reward),
+			100,
+		);
+	}
+}
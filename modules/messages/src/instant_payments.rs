@@ -89,33 +89,82 @@ where
 		.map_err(Into::into)
 	}
 
+	fn refund_delivery_and_dispatch_fee(
+		refund_to: &Sender<T::AccountId>,
+		fee: &Currency::Balance,
+		relayer_fund_account: &T::AccountId,
+	) -> Result<(), Self::Error> {
+		let root_account = RootAccount::get();
+		let account = match refund_to {
+			Sender::Signed(refund_to) => refund_to,
+			Sender::Root | Sender::None => root_account
+				.as_ref()
+				.ok_or("Refunding fees to Root or None origin is disallowed.")?,
+		};
+
+		Currency::transfer(
+			relayer_fund_account,
+			account,
+			*fee,
+			// the relayers fund account must always exist
+			ExistenceRequirement::KeepAlive,
+		)
+		.map_err(Into::into)
+	}
+
 	fn pay_relayers_rewards(
 		confirmation_relayer: &T::AccountId,
 		relayers_rewards: RelayersRewards<T::AccountId, Currency::Balance>,
 		relayer_fund_account: &T::AccountId,
-	) {
+	) -> Currency::Balance {
 		pay_relayers_rewards::<Currency, _>(
 			confirmation_relayer,
 			relayers_rewards,
 			relayer_fund_account,
 			GetConfirmationFee::get(),
-		);
+		)
 	}
 }
 
 /// Pay rewards to given relayers, optionally rewarding confirmation relayer.
+///
+/// Returns the total reward that has been paid.
 fn pay_relayers_rewards<Currency, AccountId>(
 	confirmation_relayer: &AccountId,
 	relayers_rewards: RelayersRewards<AccountId, Currency::Balance>,
 	relayer_fund_account: &AccountId,
 	confirmation_fee: Currency::Balance,
-) where
+) -> Currency::Balance
+where
 	AccountId: Debug + Default + Encode + PartialEq,
 	Currency: CurrencyT<AccountId>,
 	Currency::Balance: From<u64>,
+{
+	distribute_relayers_rewards(
+		confirmation_relayer,
+		relayers_rewards,
+		confirmation_fee,
+		|relayer, reward| pay_relayer_reward::<Currency, _>(relayer_fund_account, relayer, reward),
+	)
+}
+
+/// Split relayers rewards between delivery and confirmation relayers and pay them using
+/// given `pay_relayer_reward` function.
+///
+/// The `pay_relayer_reward` returns the reward that has actually been paid. Returns the total paid reward.
+pub(crate) fn distribute_relayers_rewards<AccountId, Balance>(
+	confirmation_relayer: &AccountId,
+	relayers_rewards: RelayersRewards<AccountId, Balance>,
+	confirmation_fee: Balance,
+	mut pay_relayer_reward: impl FnMut(&AccountId, Balance) -> Balance,
+) -> Balance
+where
+	AccountId: PartialEq,
+	Balance: Copy + From<u64> + Saturating + PartialOrd + Zero,
 {
 	// reward every relayer except `confirmation_relayer`
-	let mut confirmation_relayer_reward = Currency::Balance::zero();
+	let mut paid_reward = Balance::zero();
+	let mut confirmation_relayer_reward = Balance::zero();
 	for (relayer, reward) in relayers_rewards {
 		let mut relayer_reward = reward.reward;
 
@@ -138,24 +187,27 @@ fn pay_relayers_rewards<Currency, AccountId>(
 			continue;
 		}
 
-		pay_relayer_reward::<Currency, _>(relayer_fund_account, &relayer, relayer_reward);
+		paid_reward = paid_reward.saturating_add(pay_relayer_reward(&relayer, relayer_reward));
 	}
 
 	// finally - pay reward to confirmation relayer
-	pay_relayer_reward::<Currency, _>(relayer_fund_account, confirmation_relayer, confirmation_relayer_reward);
+	paid_reward.saturating_add(pay_relayer_reward(confirmation_relayer, confirmation_relayer_reward))
 }
 
 /// Transfer funds from relayers fund account to given relayer.
+///
+/// Returns the paid reward (zero if the transfer has failed).
 fn pay_relayer_reward<Currency, AccountId>(
 	relayer_fund_account: &AccountId,
 	relayer_account: &AccountId,
 	reward: Currency::Balance,
-) where
+) -> Currency::Balance
+where
 	AccountId: Debug,
 	Currency: CurrencyT<AccountId>,
 {
 	if reward.is_zero() {
-		return;
+		return reward;
 	}
 
 	let pay_result = Currency::transfer(
@@ -167,19 +219,25 @@ fn pay_relayer_reward<Currency, AccountId>(
 	);
 
 	match pay_result {
-		Ok(_) => log::trace!(
-			target: "runtime::bridge-messages",
-			"Rewarded relayer {:?} with {:?}",
-			relayer_account,
-			reward,
-		),
-		Err(error) => log::trace!(
-			target: "runtime::bridge-messages",
-			"Failed to pay relayer {:?} reward {:?}: {:?}",
-			relayer_account,
-			reward,
-			error,
-		),
+		Ok(_) => {
+			log::trace!(
+				target: "runtime::bridge-messages",
+				"Rewarded relayer {:?} with {:?}",
+				relayer_account,
+				reward,
+			);
+			reward
+		}
+		Err(error) => {
+			log::trace!(
+				target: "runtime::bridge-messages",
+				"Failed to pay relayer {:?} reward {:?}: {:?}",
+				relayer_account,
+				reward,
+				error,
+			);
+			Zero::zero()
+		}
 	}
 }
 
@@ -220,8 +278,10 @@ mod tests {
 	#[test]
 	fn confirmation_relayer_is_rewarded_if_it_has_also_delivered_messages() {
 		run_test(|| {
-			pay_relayers_rewards::<Balances, _>(&RELAYER_2, relayers_rewards(), &RELAYERS_FUND_ACCOUNT, 10);
+			let paid_rewards =
+				pay_relayers_rewards::<Balances, _>(&RELAYER_2, relayers_rewards(), &RELAYERS_FUND_ACCOUNT, 10);
 
+			assert_eq!(paid_rewards, 200);
 			assert_eq!(Balances::free_balance(&RELAYER_1), 80);
 			assert_eq!(Balances::free_balance(&RELAYER_2), 120);
 		});
@@ -248,4 +308,16 @@ mod tests {
 			assert_eq!(Balances::free_balance(&RELAYER_3), 200);
 		});
 	}
+
+	#[test]
+	fn failed_reward_payments_are_not_counted_as_paid() {
+		run_test(|| {
+			// `RELAYER_3` account has no funds to pay rewards
+			let paid_rewards = pay_relayers_rewards::<Balances, _>(&RELAYER_2, relayers_rewards(), &RELAYER_3, 10);
+
+			assert_eq!(paid_rewards, 0);
+			assert_eq!(Balances::free_balance(&RELAYER_1), 0);
+			assert_eq!(Balances::free_balance(&RELAYER_2), 0);
+		});
+	}
 }
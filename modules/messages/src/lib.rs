@@ -48,32 +48,48 @@ use crate::weights::WeightInfo;
 
 use bp_messages::{
 	source_chain::{
-		LaneMessageVerifier, MessageDeliveryAndDispatchPayment, OnDeliveryConfirmed, RelayersRewards, TargetHeaderChain,
+		LaneMessageVerifier, MessageDeliveryAndDispatchPayment, OnDeliveryConfirmed, RelayersRewards, Sender,
+		TargetHeaderChain,
 	},
 	target_chain::{DispatchMessage, MessageDispatch, ProvedLaneMessages, ProvedMessages, SourceHeaderChain},
-	total_unrewarded_messages, DeliveredMessages, InboundLaneData, LaneId, MessageData, MessageKey, MessageNonce,
-	OperatingMode, OutboundLaneData, Parameter as MessagesParameter, UnrewardedRelayersState,
+	total_unrewarded_messages, DeliveredMessages, InboundLaneData, LaneId, LaneStatistics, MessageData, MessageKey,
+	MessageNonce, MessagePriority, MessageRejectionReason, OperatingMode, OutboundLaneData,
+	Parameter as MessagesParameter, UnrewardedRelayer, UnrewardedRelayersState, DEFAULT_MESSAGE_PRIORITY,
 };
-use bp_runtime::{ChainId, Size};
+use bp_runtime::{ChainId, Size, StorageProofStats};
 use codec::{Decode, Encode};
 use frame_support::{
 	decl_error, decl_event, decl_module, decl_storage,
 	dispatch::DispatchResultWithPostInfo,
 	ensure, fail,
+	storage::{with_transaction, TransactionOutcome},
 	traits::Get,
 	weights::{DispatchClass, Pays, PostDispatchInfo, Weight},
-	Parameter, StorageMap,
+	BoundedVec, IterableStorageMap, Parameter, StorageMap,
+};
+use frame_system::{ensure_none, ensure_root, ensure_signed, RawOrigin};
+use num_traits::{SaturatingAdd, SaturatingSub, Zero};
+use sp_core::H256;
+use sp_runtime::{
+	traits::{BadOrigin, BlakeTwo256, Hash, IdentifyAccount, Saturating, Verify},
+	transaction_validity::{
+		InvalidTransaction, TransactionLongevity, TransactionPriority, TransactionSource, TransactionValidity,
+		ValidTransaction,
+	},
+	DispatchError,
+};
+use sp_std::{
+	cell::RefCell, cmp::PartialOrd, collections::vec_deque::VecDeque, convert::TryInto, marker::PhantomData, prelude::*,
 };
-use frame_system::{ensure_signed, RawOrigin};
-use num_traits::{SaturatingAdd, Zero};
-use sp_runtime::traits::BadOrigin;
-use sp_std::{cell::RefCell, cmp::PartialOrd, marker::PhantomData, prelude::*};
 
 mod inbound_lane;
 mod outbound_lane;
 mod weights_ext;
 
+pub mod fee_split;
+pub mod instant_asset_payments;
 pub mod instant_payments;
+pub mod migrations;
 pub mod weights;
 
 #[cfg(feature = "runtime-benchmarks")]
@@ -122,11 +138,24 @@ pub trait Config<I = DefaultInstance>: frame_system::Config {
 	/// that is declaring more messages than this value, will be rejected. Even if these messages are
 	/// from different lanes.
 	type MaxUnconfirmedMessagesAtInboundLane: Get<MessageNonce>;
+	/// Maximal number of confirmed relayer entries that are pruned from the inbound lane when the
+	/// lane state is updated by the delivery transaction.
+	///
+	/// Remaining confirmed entries are pruned in `on_idle`, when the block has some spare weight.
+	type MaxConfirmedRelayerEntriesToPrune: Get<MessageNonce>;
+	/// Maximal number of messages in the delivery transaction, for which per-message events are
+	/// emitted.
+	///
+	/// If delivery transaction declares more messages, the single `MessagesReceived` event is
+	/// emitted for every lane instead. Dispatch results of received messages may then be read
+	/// from the `InboundLaneRelayers` storage. This keeps the `frame_system::Events` storage
+	/// bounded on lanes with high throughput.
+	type MaxMessageEventsInDeliveryTransaction: Get<MessageNonce>;
 
 	/// Payload type of outbound messages. This payload is dispatched on the bridged chain.
 	type OutboundPayload: Parameter + Size;
 	/// Message fee type of outbound messages. This fee is paid on this chain.
-	type OutboundMessageFee: Default + From<u64> + PartialOrd + Parameter + SaturatingAdd + Zero;
+	type OutboundMessageFee: Default + From<u64> + PartialOrd + Parameter + SaturatingAdd + SaturatingSub + Zero;
 
 	/// Payload type of inbound messages. This payload is dispatched on this chain.
 	type InboundPayload: Decode;
@@ -134,6 +163,11 @@ pub trait Config<I = DefaultInstance>: frame_system::Config {
 	type InboundMessageFee: Decode;
 	/// Identifier of relayer that deliver messages to this chain. Relayer reward is paid on the bridged chain.
 	type InboundRelayer: Parameter;
+	/// Signer of the relayer account at the bridged chain.
+	type InboundRelayerSigner: IdentifyAccount<AccountId = Self::InboundRelayer>;
+	/// Signature of the relayer account at the bridged chain. Unsigned messages delivery transaction
+	/// must be signed by the relayer that is going to receive the delivery reward.
+	type InboundRelayerSignature: Parameter + Verify<Signer = Self::InboundRelayerSigner>;
 
 	/// A type which can be turned into an AccountId from a 256-bit hash.
 	///
@@ -150,6 +184,15 @@ pub trait Config<I = DefaultInstance>: frame_system::Config {
 	type MessageDeliveryAndDispatchPayment: MessageDeliveryAndDispatchPayment<Self::AccountId, Self::OutboundMessageFee>;
 	/// Handler for delivered messages.
 	type OnDeliveryConfirmed: OnDeliveryConfirmed;
+	/// Maximal number of unrewarded relayer entries at the bridged chain inbound lane. When this limit
+	/// is reached, the bridged chain rejects new messages until delivery confirmations are received.
+	type BridgedMaxUnrewardedRelayerEntriesAtInboundLane: Get<MessageNonce>;
+	/// Number of blocks after the lane has been reported as stalled, when anyone may claim
+	/// the stalled confirmation bounty by submitting missing delivery confirmation.
+	type StalledConfirmationPeriod: Get<Self::BlockNumber>;
+	/// Bounty that is deducted from the relayer reward for every message that is confirmed by the
+	/// `claim_stalled_confirmation_bounty` call. It is paid to the call submitter.
+	type StalledConfirmationBountyPerMessage: Get<Self::OutboundMessageFee>;
 
 	// Types that are used by inbound_lane (on target chain).
 
@@ -161,6 +204,19 @@ pub trait Config<I = DefaultInstance>: frame_system::Config {
 		Self::InboundMessageFee,
 		DispatchPayload = Self::InboundPayload,
 	>;
+	/// Base priority of unsigned messages delivery transactions. The actual priority is
+	/// increased by the number of new messages in the transaction.
+	///
+	/// Anyone may copy the proof from the signed delivery transaction to the unsigned transaction,
+	/// signed by their own relayer account at the bridged chain. This priority must be lower than
+	/// the priority of signed delivery transactions. Then the signed transaction is included first
+	/// and the unsigned transaction becomes stale and is not included at all.
+	type UnsignedPriority: Get<TransactionPriority>;
+	/// Longevity of unsigned messages delivery transactions.
+	type UnsignedLongevity: Get<TransactionLongevity>;
+
+	/// Maximal number of accounts in the `AllowedRelayers` set.
+	type MaxAllowedRelayers: Get<u32>;
 
 	/// Chain Id for the bridged chain.
 	type BridgedChainId: Get<ChainId>;
@@ -202,6 +258,23 @@ decl_error! {
 		/// The number of actually confirmed messages is going to be larger than the number of messages in the proof.
 		/// This may mean that this or bridged chain storage is corrupted.
 		TryingToConfirmMoreMessagesThanExpected,
+		/// The unrewarded relayers vector at the bridged chain inbound lane has not reached its limit, or
+		/// there are no messages to confirm.
+		LaneIsNotStalled,
+		/// The lane has been reported as stalled recently, so the bounty can't be claimed yet.
+		StalledConfirmationBountyIsNotYetAvailable,
+		/// The relayer is not in the `AllowedRelayers` set.
+		RelayerIsNotAllowed,
+		/// The number of relayers exceeds the `MaxAllowedRelayers` limit.
+		TooManyAllowedRelayers,
+		/// Failed to refund part of the message fee from the relayers fund.
+		FailedToRefundMessageFee,
+		/// The submitter of the message is unknown, so the message fee can't be refunded.
+		MessageSubmitterIsUnknown,
+		/// The caller has not been proposed as the new pallet owner.
+		NotPendingOwner,
+		/// The unsigned delivery transaction has not delivered any new messages.
+		NoNewMessagesDelivered,
 	}
 }
 
@@ -214,24 +287,65 @@ decl_storage! {
 		/// runtime methods may still be used to do that (i.e. democracy::referendum to update halt
 		/// flag directly or call the `halt_operations`).
 		pub PalletOwner get(fn module_owner): Option<T::AccountId>;
+		/// Account that has been proposed as the new pallet owner, but hasn't accepted the
+		/// ownership yet.
+		pub PendingPalletOwner get(fn pending_module_owner): Option<T::AccountId>;
 		/// The current operating mode of the pallet.
 		///
 		/// Depending on the mode either all, some, or no transactions will be allowed.
 		pub PalletOperatingMode get(fn operating_mode) config(): OperatingMode;
 		/// Map of lane id => inbound lane data.
 		pub InboundLanes: map hasher(blake2_128_concat) LaneId => InboundLaneData<T::InboundRelayer>;
+		/// Copy of every entry of the `InboundLaneData::relayers`, keyed by the lane and the first
+		/// message nonce of the entry.
+		///
+		/// It allows the bridged chain to verify compact delivery proofs, which only include entries
+		/// that are not yet confirmed, instead of the whole inbound lane state.
+		pub InboundLaneRelayers: map hasher(blake2_128_concat) MessageKey
+			=> Option<UnrewardedRelayer<T::InboundRelayer>>;
 		/// Map of lane id => outbound lane data.
 		pub OutboundLanes: map hasher(blake2_128_concat) LaneId => OutboundLaneData;
 		/// All queued outbound messages.
 		pub OutboundMessages: map hasher(blake2_128_concat) MessageKey => Option<MessageData<T::OutboundMessageFee>>;
+		/// Submitters of all queued outbound messages.
+		///
+		/// The entry is removed when the message is pruned. Messages that have been sent before this map
+		/// has been added, have no entry here.
+		pub OutboundMessageSubmitters: map hasher(blake2_128_concat) MessageKey => Option<Sender<T::AccountId>>;
+		/// Map of lane id => number of block, at which the lane has been reported as stalled.
+		///
+		/// The entry is removed when delivery of new messages at this lane is confirmed.
+		pub UnrewardedRelayersLimitReachedAt: map hasher(blake2_128_concat) LaneId => Option<T::BlockNumber>;
+		/// Set of inbound lanes that have confirmed relayer entries, which are not yet pruned.
+		pub InboundLanesToPrune: map hasher(blake2_128_concat) LaneId => Option<()>;
+		/// Optional set of relayers that are allowed to deliver messages to this chain.
+		///
+		/// If it is `None`, then anyone may deliver messages (including unsigned delivery
+		/// transactions). Otherwise, only listed accounts may deliver messages.
+		pub AllowedRelayers get(fn allowed_relayers): Option<BoundedVec<T::AccountId, T::MaxAllowedRelayers>>;
+		/// Expected hash of the bridged chain genesis block.
+		///
+		/// The pallet itself doesn't use it. It is stored here, so that relayers may check that
+		/// they're connected to the right bridged chain before submitting any transactions.
+		pub BridgedChainGenesisHash get(fn bridged_chain_genesis_hash): Option<H256>;
+		/// Map of lane id => cumulative lane statistics.
+		pub LanesStatistics get(fn lane_statistics):
+			map hasher(blake2_128_concat) LaneId => LaneStatistics<T::OutboundMessageFee>;
+		/// Version of the pallet storage. It is used to decide which storage migrations need to be applied.
+		pub PalletStorageVersion get(fn storage_version): migrations::StorageVersion;
 	}
 	add_extra_genesis {
 		config(phantom): sp_std::marker::PhantomData<I>;
 		config(owner): Option<T::AccountId>;
+		config(bridged_chain_genesis_hash): Option<H256>;
 		build(|config| {
+			<PalletStorageVersion<I>>::put(migrations::StorageVersion::LATEST);
 			if let Some(ref owner) = config.owner {
 				<PalletOwner<T, I>>::put(owner);
 			}
+			if let Some(ref bridged_chain_genesis_hash) = config.bridged_chain_genesis_hash {
+				<BridgedChainGenesisHash<I>>::put(bridged_chain_genesis_hash);
+			}
 		})
 	}
 }
@@ -241,6 +355,7 @@ decl_event!(
 	where
 		AccountId = <T as frame_system::Config>::AccountId,
 		Parameter = <T as Config<I>>::Parameter,
+		OutboundMessageFee = <T as Config<I>>::OutboundMessageFee,
 	{
 		/// Pallet parameter has been updated.
 		ParameterUpdated(Parameter),
@@ -248,6 +363,21 @@ decl_event!(
 		MessageAccepted(LaneId, MessageNonce),
 		/// Messages in the inclusive range have been delivered to the bridged chain.
 		MessagesDelivered(LaneId, DeliveredMessages),
+		/// Inbound message has been rejected (or skipped) for given reason.
+		MessageRejected(LaneId, MessageNonce, MessageRejectionReason),
+		/// Messages in the inclusive range have been received from the bridged chain.
+		///
+		/// This event is emitted instead of per-message events when the delivery transaction
+		/// carries more than `MaxMessageEventsInDeliveryTransaction` messages.
+		MessagesReceived(LaneId, MessageNonce, MessageNonce),
+		/// Lane has been reported as stalled.
+		StalledLaneReported(LaneId),
+		/// Bounty for confirming delivery of messages at the stalled lane has been paid to the given account.
+		StalledConfirmationBountyPaid(LaneId, AccountId, OutboundMessageFee),
+		/// New pallet owner has been proposed. It needs to accept the ownership.
+		OwnerProposed(AccountId),
+		/// Pallet owner has been changed or removed.
+		OwnerChanged(Option<AccountId>),
 		/// Phantom member, never used.
 		Dummy(PhantomData<(AccountId, I)>),
 	}
@@ -261,6 +391,14 @@ decl_module! {
 		/// Gets the chain id value from the instance.
 		const BridgedChainId: ChainId = T::BridgedChainId::get();
 
+		/// Maximal number of unrewarded relayer entries at inbound lane.
+		const MaxUnrewardedRelayerEntriesAtInboundLane: MessageNonce =
+			T::MaxUnrewardedRelayerEntriesAtInboundLane::get();
+
+		/// Maximal number of unconfirmed messages at inbound lane. This is also the maximal
+		/// number of messages in single delivery transaction.
+		const MaxUnconfirmedMessagesAtInboundLane: MessageNonce = T::MaxUnconfirmedMessagesAtInboundLane::get();
+
 		/// Ensure runtime invariants.
 		fn on_runtime_upgrade() -> Weight {
 			let reads = T::MessageDeliveryAndDispatchPayment::initialize(
@@ -269,20 +407,31 @@ decl_module! {
 			T::DbWeight::get().reads(reads as u64)
 		}
 
-		/// Change `PalletOwner`.
+		/// Prune confirmed relayer entries of inbound lanes, using spare block weight.
+		fn on_idle(_block_number: T::BlockNumber, remaining_weight: Weight) -> Weight {
+			prune_inbound_lanes::<T, I>(remaining_weight)
+		}
+
+		/// Propose new `PalletOwner`, or remove the current owner.
+		///
+		/// The proposed owner only becomes `PalletOwner` after calling `accept_owner`. The owner
+		/// is removed immediately, because root is still able to manage the pallet.
 		///
 		/// May only be called either by root, or by `PalletOwner`.
-		#[weight = (T::DbWeight::get().reads_writes(1, 1), DispatchClass::Operational)]
-		pub fn set_owner(origin, new_owner: Option<T::AccountId>) {
+		#[weight = (T::WeightInfo::propose_owner(), DispatchClass::Operational)]
+		pub fn propose_owner(origin, new_owner: Option<T::AccountId>) {
 			ensure_owner_or_root::<T, I>(origin)?;
 			match new_owner {
 				Some(new_owner) => {
-					PalletOwner::<T, I>::put(&new_owner);
-					log::info!(target: "runtime::bridge-messages", "Setting pallet Owner to: {:?}", new_owner);
+					PendingPalletOwner::<T, I>::put(&new_owner);
+					log::info!(target: "runtime::bridge-messages", "Proposing pallet Owner: {:?}", new_owner);
+					Self::deposit_event(RawEvent::OwnerProposed(new_owner));
 				},
 				None => {
 					PalletOwner::<T, I>::kill();
+					PendingPalletOwner::<T, I>::kill();
 					log::info!(target: "runtime::bridge-messages", "Removed Owner of pallet.");
+					Self::deposit_event(RawEvent::OwnerChanged(None));
 				},
 			}
 		}
@@ -290,7 +439,7 @@ decl_module! {
 		/// Halt or resume all/some pallet operations.
 		///
 		/// May only be called either by root, or by `PalletOwner`.
-		#[weight = (T::DbWeight::get().reads_writes(1, 1), DispatchClass::Operational)]
+		#[weight = (T::WeightInfo::set_operating_mode(), DispatchClass::Operational)]
 		pub fn set_operating_mode(origin, operating_mode: OperatingMode) {
 			ensure_owner_or_root::<T, I>(origin)?;
 			<PalletOperatingMode<I>>::put(operating_mode);
@@ -304,9 +453,7 @@ decl_module! {
 		/// Update pallet parameter.
 		///
 		/// May only be called either by root, or by `PalletOwner`.
-		///
-		/// The weight is: single read for permissions check + 2 writes for parameter value and event.
-		#[weight = (T::DbWeight::get().reads_writes(1, 2), DispatchClass::Operational)]
+		#[weight = (T::WeightInfo::update_pallet_parameter(), DispatchClass::Operational)]
 		pub fn update_pallet_parameter(origin, parameter: T::Parameter) {
 			ensure_owner_or_root::<T, I>(origin)?;
 			parameter.save();
@@ -314,104 +461,19 @@ decl_module! {
 		}
 
 		/// Send message over lane.
-		#[weight = T::WeightInfo::send_message_weight(payload)]
+		#[weight = send_message_weight::<T, I>(payload)]
 		pub fn send_message(
 			origin,
 			lane_id: LaneId,
 			payload: T::OutboundPayload,
 			delivery_and_dispatch_fee: T::OutboundMessageFee,
 		) -> DispatchResultWithPostInfo {
-			ensure_normal_operating_mode::<T, I>()?;
 			let submitter = origin.into().map_err(|_| BadOrigin)?;
-
-			// initially, actual (post-dispatch) weight is equal to pre-dispatch weight
-			let mut actual_weight = T::WeightInfo::send_message_weight(&payload);
-
-			// let's first check if message can be delivered to target chain
-			T::TargetHeaderChain::verify_message(&payload)
-				.map_err(|err| {
-					log::trace!(
-						target: "runtime::bridge-messages",
-						"Message to lane {:?} is rejected by target chain: {:?}",
-						lane_id,
-						err,
-					);
-
-					Error::<T, I>::MessageRejectedByChainVerifier
-				})?;
-
-			// now let's enforce any additional lane rules
-			let mut lane = outbound_lane::<T, I>(lane_id);
-			T::LaneMessageVerifier::verify_message(
-				&submitter,
-				&delivery_and_dispatch_fee,
-				&lane_id,
-				&lane.data(),
-				&payload,
-			).map_err(|err| {
-				log::trace!(
-					target: "runtime::bridge-messages",
-					"Message to lane {:?} is rejected by lane verifier: {:?}",
-					lane_id,
-					err,
-				);
-
-				Error::<T, I>::MessageRejectedByLaneVerifier
-			})?;
-
-			// let's withdraw delivery and dispatch fee from submitter
-			T::MessageDeliveryAndDispatchPayment::pay_delivery_and_dispatch_fee(
-				&submitter,
-				&delivery_and_dispatch_fee,
-				&Self::relayer_fund_account_id(),
-			).map_err(|err| {
-				log::trace!(
-					target: "runtime::bridge-messages",
-					"Message to lane {:?} is rejected because submitter {:?} is unable to pay fee {:?}: {:?}",
-					lane_id,
-					submitter,
-					delivery_and_dispatch_fee,
-					err,
-				);
-
-				Error::<T, I>::FailedToWithdrawMessageFee
-			})?;
-
-			// finally, save message in outbound storage and emit event
-			let encoded_payload = payload.encode();
-			let encoded_payload_len = encoded_payload.len();
-			let nonce = lane.send_message(MessageData {
-				payload: encoded_payload,
-				fee: delivery_and_dispatch_fee,
-			});
-
-			// message sender pays for pruning at most `MaxMessagesToPruneAtOnce` messages
-			// the cost of pruning every message is roughly single db write
-			// => lets refund sender if less than `MaxMessagesToPruneAtOnce` messages pruned
-			let max_messages_to_prune = T::MaxMessagesToPruneAtOnce::get();
-			let pruned_messages = lane.prune_messages(max_messages_to_prune);
-			if let Some(extra_messages) = max_messages_to_prune.checked_sub(pruned_messages) {
-				actual_weight = actual_weight.saturating_sub(T::DbWeight::get().writes(extra_messages));
-			}
-
-			log::trace!(
-				target: "runtime::bridge-messages",
-				"Accepted message {} to lane {:?}. Message size: {:?}",
-				nonce,
-				lane_id,
-				encoded_payload_len,
-			);
-
-			Self::deposit_event(RawEvent::MessageAccepted(lane_id, nonce));
-
-			Ok(PostDispatchInfo {
-				actual_weight: Some(actual_weight),
-				pays_fee: Pays::Yes,
-			})
+			send_message::<T, I>(submitter, lane_id, payload, delivery_and_dispatch_fee, DEFAULT_MESSAGE_PRIORITY)
 		}
 
 		/// Pay additional fee for the message.
-		#[weight = T::WeightInfo::maximal_increase_message_fee()]
+		#[weight = maximal_increase_message_fee_weight::<T, I>()]
 		pub fn increase_message_fee(
 			origin,
 			lane_id: LaneId,
@@ -460,12 +522,16 @@ decl_module! {
 				message_data.fee = message_data.fee.saturating_add(&additional_fee);
 				message_data.payload.len()
 			});
+			LanesStatistics::<T, I>::mutate(lane_id, |statistics| {
+				statistics.total_fees_collected = statistics.total_fees_collected.saturating_add(&additional_fee);
+			});
 
 			// compute actual dispatch weight that depends on the stored message size
 			let actual_weight = sp_std::cmp::min(
 				T::WeightInfo::maximal_increase_message_fee(),
 				T::WeightInfo::increase_message_fee(message_size as _),
-			);
+			)
+			.saturating_add(lane_statistics_update_weight::<T, I>());
 
 			Ok(PostDispatchInfo {
 				actual_weight: Some(actual_weight),
@@ -478,7 +544,7 @@ decl_module! {
 		/// The weight of the call assumes that the transaction always brings outbound lane
 		/// state update. Because of that, the submitter (relayer) has no benefit of not including
 		/// this data in the transaction, so reward confirmations lags should be minimal.
-		#[weight = T::WeightInfo::receive_messages_proof_weight(proof, *messages_count, *dispatch_weight)]
+		#[weight = receive_messages_proof_weight::<T, I>(proof, *messages_count, *dispatch_weight)]
 		pub fn receive_messages_proof(
 			origin,
 			relayer_id_at_bridged_chain: T::InboundRelayer,
@@ -488,132 +554,14 @@ decl_module! {
 		) -> DispatchResultWithPostInfo {
 			ensure_not_halted::<T, I>()?;
 			let relayer_id_at_this_chain = ensure_signed(origin)?;
-
-			// reject transactions that are declaring too many messages
-			ensure!(
-				MessageNonce::from(messages_count) <= T::MaxUnconfirmedMessagesAtInboundLane::get(),
-				Error::<T, I>::TooManyMessagesInTheProof
-			);
-
-			// why do we need to know the weight of this (`receive_messages_proof`) call? Because
-			// we may want to return some funds for not-dispatching (or partially dispatching) some
-			// messages to the call origin (relayer). And this is done by returning actual weight
-			// from the call. But we only know dispatch weight of every messages. So to refund relayer
-			// because we have not dispatched Message, we need to:
-			//
-			// ActualWeight = DeclaredWeight - Message.DispatchWeight
-			//
-			// The DeclaredWeight is exactly what's computed here. Unfortunately it is impossible
-			// to get pre-computed value (and it has been already computed by the executive).
-			let declared_weight = T::WeightInfo::receive_messages_proof_weight(
-				&proof,
+			ensure_relayer_allowed::<T, I>(Some(&relayer_id_at_this_chain))?;
+			let (actual_weight, _) = receive_messages::<T, I>(
+				&relayer_id_at_bridged_chain,
+				&relayer_id_at_this_chain,
+				proof,
 				messages_count,
 				dispatch_weight,
-			);
-			let mut actual_weight = declared_weight;
-
-			// verify messages proof && convert proof into messages
-			let messages = verify_and_decode_messages_proof::<
-				T::SourceHeaderChain,
-				T::InboundMessageFee,
-				T::InboundPayload,
-			>(proof, messages_count)
-				.map_err(|err| {
-					log::trace!(
-						target: "runtime::bridge-messages",
-						"Rejecting invalid messages proof: {:?}",
-						err,
-					);
-
-					Error::<T, I>::InvalidMessagesProof
-				})?;
-
-			// dispatch messages and (optionally) update lane(s) state(s)
-			let mut total_messages = 0;
-			let mut valid_messages = 0;
-			let mut dispatch_weight_left = dispatch_weight;
-			for (lane_id, lane_data) in messages {
-				let mut lane = inbound_lane::<T, I>(lane_id);
-
-				if let Some(lane_state) = lane_data.lane_state {
-					let updated_latest_confirmed_nonce = lane.receive_state_update(lane_state);
-					if let Some(updated_latest_confirmed_nonce) = updated_latest_confirmed_nonce {
-						log::trace!(
-							target: "runtime::bridge-messages",
-							"Received lane {:?} state update: latest_confirmed_nonce={}",
-							lane_id,
-							updated_latest_confirmed_nonce,
-						);
-					}
-				}
-
-				for message in lane_data.messages {
-					debug_assert_eq!(message.key.lane_id, lane_id);
-
-					// ensure that relayer has declared enough weight for dispatching next message on
-					// this lane. We can't dispatch lane messages out-of-order, so if declared weight
-					// is not enough, let's move to next lane
-					let dispatch_weight = T::MessageDispatch::dispatch_weight(&message);
-					if dispatch_weight > dispatch_weight_left {
-						log::trace!(
-							target: "runtime::bridge-messages",
-							"Cannot dispatch any more messages on lane {:?}. Weight: declared={}, left={}",
-							lane_id,
-							dispatch_weight,
-							dispatch_weight_left,
-						);
-						break;
-					}
-					total_messages += 1;
-
-					let receival_result = lane.receive_message::<T::MessageDispatch, T::AccountId>(
-						&relayer_id_at_bridged_chain,
-						&relayer_id_at_this_chain,
-						message.key.nonce,
-						message.data,
-					);
-
-					// note that we're returning unspent weight to relayer even if message has been
-					// rejected by the lane. This allows relayers to submit spam transactions with
-					// e.g. the same set of already delivered messages over and over again, without
-					// losing funds for messages dispatch. But keep in mind that relayer pays base
-					// delivery transaction cost anyway. And base cost covers everything except
-					// dispatch, so we have a balance here.
-					let (unspent_weight, refund_pay_dispatch_fee) = match receival_result {
-						ReceivalResult::Dispatched(dispatch_result) => {
-							valid_messages += 1;
-							(dispatch_result.unspent_weight, !dispatch_result.dispatch_fee_paid_during_dispatch)
-						},
-						ReceivalResult::InvalidNonce
-							| ReceivalResult::TooManyUnrewardedRelayers
-							| ReceivalResult::TooManyUnconfirmedMessages => (dispatch_weight, true),
-					};
-
-					let unspent_weight = sp_std::cmp::min(unspent_weight, dispatch_weight);
-					dispatch_weight_left -= dispatch_weight - unspent_weight;
-					actual_weight = actual_weight
-						.saturating_sub(unspent_weight)
-						.saturating_sub(
-							// delivery call weight formula assumes that the fee is paid at
-							// this (target) chain. If the message is prepaid at the source
-							// chain, let's refund relayer with this extra cost.
-							if refund_pay_dispatch_fee {
-								T::WeightInfo::pay_inbound_dispatch_fee_overhead()
-							} else {
-								0
-							}
-						);
-				}
-			}
-
-			log::trace!(
-				target: "runtime::bridge-messages",
-				"Received messages: total={}, valid={}. Weight used: {}/{}",
-				total_messages,
-				valid_messages,
-				actual_weight,
-				declared_weight,
-			);
+			)?;
 
 			Ok(PostDispatchInfo {
 				actual_weight: Some(actual_weight),
@@ -622,11 +570,7 @@ decl_module! {
 		}
 
 		/// Receive messages delivery proof from bridged chain.
-		#[weight = T::WeightInfo::receive_messages_delivery_proof_weight(
-			proof,
-			relayers_state,
-			T::DbWeight::get(),
-		)]
+		#[weight = receive_messages_delivery_proof_weight::<T, I>(proof, relayers_state)]
 		pub fn receive_messages_delivery_proof(
 			origin,
 			proof: MessagesDeliveryProofOf<T, I>,
@@ -643,146 +587,323 @@ decl_module! {
 			//
 			// The DeclaredWeight is exactly what's computed here. Unfortunately it is impossible
 			// to get pre-computed value (and it has been already computed by the executive).
-			let single_message_callback_overhead = T::WeightInfo::single_message_callback_overhead(T::DbWeight::get());
-			let declared_weight = T::WeightInfo::receive_messages_delivery_proof_weight(
-				&proof,
-				&relayers_state,
-				T::DbWeight::get(),
-			);
-			let mut actual_weight = declared_weight;
+			let declared_weight = receive_messages_delivery_proof_weight::<T, I>(&proof, &relayers_state);
 
 			let confirmation_relayer = ensure_signed(origin)?;
-			let (lane_id, lane_data) = T::TargetHeaderChain::verify_messages_delivery_proof(proof).map_err(|err| {
-				log::trace!(
-					target: "runtime::bridge-messages",
-					"Rejecting invalid messages delivery proof: {:?}",
-					err,
-				);
+			let (lane_id, lane_data) = verify_messages_delivery_proof::<T, I>(proof, &relayers_state)?;
+			let (actual_weight, _) = confirm_messages_delivery::<T, I>(
+				&confirmation_relayer,
+				lane_id,
+				lane_data,
+				&relayers_state,
+				declared_weight,
+				None,
+			)?;
 
-				Error::<T, I>::InvalidMessagesDeliveryProof
-			})?;
+			Ok(PostDispatchInfo {
+				actual_weight: Some(actual_weight),
+				pays_fee: Pays::Yes,
+			})
+		}
 
-			// verify that the relayer has declared correct `lane_data::relayers` state
-			// (we only care about total number of entries and messages, because this affects call weight)
-			ensure!(
-				total_unrewarded_messages(&lane_data.relayers)
-					.unwrap_or(MessageNonce::MAX) == relayers_state.total_messages
-					&& lane_data.relayers.len() as MessageNonce == relayers_state.unrewarded_relayer_entries,
-				Error::<T, I>::InvalidUnrewardedRelayersState
-			);
-
-			// mark messages as delivered
-			let mut lane = outbound_lane::<T, I>(lane_id);
-			let mut relayers_rewards: RelayersRewards<_, T::OutboundMessageFee> = RelayersRewards::new();
-			let last_delivered_nonce = lane_data.last_delivered_nonce();
-			let confirmed_messages = match lane.confirm_delivery(
-				relayers_state.total_messages,
-				last_delivered_nonce,
-				&lane_data.relayers,
-			) {
-				ReceivalConfirmationResult::ConfirmedMessages(confirmed_messages) => Some(confirmed_messages),
-				ReceivalConfirmationResult::NoNewConfirmations => None,
-				ReceivalConfirmationResult::TryingToConfirmMoreMessagesThanExpected(to_confirm_messages_count) => {
-					log::trace!(
-						target: "runtime::bridge-messages",
-						"Messages delivery proof contains too many messages to confirm: {} vs declared {}",
-						to_confirm_messages_count,
-						relayers_state.total_messages,
-					);
+		/// Claim bounty for submitting delivery confirmation of the stalled lane.
+		///
+		/// The lane is stalled if the unrewarded relayers vector at the bridged chain inbound lane has
+		/// reached its limit and no one has confirmed delivery of these messages to this chain. The first
+		/// call with the proof of such state only records the block at which the lane has been reported
+		/// as stalled. If no delivery confirmation arrives within `StalledConfirmationPeriod` blocks after
+		/// that, anyone may call this method again to confirm delivery and receive bounty. The bounty is
+		/// `StalledConfirmationBountyPerMessage` for every confirmed message, deducted from the stalled
+		/// relayers rewards.
+		#[weight = receive_messages_delivery_proof_weight::<T, I>(proof, relayers_state)
+			.saturating_add(T::DbWeight::get().reads_writes(1, 1))]
+		pub fn claim_stalled_confirmation_bounty(
+			origin,
+			proof: MessagesDeliveryProofOf<T, I>,
+			relayers_state: UnrewardedRelayersState,
+		) -> DispatchResultWithPostInfo {
+			ensure_not_halted::<T, I>()?;
+
+			let declared_weight = receive_messages_delivery_proof_weight::<T, I>(&proof, &relayers_state)
+				.saturating_add(T::DbWeight::get().reads_writes(1, 1));
+
+			let claimant = ensure_signed(origin)?;
+			let (lane_id, lane_data) = verify_messages_delivery_proof::<T, I>(proof, &relayers_state)?;
+
+			// the lane is stalled if the limit is reached and there are some messages that are delivered,
+			// but not yet confirmed
+			let is_limit_reached = lane_data.relayers.len() as MessageNonce
+				>= T::BridgedMaxUnrewardedRelayerEntriesAtInboundLane::get();
+			let has_unconfirmed_messages = lane_data.last_delivered_nonce()
+				> OutboundLanes::<I>::get(&lane_id).latest_received_nonce;
+			ensure!(is_limit_reached && has_unconfirmed_messages, Error::<T, I>::LaneIsNotStalled);
+
+			let current_block = frame_system::Pallet::<T>::block_number();
+			let limit_reached_at = match UnrewardedRelayersLimitReachedAt::<T, I>::get(&lane_id) {
+				Some(limit_reached_at) => limit_reached_at,
+				None => {
+					UnrewardedRelayersLimitReachedAt::<T, I>::insert(&lane_id, current_block);
+					Self::deposit_event(RawEvent::StalledLaneReported(lane_id));
 
-					fail!(Error::<T, I>::TryingToConfirmMoreMessagesThanExpected);
-				},
-				error => {
 					log::trace!(
 						target: "runtime::bridge-messages",
-						"Messages delivery proof contains invalid unrewarded relayers vec: {:?}",
-						error,
+						"Lane {:?} has been reported as stalled at block {:?}",
+						lane_id,
+						current_block,
 					);
 
-					fail!(Error::<T, I>::InvalidUnrewardedRelayers);
+					return Ok(PostDispatchInfo {
+						actual_weight: Some(declared_weight),
+						pays_fee: Pays::Yes,
+					});
 				},
 			};
+			ensure!(
+				current_block >= Saturating::saturating_add(limit_reached_at, T::StalledConfirmationPeriod::get()),
+				Error::<T, I>::StalledConfirmationBountyIsNotYetAvailable
+			);
 
-			if let Some(confirmed_messages) = confirmed_messages {
-				// handle messages delivery confirmation
-				let preliminary_callback_overhead = relayers_state.total_messages.saturating_mul(
-					single_message_callback_overhead
-				);
-				let actual_callback_weight = T::OnDeliveryConfirmed::on_messages_delivered(
-					&lane_id,
-					&confirmed_messages,
-				);
-				match preliminary_callback_overhead.checked_sub(actual_callback_weight) {
-					Some(difference) if difference == 0 => (),
-					Some(difference) => {
-						log::trace!(
-							target: "runtime::bridge-messages",
-							"Messages delivery callback has returned unspent weight to refund the submitter: \
-							{} - {} = {}",
-							preliminary_callback_overhead,
-							actual_callback_weight,
-							difference,
-						);
-						actual_weight -= difference;
-					},
-					None => {
-						debug_assert!(false, "The delivery confirmation callback is wrong");
-						log::trace!(
-							target: "runtime::bridge-messages",
-							"Messages delivery callback has returned more weight that it may spent: \
-							{} vs {}",
-							preliminary_callback_overhead,
-							actual_callback_weight,
-						);
-					}
-				}
+			let (actual_weight, bounty) = confirm_messages_delivery::<T, I>(
+				&claimant,
+				lane_id,
+				lane_data,
+				&relayers_state,
+				declared_weight,
+				Some(T::StalledConfirmationBountyPerMessage::get()),
+			)?;
+			Self::deposit_event(RawEvent::StalledConfirmationBountyPaid(lane_id, claimant, bounty));
+
+			Ok(PostDispatchInfo {
+				actual_weight: Some(actual_weight),
+				pays_fee: Pays::Yes,
+			})
+		}
 
-				// emit 'delivered' event
-				let received_range = confirmed_messages.begin..=confirmed_messages.end;
-				Self::deposit_event(RawEvent::MessagesDelivered(lane_id, confirmed_messages));
-
-				// remember to reward relayers that have delivered messages
-				// this loop is bounded by `T::MaxUnrewardedRelayerEntriesAtInboundLane` on the bridged chain
-				for entry in lane_data.relayers {
-					let nonce_begin = sp_std::cmp::max(entry.messages.begin, *received_range.start());
-					let nonce_end = sp_std::cmp::min(entry.messages.end, *received_range.end());
-
-					// loop won't proceed if current entry is ahead of received range (begin > end).
-					// this loop is bound by `T::MaxUnconfirmedMessagesAtInboundLane` on the bridged chain
-					let mut relayer_reward = relayers_rewards.entry(entry.relayer).or_default();
-					for nonce in nonce_begin..nonce_end + 1 {
-						let message_data = OutboundMessages::<T, I>::get(MessageKey {
-							lane_id,
-							nonce,
-						}).expect("message was just confirmed; we never prune unconfirmed messages; qed");
-						relayer_reward.reward = relayer_reward.reward.saturating_add(&message_data.fee);
-						relayer_reward.messages += 1;
+		/// Receive messages proof from bridged chain, using unsigned transaction.
+		///
+		/// This call allows relayers that have no funds at this chain to deliver messages. The reward
+		/// is still paid at the bridged chain to the `relayer_id_at_bridged_chain` account. The dispatch
+		/// fee of messages that are paying it at this chain is paid to the relayers fund account.
+		///
+		/// The `relayer_signature` is the signature of the `unsigned_delivery_signature_payload` of the
+		/// proof, made by the `relayer_id_at_bridged_chain` account. It is verified when the transaction
+		/// is validated, so nobody may copy the transaction and replace the reward account.
+		///
+		/// The transaction is only accepted to the pool if it delivers at least one new message and
+		/// all new messages may be accepted by the lane. Its priority depends on the number of new
+		/// messages in the proof. If the call doesn't deliver any new messages, it fails and all its
+		/// changes are reverted.
+		#[weight = receive_messages_proof_weight::<T, I>(proof, *messages_count, *dispatch_weight)]
+		pub fn receive_messages_proof_unsigned(
+			origin,
+			relayer_id_at_bridged_chain: T::InboundRelayer,
+			_relayer_signature: T::InboundRelayerSignature,
+			proof: MessagesProofOf<T, I>,
+			messages_count: u32,
+			dispatch_weight: Weight,
+		) -> DispatchResultWithPostInfo {
+			ensure_not_halted::<T, I>()?;
+			ensure_none(origin)?;
+			ensure_relayer_allowed::<T, I>(None)?;
+
+			let relayer_fund_account_id = Self::relayer_fund_account_id();
+			let actual_weight = with_transaction(|| {
+				match receive_messages::<T, I>(
+					&relayer_id_at_bridged_chain,
+					&relayer_fund_account_id,
+					proof,
+					messages_count,
+					dispatch_weight,
+				) {
+					Ok((actual_weight, valid_messages)) if valid_messages != 0 => {
+						TransactionOutcome::Commit(Ok(actual_weight))
 					}
+					Ok(_) => TransactionOutcome::Rollback(Err(Error::<T, I>::NoNewMessagesDelivered.into())),
+					Err(err) => TransactionOutcome::Rollback(Err(err)),
 				}
+			})?;
+
+			Ok(PostDispatchInfo {
+				actual_weight: Some(actual_weight),
+				pays_fee: Pays::No,
+			})
+		}
+
+		/// Send message with given priority over lane.
+		///
+		/// Priority doesn't change order of messages within the lane. It is only used by relayers
+		/// to select lanes that need to be served first.
+		#[weight = send_message_weight::<T, I>(payload)]
+		pub fn send_message_with_priority(
+			origin,
+			lane_id: LaneId,
+			payload: T::OutboundPayload,
+			delivery_and_dispatch_fee: T::OutboundMessageFee,
+			priority: MessagePriority,
+		) -> DispatchResultWithPostInfo {
+			let submitter = origin.into().map_err(|_| BadOrigin)?;
+			send_message::<T, I>(submitter, lane_id, payload, delivery_and_dispatch_fee, priority)
+		}
+
+		/// Set accounts that are allowed to deliver messages to this chain.
+		///
+		/// Passing `None` removes the restriction, so anyone may deliver messages.
+		///
+		/// May only be called by root.
+		#[weight = (T::DbWeight::get().writes(1), DispatchClass::Operational)]
+		pub fn set_allowed_relayers(origin, relayers: Option<Vec<T::AccountId>>) {
+			ensure_root(origin)?;
+			match relayers {
+				Some(relayers) => {
+					let relayers: BoundedVec<_, _> = relayers
+						.try_into()
+						.map_err(|_| Error::<T, I>::TooManyAllowedRelayers)?;
+					log::info!(
+						target: "runtime::bridge-messages",
+						"Restricting messages delivery to {} relayers.",
+						relayers.len(),
+					);
+					AllowedRelayers::<T, I>::put(relayers);
+				},
+				None => {
+					AllowedRelayers::<T, I>::kill();
+					log::info!(target: "runtime::bridge-messages", "Allowing anyone to deliver messages.");
+				},
 			}
+		}
 
-			// if some new messages have been confirmed, reward relayers
-			if !relayers_rewards.is_empty() {
-				let relayer_fund_account = Self::relayer_fund_account_id();
-				<T as Config<I>>::MessageDeliveryAndDispatchPayment::pay_relayers_rewards(
-					&confirmation_relayer,
-					relayers_rewards,
-					&relayer_fund_account,
-				);
+		/// Set new fee for the message.
+		///
+		/// If the new fee is larger than the current one, the difference is paid by the caller.
+		/// Decreasing the fee is only allowed to the pallet owner or root. The difference is then
+		/// refunded from the relayers fund to the message submitter (or to the account that is paying
+		/// for root messages). Fees of messages with unknown submitter can't be decreased.
+		#[weight = update_message_fee_weight::<T, I>()]
+		pub fn update_message_fee(
+			origin,
+			lane_id: LaneId,
+			nonce: MessageNonce,
+			new_fee: T::OutboundMessageFee,
+		) -> DispatchResultWithPostInfo {
+			ensure_not_halted::<T, I>()?;
+			// same checks as in the `increase_message_fee`
+			let lane = outbound_lane::<T, I>(lane_id);
+			ensure!(nonce > lane.data().latest_received_nonce, Error::<T, I>::MessageIsAlreadyDelivered);
+			ensure!(nonce <= lane.data().latest_generated_nonce, Error::<T, I>::MessageIsNotYetSent);
+
+			let message_key = MessageKey { lane_id, nonce };
+			let message_data = OutboundMessages::<T, I>::get(&message_key)
+				.expect("the message is sent and not yet delivered; so it is in the storage; qed");
+			let current_fee = message_data.fee;
+			if new_fee > current_fee {
+				let additional_fee = new_fee.saturating_sub(&current_fee);
+				let submitter = origin.into().map_err(|_| BadOrigin)?;
+				T::MessageDeliveryAndDispatchPayment::pay_delivery_and_dispatch_fee(
+					&submitter,
+					&additional_fee,
+					&Self::relayer_fund_account_id(),
+				).map_err(|err| {
+					log::trace!(
+						target: "runtime::bridge-messages",
+						"Submitter {:?} can't pay additional fee {:?} for the message {:?}/{:?}: {:?}",
+						submitter,
+						additional_fee,
+						lane_id,
+						nonce,
+						err,
+					);
+
+					Error::<T, I>::FailedToWithdrawMessageFee
+				})?;
+				LanesStatistics::<T, I>::mutate(lane_id, |statistics| {
+					statistics.total_fees_collected = statistics.total_fees_collected.saturating_add(&additional_fee);
+				});
+			} else if new_fee < current_fee {
+				ensure_owner_or_root::<T, I>(origin)?;
+
+				let refund = current_fee.saturating_sub(&new_fee);
+				let refund_to = OutboundMessageSubmitters::<T, I>::get(&message_key)
+					.ok_or(Error::<T, I>::MessageSubmitterIsUnknown)?;
+				T::MessageDeliveryAndDispatchPayment::refund_delivery_and_dispatch_fee(
+					&refund_to,
+					&refund,
+					&Self::relayer_fund_account_id(),
+				).map_err(|err| {
+					log::trace!(
+						target: "runtime::bridge-messages",
+						"Failed to refund {:?} to {:?} for the message {:?}/{:?}: {:?}",
+						refund,
+						refund_to,
+						lane_id,
+						nonce,
+						err,
+					);
+
+					Error::<T, I>::FailedToRefundMessageFee
+				})?;
+				LanesStatistics::<T, I>::mutate(lane_id, |statistics| {
+					statistics.total_fees_collected = statistics.total_fees_collected.saturating_sub(&refund);
+				});
 			}
 
-			log::trace!(
-				target: "runtime::bridge-messages",
-				"Received messages delivery proof up to (and including) {} at lane {:?}",
-				last_delivered_nonce,
-				lane_id,
-			);
+			// and finally update fee in the storage
+			let message_size = message_data.payload.len();
+			OutboundMessages::<T, I>::insert(message_key, MessageData { fee: new_fee, ..message_data });
+
+			// compute actual dispatch weight that depends on the stored message size
+			let mut actual_weight = sp_std::cmp::min(
+				T::WeightInfo::maximal_increase_message_fee(),
+				T::WeightInfo::increase_message_fee(message_size as _),
+			)
+			.saturating_add(lane_statistics_update_weight::<T, I>());
+			if new_fee < current_fee {
+				actual_weight = actual_weight.saturating_add(fee_decrease_weight::<T, I>());
+			}
 
 			Ok(PostDispatchInfo {
 				actual_weight: Some(actual_weight),
 				pays_fee: Pays::Yes,
 			})
 		}
+
+		/// Set or reset expected hash of the bridged chain genesis block.
+		///
+		/// May only be called either by root, or by `PalletOwner`.
+		#[weight = (T::DbWeight::get().writes(1), DispatchClass::Operational)]
+		pub fn set_bridged_chain_genesis_hash(origin, genesis_hash: Option<H256>) {
+			ensure_owner_or_root::<T, I>(origin)?;
+			match genesis_hash {
+				Some(genesis_hash) => {
+					BridgedChainGenesisHash::<I>::put(genesis_hash);
+					log::info!(
+						target: "runtime::bridge-messages",
+						"Setting bridged chain genesis hash to: {:?}",
+						genesis_hash,
+					);
+				},
+				None => {
+					BridgedChainGenesisHash::<I>::kill();
+					log::info!(target: "runtime::bridge-messages", "Removed bridged chain genesis hash.");
+				},
+			}
+		}
+
+		/// Accept the pallet ownership, proposed by the `propose_owner` call.
+		///
+		/// May only be called by the proposed owner.
+		#[weight = (T::WeightInfo::accept_owner(), DispatchClass::Operational)]
+		pub fn accept_owner(origin) {
+			let new_owner = ensure_signed(origin)?;
+			ensure!(
+				PendingPalletOwner::<T, I>::get().as_ref() == Some(&new_owner),
+				Error::<T, I>::NotPendingOwner,
+			);
+
+			PalletOwner::<T, I>::put(&new_owner);
+			PendingPalletOwner::<T, I>::kill();
+			log::info!(target: "runtime::bridge-messages", "Setting pallet Owner to: {:?}", new_owner);
+			Self::deposit_event(RawEvent::OwnerChanged(Some(new_owner)));
+		}
 	}
 }
 
@@ -813,6 +934,11 @@ impl<T: Config<I>, I: Instance> Pallet<T, I> {
 	}
 
 	/// Get state of unrewarded relayers set.
+	///
+	/// Relayer entries that have already been confirmed by the bridged chain, but are not yet pruned
+	/// (at most `MaxConfirmedRelayerEntriesToPrune` entries are pruned at once), are included here.
+	/// The state must match the inbound lane data in the storage proof and the bridged chain ignores
+	/// messages that it has already confirmed, so relayers are never rewarded twice.
 	pub fn inbound_unrewarded_relayers_state(lane: bp_messages::LaneId) -> bp_messages::UnrewardedRelayersState {
 		let relayers = InboundLanes::<T, I>::get(&lane).relayers;
 		bp_messages::UnrewardedRelayersState {
@@ -825,6 +951,12 @@ impl<T: Config<I>, I: Instance> Pallet<T, I> {
 		}
 	}
 
+	/// Returns payload that is signed by the relayer account at the bridged chain when messages are
+	/// delivered using unsigned transaction.
+	pub fn unsigned_delivery_signature_payload(proof: &MessagesProofOf<T, I>) -> Vec<u8> {
+		(T::BridgedChainId::get(), BlakeTwo256::hash_of(proof)).encode()
+	}
+
 	/// AccountId of the shared relayer fund account.
 	///
 	/// This account is passed to `MessageDeliveryAndDispatchPayment` trait, and depending
@@ -835,16 +967,87 @@ impl<T: Config<I>, I: Instance> Pallet<T, I> {
 		let encoded_id = bp_runtime::derive_relayer_fund_account_id(bp_runtime::NO_INSTANCE_ID);
 		T::AccountIdConverter::convert(encoded_id)
 	}
-}
 
-/// Getting storage keys for messages and lanes states. These keys are normally used when building
-/// messages and lanes states proofs.
-///
-/// Keep in mind that all functions in this module are **NOT** using passed `T` argument, so any
-/// runtime can be passed. E.g. if you're verifying proof from Runtime1 in Runtime2, you only have
-/// access to Runtime2 and you may pass it to the functions, where required. This is because our
-/// maps are not using any Runtime-specific data in the keys.
-///
+	/// Verify that data of all inbound and outbound lanes is consistent.
+	///
+	/// This function reads all lanes from the storage, so it must only be used in tests and
+	/// `try-runtime` checks.
+	pub fn check_lanes_invariants() -> Result<(), &'static str> {
+		for (lane_id, lane_data) in OutboundLanes::<I>::iter() {
+			lane_data.check_invariants().map_err(|error| {
+				log::error!(
+					target: "runtime::bridge-messages",
+					"Outbound lane {:?} is inconsistent: {}",
+					lane_id,
+					error,
+				);
+				error
+			})?;
+		}
+		for (lane_id, lane_data) in InboundLanes::<T, I>::iter() {
+			lane_data.check_invariants().map_err(|error| {
+				log::error!(
+					target: "runtime::bridge-messages",
+					"Inbound lane {:?} is inconsistent: {}",
+					lane_id,
+					error,
+				);
+				error
+			})?;
+		}
+
+		Ok(())
+	}
+}
+
+/// Runtime upgrade hook that verifies consistency of all lanes after the upgrade.
+///
+/// It does nothing during the upgrade itself. Runtimes should put it after all migrations of the
+/// pallet, so that `try-runtime` detects lanes that have been corrupted by these migrations.
+pub struct CheckLanesInvariants<T, I = DefaultInstance>(PhantomData<(T, I)>);
+
+impl<T: Config<I>, I: Instance> frame_support::traits::OnRuntimeUpgrade for CheckLanesInvariants<T, I> {
+	fn on_runtime_upgrade() -> Weight {
+		0
+	}
+
+	#[cfg(feature = "try-runtime")]
+	fn post_upgrade() -> Result<(), &'static str> {
+		Pallet::<T, I>::check_lanes_invariants()
+	}
+}
+
+impl<T: Config<I>, I: Instance> frame_support::unsigned::ValidateUnsigned for Pallet<T, I> {
+	type Call = Call<T, I>;
+
+	fn validate_unsigned(_source: TransactionSource, call: &Self::Call) -> TransactionValidity {
+		match *call {
+			Self::Call::receive_messages_proof_unsigned(
+				ref relayer_id_at_bridged_chain,
+				ref relayer_signature,
+				ref proof,
+				messages_count,
+				dispatch_weight,
+			) => validate_unsigned_messages_proof::<T, I>(
+				relayer_id_at_bridged_chain,
+				relayer_signature,
+				proof,
+				messages_count,
+				dispatch_weight,
+			),
+			_ => InvalidTransaction::Call.into(),
+		}
+	}
+}
+
+/// Getting storage keys for messages and lanes states. These keys are normally used when building
+/// messages and lanes states proofs.
+///
+/// Keep in mind that all functions in this module are **NOT** using passed `T` argument, so any
+/// runtime can be passed. E.g. if you're verifying proof from Runtime1 in Runtime2, you only have
+/// access to Runtime2 and you may pass it to the functions, where required. This is because our
+/// maps are not using any Runtime-specific data in the keys.
+///
 /// On the other side, passing correct instance is required. So if proof has been crafted by the
 /// Instance1, you should verify it using Instance1. This is inconvenient if you're using different
 /// instances on different sides of the bridge. I.e. in Runtime1 it is Instance2, but on Runtime2
@@ -855,6 +1058,16 @@ pub mod storage_keys {
 	use frame_support::{traits::Instance, StorageHasher};
 	use sp_core::storage::StorageKey;
 
+	/// Storage key of the pallet operating mode in the runtime storage.
+	pub fn operating_mode_key<I: Instance>() -> StorageKey {
+		bp_runtime::storage_value_key(I::PREFIX, "PalletOperatingMode")
+	}
+
+	/// Storage key of the expected bridged chain genesis hash in the runtime storage.
+	pub fn bridged_chain_genesis_hash_key<I: Instance>() -> StorageKey {
+		bp_runtime::storage_value_key(I::PREFIX, "BridgedChainGenesisHash")
+	}
+
 	/// Storage key of the outbound message in the runtime storage.
 	pub fn message_key<I: Instance>(lane: &LaneId, nonce: MessageNonce) -> StorageKey {
 		storage_map_final_key::<I>("OutboundMessages", &MessageKey { lane_id: *lane, nonce }.encode())
@@ -862,12 +1075,24 @@ pub mod storage_keys {
 
 	/// Storage key of the outbound message lane state in the runtime storage.
 	pub fn outbound_lane_data_key<I: Instance>(lane: &LaneId) -> StorageKey {
-		storage_map_final_key::<I>("OutboundLanes", lane)
+		storage_map_final_key::<I>("OutboundLanes", &lane.encode())
 	}
 
 	/// Storage key of the inbound message lane state in the runtime storage.
 	pub fn inbound_lane_data_key<I: Instance>(lane: &LaneId) -> StorageKey {
-		storage_map_final_key::<I>("InboundLanes", lane)
+		storage_map_final_key::<I>("InboundLanes", &lane.encode())
+	}
+
+	/// Storage key of the inbound lane unrewarded relayer entry, starting at given nonce, in the runtime storage.
+	pub fn inbound_lane_relayer_entry_key<I: Instance>(lane: &LaneId, begin: MessageNonce) -> StorageKey {
+		storage_map_final_key::<I>(
+			"InboundLaneRelayers",
+			&MessageKey {
+				lane_id: *lane,
+				nonce: begin,
+			}
+			.encode(),
+		)
 	}
 
 	/// This is a copypaste of the `frame_support::storage::generator::StorageMap::storage_map_final_key`.
@@ -914,6 +1139,649 @@ fn ensure_not_halted<T: Config<I>, I: Instance>() -> Result<(), Error<T, I>> {
 	}
 }
 
+/// Ensure that the relayer is allowed to deliver messages to this chain.
+///
+/// The `None` relayer stands for the unsigned delivery transaction, which is only allowed when
+/// the `AllowedRelayers` set is not configured.
+fn ensure_relayer_allowed<T: Config<I>, I: Instance>(relayer: Option<&T::AccountId>) -> Result<(), Error<T, I>> {
+	let is_allowed = match (AllowedRelayers::<T, I>::get(), relayer) {
+		(None, _) => true,
+		(Some(allowed_relayers), Some(relayer)) => allowed_relayers.contains(relayer),
+		(Some(_), None) => false,
+	};
+	ensure!(is_allowed, Error::<T, I>::RelayerIsNotAllowed);
+	Ok(())
+}
+
+/// Send message over lane.
+fn send_message<T: Config<I>, I: Instance>(
+	submitter: Sender<T::AccountId>,
+	lane_id: LaneId,
+	payload: T::OutboundPayload,
+	delivery_and_dispatch_fee: T::OutboundMessageFee,
+	priority: MessagePriority,
+) -> DispatchResultWithPostInfo {
+	ensure_normal_operating_mode::<T, I>()?;
+
+	// initially, actual (post-dispatch) weight is equal to pre-dispatch weight
+	let mut actual_weight = send_message_weight::<T, I>(&payload);
+
+	// let's first check if message can be delivered to target chain
+	T::TargetHeaderChain::verify_message(&payload).map_err(|err| {
+		log::trace!(
+			target: "runtime::bridge-messages",
+			"Message to lane {:?} is rejected by target chain: {:?}",
+			lane_id,
+			err,
+		);
+
+		Error::<T, I>::MessageRejectedByChainVerifier
+	})?;
+
+	// now let's enforce any additional lane rules
+	let mut lane = outbound_lane::<T, I>(lane_id);
+	T::LaneMessageVerifier::verify_message(&submitter, &delivery_and_dispatch_fee, &lane_id, &lane.data(), &payload)
+		.map_err(|err| {
+			log::trace!(
+				target: "runtime::bridge-messages",
+				"Message to lane {:?} is rejected by lane verifier: {:?}",
+				lane_id,
+				err,
+			);
+
+			Error::<T, I>::MessageRejectedByLaneVerifier
+		})?;
+
+	// let's withdraw delivery and dispatch fee from submitter
+	T::MessageDeliveryAndDispatchPayment::pay_delivery_and_dispatch_fee(
+		&submitter,
+		&delivery_and_dispatch_fee,
+		&Pallet::<T, I>::relayer_fund_account_id(),
+	)
+	.map_err(|err| {
+		log::trace!(
+			target: "runtime::bridge-messages",
+			"Message to lane {:?} is rejected because submitter {:?} is unable to pay fee {:?}: {:?}",
+			lane_id,
+			submitter,
+			delivery_and_dispatch_fee,
+			err,
+		);
+
+		Error::<T, I>::FailedToWithdrawMessageFee
+	})?;
+
+	// finally, save message in outbound storage and emit event
+	let encoded_payload = payload.encode();
+	let encoded_payload_len = encoded_payload.len();
+	let nonce = lane.send_message(MessageData {
+		payload: encoded_payload,
+		fee: delivery_and_dispatch_fee.clone(),
+		priority,
+	});
+	OutboundMessageSubmitters::<T, I>::insert(MessageKey { lane_id, nonce }, submitter);
+	LanesStatistics::<T, I>::mutate(lane_id, |statistics| {
+		statistics.messages_sent = statistics.messages_sent.saturating_add(1);
+		statistics.total_payload_size = statistics.total_payload_size.saturating_add(encoded_payload_len as u64);
+		statistics.total_fees_collected = statistics
+			.total_fees_collected
+			.saturating_add(&delivery_and_dispatch_fee);
+	});
+
+	// message sender pays for pruning at most `MaxMessagesToPruneAtOnce` messages
+	// the cost of pruning every message is roughly two db writes (message and its submitter removal)
+	// => lets refund sender if less than `MaxMessagesToPruneAtOnce` messages pruned
+	let max_messages_to_prune = T::MaxMessagesToPruneAtOnce::get();
+	let pruned_messages = lane.prune_messages(max_messages_to_prune);
+	if let Some(extra_messages) = max_messages_to_prune.checked_sub(pruned_messages) {
+		actual_weight = actual_weight.saturating_sub(T::DbWeight::get().writes(extra_messages.saturating_mul(2)));
+	}
+
+	log::trace!(
+		target: "runtime::bridge-messages",
+		"Accepted message {} to lane {:?}. Message size: {:?}. Priority: {}",
+		nonce,
+		lane_id,
+		encoded_payload_len,
+		priority,
+	);
+
+	Pallet::<T, I>::deposit_event(RawEvent::MessageAccepted(lane_id, nonce));
+
+	Ok(PostDispatchInfo {
+		actual_weight: Some(actual_weight),
+		pays_fee: Pays::Yes,
+	})
+}
+
+/// Verify messages proof, dispatch messages and (optionally) update lane(s) state(s).
+///
+/// Returns actual weight of the delivery call and the number of delivered messages.
+fn receive_messages<T: Config<I>, I: Instance>(
+	relayer_id_at_bridged_chain: &T::InboundRelayer,
+	relayer_id_at_this_chain: &T::AccountId,
+	proof: MessagesProofOf<T, I>,
+	messages_count: u32,
+	dispatch_weight: Weight,
+) -> Result<(Weight, MessageNonce), DispatchError> {
+	// reject transactions that are declaring too many messages
+	ensure!(
+		MessageNonce::from(messages_count) <= T::MaxUnconfirmedMessagesAtInboundLane::get(),
+		Error::<T, I>::TooManyMessagesInTheProof
+	);
+
+	// why do we need to know the weight of this (`receive_messages_proof`) call? Because
+	// we may want to return some funds for not-dispatching (or partially dispatching) some
+	// messages to the call origin (relayer). And this is done by returning actual weight
+	// from the call. But we only know dispatch weight of every messages. So to refund relayer
+	// because we have not dispatched Message, we need to:
+	//
+	// ActualWeight = DeclaredWeight - Message.DispatchWeight
+	//
+	// The DeclaredWeight is exactly what's computed here. Unfortunately it is impossible
+	// to get pre-computed value (and it has been already computed by the executive).
+	let declared_weight = receive_messages_proof_weight::<T, I>(&proof, messages_count, dispatch_weight);
+	let declared_proof_size = proof.size_hint();
+	let mut actual_weight = declared_weight;
+
+	// verify messages proof && convert proof into messages
+	let (messages, proof_stats) = verify_and_decode_messages_proof::<
+		T::SourceHeaderChain,
+		T::InboundMessageFee,
+		T::InboundPayload,
+	>(proof, messages_count)
+	.map_err(|err| {
+		log::trace!(
+			target: "runtime::bridge-messages",
+			"Rejecting invalid messages proof: {:?}",
+			err,
+		);
+
+		Error::<T, I>::InvalidMessagesProof
+	})?;
+
+	// the proof size overhead is charged for all proof bytes, but only trie nodes are actually processed
+	if let Some(proof_stats) = proof_stats {
+		actual_weight = actual_weight.saturating_sub(T::WeightInfo::receive_messages_proof_storage_proof_refund(
+			declared_proof_size,
+			messages_count,
+			&proof_stats,
+		));
+	}
+
+	// dispatch messages and (optionally) update lane(s) state(s)
+	let aggregate_events = MessageNonce::from(messages_count) > T::MaxMessageEventsInDeliveryTransaction::get();
+	let mut total_messages = 0;
+	let mut valid_messages: MessageNonce = 0;
+	let mut dispatch_weight_left = dispatch_weight;
+	let mut messages_dispatch_weight: Weight = 0;
+	for (lane_id, lane_data) in messages {
+		let mut lane = inbound_lane::<T, I>(lane_id);
+
+		if let Some(lane_state) = lane_data.lane_state {
+			let updated_latest_confirmed_nonce = lane.receive_state_update(lane_state);
+			if let Some(updated_latest_confirmed_nonce) = updated_latest_confirmed_nonce {
+				log::trace!(
+					target: "runtime::bridge-messages",
+					"Received lane {:?} state update: latest_confirmed_nonce={}",
+					lane_id,
+					updated_latest_confirmed_nonce,
+				);
+
+				if lane.has_confirmed_relayers() {
+					InboundLanesToPrune::<I>::insert(lane_id, ());
+				}
+			}
+		}
+
+		let mut received_messages: Option<(MessageNonce, MessageNonce)> = None;
+		for message in lane_data.messages {
+			debug_assert_eq!(message.key.lane_id, lane_id);
+
+			// ensure that relayer has declared enough weight for dispatching next message on
+			// this lane. We can't dispatch lane messages out-of-order, so if declared weight
+			// is not enough, let's move to next lane
+			let dispatch_weight = T::MessageDispatch::dispatch_weight(&message);
+			if dispatch_weight > dispatch_weight_left {
+				log::trace!(
+					target: "runtime::bridge-messages",
+					"Cannot dispatch any more messages on lane {:?}. Weight: declared={}, left={}",
+					lane_id,
+					dispatch_weight,
+					dispatch_weight_left,
+				);
+				if !aggregate_events {
+					Pallet::<T, I>::deposit_event(RawEvent::MessageRejected(
+						lane_id,
+						message.key.nonce,
+						MessageRejectionReason::DispatchWeightTooLow,
+					));
+				}
+				break;
+			}
+			total_messages += 1;
+			messages_dispatch_weight = messages_dispatch_weight.saturating_add(dispatch_weight);
+
+			let message_nonce = message.key.nonce;
+			let receival_result = lane.receive_message::<T::MessageDispatch, T::AccountId>(
+				relayer_id_at_bridged_chain,
+				relayer_id_at_this_chain,
+				message_nonce,
+				message.data,
+			);
+
+			// note that we're returning unspent weight to relayer even if message has been
+			// rejected by the lane. This allows relayers to submit spam transactions with
+			// e.g. the same set of already delivered messages over and over again, without
+			// losing funds for messages dispatch. But keep in mind that relayer pays base
+			// delivery transaction cost anyway. And base cost covers everything except
+			// dispatch, so we have a balance here.
+			let (unspent_weight, refund_pay_dispatch_fee, rejection_reason) = match receival_result {
+				ReceivalResult::Dispatched(dispatch_result) => {
+					valid_messages += 1;
+					received_messages = Some((
+						received_messages.map(|(begin, _)| begin).unwrap_or(message_nonce),
+						message_nonce,
+					));
+					(
+						dispatch_result.unspent_weight,
+						!dispatch_result.dispatch_fee_paid_during_dispatch,
+						if dispatch_result.dispatch_result {
+							None
+						} else {
+							Some(MessageRejectionReason::DispatchFailed)
+						},
+					)
+				}
+				ReceivalResult::InvalidNonce if message_nonce <= lane.last_delivered_nonce() => {
+					(dispatch_weight, true, Some(MessageRejectionReason::AlreadyDelivered))
+				}
+				ReceivalResult::InvalidNonce => (dispatch_weight, true, Some(MessageRejectionReason::UnexpectedNonce)),
+				ReceivalResult::TooManyUnrewardedRelayers => (
+					dispatch_weight,
+					true,
+					Some(MessageRejectionReason::TooManyUnrewardedRelayers),
+				),
+				ReceivalResult::TooManyUnconfirmedMessages => (
+					dispatch_weight,
+					true,
+					Some(MessageRejectionReason::TooManyUnconfirmedMessages),
+				),
+			};
+			if let Some(rejection_reason) = rejection_reason.filter(|_| !aggregate_events) {
+				Pallet::<T, I>::deposit_event(RawEvent::MessageRejected(lane_id, message_nonce, rejection_reason));
+			}
+
+			let unspent_weight = sp_std::cmp::min(unspent_weight, dispatch_weight);
+			dispatch_weight_left -= dispatch_weight - unspent_weight;
+			actual_weight = actual_weight.saturating_sub(unspent_weight).saturating_sub(
+				// delivery call weight formula assumes that the fee is paid at
+				// this (target) chain. If the message is prepaid at the source
+				// chain, let's refund relayer with this extra cost.
+				if refund_pay_dispatch_fee {
+					T::WeightInfo::pay_inbound_dispatch_fee_overhead()
+				} else {
+					0
+				},
+			);
+		}
+
+		if let Some((begin, end)) = received_messages {
+			LanesStatistics::<T, I>::mutate(lane_id, |statistics| {
+				statistics.messages_delivered = statistics
+					.messages_delivered
+					.saturating_add(end.saturating_sub(begin).saturating_add(1));
+			});
+		}
+		if let Some((begin, end)) = received_messages.filter(|_| aggregate_events) {
+			Pallet::<T, I>::deposit_event(RawEvent::MessagesReceived(lane_id, begin, end));
+		}
+	}
+
+	// relayer may declare dispatch weight that is larger than the dispatch weight of all delivered
+	// messages. This weight is never spent, so let's refund it
+	actual_weight = actual_weight.saturating_sub(dispatch_weight.saturating_sub(messages_dispatch_weight));
+
+	log::trace!(
+		target: "runtime::bridge-messages",
+		"Received messages: total={}, valid={}. Weight used: {}/{}",
+		total_messages,
+		valid_messages,
+		actual_weight,
+		declared_weight,
+	);
+
+	Ok((actual_weight, valid_messages))
+}
+
+/// Validate unsigned messages delivery transaction.
+///
+/// The transaction is valid if it is signed by the relayer, the proof is valid and it brings at least
+/// one message that has not been delivered yet. All new messages must be accepted by their lanes and
+/// the declared dispatch weight must cover dispatch of all new messages. Transactions that are
+/// delivering the same messages are conflicting with each other and the transaction that delivers
+/// more new messages is preferred.
+fn validate_unsigned_messages_proof<T: Config<I>, I: Instance>(
+	relayer_id_at_bridged_chain: &T::InboundRelayer,
+	relayer_signature: &T::InboundRelayerSignature,
+	proof: &MessagesProofOf<T, I>,
+	messages_count: u32,
+	dispatch_weight: Weight,
+) -> TransactionValidity {
+	if PalletOperatingMode::<I>::get() == OperatingMode::Halted {
+		return InvalidTransaction::Call.into();
+	}
+	if ensure_relayer_allowed::<T, I>(None).is_err() {
+		return InvalidTransaction::Call.into();
+	}
+	let signature_payload = Pallet::<T, I>::unsigned_delivery_signature_payload(proof);
+	if !relayer_signature.verify(&signature_payload[..], relayer_id_at_bridged_chain) {
+		return InvalidTransaction::BadProof.into();
+	}
+	if MessageNonce::from(messages_count) > T::MaxUnconfirmedMessagesAtInboundLane::get() {
+		return InvalidTransaction::ExhaustsResources.into();
+	}
+
+	let (messages, _) =
+		verify_and_decode_messages_proof::<T::SourceHeaderChain, T::InboundMessageFee, T::InboundPayload>(
+			proof.clone(),
+			messages_count,
+		)
+		.map_err(|err| {
+			log::trace!(
+				target: "runtime::bridge-messages",
+				"Rejecting unsigned transaction with invalid messages proof: {:?}",
+				err,
+			);
+
+			InvalidTransaction::BadProof
+		})?;
+
+	let mut transaction = ValidTransaction::with_tag_prefix("BridgeMessagesDelivery");
+	let mut new_messages: TransactionPriority = 0;
+	let mut dispatch_weight_left = dispatch_weight;
+	for (lane_id, lane_data) in messages {
+		let inbound_lane_data = InboundLanes::<T, I>::get(&lane_id);
+		let last_delivered_nonce = inbound_lane_data.last_delivered_nonce();
+		let mut last_new_nonce = last_delivered_nonce;
+		for message in &lane_data.messages {
+			// messages are dispatched in order, so declared weight must be enough to dispatch every
+			// message (even already delivered) after all previous new messages are dispatched
+			let message_dispatch_weight = T::MessageDispatch::dispatch_weight(message);
+			if message_dispatch_weight > dispatch_weight_left {
+				return InvalidTransaction::Call.into();
+			}
+			if message.key.nonce <= last_delivered_nonce {
+				continue;
+			}
+			if message.key.nonce != last_new_nonce + 1 {
+				return InvalidTransaction::Call.into();
+			}
+
+			dispatch_weight_left -= message_dispatch_weight;
+			last_new_nonce = message.key.nonce;
+		}
+
+		let lane_new_messages = last_new_nonce - last_delivered_nonce;
+		if lane_new_messages == 0 {
+			continue;
+		}
+
+		// the lane state update (if any) is applied before messages are received
+		let last_confirmed_nonce = match lane_data.lane_state {
+			Some(ref lane_state)
+				if lane_state.latest_received_nonce > inbound_lane_data.last_confirmed_nonce
+					&& lane_state.latest_received_nonce <= last_delivered_nonce =>
+			{
+				lane_state.latest_received_nonce
+			}
+			_ => inbound_lane_data.last_confirmed_nonce,
+		};
+		if last_new_nonce - last_confirmed_nonce > T::MaxUnconfirmedMessagesAtInboundLane::get() {
+			return InvalidTransaction::ExhaustsResources.into();
+		}
+		let mut unconfirmed_relayers = inbound_lane_data
+			.relayers
+			.iter()
+			.filter(|entry| entry.messages.end > last_confirmed_nonce);
+		let unconfirmed_relayer_entries = unconfirmed_relayers.clone().count() as MessageNonce;
+		let requires_new_entry = unconfirmed_relayers
+			.next_back()
+			.map(|entry| entry.relayer != *relayer_id_at_bridged_chain)
+			.unwrap_or(true);
+		if requires_new_entry && unconfirmed_relayer_entries >= T::MaxUnrewardedRelayerEntriesAtInboundLane::get() {
+			return InvalidTransaction::ExhaustsResources.into();
+		}
+
+		transaction = transaction.and_provides((lane_id, last_delivered_nonce + 1));
+		new_messages = new_messages.saturating_add(lane_new_messages as TransactionPriority);
+	}
+
+	if new_messages == 0 {
+		return InvalidTransaction::Stale.into();
+	}
+
+	transaction
+		.priority(T::UnsignedPriority::get().saturating_add(new_messages))
+		.longevity(T::UnsignedLongevity::get())
+		.propagate(true)
+		.build()
+}
+
+/// Verify messages delivery proof and the unrewarded relayers state, declared by the submitter.
+fn verify_messages_delivery_proof<T: Config<I>, I: Instance>(
+	proof: MessagesDeliveryProofOf<T, I>,
+	relayers_state: &UnrewardedRelayersState,
+) -> Result<(LaneId, InboundLaneData<T::AccountId>), Error<T, I>> {
+	let (lane_id, lane_data) = T::TargetHeaderChain::verify_messages_delivery_proof(proof).map_err(|err| {
+		log::trace!(
+			target: "runtime::bridge-messages",
+			"Rejecting invalid messages delivery proof: {:?}",
+			err,
+		);
+
+		Error::<T, I>::InvalidMessagesDeliveryProof
+	})?;
+
+	// verify that the relayer has declared correct `lane_data::relayers` state
+	// (we only care about total number of entries and messages, because this affects call weight)
+	ensure!(
+		total_unrewarded_messages(&lane_data.relayers).unwrap_or(MessageNonce::MAX) == relayers_state.total_messages
+			&& lane_data.relayers.len() as MessageNonce == relayers_state.unrewarded_relayer_entries,
+		Error::<T, I>::InvalidUnrewardedRelayersState
+	);
+
+	Ok((lane_id, lane_data))
+}
+
+/// Prune confirmed relayer entries of inbound lanes, listed in the `InboundLanesToPrune`.
+///
+/// Every lane is pruned by at most `MaxConfirmedRelayerEntriesToPrune` entries. Returns weight that
+/// has been spent.
+fn prune_inbound_lanes<T: Config<I>, I: Instance>(remaining_weight: Weight) -> Weight {
+	let db_weight = T::DbWeight::get();
+	// we're reading the `InboundLanesToPrune` entry and the lane itself. Then we're writing lane data,
+	// removing pruned entries from the `InboundLaneRelayers` and (possibly) removing lane from the
+	// `InboundLanesToPrune`
+	let max_entries_to_prune = T::MaxConfirmedRelayerEntriesToPrune::get();
+	let single_lane_weight = db_weight.reads_writes(2, max_entries_to_prune.saturating_add(2));
+	let mut spent_weight = db_weight.reads(1);
+	if spent_weight.saturating_add(single_lane_weight) > remaining_weight {
+		return 0;
+	}
+
+	let mut pruned_lanes = Vec::new();
+	for lane_id in InboundLanesToPrune::<I>::iter_keys() {
+		if spent_weight.saturating_add(single_lane_weight) > remaining_weight {
+			break;
+		}
+
+		let mut lane = inbound_lane::<T, I>(lane_id);
+		let pruned_entries = lane.prune_confirmed_relayers(max_entries_to_prune);
+		if !lane.has_confirmed_relayers() {
+			pruned_lanes.push(lane_id);
+		}
+		spent_weight = spent_weight.saturating_add(single_lane_weight);
+
+		log::trace!(
+			target: "runtime::bridge-messages",
+			"Pruned {} confirmed relayer entries at inbound lane {:?}",
+			pruned_entries,
+			lane_id,
+		);
+	}
+
+	for lane_id in pruned_lanes {
+		InboundLanesToPrune::<I>::remove(lane_id);
+	}
+
+	spent_weight
+}
+
+/// Mark messages as delivered and reward relayers that have delivered them.
+///
+/// If `bounty_per_message` is specified, it is deducted from the reward of every confirmed message
+/// and is paid to the `confirmation_relayer` instead. Returns actual weight of the call and the total
+/// bounty amount.
+fn confirm_messages_delivery<T: Config<I>, I: Instance>(
+	confirmation_relayer: &T::AccountId,
+	lane_id: LaneId,
+	lane_data: InboundLaneData<T::AccountId>,
+	relayers_state: &UnrewardedRelayersState,
+	declared_weight: Weight,
+	bounty_per_message: Option<T::OutboundMessageFee>,
+) -> Result<(Weight, T::OutboundMessageFee), DispatchError> {
+	let single_message_callback_overhead = T::WeightInfo::single_message_callback_overhead(T::DbWeight::get());
+	let mut actual_weight = declared_weight;
+
+	// mark messages as delivered
+	let mut lane = outbound_lane::<T, I>(lane_id);
+	let mut relayers_rewards: RelayersRewards<_, T::OutboundMessageFee> = RelayersRewards::new();
+	let mut total_bounty = T::OutboundMessageFee::zero();
+	let mut confirmed_messages_count = 0;
+	let last_delivered_nonce = lane_data.last_delivered_nonce();
+	let confirmed_messages =
+		match lane.confirm_delivery(relayers_state.total_messages, last_delivered_nonce, &lane_data.relayers) {
+			ReceivalConfirmationResult::ConfirmedMessages(confirmed_messages) => Some(confirmed_messages),
+			ReceivalConfirmationResult::NoNewConfirmations => None,
+			ReceivalConfirmationResult::TryingToConfirmMoreMessagesThanExpected(to_confirm_messages_count) => {
+				log::trace!(
+					target: "runtime::bridge-messages",
+					"Messages delivery proof contains too many messages to confirm: {} vs declared {}",
+					to_confirm_messages_count,
+					relayers_state.total_messages,
+				);
+
+				fail!(Error::<T, I>::TryingToConfirmMoreMessagesThanExpected);
+			}
+			error => {
+				log::trace!(
+					target: "runtime::bridge-messages",
+					"Messages delivery proof contains invalid unrewarded relayers vec: {:?}",
+					error,
+				);
+
+				fail!(Error::<T, I>::InvalidUnrewardedRelayers);
+			}
+		};
+
+	if let Some(confirmed_messages) = confirmed_messages {
+		// the lane is not stalled anymore
+		UnrewardedRelayersLimitReachedAt::<T, I>::remove(&lane_id);
+
+		// handle messages delivery confirmation
+		let preliminary_callback_overhead = relayers_state
+			.total_messages
+			.saturating_mul(single_message_callback_overhead);
+		let actual_callback_weight = T::OnDeliveryConfirmed::on_messages_delivered(&lane_id, &confirmed_messages);
+		match preliminary_callback_overhead.checked_sub(actual_callback_weight) {
+			Some(difference) if difference == 0 => (),
+			Some(difference) => {
+				log::trace!(
+					target: "runtime::bridge-messages",
+					"Messages delivery callback has returned unspent weight to refund the submitter: \
+					{} - {} = {}",
+					preliminary_callback_overhead,
+					actual_callback_weight,
+					difference,
+				);
+				actual_weight -= difference;
+			}
+			None => {
+				debug_assert!(false, "The delivery confirmation callback is wrong");
+				log::trace!(
+					target: "runtime::bridge-messages",
+					"Messages delivery callback has returned more weight that it may spent: \
+					{} vs {}",
+					preliminary_callback_overhead,
+					actual_callback_weight,
+				);
+			}
+		}
+
+		// emit 'delivered' event
+		let received_range = confirmed_messages.begin..=confirmed_messages.end;
+		confirmed_messages_count = confirmed_messages.total_messages();
+		Pallet::<T, I>::deposit_event(RawEvent::MessagesDelivered(lane_id, confirmed_messages));
+
+		// remember to reward relayers that have delivered messages
+		// this loop is bounded by `T::MaxUnrewardedRelayerEntriesAtInboundLane` on the bridged chain
+		for entry in lane_data.relayers {
+			let nonce_begin = sp_std::cmp::max(entry.messages.begin, *received_range.start());
+			let nonce_end = sp_std::cmp::min(entry.messages.end, *received_range.end());
+
+			// loop won't proceed if current entry is ahead of received range (begin > end).
+			// this loop is bound by `T::MaxUnconfirmedMessagesAtInboundLane` on the bridged chain
+			let mut relayer_reward = relayers_rewards.entry(entry.relayer).or_default();
+			for nonce in nonce_begin..nonce_end + 1 {
+				let message_data = OutboundMessages::<T, I>::get(MessageKey { lane_id, nonce })
+					.expect("message was just confirmed; we never prune unconfirmed messages; qed");
+				let mut message_reward = message_data.fee;
+				if let Some(ref bounty_per_message) = bounty_per_message {
+					let bounty = if *bounty_per_message > message_reward {
+						message_reward.clone()
+					} else {
+						bounty_per_message.clone()
+					};
+					message_reward = message_reward.saturating_sub(&bounty);
+					total_bounty = total_bounty.saturating_add(&bounty);
+				}
+				relayer_reward.reward = relayer_reward.reward.saturating_add(&message_reward);
+				relayer_reward.messages += 1;
+			}
+		}
+	}
+
+	// the bounty is paid along with the reward of the confirmation relayer
+	if !total_bounty.is_zero() {
+		let confirmation_relayer_reward = relayers_rewards.entry(confirmation_relayer.clone()).or_default();
+		confirmation_relayer_reward.reward = confirmation_relayer_reward.reward.saturating_add(&total_bounty);
+	}
+
+	// if some new messages have been confirmed, reward relayers
+	if !relayers_rewards.is_empty() {
+		let relayer_fund_account = Pallet::<T, I>::relayer_fund_account_id();
+		let paid_rewards = <T as Config<I>>::MessageDeliveryAndDispatchPayment::pay_relayers_rewards(
+			confirmation_relayer,
+			relayers_rewards,
+			&relayer_fund_account,
+		);
+		LanesStatistics::<T, I>::mutate(lane_id, |statistics| {
+			statistics.messages_confirmed = statistics.messages_confirmed.saturating_add(confirmed_messages_count);
+			statistics.total_rewards_paid = statistics.total_rewards_paid.saturating_add(&paid_rewards);
+		});
+	}
+
+	log::trace!(
+		target: "runtime::bridge-messages",
+		"Received messages delivery proof up to (and including) {} at lane {:?}",
+		last_delivered_nonce,
+		lane_id,
+	);
+
+	Ok((actual_weight, total_bounty))
+}
+
 /// Creates new inbound lane object, backed by runtime storage.
 fn inbound_lane<T: Config<I>, I: Instance>(lane_id: LaneId) -> InboundLane<RuntimeInboundLaneStorage<T, I>> {
 	InboundLane::new(inbound_lane_storage::<T, I>(lane_id))
@@ -959,6 +1827,10 @@ impl<T: Config<I>, I: Instance> InboundLaneStorage for RuntimeInboundLaneStorage
 		T::MaxUnconfirmedMessagesAtInboundLane::get()
 	}
 
+	fn max_confirmed_relayer_entries_to_prune(&self) -> MessageNonce {
+		T::MaxConfirmedRelayerEntriesToPrune::get()
+	}
+
 	fn data(&self) -> InboundLaneData<T::InboundRelayer> {
 		match self.cached_data.clone().into_inner() {
 			Some(data) => data,
@@ -974,6 +1846,9 @@ impl<T: Config<I>, I: Instance> InboundLaneStorage for RuntimeInboundLaneStorage
 	}
 
 	fn set_data(&mut self, data: InboundLaneData<T::InboundRelayer>) {
+		let prev_data = self.data();
+		update_inbound_lane_relayers::<T, I>(self.lane_id, &prev_data.relayers, &data.relayers);
+
 		*self.cached_data.try_borrow_mut().expect(
 			"we're in the single-threaded environment;\
 				we have no recursive borrows; qed",
@@ -982,6 +1857,40 @@ impl<T: Config<I>, I: Instance> InboundLaneStorage for RuntimeInboundLaneStorage
 	}
 }
 
+/// Update `InboundLaneRelayers` entries after the `InboundLaneData::relayers` has been changed.
+///
+/// Entries are only written if they have been added or modified. Both vectors are bounded by the
+/// `MaxUnrewardedRelayerEntriesAtInboundLane`.
+fn update_inbound_lane_relayers<T: Config<I>, I: Instance>(
+	lane_id: LaneId,
+	prev_relayers: &VecDeque<UnrewardedRelayer<T::InboundRelayer>>,
+	new_relayers: &VecDeque<UnrewardedRelayer<T::InboundRelayer>>,
+) {
+	for prev_entry in prev_relayers {
+		let is_removed = new_relayers
+			.iter()
+			.all(|new_entry| new_entry.messages.begin != prev_entry.messages.begin);
+		if is_removed {
+			InboundLaneRelayers::<T, I>::remove(MessageKey {
+				lane_id,
+				nonce: prev_entry.messages.begin,
+			});
+		}
+	}
+	for new_entry in new_relayers {
+		let is_updated = prev_relayers.iter().all(|prev_entry| prev_entry != new_entry);
+		if is_updated {
+			InboundLaneRelayers::<T, I>::insert(
+				MessageKey {
+					lane_id,
+					nonce: new_entry.messages.begin,
+				},
+				new_entry,
+			);
+		}
+	}
+}
+
 /// Runtime outbound lane storage.
 struct RuntimeOutboundLaneStorage<T, I = DefaultInstance> {
 	lane_id: LaneId,
@@ -1022,23 +1931,105 @@ impl<T: Config<I>, I: Instance> OutboundLaneStorage for RuntimeOutboundLaneStora
 	}
 
 	fn remove_message(&mut self, nonce: &MessageNonce) {
-		OutboundMessages::<T, I>::remove(MessageKey {
+		let message_key = MessageKey {
 			lane_id: self.lane_id,
 			nonce: *nonce,
-		});
+		};
+		OutboundMessages::<T, I>::remove(&message_key);
+		OutboundMessageSubmitters::<T, I>::remove(&message_key);
 	}
 }
 
-/// Verify messages proof and return proved messages with decoded payload.
+/// Returns declared weight of the `send_message` call.
+pub fn send_message_weight<T: Config<I>, I: Instance>(payload: &T::OutboundPayload) -> Weight {
+	// the submitter of the message is stored and submitters of all pruned messages are removed
+	let submitters_weight = T::DbWeight::get().writes(T::MaxMessagesToPruneAtOnce::get().saturating_add(1));
+	T::WeightInfo::send_message_weight(payload)
+		.saturating_add(T::LaneMessageVerifier::verify_message_weight())
+		.saturating_add(lane_statistics_update_weight::<T, I>())
+		.saturating_add(submitters_weight)
+}
+
+/// Returns declared weight of the `increase_message_fee` call.
+fn maximal_increase_message_fee_weight<T: Config<I>, I: Instance>() -> Weight {
+	T::WeightInfo::maximal_increase_message_fee().saturating_add(lane_statistics_update_weight::<T, I>())
+}
+
+/// Returns declared weight of the `update_message_fee` call.
+fn update_message_fee_weight<T: Config<I>, I: Instance>() -> Weight {
+	maximal_increase_message_fee_weight::<T, I>().saturating_add(fee_decrease_weight::<T, I>())
+}
+
+/// Returns weight of the pallet owner and message submitter reads, made when the message fee is decreased.
+fn fee_decrease_weight<T: Config<I>, I: Instance>() -> Weight {
+	T::DbWeight::get().reads(2)
+}
+
+/// Returns declared weight of the `receive_messages_delivery_proof` call.
+fn receive_messages_delivery_proof_weight<T: Config<I>, I: Instance>(
+	proof: &MessagesDeliveryProofOf<T, I>,
+	relayers_state: &UnrewardedRelayersState,
+) -> Weight {
+	T::WeightInfo::receive_messages_delivery_proof_weight(proof, relayers_state, T::DbWeight::get())
+		.saturating_add(lane_statistics_update_weight::<T, I>())
+}
+
+/// Returns weight of the single `LanesStatistics` entry update.
+fn lane_statistics_update_weight<T: Config<I>, I: Instance>() -> Weight {
+	T::DbWeight::get().reads_writes(1, 1)
+}
+
+/// Returns declared weight of the `receive_messages_proof` call.
+fn receive_messages_proof_weight<T: Config<I>, I: Instance>(
+	proof: &MessagesProofOf<T, I>,
+	messages_count: u32,
+	dispatch_weight: Weight,
+) -> Weight {
+	let lanes_count = <T::SourceHeaderChain as SourceHeaderChain<T::InboundMessageFee>>::proof_lanes_count(proof);
+	T::WeightInfo::receive_messages_proof_weight(proof.size_hint(), lanes_count, messages_count, dispatch_weight)
+		.saturating_add(T::MessageDispatch::dispatch_overhead_weight().saturating_mul(messages_count as Weight))
+		.saturating_add(inbound_lane_relayers_update_weight::<T, I>(lanes_count, messages_count))
+		.saturating_add(lane_statistics_update_weight::<T, I>().saturating_mul(lanes_count as Weight))
+		.saturating_add(allowed_relayers_read_weight::<T, I>())
+}
+
+/// Returns weight of the `AllowedRelayers` read, made by the `ensure_relayer_allowed`.
+///
+/// The set is stored as a single value with at most `MaxAllowedRelayers` accounts, so it is read
+/// using single storage read.
+fn allowed_relayers_read_weight<T: Config<I>, I: Instance>() -> Weight {
+	T::DbWeight::get().reads(1)
+}
+
+/// Returns weight of `InboundLaneRelayers` updates, made by the `receive_messages_proof` call.
+///
+/// Every received message inserts (or updates) the relayer entry. The outbound lane state of every
+/// lane may prune up to `MaxConfirmedRelayerEntriesToPrune` confirmed entries and move the partially
+/// confirmed entry (removal + insertion).
+fn inbound_lane_relayers_update_weight<T: Config<I>, I: Instance>(lanes_count: u32, messages_count: u32) -> Weight {
+	let lane_state_writes = T::MaxConfirmedRelayerEntriesToPrune::get()
+		.saturating_add(2)
+		.saturating_mul(sp_std::cmp::max(lanes_count, 1) as Weight);
+	T::DbWeight::get().writes(lane_state_writes.saturating_add(messages_count as Weight))
+}
+
+/// Verify messages proof and return proved messages with decoded payload, along with the storage
+/// proof statistics (if they're provided by the `SourceHeaderChain`).
 fn verify_and_decode_messages_proof<Chain: SourceHeaderChain<Fee>, Fee, DispatchPayload: Decode>(
 	proof: Chain::MessagesProof,
 	messages_count: u32,
-) -> Result<ProvedMessages<DispatchMessage<DispatchPayload, Fee>>, Chain::Error> {
+) -> Result<
+	(
+		ProvedMessages<DispatchMessage<DispatchPayload, Fee>>,
+		Option<StorageProofStats>,
+	),
+	Chain::Error,
+> {
 	// `receive_messages_proof` weight formula and `MaxUnconfirmedMessagesAtInboundLane` check
 	// guarantees that the `message_count` is sane and Vec<Message> may be allocated.
 	// (tx with too many messages will either be rejected from the pool, or will fail earlier)
-	Chain::verify_messages_proof(proof, messages_count).map(|messages_by_lane| {
-		messages_by_lane
+	Chain::verify_messages_proof_with_stats(proof, messages_count).map(|(messages_by_lane, stats)| {
+		let messages_by_lane = messages_by_lane
 			.into_iter()
 			.map(|(lane, lane_data)| {
 				(
@@ -1049,24 +2040,55 @@ fn verify_and_decode_messages_proof<Chain: SourceHeaderChain<Fee>, Fee, Dispatch
 					},
 				)
 			})
-			.collect()
+			.collect();
+		(messages_by_lane, stats)
 	})
 }
 
+impl<T: Config<I>, I: Instance> From<Error<T, I>> for bp_runtime::BridgeError {
+	fn from(error: Error<T, I>) -> Self {
+		match error {
+			Error::Halted => Self::Halted,
+			Error::MessageRejectedByChainVerifier => Self::MessageRejected,
+			Error::MessageRejectedByLaneVerifier => Self::MessageRejected,
+			Error::FailedToWithdrawMessageFee => Self::PaymentFailed,
+			Error::TooManyMessagesInTheProof => Self::LimitExceeded,
+			Error::InvalidMessagesProof => Self::InvalidMessagesProof,
+			Error::InvalidMessagesDeliveryProof => Self::InvalidMessagesProof,
+			Error::InvalidUnrewardedRelayers => Self::InvalidMessagesProof,
+			Error::InvalidUnrewardedRelayersState => Self::InvalidMessagesProof,
+			Error::MessageIsAlreadyDelivered => Self::InvalidState,
+			Error::MessageIsNotYetSent => Self::InvalidState,
+			Error::TryingToConfirmMoreMessagesThanExpected => Self::InvalidMessagesProof,
+			Error::LaneIsNotStalled => Self::InvalidState,
+			Error::StalledConfirmationBountyIsNotYetAvailable => Self::InvalidState,
+			Error::RelayerIsNotAllowed => Self::NotAllowed,
+			Error::TooManyAllowedRelayers => Self::LimitExceeded,
+			Error::FailedToRefundMessageFee => Self::PaymentFailed,
+			Error::MessageSubmitterIsUnknown => Self::InvalidState,
+			Error::NotPendingOwner => Self::NotAllowed,
+			Error::NoNewMessagesDelivered => Self::InvalidState,
+			// hidden variant that is never constructed
+			_ => Self::InvalidState,
+		}
+	}
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
 	use crate::mock::{
-		message, message_payload, run_test, unrewarded_relayer, Event as TestEvent, Origin,
-		TestMessageDeliveryAndDispatchPayment, TestMessagesDeliveryProof, TestMessagesParameter, TestMessagesProof,
-		TestOnDeliveryConfirmed1, TestOnDeliveryConfirmed2, TestRuntime, TokenConversionRate,
-		PAYLOAD_REJECTED_BY_TARGET_CHAIN, REGULAR_PAYLOAD, TEST_LANE_ID, TEST_RELAYER_A, TEST_RELAYER_B,
+		message, message_payload, run_test, unrewarded_relayer, DbWeight, Event as TestEvent,
+		MaxConfirmedRelayerEntriesToPrune, Origin, TestDispatchOverheadWeight, TestMessageDeliveryAndDispatchPayment,
+		TestMessagesDeliveryProof, TestMessagesParameter, TestMessagesProof, TestOnDeliveryConfirmed1,
+		TestOnDeliveryConfirmed2, TestRuntime, TokenConversionRate, PAYLOAD_REJECTED_BY_TARGET_CHAIN, REGULAR_PAYLOAD,
+		TEST_LANE_ID, TEST_RELAYER_A, TEST_RELAYER_B,
 	};
 	use bp_messages::{UnrewardedRelayer, UnrewardedRelayersState};
-	use frame_support::{assert_noop, assert_ok};
+	use frame_support::{assert_noop, assert_ok, traits::OnIdle};
 	use frame_system::{EventRecord, Pallet as System, Phase};
 	use hex_literal::hex;
-	use sp_runtime::DispatchError;
+	use sp_runtime::{testing::TestSignature, DispatchError};
 
 	fn get_ready_for_events() {
 		System::<TestRuntime>::set_block_number(1);
@@ -1152,7 +2174,9 @@ mod tests {
 		run_test(|| {
 			PalletOwner::<TestRuntime>::put(2);
 
-			assert_ok!(Pallet::<TestRuntime>::set_owner(Origin::root(), Some(1)));
+			assert_ok!(Pallet::<TestRuntime>::propose_owner(Origin::root(), Some(1)));
+			assert_eq!(PalletOwner::<TestRuntime>::get(), Some(2));
+			assert_ok!(Pallet::<TestRuntime>::accept_owner(Origin::signed(1)));
 			assert_noop!(
 				Pallet::<TestRuntime>::set_operating_mode(Origin::signed(2), OperatingMode::Halted),
 				DispatchError::BadOrigin,
@@ -1162,7 +2186,7 @@ mod tests {
 				OperatingMode::Halted
 			));
 
-			assert_ok!(Pallet::<TestRuntime>::set_owner(Origin::signed(1), None));
+			assert_ok!(Pallet::<TestRuntime>::propose_owner(Origin::signed(1), None));
 			assert_noop!(
 				Pallet::<TestRuntime>::set_operating_mode(Origin::signed(1), OperatingMode::Normal),
 				DispatchError::BadOrigin,
@@ -1178,6 +2202,51 @@ mod tests {
 		});
 	}
 
+	#[test]
+	fn ownership_may_only_be_accepted_by_proposed_owner() {
+		run_test(|| {
+			System::<TestRuntime>::set_block_number(1);
+			System::<TestRuntime>::reset_events();
+			PalletOwner::<TestRuntime>::put(2);
+
+			assert_ok!(Pallet::<TestRuntime>::propose_owner(Origin::signed(2), Some(1)));
+			assert_noop!(
+				Pallet::<TestRuntime>::accept_owner(Origin::signed(3)),
+				Error::<TestRuntime, DefaultInstance>::NotPendingOwner,
+			);
+			assert_ok!(Pallet::<TestRuntime>::accept_owner(Origin::signed(1)));
+			assert_eq!(PalletOwner::<TestRuntime>::get(), Some(1));
+			assert_eq!(PendingPalletOwner::<TestRuntime>::get(), None);
+
+			assert_eq!(
+				System::<TestRuntime>::events(),
+				vec![
+					EventRecord {
+						phase: Phase::Initialization,
+						event: TestEvent::Messages(RawEvent::OwnerProposed(1)),
+						topics: vec![],
+					},
+					EventRecord {
+						phase: Phase::Initialization,
+						event: TestEvent::Messages(RawEvent::OwnerChanged(Some(1))),
+						topics: vec![],
+					},
+				],
+			);
+		});
+	}
+
+	#[test]
+	fn indices_of_existing_calls_are_not_changed() {
+		// relay clients of some chains are using hardcoded call indices
+		assert_eq!(Call::<TestRuntime>::propose_owner(None).encode()[0], 0);
+		assert_eq!(
+			Call::<TestRuntime>::increase_message_fee(TEST_LANE_ID, 1, 0).encode()[0],
+			4
+		);
+		assert_eq!(Call::<TestRuntime>::accept_owner().encode()[0], 13);
+	}
+
 	#[test]
 	fn pallet_may_be_halted_by_root() {
 		run_test(|| {
@@ -1434,6 +2503,29 @@ mod tests {
 		});
 	}
 
+	#[test]
+	fn send_message_with_priority_works() {
+		run_test(|| {
+			send_regular_message();
+			assert_ok!(Pallet::<TestRuntime>::send_message_with_priority(
+				Origin::signed(1),
+				TEST_LANE_ID,
+				REGULAR_PAYLOAD,
+				REGULAR_PAYLOAD.declared_weight,
+				42,
+			));
+
+			assert_eq!(
+				Pallet::<TestRuntime>::outbound_message_data(TEST_LANE_ID, 1).map(|message| message.priority),
+				Some(DEFAULT_MESSAGE_PRIORITY),
+			);
+			assert_eq!(
+				Pallet::<TestRuntime>::outbound_message_data(TEST_LANE_ID, 2).map(|message| message.priority),
+				Some(42),
+			);
+		});
+	}
+
 	#[test]
 	fn chain_verifier_rejects_invalid_message_in_send_message() {
 		run_test(|| {
@@ -1492,6 +2584,132 @@ mod tests {
 		});
 	}
 
+	#[test]
+	fn set_allowed_relayers_works() {
+		run_test(|| {
+			assert_noop!(
+				Pallet::<TestRuntime>::set_allowed_relayers(Origin::signed(1), Some(vec![1])),
+				DispatchError::BadOrigin,
+			);
+			assert_noop!(
+				Pallet::<TestRuntime>::set_allowed_relayers(Origin::root(), Some(vec![1, 2, 3])),
+				Error::<TestRuntime, DefaultInstance>::TooManyAllowedRelayers,
+			);
+
+			assert_ok!(Pallet::<TestRuntime>::set_allowed_relayers(
+				Origin::root(),
+				Some(vec![1, 2])
+			));
+			assert_eq!(
+				Pallet::<TestRuntime>::allowed_relayers().map(|relayers| relayers.to_vec()),
+				Some(vec![1, 2]),
+			);
+
+			assert_ok!(Pallet::<TestRuntime>::set_allowed_relayers(Origin::root(), None));
+			assert_eq!(Pallet::<TestRuntime>::allowed_relayers(), None);
+		});
+	}
+
+	#[test]
+	fn set_bridged_chain_genesis_hash_works() {
+		run_test(|| {
+			assert_noop!(
+				Pallet::<TestRuntime>::set_bridged_chain_genesis_hash(Origin::signed(1), Some([42u8; 32].into())),
+				DispatchError::BadOrigin,
+			);
+
+			assert_ok!(Pallet::<TestRuntime>::set_bridged_chain_genesis_hash(
+				Origin::root(),
+				Some([42u8; 32].into())
+			));
+			assert_eq!(
+				Pallet::<TestRuntime>::bridged_chain_genesis_hash(),
+				Some([42u8; 32].into()),
+			);
+
+			assert_ok!(Pallet::<TestRuntime>::set_bridged_chain_genesis_hash(
+				Origin::root(),
+				None
+			));
+			assert_eq!(Pallet::<TestRuntime>::bridged_chain_genesis_hash(), None);
+		});
+	}
+
+	#[test]
+	fn receive_messages_proof_is_restricted_to_allowed_relayers() {
+		run_test(|| {
+			assert_ok!(Pallet::<TestRuntime>::set_allowed_relayers(
+				Origin::root(),
+				Some(vec![1])
+			));
+
+			assert_noop!(
+				Pallet::<TestRuntime>::receive_messages_proof(
+					Origin::signed(2),
+					TEST_RELAYER_A,
+					Ok(vec![message(1, REGULAR_PAYLOAD)]).into(),
+					1,
+					REGULAR_PAYLOAD.declared_weight,
+				),
+				Error::<TestRuntime, DefaultInstance>::RelayerIsNotAllowed,
+			);
+			assert_noop!(
+				receive_messages_proof_unsigned(
+					Origin::none(),
+					TEST_RELAYER_A,
+					Ok(vec![message(1, REGULAR_PAYLOAD)]).into(),
+					1,
+					REGULAR_PAYLOAD.declared_weight,
+				),
+				Error::<TestRuntime, DefaultInstance>::RelayerIsNotAllowed,
+			);
+
+			assert_ok!(Pallet::<TestRuntime>::receive_messages_proof(
+				Origin::signed(1),
+				TEST_RELAYER_A,
+				Ok(vec![message(1, REGULAR_PAYLOAD)]).into(),
+				1,
+				REGULAR_PAYLOAD.declared_weight,
+			));
+			assert_eq!(InboundLanes::<TestRuntime>::get(TEST_LANE_ID).last_delivered_nonce(), 1);
+		});
+	}
+
+	#[test]
+	fn receive_messages_proof_accepts_messages_from_multiple_lanes() {
+		run_test(|| {
+			let other_lane_id = LaneId::Legacy([0, 0, 0, 2]);
+			let mut other_lane_message = message(1, REGULAR_PAYLOAD);
+			other_lane_message.key.lane_id = other_lane_id;
+			let proof: TestMessagesProof = Ok(vec![
+				message(1, REGULAR_PAYLOAD),
+				message(2, REGULAR_PAYLOAD),
+				other_lane_message,
+			])
+			.into();
+
+			// the weight of multi-lane proof is larger than the weight of single-lane proof
+			let single_lane_weight =
+				<TestRuntime as Config>::WeightInfo::receive_messages_proof_weight(proof.size_hint(), 1, 3, 0);
+			let multi_lane_weight = receive_messages_proof_weight::<TestRuntime, DefaultInstance>(&proof, 3, 0);
+			assert!(multi_lane_weight > single_lane_weight);
+
+			assert_ok!(Pallet::<TestRuntime>::receive_messages_proof(
+				Origin::signed(1),
+				TEST_RELAYER_A,
+				proof,
+				3,
+				REGULAR_PAYLOAD.declared_weight * 3,
+			));
+
+			assert_eq!(InboundLanes::<TestRuntime>::get(TEST_LANE_ID).last_delivered_nonce(), 2);
+			assert_eq!(
+				InboundLanes::<TestRuntime>::get(other_lane_id).last_delivered_nonce(),
+				1
+			);
+		});
+	}
+
 	#[test]
 	fn receive_messages_proof_updates_confirmed_message_nonce() {
 		run_test(|| {
@@ -1556,654 +2774,1797 @@ mod tests {
 	}
 
 	#[test]
-	fn receive_messages_proof_does_not_accept_message_if_dispatch_weight_is_not_enough() {
-		run_test(|| {
-			assert_ok!(Pallet::<TestRuntime>::receive_messages_proof(
-				Origin::signed(1),
-				TEST_RELAYER_A,
-				Ok(vec![message(1, REGULAR_PAYLOAD)]).into(),
-				1,
-				REGULAR_PAYLOAD.declared_weight - 1,
-			));
-			assert_eq!(InboundLanes::<TestRuntime>::get(TEST_LANE_ID).last_delivered_nonce(), 0);
-		});
-	}
-
-	#[test]
-	fn receive_messages_proof_rejects_invalid_proof() {
+	fn confirmed_relayer_entries_are_pruned_in_on_idle() {
 		run_test(|| {
-			assert_noop!(
-				Pallet::<TestRuntime, DefaultInstance>::receive_messages_proof(
-					Origin::signed(1),
-					TEST_RELAYER_A,
-					Err(()).into(),
-					1,
-					0,
-				),
-				Error::<TestRuntime, DefaultInstance>::InvalidMessagesProof,
+			InboundLanes::<TestRuntime, DefaultInstance>::insert(
+				TEST_LANE_ID,
+				InboundLaneData {
+					last_confirmed_nonce: 3,
+					relayers: vec![
+						unrewarded_relayer(1, 1, TEST_RELAYER_A),
+						unrewarded_relayer(2, 2, TEST_RELAYER_B),
+						unrewarded_relayer(3, 3, TEST_RELAYER_A),
+						unrewarded_relayer(4, 4, TEST_RELAYER_B),
+					]
+					.into_iter()
+					.collect(),
+				},
 			);
-		});
-	}
+			InboundLanesToPrune::<DefaultInstance>::insert(TEST_LANE_ID, ());
 
-	#[test]
-	fn receive_messages_proof_rejects_proof_with_too_many_messages() {
-		run_test(|| {
-			assert_noop!(
-				Pallet::<TestRuntime, DefaultInstance>::receive_messages_proof(
-					Origin::signed(1),
-					TEST_RELAYER_A,
-					Ok(vec![message(1, REGULAR_PAYLOAD)]).into(),
-					u32::MAX,
-					0,
-				),
-				Error::<TestRuntime, DefaultInstance>::TooManyMessagesInTheProof,
-			);
-		});
-	}
+			// nothing is pruned if there's not enough weight
+			assert_eq!(Pallet::<TestRuntime>::on_idle(0, 1), 0);
+			assert_eq!(InboundLanes::<TestRuntime>::get(TEST_LANE_ID).relayers.len(), 4);
 
-	#[test]
-	fn receive_messages_delivery_proof_works() {
-		run_test(|| {
-			send_regular_message();
-			receive_messages_delivery_proof();
+			// at most `MaxConfirmedRelayerEntriesToPrune` entries are pruned at once
+			let single_lane_weight = DbWeight::get().reads(1)
+				+ DbWeight::get().reads_writes(2, 2 + MaxConfirmedRelayerEntriesToPrune::get());
+			assert_eq!(Pallet::<TestRuntime>::on_idle(0, Weight::MAX), single_lane_weight);
+			assert_eq!(InboundLanes::<TestRuntime>::get(TEST_LANE_ID).relayers.len(), 2);
+			assert!(InboundLanesToPrune::<DefaultInstance>::contains_key(TEST_LANE_ID));
 
+			// lane is removed from the set when all confirmed entries are pruned
+			assert_eq!(Pallet::<TestRuntime>::on_idle(0, Weight::MAX), single_lane_weight);
 			assert_eq!(
-				OutboundLanes::<DefaultInstance>::get(&TEST_LANE_ID).latest_received_nonce,
-				1,
+				InboundLanes::<TestRuntime>::get(TEST_LANE_ID).relayers,
+				vec![unrewarded_relayer(4, 4, TEST_RELAYER_B)]
 			);
+			assert!(!InboundLanesToPrune::<DefaultInstance>::contains_key(TEST_LANE_ID));
 		});
 	}
 
 	#[test]
-	fn receive_messages_delivery_proof_rewards_relayers() {
+	fn confirmed_relayer_entries_that_are_not_pruned_are_ignored_by_delivery_proof() {
 		run_test(|| {
-			assert_ok!(Pallet::<TestRuntime>::send_message(
-				Origin::signed(1),
+			for _ in 1..=5 {
+				assert_ok!(Pallet::<TestRuntime>::send_message(
+					Origin::signed(1),
+					TEST_LANE_ID,
+					REGULAR_PAYLOAD,
+					1000,
+				));
+			}
+			OutboundLanes::<DefaultInstance>::mutate(TEST_LANE_ID, |lane_data| lane_data.latest_received_nonce = 3);
+
+			// messages 1..=3 are confirmed, but only `MaxConfirmedRelayerEntriesToPrune` entries are pruned
+			InboundLanes::<TestRuntime, DefaultInstance>::insert(
 				TEST_LANE_ID,
-				REGULAR_PAYLOAD,
-				1000,
-			));
-			assert_ok!(Pallet::<TestRuntime>::send_message(
+				InboundLaneData {
+					last_confirmed_nonce: 0,
+					relayers: vec![
+						unrewarded_relayer(1, 1, TEST_RELAYER_A),
+						unrewarded_relayer(2, 2, TEST_RELAYER_B),
+						unrewarded_relayer(3, 3, TEST_RELAYER_A),
+						unrewarded_relayer(4, 4, TEST_RELAYER_B),
+					]
+					.into_iter()
+					.collect(),
+				},
+			);
+			let mut message_proof: TestMessagesProof = Ok(vec![message(5, REGULAR_PAYLOAD)]).into();
+			message_proof.result.as_mut().unwrap()[0].1.lane_state = Some(OutboundLaneData {
+				latest_received_nonce: 3,
+				..Default::default()
+			});
+			assert_ok!(Pallet::<TestRuntime>::receive_messages_proof(
 				Origin::signed(1),
-				TEST_LANE_ID,
-				REGULAR_PAYLOAD,
-				2000,
+				TEST_RELAYER_A,
+				message_proof,
+				1,
+				REGULAR_PAYLOAD.declared_weight,
 			));
 
-			// this reports delivery of message 1 => reward is paid to TEST_RELAYER_A
-			assert_ok!(Pallet::<TestRuntime>::receive_messages_delivery_proof(
-				Origin::signed(1),
-				TestMessagesDeliveryProof(Ok((
-					TEST_LANE_ID,
-					InboundLaneData {
-						relayers: vec![unrewarded_relayer(1, 1, TEST_RELAYER_A)].into_iter().collect(),
-						..Default::default()
-					}
-				))),
+			// confirmed entry of message 3 is still a part of unrewarded relayers state
+			let inbound_lane_data = InboundLanes::<TestRuntime>::get(TEST_LANE_ID);
+			assert_eq!(
+				inbound_lane_data.relayers,
+				vec![
+					unrewarded_relayer(3, 3, TEST_RELAYER_A),
+					unrewarded_relayer(4, 4, TEST_RELAYER_B),
+					unrewarded_relayer(5, 5, TEST_RELAYER_A),
+				]
+			);
+			let relayers_state = Pallet::<TestRuntime>::inbound_unrewarded_relayers_state(TEST_LANE_ID);
+			assert_eq!(
+				relayers_state,
 				UnrewardedRelayersState {
-					unrewarded_relayer_entries: 1,
-					total_messages: 1,
-					..Default::default()
+					unrewarded_relayer_entries: 3,
+					messages_in_oldest_entry: 1,
+					total_messages: 3,
 				},
-			));
-			assert!(TestMessageDeliveryAndDispatchPayment::is_reward_paid(
-				TEST_RELAYER_A,
-				1000
-			));
-			assert!(!TestMessageDeliveryAndDispatchPayment::is_reward_paid(
-				TEST_RELAYER_B,
-				2000
-			));
+			);
 
-			// this reports delivery of both message 1 and message 2 => reward is paid only to TEST_RELAYER_B
+			// delivery proof with this state is accepted and message 3 is not rewarded again
 			assert_ok!(Pallet::<TestRuntime>::receive_messages_delivery_proof(
 				Origin::signed(1),
-				TestMessagesDeliveryProof(Ok((
-					TEST_LANE_ID,
-					InboundLaneData {
-						relayers: vec![
-							unrewarded_relayer(1, 1, TEST_RELAYER_A),
-							unrewarded_relayer(2, 2, TEST_RELAYER_B)
-						]
-						.into_iter()
-						.collect(),
-						..Default::default()
-					}
-				))),
-				UnrewardedRelayersState {
-					unrewarded_relayer_entries: 2,
-					total_messages: 2,
-					..Default::default()
-				},
+				TestMessagesDeliveryProof(Ok((TEST_LANE_ID, inbound_lane_data))),
+				relayers_state,
 			));
-			assert!(!TestMessageDeliveryAndDispatchPayment::is_reward_paid(
+			assert_eq!(
+				OutboundLanes::<DefaultInstance>::get(TEST_LANE_ID).latest_received_nonce,
+				5
+			);
+			assert!(TestMessageDeliveryAndDispatchPayment::is_reward_paid(
 				TEST_RELAYER_A,
 				1000
 			));
 			assert!(TestMessageDeliveryAndDispatchPayment::is_reward_paid(
 				TEST_RELAYER_B,
-				2000
+				1000
 			));
 		});
 	}
 
 	#[test]
-	fn receive_messages_delivery_proof_rejects_invalid_proof() {
+	fn lanes_invariants_are_checked() {
 		run_test(|| {
+			send_regular_message();
+			receive_messages_delivery_proof();
+			InboundLanes::<TestRuntime, DefaultInstance>::insert(
+				TEST_LANE_ID,
+				InboundLaneData {
+					last_confirmed_nonce: 1,
+					relayers: vec![
+						unrewarded_relayer(1, 1, TEST_RELAYER_A),
+						unrewarded_relayer(2, 3, TEST_RELAYER_B),
+					]
+					.into_iter()
+					.collect(),
+				},
+			);
+			assert_eq!(Pallet::<TestRuntime>::check_lanes_invariants(), Ok(()));
+
+			// gap between nonces of inbound lane relayer entries
+			InboundLanes::<TestRuntime, DefaultInstance>::mutate(TEST_LANE_ID, |lane_data| {
+				lane_data.relayers.push_back(unrewarded_relayer(5, 5, TEST_RELAYER_A))
+			});
+			assert!(Pallet::<TestRuntime>::check_lanes_invariants().is_err());
+			InboundLanes::<TestRuntime, DefaultInstance>::mutate(TEST_LANE_ID, |lane_data| {
+				lane_data.relayers.pop_back()
+			});
+			assert_eq!(Pallet::<TestRuntime>::check_lanes_invariants(), Ok(()));
+
+			// outbound lane has received messages that have never been generated
+			OutboundLanes::<DefaultInstance>::mutate(TEST_LANE_ID, |lane_data| lane_data.latest_received_nonce = 2);
+			assert!(Pallet::<TestRuntime>::check_lanes_invariants().is_err());
+		});
+	}
+
+	#[test]
+	fn inbound_lane_relayers_are_copied_to_separate_storage() {
+		run_test(|| {
+			let entry_key = |nonce| MessageKey {
+				lane_id: TEST_LANE_ID,
+				nonce,
+			};
+
+			// new entry is inserted when message is delivered
+			assert_ok!(Pallet::<TestRuntime>::receive_messages_proof(
+				Origin::signed(1),
+				TEST_RELAYER_A,
+				Ok(vec![message(1, REGULAR_PAYLOAD)]).into(),
+				1,
+				REGULAR_PAYLOAD.declared_weight,
+			));
+			assert_eq!(
+				InboundLaneRelayers::<TestRuntime>::get(entry_key(1)),
+				Some(unrewarded_relayer(1, 1, TEST_RELAYER_A)),
+			);
+
+			// entry is updated when the same relayer delivers next message
+			assert_ok!(Pallet::<TestRuntime>::receive_messages_proof(
+				Origin::signed(1),
+				TEST_RELAYER_A,
+				Ok(vec![message(2, REGULAR_PAYLOAD)]).into(),
+				1,
+				REGULAR_PAYLOAD.declared_weight,
+			));
+			assert_eq!(
+				InboundLaneRelayers::<TestRuntime>::get(entry_key(1)),
+				Some(unrewarded_relayer(1, 2, TEST_RELAYER_A)),
+			);
+
+			// entry is removed when it is pruned from the lane
+			let mut message_proof: TestMessagesProof = Ok(vec![message(3, REGULAR_PAYLOAD)]).into();
+			message_proof.result.as_mut().unwrap()[0].1.lane_state = Some(OutboundLaneData {
+				latest_received_nonce: 2,
+				..Default::default()
+			});
+			assert_ok!(Pallet::<TestRuntime>::receive_messages_proof(
+				Origin::signed(1),
+				TEST_RELAYER_B,
+				message_proof,
+				1,
+				REGULAR_PAYLOAD.declared_weight,
+			));
+			assert_eq!(InboundLaneRelayers::<TestRuntime>::get(entry_key(1)), None);
+			assert_eq!(
+				InboundLaneRelayers::<TestRuntime>::get(entry_key(3)),
+				Some(unrewarded_relayer(3, 3, TEST_RELAYER_B)),
+			);
+		});
+	}
+
+	#[test]
+	fn receive_messages_proof_marks_lane_with_confirmed_relayer_entries_for_pruning() {
+		run_test(|| {
+			InboundLanes::<TestRuntime, DefaultInstance>::insert(
+				TEST_LANE_ID,
+				InboundLaneData {
+					last_confirmed_nonce: 0,
+					relayers: vec![
+						unrewarded_relayer(1, 1, TEST_RELAYER_A),
+						unrewarded_relayer(2, 2, TEST_RELAYER_B),
+						unrewarded_relayer(3, 3, TEST_RELAYER_A),
+					]
+					.into_iter()
+					.collect(),
+				},
+			);
+
+			let mut message_proof: TestMessagesProof = Ok(vec![message(4, REGULAR_PAYLOAD)]).into();
+			message_proof.result.as_mut().unwrap()[0].1.lane_state = Some(OutboundLaneData {
+				latest_received_nonce: 3,
+				..Default::default()
+			});
+
+			assert_ok!(Pallet::<TestRuntime>::receive_messages_proof(
+				Origin::signed(1),
+				TEST_RELAYER_B,
+				message_proof,
+				1,
+				REGULAR_PAYLOAD.declared_weight,
+			));
+
+			assert_eq!(
+				InboundLanes::<TestRuntime>::get(TEST_LANE_ID).relayers,
+				vec![
+					unrewarded_relayer(3, 3, TEST_RELAYER_A),
+					unrewarded_relayer(4, 4, TEST_RELAYER_B)
+				]
+			);
+			assert!(InboundLanesToPrune::<DefaultInstance>::contains_key(TEST_LANE_ID));
+		});
+	}
+
+	#[test]
+	fn receive_messages_proof_does_not_accept_message_if_dispatch_weight_is_not_enough() {
+		run_test(|| {
+			assert_ok!(Pallet::<TestRuntime>::receive_messages_proof(
+				Origin::signed(1),
+				TEST_RELAYER_A,
+				Ok(vec![message(1, REGULAR_PAYLOAD)]).into(),
+				1,
+				REGULAR_PAYLOAD.declared_weight - 1,
+			));
+			assert_eq!(InboundLanes::<TestRuntime>::get(TEST_LANE_ID).last_delivered_nonce(), 0);
+		});
+	}
+
+	#[test]
+	fn receive_messages_proof_emits_message_rejected_events() {
+		run_test(|| {
+			System::<TestRuntime>::set_block_number(1);
+			System::<TestRuntime>::reset_events();
+
+			assert_ok!(Pallet::<TestRuntime>::receive_messages_proof(
+				Origin::signed(1),
+				TEST_RELAYER_A,
+				Ok(vec![message(1, REGULAR_PAYLOAD)]).into(),
+				1,
+				REGULAR_PAYLOAD.declared_weight,
+			));
+
+			// message#1 is already delivered and message#3 can't be delivered before message#2
+			assert_ok!(Pallet::<TestRuntime>::receive_messages_proof(
+				Origin::signed(1),
+				TEST_RELAYER_A,
+				Ok(vec![message(1, REGULAR_PAYLOAD), message(3, REGULAR_PAYLOAD)]).into(),
+				2,
+				REGULAR_PAYLOAD.declared_weight * 2,
+			));
+
+			// relayer hasn't declared enough weight to dispatch message#2
+			assert_ok!(Pallet::<TestRuntime>::receive_messages_proof(
+				Origin::signed(1),
+				TEST_RELAYER_A,
+				Ok(vec![message(2, REGULAR_PAYLOAD)]).into(),
+				1,
+				REGULAR_PAYLOAD.declared_weight - 1,
+			));
+
+			// message#2 is delivered, but its dispatch fails
+			let mut failing_payload = REGULAR_PAYLOAD;
+			failing_payload.dispatch_result.dispatch_result = false;
+			assert_ok!(Pallet::<TestRuntime>::receive_messages_proof(
+				Origin::signed(1),
+				TEST_RELAYER_A,
+				Ok(vec![message(2, failing_payload)]).into(),
+				1,
+				REGULAR_PAYLOAD.declared_weight,
+			));
+			assert_eq!(InboundLanes::<TestRuntime>::get(TEST_LANE_ID).last_delivered_nonce(), 2);
+
+			assert_eq!(
+				System::<TestRuntime>::events()
+					.into_iter()
+					.filter_map(|record| match record.event {
+						TestEvent::Messages(event) => Some(event),
+						_ => None,
+					})
+					.collect::<Vec<_>>(),
+				vec![
+					RawEvent::MessageRejected(TEST_LANE_ID, 1, MessageRejectionReason::AlreadyDelivered),
+					RawEvent::MessageRejected(TEST_LANE_ID, 3, MessageRejectionReason::UnexpectedNonce),
+					RawEvent::MessageRejected(TEST_LANE_ID, 2, MessageRejectionReason::DispatchWeightTooLow),
+					RawEvent::MessageRejected(TEST_LANE_ID, 2, MessageRejectionReason::DispatchFailed),
+				],
+			);
+		});
+	}
+
+	#[test]
+	fn receive_messages_proof_emits_aggregated_event_for_large_delivery() {
+		run_test(|| {
+			System::<TestRuntime>::set_block_number(1);
+			System::<TestRuntime>::reset_events();
+
+			// message#2 is delivered, but its dispatch fails
+			let mut failing_payload = REGULAR_PAYLOAD;
+			failing_payload.dispatch_result.dispatch_result = false;
+			assert_ok!(Pallet::<TestRuntime>::receive_messages_proof(
+				Origin::signed(1),
+				TEST_RELAYER_A,
+				Ok(vec![
+					message(1, REGULAR_PAYLOAD),
+					message(2, failing_payload),
+					message(3, REGULAR_PAYLOAD),
+				])
+				.into(),
+				3,
+				REGULAR_PAYLOAD.declared_weight * 3,
+			));
+
+			// all messages are already delivered, but rejections are not reported
+			assert_ok!(Pallet::<TestRuntime>::receive_messages_proof(
+				Origin::signed(1),
+				TEST_RELAYER_A,
+				Ok(vec![
+					message(1, REGULAR_PAYLOAD),
+					message(2, REGULAR_PAYLOAD),
+					message(3, REGULAR_PAYLOAD),
+				])
+				.into(),
+				3,
+				REGULAR_PAYLOAD.declared_weight * 3,
+			));
+			assert_eq!(InboundLanes::<TestRuntime>::get(TEST_LANE_ID).last_delivered_nonce(), 3);
+
+			assert_eq!(
+				System::<TestRuntime>::events()
+					.into_iter()
+					.filter_map(|record| match record.event {
+						TestEvent::Messages(event) => Some(event),
+						_ => None,
+					})
+					.collect::<Vec<_>>(),
+				vec![RawEvent::MessagesReceived(TEST_LANE_ID, 1, 3)],
+			);
+		});
+	}
+
+	#[test]
+	fn receive_messages_proof_rejects_invalid_proof() {
+		run_test(|| {
+			assert_noop!(
+				Pallet::<TestRuntime, DefaultInstance>::receive_messages_proof(
+					Origin::signed(1),
+					TEST_RELAYER_A,
+					Err(()).into(),
+					1,
+					0,
+				),
+				Error::<TestRuntime, DefaultInstance>::InvalidMessagesProof,
+			);
+		});
+	}
+
+	#[test]
+	fn receive_messages_proof_rejects_proof_with_too_many_messages() {
+		run_test(|| {
+			assert_noop!(
+				Pallet::<TestRuntime, DefaultInstance>::receive_messages_proof(
+					Origin::signed(1),
+					TEST_RELAYER_A,
+					Ok(vec![message(1, REGULAR_PAYLOAD)]).into(),
+					u32::MAX,
+					0,
+				),
+				Error::<TestRuntime, DefaultInstance>::TooManyMessagesInTheProof,
+			);
+		});
+	}
+
+	#[test]
+	fn receive_messages_delivery_proof_works() {
+		run_test(|| {
+			send_regular_message();
+			receive_messages_delivery_proof();
+
+			assert_eq!(
+				OutboundLanes::<DefaultInstance>::get(&TEST_LANE_ID).latest_received_nonce,
+				1,
+			);
+		});
+	}
+
+	#[test]
+	fn receive_messages_delivery_proof_rewards_relayers() {
+		run_test(|| {
+			assert_ok!(Pallet::<TestRuntime>::send_message(
+				Origin::signed(1),
+				TEST_LANE_ID,
+				REGULAR_PAYLOAD,
+				1000,
+			));
+			assert_ok!(Pallet::<TestRuntime>::send_message(
+				Origin::signed(1),
+				TEST_LANE_ID,
+				REGULAR_PAYLOAD,
+				2000,
+			));
+
+			// this reports delivery of message 1 => reward is paid to TEST_RELAYER_A
+			assert_ok!(Pallet::<TestRuntime>::receive_messages_delivery_proof(
+				Origin::signed(1),
+				TestMessagesDeliveryProof(Ok((
+					TEST_LANE_ID,
+					InboundLaneData {
+						relayers: vec![unrewarded_relayer(1, 1, TEST_RELAYER_A)].into_iter().collect(),
+						..Default::default()
+					}
+				))),
+				UnrewardedRelayersState {
+					unrewarded_relayer_entries: 1,
+					total_messages: 1,
+					..Default::default()
+				},
+			));
+			assert!(TestMessageDeliveryAndDispatchPayment::is_reward_paid(
+				TEST_RELAYER_A,
+				1000
+			));
+			assert!(!TestMessageDeliveryAndDispatchPayment::is_reward_paid(
+				TEST_RELAYER_B,
+				2000
+			));
+
+			// this reports delivery of both message 1 and message 2 => reward is paid only to TEST_RELAYER_B
+			assert_ok!(Pallet::<TestRuntime>::receive_messages_delivery_proof(
+				Origin::signed(1),
+				TestMessagesDeliveryProof(Ok((
+					TEST_LANE_ID,
+					InboundLaneData {
+						relayers: vec![
+							unrewarded_relayer(1, 1, TEST_RELAYER_A),
+							unrewarded_relayer(2, 2, TEST_RELAYER_B)
+						]
+						.into_iter()
+						.collect(),
+						..Default::default()
+					}
+				))),
+				UnrewardedRelayersState {
+					unrewarded_relayer_entries: 2,
+					total_messages: 2,
+					..Default::default()
+				},
+			));
+			assert!(!TestMessageDeliveryAndDispatchPayment::is_reward_paid(
+				TEST_RELAYER_A,
+				1000
+			));
+			assert!(TestMessageDeliveryAndDispatchPayment::is_reward_paid(
+				TEST_RELAYER_B,
+				2000
+			));
+		});
+	}
+
+	#[test]
+	fn receive_messages_delivery_proof_rejects_invalid_proof() {
+		run_test(|| {
+			assert_noop!(
+				Pallet::<TestRuntime>::receive_messages_delivery_proof(
+					Origin::signed(1),
+					TestMessagesDeliveryProof(Err(())),
+					Default::default(),
+				),
+				Error::<TestRuntime, DefaultInstance>::InvalidMessagesDeliveryProof,
+			);
+		});
+	}
+
+	#[test]
+	fn receive_messages_delivery_proof_rejects_proof_if_declared_relayers_state_is_invalid() {
+		run_test(|| {
+			// when number of relayers entires is invalid
+			assert_noop!(
+				Pallet::<TestRuntime>::receive_messages_delivery_proof(
+					Origin::signed(1),
+					TestMessagesDeliveryProof(Ok((
+						TEST_LANE_ID,
+						InboundLaneData {
+							relayers: vec![
+								unrewarded_relayer(1, 1, TEST_RELAYER_A),
+								unrewarded_relayer(2, 2, TEST_RELAYER_B)
+							]
+							.into_iter()
+							.collect(),
+							..Default::default()
+						}
+					))),
+					UnrewardedRelayersState {
+						unrewarded_relayer_entries: 1,
+						total_messages: 2,
+						..Default::default()
+					},
+				),
+				Error::<TestRuntime, DefaultInstance>::InvalidUnrewardedRelayersState,
+			);
+
+			// when number of messages is invalid
+			assert_noop!(
+				Pallet::<TestRuntime>::receive_messages_delivery_proof(
+					Origin::signed(1),
+					TestMessagesDeliveryProof(Ok((
+						TEST_LANE_ID,
+						InboundLaneData {
+							relayers: vec![
+								unrewarded_relayer(1, 1, TEST_RELAYER_A),
+								unrewarded_relayer(2, 2, TEST_RELAYER_B)
+							]
+							.into_iter()
+							.collect(),
+							..Default::default()
+						}
+					))),
+					UnrewardedRelayersState {
+						unrewarded_relayer_entries: 2,
+						total_messages: 1,
+						..Default::default()
+					},
+				),
+				Error::<TestRuntime, DefaultInstance>::InvalidUnrewardedRelayersState,
+			);
+		});
+	}
+
+	#[test]
+	fn receive_messages_accepts_single_message_with_invalid_payload() {
+		run_test(|| {
+			let mut invalid_message = message(1, REGULAR_PAYLOAD);
+			invalid_message.data.payload = Vec::new();
+
+			assert_ok!(Pallet::<TestRuntime, DefaultInstance>::receive_messages_proof(
+				Origin::signed(1),
+				TEST_RELAYER_A,
+				Ok(vec![invalid_message]).into(),
+				1,
+				0, // weight may be zero in this case (all messages are improperly encoded)
+			),);
+
+			assert_eq!(
+				InboundLanes::<TestRuntime>::get(&TEST_LANE_ID).last_delivered_nonce(),
+				1,
+			);
+		});
+	}
+
+	#[test]
+	fn receive_messages_accepts_batch_with_message_with_invalid_payload() {
+		run_test(|| {
+			let mut invalid_message = message(2, REGULAR_PAYLOAD);
+			invalid_message.data.payload = Vec::new();
+
+			assert_ok!(Pallet::<TestRuntime, DefaultInstance>::receive_messages_proof(
+				Origin::signed(1),
+				TEST_RELAYER_A,
+				Ok(vec![
+					message(1, REGULAR_PAYLOAD),
+					invalid_message,
+					message(3, REGULAR_PAYLOAD),
+				])
+				.into(),
+				3,
+				REGULAR_PAYLOAD.declared_weight + REGULAR_PAYLOAD.declared_weight,
+			),);
+
+			assert_eq!(
+				InboundLanes::<TestRuntime>::get(&TEST_LANE_ID).last_delivered_nonce(),
+				3,
+			);
+		});
+	}
+
+	#[test]
+	fn storage_message_key_computed_properly() {
+		// If this test fails, then something has been changed in module storage that is breaking all
+		// previously crafted messages proofs.
+		let storage_key = storage_keys::message_key::<DefaultInstance>(&LaneId::Legacy(*b"test"), 42).0;
+		assert_eq!(
+			storage_key,
+			hex!("dd16c784ebd3390a9bc0357c7511ed018a395e6242c6813b196ca31ed0547ea79446af0e09063bd4a7874aef8a997cec746573742a00000000000000").to_vec(),
+			"Unexpected storage key: {}",
+			hex::encode(&storage_key),
+		);
+	}
+
+	#[test]
+	fn outbound_lane_data_key_computed_properly() {
+		// If this test fails, then something has been changed in module storage that is breaking all
+		// previously crafted outbound lane state proofs.
+		let storage_key = storage_keys::outbound_lane_data_key::<DefaultInstance>(&LaneId::Legacy(*b"test")).0;
+		assert_eq!(
+			storage_key,
+			hex!("dd16c784ebd3390a9bc0357c7511ed0196c246acb9b55077390e3ca723a0ca1f44a8995dd50b6657a037a7839304535b74657374").to_vec(),
+			"Unexpected storage key: {}",
+			hex::encode(&storage_key),
+		);
+	}
+
+	#[test]
+	fn inbound_lane_data_key_computed_properly() {
+		// If this test fails, then something has been changed in module storage that is breaking all
+		// previously crafted inbound lane state proofs.
+		let storage_key = storage_keys::inbound_lane_data_key::<DefaultInstance>(&LaneId::Legacy(*b"test")).0;
+		assert_eq!(
+			storage_key,
+			hex!("dd16c784ebd3390a9bc0357c7511ed01e5f83cf83f2127eb47afdc35d6e43fab44a8995dd50b6657a037a7839304535b74657374").to_vec(),
+			"Unexpected storage key: {}",
+			hex::encode(&storage_key),
+		);
+	}
+
+	#[test]
+	fn operating_mode_key_computed_properly() {
+		assert_eq!(
+			storage_keys::operating_mode_key::<DefaultInstance>().0,
+			<PalletOperatingMode<DefaultInstance> as frame_support::storage::StorageValue<_>>::hashed_key().to_vec(),
+		);
+	}
+
+	#[test]
+	fn actual_dispatch_weight_does_not_overlow() {
+		run_test(|| {
+			let message1 = message(1, message_payload(0, Weight::MAX / 2));
+			let message2 = message(2, message_payload(0, Weight::MAX / 2));
+			let message3 = message(3, message_payload(0, Weight::MAX / 2));
+
+			assert_ok!(Pallet::<TestRuntime, DefaultInstance>::receive_messages_proof(
+				Origin::signed(1),
+				TEST_RELAYER_A,
+				// this may cause overflow if source chain storage is invalid
+				Ok(vec![message1, message2, message3]).into(),
+				3,
+				Weight::MAX,
+			));
+			assert_eq!(InboundLanes::<TestRuntime>::get(TEST_LANE_ID).last_delivered_nonce(), 2);
+		});
+	}
+
+	#[test]
+	fn increase_message_fee_fails_if_message_is_already_delivered() {
+		run_test(|| {
+			send_regular_message();
+			receive_messages_delivery_proof();
+
+			assert_noop!(
+				Pallet::<TestRuntime, DefaultInstance>::increase_message_fee(Origin::signed(1), TEST_LANE_ID, 1, 100,),
+				Error::<TestRuntime, DefaultInstance>::MessageIsAlreadyDelivered,
+			);
+		});
+	}
+
+	#[test]
+	fn increase_message_fee_fails_if_message_is_not_yet_sent() {
+		run_test(|| {
+			assert_noop!(
+				Pallet::<TestRuntime, DefaultInstance>::increase_message_fee(Origin::signed(1), TEST_LANE_ID, 1, 100,),
+				Error::<TestRuntime, DefaultInstance>::MessageIsNotYetSent,
+			);
+		});
+	}
+
+	#[test]
+	fn increase_message_fee_fails_if_submitter_cant_pay_additional_fee() {
+		run_test(|| {
+			send_regular_message();
+
+			TestMessageDeliveryAndDispatchPayment::reject_payments();
+
+			assert_noop!(
+				Pallet::<TestRuntime, DefaultInstance>::increase_message_fee(Origin::signed(1), TEST_LANE_ID, 1, 100,),
+				Error::<TestRuntime, DefaultInstance>::FailedToWithdrawMessageFee,
+			);
+		});
+	}
+
+	#[test]
+	fn increase_message_fee_succeeds() {
+		run_test(|| {
+			send_regular_message();
+
+			assert_ok!(Pallet::<TestRuntime, DefaultInstance>::increase_message_fee(
+				Origin::signed(1),
+				TEST_LANE_ID,
+				1,
+				100,
+			),);
+			assert!(TestMessageDeliveryAndDispatchPayment::is_fee_paid(1, 100));
+		});
+	}
+
+	#[test]
+	fn lane_statistics_are_updated() {
+		run_test(|| {
+			send_regular_message();
+			send_regular_message();
+			assert_ok!(Pallet::<TestRuntime, DefaultInstance>::increase_message_fee(
+				Origin::signed(1),
+				TEST_LANE_ID,
+				2,
+				100,
+			));
+			receive_messages_delivery_proof();
+			assert_ok!(Pallet::<TestRuntime>::receive_messages_proof(
+				Origin::signed(1),
+				TEST_RELAYER_A,
+				Ok(vec![message(1, REGULAR_PAYLOAD)]).into(),
+				1,
+				REGULAR_PAYLOAD.declared_weight,
+			));
+
+			assert_eq!(
+				Pallet::<TestRuntime>::lane_statistics(TEST_LANE_ID),
+				LaneStatistics {
+					messages_sent: 2,
+					messages_delivered: 1,
+					messages_confirmed: 1,
+					total_payload_size: 2 * REGULAR_PAYLOAD.encode().len() as u64,
+					total_fees_collected: 2 * REGULAR_PAYLOAD.declared_weight + 100,
+					total_rewards_paid: REGULAR_PAYLOAD.declared_weight,
+				},
+			);
+		});
+	}
+
+	#[test]
+	fn update_message_fee_fails_if_message_is_already_delivered() {
+		run_test(|| {
+			send_regular_message();
+			receive_messages_delivery_proof();
+
+			assert_noop!(
+				Pallet::<TestRuntime, DefaultInstance>::update_message_fee(
+					Origin::signed(1),
+					TEST_LANE_ID,
+					1,
+					REGULAR_PAYLOAD.declared_weight + 100,
+				),
+				Error::<TestRuntime, DefaultInstance>::MessageIsAlreadyDelivered,
+			);
+		});
+	}
+
+	#[test]
+	fn update_message_fee_increases_fee() {
+		run_test(|| {
+			send_regular_message();
+
+			assert_ok!(Pallet::<TestRuntime, DefaultInstance>::update_message_fee(
+				Origin::signed(1),
+				TEST_LANE_ID,
+				1,
+				REGULAR_PAYLOAD.declared_weight + 100,
+			));
+			assert!(TestMessageDeliveryAndDispatchPayment::is_fee_paid(1, 100));
+			assert_eq!(
+				OutboundMessages::<TestRuntime, DefaultInstance>::get(MessageKey {
+					lane_id: TEST_LANE_ID,
+					nonce: 1
+				})
+				.unwrap()
+				.fee,
+				REGULAR_PAYLOAD.declared_weight + 100,
+			);
+		});
+	}
+
+	#[test]
+	fn update_message_fee_decrease_fails_if_submitter_is_unknown() {
+		run_test(|| {
+			send_regular_message();
+			// e.g. the message has been sent before submitters have been recorded
+			OutboundMessageSubmitters::<TestRuntime, DefaultInstance>::remove(MessageKey {
+				lane_id: TEST_LANE_ID,
+				nonce: 1,
+			});
+
+			assert_noop!(
+				Pallet::<TestRuntime, DefaultInstance>::update_message_fee(
+					Origin::root(),
+					TEST_LANE_ID,
+					1,
+					REGULAR_PAYLOAD.declared_weight - 10,
+				),
+				Error::<TestRuntime, DefaultInstance>::MessageSubmitterIsUnknown,
+			);
+		});
+	}
+
+	#[test]
+	fn update_message_fee_decrease_is_restricted_to_owner_or_root() {
+		run_test(|| {
+			send_regular_message();
+			PalletOwner::<TestRuntime>::put(2);
+
+			assert_noop!(
+				Pallet::<TestRuntime, DefaultInstance>::update_message_fee(
+					Origin::signed(1),
+					TEST_LANE_ID,
+					1,
+					REGULAR_PAYLOAD.declared_weight - 10,
+				),
+				DispatchError::BadOrigin,
+			);
+
+			assert_ok!(Pallet::<TestRuntime, DefaultInstance>::update_message_fee(
+				Origin::signed(2),
+				TEST_LANE_ID,
+				1,
+				REGULAR_PAYLOAD.declared_weight - 10,
+			));
+			// the fee is refunded to the message submitter, not to the caller
+			assert!(TestMessageDeliveryAndDispatchPayment::is_fee_refunded(
+				Sender::Signed(1),
+				10
+			));
+
+			assert_ok!(Pallet::<TestRuntime, DefaultInstance>::update_message_fee(
+				Origin::root(),
+				TEST_LANE_ID,
+				1,
+				REGULAR_PAYLOAD.declared_weight - 30,
+			));
+			assert!(TestMessageDeliveryAndDispatchPayment::is_fee_refunded(
+				Sender::Signed(1),
+				20
+			));
+			assert_eq!(
+				OutboundMessages::<TestRuntime, DefaultInstance>::get(MessageKey {
+					lane_id: TEST_LANE_ID,
+					nonce: 1
+				})
+				.unwrap()
+				.fee,
+				REGULAR_PAYLOAD.declared_weight - 30,
+			);
+		});
+	}
+
+	#[test]
+	fn weight_refund_from_receive_messages_proof_works() {
+		run_test(|| {
+			fn submit_with_unspent_weight(
+				nonce: MessageNonce,
+				unspent_weight: Weight,
+				is_prepaid: bool,
+			) -> (Weight, Weight) {
+				let mut payload = REGULAR_PAYLOAD;
+				payload.dispatch_result.unspent_weight = unspent_weight;
+				payload.dispatch_result.dispatch_fee_paid_during_dispatch = !is_prepaid;
+				let proof: TestMessagesProof = Ok(vec![message(nonce, payload)]).into();
+				let messages_count = 1;
+				let pre_dispatch_weight = receive_messages_proof_weight::<TestRuntime, DefaultInstance>(
+					&proof,
+					messages_count,
+					REGULAR_PAYLOAD.declared_weight,
+				);
+				let post_dispatch_weight = Pallet::<TestRuntime>::receive_messages_proof(
+					Origin::signed(1),
+					TEST_RELAYER_A,
+					proof,
+					messages_count,
+					REGULAR_PAYLOAD.declared_weight,
+				)
+				.expect("delivery has failed")
+				.actual_weight
+				.expect("receive_messages_proof always returns Some");
+
+				(pre_dispatch_weight, post_dispatch_weight)
+			}
+
+			// when dispatch is returning `unspent_weight < declared_weight`
+			let (pre, post) = submit_with_unspent_weight(1, 1, false);
+			assert_eq!(post, pre - 1);
+
+			// when dispatch is returning `unspent_weight = declared_weight`
+			let (pre, post) = submit_with_unspent_weight(2, REGULAR_PAYLOAD.declared_weight, false);
+			assert_eq!(post, pre - REGULAR_PAYLOAD.declared_weight);
+
+			// when dispatch is returning `unspent_weight > declared_weight`
+			let (pre, post) = submit_with_unspent_weight(3, REGULAR_PAYLOAD.declared_weight + 1, false);
+			assert_eq!(post, pre - REGULAR_PAYLOAD.declared_weight);
+
+			// when there's no unspent weight
+			let (pre, post) = submit_with_unspent_weight(4, 0, false);
+			assert_eq!(post, pre);
+
+			// when dispatch is returning `unspent_weight < declared_weight` AND message is prepaid
+			let (pre, post) = submit_with_unspent_weight(5, 1, true);
+			assert_eq!(
+				post,
+				pre - 1 - <TestRuntime as Config>::WeightInfo::pay_inbound_dispatch_fee_overhead()
+			);
+		});
+	}
+
+	#[test]
+	fn overestimated_dispatch_weight_is_refunded_by_receive_messages_proof() {
+		run_test(|| {
+			let proof: TestMessagesProof = Ok(vec![message(1, REGULAR_PAYLOAD)]).into();
+			let declared_dispatch_weight = REGULAR_PAYLOAD.declared_weight * 10;
+			let pre_dispatch_weight =
+				receive_messages_proof_weight::<TestRuntime, DefaultInstance>(&proof, 1, declared_dispatch_weight);
+			let post_dispatch_weight = Pallet::<TestRuntime>::receive_messages_proof(
+				Origin::signed(1),
+				TEST_RELAYER_A,
+				proof,
+				1,
+				declared_dispatch_weight,
+			)
+			.expect("delivery has failed")
+			.actual_weight
+			.expect("receive_messages_proof always returns Some");
+
+			assert_eq!(
+				post_dispatch_weight,
+				pre_dispatch_weight - (declared_dispatch_weight - REGULAR_PAYLOAD.declared_weight),
+			);
+		});
+	}
+
+	#[test]
+	fn receive_messages_proof_weight_depends_on_proof_size() {
+		let expected_proof_size =
+			EXPECTED_DEFAULT_MESSAGE_LENGTH + <TestRuntime as Config>::WeightInfo::expected_extra_storage_proof_size();
+		let weight_of_proof =
+			|proof_size| <TestRuntime as Config>::WeightInfo::receive_messages_proof_weight(proof_size, 1, 1, 0);
+
+		// relayer isn't charged for proof bytes that are already covered by the base weight
+		assert_eq!(weight_of_proof(0), weight_of_proof(expected_proof_size));
+		// every extra proof byte is charged using the `receive_message_proofs_with_extra_nodes` coefficient
+		assert_eq!(
+			weight_of_proof(expected_proof_size + 1024),
+			weight_of_proof(expected_proof_size)
+				+ 1024
+					* (<TestRuntime as Config>::WeightInfo::receive_message_proofs_with_extra_nodes(1)
+						- <TestRuntime as Config>::WeightInfo::receive_message_proofs_with_extra_nodes(0)),
+		);
+	}
+
+	#[test]
+	fn receive_messages_proof_weight_includes_inbound_lane_relayers_updates() {
+		run_test(|| {
+			let proof: TestMessagesProof = Ok(vec![message(1, REGULAR_PAYLOAD), message(2, REGULAR_PAYLOAD)]).into();
+			assert_eq!(
+				receive_messages_proof_weight::<TestRuntime, DefaultInstance>(&proof, 2, 0),
+				<TestRuntime as Config>::WeightInfo::receive_messages_proof_weight(proof.size_hint(), 1, 2, 0)
+					+ DbWeight::get().writes(MaxConfirmedRelayerEntriesToPrune::get() + 2 + 2)
+					+ DbWeight::get().reads_writes(1, 1),
+			);
+		});
+	}
+
+	#[test]
+	fn receive_messages_proof_refunds_unused_storage_proof_weight() {
+		type WeightInfo = <TestRuntime as Config>::WeightInfo;
+
+		let expected_proof_size = WeightInfo::expected_messages_proof_size(2);
+		let declared_proof_size = expected_proof_size + 1_000;
+		let stats_with_proof_size = |proof_size| StorageProofStats {
+			proof_size,
+			..Default::default()
+		};
+
+		// nothing is refunded if all declared proof bytes are trie nodes
+		assert_eq!(
+			WeightInfo::receive_messages_proof_storage_proof_refund(
+				declared_proof_size,
+				2,
+				&stats_with_proof_size(declared_proof_size),
+			),
+			0,
+		);
+		// overhead of proof bytes that are not trie nodes is refunded
+		assert_eq!(
+			WeightInfo::receive_messages_proof_storage_proof_refund(
+				declared_proof_size,
+				2,
+				&stats_with_proof_size(expected_proof_size + 400),
+			),
+			WeightInfo::storage_proof_size_overhead(600),
+		);
+		// the whole overhead is refunded if trie nodes fit into the expected proof size
+		assert_eq!(
+			WeightInfo::receive_messages_proof_storage_proof_refund(
+				declared_proof_size,
+				2,
+				&stats_with_proof_size(expected_proof_size / 2),
+			),
+			WeightInfo::storage_proof_size_overhead(1_000),
+		);
+		// refund is never negative
+		assert_eq!(
+			WeightInfo::receive_messages_proof_storage_proof_refund(
+				declared_proof_size,
+				2,
+				&stats_with_proof_size(declared_proof_size + 1_000),
+			),
+			0,
+		);
+	}
+
+	#[test]
+	fn receive_messages_proof_weight_includes_dispatch_overhead_of_every_message() {
+		run_test(|| {
+			let proof: TestMessagesProof = Ok(vec![message(1, REGULAR_PAYLOAD), message(2, REGULAR_PAYLOAD)]).into();
+			let weight_without_overhead = receive_messages_proof_weight::<TestRuntime, DefaultInstance>(&proof, 2, 0);
+
+			TestDispatchOverheadWeight::set(&100);
+			assert_eq!(
+				receive_messages_proof_weight::<TestRuntime, DefaultInstance>(&proof, 2, 0),
+				weight_without_overhead + 2 * 100,
+			);
+		});
+	}
+
+	#[test]
+	fn messages_delivered_callbacks_are_called() {
+		run_test(|| {
+			send_regular_message();
+			send_regular_message();
+			send_regular_message();
+
+			// messages 1+2 are confirmed in 1 tx, message 3 in a separate tx
+			// dispatch of message 2 has failed
+			let mut delivered_messages_1_and_2 = DeliveredMessages::new(1, true);
+			delivered_messages_1_and_2.note_dispatched_message(false);
+			let messages_1_and_2_proof = Ok((
+				TEST_LANE_ID,
+				InboundLaneData {
+					last_confirmed_nonce: 0,
+					relayers: vec![UnrewardedRelayer {
+						relayer: 0,
+						messages: delivered_messages_1_and_2.clone(),
+					}]
+					.into_iter()
+					.collect(),
+				},
+			));
+			let delivered_message_3 = DeliveredMessages::new(3, true);
+			let messages_3_proof = Ok((
+				TEST_LANE_ID,
+				InboundLaneData {
+					last_confirmed_nonce: 0,
+					relayers: vec![UnrewardedRelayer {
+						relayer: 0,
+						messages: delivered_message_3.clone(),
+					}]
+					.into_iter()
+					.collect(),
+				},
+			));
+
+			// first tx with messages 1+2
+			assert_ok!(Pallet::<TestRuntime>::receive_messages_delivery_proof(
+				Origin::signed(1),
+				TestMessagesDeliveryProof(messages_1_and_2_proof),
+				UnrewardedRelayersState {
+					unrewarded_relayer_entries: 1,
+					total_messages: 2,
+					..Default::default()
+				},
+			));
+			// second tx with message 3
+			assert_ok!(Pallet::<TestRuntime>::receive_messages_delivery_proof(
+				Origin::signed(1),
+				TestMessagesDeliveryProof(messages_3_proof),
+				UnrewardedRelayersState {
+					unrewarded_relayer_entries: 1,
+					total_messages: 1,
+					..Default::default()
+				},
+			));
+
+			// ensure that both callbacks have been called twice: for 1+2, then for 3
+			TestOnDeliveryConfirmed1::ensure_called(&TEST_LANE_ID, &delivered_messages_1_and_2);
+			TestOnDeliveryConfirmed1::ensure_called(&TEST_LANE_ID, &delivered_message_3);
+			TestOnDeliveryConfirmed2::ensure_called(&TEST_LANE_ID, &delivered_messages_1_and_2);
+			TestOnDeliveryConfirmed2::ensure_called(&TEST_LANE_ID, &delivered_message_3);
+		});
+	}
+
+	fn confirm_3_messages_delivery() -> (Weight, Weight) {
+		send_regular_message();
+		send_regular_message();
+		send_regular_message();
+
+		let proof = TestMessagesDeliveryProof(Ok((
+			TEST_LANE_ID,
+			InboundLaneData {
+				last_confirmed_nonce: 0,
+				relayers: vec![unrewarded_relayer(1, 3, TEST_RELAYER_A)].into_iter().collect(),
+			},
+		)));
+		let relayers_state = UnrewardedRelayersState {
+			unrewarded_relayer_entries: 1,
+			total_messages: 3,
+			..Default::default()
+		};
+		let pre_dispatch_weight =
+			receive_messages_delivery_proof_weight::<TestRuntime, DefaultInstance>(&proof, &relayers_state);
+		let post_dispatch_weight =
+			Pallet::<TestRuntime>::receive_messages_delivery_proof(Origin::signed(1), proof, relayers_state)
+				.expect("confirmation has failed")
+				.actual_weight
+				.expect("receive_messages_delivery_proof always returns Some");
+		(pre_dispatch_weight, post_dispatch_weight)
+	}
+
+	#[test]
+	fn receive_messages_delivery_proof_refunds_zero_weight() {
+		run_test(|| {
+			let (pre_dispatch_weight, post_dispatch_weight) = confirm_3_messages_delivery();
+			assert_eq!(pre_dispatch_weight, post_dispatch_weight);
+		});
+	}
+
+	#[test]
+	fn receive_messages_delivery_proof_refunds_non_zero_weight() {
+		run_test(|| {
+			TestOnDeliveryConfirmed1::set_consumed_weight_per_message(crate::mock::DbWeight::get().writes(1));
+
+			let (pre_dispatch_weight, post_dispatch_weight) = confirm_3_messages_delivery();
+			assert_eq!(
+				pre_dispatch_weight.saturating_sub(post_dispatch_weight),
+				crate::mock::DbWeight::get().reads(1) * 3
+			);
+		});
+	}
+
+	#[test]
+	#[should_panic]
+	fn receive_messages_panics_in_debug_mode_if_callback_is_wrong() {
+		run_test(|| {
+			TestOnDeliveryConfirmed1::set_consumed_weight_per_message(crate::mock::DbWeight::get().reads_writes(2, 2));
+			confirm_3_messages_delivery()
+		});
+	}
+
+	#[test]
+	fn receive_messages_delivery_proof_rejects_proof_if_trying_to_confirm_more_messages_than_expected() {
+		run_test(|| {
+			// send message first to be able to check that delivery_proof fails later
+			send_regular_message();
+
+			// 1) InboundLaneData declares that the `last_confirmed_nonce` is 1;
+			// 2) InboundLaneData has no entries => `InboundLaneData::last_delivered_nonce()`
+			//    returns `last_confirmed_nonce`;
+			// 3) it means that we're going to confirm delivery of messages 1..=1;
+			// 4) so the number of declared messages (see `UnrewardedRelayersState`) is `0` and
+			//    numer of actually confirmed messages is `1`.
+			assert_noop!(
+				Pallet::<TestRuntime>::receive_messages_delivery_proof(
+					Origin::signed(1),
+					TestMessagesDeliveryProof(Ok((
+						TEST_LANE_ID,
+						InboundLaneData {
+							last_confirmed_nonce: 1,
+							relayers: Default::default(),
+						},
+					))),
+					UnrewardedRelayersState::default(),
+				),
+				Error::<TestRuntime, DefaultInstance>::TryingToConfirmMoreMessagesThanExpected,
+			);
+		});
+	}
+
+	#[test]
+	fn increase_message_fee_weight_depends_on_message_size() {
+		run_test(|| {
+			let mut small_payload = message_payload(0, 100);
+			let mut large_payload = message_payload(1, 100);
+			small_payload.extra = vec![1; 100];
+			large_payload.extra = vec![2; 16_384];
+
+			assert_ok!(Pallet::<TestRuntime>::send_message(
+				Origin::signed(1),
+				TEST_LANE_ID,
+				small_payload,
+				100,
+			));
+			assert_ok!(Pallet::<TestRuntime>::send_message(
+				Origin::signed(1),
+				TEST_LANE_ID,
+				large_payload,
+				100,
+			));
+
+			let small_weight = Pallet::<TestRuntime>::increase_message_fee(Origin::signed(1), TEST_LANE_ID, 1, 1)
+				.expect("increase_message_fee has failed")
+				.actual_weight
+				.expect("increase_message_fee always returns Some");
+
+			let large_weight = Pallet::<TestRuntime>::increase_message_fee(Origin::signed(1), TEST_LANE_ID, 2, 1)
+				.expect("increase_message_fee has failed")
+				.actual_weight
+				.expect("increase_message_fee always returns Some");
+
+			assert!(
+				large_weight > small_weight,
+				"Actual post-dispatch weigth for larger message {} must be larger than {} for small message",
+				large_weight,
+				small_weight,
+			);
+		});
+	}
+
+	#[test]
+	fn weight_is_refunded_for_messages_that_are_not_pruned() {
+		run_test(|| {
+			// send first MAX messages - no messages are pruned
+			let max_messages_to_prune = crate::mock::MaxMessagesToPruneAtOnce::get();
+			let when_zero_messages_are_pruned = send_regular_message();
+			let mut delivered_messages = DeliveredMessages::new(1, true);
+			for _ in 1..max_messages_to_prune {
+				assert_eq!(send_regular_message(), when_zero_messages_are_pruned);
+				delivered_messages.note_dispatched_message(true);
+			}
+
+			// confirm delivery of all sent messages
+			assert_ok!(Pallet::<TestRuntime>::receive_messages_delivery_proof(
+				Origin::signed(1),
+				TestMessagesDeliveryProof(Ok((
+					TEST_LANE_ID,
+					InboundLaneData {
+						last_confirmed_nonce: 1,
+						relayers: vec![UnrewardedRelayer {
+							relayer: 0,
+							messages: delivered_messages,
+						}]
+						.into_iter()
+						.collect(),
+					},
+				))),
+				UnrewardedRelayersState {
+					unrewarded_relayer_entries: 1,
+					total_messages: max_messages_to_prune,
+					..Default::default()
+				},
+			));
+
+			// when next message is sent, MAX messages are pruned (together with their submitters)
+			let first_message_key = MessageKey {
+				lane_id: TEST_LANE_ID,
+				nonce: 1,
+			};
+			assert_eq!(
+				OutboundMessageSubmitters::<TestRuntime, DefaultInstance>::get(&first_message_key),
+				Some(Sender::Signed(1)),
+			);
+			let weight_when_max_messages_are_pruned = send_regular_message();
+			assert_eq!(
+				weight_when_max_messages_are_pruned,
+				when_zero_messages_are_pruned + crate::mock::DbWeight::get().writes(2 * max_messages_to_prune),
+			);
+			assert_eq!(
+				OutboundMessageSubmitters::<TestRuntime, DefaultInstance>::get(&first_message_key),
+				None,
+			);
+		});
+	}
+
+	fn send_messages_and_prepare_stalled_lane_proof() -> (TestMessagesDeliveryProof, UnrewardedRelayersState) {
+		let max_entries = crate::mock::MaxUnrewardedRelayerEntriesAtInboundLane::get();
+		for _ in 0..max_entries {
+			assert_ok!(Pallet::<TestRuntime>::send_message(
+				Origin::signed(1),
+				TEST_LANE_ID,
+				REGULAR_PAYLOAD,
+				1000,
+			));
+		}
+
+		(
+			TestMessagesDeliveryProof(Ok((
+				TEST_LANE_ID,
+				InboundLaneData {
+					relayers: (1..=max_entries)
+						.map(|nonce| unrewarded_relayer(nonce, nonce, TEST_RELAYER_A))
+						.collect(),
+					..Default::default()
+				},
+			))),
+			UnrewardedRelayersState {
+				unrewarded_relayer_entries: max_entries,
+				total_messages: max_entries,
+				..Default::default()
+			},
+		)
+	}
+
+	#[test]
+	fn claim_stalled_confirmation_bounty_works() {
+		run_test(|| {
+			let (proof, relayers_state) = send_messages_and_prepare_stalled_lane_proof();
+
+			// first call only reports the lane as stalled
+			get_ready_for_events();
+			assert_ok!(Pallet::<TestRuntime>::claim_stalled_confirmation_bounty(
+				Origin::signed(1),
+				proof.clone(),
+				relayers_state.clone(),
+			));
+			assert_eq!(
+				UnrewardedRelayersLimitReachedAt::<TestRuntime>::get(&TEST_LANE_ID),
+				Some(1)
+			);
+			assert_eq!(
+				OutboundLanes::<DefaultInstance>::get(&TEST_LANE_ID).latest_received_nonce,
+				0,
+			);
+			assert_eq!(
+				System::<TestRuntime>::events(),
+				vec![EventRecord {
+					phase: Phase::Initialization,
+					event: TestEvent::Messages(RawEvent::StalledLaneReported(TEST_LANE_ID)),
+					topics: vec![],
+				}],
+			);
+
+			// bounty may not be claimed until stalled confirmation period ends
+			System::<TestRuntime>::set_block_number(1 + crate::mock::StalledConfirmationPeriod::get() - 1);
 			assert_noop!(
-				Pallet::<TestRuntime>::receive_messages_delivery_proof(
+				Pallet::<TestRuntime>::claim_stalled_confirmation_bounty(
 					Origin::signed(1),
-					TestMessagesDeliveryProof(Err(())),
-					Default::default(),
+					proof.clone(),
+					relayers_state.clone(),
 				),
-				Error::<TestRuntime, DefaultInstance>::InvalidMessagesDeliveryProof,
+				Error::<TestRuntime, DefaultInstance>::StalledConfirmationBountyIsNotYetAvailable,
+			);
+
+			// and then anyone may confirm delivery and claim bounty
+			System::<TestRuntime>::set_block_number(1 + crate::mock::StalledConfirmationPeriod::get());
+			assert_ok!(Pallet::<TestRuntime>::claim_stalled_confirmation_bounty(
+				Origin::signed(1),
+				proof,
+				relayers_state,
+			));
+			let messages = crate::mock::MaxUnrewardedRelayerEntriesAtInboundLane::get();
+			let bounty = crate::mock::StalledConfirmationBountyPerMessage::get() * messages;
+			assert_eq!(
+				OutboundLanes::<DefaultInstance>::get(&TEST_LANE_ID).latest_received_nonce,
+				messages,
 			);
+			assert_eq!(
+				UnrewardedRelayersLimitReachedAt::<TestRuntime>::get(&TEST_LANE_ID),
+				None
+			);
+			assert!(TestMessageDeliveryAndDispatchPayment::is_reward_paid(
+				TEST_RELAYER_A,
+				1000 * messages - bounty,
+			));
+			assert!(TestMessageDeliveryAndDispatchPayment::is_reward_paid(1, bounty));
+			assert!(System::<TestRuntime>::events().contains(&EventRecord {
+				phase: Phase::Initialization,
+				event: TestEvent::Messages(RawEvent::StalledConfirmationBountyPaid(TEST_LANE_ID, 1, bounty)),
+				topics: vec![],
+			}));
 		});
 	}
 
 	#[test]
-	fn receive_messages_delivery_proof_rejects_proof_if_declared_relayers_state_is_invalid() {
+	fn claim_stalled_confirmation_bounty_rejects_lane_that_is_not_stalled() {
 		run_test(|| {
-			// when number of relayers entires is invalid
-			assert_noop!(
-				Pallet::<TestRuntime>::receive_messages_delivery_proof(
-					Origin::signed(1),
-					TestMessagesDeliveryProof(Ok((
-						TEST_LANE_ID,
-						InboundLaneData {
-							relayers: vec![
-								unrewarded_relayer(1, 1, TEST_RELAYER_A),
-								unrewarded_relayer(2, 2, TEST_RELAYER_B)
-							]
-							.into_iter()
-							.collect(),
-							..Default::default()
-						}
-					))),
-					UnrewardedRelayersState {
-						unrewarded_relayer_entries: 1,
-						total_messages: 2,
-						..Default::default()
-					},
-				),
-				Error::<TestRuntime, DefaultInstance>::InvalidUnrewardedRelayersState,
-			);
+			send_regular_message();
 
-			// when number of messages is invalid
+			// unrewarded relayers limit is not reached
 			assert_noop!(
-				Pallet::<TestRuntime>::receive_messages_delivery_proof(
+				Pallet::<TestRuntime>::claim_stalled_confirmation_bounty(
 					Origin::signed(1),
 					TestMessagesDeliveryProof(Ok((
 						TEST_LANE_ID,
 						InboundLaneData {
-							relayers: vec![
-								unrewarded_relayer(1, 1, TEST_RELAYER_A),
-								unrewarded_relayer(2, 2, TEST_RELAYER_B)
-							]
-							.into_iter()
-							.collect(),
+							relayers: vec![unrewarded_relayer(1, 1, TEST_RELAYER_A)].into_iter().collect(),
 							..Default::default()
 						}
 					))),
 					UnrewardedRelayersState {
-						unrewarded_relayer_entries: 2,
+						unrewarded_relayer_entries: 1,
 						total_messages: 1,
 						..Default::default()
 					},
 				),
-				Error::<TestRuntime, DefaultInstance>::InvalidUnrewardedRelayersState,
+				Error::<TestRuntime, DefaultInstance>::LaneIsNotStalled,
 			);
 		});
 	}
 
 	#[test]
-	fn receive_messages_accepts_single_message_with_invalid_payload() {
+	fn claim_stalled_confirmation_bounty_rejects_lane_without_messages_to_confirm() {
 		run_test(|| {
-			let mut invalid_message = message(1, REGULAR_PAYLOAD);
-			invalid_message.data.payload = Vec::new();
-
-			assert_ok!(Pallet::<TestRuntime, DefaultInstance>::receive_messages_proof(
+			let (proof, relayers_state) = send_messages_and_prepare_stalled_lane_proof();
+			assert_ok!(Pallet::<TestRuntime>::receive_messages_delivery_proof(
 				Origin::signed(1),
-				TEST_RELAYER_A,
-				Ok(vec![invalid_message]).into(),
-				1,
-				0, // weight may be zero in this case (all messages are improperly encoded)
-			),);
+				proof.clone(),
+				relayers_state.clone(),
+			));
 
-			assert_eq!(
-				InboundLanes::<TestRuntime>::get(&TEST_LANE_ID).last_delivered_nonce(),
-				1,
+			assert_noop!(
+				Pallet::<TestRuntime>::claim_stalled_confirmation_bounty(Origin::signed(1), proof, relayers_state),
+				Error::<TestRuntime, DefaultInstance>::LaneIsNotStalled,
 			);
 		});
 	}
 
 	#[test]
-	fn receive_messages_accepts_batch_with_message_with_invalid_payload() {
+	fn regular_delivery_confirmation_clears_stalled_lane_report() {
 		run_test(|| {
-			let mut invalid_message = message(2, REGULAR_PAYLOAD);
-			invalid_message.data.payload = Vec::new();
-
-			assert_ok!(Pallet::<TestRuntime, DefaultInstance>::receive_messages_proof(
+			let (proof, relayers_state) = send_messages_and_prepare_stalled_lane_proof();
+			assert_ok!(Pallet::<TestRuntime>::claim_stalled_confirmation_bounty(
 				Origin::signed(1),
-				TEST_RELAYER_A,
-				Ok(vec![
-					message(1, REGULAR_PAYLOAD),
-					invalid_message,
-					message(3, REGULAR_PAYLOAD),
-				])
-				.into(),
-				3,
-				REGULAR_PAYLOAD.declared_weight + REGULAR_PAYLOAD.declared_weight,
-			),);
+				proof.clone(),
+				relayers_state.clone(),
+			));
+			assert!(UnrewardedRelayersLimitReachedAt::<TestRuntime>::get(&TEST_LANE_ID).is_some());
 
+			assert_ok!(Pallet::<TestRuntime>::receive_messages_delivery_proof(
+				Origin::signed(1),
+				proof,
+				relayers_state,
+			));
 			assert_eq!(
-				InboundLanes::<TestRuntime>::get(&TEST_LANE_ID).last_delivered_nonce(),
-				3,
+				UnrewardedRelayersLimitReachedAt::<TestRuntime>::get(&TEST_LANE_ID),
+				None
 			);
+			assert!(TestMessageDeliveryAndDispatchPayment::is_reward_paid(
+				TEST_RELAYER_A,
+				1000 * crate::mock::MaxUnrewardedRelayerEntriesAtInboundLane::get(),
+			));
 		});
 	}
 
-	#[test]
-	fn storage_message_key_computed_properly() {
-		// If this test fails, then something has been changed in module storage that is breaking all
-		// previously crafted messages proofs.
-		let storage_key = storage_keys::message_key::<DefaultInstance>(&*b"test", 42).0;
-		assert_eq!(
-			storage_key,
-			hex!("dd16c784ebd3390a9bc0357c7511ed018a395e6242c6813b196ca31ed0547ea79446af0e09063bd4a7874aef8a997cec746573742a00000000000000").to_vec(),
-			"Unexpected storage key: {}",
-			hex::encode(&storage_key),
-		);
+	fn relayer_signature(relayer: crate::mock::TestRelayer, proof: &TestMessagesProof) -> TestSignature {
+		TestSignature(
+			relayer,
+			Pallet::<TestRuntime>::unsigned_delivery_signature_payload(proof),
+		)
 	}
 
-	#[test]
-	fn outbound_lane_data_key_computed_properly() {
-		// If this test fails, then something has been changed in module storage that is breaking all
-		// previously crafted outbound lane state proofs.
-		let storage_key = storage_keys::outbound_lane_data_key::<DefaultInstance>(&*b"test").0;
-		assert_eq!(
-			storage_key,
-			hex!("dd16c784ebd3390a9bc0357c7511ed0196c246acb9b55077390e3ca723a0ca1f44a8995dd50b6657a037a7839304535b74657374").to_vec(),
-			"Unexpected storage key: {}",
-			hex::encode(&storage_key),
-		);
+	fn receive_messages_proof_unsigned(
+		origin: Origin,
+		relayer: crate::mock::TestRelayer,
+		proof: TestMessagesProof,
+		messages_count: u32,
+		dispatch_weight: Weight,
+	) -> DispatchResultWithPostInfo {
+		Pallet::<TestRuntime>::receive_messages_proof_unsigned(
+			origin,
+			relayer,
+			relayer_signature(relayer, &proof),
+			proof,
+			messages_count,
+			dispatch_weight,
+		)
 	}
 
-	#[test]
-	fn inbound_lane_data_key_computed_properly() {
-		// If this test fails, then something has been changed in module storage that is breaking all
-		// previously crafted inbound lane state proofs.
-		let storage_key = storage_keys::inbound_lane_data_key::<DefaultInstance>(&*b"test").0;
-		assert_eq!(
-			storage_key,
-			hex!("dd16c784ebd3390a9bc0357c7511ed01e5f83cf83f2127eb47afdc35d6e43fab44a8995dd50b6657a037a7839304535b74657374").to_vec(),
-			"Unexpected storage key: {}",
-			hex::encode(&storage_key),
-		);
+	fn unsigned_delivery_call(
+		relayer: crate::mock::TestRelayer,
+		proof: TestMessagesProof,
+		messages_count: u32,
+		dispatch_weight: Weight,
+	) -> Call<TestRuntime> {
+		Call::<TestRuntime>::receive_messages_proof_unsigned(
+			relayer,
+			relayer_signature(relayer, &proof),
+			proof,
+			messages_count,
+			dispatch_weight,
+		)
 	}
 
 	#[test]
-	fn actual_dispatch_weight_does_not_overlow() {
+	fn receive_messages_proof_unsigned_works() {
 		run_test(|| {
-			let message1 = message(1, message_payload(0, Weight::MAX / 2));
-			let message2 = message(2, message_payload(0, Weight::MAX / 2));
-			let message3 = message(3, message_payload(0, Weight::MAX / 2));
-
-			assert_ok!(Pallet::<TestRuntime, DefaultInstance>::receive_messages_proof(
-				Origin::signed(1),
+			assert_ok!(receive_messages_proof_unsigned(
+				Origin::none(),
 				TEST_RELAYER_A,
-				// this may cause overflow if source chain storage is invalid
-				Ok(vec![message1, message2, message3]).into(),
-				3,
-				Weight::MAX,
+				Ok(vec![message(1, REGULAR_PAYLOAD)]).into(),
+				1,
+				REGULAR_PAYLOAD.declared_weight,
 			));
-			assert_eq!(InboundLanes::<TestRuntime>::get(TEST_LANE_ID).last_delivered_nonce(), 2);
-		});
-	}
-
-	#[test]
-	fn increase_message_fee_fails_if_message_is_already_delivered() {
-		run_test(|| {
-			send_regular_message();
-			receive_messages_delivery_proof();
 
-			assert_noop!(
-				Pallet::<TestRuntime, DefaultInstance>::increase_message_fee(Origin::signed(1), TEST_LANE_ID, 1, 100,),
-				Error::<TestRuntime, DefaultInstance>::MessageIsAlreadyDelivered,
-			);
-		});
-	}
-
-	#[test]
-	fn increase_message_fee_fails_if_message_is_not_yet_sent() {
-		run_test(|| {
-			assert_noop!(
-				Pallet::<TestRuntime, DefaultInstance>::increase_message_fee(Origin::signed(1), TEST_LANE_ID, 1, 100,),
-				Error::<TestRuntime, DefaultInstance>::MessageIsNotYetSent,
+			assert_eq!(InboundLanes::<TestRuntime>::get(TEST_LANE_ID).last_delivered_nonce(), 1);
+			assert_eq!(
+				Pallet::<TestRuntime>::inbound_unrewarded_relayers_state(TEST_LANE_ID).unrewarded_relayer_entries,
+				1,
 			);
 		});
 	}
 
 	#[test]
-	fn increase_message_fee_fails_if_submitter_cant_pay_additional_fee() {
+	fn receive_messages_proof_unsigned_rejects_signed_origin() {
 		run_test(|| {
-			send_regular_message();
-
-			TestMessageDeliveryAndDispatchPayment::reject_payments();
-
 			assert_noop!(
-				Pallet::<TestRuntime, DefaultInstance>::increase_message_fee(Origin::signed(1), TEST_LANE_ID, 1, 100,),
-				Error::<TestRuntime, DefaultInstance>::FailedToWithdrawMessageFee,
+				receive_messages_proof_unsigned(
+					Origin::signed(1),
+					TEST_RELAYER_A,
+					Ok(vec![message(1, REGULAR_PAYLOAD)]).into(),
+					1,
+					REGULAR_PAYLOAD.declared_weight,
+				),
+				DispatchError::BadOrigin,
 			);
 		});
 	}
 
 	#[test]
-	fn increase_message_fee_succeeds() {
-		run_test(|| {
-			send_regular_message();
-
-			assert_ok!(Pallet::<TestRuntime, DefaultInstance>::increase_message_fee(
-				Origin::signed(1),
-				TEST_LANE_ID,
-				1,
-				100,
-			),);
-			assert!(TestMessageDeliveryAndDispatchPayment::is_fee_paid(1, 100));
-		});
-	}
+	fn unsigned_messages_delivery_priority_depends_on_new_messages_count() {
+		use frame_support::unsigned::ValidateUnsigned;
 
-	#[test]
-	fn weight_refund_from_receive_messages_proof_works() {
 		run_test(|| {
-			fn submit_with_unspent_weight(
-				nonce: MessageNonce,
-				unspent_weight: Weight,
-				is_prepaid: bool,
-			) -> (Weight, Weight) {
-				let mut payload = REGULAR_PAYLOAD;
-				payload.dispatch_result.unspent_weight = unspent_weight;
-				payload.dispatch_result.dispatch_fee_paid_during_dispatch = !is_prepaid;
-				let proof = Ok(vec![message(nonce, payload)]).into();
-				let messages_count = 1;
-				let pre_dispatch_weight = <TestRuntime as Config>::WeightInfo::receive_messages_proof_weight(
-					&proof,
-					messages_count,
-					REGULAR_PAYLOAD.declared_weight,
-				);
-				let post_dispatch_weight = Pallet::<TestRuntime>::receive_messages_proof(
-					Origin::signed(1),
-					TEST_RELAYER_A,
-					proof,
-					messages_count,
-					REGULAR_PAYLOAD.declared_weight,
+			let validate = |messages: Vec<bp_messages::Message<crate::mock::TestMessageFee>>| {
+				let messages_count = messages.len() as u32;
+				Pallet::<TestRuntime>::validate_unsigned(
+					TransactionSource::External,
+					&unsigned_delivery_call(
+						TEST_RELAYER_A,
+						Ok(messages).into(),
+						messages_count,
+						REGULAR_PAYLOAD.declared_weight * messages_count as Weight,
+					),
 				)
-				.expect("delivery has failed")
-				.actual_weight
-				.expect("receive_messages_proof always returns Some");
-
-				(pre_dispatch_weight, post_dispatch_weight)
-			}
-
-			// when dispatch is returning `unspent_weight < declared_weight`
-			let (pre, post) = submit_with_unspent_weight(1, 1, false);
-			assert_eq!(post, pre - 1);
-
-			// when dispatch is returning `unspent_weight = declared_weight`
-			let (pre, post) = submit_with_unspent_weight(2, REGULAR_PAYLOAD.declared_weight, false);
-			assert_eq!(post, pre - REGULAR_PAYLOAD.declared_weight);
-
-			// when dispatch is returning `unspent_weight > declared_weight`
-			let (pre, post) = submit_with_unspent_weight(3, REGULAR_PAYLOAD.declared_weight + 1, false);
-			assert_eq!(post, pre - REGULAR_PAYLOAD.declared_weight);
+			};
 
-			// when there's no unspent weight
-			let (pre, post) = submit_with_unspent_weight(4, 0, false);
-			assert_eq!(post, pre);
+			assert_eq!(
+				validate(vec![message(1, REGULAR_PAYLOAD)]).map(|tx| tx.priority),
+				Ok(crate::mock::UnsignedPriority::get() + 1),
+			);
+			assert_eq!(
+				validate(vec![message(1, REGULAR_PAYLOAD), message(2, REGULAR_PAYLOAD)]).map(|tx| tx.priority),
+				Ok(crate::mock::UnsignedPriority::get() + 2),
+			);
 
-			// when dispatch is returning `unspent_weight < declared_weight` AND message is prepaid
-			let (pre, post) = submit_with_unspent_weight(5, 1, true);
+			// when the first message is delivered, only the second message is new
+			assert_ok!(receive_messages_proof_unsigned(
+				Origin::none(),
+				TEST_RELAYER_A,
+				Ok(vec![message(1, REGULAR_PAYLOAD)]).into(),
+				1,
+				REGULAR_PAYLOAD.declared_weight,
+			));
 			assert_eq!(
-				post,
-				pre - 1 - <TestRuntime as Config>::WeightInfo::pay_inbound_dispatch_fee_overhead()
+				validate(vec![message(1, REGULAR_PAYLOAD), message(2, REGULAR_PAYLOAD)]).map(|tx| tx.priority),
+				Ok(crate::mock::UnsignedPriority::get() + 1),
 			);
 		});
 	}
 
 	#[test]
-	fn messages_delivered_callbacks_are_called() {
-		run_test(|| {
-			send_regular_message();
-			send_regular_message();
-			send_regular_message();
-
-			// messages 1+2 are confirmed in 1 tx, message 3 in a separate tx
-			// dispatch of message 2 has failed
-			let mut delivered_messages_1_and_2 = DeliveredMessages::new(1, true);
-			delivered_messages_1_and_2.note_dispatched_message(false);
-			let messages_1_and_2_proof = Ok((
-				TEST_LANE_ID,
-				InboundLaneData {
-					last_confirmed_nonce: 0,
-					relayers: vec![UnrewardedRelayer {
-						relayer: 0,
-						messages: delivered_messages_1_and_2.clone(),
-					}]
-					.into_iter()
-					.collect(),
-				},
-			));
-			let delivered_message_3 = DeliveredMessages::new(3, true);
-			let messages_3_proof = Ok((
-				TEST_LANE_ID,
-				InboundLaneData {
-					last_confirmed_nonce: 0,
-					relayers: vec![UnrewardedRelayer {
-						relayer: 0,
-						messages: delivered_message_3.clone(),
-					}]
-					.into_iter()
-					.collect(),
-				},
-			));
+	fn unsigned_messages_delivery_is_rejected_if_it_brings_no_new_messages() {
+		use frame_support::unsigned::ValidateUnsigned;
 
-			// first tx with messages 1+2
-			assert_ok!(Pallet::<TestRuntime>::receive_messages_delivery_proof(
-				Origin::signed(1),
-				TestMessagesDeliveryProof(messages_1_and_2_proof),
-				UnrewardedRelayersState {
-					unrewarded_relayer_entries: 1,
-					total_messages: 2,
-					..Default::default()
-				},
-			));
-			// second tx with message 3
-			assert_ok!(Pallet::<TestRuntime>::receive_messages_delivery_proof(
-				Origin::signed(1),
-				TestMessagesDeliveryProof(messages_3_proof),
-				UnrewardedRelayersState {
-					unrewarded_relayer_entries: 1,
-					total_messages: 1,
-					..Default::default()
-				},
+		run_test(|| {
+			assert_ok!(receive_messages_proof_unsigned(
+				Origin::none(),
+				TEST_RELAYER_A,
+				Ok(vec![message(1, REGULAR_PAYLOAD)]).into(),
+				1,
+				REGULAR_PAYLOAD.declared_weight,
 			));
 
-			// ensure that both callbacks have been called twice: for 1+2, then for 3
-			TestOnDeliveryConfirmed1::ensure_called(&TEST_LANE_ID, &delivered_messages_1_and_2);
-			TestOnDeliveryConfirmed1::ensure_called(&TEST_LANE_ID, &delivered_message_3);
-			TestOnDeliveryConfirmed2::ensure_called(&TEST_LANE_ID, &delivered_messages_1_and_2);
-			TestOnDeliveryConfirmed2::ensure_called(&TEST_LANE_ID, &delivered_message_3);
+			assert_eq!(
+				Pallet::<TestRuntime>::validate_unsigned(
+					TransactionSource::External,
+					&unsigned_delivery_call(
+						TEST_RELAYER_A,
+						Ok(vec![message(1, REGULAR_PAYLOAD)]).into(),
+						1,
+						REGULAR_PAYLOAD.declared_weight,
+					),
+				),
+				InvalidTransaction::Stale.into(),
+			);
 		});
 	}
 
-	fn confirm_3_messages_delivery() -> (Weight, Weight) {
-		send_regular_message();
-		send_regular_message();
-		send_regular_message();
-
-		let proof = TestMessagesDeliveryProof(Ok((
-			TEST_LANE_ID,
-			InboundLaneData {
-				last_confirmed_nonce: 0,
-				relayers: vec![unrewarded_relayer(1, 3, TEST_RELAYER_A)].into_iter().collect(),
-			},
-		)));
-		let relayers_state = UnrewardedRelayersState {
-			unrewarded_relayer_entries: 1,
-			total_messages: 3,
-			..Default::default()
-		};
-		let pre_dispatch_weight = <TestRuntime as Config>::WeightInfo::receive_messages_delivery_proof_weight(
-			&proof,
-			&relayers_state,
-			crate::mock::DbWeight::get(),
-		);
-		let post_dispatch_weight =
-			Pallet::<TestRuntime>::receive_messages_delivery_proof(Origin::signed(1), proof, relayers_state)
-				.expect("confirmation has failed")
-				.actual_weight
-				.expect("receive_messages_delivery_proof always returns Some");
-		(pre_dispatch_weight, post_dispatch_weight)
-	}
-
 	#[test]
-	fn receive_messages_delivery_proof_refunds_zero_weight() {
+	fn unsigned_messages_delivery_is_rejected_if_proof_is_invalid() {
+		use frame_support::unsigned::ValidateUnsigned;
+
 		run_test(|| {
-			let (pre_dispatch_weight, post_dispatch_weight) = confirm_3_messages_delivery();
-			assert_eq!(pre_dispatch_weight, post_dispatch_weight);
+			assert_eq!(
+				Pallet::<TestRuntime>::validate_unsigned(
+					TransactionSource::External,
+					&unsigned_delivery_call(TEST_RELAYER_A, Err(()).into(), 1, REGULAR_PAYLOAD.declared_weight,),
+				),
+				InvalidTransaction::BadProof.into(),
+			);
 		});
 	}
 
 	#[test]
-	fn receive_messages_delivery_proof_refunds_non_zero_weight() {
+	fn unsigned_messages_delivery_is_rejected_if_relayer_signature_is_invalid() {
+		use frame_support::unsigned::ValidateUnsigned;
+
 		run_test(|| {
-			TestOnDeliveryConfirmed1::set_consumed_weight_per_message(crate::mock::DbWeight::get().writes(1));
+			let proof: TestMessagesProof = Ok(vec![message(1, REGULAR_PAYLOAD)]).into();
+			let validate = |relayer_signature| {
+				Pallet::<TestRuntime>::validate_unsigned(
+					TransactionSource::External,
+					&Call::<TestRuntime>::receive_messages_proof_unsigned(
+						TEST_RELAYER_A,
+						relayer_signature,
+						proof.clone(),
+						1,
+						REGULAR_PAYLOAD.declared_weight,
+					),
+				)
+			};
 
-			let (pre_dispatch_weight, post_dispatch_weight) = confirm_3_messages_delivery();
+			// signature of other relayer
 			assert_eq!(
-				pre_dispatch_weight.saturating_sub(post_dispatch_weight),
-				crate::mock::DbWeight::get().reads(1) * 3
+				validate(relayer_signature(TEST_RELAYER_B, &proof)),
+				InvalidTransaction::BadProof.into(),
+			);
+			// signature of other proof
+			let other_proof: TestMessagesProof =
+				Ok(vec![message(1, REGULAR_PAYLOAD), message(2, REGULAR_PAYLOAD)]).into();
+			assert_eq!(
+				validate(relayer_signature(TEST_RELAYER_A, &other_proof)),
+				InvalidTransaction::BadProof.into(),
 			);
+			// valid signature
+			assert!(validate(relayer_signature(TEST_RELAYER_A, &proof)).is_ok());
 		});
 	}
 
+	fn validate_unsigned_delivery(
+		relayer: crate::mock::TestRelayer,
+		messages: Vec<bp_messages::Message<crate::mock::TestMessageFee>>,
+		dispatch_weight: Weight,
+	) -> TransactionValidity {
+		use frame_support::unsigned::ValidateUnsigned;
+
+		let messages_count = messages.len() as u32;
+		Pallet::<TestRuntime>::validate_unsigned(
+			TransactionSource::External,
+			&unsigned_delivery_call(relayer, Ok(messages).into(), messages_count, dispatch_weight),
+		)
+	}
+
 	#[test]
-	#[should_panic]
-	fn receive_messages_panics_in_debug_mode_if_callback_is_wrong() {
+	fn unsigned_messages_delivery_has_configured_longevity() {
 		run_test(|| {
-			TestOnDeliveryConfirmed1::set_consumed_weight_per_message(crate::mock::DbWeight::get().reads_writes(2, 2));
-			confirm_3_messages_delivery()
+			assert_eq!(
+				validate_unsigned_delivery(
+					TEST_RELAYER_A,
+					vec![message(1, REGULAR_PAYLOAD)],
+					REGULAR_PAYLOAD.declared_weight
+				)
+				.map(|tx| tx.longevity),
+				Ok(crate::mock::UnsignedLongevity::get()),
+			);
 		});
 	}
 
 	#[test]
-	fn receive_messages_delivery_proof_rejects_proof_if_trying_to_confirm_more_messages_than_expected() {
+	fn unsigned_messages_delivery_is_rejected_if_dispatch_weight_is_too_low() {
 		run_test(|| {
-			// send message first to be able to check that delivery_proof fails later
-			send_regular_message();
-
-			// 1) InboundLaneData declares that the `last_confirmed_nonce` is 1;
-			// 2) InboundLaneData has no entries => `InboundLaneData::last_delivered_nonce()`
-			//    returns `last_confirmed_nonce`;
-			// 3) it means that we're going to confirm delivery of messages 1..=1;
-			// 4) so the number of declared messages (see `UnrewardedRelayersState`) is `0` and
-			//    numer of actually confirmed messages is `1`.
-			assert_noop!(
-				Pallet::<TestRuntime>::receive_messages_delivery_proof(
-					Origin::signed(1),
-					TestMessagesDeliveryProof(Ok((
-						TEST_LANE_ID,
-						InboundLaneData {
-							last_confirmed_nonce: 1,
-							relayers: Default::default(),
-						},
-					))),
-					UnrewardedRelayersState::default(),
+			assert_eq!(
+				validate_unsigned_delivery(
+					TEST_RELAYER_A,
+					vec![message(1, REGULAR_PAYLOAD), message(2, REGULAR_PAYLOAD)],
+					REGULAR_PAYLOAD.declared_weight * 2 - 1,
 				),
-				Error::<TestRuntime, DefaultInstance>::TryingToConfirmMoreMessagesThanExpected,
+				InvalidTransaction::Call.into(),
 			);
 		});
 	}
 
 	#[test]
-	fn increase_message_fee_weight_depends_on_message_size() {
+	fn unsigned_messages_delivery_is_rejected_if_lane_has_too_many_unrewarded_relayers() {
 		run_test(|| {
-			let mut small_payload = message_payload(0, 100);
-			let mut large_payload = message_payload(1, 100);
-			small_payload.extra = vec![1; 100];
-			large_payload.extra = vec![2; 16_384];
-
-			assert_ok!(Pallet::<TestRuntime>::send_message(
-				Origin::signed(1),
-				TEST_LANE_ID,
-				small_payload,
-				100,
-			));
-			assert_ok!(Pallet::<TestRuntime>::send_message(
-				Origin::signed(1),
+			let max_entries = crate::mock::MaxUnrewardedRelayerEntriesAtInboundLane::get();
+			InboundLanes::<TestRuntime, DefaultInstance>::insert(
 				TEST_LANE_ID,
-				large_payload,
-				100,
-			));
-
-			let small_weight = Pallet::<TestRuntime>::increase_message_fee(Origin::signed(1), TEST_LANE_ID, 1, 1)
-				.expect("increase_message_fee has failed")
-				.actual_weight
-				.expect("increase_message_fee always returns Some");
-
-			let large_weight = Pallet::<TestRuntime>::increase_message_fee(Origin::signed(1), TEST_LANE_ID, 2, 1)
-				.expect("increase_message_fee has failed")
-				.actual_weight
-				.expect("increase_message_fee always returns Some");
+				InboundLaneData {
+					last_confirmed_nonce: 0,
+					relayers: (1..=max_entries)
+						.map(|nonce| unrewarded_relayer(nonce, nonce, TEST_RELAYER_B + nonce % 2))
+						.collect(),
+				},
+			);
 
-			assert!(
-				large_weight > small_weight,
-				"Actual post-dispatch weigth for larger message {} must be larger than {} for small message",
-				large_weight,
-				small_weight,
+			// new entry is required for the TEST_RELAYER_A
+			assert_eq!(
+				validate_unsigned_delivery(
+					TEST_RELAYER_A,
+					vec![message(max_entries + 1, REGULAR_PAYLOAD)],
+					REGULAR_PAYLOAD.declared_weight,
+				),
+				InvalidTransaction::ExhaustsResources.into(),
 			);
+
+			// but the last relayer may still deliver messages, because new entry is not required
+			let last_relayer = TEST_RELAYER_B + max_entries % 2;
+			assert!(validate_unsigned_delivery(
+				last_relayer,
+				vec![message(max_entries + 1, REGULAR_PAYLOAD)],
+				REGULAR_PAYLOAD.declared_weight,
+			)
+			.is_ok());
 		});
 	}
 
 	#[test]
-	fn weight_is_refunded_for_messages_that_are_not_pruned() {
+	fn unsigned_messages_delivery_is_rejected_if_lane_has_too_many_unconfirmed_messages() {
 		run_test(|| {
-			// send first MAX messages - no messages are pruned
-			let max_messages_to_prune = crate::mock::MaxMessagesToPruneAtOnce::get();
-			let when_zero_messages_are_pruned = send_regular_message();
-			let mut delivered_messages = DeliveredMessages::new(1, true);
-			for _ in 1..max_messages_to_prune {
-				assert_eq!(send_regular_message(), when_zero_messages_are_pruned);
-				delivered_messages.note_dispatched_message(true);
-			}
-
-			// confirm delivery of all sent messages
-			assert_ok!(Pallet::<TestRuntime>::receive_messages_delivery_proof(
-				Origin::signed(1),
-				TestMessagesDeliveryProof(Ok((
-					TEST_LANE_ID,
-					InboundLaneData {
-						last_confirmed_nonce: 1,
-						relayers: vec![UnrewardedRelayer {
-							relayer: 0,
-							messages: delivered_messages,
-						}]
+			let max_unconfirmed = crate::mock::MaxUnconfirmedMessagesAtInboundLane::get();
+			InboundLanes::<TestRuntime, DefaultInstance>::insert(
+				TEST_LANE_ID,
+				InboundLaneData {
+					last_confirmed_nonce: 0,
+					relayers: vec![unrewarded_relayer(1, max_unconfirmed, TEST_RELAYER_A)]
 						.into_iter()
 						.collect(),
-					},
-				))),
-				UnrewardedRelayersState {
-					unrewarded_relayer_entries: 1,
-					total_messages: max_messages_to_prune,
-					..Default::default()
 				},
-			));
+			);
 
-			// when next message is sent, MAX messages are pruned
-			let weight_when_max_messages_are_pruned = send_regular_message();
 			assert_eq!(
-				weight_when_max_messages_are_pruned,
-				when_zero_messages_are_pruned + crate::mock::DbWeight::get().writes(max_messages_to_prune),
+				validate_unsigned_delivery(
+					TEST_RELAYER_A,
+					vec![message(max_unconfirmed + 1, REGULAR_PAYLOAD)],
+					REGULAR_PAYLOAD.declared_weight,
+				),
+				InvalidTransaction::ExhaustsResources.into(),
+			);
+		});
+	}
+
+	#[test]
+	fn receive_messages_proof_unsigned_fails_if_it_delivers_no_new_messages() {
+		run_test(|| {
+			assert_ok!(receive_messages_proof_unsigned(
+				Origin::none(),
+				TEST_RELAYER_A,
+				Ok(vec![message(1, REGULAR_PAYLOAD)]).into(),
+				1,
+				REGULAR_PAYLOAD.declared_weight,
+			));
+
+			assert_noop!(
+				receive_messages_proof_unsigned(
+					Origin::none(),
+					TEST_RELAYER_B,
+					Ok(vec![message(1, REGULAR_PAYLOAD)]).into(),
+					1,
+					REGULAR_PAYLOAD.declared_weight,
+				),
+				Error::<TestRuntime, DefaultInstance>::NoNewMessagesDelivered,
 			);
 		});
 	}
@@ -0,0 +1,265 @@
+// Copyright 2019-2021 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Storage migrations of the messages pallet.
+//!
+//! Every migration is only applied if the `PalletStorageVersion` is lower than the version that
+//! it migrates to. So all migrations may be kept in the runtime `Migrations` list, ordered by
+//! their version.
+
+use crate::{Config, InboundLaneRelayers, InboundLanes, Instance, OutboundMessages, PalletStorageVersion};
+
+use bp_messages::{MessageDataV0, MessageKey};
+use codec::{Decode, Encode};
+use frame_support::{
+	traits::{Get, OnRuntimeUpgrade},
+	weights::Weight,
+	IterableStorageMap, RuntimeDebug, StorageValue,
+};
+use sp_std::marker::PhantomData;
+
+/// Version of the messages pallet storage.
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, RuntimeDebug)]
+pub enum StorageVersion {
+	/// Initial version.
+	V0,
+	/// Priority has been added to the outbound messages data.
+	V1,
+	/// Relayer entries of inbound lanes are copied to the `InboundLaneRelayers` map.
+	V2,
+}
+
+impl StorageVersion {
+	/// Version of storage, used by the current pallet code.
+	pub const LATEST: StorageVersion = StorageVersion::V2;
+}
+
+impl Default for StorageVersion {
+	fn default() -> Self {
+		StorageVersion::V0
+	}
+}
+
+/// Migration that adds default priority to all queued outbound messages.
+pub struct MigrateToV1<T, I>(PhantomData<(T, I)>);
+
+impl<T: Config<I>, I: Instance> OnRuntimeUpgrade for MigrateToV1<T, I> {
+	fn on_runtime_upgrade() -> Weight {
+		let db_weight = T::DbWeight::get();
+		if PalletStorageVersion::<I>::get() >= StorageVersion::V1 {
+			return db_weight.reads(1);
+		}
+
+		let mut migrated_messages = 0;
+		OutboundMessages::<T, I>::translate::<MessageDataV0<T::OutboundMessageFee>, _>(|_, message_data| {
+			migrated_messages += 1;
+			Some(message_data.into())
+		});
+		PalletStorageVersion::<I>::put(StorageVersion::V1);
+
+		log::info!(
+			target: "runtime::bridge-messages",
+			"Migrated {} outbound messages to storage version {:?}",
+			migrated_messages,
+			StorageVersion::V1,
+		);
+
+		db_weight.reads_writes(migrated_messages + 1, migrated_messages + 1)
+	}
+
+	#[cfg(feature = "try-runtime")]
+	fn post_upgrade() -> Result<(), &'static str> {
+		frame_support::ensure!(
+			PalletStorageVersion::<I>::get() >= StorageVersion::V1,
+			"Storage version is not updated"
+		);
+		Ok(())
+	}
+}
+
+/// Migration that copies relayer entries of all inbound lanes to the `InboundLaneRelayers` map.
+pub struct MigrateToV2<T, I>(PhantomData<(T, I)>);
+
+impl<T: Config<I>, I: Instance> OnRuntimeUpgrade for MigrateToV2<T, I> {
+	fn on_runtime_upgrade() -> Weight {
+		let db_weight = T::DbWeight::get();
+		if PalletStorageVersion::<I>::get() >= StorageVersion::V2 {
+			return db_weight.reads(1);
+		}
+
+		let mut migrated_lanes = 0;
+		let mut migrated_entries = 0;
+		for (lane_id, lane_data) in InboundLanes::<T, I>::iter() {
+			migrated_lanes += 1;
+			for entry in lane_data.relayers {
+				migrated_entries += 1;
+				InboundLaneRelayers::<T, I>::insert(
+					MessageKey {
+						lane_id,
+						nonce: entry.messages.begin,
+					},
+					entry,
+				);
+			}
+		}
+		PalletStorageVersion::<I>::put(StorageVersion::V2);
+
+		log::info!(
+			target: "runtime::bridge-messages",
+			"Migrated {} relayer entries of {} inbound lanes to storage version {:?}",
+			migrated_entries,
+			migrated_lanes,
+			StorageVersion::V2,
+		);
+
+		db_weight.reads_writes(migrated_lanes + 1, migrated_entries + 1)
+	}
+
+	#[cfg(feature = "try-runtime")]
+	fn post_upgrade() -> Result<(), &'static str> {
+		frame_support::ensure!(
+			PalletStorageVersion::<I>::get() >= StorageVersion::V2,
+			"Storage version is not updated"
+		);
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::mock::{run_test, unrewarded_relayer, TestRuntime, TEST_LANE_ID, TEST_RELAYER_A, TEST_RELAYER_B};
+	use crate::DefaultInstance;
+	use bp_messages::{InboundLaneData, MessageData, DEFAULT_MESSAGE_PRIORITY};
+	use frame_support::{storage::unhashed, StorageMap};
+
+	#[test]
+	fn migration_to_v1_adds_default_priority_to_queued_messages() {
+		run_test(|| {
+			let message_key = MessageKey {
+				lane_id: TEST_LANE_ID,
+				nonce: 1,
+			};
+			unhashed::put(
+				&OutboundMessages::<TestRuntime, DefaultInstance>::hashed_key_for(&message_key),
+				&MessageDataV0 {
+					payload: vec![42],
+					fee: 100u64,
+				},
+			);
+
+			MigrateToV1::<TestRuntime, DefaultInstance>::on_runtime_upgrade();
+
+			assert_eq!(
+				OutboundMessages::<TestRuntime, DefaultInstance>::get(&message_key),
+				Some(MessageData {
+					payload: vec![42],
+					fee: 100,
+					priority: DEFAULT_MESSAGE_PRIORITY,
+				}),
+			);
+			assert_eq!(PalletStorageVersion::<DefaultInstance>::get(), StorageVersion::V1);
+		});
+	}
+
+	#[test]
+	fn migration_to_v1_is_not_applied_twice() {
+		run_test(|| {
+			PalletStorageVersion::<DefaultInstance>::put(StorageVersion::V1);
+
+			let message_key = MessageKey {
+				lane_id: TEST_LANE_ID,
+				nonce: 1,
+			};
+			let message_data = MessageData {
+				payload: vec![42],
+				fee: 100u64,
+				priority: 7,
+			};
+			OutboundMessages::<TestRuntime, DefaultInstance>::insert(&message_key, &message_data);
+
+			MigrateToV1::<TestRuntime, DefaultInstance>::on_runtime_upgrade();
+
+			assert_eq!(
+				OutboundMessages::<TestRuntime, DefaultInstance>::get(&message_key),
+				Some(message_data),
+			);
+		});
+	}
+
+	#[test]
+	fn migration_to_v2_copies_inbound_lane_relayers() {
+		run_test(|| {
+			PalletStorageVersion::<DefaultInstance>::put(StorageVersion::V1);
+			InboundLanes::<TestRuntime, DefaultInstance>::insert(
+				TEST_LANE_ID,
+				InboundLaneData {
+					relayers: vec![
+						unrewarded_relayer(1, 2, TEST_RELAYER_A),
+						unrewarded_relayer(3, 3, TEST_RELAYER_B),
+					]
+					.into_iter()
+					.collect(),
+					last_confirmed_nonce: 0,
+				},
+			);
+
+			MigrateToV2::<TestRuntime, DefaultInstance>::on_runtime_upgrade();
+
+			let entry_key = |nonce| MessageKey {
+				lane_id: TEST_LANE_ID,
+				nonce,
+			};
+			assert_eq!(
+				InboundLaneRelayers::<TestRuntime, DefaultInstance>::get(entry_key(1)),
+				Some(unrewarded_relayer(1, 2, TEST_RELAYER_A)),
+			);
+			assert_eq!(
+				InboundLaneRelayers::<TestRuntime, DefaultInstance>::get(entry_key(3)),
+				Some(unrewarded_relayer(3, 3, TEST_RELAYER_B)),
+			);
+			assert_eq!(
+				InboundLaneRelayers::<TestRuntime, DefaultInstance>::get(entry_key(2)),
+				None
+			);
+			assert_eq!(PalletStorageVersion::<DefaultInstance>::get(), StorageVersion::V2);
+		});
+	}
+
+	#[test]
+	fn migration_to_v2_is_not_applied_twice() {
+		run_test(|| {
+			PalletStorageVersion::<DefaultInstance>::put(StorageVersion::V2);
+			InboundLanes::<TestRuntime, DefaultInstance>::insert(
+				TEST_LANE_ID,
+				InboundLaneData {
+					relayers: vec![unrewarded_relayer(1, 2, TEST_RELAYER_A)].into_iter().collect(),
+					last_confirmed_nonce: 0,
+				},
+			);
+
+			MigrateToV2::<TestRuntime, DefaultInstance>::on_runtime_upgrade();
+
+			assert_eq!(
+				InboundLaneRelayers::<TestRuntime, DefaultInstance>::get(MessageKey {
+					lane_id: TEST_LANE_ID,
+					nonce: 1,
+				}),
+				None,
+			);
+		});
+	}
+}
@@ -27,7 +27,7 @@ use bp_messages::{
 	},
 	target_chain::{DispatchMessage, MessageDispatch, ProvedLaneMessages, ProvedMessages, SourceHeaderChain},
 	DeliveredMessages, InboundLaneData, LaneId, Message, MessageData, MessageKey, MessageNonce, OutboundLaneData,
-	Parameter as MessagesParameter, UnrewardedRelayer,
+	Parameter as MessagesParameter, UnrewardedRelayer, DEFAULT_MESSAGE_PRIORITY,
 };
 use bp_runtime::{messages::MessageDispatchResult, Size};
 use codec::{Decode, Encode};
@@ -37,8 +37,9 @@ use frame_support::{
 };
 use sp_core::H256;
 use sp_runtime::{
-	testing::Header as SubstrateHeader,
+	testing::{Header as SubstrateHeader, TestSignature, UintAuthorityId},
 	traits::{BlakeTwo256, IdentityLookup},
+	transaction_validity::{TransactionLongevity, TransactionPriority},
 	FixedU128, Perbill,
 };
 use std::collections::BTreeMap;
@@ -141,7 +142,15 @@ parameter_types! {
 	pub const MaxMessagesToPruneAtOnce: u64 = 10;
 	pub const MaxUnrewardedRelayerEntriesAtInboundLane: u64 = 16;
 	pub const MaxUnconfirmedMessagesAtInboundLane: u64 = 32;
+	pub const MaxConfirmedRelayerEntriesToPrune: u64 = 2;
+	pub const MaxMessageEventsInDeliveryTransaction: u64 = 2;
+	pub const StalledConfirmationPeriod: u64 = 10;
+	pub const StalledConfirmationBountyPerMessage: TestMessageFee = 100;
+	pub const UnsignedPriority: TransactionPriority = 1_000;
+	pub const UnsignedLongevity: TransactionLongevity = 16;
+	pub const MaxAllowedRelayers: u32 = 2;
 	pub storage TokenConversionRate: FixedU128 = 1.into();
+	pub storage TestDispatchOverheadWeight: Weight = 0;
   pub const TestBridgedChainId: bp_runtime::ChainId = *b"test";
 }
 
@@ -165,6 +174,8 @@ impl Config for TestRuntime {
 	type MaxMessagesToPruneAtOnce = MaxMessagesToPruneAtOnce;
 	type MaxUnrewardedRelayerEntriesAtInboundLane = MaxUnrewardedRelayerEntriesAtInboundLane;
 	type MaxUnconfirmedMessagesAtInboundLane = MaxUnconfirmedMessagesAtInboundLane;
+	type MaxConfirmedRelayerEntriesToPrune = MaxConfirmedRelayerEntriesToPrune;
+	type MaxMessageEventsInDeliveryTransaction = MaxMessageEventsInDeliveryTransaction;
 
 	type OutboundPayload = TestPayload;
 	type OutboundMessageFee = TestMessageFee;
@@ -172,6 +183,8 @@ impl Config for TestRuntime {
 	type InboundPayload = TestPayload;
 	type InboundMessageFee = TestMessageFee;
 	type InboundRelayer = TestRelayer;
+	type InboundRelayerSigner = UintAuthorityId;
+	type InboundRelayerSignature = TestSignature;
 
 	type AccountIdConverter = AccountIdConverter;
 
@@ -179,9 +192,15 @@ impl Config for TestRuntime {
 	type LaneMessageVerifier = TestLaneMessageVerifier;
 	type MessageDeliveryAndDispatchPayment = TestMessageDeliveryAndDispatchPayment;
 	type OnDeliveryConfirmed = (TestOnDeliveryConfirmed1, TestOnDeliveryConfirmed2);
+	type BridgedMaxUnrewardedRelayerEntriesAtInboundLane = MaxUnrewardedRelayerEntriesAtInboundLane;
+	type StalledConfirmationPeriod = StalledConfirmationPeriod;
+	type StalledConfirmationBountyPerMessage = StalledConfirmationBountyPerMessage;
 
 	type SourceHeaderChain = TestSourceHeaderChain;
 	type MessageDispatch = TestMessageDispatch;
+	type UnsignedPriority = UnsignedPriority;
+	type UnsignedLongevity = UnsignedLongevity;
+	type MaxAllowedRelayers = MaxAllowedRelayers;
 	type BridgedChainId = TestBridgedChainId;
 }
 
@@ -207,7 +226,7 @@ pub const TEST_RELAYER_C: AccountId = 102;
 pub const TEST_ERROR: &str = "Test error";
 
 /// Lane that we're using in tests.
-pub const TEST_LANE_ID: LaneId = [0, 0, 0, 1];
+pub const TEST_LANE_ID: LaneId = LaneId::Legacy([0, 0, 0, 1]);
 
 /// Regular message payload.
 pub const REGULAR_PAYLOAD: TestPayload = message_payload(0, 50);
@@ -320,6 +339,11 @@ impl TestMessageDeliveryAndDispatchPayment {
 		frame_support::storage::unhashed::get(b":message-fee:") == Some((Sender::Signed(submitter), fee))
 	}
 
+	/// Returns true if given fee has been refunded to given account.
+	pub fn is_fee_refunded(refund_to: Sender<AccountId>, fee: TestMessageFee) -> bool {
+		frame_support::storage::unhashed::get(b":message-fee-refund:") == Some((refund_to, fee))
+	}
+
 	/// Returns true if given relayer has been rewarded with given balance. The reward-paid flag is
 	/// cleared after the call.
 	pub fn is_reward_paid(relayer: AccountId, fee: TestMessageFee) -> bool {
@@ -344,15 +368,27 @@ impl MessageDeliveryAndDispatchPayment<AccountId, TestMessageFee> for TestMessag
 		Ok(())
 	}
 
+	fn refund_delivery_and_dispatch_fee(
+		refund_to: &Sender<AccountId>,
+		fee: &TestMessageFee,
+		_relayer_fund_account: &AccountId,
+	) -> Result<(), Self::Error> {
+		frame_support::storage::unhashed::put(b":message-fee-refund:", &(refund_to, fee));
+		Ok(())
+	}
+
 	fn pay_relayers_rewards(
 		_confirmation_relayer: &AccountId,
 		relayers_rewards: RelayersRewards<AccountId, TestMessageFee>,
 		_relayer_fund_account: &AccountId,
-	) {
+	) -> TestMessageFee {
+		let mut total_reward = 0;
 		for (relayer, reward) in relayers_rewards {
 			let key = (b":relayer-reward:", relayer, reward.reward).encode();
 			frame_support::storage::unhashed::put(&key, &true);
+			total_reward += reward.reward;
 		}
+		total_reward
 	}
 }
 
@@ -417,6 +453,10 @@ impl SourceHeaderChain<TestMessageFee> for TestSourceHeaderChain {
 
 	type MessagesProof = TestMessagesProof;
 
+	fn proof_lanes_count(proof: &Self::MessagesProof) -> u32 {
+		proof.result.as_ref().map(|lanes| lanes.len() as u32).unwrap_or(1)
+	}
+
 	fn verify_messages_proof(
 		proof: Self::MessagesProof,
 		_messages_count: u32,
@@ -442,6 +482,10 @@ impl MessageDispatch<AccountId, TestMessageFee> for TestMessageDispatch {
 		}
 	}
 
+	fn dispatch_overhead_weight() -> Weight {
+		TestDispatchOverheadWeight::get()
+	}
+
 	fn dispatch(
 		_relayer_account: &AccountId,
 		message: DispatchMessage<TestPayload, TestMessageFee>,
@@ -479,6 +523,7 @@ pub fn message_data(payload: TestPayload) -> MessageData<TestMessageFee> {
 	MessageData {
 		payload: payload.encode(),
 		fee: 1,
+		priority: DEFAULT_MESSAGE_PRIORITY,
 	}
 }
 
@@ -221,6 +221,12 @@ fn extract_dispatch_results<RelayerId>(
 		);
 	}
 
+	// entries must cover all new messages. It is always true when the whole unrewarded relayers vec
+	// is provided, but compact delivery proofs only bring some entries of this vec
+	if received_dispatch_result.len() as MessageNonce != latest_received_nonce - prev_latest_received_nonce {
+		return Err(ReceivalConfirmationResult::NonConsecutiveUnrewardedRelayerEntries);
+	}
+
 	Ok(received_dispatch_result)
 }
 
@@ -377,6 +383,14 @@ mod tests {
 		);
 	}
 
+	#[test]
+	fn confirm_delivery_fails_if_entries_do_not_cover_all_new_messages() {
+		assert_eq!(
+			assert_3_messages_confirmation_fails(3, &unrewarded_relayers(2..=3)),
+			ReceivalConfirmationResult::NonConsecutiveUnrewardedRelayerEntries,
+		);
+	}
+
 	#[test]
 	fn confirm_delivery_fails_if_number_of_dispatch_results_in_entry_is_invalid() {
 		let mut relayers: VecDeque<_> = unrewarded_relayers(1..=1)
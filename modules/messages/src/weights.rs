@@ -59,9 +59,14 @@ pub trait WeightInfo {
 	fn receive_single_message_proof_1_kb() -> Weight;
 	fn receive_single_message_proof_16_kb() -> Weight;
 	fn receive_single_prepaid_message_proof() -> Weight;
+	fn receive_multiple_lanes_messages_proof(i: u32) -> Weight;
 	fn receive_delivery_proof_for_single_message() -> Weight;
 	fn receive_delivery_proof_for_two_messages_by_single_relayer() -> Weight;
 	fn receive_delivery_proof_for_two_messages_by_two_relayers() -> Weight;
+	fn propose_owner() -> Weight;
+	fn accept_owner() -> Weight;
+	fn set_operating_mode() -> Weight;
+	fn update_pallet_parameter() -> Weight;
 	fn send_messages_of_various_lengths(i: u32) -> Weight;
 	fn receive_multiple_messages_proof(i: u32) -> Weight;
 	fn receive_message_proofs_with_extra_nodes(i: u32) -> Weight;
@@ -130,6 +135,14 @@ impl<T: frame_system::Config> WeightInfo for RialtoWeight<T> {
 			.saturating_add(T::DbWeight::get().reads(3 as Weight))
 			.saturating_add(T::DbWeight::get().writes(1 as Weight))
 	}
+	fn receive_multiple_lanes_messages_proof(i: u32) -> Weight {
+		(52_587_000 as Weight)
+			.saturating_add((154_182_000 as Weight).saturating_mul(i as Weight))
+			.saturating_add(T::DbWeight::get().reads(3 as Weight))
+			.saturating_add(T::DbWeight::get().reads((2 as Weight).saturating_mul(i as Weight)))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes((2 as Weight).saturating_mul(i as Weight)))
+	}
 	fn receive_delivery_proof_for_single_message() -> Weight {
 		(128_805_000 as Weight)
 			.saturating_add(T::DbWeight::get().reads(6 as Weight))
@@ -145,6 +158,26 @@ impl<T: frame_system::Config> WeightInfo for RialtoWeight<T> {
 			.saturating_add(T::DbWeight::get().reads(8 as Weight))
 			.saturating_add(T::DbWeight::get().writes(4 as Weight))
 	}
+	fn propose_owner() -> Weight {
+		(22_475_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes(3 as Weight))
+	}
+	fn accept_owner() -> Weight {
+		(22_475_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes(3 as Weight))
+	}
+	fn set_operating_mode() -> Weight {
+		(23_146_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	fn update_pallet_parameter() -> Weight {
+		(25_709_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes(2 as Weight))
+	}
 	fn send_messages_of_various_lengths(i: u32) -> Weight {
 		(133_632_000 as Weight)
 			.saturating_add((4_000 as Weight).saturating_mul(i as Weight))
@@ -250,6 +283,14 @@ impl WeightInfo for () {
 			.saturating_add(RocksDbWeight::get().reads(3 as Weight))
 			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
 	}
+	fn receive_multiple_lanes_messages_proof(i: u32) -> Weight {
+		(52_587_000 as Weight)
+			.saturating_add((154_182_000 as Weight).saturating_mul(i as Weight))
+			.saturating_add(RocksDbWeight::get().reads(3 as Weight))
+			.saturating_add(RocksDbWeight::get().reads((2 as Weight).saturating_mul(i as Weight)))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+			.saturating_add(RocksDbWeight::get().writes((2 as Weight).saturating_mul(i as Weight)))
+	}
 	fn receive_delivery_proof_for_single_message() -> Weight {
 		(128_805_000 as Weight)
 			.saturating_add(RocksDbWeight::get().reads(6 as Weight))
@@ -265,6 +306,26 @@ impl WeightInfo for () {
 			.saturating_add(RocksDbWeight::get().reads(8 as Weight))
 			.saturating_add(RocksDbWeight::get().writes(4 as Weight))
 	}
+	fn propose_owner() -> Weight {
+		(22_475_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(3 as Weight))
+	}
+	fn accept_owner() -> Weight {
+		(22_475_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(3 as Weight))
+	}
+	fn set_operating_mode() -> Weight {
+		(23_146_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+	fn update_pallet_parameter() -> Weight {
+		(25_709_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(2 as Weight))
+	}
 	fn send_messages_of_various_lengths(i: u32) -> Weight {
 		(133_632_000 as Weight)
 			.saturating_add((4_000 as Weight).saturating_mul(i as Weight))
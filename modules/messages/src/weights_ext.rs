@@ -19,7 +19,7 @@
 use crate::weights::WeightInfo;
 
 use bp_messages::{MessageNonce, UnrewardedRelayersState};
-use bp_runtime::{PreComputedSize, Size};
+use bp_runtime::{PreComputedSize, Size, StorageProofStats};
 use frame_support::weights::{RuntimeDbWeight, Weight};
 
 /// Size of the message being delivered in benchmarks.
@@ -45,11 +45,13 @@ pub fn ensure_weights_are_correct<W: WeightInfoExt>(
 	assert_ne!(W::receive_messages_proof_overhead(), 0);
 	assert_ne!(W::receive_messages_proof_messages_overhead(1), 0);
 	assert_ne!(W::receive_messages_proof_outbound_lane_state_overhead(), 0);
+	assert_ne!(W::receive_messages_proof_lanes_overhead(2), 0);
 	assert_ne!(W::storage_proof_size_overhead(1), 0);
 
 	// verify that the hardcoded value covers `receive_messages_proof` weight
 	let actual_single_regular_message_delivery_tx_weight = W::receive_messages_proof_weight(
-		&PreComputedSize((EXPECTED_DEFAULT_MESSAGE_LENGTH + W::expected_extra_storage_proof_size()) as usize),
+		EXPECTED_DEFAULT_MESSAGE_LENGTH + W::expected_extra_storage_proof_size(),
+		1,
 		1,
 		0,
 	);
@@ -121,7 +123,8 @@ pub fn ensure_able_to_receive_message<W: WeightInfoExt>(
 
 	// verify that we're able to receive proof of maximal-size message with maximal dispatch weight
 	let max_delivery_transaction_dispatch_weight = W::receive_messages_proof_weight(
-		&PreComputedSize((max_incoming_message_proof_size + W::expected_extra_storage_proof_size()) as usize),
+		max_incoming_message_proof_size.saturating_add(W::expected_extra_storage_proof_size()),
+		1,
 		1,
 		max_incoming_message_dispatch_weight,
 	);
@@ -192,23 +195,31 @@ pub trait WeightInfoExt: WeightInfo {
 	}
 
 	/// Weight of message delivery extrinsic.
-	fn receive_messages_proof_weight(proof: &impl Size, messages_count: u32, dispatch_weight: Weight) -> Weight {
+	///
+	/// The `proof_size` is the size (in bytes) of the messages proof. The `lanes_count` is the number of lanes
+	/// covered by the proof. The `messages_count` is the total number of messages at all these lanes.
+	fn receive_messages_proof_weight(
+		proof_size: u32,
+		lanes_count: u32,
+		messages_count: u32,
+		dispatch_weight: Weight,
+	) -> Weight {
 		// basic components of extrinsic weight
 		let transaction_overhead = Self::receive_messages_proof_overhead();
-		let outbound_state_delivery_weight = Self::receive_messages_proof_outbound_lane_state_overhead();
+		let lanes_overhead = Self::receive_messages_proof_lanes_overhead(lanes_count);
+		// every lane may bring its outbound lane state
+		let outbound_state_delivery_weight = Self::receive_messages_proof_outbound_lane_state_overhead()
+			.saturating_mul(sp_std::cmp::max(lanes_count, 1) as Weight);
 		let messages_delivery_weight =
 			Self::receive_messages_proof_messages_overhead(MessageNonce::from(messages_count));
 		let messages_dispatch_weight = dispatch_weight;
 
 		// proof size overhead weight
-		let expected_proof_size = EXPECTED_DEFAULT_MESSAGE_LENGTH
-			.saturating_mul(messages_count.saturating_sub(1))
-			.saturating_add(Self::expected_extra_storage_proof_size());
-		let actual_proof_size = proof.size_hint();
-		let proof_size_overhead =
-			Self::storage_proof_size_overhead(actual_proof_size.saturating_sub(expected_proof_size));
+		let expected_proof_size = Self::expected_messages_proof_size(messages_count);
+		let proof_size_overhead = Self::storage_proof_size_overhead(proof_size.saturating_sub(expected_proof_size));
 
 		transaction_overhead
+			.saturating_add(lanes_overhead)
 			.saturating_add(outbound_state_delivery_weight)
 			.saturating_add(messages_delivery_weight)
 			.saturating_add(messages_dispatch_weight)
@@ -284,6 +295,19 @@ pub trait WeightInfoExt: WeightInfo {
 		weight_of_single_message_and_lane_state.saturating_sub(weight_of_single_message)
 	}
 
+	/// Returns weight that needs to be accounted when message delivery transaction (`receive_messages_proof`)
+	/// is carrying messages of given number of lanes.
+	///
+	/// The first lane is already covered by the transaction overhead, so this is zero for single-lane proofs.
+	fn receive_messages_proof_lanes_overhead(lanes: u32) -> Weight {
+		let weight_of_two_lanes = Self::receive_multiple_lanes_messages_proof(2);
+		let weight_of_single_lane = Self::receive_multiple_lanes_messages_proof(1);
+		weight_of_two_lanes
+			.saturating_sub(weight_of_single_lane)
+			.saturating_sub(Self::receive_messages_proof_messages_overhead(1))
+			.saturating_mul(lanes.saturating_sub(1) as Weight)
+	}
+
 	/// Returns weight overhead of delivery confirmation transaction (`receive_messages_delivery_proof`).
 	fn receive_messages_delivery_proof_overhead() -> Weight {
 		let weight_of_two_messages_and_two_tx_overheads =
@@ -322,11 +346,40 @@ pub trait WeightInfoExt: WeightInfo {
 	/// shouldn't be added to cost of transaction, but instead should act as a minimal cost that the
 	/// relayer must pay when it relays proof of given size (even if cost based on other parameters
 	/// is less than that cost).
+	///
+	/// The weight of single proof byte is derived from the `receive_message_proofs_with_extra_nodes` benchmark,
+	/// which measures the cost of proof with given number of extra trie bytes.
 	fn storage_proof_size_overhead(proof_size: u32) -> Weight {
 		let proof_size_in_bytes = proof_size as Weight;
-		let byte_weight =
-			(Self::receive_single_message_proof_16_kb() - Self::receive_single_message_proof_1_kb()) / (15 * 1024);
-		proof_size_in_bytes * byte_weight
+		let byte_weight = Self::receive_message_proofs_with_extra_nodes(1)
+			.saturating_sub(Self::receive_message_proofs_with_extra_nodes(0));
+		proof_size_in_bytes.saturating_mul(byte_weight)
+	}
+
+	/// Returns expected size (in bytes) of the proof of given number of messages, that is covered by
+	/// the base weight of the message delivery transaction (`receive_messages_proof`).
+	fn expected_messages_proof_size(messages_count: u32) -> u32 {
+		EXPECTED_DEFAULT_MESSAGE_LENGTH
+			.saturating_mul(messages_count.saturating_sub(1))
+			.saturating_add(Self::expected_extra_storage_proof_size())
+	}
+
+	/// Returns weight that is refunded to the submitter of message delivery transaction
+	/// (`receive_messages_proof`), after the messages proof has been verified.
+	///
+	/// The declared weight charges every byte of the encoded proof that exceeds the expected proof size.
+	/// But only trie nodes need to be decoded and hashed. So after the proof is verified, the proof size
+	/// overhead is recomputed using the actual size of trie nodes and the difference is refunded.
+	fn receive_messages_proof_storage_proof_refund(
+		declared_proof_size: u32,
+		messages_count: u32,
+		stats: &StorageProofStats,
+	) -> Weight {
+		let expected_proof_size = Self::expected_messages_proof_size(messages_count);
+		let declared_overhead =
+			Self::storage_proof_size_overhead(declared_proof_size.saturating_sub(expected_proof_size));
+		let actual_overhead = Self::storage_proof_size_overhead(stats.proof_size.saturating_sub(expected_proof_size));
+		declared_overhead.saturating_sub(actual_overhead)
 	}
 
 	/// Returns weight of the pay-dispatch-fee operation for inbound messages.
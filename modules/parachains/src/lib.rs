@@ -0,0 +1,893 @@
+// Copyright 2021 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Runtime module that tracks finalized heads of parachains of the bridged relay chain.
+//!
+//! Parachain heads are stored in the `Heads` map of the relay chain `Paras` pallet. Relayers
+//! submit storage proofs of this map, generated at relay chain headers that have been finalized
+//! by the GRANDPA pallet. The pallet verifies these proofs and stores finalized parachain heads.
+//! Then parachain storage proofs may be verified using `Pallet::parse_finalized_storage_proof`,
+//! so that messages pallet instances may be bridged directly to parachains.
+//!
+//! Instance `I` of this pallet tracks parachains of the relay chain, whose headers are tracked by
+//! the same instance of the GRANDPA pallet. Only heads of parachains from the configured
+//! `TrackedParachains` set are accepted. Heads that are larger than `MaxParaHeadSize` are
+//! ignored, so the weight of the head submission is bounded by the number of parachains and the
+//! size of the storage proof.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use bp_runtime::{Chain, HasherOf, HeaderOf, StorageProofChecker, StorageProofError};
+use codec::{Decode, Encode};
+use frame_support::{weights::Weight, RuntimeDebug, Twox64Concat};
+use frame_system::RawOrigin;
+use pallet_bridge_grandpa::{BridgedBlockHash, BridgedBlockNumber};
+use sp_core::{storage::StorageKey, H256};
+use sp_runtime::traits::{BadOrigin, Header as HeaderT};
+use sp_std::prelude::*;
+
+#[cfg(test)]
+mod mock;
+
+// Re-export in crate namespace for `construct_runtime!`
+pub use pallet::*;
+
+/// Name of the parachain heads map in the relay chain `Paras` pallet.
+pub const PARAS_HEADS_MAP_NAME: &str = "Heads";
+
+/// Raw storage proof - set of trie nodes.
+pub type RawStorageProof = Vec<Vec<u8>>;
+/// Hash of the parachain head.
+pub type ParaHash = H256;
+
+/// Parachain id.
+#[derive(Encode, Decode, Clone, Copy, Default, RuntimeDebug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct ParaId(pub u32);
+
+/// Parachain head, as it is stored by the relay chain. This is the encoded parachain header.
+#[derive(Encode, Decode, Clone, Default, RuntimeDebug, PartialEq, Eq)]
+pub struct ParaHead(pub Vec<u8>);
+
+impl ParaHead {
+	/// Returns hash of the parachain head.
+	pub fn hash(&self) -> ParaHash {
+		sp_io::hashing::blake2_256(&self.0).into()
+	}
+}
+
+/// Best known head of the parachain.
+#[derive(Encode, Decode, Clone, RuntimeDebug, PartialEq, Eq)]
+pub struct BestParaHead<RelayBlockNumber> {
+	/// Number of the relay chain block, the head has been read at.
+	pub at_relay_block_number: RelayBlockNumber,
+	/// Hash of the parachain head.
+	pub head_hash: ParaHash,
+	/// Position of the next head in the `ImportedParaHashes` ring buffer of the parachain.
+	pub next_imported_hash_position: u32,
+}
+
+/// Returns size of the raw storage proof.
+pub fn storage_proof_size(proof: &RawStorageProof) -> u32 {
+	proof
+		.iter()
+		.fold(0u32, |size, node| size.saturating_add(node.len() as u32))
+}
+
+/// Returns weight of the `submit_parachain_heads` call.
+///
+/// The `heads_size` is the total size of heads that are written to the storage.
+pub fn submit_parachain_heads_weight<T: Config<I>, I: 'static>(
+	parachains_count: u32,
+	proof_size: u32,
+	heads_size: u32,
+) -> Weight {
+	use frame_support::traits::Get;
+
+	let parachains_count = parachains_count as Weight;
+	<T as frame_system::Config>::DbWeight::get()
+		.reads_writes(2 + 2 * parachains_count, 5 * parachains_count)
+		.saturating_add(T::StorageProofByteWeight::get().saturating_mul(proof_size as Weight))
+		.saturating_add(T::ParaHeadByteWeight::get().saturating_mul(heads_size as Weight))
+}
+
+/// Returns storage key of the parachain head at the relay chain.
+pub fn parachain_head_storage_key(paras_pallet_name: &str, para_id: ParaId) -> StorageKey {
+	bp_runtime::storage_map_final_key::<Twox64Concat>(paras_pallet_name, PARAS_HEADS_MAP_NAME, &para_id.encode())
+}
+
+// comes from #[pallet::event]
+#[allow(clippy::unused_unit)]
+#[frame_support::pallet]
+pub mod pallet {
+	use super::*;
+	use frame_support::pallet_prelude::*;
+	use frame_system::pallet_prelude::*;
+
+	#[pallet::config]
+	pub trait Config<I: 'static = ()>: frame_system::Config + pallet_bridge_grandpa::Config<I> {
+		/// The overarching event type.
+		type Event: From<Event<Self, I>> + IsType<<Self as frame_system::Config>::Event>;
+		/// Name of the `Paras` pallet in the bridged relay chain runtime.
+		#[pallet::constant]
+		type ParasPalletName: Get<&'static str>;
+		/// Maximal number of heads of the single parachain to keep in the storage.
+		#[pallet::constant]
+		type HeadsToKeep: Get<u32>;
+		/// Parachains, whose heads are accepted by the pallet.
+		///
+		/// Every tracked parachain occupies up to `HeadsToKeep` heads in the storage, so the set
+		/// should only contain parachains that this chain is bridged with.
+		#[pallet::constant]
+		type TrackedParachains: Get<Vec<ParaId>>;
+		/// Maximal size of the parachain head. Larger heads are ignored by the pallet.
+		#[pallet::constant]
+		type MaxParaHeadSize: Get<u32>;
+		/// Maximal size of the parachain heads storage proof.
+		#[pallet::constant]
+		type MaxStorageProofSize: Get<u32>;
+		/// Weight of verifying single byte of the parachain heads storage proof.
+		#[pallet::constant]
+		type StorageProofByteWeight: Get<Weight>;
+		/// Weight of writing single byte of the parachain head to the storage.
+		#[pallet::constant]
+		type ParaHeadByteWeight: Get<Weight>;
+	}
+
+	#[pallet::pallet]
+	pub struct Pallet<T, I = ()>(PhantomData<(T, I)>);
+
+	#[pallet::hooks]
+	impl<T: Config<I>, I: 'static> Hooks<BlockNumberFor<T>> for Pallet<T, I> {}
+
+	#[pallet::call]
+	impl<T: Config<I>, I: 'static> Pallet<T, I> {
+		/// Submit proof of parachain heads, read at the finalized relay chain block.
+		///
+		/// All parachains must be in the `TrackedParachains` set. Heads that are missing from the
+		/// relay chain storage, heads that are larger than `MaxParaHeadSize`, and heads that are read
+		/// at relay chain blocks that are older than the block of the already known head, are ignored.
+		///
+		/// The call weight assumes that all heads have the maximal size. The difference is refunded
+		/// after the call.
+		#[pallet::weight(submit_parachain_heads_weight::<T, I>(
+			parachains.len() as u32,
+			storage_proof_size(parachain_heads_proof),
+			(parachains.len() as u32).saturating_mul(T::MaxParaHeadSize::get()),
+		))]
+		pub fn submit_parachain_heads(
+			origin: OriginFor<T>,
+			relay_block_hash: BridgedBlockHash<T, I>,
+			parachains: Vec<ParaId>,
+			parachain_heads_proof: RawStorageProof,
+		) -> DispatchResultWithPostInfo {
+			ensure_operational::<T, I>()?;
+			let _ = ensure_signed(origin)?;
+
+			let parachains_count = parachains.len() as u32;
+			let proof_size = storage_proof_size(&parachain_heads_proof);
+			ensure!(
+				proof_size <= T::MaxStorageProofSize::get(),
+				Error::<T, I>::StorageProofTooLarge,
+			);
+
+			let tracked_parachains = T::TrackedParachains::get();
+			ensure!(
+				parachains.iter().all(|para_id| tracked_parachains.contains(para_id)),
+				Error::<T, I>::UntrackedParachain,
+			);
+
+			let relay_block = pallet_bridge_grandpa::Pallet::<T, I>::imported_header(relay_block_hash)
+				.ok_or(Error::<T, I>::UnknownRelayChainBlock)?;
+			let relay_block_number = *relay_block.number();
+			let paras_pallet_name = T::ParasPalletName::get();
+			let heads = pallet_bridge_grandpa::Pallet::<T, I>::parse_finalized_storage_proof(
+				relay_block_hash,
+				sp_trie::StorageProof::new(parachain_heads_proof),
+				|storage| {
+					parachains
+						.into_iter()
+						.map(|para_id| {
+							storage
+								.read_value(parachain_head_storage_key(paras_pallet_name, para_id).0.as_ref())
+								.map(|head| (para_id, head))
+						})
+						.collect::<Result<Vec<_>, _>>()
+				},
+			)
+			.map_err(|_| Error::<T, I>::InvalidStorageProof)?
+			.map_err(|_| Error::<T, I>::InvalidStorageProof)?;
+
+			let max_head_size = T::MaxParaHeadSize::get();
+			let mut heads_size = 0u32;
+			for (para_id, head) in heads {
+				if let Some(ref head) = head {
+					if head.len() > max_head_size as usize {
+						log::trace!(
+							target: "runtime::bridge-parachains",
+							"Ignoring head of parachain {:?} at relay block {:?}: head size {} is larger than {}",
+							para_id,
+							relay_block_hash,
+							head.len(),
+							max_head_size,
+						);
+						continue;
+					}
+				}
+
+				let head = match head.map(|head| ParaHead::decode(&mut &head[..])) {
+					Some(Ok(head)) => head,
+					Some(Err(_)) => {
+						log::trace!(
+							target: "runtime::bridge-parachains",
+							"Failed to decode head of parachain {:?} at relay block {:?}",
+							para_id,
+							relay_block_hash,
+						);
+						continue;
+					}
+					None => {
+						log::trace!(
+							target: "runtime::bridge-parachains",
+							"Parachain {:?} is unknown at relay block {:?}",
+							para_id,
+							relay_block_hash,
+						);
+						continue;
+					}
+				};
+
+				let head_size = head.0.len() as u32;
+				if update_parachain_head::<T, I>(para_id, relay_block_number, head) {
+					heads_size = heads_size.saturating_add(head_size);
+				}
+			}
+
+			Ok(Some(submit_parachain_heads_weight::<T, I>(
+				parachains_count,
+				proof_size,
+				heads_size,
+			))
+			.into())
+		}
+
+		/// Propose new `PalletOwner`, or remove the current owner.
+		///
+		/// The proposed owner only becomes `PalletOwner` after calling `accept_owner`. The owner
+		/// is removed immediately, because root is still able to manage the pallet.
+		///
+		/// May only be called either by root, or by `PalletOwner`.
+		#[pallet::weight((T::DbWeight::get().reads_writes(1, 2), DispatchClass::Operational))]
+		pub fn propose_owner(origin: OriginFor<T>, new_owner: Option<T::AccountId>) -> DispatchResultWithPostInfo {
+			ensure_owner_or_root::<T, I>(origin)?;
+			match new_owner {
+				Some(new_owner) => {
+					PendingPalletOwner::<T, I>::put(&new_owner);
+					log::info!(target: "runtime::bridge-parachains", "Proposing pallet Owner: {:?}", new_owner);
+					Self::deposit_event(Event::OwnerProposed(new_owner));
+				}
+				None => {
+					PalletOwner::<T, I>::kill();
+					PendingPalletOwner::<T, I>::kill();
+					log::info!(target: "runtime::bridge-parachains", "Removed Owner of pallet.");
+					Self::deposit_event(Event::OwnerChanged(None));
+				}
+			}
+
+			Ok(().into())
+		}
+
+		/// Accept the pallet ownership, proposed by the `propose_owner` call.
+		///
+		/// May only be called by the proposed owner.
+		#[pallet::weight((T::DbWeight::get().reads_writes(1, 2), DispatchClass::Operational))]
+		pub fn accept_owner(origin: OriginFor<T>) -> DispatchResultWithPostInfo {
+			let new_owner = ensure_signed(origin)?;
+			ensure!(
+				PendingPalletOwner::<T, I>::get().as_ref() == Some(&new_owner),
+				<Error<T, I>>::NotPendingOwner,
+			);
+
+			PalletOwner::<T, I>::put(&new_owner);
+			PendingPalletOwner::<T, I>::kill();
+			log::info!(target: "runtime::bridge-parachains", "Setting pallet Owner to: {:?}", new_owner);
+			Self::deposit_event(Event::OwnerChanged(Some(new_owner)));
+
+			Ok(().into())
+		}
+
+		/// Halt or resume all pallet operations.
+		///
+		/// May only be called either by root, or by `PalletOwner`.
+		#[pallet::weight((T::DbWeight::get().reads_writes(1, 1), DispatchClass::Operational))]
+		pub fn set_operational(origin: OriginFor<T>, operational: bool) -> DispatchResultWithPostInfo {
+			ensure_owner_or_root::<T, I>(origin)?;
+			<IsHalted<T, I>>::put(!operational);
+
+			if operational {
+				log::info!(target: "runtime::bridge-parachains", "Resuming pallet operations.");
+			} else {
+				log::warn!(target: "runtime::bridge-parachains", "Stopping pallet operations.");
+			}
+
+			Ok(().into())
+		}
+	}
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	#[pallet::metadata(T::AccountId = "AccountId")]
+	pub enum Event<T: Config<I>, I: 'static = ()> {
+		/// Best head of the parachain has been updated. \[para_id, head_hash\]
+		ParachainHeadUpdated(ParaId, ParaHash),
+		/// New pallet owner has been proposed. It needs to accept the ownership.
+		/// \[proposed_owner\]
+		OwnerProposed(T::AccountId),
+		/// Pallet owner has been changed or removed. \[new_owner\]
+		OwnerChanged(Option<T::AccountId>),
+	}
+
+	#[pallet::error]
+	pub enum Error<T, I = ()> {
+		/// The relay chain block is not known to the GRANDPA pallet.
+		UnknownRelayChainBlock,
+		/// The storage proof is invalid.
+		InvalidStorageProof,
+		/// The parachain head is not known to the pallet.
+		UnknownParaHead,
+		/// The parachain head is not a valid header.
+		InvalidParaHead,
+		/// The storage proof doesn't contain storage root of the parachain head.
+		StorageRootMismatch,
+		/// The parachain is not in the `TrackedParachains` set.
+		UntrackedParachain,
+		/// The storage proof is larger than `MaxStorageProofSize`.
+		StorageProofTooLarge,
+		/// All pallet operations are halted.
+		Halted,
+		/// The caller has not been proposed as the new pallet owner.
+		NotPendingOwner,
+	}
+
+	/// Optional pallet owner.
+	///
+	/// Pallet owner has a right to halt all pallet operations and then resume it. If it is
+	/// `None`, then there are no direct ways to halt/resume pallet operations, but other
+	/// runtime methods may still be used to do that (i.e. democracy::referendum to update halt
+	/// flag directly).
+	#[pallet::storage]
+	pub(super) type PalletOwner<T: Config<I>, I: 'static = ()> = StorageValue<_, T::AccountId, OptionQuery>;
+
+	/// Account that has been proposed as the new pallet owner, but hasn't accepted the
+	/// ownership yet.
+	#[pallet::storage]
+	pub(super) type PendingPalletOwner<T: Config<I>, I: 'static = ()> = StorageValue<_, T::AccountId, OptionQuery>;
+
+	/// If true, all pallet transactions are failed immediately.
+	#[pallet::storage]
+	pub(super) type IsHalted<T: Config<I>, I: 'static = ()> = StorageValue<_, bool, ValueQuery>;
+
+	/// Best known heads of parachains.
+	#[pallet::storage]
+	pub(super) type BestParaHeads<T: Config<I>, I: 'static = ()> =
+		StorageMap<_, Twox64Concat, ParaId, BestParaHead<BridgedBlockNumber<T, I>>>;
+
+	/// Imported parachain heads.
+	#[pallet::storage]
+	pub(super) type ImportedParaHeads<T: Config<I>, I: 'static = ()> =
+		StorageDoubleMap<_, Twox64Concat, ParaId, Identity, ParaHash, ParaHead>;
+
+	/// Ring buffers of imported parachain head hashes. Ordered by the insertion time.
+	#[pallet::storage]
+	pub(super) type ImportedParaHashes<T: Config<I>, I: 'static = ()> =
+		StorageDoubleMap<_, Twox64Concat, ParaId, Twox64Concat, u32, ParaHash>;
+
+	#[pallet::genesis_config]
+	pub struct GenesisConfig<T: Config<I>, I: 'static = ()> {
+		/// Optional module owner account.
+		pub owner: Option<T::AccountId>,
+		/// Whether the pallet is halted at genesis.
+		pub is_halted: bool,
+	}
+
+	#[cfg(feature = "std")]
+	impl<T: Config<I>, I: 'static> Default for GenesisConfig<T, I> {
+		fn default() -> Self {
+			Self {
+				owner: None,
+				is_halted: false,
+			}
+		}
+	}
+
+	#[pallet::genesis_build]
+	impl<T: Config<I>, I: 'static> GenesisBuild<T, I> for GenesisConfig<T, I> {
+		fn build(&self) {
+			if let Some(ref owner) = self.owner {
+				<PalletOwner<T, I>>::put(owner);
+			}
+			<IsHalted<T, I>>::put(self.is_halted);
+		}
+	}
+
+	/// Update best head of the parachain, pruning the oldest head if required.
+	///
+	/// Returns false if the head has been ignored, because newer head is already known.
+	pub(crate) fn update_parachain_head<T: Config<I>, I: 'static>(
+		para_id: ParaId,
+		relay_block_number: BridgedBlockNumber<T, I>,
+		head: ParaHead,
+	) -> bool {
+		let head_hash = head.hash();
+		let best_head = <BestParaHeads<T, I>>::get(para_id);
+		let next_imported_hash_position = match best_head {
+			Some(ref best_head) if best_head.at_relay_block_number >= relay_block_number => {
+				log::trace!(
+					target: "runtime::bridge-parachains",
+					"Ignoring head of parachain {:?} at relay block {:?}: already known head at relay block {:?}",
+					para_id,
+					relay_block_number,
+					best_head.at_relay_block_number,
+				);
+				return false;
+			}
+			Some(ref best_head) if best_head.head_hash == head_hash => {
+				// the head is not changed, so we only need to update the relay block number
+				<BestParaHeads<T, I>>::insert(
+					para_id,
+					BestParaHead {
+						at_relay_block_number: relay_block_number,
+						head_hash,
+						next_imported_hash_position: best_head.next_imported_hash_position,
+					},
+				);
+				return true;
+			}
+			Some(ref best_head) => best_head.next_imported_hash_position,
+			None => 0,
+		};
+
+		let pruning = <ImportedParaHashes<T, I>>::try_get(para_id, next_imported_hash_position);
+		<ImportedParaHeads<T, I>>::insert(para_id, head_hash, head);
+		<ImportedParaHashes<T, I>>::insert(para_id, next_imported_hash_position, head_hash);
+		<BestParaHeads<T, I>>::insert(
+			para_id,
+			BestParaHead {
+				at_relay_block_number: relay_block_number,
+				head_hash,
+				next_imported_hash_position: (next_imported_hash_position + 1) % T::HeadsToKeep::get(),
+			},
+		);
+		if let Ok(pruned_hash) = pruning {
+			if pruned_hash != head_hash {
+				log::debug!(
+					target: "runtime::bridge-parachains",
+					"Pruning old head of parachain {:?}: {:?}.",
+					para_id,
+					pruned_hash,
+				);
+				<ImportedParaHeads<T, I>>::remove(para_id, pruned_hash);
+			}
+		}
+
+		Pallet::<T, I>::deposit_event(Event::ParachainHeadUpdated(para_id, head_hash));
+		log::trace!(
+			target: "runtime::bridge-parachains",
+			"Updated head of parachain {:?} to {:?} at relay block {:?}",
+			para_id,
+			head_hash,
+			relay_block_number,
+		);
+
+		true
+	}
+
+	/// Ensure that the origin is either root, or `PalletOwner`.
+	fn ensure_owner_or_root<T: Config<I>, I: 'static>(origin: T::Origin) -> Result<(), BadOrigin> {
+		match origin.into() {
+			Ok(RawOrigin::Root) => Ok(()),
+			Ok(RawOrigin::Signed(ref signer)) if Some(signer) == <PalletOwner<T, I>>::get().as_ref() => Ok(()),
+			_ => Err(BadOrigin),
+		}
+	}
+
+	/// Ensure that the pallet is in operational mode (not halted).
+	pub(crate) fn ensure_operational<T: Config<I>, I: 'static>() -> Result<(), Error<T, I>> {
+		if <IsHalted<T, I>>::get() {
+			Err(<Error<T, I>>::Halted)
+		} else {
+			Ok(())
+		}
+	}
+}
+
+impl<T: Config<I>, I: 'static> Pallet<T, I> {
+	/// Returns best finalized head of the parachain, known to the pallet.
+	pub fn best_parachain_head(para_id: ParaId) -> Option<ParaHead> {
+		let best_head = <BestParaHeads<T, I>>::get(para_id)?;
+		<ImportedParaHeads<T, I>>::get(para_id, best_head.head_hash)
+	}
+
+	/// Returns imported head of the parachain with given hash, if it is still in the storage.
+	pub fn parachain_head(para_id: ParaId, head_hash: ParaHash) -> Option<ParaHead> {
+		<ImportedParaHeads<T, I>>::get(para_id, head_hash)
+	}
+
+	/// Verify that the passed storage proof is valid, given it is crafted using known finalized
+	/// head of the parachain `C`. If the proof is valid, then the `parse` callback is called and
+	/// the function returns its result.
+	pub fn parse_finalized_storage_proof<C: Chain, R>(
+		para_id: ParaId,
+		head_hash: ParaHash,
+		storage_proof: sp_trie::StorageProof,
+		parse: impl FnOnce(StorageProofChecker<HasherOf<C>>) -> R,
+	) -> Result<R, sp_runtime::DispatchError> {
+		pallet::ensure_operational::<T, I>()?;
+		let head = <ImportedParaHeads<T, I>>::get(para_id, head_hash).ok_or(Error::<T, I>::UnknownParaHead)?;
+		let header = HeaderOf::<C>::decode(&mut &head.0[..]).map_err(|_| Error::<T, I>::InvalidParaHead)?;
+		let storage_proof_checker =
+			StorageProofChecker::new(*header.state_root(), storage_proof).map_err(|error| match error {
+				StorageProofError::DuplicateNodesInProof => Error::<T, I>::InvalidStorageProof,
+				_ => Error::<T, I>::StorageRootMismatch,
+			})?;
+
+		Ok(parse(storage_proof_checker))
+	}
+}
+
+impl<T: Config<I>, I: 'static> From<Error<T, I>> for bp_runtime::BridgeError {
+	fn from(error: Error<T, I>) -> Self {
+		match error {
+			Error::UnknownRelayChainBlock => Self::UnknownHeader,
+			Error::InvalidStorageProof => Self::InvalidStorageProof,
+			Error::UnknownParaHead => Self::UnknownHeader,
+			Error::InvalidParaHead => Self::InvalidStorageProof,
+			Error::StorageRootMismatch => Self::InvalidStorageProof,
+			Error::UntrackedParachain => Self::NotAllowed,
+			Error::StorageProofTooLarge => Self::LimitExceeded,
+			Error::Halted => Self::Halted,
+			Error::NotPendingOwner => Self::NotAllowed,
+			// hidden variant that is never constructed
+			_ => Self::InvalidState,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::mock::{
+		import_relay_header, parachain_header, parachain_storage_proof, run_test, Event as TestEvent, Origin,
+		TestBridgedChain, TestRuntime,
+	};
+	use frame_support::{assert_noop, assert_ok, traits::Get};
+	use sp_runtime::DispatchError;
+
+	const PARA_ID: ParaId = ParaId(42);
+	const OTHER_PARA_ID: ParaId = ParaId(43);
+	const UNTRACKED_PARA_ID: ParaId = ParaId(44);
+
+	fn head(number: u64) -> ParaHead {
+		ParaHead(parachain_header(number).encode())
+	}
+
+	fn submit_heads(relay_block_number: u64, heads: Vec<(ParaId, ParaHead)>, parachains: Vec<ParaId>) {
+		let (relay_block_hash, proof) = import_relay_header(relay_block_number, &heads, &parachains);
+		assert_ok!(Pallet::<TestRuntime>::submit_parachain_heads(
+			Origin::signed(1),
+			relay_block_hash,
+			parachains,
+			proof,
+		));
+	}
+
+	#[test]
+	fn imports_parachain_heads() {
+		run_test(|| {
+			submit_heads(
+				0,
+				vec![(PARA_ID, head(1)), (OTHER_PARA_ID, head(2))],
+				vec![PARA_ID, OTHER_PARA_ID],
+			);
+
+			assert_eq!(Pallet::<TestRuntime>::best_parachain_head(PARA_ID), Some(head(1)));
+			assert_eq!(Pallet::<TestRuntime>::best_parachain_head(OTHER_PARA_ID), Some(head(2)));
+			assert_eq!(
+				frame_system::Pallet::<TestRuntime>::events()
+					.into_iter()
+					.map(|record| record.event)
+					.collect::<Vec<_>>(),
+				vec![
+					TestEvent::Parachains(Event::ParachainHeadUpdated(PARA_ID, head(1).hash())),
+					TestEvent::Parachains(Event::ParachainHeadUpdated(OTHER_PARA_ID, head(2).hash())),
+				],
+			);
+		})
+	}
+
+	#[test]
+	fn ignores_unknown_parachains() {
+		run_test(|| {
+			submit_heads(0, vec![(PARA_ID, head(1))], vec![PARA_ID, OTHER_PARA_ID]);
+
+			assert_eq!(Pallet::<TestRuntime>::best_parachain_head(PARA_ID), Some(head(1)));
+			assert_eq!(Pallet::<TestRuntime>::best_parachain_head(OTHER_PARA_ID), None);
+		})
+	}
+
+	#[test]
+	fn rejects_heads_of_untracked_parachains() {
+		run_test(|| {
+			let (relay_block_hash, proof) = import_relay_header(
+				0,
+				&[(PARA_ID, head(1)), (UNTRACKED_PARA_ID, head(1))],
+				&[PARA_ID, UNTRACKED_PARA_ID],
+			);
+			assert_noop!(
+				Pallet::<TestRuntime>::submit_parachain_heads(
+					Origin::signed(1),
+					relay_block_hash,
+					vec![PARA_ID, UNTRACKED_PARA_ID],
+					proof,
+				),
+				Error::<TestRuntime>::UntrackedParachain,
+			);
+		})
+	}
+
+	#[test]
+	fn rejects_heads_at_unknown_relay_block() {
+		run_test(|| {
+			let (_, proof) = import_relay_header(0, &[(PARA_ID, head(1))], &[PARA_ID]);
+			assert_noop!(
+				Pallet::<TestRuntime>::submit_parachain_heads(
+					Origin::signed(1),
+					Default::default(),
+					vec![PARA_ID],
+					proof,
+				),
+				Error::<TestRuntime>::UnknownRelayChainBlock,
+			);
+		})
+	}
+
+	#[test]
+	fn rejects_invalid_storage_proof() {
+		run_test(|| {
+			let (relay_block_hash, _) = import_relay_header(0, &[(PARA_ID, head(1))], &[PARA_ID]);
+			assert_noop!(
+				Pallet::<TestRuntime>::submit_parachain_heads(
+					Origin::signed(1),
+					relay_block_hash,
+					vec![PARA_ID],
+					vec![],
+				),
+				Error::<TestRuntime>::InvalidStorageProof,
+			);
+		})
+	}
+
+	#[test]
+	fn rejects_too_large_storage_proof() {
+		run_test(|| {
+			let (relay_block_hash, _) = import_relay_header(0, &[(PARA_ID, head(1))], &[PARA_ID]);
+			assert_noop!(
+				Pallet::<TestRuntime>::submit_parachain_heads(
+					Origin::signed(1),
+					relay_block_hash,
+					vec![PARA_ID],
+					vec![vec![0; crate::mock::MaxStorageProofSize::get() as usize + 1]],
+				),
+				Error::<TestRuntime>::StorageProofTooLarge,
+			);
+		})
+	}
+
+	#[test]
+	fn ignores_too_large_heads() {
+		run_test(|| {
+			let large_head = ParaHead(vec![42; crate::mock::MaxParaHeadSize::get() as usize + 1]);
+			submit_heads(
+				0,
+				vec![(PARA_ID, large_head), (OTHER_PARA_ID, head(2))],
+				vec![PARA_ID, OTHER_PARA_ID],
+			);
+
+			assert_eq!(Pallet::<TestRuntime>::best_parachain_head(PARA_ID), None);
+			assert_eq!(Pallet::<TestRuntime>::best_parachain_head(OTHER_PARA_ID), Some(head(2)));
+		})
+	}
+
+	#[test]
+	fn weight_of_not_imported_heads_is_refunded() {
+		run_test(|| {
+			let heads = vec![(PARA_ID, head(1)), (OTHER_PARA_ID, head(2))];
+			let parachains = vec![PARA_ID, OTHER_PARA_ID];
+			let (relay_block_hash, proof) = import_relay_header(0, &heads, &parachains);
+			let proof_size = storage_proof_size(&proof);
+			let declared_weight = submit_parachain_heads_weight::<TestRuntime, ()>(
+				2,
+				proof_size,
+				2 * crate::mock::MaxParaHeadSize::get(),
+			);
+
+			let post_info = Pallet::<TestRuntime>::submit_parachain_heads(
+				Origin::signed(1),
+				relay_block_hash,
+				parachains.clone(),
+				proof.clone(),
+			)
+			.expect("heads are imported");
+			let expected_weight = submit_parachain_heads_weight::<TestRuntime, ()>(
+				2,
+				proof_size,
+				(head(1).0.len() + head(2).0.len()) as u32,
+			);
+			assert_eq!(post_info.actual_weight, Some(expected_weight));
+			assert!(expected_weight < declared_weight);
+
+			// nothing is written when the same heads are submitted again
+			let post_info =
+				Pallet::<TestRuntime>::submit_parachain_heads(Origin::signed(1), relay_block_hash, parachains, proof)
+					.expect("heads are ignored");
+			assert_eq!(
+				post_info.actual_weight,
+				Some(submit_parachain_heads_weight::<TestRuntime, ()>(2, proof_size, 0)),
+			);
+		})
+	}
+
+	#[test]
+	fn ignores_heads_at_older_relay_blocks() {
+		run_test(|| {
+			let (old_relay_block_hash, old_proof) = import_relay_header(0, &[(PARA_ID, head(1))], &[PARA_ID]);
+			submit_heads(1, vec![(PARA_ID, head(2))], vec![PARA_ID]);
+
+			assert_ok!(Pallet::<TestRuntime>::submit_parachain_heads(
+				Origin::signed(1),
+				old_relay_block_hash,
+				vec![PARA_ID],
+				old_proof,
+			));
+			assert_eq!(Pallet::<TestRuntime>::best_parachain_head(PARA_ID), Some(head(2)));
+			assert_eq!(Pallet::<TestRuntime>::parachain_head(PARA_ID, head(1).hash()), None);
+		})
+	}
+
+	#[test]
+	fn prunes_old_parachain_heads() {
+		run_test(|| {
+			submit_heads(0, vec![(PARA_ID, head(1))], vec![PARA_ID]);
+			submit_heads(1, vec![(PARA_ID, head(2))], vec![PARA_ID]);
+			// unchanged head doesn't occupy ring buffer slot
+			submit_heads(2, vec![(PARA_ID, head(2))], vec![PARA_ID]);
+			assert_eq!(
+				Pallet::<TestRuntime>::parachain_head(PARA_ID, head(1).hash()),
+				Some(head(1))
+			);
+
+			submit_heads(3, vec![(PARA_ID, head(3))], vec![PARA_ID]);
+			assert_eq!(Pallet::<TestRuntime>::parachain_head(PARA_ID, head(1).hash()), None);
+			assert_eq!(
+				Pallet::<TestRuntime>::parachain_head(PARA_ID, head(2).hash()),
+				Some(head(2))
+			);
+			assert_eq!(Pallet::<TestRuntime>::best_parachain_head(PARA_ID), Some(head(3)));
+		})
+	}
+
+	#[test]
+	fn parses_finalized_parachain_storage_proof() {
+		run_test(|| {
+			submit_heads(0, vec![(PARA_ID, head(1))], vec![PARA_ID]);
+
+			assert_eq!(
+				Pallet::<TestRuntime>::parse_finalized_storage_proof::<TestBridgedChain, _>(
+					PARA_ID,
+					head(1).hash(),
+					parachain_storage_proof(b"key1"),
+					|storage| storage.read_value(b"key1"),
+				),
+				Ok(Ok(Some(b"value1".to_vec()))),
+			);
+			assert_noop!(
+				Pallet::<TestRuntime>::parse_finalized_storage_proof::<TestBridgedChain, _>(
+					PARA_ID,
+					head(2).hash(),
+					parachain_storage_proof(b"key1"),
+					|_| (),
+				),
+				Error::<TestRuntime>::UnknownParaHead,
+			);
+		})
+	}
+
+	#[test]
+	fn pallet_rejects_transactions_if_halted() {
+		run_test(|| {
+			let (relay_block_hash, proof) = import_relay_header(0, &[(PARA_ID, head(1))], &[PARA_ID]);
+			assert_ok!(Pallet::<TestRuntime>::set_operational(Origin::root(), false));
+
+			assert_noop!(
+				Pallet::<TestRuntime>::submit_parachain_heads(
+					Origin::signed(1),
+					relay_block_hash,
+					vec![PARA_ID],
+					proof.clone(),
+				),
+				Error::<TestRuntime>::Halted,
+			);
+
+			assert_ok!(Pallet::<TestRuntime>::set_operational(Origin::root(), true));
+			assert_ok!(Pallet::<TestRuntime>::submit_parachain_heads(
+				Origin::signed(1),
+				relay_block_hash,
+				vec![PARA_ID],
+				proof,
+			));
+		})
+	}
+
+	#[test]
+	fn pallet_may_be_halted_by_owner() {
+		run_test(|| {
+			PalletOwner::<TestRuntime>::put(2);
+
+			assert_noop!(
+				Pallet::<TestRuntime>::set_operational(Origin::signed(1), false),
+				DispatchError::BadOrigin,
+			);
+			assert_ok!(Pallet::<TestRuntime>::set_operational(Origin::signed(2), false));
+			assert!(IsHalted::<TestRuntime>::get());
+			assert_ok!(Pallet::<TestRuntime>::set_operational(Origin::signed(2), true));
+			assert!(!IsHalted::<TestRuntime>::get());
+		})
+	}
+
+	#[test]
+	fn ownership_may_only_be_accepted_by_proposed_owner() {
+		run_test(|| {
+			PalletOwner::<TestRuntime>::put(2);
+
+			assert_noop!(
+				Pallet::<TestRuntime>::propose_owner(Origin::signed(1), Some(1)),
+				DispatchError::BadOrigin,
+			);
+			assert_ok!(Pallet::<TestRuntime>::propose_owner(Origin::signed(2), Some(1)));
+			assert_noop!(
+				Pallet::<TestRuntime>::accept_owner(Origin::signed(3)),
+				Error::<TestRuntime>::NotPendingOwner,
+			);
+
+			assert_ok!(Pallet::<TestRuntime>::accept_owner(Origin::signed(1)));
+			assert_eq!(PalletOwner::<TestRuntime>::get(), Some(1));
+			assert_eq!(PendingPalletOwner::<TestRuntime>::get(), None);
+
+			assert_ok!(Pallet::<TestRuntime>::propose_owner(Origin::root(), None));
+			assert_eq!(PalletOwner::<TestRuntime>::get(), None);
+			assert_eq!(
+				frame_system::Pallet::<TestRuntime>::events()
+					.into_iter()
+					.map(|record| record.event)
+					.collect::<Vec<_>>(),
+				vec![
+					TestEvent::Parachains(Event::OwnerProposed(1)),
+					TestEvent::Parachains(Event::OwnerChanged(Some(1))),
+					TestEvent::Parachains(Event::OwnerChanged(None)),
+				],
+			);
+		})
+	}
+}
@@ -0,0 +1,239 @@
+// Copyright 2021 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+// From construct_runtime macro
+#![allow(clippy::from_over_into)]
+
+use crate as pallet_bridge_parachains;
+use crate::{parachain_head_storage_key, ParaHead, ParaId, RawStorageProof};
+
+use bp_runtime::Chain;
+use codec::Encode;
+use frame_support::weights::Weight;
+use sp_core::H256;
+use sp_runtime::{
+	testing::Header as SubstrateHeader,
+	traits::{BlakeTwo256, Header as HeaderT, IdentityLookup},
+	Perbill,
+};
+use sp_state_machine::{backend::Backend, InMemoryBackend};
+
+pub type AccountId = u64;
+pub type Block = frame_system::mocking::MockBlock<TestRuntime>;
+pub type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<TestRuntime>;
+
+/// Name of the `Paras` pallet at the test relay chain.
+pub const PARAS_PALLET_NAME: &str = "Paras";
+
+frame_support::construct_runtime! {
+	pub enum TestRuntime where
+		Block = Block,
+		NodeBlock = Block,
+		UncheckedExtrinsic = UncheckedExtrinsic,
+	{
+		System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
+		Grandpa: pallet_bridge_grandpa::{Pallet, Call, Event<T>},
+		Parachains: pallet_bridge_parachains::{Pallet, Call, Storage, Event<T>},
+	}
+}
+
+frame_support::parameter_types! {
+	pub const BlockHashCount: u64 = 250;
+	pub const MaximumBlockWeight: Weight = 1024;
+	pub const MaximumBlockLength: u32 = 2 * 1024;
+	pub const AvailableBlockRatio: Perbill = Perbill::one();
+}
+
+impl frame_system::Config for TestRuntime {
+	type Origin = Origin;
+	type Index = u64;
+	type Call = Call;
+	type BlockNumber = u64;
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	type AccountId = AccountId;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = SubstrateHeader;
+	type Event = Event;
+	type BlockHashCount = BlockHashCount;
+	type Version = ();
+	type PalletInfo = PalletInfo;
+	type AccountData = ();
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+	type BaseCallFilter = ();
+	type SystemWeightInfo = ();
+	type BlockWeights = ();
+	type BlockLength = ();
+	type DbWeight = ();
+	type SS58Prefix = ();
+	type OnSetCode = ();
+}
+
+frame_support::parameter_types! {
+	pub const MaxRequests: u32 = 16;
+	pub const HeadersToKeep: u32 = 5;
+	pub const MaxHeadersToPruneInBlock: u32 = 1;
+	pub const AuthoritySetChangesToKeep: u32 = 3;
+	pub const AuthoritySetsToKeep: u32 = 0;
+	pub const AcceptAncestryProofs: bool = false;
+	pub const FreeMandatoryHeadersPerBlock: u32 = 1;
+}
+
+impl pallet_bridge_grandpa::Config for TestRuntime {
+	type Event = Event;
+	type BridgedChain = TestBridgedChain;
+	type MaxRequests = MaxRequests;
+	type HeadersToKeep = HeadersToKeep;
+	type MaxHeadersToPruneInBlock = MaxHeadersToPruneInBlock;
+	type AuthoritySetChangesToKeep = AuthoritySetChangesToKeep;
+	type AuthoritySetsToKeep = AuthoritySetsToKeep;
+	type AcceptAncestryProofs = AcceptAncestryProofs;
+	type FreeMandatoryHeadersPerBlock = FreeMandatoryHeadersPerBlock;
+	type OnEquivocation = ();
+	type WeightInfo = ();
+}
+
+frame_support::parameter_types! {
+	pub const ParasPalletName: &'static str = PARAS_PALLET_NAME;
+	pub const HeadsToKeep: u32 = 2;
+	pub const MaxParaHeadSize: u32 = 1024;
+	pub const MaxStorageProofSize: u32 = 16 * 1024;
+	pub const StorageProofByteWeight: Weight = 1;
+	pub const ParaHeadByteWeight: Weight = 2;
+	pub TrackedParachains: Vec<ParaId> = vec![ParaId(42), ParaId(43)];
+}
+
+impl pallet_bridge_parachains::Config for TestRuntime {
+	type Event = Event;
+	type ParasPalletName = ParasPalletName;
+	type HeadsToKeep = HeadsToKeep;
+	type TrackedParachains = TrackedParachains;
+	type MaxParaHeadSize = MaxParaHeadSize;
+	type MaxStorageProofSize = MaxStorageProofSize;
+	type StorageProofByteWeight = StorageProofByteWeight;
+	type ParaHeadByteWeight = ParaHeadByteWeight;
+}
+
+/// Bridged relay chain. Parachains of this relay chain are using the same primitives.
+#[derive(Debug)]
+pub struct TestBridgedChain;
+
+impl Chain for TestBridgedChain {
+	type BlockNumber = <TestRuntime as frame_system::Config>::BlockNumber;
+	type Hash = <TestRuntime as frame_system::Config>::Hash;
+	type Hasher = <TestRuntime as frame_system::Config>::Hashing;
+	type Header = <TestRuntime as frame_system::Config>::Header;
+}
+
+impl bp_header_chain::ChainWithGrandpa for TestBridgedChain {
+	const MAX_AUTHORITIES_COUNT: u32 = 16;
+	const REASONABLE_HEADERS_IN_JUSTIFICATON_ANCESTRY: u32 = 8;
+	const MAX_HEADER_SIZE: u32 = 256;
+}
+
+/// Run pallet test.
+pub fn run_test<T>(test: impl FnOnce() -> T) -> T {
+	let t = frame_system::GenesisConfig::default()
+		.build_storage::<TestRuntime>()
+		.unwrap();
+	let mut ext = sp_io::TestExternalities::new(t);
+	ext.execute_with(|| {
+		frame_system::Pallet::<TestRuntime>::set_block_number(1);
+		test()
+	})
+}
+
+/// Storage of the bridged relay chain with given parachain heads.
+fn relay_chain_storage(heads: &[(ParaId, ParaHead)]) -> InMemoryBackend<BlakeTwo256> {
+	vec![(
+		None,
+		heads
+			.iter()
+			.map(|(para_id, head)| {
+				(
+					parachain_head_storage_key(PARAS_PALLET_NAME, *para_id).0,
+					Some(head.encode()),
+				)
+			})
+			.collect(),
+	)]
+	.into()
+}
+
+/// Storage of the bridged parachain.
+fn parachain_storage() -> InMemoryBackend<BlakeTwo256> {
+	vec![(None, vec![(b"key1".to_vec(), Some(b"value1".to_vec()))])].into()
+}
+
+/// Import relay chain header of given number, which storage contains given parachain heads.
+/// Header with number zero is used to initialize the GRANDPA pallet.
+///
+/// Returns hash of imported header and storage proof of heads of given parachains.
+pub fn import_relay_header(
+	number: u64,
+	heads: &[(ParaId, ParaHead)],
+	parachains: &[ParaId],
+) -> (H256, RawStorageProof) {
+	let storage = relay_chain_storage(heads);
+	let mut header: SubstrateHeader = bp_test_utils::test_header(number);
+	header.set_state_root(storage.storage_root(std::iter::empty()).0);
+	if number == 0 {
+		pallet_bridge_grandpa::Pallet::<TestRuntime>::initialize(
+			Origin::root(),
+			bp_header_chain::InitializationData {
+				header: header.clone(),
+				authority_list: bp_test_utils::authority_list(),
+				set_id: 1,
+				is_halted: false,
+				genesis_hash: Default::default(),
+			},
+		)
+		.unwrap();
+	} else {
+		header.set_parent_hash(pallet_bridge_grandpa::Pallet::<TestRuntime>::best_finalized().hash());
+		let justification = bp_test_utils::make_default_justification(&header);
+		pallet_bridge_grandpa::Pallet::<TestRuntime>::submit_finality_proof(
+			Origin::signed(1),
+			header.clone(),
+			justification,
+		)
+		.unwrap();
+	}
+
+	let keys = parachains
+		.iter()
+		.map(|para_id| parachain_head_storage_key(PARAS_PALLET_NAME, *para_id).0)
+		.collect::<Vec<_>>();
+	let proof = sp_state_machine::prove_read(storage, &keys)
+		.unwrap()
+		.iter_nodes()
+		.collect();
+
+	(header.hash(), proof)
+}
+
+/// Parachain header of given number, which state root matches the parachain storage.
+pub fn parachain_header(number: u64) -> SubstrateHeader {
+	let mut header: SubstrateHeader = bp_test_utils::test_header(number);
+	header.set_state_root(parachain_storage().storage_root(std::iter::empty()).0);
+	header
+}
+
+/// Proof of given key in the parachain storage.
+pub fn parachain_storage_proof(key: &[u8]) -> sp_trie::StorageProof {
+	sp_state_machine::prove_read(parachain_storage(), &[key]).unwrap()
+}
@@ -0,0 +1,540 @@
+// Copyright 2019-2021 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Runtime module that mirrors storage values of the bridged chain.
+//!
+//! Applications may want to read the bridged chain state (e.g. staking totals or prices) without
+//! encoding and dispatching any calls at the bridged chain. To do that, they send a message with
+//! the `StorageMirrorMessage::ProveStorage` payload. The payload contains set of storage keys and
+//! the storage proof, generated at some bridged chain header, finalized by the GRANDPA pallet.
+//! When such message is dispatched by the `StorageMirrorDispatch`, the proof is verified and all
+//! proven values are written into the `MirroredValues` storage map, along with the bridged header
+//! they have been proven at and the local block number of the update. All other messages are
+//! passed to the wrapped dispatcher.
+//!
+//! Instance `I` of this pallet mirrors storage of the chain, whose headers are tracked by the same
+//! instance of the GRANDPA pallet.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use bp_message_dispatch::MessageDispatch;
+use bp_runtime::{messages::MessageDispatchResult, ChainId};
+use codec::{Decode, Encode};
+use frame_support::{weights::Weight, RuntimeDebug};
+use pallet_bridge_grandpa::{BridgedBlockHash, BridgedBlockNumber};
+use sp_runtime::traits::Header as HeaderT;
+use sp_std::{fmt::Debug, marker::PhantomData, prelude::*};
+
+#[cfg(test)]
+mod mock;
+
+/// Value of the first four bytes of the encoded `StorageMirrorMessage::ProveStorage`.
+///
+/// Payloads of the wrapped dispatcher (legacy and versioned call dispatch payloads) never start
+/// with this value, so both payload types may be sent over the same lane.
+pub const STORAGE_PROOF_MESSAGE_MARKER: u32 = u32::MAX - 1;
+
+/// Raw storage proof - set of trie nodes.
+pub type RawStorageProof = Vec<Vec<u8>>;
+
+/// Proof of bridged chain storage values.
+#[derive(Encode, Decode, Clone, RuntimeDebug, PartialEq, Eq)]
+pub struct StorageProofPayload<BridgedHash> {
+	/// Hash of the finalized bridged chain header, the proof has been generated at.
+	pub at: BridgedHash,
+	/// Storage keys that need to be mirrored.
+	pub keys: Vec<Vec<u8>>,
+	/// Storage proof of all values.
+	pub proof: RawStorageProof,
+}
+
+impl<BridgedHash> StorageProofPayload<BridgedHash> {
+	/// Returns size of the proof in bytes.
+	pub fn proof_size(&self) -> u32 {
+		self.proof
+			.iter()
+			.fold(0usize, |size, node| size.saturating_add(node.len())) as _
+	}
+}
+
+/// Storage proof payload type used by the pallet.
+pub type StorageProofPayloadOf<T, I> = StorageProofPayload<BridgedBlockHash<T, I>>;
+
+/// Message that may be dispatched by the `StorageMirrorDispatch`.
+#[derive(Clone, RuntimeDebug, PartialEq, Eq)]
+pub enum StorageMirrorMessage<BridgedHash, Message> {
+	/// Message of the wrapped dispatcher.
+	Dispatch(Message),
+	/// Proof of the bridged chain storage values.
+	ProveStorage(StorageProofPayload<BridgedHash>),
+}
+
+impl<BridgedHash: Encode, Message: Encode> Encode for StorageMirrorMessage<BridgedHash, Message> {
+	fn encode_to<T: codec::Output + ?Sized>(&self, dest: &mut T) {
+		match *self {
+			StorageMirrorMessage::Dispatch(ref message) => message.encode_to(dest),
+			StorageMirrorMessage::ProveStorage(ref payload) => {
+				STORAGE_PROOF_MESSAGE_MARKER.encode_to(dest);
+				payload.encode_to(dest);
+			}
+		}
+	}
+}
+
+impl<BridgedHash: Decode, Message: Decode> Decode for StorageMirrorMessage<BridgedHash, Message> {
+	fn decode<I: codec::Input>(input: &mut I) -> Result<Self, codec::Error> {
+		let marker = <[u8; 4]>::decode(input)?;
+		if marker == STORAGE_PROOF_MESSAGE_MARKER.to_le_bytes() {
+			return Ok(StorageMirrorMessage::ProveStorage(Decode::decode(input)?));
+		}
+
+		Ok(StorageMirrorMessage::Dispatch(Decode::decode(&mut PrefixedInput {
+			prefix: &marker[..],
+			input,
+		})?))
+	}
+}
+
+/// Input that yields prefix bytes before bytes of the wrapped input.
+struct PrefixedInput<'a, I> {
+	prefix: &'a [u8],
+	input: &'a mut I,
+}
+
+impl<'a, I: codec::Input> codec::Input for PrefixedInput<'a, I> {
+	fn remaining_len(&mut self) -> Result<Option<usize>, codec::Error> {
+		Ok(self.input.remaining_len()?.map(|len| len + self.prefix.len()))
+	}
+
+	fn read(&mut self, into: &mut [u8]) -> Result<(), codec::Error> {
+		let from_prefix = sp_std::cmp::min(self.prefix.len(), into.len());
+		into[..from_prefix].copy_from_slice(&self.prefix[..from_prefix]);
+		self.prefix = &self.prefix[from_prefix..];
+		self.input.read(&mut into[from_prefix..])
+	}
+}
+
+/// Mirrored storage value of the bridged chain.
+#[derive(Encode, Decode, Clone, RuntimeDebug, PartialEq, Eq)]
+pub struct MirroredValue<BridgedHash, BridgedNumber, BlockNumber> {
+	/// The value. `None` if it has been proven that there's no value at the bridged chain.
+	pub value: Option<Vec<u8>>,
+	/// Hash of the bridged chain header, the value has been proven at.
+	pub bridged_block_hash: BridgedHash,
+	/// Number of the bridged chain header, the value has been proven at.
+	pub bridged_block_number: BridgedNumber,
+	/// Number of this chain block, where the value has been updated.
+	pub updated_at: BlockNumber,
+}
+
+/// Mirrored value type used by the pallet.
+pub type MirroredValueOf<T, I> =
+	MirroredValue<BridgedBlockHash<T, I>, BridgedBlockNumber<T, I>, <T as frame_system::Config>::BlockNumber>;
+
+pub use pallet::*;
+
+// comes from #[pallet::event]
+#[allow(clippy::unused_unit)]
+#[frame_support::pallet]
+pub mod pallet {
+	use super::*;
+	use frame_support::pallet_prelude::*;
+	use frame_system::pallet_prelude::*;
+
+	#[pallet::config]
+	pub trait Config<I: 'static = ()>: frame_system::Config + pallet_bridge_grandpa::Config<I> {
+		/// The overarching event type.
+		type Event: From<Event<Self, I>> + IsType<<Self as frame_system::Config>::Event>;
+		/// Maximal number of keys in the single storage proof message.
+		#[pallet::constant]
+		type MaxKeysPerMessage: Get<u32>;
+		/// Maximal size of the single mirrored value.
+		#[pallet::constant]
+		type MaxValueSize: Get<u32>;
+		/// Weight of verifying single byte of the storage proof.
+		#[pallet::constant]
+		type StorageProofByteWeight: Get<Weight>;
+	}
+
+	#[pallet::pallet]
+	#[pallet::generate_store(pub(super) trait Store)]
+	pub struct Pallet<T, I = ()>(PhantomData<(T, I)>);
+
+	#[pallet::hooks]
+	impl<T: Config<I>, I: 'static> Hooks<BlockNumberFor<T>> for Pallet<T, I> {}
+
+	#[pallet::call]
+	impl<T: Config<I>, I: 'static> Pallet<T, I> {}
+
+	#[pallet::event]
+	#[pallet::metadata(BridgedBlockHash<T, I> = "BridgedHash")]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config<I>, I: 'static = ()> {
+		/// Storage values, proven at given bridged header, have been mirrored. The second field is
+		/// the number of updated values.
+		StorageMirrored(BridgedBlockHash<T, I>, u32),
+	}
+
+	#[pallet::error]
+	pub enum Error<T, I = ()> {
+		/// Too many keys in the single storage proof message.
+		TooManyKeys,
+		/// The bridged header is not known to the GRANDPA pallet.
+		UnknownHeader,
+		/// The storage proof is invalid.
+		InvalidStorageProof,
+		/// One of proven values is too large to be mirrored.
+		ValueTooLarge,
+	}
+
+	/// Mirrored storage values of the bridged chain, mapped by the bridged chain storage key.
+	#[pallet::storage]
+	#[pallet::getter(fn mirrored_value)]
+	pub type MirroredValues<T: Config<I>, I: 'static = ()> =
+		StorageMap<_, Blake2_128Concat, Vec<u8>, MirroredValueOf<T, I>>;
+
+	impl<T: Config<I>, I: 'static> Pallet<T, I> {
+		/// Verify storage proof and mirror all proven values.
+		///
+		/// Values that have already been mirrored at later bridged headers are left intact.
+		/// Returns number of updated values.
+		pub fn mirror_storage(payload: StorageProofPayloadOf<T, I>) -> Result<u32, Error<T, I>> {
+			ensure!(
+				payload.keys.len() <= T::MaxKeysPerMessage::get() as usize,
+				Error::<T, I>::TooManyKeys,
+			);
+
+			let header = pallet_bridge_grandpa::Pallet::<T, I>::imported_header(payload.at)
+				.ok_or(Error::<T, I>::UnknownHeader)?;
+			let bridged_block_number = *header.number();
+			let StorageProofPayload { at, keys, proof } = payload;
+			let values = pallet_bridge_grandpa::Pallet::<T, I>::parse_finalized_storage_proof(
+				at,
+				sp_trie::StorageProof::new(proof),
+				|storage| {
+					keys.into_iter()
+						.map(|key| storage.read_value(&key).map(|value| (key, value)))
+						.collect::<Result<Vec<_>, _>>()
+				},
+			)
+			.map_err(|_| Error::<T, I>::InvalidStorageProof)?
+			.map_err(|_| Error::<T, I>::InvalidStorageProof)?;
+
+			// check all values before writing anything, so that the storage is updated atomically
+			let max_value_size = T::MaxValueSize::get() as usize;
+			ensure!(
+				values
+					.iter()
+					.all(|(_, value)| value.as_ref().map(|value| value.len()).unwrap_or(0) <= max_value_size),
+				Error::<T, I>::ValueTooLarge,
+			);
+
+			let updated_at = frame_system::Pallet::<T>::block_number();
+			let mut updated_values = 0;
+			for (key, value) in values {
+				let is_stale = MirroredValues::<T, I>::get(&key)
+					.map(|mirrored| mirrored.bridged_block_number > bridged_block_number)
+					.unwrap_or(false);
+				if is_stale {
+					log::trace!(
+						target: "runtime::bridge-storage-mirror",
+						"Ignoring value of {:?}, proven at {:?}: already mirrored at later header",
+						key,
+						at,
+					);
+					continue;
+				}
+
+				MirroredValues::<T, I>::insert(
+					key,
+					MirroredValue {
+						value,
+						bridged_block_hash: at,
+						bridged_block_number,
+						updated_at,
+					},
+				);
+				updated_values += 1;
+			}
+
+			log::trace!(
+				target: "runtime::bridge-storage-mirror",
+				"Mirrored {} values, proven at {:?}",
+				updated_values,
+				at,
+			);
+
+			Self::deposit_event(Event::StorageMirrored(at, updated_values));
+
+			Ok(updated_values)
+		}
+	}
+}
+
+/// Returns weight of mirroring values from the given storage proof.
+pub fn mirror_storage_weight<T: Config<I>, I: 'static>(payload: &StorageProofPayloadOf<T, I>) -> Weight {
+	use frame_support::traits::Get;
+
+	let keys_count = payload.keys.len() as Weight;
+	<T as frame_system::Config>::DbWeight::get()
+		.reads_writes(1 + keys_count, keys_count)
+		.saturating_add(T::StorageProofByteWeight::get().saturating_mul(payload.proof_size() as Weight))
+}
+
+/// Message dispatcher that mirrors bridged chain storage values if the message is the storage
+/// proof and passes all other messages to the wrapped `Dispatch`.
+pub struct StorageMirrorDispatch<T, I, Dispatch>(PhantomData<(T, I, Dispatch)>);
+
+impl<T, I, Dispatch, AccountId, MessageId> MessageDispatch<AccountId, MessageId>
+	for StorageMirrorDispatch<T, I, Dispatch>
+where
+	T: Config<I>,
+	I: 'static,
+	Dispatch: MessageDispatch<AccountId, MessageId>,
+	MessageId: Debug,
+{
+	type Message = StorageMirrorMessage<BridgedBlockHash<T, I>, Dispatch::Message>;
+
+	fn dispatch_weight(message: &Self::Message) -> Weight {
+		match *message {
+			StorageMirrorMessage::Dispatch(ref message) => Dispatch::dispatch_weight(message),
+			StorageMirrorMessage::ProveStorage(ref payload) => mirror_storage_weight::<T, I>(payload),
+		}
+	}
+
+	fn dispatch<P: FnOnce(&AccountId, Weight) -> Result<(), ()>>(
+		source_chain: ChainId,
+		target_chain: ChainId,
+		id: MessageId,
+		message: Result<Self::Message, Vec<u8>>,
+		pay_dispatch_fee: P,
+	) -> MessageDispatchResult {
+		let payload = match message {
+			Ok(StorageMirrorMessage::ProveStorage(payload)) => payload,
+			Ok(StorageMirrorMessage::Dispatch(message)) => {
+				return Dispatch::dispatch(source_chain, target_chain, id, Ok(message), pay_dispatch_fee)
+			}
+			Err(payload) => return Dispatch::dispatch(source_chain, target_chain, id, Err(payload), pay_dispatch_fee),
+		};
+
+		let result = Pallet::<T, I>::mirror_storage(payload);
+		if let Err(ref error) = result {
+			log::trace!(
+				target: "runtime::bridge-storage-mirror",
+				"Message {:?}/{:?}: failed to mirror storage: {:?}",
+				source_chain,
+				id,
+				error,
+			);
+		}
+
+		MessageDispatchResult {
+			dispatch_result: result.is_ok(),
+			// the proof has (most likely) been verified even if we have failed to mirror values
+			unspent_weight: 0,
+			dispatch_fee_paid_during_dispatch: false,
+		}
+	}
+}
+
+impl<T: Config<I>, I: 'static> From<Error<T, I>> for bp_runtime::BridgeError {
+	fn from(error: Error<T, I>) -> Self {
+		match error {
+			Error::TooManyKeys => Self::LimitExceeded,
+			Error::UnknownHeader => Self::UnknownHeader,
+			Error::InvalidStorageProof => Self::InvalidStorageProof,
+			Error::ValueTooLarge => Self::LimitExceeded,
+			// hidden variant that is never constructed
+			_ => Self::InvalidState,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::mock::{
+		initialize_grandpa, run_test, storage_proof_payload, storage_proof_payload_for, Event as TestEvent,
+		TestDispatch, TestMessageId, TestRuntime, LARGE_VALUE_KEY,
+	};
+	use frame_support::assert_noop;
+	use frame_system::{EventRecord, Pallet as System, Phase};
+
+	type Dispatcher = StorageMirrorDispatch<TestRuntime, (), TestDispatch>;
+	type Message = StorageMirrorMessage<sp_core::H256, u64>;
+
+	fn dispatch(message: Message) -> MessageDispatchResult {
+		<Dispatcher as MessageDispatch<u64, TestMessageId>>::dispatch(
+			*b"srce",
+			*b"trgt",
+			[0; 4],
+			Ok(message),
+			|_, _| unreachable!(),
+		)
+	}
+
+	#[test]
+	fn storage_proof_message_is_encoded_and_decoded() {
+		let message = Message::ProveStorage(StorageProofPayload {
+			at: Default::default(),
+			keys: vec![b"key1".to_vec()],
+			proof: vec![vec![42]],
+		});
+		let encoded_message = message.encode();
+		assert_eq!(&encoded_message[..4], &STORAGE_PROOF_MESSAGE_MARKER.encode()[..]);
+		assert_eq!(Message::decode(&mut &encoded_message[..]), Ok(message));
+	}
+
+	#[test]
+	fn wrapped_message_is_encoded_and_decoded() {
+		let message = Message::Dispatch(42);
+		let encoded_message = message.encode();
+		assert_eq!(encoded_message, 42u64.encode());
+		assert_eq!(Message::decode(&mut &encoded_message[..]), Ok(message));
+	}
+
+	#[test]
+	fn wrapped_message_is_passed_to_wrapped_dispatcher() {
+		run_test(|| {
+			assert!(dispatch(Message::Dispatch(42)).dispatch_result);
+			assert!(!dispatch(Message::Dispatch(0)).dispatch_result);
+		});
+	}
+
+	#[test]
+	fn proven_values_are_mirrored() {
+		run_test(|| {
+			let at = initialize_grandpa(10);
+
+			let result = dispatch(Message::ProveStorage(storage_proof_payload(at)));
+			assert!(result.dispatch_result);
+
+			assert_eq!(
+				Pallet::<TestRuntime>::mirrored_value(b"key1".to_vec()),
+				Some(MirroredValue {
+					value: Some(b"value1".to_vec()),
+					bridged_block_hash: at,
+					bridged_block_number: 10,
+					updated_at: 1,
+				}),
+			);
+			assert_eq!(
+				Pallet::<TestRuntime>::mirrored_value(b"key22".to_vec()),
+				Some(MirroredValue {
+					value: None,
+					bridged_block_hash: at,
+					bridged_block_number: 10,
+					updated_at: 1,
+				}),
+			);
+			assert_eq!(
+				System::<TestRuntime>::events(),
+				vec![EventRecord {
+					phase: Phase::Initialization,
+					event: TestEvent::StorageMirror(Event::StorageMirrored(at, 3)),
+					topics: vec![],
+				}],
+			);
+		});
+	}
+
+	#[test]
+	fn values_proven_at_older_headers_are_ignored() {
+		run_test(|| {
+			let at = initialize_grandpa(10);
+			let mirrored_value = MirroredValue {
+				value: Some(b"fresh".to_vec()),
+				bridged_block_hash: Default::default(),
+				bridged_block_number: 20,
+				updated_at: 0,
+			};
+			MirroredValues::<TestRuntime>::insert(b"key1".to_vec(), mirrored_value.clone());
+
+			assert_eq!(Pallet::<TestRuntime>::mirror_storage(storage_proof_payload(at)), Ok(2));
+			assert_eq!(
+				Pallet::<TestRuntime>::mirrored_value(b"key1".to_vec()),
+				Some(mirrored_value)
+			);
+		});
+	}
+
+	#[test]
+	fn storage_proof_at_unknown_header_is_rejected() {
+		run_test(|| {
+			initialize_grandpa(10);
+
+			assert_noop!(
+				Pallet::<TestRuntime>::mirror_storage(storage_proof_payload(Default::default())),
+				Error::<TestRuntime, ()>::UnknownHeader,
+			);
+			assert!(!dispatch(Message::ProveStorage(storage_proof_payload(Default::default()))).dispatch_result);
+		});
+	}
+
+	#[test]
+	fn invalid_storage_proof_is_rejected() {
+		run_test(|| {
+			let at = initialize_grandpa(10);
+			let mut payload = storage_proof_payload(at);
+			payload.proof = vec![];
+
+			assert_noop!(
+				Pallet::<TestRuntime>::mirror_storage(payload),
+				Error::<TestRuntime, ()>::InvalidStorageProof,
+			);
+		});
+	}
+
+	#[test]
+	fn too_many_keys_are_rejected() {
+		run_test(|| {
+			let at = initialize_grandpa(10);
+			let mut payload = storage_proof_payload(at);
+			payload.keys = vec![b"key1".to_vec(); 100];
+
+			assert_noop!(
+				Pallet::<TestRuntime>::mirror_storage(payload),
+				Error::<TestRuntime, ()>::TooManyKeys,
+			);
+		});
+	}
+
+	#[test]
+	fn too_large_values_are_rejected() {
+		run_test(|| {
+			let at = initialize_grandpa(10);
+			let payload = storage_proof_payload_for(at, vec![b"key1".to_vec(), LARGE_VALUE_KEY.to_vec()]);
+
+			assert_noop!(
+				Pallet::<TestRuntime>::mirror_storage(payload),
+				Error::<TestRuntime, ()>::ValueTooLarge,
+			);
+		});
+	}
+
+	#[test]
+	fn storage_proof_weight_depends_on_keys_and_proof_size() {
+		let payload = storage_proof_payload(Default::default());
+		let mut larger_payload = payload.clone();
+		larger_payload.proof.push(vec![0; 1024]);
+
+		assert!(
+			<Dispatcher as MessageDispatch<u64, TestMessageId>>::dispatch_weight(&Message::ProveStorage(
+				larger_payload
+			)) > <Dispatcher as MessageDispatch<u64, TestMessageId>>::dispatch_weight(&Message::ProveStorage(payload)),
+		);
+	}
+}
@@ -0,0 +1,227 @@
+// Copyright 2019-2021 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+// From construct_runtime macro
+#![allow(clippy::from_over_into)]
+
+use crate as pallet_bridge_storage_mirror;
+
+use bp_message_dispatch::MessageDispatch;
+use bp_runtime::{messages::MessageDispatchResult, Chain, ChainId};
+use frame_support::weights::Weight;
+use sp_core::H256;
+use sp_runtime::{
+	testing::Header as SubstrateHeader,
+	traits::{BlakeTwo256, Header as HeaderT, IdentityLookup},
+	Perbill,
+};
+
+pub type AccountId = u64;
+pub type TestMessageId = [u8; 4];
+pub type Block = frame_system::mocking::MockBlock<TestRuntime>;
+pub type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<TestRuntime>;
+
+/// Maximal size of the mirrored value.
+pub const MAX_VALUE_SIZE: u32 = 16;
+/// Key of the value that is too large to be mirrored.
+pub const LARGE_VALUE_KEY: &[u8] = b"large";
+
+frame_support::construct_runtime! {
+	pub enum TestRuntime where
+		Block = Block,
+		NodeBlock = Block,
+		UncheckedExtrinsic = UncheckedExtrinsic,
+	{
+		System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
+		Grandpa: pallet_bridge_grandpa::{Pallet, Call, Event<T>},
+		StorageMirror: pallet_bridge_storage_mirror::{Pallet, Storage, Event<T>},
+	}
+}
+
+frame_support::parameter_types! {
+	pub const BlockHashCount: u64 = 250;
+	pub const MaximumBlockWeight: Weight = 1024;
+	pub const MaximumBlockLength: u32 = 2 * 1024;
+	pub const AvailableBlockRatio: Perbill = Perbill::one();
+}
+
+impl frame_system::Config for TestRuntime {
+	type Origin = Origin;
+	type Index = u64;
+	type Call = Call;
+	type BlockNumber = u64;
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	type AccountId = AccountId;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = SubstrateHeader;
+	type Event = Event;
+	type BlockHashCount = BlockHashCount;
+	type Version = ();
+	type PalletInfo = PalletInfo;
+	type AccountData = ();
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+	type BaseCallFilter = ();
+	type SystemWeightInfo = ();
+	type BlockWeights = ();
+	type BlockLength = ();
+	type DbWeight = ();
+	type SS58Prefix = ();
+	type OnSetCode = ();
+}
+
+frame_support::parameter_types! {
+	pub const MaxRequests: u32 = 2;
+	pub const HeadersToKeep: u32 = 5;
+	pub const MaxHeadersToPruneInBlock: u32 = 1;
+	pub const AuthoritySetChangesToKeep: u32 = 3;
+	pub const AuthoritySetsToKeep: u32 = 0;
+	pub const AcceptAncestryProofs: bool = false;
+	pub const FreeMandatoryHeadersPerBlock: u32 = 1;
+}
+
+impl pallet_bridge_grandpa::Config for TestRuntime {
+	type Event = Event;
+	type BridgedChain = TestBridgedChain;
+	type MaxRequests = MaxRequests;
+	type HeadersToKeep = HeadersToKeep;
+	type MaxHeadersToPruneInBlock = MaxHeadersToPruneInBlock;
+	type AuthoritySetChangesToKeep = AuthoritySetChangesToKeep;
+	type AuthoritySetsToKeep = AuthoritySetsToKeep;
+	type AcceptAncestryProofs = AcceptAncestryProofs;
+	type FreeMandatoryHeadersPerBlock = FreeMandatoryHeadersPerBlock;
+	type OnEquivocation = ();
+	type WeightInfo = ();
+}
+
+frame_support::parameter_types! {
+	pub const MaxKeysPerMessage: u32 = 16;
+	pub const MaxValueSize: u32 = MAX_VALUE_SIZE;
+	pub const StorageProofByteWeight: Weight = 10;
+}
+
+impl pallet_bridge_storage_mirror::Config for TestRuntime {
+	type Event = Event;
+	type MaxKeysPerMessage = MaxKeysPerMessage;
+	type MaxValueSize = MaxValueSize;
+	type StorageProofByteWeight = StorageProofByteWeight;
+}
+
+#[derive(Debug)]
+pub struct TestBridgedChain;
+
+impl Chain for TestBridgedChain {
+	type BlockNumber = <TestRuntime as frame_system::Config>::BlockNumber;
+	type Hash = <TestRuntime as frame_system::Config>::Hash;
+	type Hasher = <TestRuntime as frame_system::Config>::Hashing;
+	type Header = <TestRuntime as frame_system::Config>::Header;
+}
+
+impl bp_header_chain::ChainWithGrandpa for TestBridgedChain {
+	const MAX_AUTHORITIES_COUNT: u32 = 16;
+	const REASONABLE_HEADERS_IN_JUSTIFICATON_ANCESTRY: u32 = 8;
+	const MAX_HEADER_SIZE: u32 = 256;
+}
+
+/// Message dispatcher that succeeds if message is non-zero.
+pub struct TestDispatch;
+
+impl MessageDispatch<AccountId, TestMessageId> for TestDispatch {
+	type Message = u64;
+
+	fn dispatch_weight(message: &Self::Message) -> Weight {
+		*message
+	}
+
+	fn dispatch<P: FnOnce(&AccountId, Weight) -> Result<(), ()>>(
+		_source_chain: ChainId,
+		_target_chain: ChainId,
+		_id: TestMessageId,
+		message: Result<Self::Message, Vec<u8>>,
+		_pay_dispatch_fee: P,
+	) -> MessageDispatchResult {
+		MessageDispatchResult {
+			dispatch_result: message.map(|message| message != 0).unwrap_or(false),
+			unspent_weight: 0,
+			dispatch_fee_paid_during_dispatch: false,
+		}
+	}
+}
+
+/// Run pallet test.
+pub fn run_test<T>(test: impl FnOnce() -> T) -> T {
+	let t = frame_system::GenesisConfig::default()
+		.build_storage::<TestRuntime>()
+		.unwrap();
+	let mut ext = sp_io::TestExternalities::new(t);
+	ext.execute_with(|| {
+		frame_system::Pallet::<TestRuntime>::set_block_number(1);
+		test()
+	})
+}
+
+/// Storage of the bridged chain.
+fn bridged_chain_storage() -> sp_state_machine::InMemoryBackend<BlakeTwo256> {
+	vec![(
+		None,
+		vec![
+			(b"key1".to_vec(), Some(b"value1".to_vec())),
+			(b"key2".to_vec(), Some(b"value2".to_vec())),
+			(b"key3".to_vec(), Some(b"value3".to_vec())),
+			(LARGE_VALUE_KEY.to_vec(), Some(vec![42; MAX_VALUE_SIZE as usize * 2])),
+		],
+	)]
+	.into()
+}
+
+/// Initialize GRANDPA pallet with the bridged chain header of given number. Returns header hash.
+pub fn initialize_grandpa(number: u64) -> H256 {
+	use sp_state_machine::backend::Backend;
+
+	let mut header: SubstrateHeader = bp_test_utils::test_header(number);
+	header.set_state_root(bridged_chain_storage().storage_root(std::iter::empty()).0);
+	let hash = header.hash();
+
+	pallet_bridge_grandpa::Pallet::<TestRuntime>::initialize(
+		Origin::root(),
+		bp_header_chain::InitializationData {
+			header,
+			authority_list: bp_test_utils::authority_list(),
+			set_id: 1,
+			is_halted: false,
+			genesis_hash: Default::default(),
+		},
+	)
+	.unwrap();
+
+	hash
+}
+
+/// Storage proof payload with given keys.
+pub fn storage_proof_payload_for(at: H256, keys: Vec<Vec<u8>>) -> crate::StorageProofPayload<H256> {
+	let proof = sp_state_machine::prove_read(bridged_chain_storage(), &keys)
+		.unwrap()
+		.iter_nodes()
+		.collect();
+
+	crate::StorageProofPayload { at, keys, proof }
+}
+
+/// Storage proof payload of `key1`, `key2` and missing `key22`.
+pub fn storage_proof_payload(at: H256) -> crate::StorageProofPayload<H256> {
+	storage_proof_payload_for(at, vec![b"key1".to_vec(), b"key2".to_vec(), b"key22".to_vec()])
+}
@@ -532,6 +532,28 @@ pub mod pallet {
 	}
 }
 
+impl<T: Config<I>, I: 'static> From<Error<T, I>> for bp_runtime::BridgeError {
+	fn from(error: Error<T, I>) -> Self {
+		match error {
+			Error::MismatchedSwapSourceOrigin => Self::NotAllowed,
+			Error::TooLowBalanceOnThisChain => Self::PaymentFailed,
+			Error::FailedToTransferToSwapAccount => Self::PaymentFailed,
+			Error::FailedToTransferFromSwapAccount => Self::PaymentFailed,
+			Error::FailedToSendTransferMessage => Self::MessageRejected,
+			Error::SwapAlreadyStarted => Self::InvalidState,
+			Error::SwapIsPending => Self::InvalidState,
+			Error::SwapIsFailed => Self::InvalidState,
+			Error::SwapIsTemporaryLocked => Self::InvalidState,
+			Error::SwapPeriodIsFinished => Self::InvalidState,
+			Error::SwapIsConfirmed => Self::InvalidState,
+			Error::SwapIsInactive => Self::InvalidState,
+			Error::InvalidClaimant => Self::NotAllowed,
+			// hidden variant that is never constructed
+			_ => Self::InvalidState,
+		}
+	}
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
@@ -107,7 +107,7 @@ impl pallet_balances::Config for TestRuntime {
 
 frame_support::parameter_types! {
 	pub const BridgeChainId: ChainId = *b"inst";
-	pub const OutboundMessageLaneId: LaneId = *b"lane";
+	pub const OutboundMessageLaneId: LaneId = LaneId::Legacy(*b"lane");
 	pub const MessageDeliveryAndDispatchFee: Balance = 1;
 }
 
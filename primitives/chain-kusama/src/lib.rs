@@ -39,6 +39,8 @@ pub fn derive_account_from_polkadot_id(id: bp_runtime::SourceAccount<AccountId>)
 pub const BEST_FINALIZED_KUSAMA_HEADER_METHOD: &str = "KusamaFinalityApi_best_finalized";
 /// Name of the `KusamaFinalityApi::is_known_header` runtime method.
 pub const IS_KNOWN_KUSAMA_HEADER_METHOD: &str = "KusamaFinalityApi_is_known_header";
+/// Name of the `KusamaFinalityApi::is_known_and_finalized` runtime method.
+pub const IS_KNOWN_AND_FINALIZED_KUSAMA_HEADER_METHOD: &str = "KusamaFinalityApi_is_known_and_finalized";
 
 /// Name of the `ToKusamaOutboundLaneApi::estimate_message_delivery_and_dispatch_fee` runtime method.
 pub const TO_KUSAMA_ESTIMATE_MESSAGE_FEE_METHOD: &str =
@@ -67,6 +69,10 @@ sp_api::decl_runtime_apis! {
 		fn best_finalized() -> (BlockNumber, Hash);
 		/// Returns true if the header is known to the runtime.
 		fn is_known_header(hash: Hash) -> bool;
+		/// Returns number of the header if it is known to the runtime and finalized.
+		///
+		/// Storage proofs, generated at this header, may be verified by the runtime.
+		fn is_known_and_finalized(hash: Hash) -> Option<BlockNumber>;
 	}
 
 	/// Outbound message lane API for messages that are sent to Kusama chain.
@@ -22,13 +22,16 @@
 
 mod millau_hash;
 
-use bp_messages::{LaneId, MessageDetails, MessageNonce, UnrewardedRelayersState};
-use bp_runtime::Chain;
+use bp_header_chain::{AuthoritySetChange, FinalityProofError};
+use bp_message_dispatch::DerivedAccountOrigin;
+use bp_messages::{
+	LaneId, LaneStatistics, MessageDetails, MessageNonce, RelayerRewardsEstimation, UnrewardedRelayersState,
+};
+use bp_runtime::messages::MessageDispatchResult;
 use frame_support::{
-	weights::{constants::WEIGHT_PER_SECOND, DispatchClass, Weight},
+	weights::{constants::WEIGHT_PER_SECOND, Weight},
 	Parameter, RuntimeDebug,
 };
-use frame_system::limits;
 use sp_core::Hasher as HasherT;
 use sp_runtime::traits::Convert;
 use sp_runtime::{
@@ -149,15 +152,28 @@ pub type AccountSigner = MultiSigner;
 /// Balance of an account.
 pub type Balance = u64;
 
-/// Millau chain.
-#[derive(RuntimeDebug)]
-pub struct Millau;
+bp_runtime::decl_bridge_chain! {
+	/// Millau chain.
+	pub struct Millau {
+		BlockNumber = BlockNumber,
+		Hash = Hash,
+		Hasher = Hasher,
+		Header = Header,
+		AccountId = AccountId,
+		MaxBlockSize = 2 * 1024 * 1024,
+		MaxBlockWeight = MAXIMUM_BLOCK_WEIGHT,
+		NormalDispatchRatio = NORMAL_DISPATCH_RATIO,
+		AverageOnInitializeRatio = AVERAGE_ON_INITIALIZE_RATIO,
+	}
+}
 
-impl Chain for Millau {
-	type BlockNumber = BlockNumber;
-	type Hash = Hash;
-	type Hasher = Hasher;
-	type Header = Header;
+impl bp_header_chain::ChainWithGrandpa for Millau {
+	// there are at most 5 validators (Alice, Bob, Charlie, Dave and Eve) at Millau chain
+	const MAX_AUTHORITIES_COUNT: u32 = 5;
+	const REASONABLE_HEADERS_IN_JUSTIFICATON_ANCESTRY: u32 = 8;
+	// authority set change digest item takes `MAX_AUTHORITIES_COUNT * 40` bytes and other
+	// digest items and header fields fit into 1KB
+	const MAX_HEADER_SIZE: u32 = 1024 + Self::MAX_AUTHORITIES_COUNT * 40;
 }
 
 /// Millau Hasher (Blake2-256 ++ Keccak-256) implementation.
@@ -190,15 +206,6 @@ impl sp_runtime::traits::Hash for BlakeTwoAndKeccak256 {
 	}
 }
 
-/// Convert a 256-bit hash into an AccountId.
-pub struct AccountIdConverter;
-
-impl sp_runtime::traits::Convert<sp_core::H256, AccountId> for AccountIdConverter {
-	fn convert(hash: sp_core::H256) -> AccountId {
-		hash.to_fixed_bytes().into()
-	}
-}
-
 /// We use this to get the account on Millau (target) which is derived from Rialto's (source)
 /// account. We do this so we can fund the derived account on Millau at Genesis to it can pay
 /// transaction fees.
@@ -212,41 +219,37 @@ pub fn derive_account_from_rialto_id(id: bp_runtime::SourceAccount<AccountId>) -
 	AccountIdConverter::convert(encoded_id)
 }
 
-frame_support::parameter_types! {
-	pub BlockLength: limits::BlockLength =
-		limits::BlockLength::max_with_normal_ratio(2 * 1024 * 1024, NORMAL_DISPATCH_RATIO);
-	pub BlockWeights: limits::BlockWeights = limits::BlockWeights::builder()
-		// Allowance for Normal class
-		.for_class(DispatchClass::Normal, |weights| {
-			weights.max_total = Some(NORMAL_DISPATCH_RATIO * MAXIMUM_BLOCK_WEIGHT);
-		})
-		// Allowance for Operational class
-		.for_class(DispatchClass::Operational, |weights| {
-			weights.max_total = Some(MAXIMUM_BLOCK_WEIGHT);
-			// Extra reserved space for Operational class
-			weights.reserved = Some(MAXIMUM_BLOCK_WEIGHT - NORMAL_DISPATCH_RATIO * MAXIMUM_BLOCK_WEIGHT);
-		})
-		// By default Mandatory class is not limited at all.
-		// This parameter is used to derive maximal size of a single extrinsic.
-		.avg_block_initialization(AVERAGE_ON_INITIALIZE_RATIO)
-		.build_or_panic();
-}
-
-/// Get the maximum weight (compute time) that a Normal extrinsic on the Millau chain can use.
-pub fn max_extrinsic_weight() -> Weight {
-	BlockWeights::get()
-		.get(DispatchClass::Normal)
-		.max_extrinsic
-		.unwrap_or(Weight::MAX)
-}
-
-/// Get the maximum length in bytes that a Normal extrinsic on the Millau chain requires.
-pub fn max_extrinsic_size() -> u32 {
-	*BlockLength::get().max.get(DispatchClass::Normal)
-}
-
 /// Name of the `MillauFinalityApi::best_finalized` runtime method.
 pub const BEST_FINALIZED_MILLAU_HEADER_METHOD: &str = "MillauFinalityApi_best_finalized";
+/// Name of the `MillauFinalityApi::is_known_and_finalized` runtime method.
+pub const IS_KNOWN_AND_FINALIZED_MILLAU_HEADER_METHOD: &str = "MillauFinalityApi_is_known_and_finalized";
+/// Name of the `MillauFinalityApi::authority_set_changes` runtime method.
+pub const MILLAU_AUTHORITY_SET_CHANGES_METHOD: &str = "MillauFinalityApi_authority_set_changes";
+/// Name of the `MillauFinalityApi::verify_finality_proof` runtime method.
+pub const VERIFY_MILLAU_FINALITY_PROOF_METHOD: &str = "MillauFinalityApi_verify_finality_proof";
+
+/// Name of the GRANDPA pallet that is tracking Rialto headers at Millau runtime.
+pub const WITH_RIALTO_GRANDPA_PALLET_NAME: &str = "BridgeRialtoGrandpa";
+/// Name of the GRANDPA pallet that is tracking Westend headers at Millau runtime.
+pub const WITH_WESTEND_GRANDPA_PALLET_NAME: &str = "BridgeWestendGrandpa";
+/// Name of the messages pallet that is bridging with Rialto at Millau runtime.
+pub const WITH_RIALTO_MESSAGES_PALLET_NAME: &str = "BridgeRialtoMessages";
+/// Name of the lane registry pallet at Millau runtime.
+pub const LANE_REGISTRY_PALLET_NAME: &str = "BridgeLaneRegistry";
+
+/// Index of the fee settlement pallet that is settling fees with Rialto at Millau runtime.
+pub const WITH_RIALTO_FEE_SETTLEMENT_PALLET_INDEX: u8 = 16;
+/// Weight of the `settle` call of the fee settlement pallet at Millau runtime.
+///
+/// The call performs 3 reads and 3 writes, which cost `780_000_000` with the runtime `DbWeight`.
+pub const FEE_SETTLEMENT_SETTLE_CALL_WEIGHT: Weight = 1_000_000_000;
+
+/// Index of the dispatch pallet that is dispatching messages from Rialto at Millau runtime.
+pub const WITH_RIALTO_DISPATCH_PALLET_INDEX: u8 = 1;
+/// Weight of the `receive_dispatch_acknowledgement` call of the dispatch pallet at Millau runtime.
+///
+/// The call performs 1 write, which costs `200_000_000` with the runtime `DbWeight`.
+pub const DISPATCH_ACKNOWLEDGEMENT_CALL_WEIGHT: Weight = 500_000_000;
 
 /// Name of the `ToMillauOutboundLaneApi::estimate_message_delivery_and_dispatch_fee` runtime method.
 pub const TO_MILLAU_ESTIMATE_MESSAGE_FEE_METHOD: &str =
@@ -255,8 +258,15 @@ pub const TO_MILLAU_ESTIMATE_MESSAGE_FEE_METHOD: &str =
 pub const TO_MILLAU_MESSAGE_DETAILS_METHOD: &str = "ToMillauOutboundLaneApi_message_details";
 /// Name of the `ToMillauOutboundLaneApi::latest_received_nonce` runtime method.
 pub const TO_MILLAU_LATEST_RECEIVED_NONCE_METHOD: &str = "ToMillauOutboundLaneApi_latest_received_nonce";
+/// Name of the `ToMillauOutboundLaneApi::estimate_relayer_rewards` runtime method.
+pub const TO_MILLAU_ESTIMATE_RELAYER_REWARDS_METHOD: &str = "ToMillauOutboundLaneApi_estimate_relayer_rewards";
 /// Name of the `ToMillauOutboundLaneApi::latest_generated_nonce` runtime method.
 pub const TO_MILLAU_LATEST_GENERATED_NONCE_METHOD: &str = "ToMillauOutboundLaneApi_latest_generated_nonce";
+/// Name of the `ToMillauOutboundLaneApi::lane_statistics` runtime method.
+pub const TO_MILLAU_LANE_STATISTICS_METHOD: &str = "ToMillauOutboundLaneApi_lane_statistics";
+/// Name of the `ToMillauOutboundLaneApi::maximal_message_arguments_size` runtime method.
+pub const TO_MILLAU_MAXIMAL_MESSAGE_ARGUMENTS_SIZE_METHOD: &str =
+	"ToMillauOutboundLaneApi_maximal_message_arguments_size";
 
 /// Name of the `FromMillauInboundLaneApi::latest_received_nonce` runtime method.
 pub const FROM_MILLAU_LATEST_RECEIVED_NONCE_METHOD: &str = "FromMillauInboundLaneApi_latest_received_nonce";
@@ -264,6 +274,11 @@ pub const FROM_MILLAU_LATEST_RECEIVED_NONCE_METHOD: &str = "FromMillauInboundLan
 pub const FROM_MILLAU_LATEST_CONFIRMED_NONCE_METHOD: &str = "FromMillauInboundLaneApi_latest_confirmed_nonce";
 /// Name of the `FromMillauInboundLaneApi::unrewarded_relayers_state` runtime method.
 pub const FROM_MILLAU_UNREWARDED_RELAYERS_STATE: &str = "FromMillauInboundLaneApi_unrewarded_relayers_state";
+/// Name of the `FromMillauInboundLaneApi::dry_run_message_dispatch` runtime method.
+pub const FROM_MILLAU_DRY_RUN_MESSAGE_DISPATCH_METHOD: &str = "FromMillauInboundLaneApi_dry_run_message_dispatch";
+
+/// Name of the `FromMillauDerivedAccountsApi::derived_account_origin` runtime method.
+pub const FROM_MILLAU_DERIVED_ACCOUNT_ORIGIN_METHOD: &str = "FromMillauDerivedAccountsApi_derived_account_origin";
 
 sp_api::decl_runtime_apis! {
 	/// API for querying information about the finalized Millau headers.
@@ -275,6 +290,17 @@ sp_api::decl_runtime_apis! {
 		fn best_finalized() -> (BlockNumber, Hash);
 		/// Returns true if the header is known to the runtime.
 		fn is_known_header(hash: Hash) -> bool;
+		/// Returns number of the header if it is known to the runtime and finalized.
+		///
+		/// Storage proofs, generated at this header, may be verified by the runtime.
+		fn is_known_and_finalized(hash: Hash) -> Option<BlockNumber>;
+		/// Returns last authority set changes, known to the bridge module, ordered from the oldest
+		/// to the newest.
+		fn authority_set_changes() -> Vec<AuthoritySetChange<Hash, BlockNumber>>;
+		/// Verify finality proof (encoded header and encoded justification) without importing the header.
+		///
+		/// Returns error if the finality proof would be rejected by the bridge module.
+		fn verify_finality_proof(header: Vec<u8>, justification: Vec<u8>) -> Result<(), FinalityProofError>;
 	}
 
 	/// Outbound message lane API for messages that are sent to Millau chain.
@@ -309,6 +335,19 @@ sp_api::decl_runtime_apis! {
 		fn latest_received_nonce(lane: LaneId) -> MessageNonce;
 		/// Returns nonce of the latest message, generated by given lane.
 		fn latest_generated_nonce(lane: LaneId) -> MessageNonce;
+		/// Estimate rewards for delivering at most `max_messages` next undelivered messages of
+		/// given lane.
+		fn estimate_relayer_rewards(
+			lane: LaneId,
+			max_messages: MessageNonce,
+		) -> RelayerRewardsEstimation<OutboundMessageFee>;
+		/// Returns cumulative statistics of given lane.
+		fn lane_statistics(lane: LaneId) -> LaneStatistics<OutboundMessageFee>;
+		/// Returns maximal size of arguments of the Millau call that may be sent in a message.
+		///
+		/// The call must fit into both `send_message` transaction at this chain and message
+		/// delivery transaction at Millau.
+		fn maximal_message_arguments_size() -> u32;
 	}
 
 	/// Inbound message lane API for messages sent by Millau chain.
@@ -322,6 +361,23 @@ sp_api::decl_runtime_apis! {
 		fn latest_confirmed_nonce(lane: LaneId) -> MessageNonce;
 		/// State of the unrewarded relayers set at given lane.
 		fn unrewarded_relayers_state(lane: LaneId) -> UnrewardedRelayersState;
+		/// Dispatch message with given encoded payload, as if it has been delivered over given lane.
+		///
+		/// Changes made by the dispatch are never committed, so this method may be used to check
+		/// whether the message would be dispatched successfully and to get its actual dispatch weight.
+		fn dry_run_message_dispatch(lane: LaneId, payload: Vec<u8>) -> MessageDispatchResult;
+	}
+
+	/// API for resolving accounts that have been derived from Millau accounts.
+	///
+	/// This API is implemented by runtimes that are dispatching messages sent by Millau chain, not the
+	/// Millau runtime itself.
+	pub trait FromMillauDerivedAccountsApi<ThisChainAccountId: Parameter> {
+		/// Returns the Millau account (or Millau Root) that controls given derived account.
+		///
+		/// Returns `None` if the account hasn't been derived from Millau account, or if it hasn't
+		/// been used to dispatch any messages yet.
+		fn derived_account_origin(account_id: ThisChainAccountId) -> Option<DerivedAccountOrigin<AccountId>>;
 	}
 }
 
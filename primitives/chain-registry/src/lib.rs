@@ -0,0 +1,211 @@
+// Copyright 2019-2021 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Registry of chains, known to the bridges code.
+//!
+//! Chain ids are used to derive accounts and to identify bridge instances, so two chains sharing
+//! the same id would silently share these accounts. All known ids are declared here and their
+//! uniqueness is checked at compile time.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+/// Unique identifier of the chain.
+///
+/// In addition to its main function (identifying the chain), this type may also be used to
+/// identify module instance. We have a bunch of pallets that may be used in different bridges. E.g.
+/// messages pallet may be deployed twice in the same runtime to bridge ThisChain with Chain1 and Chain2.
+/// Sometimes we need to be able to identify deployed instance dynamically. This type may be used for that.
+pub type ChainId = [u8; 4];
+
+/// Use this when something must be shared among all instances.
+pub const NO_INSTANCE_ID: ChainId = [0, 0, 0, 0];
+
+/// Bridge-with-Rialto instance id.
+pub const RIALTO_CHAIN_ID: ChainId = *b"rlto";
+
+/// Bridge-with-Millau instance id.
+pub const MILLAU_CHAIN_ID: ChainId = *b"mlau";
+
+/// Bridge-with-Polkadot instance id.
+pub const POLKADOT_CHAIN_ID: ChainId = *b"pdot";
+
+/// Bridge-with-Kusama instance id.
+pub const KUSAMA_CHAIN_ID: ChainId = *b"ksma";
+
+/// Bridge-with-Rococo instance id.
+pub const ROCOCO_CHAIN_ID: ChainId = *b"roco";
+
+/// Bridge-with-Wococo instance id.
+pub const WOCOCO_CHAIN_ID: ChainId = *b"woco";
+
+/// Bridge-with-Westend instance id.
+pub const WESTEND_CHAIN_ID: ChainId = *b"wend";
+
+/// Information about the known chain.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChainInfo {
+	/// Chain id.
+	pub id: ChainId,
+	/// Chain name.
+	pub name: &'static str,
+	/// SS58 prefix of chain accounts.
+	pub ss58_prefix: u16,
+	/// Symbol of the chain native token.
+	pub token_symbol: &'static str,
+	/// Number of decimals of the chain native token.
+	pub token_decimals: u8,
+}
+
+/// Rialto chain information.
+pub const RIALTO: ChainInfo = ChainInfo {
+	id: RIALTO_CHAIN_ID,
+	name: "Rialto",
+	ss58_prefix: 48,
+	token_symbol: "RLT",
+	token_decimals: 9,
+};
+
+/// Millau chain information.
+pub const MILLAU: ChainInfo = ChainInfo {
+	id: MILLAU_CHAIN_ID,
+	name: "Millau",
+	ss58_prefix: 60,
+	token_symbol: "MLAU",
+	token_decimals: 9,
+};
+
+/// Polkadot chain information.
+pub const POLKADOT: ChainInfo = ChainInfo {
+	id: POLKADOT_CHAIN_ID,
+	name: "Polkadot",
+	ss58_prefix: 0,
+	token_symbol: "DOT",
+	token_decimals: 10,
+};
+
+/// Kusama chain information.
+pub const KUSAMA: ChainInfo = ChainInfo {
+	id: KUSAMA_CHAIN_ID,
+	name: "Kusama",
+	ss58_prefix: 2,
+	token_symbol: "KSM",
+	token_decimals: 12,
+};
+
+/// Rococo chain information.
+pub const ROCOCO: ChainInfo = ChainInfo {
+	id: ROCOCO_CHAIN_ID,
+	name: "Rococo",
+	ss58_prefix: 42,
+	token_symbol: "ROC",
+	token_decimals: 12,
+};
+
+/// Wococo chain information.
+pub const WOCOCO: ChainInfo = ChainInfo {
+	id: WOCOCO_CHAIN_ID,
+	name: "Wococo",
+	ss58_prefix: 42,
+	token_symbol: "WOOK",
+	token_decimals: 12,
+};
+
+/// Westend chain information.
+pub const WESTEND: ChainInfo = ChainInfo {
+	id: WESTEND_CHAIN_ID,
+	name: "Westend",
+	ss58_prefix: 42,
+	token_symbol: "WND",
+	token_decimals: 12,
+};
+
+/// All chains, known to the bridges code.
+pub const KNOWN_CHAINS: &[ChainInfo] = &[RIALTO, MILLAU, POLKADOT, KUSAMA, ROCOCO, WOCOCO, WESTEND];
+
+// Fails to compile if ids of known chains collide with each other or with the `NO_INSTANCE_ID`.
+const _: [(); 1] = [(); are_chain_ids_unique(KNOWN_CHAINS) as usize];
+
+/// Returns information about the known chain with given id.
+pub fn chain_info(id: ChainId) -> Option<&'static ChainInfo> {
+	KNOWN_CHAINS.iter().find(|chain| chain.id == id)
+}
+
+/// Returns SS58 prefix of the known chain with given id.
+pub fn ss58_prefix(id: ChainId) -> Option<u16> {
+	chain_info(id).map(|chain| chain.ss58_prefix)
+}
+
+/// Returns native token symbol of the known chain with given id.
+pub fn token_symbol(id: ChainId) -> Option<&'static str> {
+	chain_info(id).map(|chain| chain.token_symbol)
+}
+
+/// Returns number of decimals of native token of the known chain with given id.
+pub fn token_decimals(id: ChainId) -> Option<u8> {
+	chain_info(id).map(|chain| chain.token_decimals)
+}
+
+/// Returns true if all chains have different ids and no chain is using the `NO_INSTANCE_ID`.
+const fn are_chain_ids_unique(chains: &[ChainInfo]) -> bool {
+	let mut i = 0;
+	while i < chains.len() {
+		if is_same_chain_id(&chains[i].id, &NO_INSTANCE_ID) {
+			return false;
+		}
+
+		let mut j = i + 1;
+		while j < chains.len() {
+			if is_same_chain_id(&chains[i].id, &chains[j].id) {
+				return false;
+			}
+			j += 1;
+		}
+		i += 1;
+	}
+
+	true
+}
+
+/// Const version of `ChainId` comparison.
+const fn is_same_chain_id(a: &ChainId, b: &ChainId) -> bool {
+	a[0] == b[0] && a[1] == b[1] && a[2] == b[2] && a[3] == b[3]
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn chain_ids_collisions_are_detected() {
+		assert!(are_chain_ids_unique(KNOWN_CHAINS));
+		assert!(are_chain_ids_unique(&[]));
+		assert!(!are_chain_ids_unique(&[RIALTO, MILLAU, RIALTO]));
+		assert!(!are_chain_ids_unique(&[ChainInfo {
+			id: NO_INSTANCE_ID,
+			..RIALTO
+		}]));
+	}
+
+	#[test]
+	fn known_chains_are_found_by_id() {
+		assert_eq!(chain_info(WOCOCO_CHAIN_ID), Some(&WOCOCO));
+		assert_eq!(ss58_prefix(MILLAU_CHAIN_ID), Some(60));
+		assert_eq!(token_symbol(KUSAMA_CHAIN_ID), Some("KSM"));
+		assert_eq!(token_decimals(POLKADOT_CHAIN_ID), Some(10));
+		assert_eq!(chain_info(*b"unkn"), None);
+		assert_eq!(chain_info(NO_INSTANCE_ID), None);
+	}
+}
@@ -76,6 +76,13 @@ pub fn derive_account_from_wococo_id(id: bp_runtime::SourceAccount<AccountId>) -
 pub const BEST_FINALIZED_ROCOCO_HEADER_METHOD: &str = "RococoFinalityApi_best_finalized";
 /// Name of the `RococoFinalityApi::is_known_header` runtime method.
 pub const IS_KNOWN_ROCOCO_HEADER_METHOD: &str = "RococoFinalityApi_is_known_header";
+/// Name of the `RococoFinalityApi::is_known_and_finalized` runtime method.
+pub const IS_KNOWN_AND_FINALIZED_ROCOCO_HEADER_METHOD: &str = "RococoFinalityApi_is_known_and_finalized";
+
+/// Name of the GRANDPA pallet that is tracking Wococo headers at Rococo runtime.
+pub const WITH_WOCOCO_GRANDPA_PALLET_NAME: &str = "BridgeGrandpaWococo";
+/// Name of the messages pallet that is bridging with Wococo at Rococo runtime.
+pub const WITH_WOCOCO_MESSAGES_PALLET_NAME: &str = "BridgeWococoMessages";
 
 /// Name of the `ToRococoOutboundLaneApi::estimate_message_delivery_and_dispatch_fee` runtime method.
 pub const TO_ROCOCO_ESTIMATE_MESSAGE_FEE_METHOD: &str =
@@ -104,6 +111,10 @@ sp_api::decl_runtime_apis! {
 		fn best_finalized() -> (BlockNumber, Hash);
 		/// Returns true if the header is known to the runtime.
 		fn is_known_header(hash: Hash) -> bool;
+		/// Returns number of the header if it is known to the runtime and finalized.
+		///
+		/// Storage proofs, generated at this header, may be verified by the runtime.
+		fn is_known_and_finalized(hash: Hash) -> Option<BlockNumber>;
 	}
 
 	/// Outbound message lane API for messages that are sent to Rococo chain.
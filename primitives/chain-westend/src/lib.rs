@@ -20,6 +20,7 @@
 // Runtime-generated DecodeLimit::decode_all_with_depth_limit
 #![allow(clippy::unnecessary_mut_passed)]
 
+use bp_header_chain::{AuthoritySetChange, FinalityProofError};
 use bp_messages::{LaneId, MessageDetails, MessageNonce, UnrewardedRelayersState};
 use bp_runtime::Chain;
 use sp_std::prelude::*;
@@ -94,6 +95,12 @@ pub fn derive_account_from_rococo_id(id: bp_runtime::SourceAccount<AccountId>) -
 pub const BEST_FINALIZED_WESTEND_HEADER_METHOD: &str = "WestendFinalityApi_best_finalized";
 /// Name of the `WestendFinalityApi::is_known_header` runtime method.
 pub const IS_KNOWN_WESTEND_HEADER_METHOD: &str = "WestendFinalityApi_is_known_header";
+/// Name of the `WestendFinalityApi::is_known_and_finalized` runtime method.
+pub const IS_KNOWN_AND_FINALIZED_WESTEND_HEADER_METHOD: &str = "WestendFinalityApi_is_known_and_finalized";
+/// Name of the `WestendFinalityApi::authority_set_changes` runtime method.
+pub const WESTEND_AUTHORITY_SET_CHANGES_METHOD: &str = "WestendFinalityApi_authority_set_changes";
+/// Name of the `WestendFinalityApi::verify_finality_proof` runtime method.
+pub const VERIFY_WESTEND_FINALITY_PROOF_METHOD: &str = "WestendFinalityApi_verify_finality_proof";
 
 /// Name of the `ToWestendOutboundLaneApi::estimate_message_delivery_and_dispatch_fee` runtime method.
 pub const TO_WESTEND_ESTIMATE_MESSAGE_FEE_METHOD: &str =
@@ -129,6 +136,17 @@ sp_api::decl_runtime_apis! {
 		fn best_finalized() -> (BlockNumber, Hash);
 		/// Returns true if the header is known to the runtime.
 		fn is_known_header(hash: Hash) -> bool;
+		/// Returns number of the header if it is known to the runtime and finalized.
+		///
+		/// Storage proofs, generated at this header, may be verified by the runtime.
+		fn is_known_and_finalized(hash: Hash) -> Option<BlockNumber>;
+		/// Returns last authority set changes, known to the bridge module, ordered from the oldest
+		/// to the newest.
+		fn authority_set_changes() -> Vec<AuthoritySetChange<Hash, BlockNumber>>;
+		/// Verify finality proof (encoded header and encoded justification) without importing the header.
+		///
+		/// Returns error if the finality proof would be rejected by the bridge module.
+		fn verify_finality_proof(header: Vec<u8>, justification: Vec<u8>) -> Result<(), FinalityProofError>;
 	}
 
 	/// Outbound message lane API for messages that are sent to Westend chain.
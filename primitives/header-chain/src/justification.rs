@@ -163,6 +163,72 @@ where
 	}
 }
 
+/// Optimize justification, so that it only contains votes and ancestry headers that are required
+/// to prove finality of the given header.
+///
+/// Precommits of unknown authorities, duplicate precommits, precommits for blocks that are not
+/// descendants of the justification target and precommits that are not required to reach the
+/// threshold are removed. Then all ancestry headers that are not used by remaining precommits are
+/// removed too.
+///
+/// Precommit signatures are not verified here, because the caller may not know the authorities
+/// set id. That's fine, because `verify_justification` rejects justifications with any invalid
+/// signature, so valid justification stays valid after optimization.
+pub fn optimize<Header: HeaderT>(
+	finalized_target: (Header::Hash, Header::Number),
+	authorities_set: &VoterSet<AuthorityId>,
+	mut justification: GrandpaJustification<Header>,
+) -> Result<GrandpaJustification<Header>, Error> {
+	// ensure that it is justification for the expected header
+	if (justification.commit.target_hash, justification.commit.target_number) != finalized_target {
+		return Err(Error::InvalidJustificationTarget);
+	}
+
+	let chain = AncestryChain::new(&justification.votes_ancestries);
+	let (target_hash, target_number) = finalized_target;
+	let threshold = authorities_set.threshold().0.into();
+	let mut votes = BTreeSet::new();
+	let mut used_ancestry = BTreeSet::new();
+	let mut cumulative_weight = 0u64;
+	justification.commit.precommits.retain(|signed| {
+		// all remaining precommits are redundant
+		if cumulative_weight >= threshold {
+			return false;
+		}
+
+		let authority_info = match authorities_set.get(&signed.id) {
+			Some(authority_info) => authority_info,
+			None => return false,
+		};
+		if votes.contains(&signed.id) {
+			return false;
+		}
+		if signed.precommit.target_number < target_number {
+			return false;
+		}
+		let route = match chain.route(&target_hash, &signed.precommit.target_hash) {
+			Some(route) => route,
+			None => return false,
+		};
+
+		votes.insert(signed.id.clone());
+		used_ancestry.extend(route);
+		cumulative_weight = cumulative_weight.saturating_add(authority_info.weight().0.into());
+		true
+	});
+
+	if cumulative_weight < threshold {
+		return Err(Error::TooLowCumulativeWeight);
+	}
+
+	// removing hash from the set also removes duplicate headers
+	justification
+		.votes_ancestries
+		.retain(|header| used_ancestry.remove(&header.hash()));
+
+	Ok(justification)
+}
+
 /// Votes ancestries with useful methods.
 #[derive(RuntimeDebug)]
 pub struct AncestryChain<Header: HeaderT> {
@@ -186,6 +252,21 @@ impl<Header: HeaderT> AncestryChain<Header> {
 		AncestryChain { parents, unvisited }
 	}
 
+	/// Returns hashes of all headers between `precommit_target` (inclusive) and `commit_target`
+	/// (exclusive), or `None` if `precommit_target` is not a descendant of the `commit_target` block.
+	///
+	/// Unlike `ensure_descendant`, this method doesn't mark headers as visited.
+	pub fn route(&self, commit_target: &Header::Hash, precommit_target: &Header::Hash) -> Option<Vec<Header::Hash>> {
+		let mut route = Vec::new();
+		let mut current_hash = *precommit_target;
+		while current_hash != *commit_target {
+			let parent_hash = *self.parents.get(&current_hash)?;
+			route.push(current_hash);
+			current_hash = parent_hash;
+		}
+		Some(route)
+	}
+
 	/// Returns `Err(_)` if `precommit_target` is a descendant of the `commit_target` block and `Ok(_)` otherwise.
 	pub fn ensure_descendant(
 		mut self,
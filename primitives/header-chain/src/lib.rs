@@ -19,6 +19,7 @@
 
 #![cfg_attr(not(feature = "std"), no_std)]
 
+use bp_runtime::{BridgeError, Chain};
 use codec::{Codec, Decode, Encode, EncodeLike};
 use core::clone::Clone;
 use core::cmp::Eq;
@@ -29,6 +30,7 @@ use serde::{Deserialize, Serialize};
 use sp_finality_grandpa::{AuthorityList, ConsensusLog, SetId, GRANDPA_ENGINE_ID};
 use sp_runtime::RuntimeDebug;
 use sp_runtime::{generic::OpaqueDigestItemId, traits::Header as HeaderT};
+use sp_std::vec::Vec;
 
 pub mod justification;
 
@@ -55,6 +57,40 @@ impl AuthoritySet {
 	}
 }
 
+/// Record of the GRANDPA authority set change, enacted by the bridged chain header.
+#[derive(Encode, Decode, RuntimeDebug, PartialEq, Eq, Clone)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct AuthoritySetChange<Hash, Number> {
+	/// Identifier of the authority set that has been enacted.
+	pub set_id: SetId,
+	/// Hash of the header that has enacted the set.
+	pub block_hash: Hash,
+	/// Number of the header that has enacted the set.
+	pub block_number: Number,
+}
+
+/// GRANDPA authority set, stored in the authority sets history.
+#[derive(Encode, Decode, RuntimeDebug, PartialEq, Eq, Clone)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct HistoricalAuthoritySet<Number> {
+	/// List of authorities of the set.
+	pub authorities: AuthorityList,
+	/// Number of the header that has enacted the set.
+	pub enacted_at: Number,
+	/// Number of the header that has enacted the next set. It is `None` for the current set.
+	pub superseded_at: Option<Number>,
+}
+
+/// Reason why the finality proof is rejected by the pre-validation.
+#[derive(Encode, Decode, RuntimeDebug, PartialEq, Eq, Clone)]
+pub enum FinalityProofError {
+	/// Failed to decode the header or the justification.
+	InvalidEncoding,
+	/// The finality proof would be rejected by the bridge pallet. The payload is the structured
+	/// error code and the name of the pallet error.
+	Rejected(BridgeError, Vec<u8>),
+}
+
 /// Data required for initializing the bridge pallet.
 ///
 /// The bridge needs to know where to start its sync from, and this provides that initial context.
@@ -69,6 +105,11 @@ pub struct InitializationData<H: HeaderT> {
 	pub set_id: SetId,
 	/// Should the pallet block transaction immediately after initialization.
 	pub is_halted: bool,
+	/// Hash of the bridged chain genesis block.
+	///
+	/// It is stored by the pallet, so that relayers may check that they're connected to the
+	/// right chain before submitting any transactions.
+	pub genesis_hash: H::Hash,
 }
 
 /// base trait for verifying transaction inclusion proofs.
@@ -116,6 +157,25 @@ pub trait FinalityProof<Number>: Clone + Send + Sync + Debug {
 	fn target_header_number(&self) -> Number;
 }
 
+/// Chain that uses GRANDPA finality.
+///
+/// Limits, declared here, are used to compute weights of GRANDPA bridge pallet calls and by
+/// relays, submitting finality proofs of this chain.
+pub trait ChainWithGrandpa: Chain {
+	/// Maximal number of GRANDPA authorities at the chain.
+	///
+	/// Every GRANDPA justification has at most this number of precommits.
+	const MAX_AUTHORITIES_COUNT: u32;
+	/// Reasonable number of headers in the `votes_ancestries` of GRANDPA justification.
+	///
+	/// The number of ancestries is not limited by the GRANDPA protocol. In practice it is
+	/// limited by the session length of the chain. Justifications with more ancestries are
+	/// still accepted, but they're more expensive to verify.
+	const REASONABLE_HEADERS_IN_JUSTIFICATON_ANCESTRY: u32;
+	/// Maximal size (in bytes) of the encoded chain header.
+	const MAX_HEADER_SIZE: u32;
+}
+
 /// Find header digest that schedules next GRANDPA authorities set.
 pub fn find_grandpa_authorities_scheduled_change<H: HeaderT>(
 	header: &H,
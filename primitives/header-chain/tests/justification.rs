@@ -16,7 +16,7 @@
 
 //! Tests for Grandpa Justification code.
 
-use bp_header_chain::justification::{verify_justification, Error};
+use bp_header_chain::justification::{optimize, verify_justification, Error};
 use bp_test_utils::*;
 
 type TestHeader = sp_runtime::testing::Header;
@@ -189,3 +189,101 @@ fn justification_is_invalid_if_we_dont_meet_threshold() {
 		Err(Error::TooLowCumulativeWeight),
 	);
 }
+
+#[test]
+fn optimizer_removes_redundant_precommits_and_ancestry() {
+	use finality_grandpa::voter_set::VoterSet;
+	use sp_finality_grandpa::AuthorityId;
+
+	let authorities = vec![(ALICE, 1), (BOB, 1), (CHARLIE, 1), (DAVE, 1), (EVE, 1)];
+	let params = JustificationGeneratorParams {
+		header: test_header(1),
+		round: TEST_GRANDPA_ROUND,
+		set_id: TEST_GRANDPA_SET_ID,
+		authorities: authorities.clone(),
+		ancestors: 10,
+		forks: 5,
+	};
+
+	let voter_set = VoterSet::new(
+		authorities
+			.iter()
+			.map(|(id, w)| (AuthorityId::from(*id), *w))
+			.collect::<Vec<(AuthorityId, _)>>(),
+	)
+	.unwrap();
+	let justification = optimize::<TestHeader>(
+		header_id::<TestHeader>(1),
+		&voter_set,
+		make_justification_for_header::<TestHeader>(params),
+	)
+	.unwrap();
+
+	// 4 of 5 votes are enough to reach the threshold and every fork has 2 headers
+	assert_eq!(justification.commit.precommits.len(), 4);
+	assert_eq!(justification.votes_ancestries.len(), 8);
+	assert_eq!(
+		verify_justification::<TestHeader>(
+			header_id::<TestHeader>(1),
+			TEST_GRANDPA_SET_ID,
+			&voter_set,
+			&justification,
+		),
+		Ok(()),
+	);
+}
+
+#[test]
+fn optimizer_removes_unknown_and_duplicate_precommits() {
+	let mut justification = make_default_justification::<TestHeader>(&test_header(1));
+	let mut unknown_precommit = justification.commit.precommits[0].clone();
+	unknown_precommit.id = EVE.into();
+	let duplicate_precommit = justification.commit.precommits[1].clone();
+	justification.commit.precommits.insert(0, unknown_precommit);
+	justification.commit.precommits.insert(2, duplicate_precommit);
+
+	let optimized_justification =
+		optimize::<TestHeader>(header_id::<TestHeader>(1), &voter_set(), justification).unwrap();
+
+	assert_eq!(
+		optimized_justification,
+		make_default_justification::<TestHeader>(&test_header(1)),
+	);
+}
+
+#[test]
+fn optimizer_removes_extra_ancestry_headers() {
+	let mut justification = make_default_justification::<TestHeader>(&test_header(1));
+	justification.votes_ancestries.push(test_header(10));
+	justification
+		.votes_ancestries
+		.push(justification.votes_ancestries[0].clone());
+
+	let optimized_justification =
+		optimize::<TestHeader>(header_id::<TestHeader>(1), &voter_set(), justification).unwrap();
+
+	assert_eq!(
+		verify_justification::<TestHeader>(
+			header_id::<TestHeader>(1),
+			TEST_GRANDPA_SET_ID,
+			&voter_set(),
+			&optimized_justification,
+		),
+		Ok(()),
+	);
+	assert_eq!(
+		optimized_justification,
+		make_default_justification::<TestHeader>(&test_header(1)),
+	);
+}
+
+#[test]
+fn optimizer_rejects_justification_if_we_dont_meet_threshold() {
+	let mut justification = make_default_justification::<TestHeader>(&test_header(1));
+	justification.commit.precommits.pop();
+
+	assert_eq!(
+		optimize::<TestHeader>(header_id::<TestHeader>(1), &voter_set(), justification),
+		Err(Error::TooLowCumulativeWeight),
+	);
+}
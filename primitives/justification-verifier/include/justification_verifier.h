@@ -0,0 +1,36 @@
+/*
+ * C interface of the bp-justification-verifier library.
+ *
+ * All arguments are SCALE-encoded: `header` is the Polkadot-like header, `justification` is the
+ * `GrandpaJustification` and `authorities` is the `AuthorityList` of the set `authorities_set_id`.
+ */
+
+#ifndef BP_JUSTIFICATION_VERIFIER_H
+#define BP_JUSTIFICATION_VERIFIER_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+#define BP_JUSTIFICATION_OK 0
+#define BP_JUSTIFICATION_HEADER_DECODE 1
+#define BP_JUSTIFICATION_AUTHORITIES_DECODE 2
+#define BP_JUSTIFICATION_INVALID_AUTHORITY_SET 3
+#define BP_JUSTIFICATION_JUSTIFICATION_DECODE 4
+#define BP_JUSTIFICATION_INVALID_JUSTIFICATION_TARGET 5
+#define BP_JUSTIFICATION_INVALID_AUTHORITY_SIGNATURE 6
+#define BP_JUSTIFICATION_PRECOMMIT_IS_NOT_COMMIT_DESCENDANT 7
+#define BP_JUSTIFICATION_TOO_LOW_CUMULATIVE_WEIGHT 8
+#define BP_JUSTIFICATION_EXTRA_HEADERS_IN_VOTES_ANCESTRIES 9
+#define BP_JUSTIFICATION_NULL_POINTER 10
+
+uint32_t bp_verify_grandpa_justification(
+	const uint8_t *header,
+	size_t header_len,
+	const uint8_t *justification,
+	size_t justification_len,
+	const uint8_t *authorities,
+	size_t authorities_len,
+	uint64_t authorities_set_id
+);
+
+#endif /* BP_JUSTIFICATION_VERIFIER_H */
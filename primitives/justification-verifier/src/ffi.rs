@@ -0,0 +1,48 @@
+// Copyright 2019-2021 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+//! C FFI interface of the justification verifier.
+
+use crate::{verify_encoded_justification, Header, VerificationResult};
+
+/// Verify that SCALE-encoded justification, that is generated by given SCALE-encoded authority
+/// list with given set id, finalizes given SCALE-encoded Polkadot-like header.
+///
+/// Returns numeric code of the `VerificationResult`. Zero means that the justification is valid.
+///
+/// # Safety
+///
+/// Every `(pointer, length)` pair must describe readable memory region that stays valid for
+/// the whole duration of the call.
+#[no_mangle]
+pub unsafe extern "C" fn bp_verify_grandpa_justification(
+	header: *const u8,
+	header_len: usize,
+	justification: *const u8,
+	justification_len: usize,
+	authorities: *const u8,
+	authorities_len: usize,
+	authorities_set_id: u64,
+) -> u32 {
+	if header.is_null() || justification.is_null() || authorities.is_null() {
+		return VerificationResult::NullPointer as u32;
+	}
+
+	let header = std::slice::from_raw_parts(header, header_len);
+	let justification = std::slice::from_raw_parts(justification, justification_len);
+	let authorities = std::slice::from_raw_parts(authorities, authorities_len);
+	verify_encoded_justification::<Header>(header, justification, authorities, authorities_set_id) as u32
+}
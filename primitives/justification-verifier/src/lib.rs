@@ -0,0 +1,127 @@
+// Copyright 2019-2021 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Standalone GRANDPA justification verifier.
+//!
+//! This crate exposes exactly the same justification verification code that is used by the
+//! `pallet-bridge-grandpa` (see `bp_header_chain::justification::verify_justification`) to
+//! non-Rust environments. There are two interfaces: C FFI (see the `ffi` module and
+//! `include/justification_verifier.h`) and `wasm-bindgen` (enabled by the `wasm` feature).
+//!
+//! All interfaces accept SCALE-encoded header, justification and authority list and return
+//! numeric `VerificationResult` code.
+
+use bp_header_chain::justification::{verify_justification, Error as JustificationError, GrandpaJustification};
+use codec::Decode;
+use finality_grandpa::voter_set::VoterSet;
+use sp_finality_grandpa::{AuthorityList, SetId};
+use sp_runtime::traits::Header as HeaderT;
+
+pub mod ffi;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+
+/// Header type that is supported by the C FFI and WASM interfaces.
+///
+/// This is the header of all Polkadot-like chains (Polkadot, Kusama, Rococo, Wococo, Westend
+/// and Rialto).
+pub type Header = sp_runtime::generic::Header<u32, sp_runtime::traits::BlakeTwo256>;
+
+/// Result of justification verification.
+///
+/// The numeric values of this enum are a part of the public interface and shall never change.
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VerificationResult {
+	/// Justification is valid.
+	Ok = 0,
+	/// Failed to decode header.
+	HeaderDecode = 1,
+	/// Failed to decode authority list.
+	AuthoritiesDecode = 2,
+	/// The authority list is not a valid GRANDPA voter set (e.g. it is empty or weights overflow).
+	InvalidAuthoritySet = 3,
+	/// Failed to decode justification.
+	JustificationDecode = 4,
+	/// Justification is finalizing unexpected header.
+	InvalidJustificationTarget = 5,
+	/// The authority has provided an invalid signature.
+	InvalidAuthoritySignature = 6,
+	/// The justification contains precommit for header that is not a descendant of the commit header.
+	PrecommitIsNotCommitDescendant = 7,
+	/// The cumulative weight of all votes in the justification is not enough to justify commit
+	/// header finalization.
+	TooLowCumulativeWeight = 8,
+	/// The justification contains extra (unused) headers in its `votes_ancestries` field.
+	ExtraHeadersInVotesAncestries = 9,
+	/// One of pointers, passed to the FFI function, is null.
+	NullPointer = 10,
+}
+
+impl From<JustificationError> for VerificationResult {
+	fn from(error: JustificationError) -> Self {
+		match error {
+			JustificationError::JustificationDecode => VerificationResult::JustificationDecode,
+			JustificationError::InvalidJustificationTarget => VerificationResult::InvalidJustificationTarget,
+			JustificationError::InvalidAuthoritySignature => VerificationResult::InvalidAuthoritySignature,
+			JustificationError::PrecommitIsNotCommitDescendant => VerificationResult::PrecommitIsNotCommitDescendant,
+			JustificationError::TooLowCumulativeWeight => VerificationResult::TooLowCumulativeWeight,
+			JustificationError::ExtraHeadersInVotesAncestries => VerificationResult::ExtraHeadersInVotesAncestries,
+		}
+	}
+}
+
+/// Verify that SCALE-encoded justification, that is generated by given (SCALE-encoded) authority
+/// set, finalizes given SCALE-encoded header.
+///
+/// This function performs the same steps as the `pallet-bridge-grandpa` does when it verifies
+/// justification in the `submit_finality_proof` call.
+pub fn verify_encoded_justification<Header: HeaderT>(
+	encoded_header: &[u8],
+	encoded_justification: &[u8],
+	encoded_authorities: &[u8],
+	authorities_set_id: SetId,
+) -> VerificationResult
+where
+	Header::Number: finality_grandpa::BlockNumberOps,
+{
+	let header = match Header::decode(&mut &*encoded_header) {
+		Ok(header) => header,
+		Err(_) => return VerificationResult::HeaderDecode,
+	};
+	let authorities = match AuthorityList::decode(&mut &*encoded_authorities) {
+		Ok(authorities) => authorities,
+		Err(_) => return VerificationResult::AuthoritiesDecode,
+	};
+	let voter_set = match VoterSet::new(authorities) {
+		Some(voter_set) => voter_set,
+		None => return VerificationResult::InvalidAuthoritySet,
+	};
+	let justification = match GrandpaJustification::<Header>::decode(&mut &*encoded_justification) {
+		Ok(justification) => justification,
+		Err(_) => return VerificationResult::JustificationDecode,
+	};
+
+	match verify_justification::<Header>(
+		(header.hash(), *header.number()),
+		authorities_set_id,
+		&voter_set,
+		&justification,
+	) {
+		Ok(()) => VerificationResult::Ok,
+		Err(error) => error.into(),
+	}
+}
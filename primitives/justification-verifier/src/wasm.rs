@@ -0,0 +1,34 @@
+// Copyright 2019-2021 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+//! WASM (`wasm-bindgen`) interface of the justification verifier.
+
+use crate::{verify_encoded_justification, Header};
+use wasm_bindgen::prelude::*;
+
+/// Verify that SCALE-encoded justification, that is generated by given SCALE-encoded authority
+/// list with given set id, finalizes given SCALE-encoded Polkadot-like header.
+///
+/// Returns numeric code of the `VerificationResult`. Zero means that the justification is valid.
+#[wasm_bindgen(js_name = verifyGrandpaJustification)]
+pub fn verify_grandpa_justification(
+	header: &[u8],
+	justification: &[u8],
+	authorities: &[u8],
+	authorities_set_id: u64,
+) -> u32 {
+	verify_encoded_justification::<Header>(header, justification, authorities, authorities_set_id) as u32
+}
@@ -0,0 +1,213 @@
+// Copyright 2019-2021 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Golden tests that ensure that the standalone verifier produces exactly the same results
+//! as the code that is used by the `pallet-bridge-grandpa`.
+
+use bp_header_chain::justification::{verify_justification, GrandpaJustification};
+use bp_justification_verifier::{ffi::bp_verify_grandpa_justification, Header, VerificationResult};
+use bp_test_utils::{
+	authority_list, make_default_justification, make_justification_for_header, test_header,
+	JustificationGeneratorParams, ALICE, BOB, TEST_GRANDPA_SET_ID,
+};
+use codec::Encode;
+use finality_grandpa::voter_set::VoterSet;
+use sp_finality_grandpa::{AuthorityList, SetId};
+use sp_runtime::traits::Header as HeaderT;
+
+/// Verify justification using both FFI and the pallet code, ensure that results are the same
+/// and return the result.
+fn verify_and_compare(
+	header: &Header,
+	justification: &GrandpaJustification<Header>,
+	authorities: AuthorityList,
+	set_id: SetId,
+) -> VerificationResult {
+	let expected = match VoterSet::new(authorities.clone()) {
+		Some(voter_set) => match verify_justification::<Header>(
+			(header.hash(), *header.number()),
+			set_id,
+			&voter_set,
+			justification,
+		) {
+			Ok(()) => VerificationResult::Ok,
+			Err(error) => error.into(),
+		},
+		None => VerificationResult::InvalidAuthoritySet,
+	};
+
+	let (encoded_header, encoded_justification, encoded_authorities) =
+		(header.encode(), justification.encode(), authorities.encode());
+	let actual = unsafe {
+		bp_verify_grandpa_justification(
+			encoded_header.as_ptr(),
+			encoded_header.len(),
+			encoded_justification.as_ptr(),
+			encoded_justification.len(),
+			encoded_authorities.as_ptr(),
+			encoded_authorities.len(),
+			set_id,
+		)
+	};
+
+	assert_eq!(actual, expected as u32);
+	expected
+}
+
+#[test]
+fn valid_justification_is_accepted() {
+	let header = test_header::<Header>(1);
+	assert_eq!(
+		verify_and_compare(
+			&header,
+			&make_default_justification(&header),
+			authority_list(),
+			TEST_GRANDPA_SET_ID,
+		),
+		VerificationResult::Ok,
+	);
+}
+
+#[test]
+fn justification_for_other_header_is_rejected() {
+	assert_eq!(
+		verify_and_compare(
+			&test_header::<Header>(2),
+			&make_default_justification(&test_header::<Header>(1)),
+			authority_list(),
+			TEST_GRANDPA_SET_ID,
+		),
+		VerificationResult::InvalidJustificationTarget,
+	);
+}
+
+#[test]
+fn justification_with_invalid_signature_is_rejected() {
+	let header = test_header::<Header>(1);
+	let mut justification = make_default_justification(&header);
+	justification.commit.precommits[0].signature = Default::default();
+
+	assert_eq!(
+		verify_and_compare(&header, &justification, authority_list(), TEST_GRANDPA_SET_ID),
+		VerificationResult::InvalidAuthoritySignature,
+	);
+}
+
+#[test]
+fn justification_for_other_round_or_set_is_rejected() {
+	let header = test_header::<Header>(1);
+	let mut justification = make_default_justification(&header);
+	assert_eq!(
+		verify_and_compare(&header, &justification, authority_list(), TEST_GRANDPA_SET_ID + 1),
+		VerificationResult::InvalidAuthoritySignature,
+	);
+
+	justification.round = 42;
+	assert_eq!(
+		verify_and_compare(&header, &justification, authority_list(), TEST_GRANDPA_SET_ID),
+		VerificationResult::InvalidAuthoritySignature,
+	);
+}
+
+#[test]
+fn justification_with_extra_ancestry_is_rejected() {
+	let header = test_header::<Header>(1);
+	let mut justification = make_default_justification(&header);
+	justification.votes_ancestries.push(test_header(10));
+
+	assert_eq!(
+		verify_and_compare(&header, &justification, authority_list(), TEST_GRANDPA_SET_ID),
+		VerificationResult::ExtraHeadersInVotesAncestries,
+	);
+}
+
+#[test]
+fn justification_with_too_low_weight_is_rejected() {
+	let header = test_header::<Header>(1);
+	let justification = make_justification_for_header(JustificationGeneratorParams {
+		header: header.clone(),
+		authorities: vec![(ALICE, 1), (BOB, 1)],
+		ancestors: 4,
+		forks: 2,
+		..Default::default()
+	});
+
+	assert_eq!(
+		verify_and_compare(&header, &justification, authority_list(), TEST_GRANDPA_SET_ID),
+		VerificationResult::TooLowCumulativeWeight,
+	);
+}
+
+#[test]
+fn empty_authority_set_is_rejected() {
+	let header = test_header::<Header>(1);
+	assert_eq!(
+		verify_and_compare(
+			&header,
+			&make_default_justification(&header),
+			Vec::new(),
+			TEST_GRANDPA_SET_ID,
+		),
+		VerificationResult::InvalidAuthoritySet,
+	);
+}
+
+#[test]
+fn malformed_arguments_are_rejected() {
+	let header = test_header::<Header>(1);
+	let encoded_header = header.encode();
+	let encoded_justification = make_default_justification(&header).encode();
+	let encoded_authorities = authority_list().encode();
+
+	let verify = |header: &[u8], justification: &[u8], authorities: &[u8]| unsafe {
+		bp_verify_grandpa_justification(
+			header.as_ptr(),
+			header.len(),
+			justification.as_ptr(),
+			justification.len(),
+			authorities.as_ptr(),
+			authorities.len(),
+			TEST_GRANDPA_SET_ID,
+		)
+	};
+
+	assert_eq!(
+		verify(&[], &encoded_justification, &encoded_authorities),
+		VerificationResult::HeaderDecode as u32,
+	);
+	assert_eq!(
+		verify(&encoded_header, &encoded_justification, &[42]),
+		VerificationResult::AuthoritiesDecode as u32,
+	);
+	assert_eq!(
+		verify(&encoded_header, &encoded_justification[..10], &encoded_authorities),
+		VerificationResult::JustificationDecode as u32,
+	);
+	assert_eq!(
+		unsafe {
+			bp_verify_grandpa_justification(
+				std::ptr::null(),
+				0,
+				encoded_justification.as_ptr(),
+				encoded_justification.len(),
+				encoded_authorities.as_ptr(),
+				encoded_authorities.len(),
+				TEST_GRANDPA_SET_ID,
+			)
+		},
+		VerificationResult::NullPointer as u32,
+	);
+}
@@ -21,7 +21,7 @@
 
 use bp_runtime::{
 	messages::{DispatchFeePayment, MessageDispatchResult},
-	ChainId, Size,
+	ChainId, Size, SourceAccount,
 };
 use codec::{Decode, Encode};
 use frame_support::RuntimeDebug;
@@ -54,18 +54,50 @@ pub trait MessageDispatch<AccountId, MessageId> {
 	/// If message is `Ok`, then it should be dispatched. If it is `Err`, then it's just
 	/// a sign that some other component has rejected the message even before it has
 	/// reached `dispatch` method (right now this may only be caused if we fail to decode
-	/// the whole message).
+	/// the whole message). The `Err` contains the raw (encoded) message payload.
 	///
 	/// Returns unspent dispatch weight.
 	fn dispatch<P: FnOnce(&AccountId, Weight) -> Result<(), ()>>(
 		source_chain: ChainId,
 		target_chain: ChainId,
 		id: MessageId,
-		message: Result<Self::Message, ()>,
+		message: Result<Self::Message, Vec<u8>>,
 		pay_dispatch_fee: P,
 	) -> MessageDispatchResult;
 }
 
+/// Dispatch result of the message, that is sent back to the source chain.
+#[derive(RuntimeDebug, Encode, Decode, Clone, PartialEq, Eq)]
+pub struct DispatchAcknowledgement<MessageId> {
+	/// Id of the dispatched message.
+	pub message_id: MessageId,
+	/// True if the message has been dispatched successfully.
+	pub dispatch_result: bool,
+}
+
+/// A hook that sends dispatch acknowledgements back to the source chain.
+///
+/// Runtimes would implement it by queueing the acknowledgement at the messages pallet lane
+/// that is delivering messages to the source chain.
+pub trait SendMessage<MessageId> {
+	/// Weight of the `send_message` call.
+	fn send_message_weight() -> Weight;
+
+	/// Queue the acknowledgement for delivery to the source chain.
+	fn send_message(source_chain: ChainId, acknowledgement: DispatchAcknowledgement<MessageId>) -> Result<(), ()>;
+}
+
+/// Acknowledgement sender that rejects all acknowledgements.
+impl<MessageId> SendMessage<MessageId> for () {
+	fn send_message_weight() -> Weight {
+		0
+	}
+
+	fn send_message(_source_chain: ChainId, _acknowledgement: DispatchAcknowledgement<MessageId>) -> Result<(), ()> {
+		Err(())
+	}
+}
+
 /// Origin of a Call when it is dispatched on the target chain.
 ///
 /// The source chain can (and should) verify that the message can be dispatched on the target chain
@@ -88,6 +120,12 @@ pub enum CallOrigin<SourceChainAccountId, TargetChainAccountPublic, TargetChainS
 	/// `SourceChainAccountId` controls `TargetChainAccountPublic` is the `TargetChainSignature`
 	/// over `(Call, SourceChainAccountId, TargetChainSpecVersion, SourceChainBridgeId).encode()`.
 	///
+	/// The signature scheme is selected by the target chain. Substrate-based chains would normally
+	/// use `sp_runtime::MultiSigner` and `sp_runtime::MultiSignature`, so the target account may be
+	/// controlled by sr25519, ed25519 or ECDSA key. Ethereum-compatible chains may use their own
+	/// signature type (e.g. ECDSA signature that is verified against 20-byte account), as long as it
+	/// implements the `sp_runtime::traits::Verify` trait.
+	///
 	/// NOTE sending messages using this origin (or any other) does not have replay protection!
 	/// The assumption is that both the source account and the target account is controlled by
 	/// the same entity, so source-chain replay protection is sufficient.
@@ -108,6 +146,29 @@ pub enum CallOrigin<SourceChainAccountId, TargetChainAccountPublic, TargetChainS
 	/// Note that the derived account does not need to have a private key on the target chain. This
 	/// origin can therefore represent proxies, pallets, etc. as well as "regular" accounts.
 	SourceAccount(SourceChainAccountId),
+
+	/// Call is sent by the `SourceChainAccountId` on the source chain. On the target chain it is
+	/// dispatched from the origin that the target chain governance has allowed this account to use
+	/// (like `pallet_utility::dispatch_as` does).
+	///
+	/// If the source account isn't allowed to use any origin at the target chain, the message is
+	/// rejected. Dispatch fee and storage deposits are paid by the account that is derived from
+	/// the source account (same as for the `SourceAccount` origin).
+	///
+	/// Target chains that don't support this origin are unable to decode the message and reject it.
+	SourceAccountAs(SourceChainAccountId),
+}
+
+/// Origin of the target chain account that has been derived from the source chain account.
+///
+/// Derived accounts have no private keys, so this record is the only way to find out who controls
+/// such account.
+#[derive(RuntimeDebug, Encode, Decode, Clone, PartialEq, Eq)]
+pub struct DerivedAccountOrigin<SourceChainAccountId> {
+	/// Id of the chain where the message, dispatched from the derived account, has been sent.
+	pub source_chain: ChainId,
+	/// The source chain account (or Root) that controls the derived account.
+	pub source_account: SourceAccount<SourceChainAccountId>,
 }
 
 /// Message payload type used by dispatch module.
@@ -127,6 +188,30 @@ pub struct MessagePayload<SourceChainAccountId, TargetChainAccountPublic, Target
 	pub call: Call,
 }
 
+/// Payload of the message that carries XCM instead of the runtime call.
+///
+/// The XCM message is executed by the XCM executor of the target chain. Since versioned XCM is
+/// self-describing, there's no need to check the target chain runtime version before execution.
+#[derive(RuntimeDebug, Encode, Decode, Clone, PartialEq, Eq)]
+pub struct XcmMessagePayload<SourceChainAccountId> {
+	/// Weight of the XCM message, declared by the message sender. If it is less than the weight,
+	/// computed by the target chain XCM weigher, the message is not executed.
+	pub weight: Weight,
+	/// The source chain account (or Root). The message is executed on behalf of the target chain
+	/// account, derived from this account.
+	pub origin: SourceAccount<SourceChainAccountId>,
+	/// Where the fee for dispatching message is paid?
+	pub dispatch_fee_payment: DispatchFeePayment,
+	/// Encoded versioned XCM message.
+	pub xcm: Vec<u8>,
+}
+
+impl<SourceChainAccountId> Size for XcmMessagePayload<SourceChainAccountId> {
+	fn size_hint(&self) -> u32 {
+		self.xcm.len() as _
+	}
+}
+
 impl<SourceChainAccountId, TargetChainAccountPublic, TargetChainSignature> Size
 	for MessagePayload<SourceChainAccountId, TargetChainAccountPublic, TargetChainSignature, Vec<u8>>
 {
@@ -134,3 +219,238 @@ impl<SourceChainAccountId, TargetChainAccountPublic, TargetChainSignature> Size
 		self.call.len() as _
 	}
 }
+
+/// Value of the first four bytes of the encoded `VersionedMessagePayload`.
+///
+/// Legacy (unversioned) `MessagePayload` starts with the `spec_version` field, which is never
+/// equal to this value. So the target chain is able to decode both legacy and versioned payloads.
+/// Runtimes that only support legacy payloads will reject versioned payloads because of spec version
+/// mismatch, instead of misinterpreting them.
+pub const VERSIONED_MESSAGE_PAYLOAD_MARKER: SpecVersion = SpecVersion::MAX;
+
+/// Index of the `VersionedMessagePayload::V1` in the encoded payload.
+const MESSAGE_PAYLOAD_V1_INDEX: u8 = 1;
+/// Index of the `VersionedMessagePayload::Xcm` in the encoded payload.
+const MESSAGE_PAYLOAD_XCM_INDEX: u8 = 2;
+/// Index of the `VersionedMessagePayload::V2` in the encoded payload.
+const MESSAGE_PAYLOAD_V2_INDEX: u8 = 3;
+
+/// Versioned message payload.
+///
+/// When the payload format is changed (e.g. new origin types are added), the new version of
+/// the payload is added here. Target chain runtimes must be upgraded to support new version
+/// before source chains start sending messages using this version.
+#[derive(RuntimeDebug, Clone, PartialEq, Eq)]
+pub enum VersionedMessagePayload<SourceChainAccountId, TargetChainAccountPublic, TargetChainSignature, Call> {
+	/// Initial payload version. Legacy (unversioned) payloads are decoded as this version.
+	V1(MessagePayload<SourceChainAccountId, TargetChainAccountPublic, TargetChainSignature, Call>),
+	/// Payload that carries XCM message instead of the runtime call.
+	Xcm(XcmMessagePayload<SourceChainAccountId>),
+	/// Same as `V1`, but with the flag that requests acknowledgement of the dispatch result.
+	V2 {
+		/// The payload itself.
+		payload: MessagePayload<SourceChainAccountId, TargetChainAccountPublic, TargetChainSignature, Call>,
+		/// If true, the dispatch result is sent back to the source chain.
+		acknowledge_dispatch: bool,
+	},
+}
+
+impl<SourceChainAccountId, TargetChainAccountPublic, TargetChainSignature, Call>
+	VersionedMessagePayload<SourceChainAccountId, TargetChainAccountPublic, TargetChainSignature, Call>
+{
+	/// Return weight of the call (or XCM message), declared by the message sender.
+	pub fn weight(&self) -> Weight {
+		match *self {
+			VersionedMessagePayload::V1(ref payload) => payload.weight,
+			VersionedMessagePayload::Xcm(ref payload) => payload.weight,
+			VersionedMessagePayload::V2 { ref payload, .. } => payload.weight,
+		}
+	}
+
+	/// Returns true if the dispatch result must be sent back to the source chain.
+	pub fn acknowledge_dispatch(&self) -> bool {
+		match *self {
+			VersionedMessagePayload::V2 {
+				acknowledge_dispatch, ..
+			} => acknowledge_dispatch,
+			_ => false,
+		}
+	}
+
+	/// Convert call payload into the latest supported payload version.
+	///
+	/// XCM payload is returned as is, in the `Err` variant.
+	#[allow(clippy::type_complexity)]
+	pub fn into_latest(
+		self,
+	) -> Result<
+		MessagePayload<SourceChainAccountId, TargetChainAccountPublic, TargetChainSignature, Call>,
+		XcmMessagePayload<SourceChainAccountId>,
+	> {
+		match self {
+			VersionedMessagePayload::V1(payload) => Ok(payload),
+			VersionedMessagePayload::Xcm(payload) => Err(payload),
+			VersionedMessagePayload::V2 { payload, .. } => Ok(payload),
+		}
+	}
+}
+
+impl<SourceChainAccountId, TargetChainAccountPublic, TargetChainSignature, Call>
+	From<MessagePayload<SourceChainAccountId, TargetChainAccountPublic, TargetChainSignature, Call>>
+	for VersionedMessagePayload<SourceChainAccountId, TargetChainAccountPublic, TargetChainSignature, Call>
+{
+	fn from(
+		payload: MessagePayload<SourceChainAccountId, TargetChainAccountPublic, TargetChainSignature, Call>,
+	) -> Self {
+		VersionedMessagePayload::V1(payload)
+	}
+}
+
+impl<SourceChainAccountId, TargetChainAccountPublic, TargetChainSignature, Call> Encode
+	for VersionedMessagePayload<SourceChainAccountId, TargetChainAccountPublic, TargetChainSignature, Call>
+where
+	SourceChainAccountId: Encode,
+	TargetChainAccountPublic: Encode,
+	TargetChainSignature: Encode,
+	Call: Encode,
+{
+	fn encode_to<T: codec::Output + ?Sized>(&self, dest: &mut T) {
+		VERSIONED_MESSAGE_PAYLOAD_MARKER.encode_to(dest);
+		match *self {
+			VersionedMessagePayload::V1(ref payload) => {
+				MESSAGE_PAYLOAD_V1_INDEX.encode_to(dest);
+				payload.encode_to(dest);
+			}
+			VersionedMessagePayload::Xcm(ref payload) => {
+				MESSAGE_PAYLOAD_XCM_INDEX.encode_to(dest);
+				payload.encode_to(dest);
+			}
+			VersionedMessagePayload::V2 {
+				ref payload,
+				acknowledge_dispatch,
+			} => {
+				MESSAGE_PAYLOAD_V2_INDEX.encode_to(dest);
+				payload.encode_to(dest);
+				acknowledge_dispatch.encode_to(dest);
+			}
+		}
+	}
+}
+
+impl<SourceChainAccountId, TargetChainAccountPublic, TargetChainSignature, Call> Decode
+	for VersionedMessagePayload<SourceChainAccountId, TargetChainAccountPublic, TargetChainSignature, Call>
+where
+	SourceChainAccountId: Decode,
+	TargetChainAccountPublic: Decode,
+	TargetChainSignature: Decode,
+	Call: Decode,
+{
+	fn decode<I: codec::Input>(input: &mut I) -> Result<Self, codec::Error> {
+		let marker_or_spec_version = SpecVersion::decode(input)?;
+		if marker_or_spec_version != VERSIONED_MESSAGE_PAYLOAD_MARKER {
+			// legacy payload, which starts with the `spec_version` field
+			return Ok(VersionedMessagePayload::V1(MessagePayload {
+				spec_version: marker_or_spec_version,
+				weight: Decode::decode(input)?,
+				origin: Decode::decode(input)?,
+				dispatch_fee_payment: Decode::decode(input)?,
+				call: Decode::decode(input)?,
+			}));
+		}
+
+		match u8::decode(input)? {
+			MESSAGE_PAYLOAD_V1_INDEX => Ok(VersionedMessagePayload::V1(Decode::decode(input)?)),
+			MESSAGE_PAYLOAD_XCM_INDEX => Ok(VersionedMessagePayload::Xcm(Decode::decode(input)?)),
+			MESSAGE_PAYLOAD_V2_INDEX => Ok(VersionedMessagePayload::V2 {
+				payload: Decode::decode(input)?,
+				acknowledge_dispatch: Decode::decode(input)?,
+			}),
+			_ => Err("Unsupported message payload version".into()),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	type TestPayload = MessagePayload<u64, u64, u64, Vec<u8>>;
+	type TestVersionedPayload = VersionedMessagePayload<u64, u64, u64, Vec<u8>>;
+
+	fn test_payload() -> TestPayload {
+		MessagePayload {
+			spec_version: 1,
+			weight: 100,
+			origin: CallOrigin::TargetAccount(1, 2, 3),
+			dispatch_fee_payment: DispatchFeePayment::AtTargetChain,
+			call: vec![42; 42],
+		}
+	}
+
+	#[test]
+	fn versioned_payload_is_encoded_and_decoded() {
+		let payload: TestVersionedPayload = test_payload().into();
+		let encoded_payload = payload.encode();
+		assert_eq!(&encoded_payload[..4], &VERSIONED_MESSAGE_PAYLOAD_MARKER.encode()[..]);
+		assert_eq!(TestVersionedPayload::decode(&mut &encoded_payload[..]), Ok(payload));
+	}
+
+	#[test]
+	fn legacy_payload_is_decoded_as_v1() {
+		let encoded_payload = test_payload().encode();
+		assert_eq!(
+			TestVersionedPayload::decode(&mut &encoded_payload[..]),
+			Ok(VersionedMessagePayload::V1(test_payload())),
+		);
+	}
+
+	#[test]
+	fn legacy_runtime_rejects_versioned_payload_with_spec_version_mismatch() {
+		let encoded_payload = TestVersionedPayload::from(test_payload()).encode();
+		let decoded_payload = TestPayload::decode(&mut &encoded_payload[..]);
+		assert!(matches!(
+			decoded_payload,
+			Ok(MessagePayload {
+				spec_version: VERSIONED_MESSAGE_PAYLOAD_MARKER,
+				..
+			}) | Err(_)
+		));
+	}
+
+	#[test]
+	fn xcm_payload_is_encoded_and_decoded() {
+		let payload = TestVersionedPayload::Xcm(XcmMessagePayload {
+			weight: 100,
+			origin: SourceAccount::Account(1),
+			dispatch_fee_payment: DispatchFeePayment::AtTargetChain,
+			xcm: vec![42; 42],
+		});
+		let encoded_payload = payload.encode();
+		assert_eq!(&encoded_payload[..4], &VERSIONED_MESSAGE_PAYLOAD_MARKER.encode()[..]);
+		assert_eq!(TestVersionedPayload::decode(&mut &encoded_payload[..]), Ok(payload));
+	}
+
+	#[test]
+	fn v2_payload_is_encoded_and_decoded() {
+		let payload = TestVersionedPayload::V2 {
+			payload: test_payload(),
+			acknowledge_dispatch: true,
+		};
+		let encoded_payload = payload.encode();
+		assert_eq!(&encoded_payload[..4], &VERSIONED_MESSAGE_PAYLOAD_MARKER.encode()[..]);
+		assert_eq!(
+			TestVersionedPayload::decode(&mut &encoded_payload[..]),
+			Ok(payload.clone())
+		);
+		assert!(payload.acknowledge_dispatch());
+		assert!(!TestVersionedPayload::from(test_payload()).acknowledge_dispatch());
+	}
+
+	#[test]
+	fn unknown_payload_version_is_rejected() {
+		let mut encoded_payload = VERSIONED_MESSAGE_PAYLOAD_MARKER.encode();
+		encoded_payload.push(MESSAGE_PAYLOAD_V2_INDEX + 1);
+		encoded_payload.extend(test_payload().encode());
+		assert!(TestVersionedPayload::decode(&mut &encoded_payload[..]).is_err());
+	}
+}
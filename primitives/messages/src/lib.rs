@@ -23,9 +23,10 @@
 #![allow(clippy::unnecessary_mut_passed)]
 
 use bitvec::prelude::*;
-use bp_runtime::messages::DispatchFeePayment;
-use codec::{Decode, Encode};
-use frame_support::RuntimeDebug;
+use bp_runtime::{messages::DispatchFeePayment, ChainId};
+use codec::{Decode, DecodeAll, Encode, MaxEncodedLen};
+use frame_support::{RuntimeDebug, StorageHasher};
+use sp_core::H256;
 use sp_std::{collections::vec_deque::VecDeque, prelude::*};
 
 pub mod source_chain;
@@ -35,7 +36,7 @@ pub mod target_chain;
 pub use frame_support::weights::Weight;
 
 /// Messages pallet operating mode.
-#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug, MaxEncodedLen)]
 #[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
 pub enum OperatingMode {
 	/// Normal mode, when all operations are allowed.
@@ -58,6 +59,28 @@ impl Default for OperatingMode {
 	}
 }
 
+/// Reason why the inbound message has been rejected (or skipped) by the messages pallet.
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+pub enum MessageRejectionReason {
+	/// The message has already been delivered. Relayer may safely forget about this message.
+	AlreadyDelivered,
+	/// The message nonce is not the next expected nonce at the inbound lane. Previous messages
+	/// must be delivered first.
+	UnexpectedNonce,
+	/// There are too many unrewarded relayer entries at the inbound lane. The message may be
+	/// delivered again once delivery confirmations are received by the bridged chain.
+	TooManyUnrewardedRelayers,
+	/// There are too many unconfirmed messages at the inbound lane. The message may be delivered
+	/// again once delivery confirmations are received by the bridged chain.
+	TooManyUnconfirmedMessages,
+	/// Dispatch weight, declared by the relayer, is not enough to dispatch the message. All
+	/// following messages of the same lane are skipped as well.
+	DispatchWeightTooLow,
+	/// The message has been delivered, but its dispatch has failed. The message won't be
+	/// dispatched again.
+	DispatchFailed,
+}
+
 /// Messages pallet parameter.
 pub trait Parameter: frame_support::Parameter {
 	/// Save parameter value in the runtime storage.
@@ -69,7 +92,176 @@ impl Parameter for () {
 }
 
 /// Lane identifier.
-pub type LaneId = [u8; 4];
+///
+/// Lanes of the first bridge deployments are identified by 4-byte identifiers. These legacy
+/// identifiers are short, so lanes of unrelated bridge deployments may easily get the same
+/// identifier. New lanes should use identifiers that are derived from both lane endpoints (see
+/// [`LaneId::new`]).
+///
+/// Legacy identifiers are SCALE-encoded exactly as the raw `[u8; 4]` identifiers were, so existing
+/// storage, messages and proofs don't need to be migrated. Hashed identifiers are encoded as the
+/// [`HASHED_LANE_ID_MARKER`], followed by the hash.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
+pub enum LaneId {
+	/// Legacy 4-byte lane identifier. The [`HASHED_LANE_ID_MARKER`] is never used as a legacy
+	/// identifier.
+	Legacy([u8; 4]),
+	/// Lane identifier, derived from lane endpoints.
+	Hashed(H256),
+}
+
+impl LaneId {
+	/// Derive lane identifier from its endpoints.
+	///
+	/// The endpoint is normally a tuple of the chain id and the application (pallet, contract,
+	/// ...) identifier at this chain. The result doesn't depend on the order of arguments, so both
+	/// sides of the bridge are computing the same lane identifier.
+	pub fn new<T: Encode + Ord>(endpoint_a: T, endpoint_b: T) -> Self {
+		let (first, second) = if endpoint_a <= endpoint_b {
+			(endpoint_a, endpoint_b)
+		} else {
+			(endpoint_b, endpoint_a)
+		};
+
+		LaneId::Hashed(
+			(HASHED_LANE_ID_DERIVATION_PREFIX, first, second)
+				.using_encoded(frame_support::Blake2_256::hash)
+				.into(),
+		)
+	}
+
+	/// Returns raw legacy identifier if this is the legacy lane.
+	pub fn legacy(&self) -> Option<[u8; 4]> {
+		match *self {
+			LaneId::Legacy(id) => Some(id),
+			LaneId::Hashed(_) => None,
+		}
+	}
+
+	/// Returns true if this is the legacy lane.
+	pub fn is_legacy(&self) -> bool {
+		self.legacy().is_some()
+	}
+}
+
+impl Default for LaneId {
+	fn default() -> Self {
+		LaneId::Legacy([0, 0, 0, 0])
+	}
+}
+
+impl From<[u8; 4]> for LaneId {
+	fn from(id: [u8; 4]) -> Self {
+		LaneId::Legacy(id)
+	}
+}
+
+impl PartialEq<[u8; 4]> for LaneId {
+	fn eq(&self, other: &[u8; 4]) -> bool {
+		self.legacy().as_ref() == Some(other)
+	}
+}
+
+impl AsRef<[u8]> for LaneId {
+	fn as_ref(&self) -> &[u8] {
+		match *self {
+			LaneId::Legacy(ref id) => &id[..],
+			LaneId::Hashed(ref hash) => hash.as_ref(),
+		}
+	}
+}
+
+impl Encode for LaneId {
+	fn size_hint(&self) -> usize {
+		match *self {
+			LaneId::Legacy(_) => 4,
+			LaneId::Hashed(_) => 4 + 32,
+		}
+	}
+
+	fn encode_to<T: codec::Output + ?Sized>(&self, dest: &mut T) {
+		match *self {
+			LaneId::Legacy(ref id) => id.encode_to(dest),
+			LaneId::Hashed(ref hash) => {
+				HASHED_LANE_ID_MARKER.encode_to(dest);
+				hash.encode_to(dest);
+			}
+		}
+	}
+}
+
+impl codec::EncodeLike for LaneId {}
+
+impl Decode for LaneId {
+	fn decode<I: codec::Input>(input: &mut I) -> Result<Self, codec::Error> {
+		let id = <[u8; 4]>::decode(input)?;
+		if id == HASHED_LANE_ID_MARKER {
+			return Ok(LaneId::Hashed(H256::decode(input)?));
+		}
+
+		Ok(LaneId::Legacy(id))
+	}
+}
+
+impl MaxEncodedLen for LaneId {
+	fn max_encoded_len() -> usize {
+		4 + 32
+	}
+}
+
+/// Prefix of encoded hashed lane identifier.
+pub const HASHED_LANE_ID_MARKER: [u8; 4] = [0xFF, 0xFF, 0xFF, 0xFF];
+
+/// Prefix that is used when legacy lane identifier is derived from application identifiers.
+pub const LANE_ID_DERIVATION_PREFIX: &[u8] = b"pallet-bridge/messages/lane";
+
+/// Prefix that is used when hashed lane identifier is derived from lane endpoints.
+pub const HASHED_LANE_ID_DERIVATION_PREFIX: &[u8] = b"pallet-bridge/messages/hashed-lane";
+
+/// Lane that is used to send heartbeat messages.
+///
+/// Heartbeat messages are sent periodically by the source chain. Their payload is ignored by
+/// the target chain, which only tracks the time of the last heartbeat delivery to detect
+/// stale bridges.
+pub const HEARTBEAT_LANE_ID: LaneId = LaneId::Legacy(*b"beat");
+
+/// Lane identifiers that are never returned by the `derive_lane_id`.
+///
+/// The all-zeroes lane is used by default in test deployments and CLI tools. The lane with
+/// [`HASHED_LANE_ID_MARKER`] identifier can't be encoded.
+pub const RESERVED_LANE_IDS: [LaneId; 3] = [
+	LaneId::Legacy([0, 0, 0, 0]),
+	HEARTBEAT_LANE_ID,
+	LaneId::Legacy(HASHED_LANE_ID_MARKER),
+];
+
+/// Derive legacy lane identifier for the application and its counterpart at the bridged chain.
+///
+/// The result doesn't depend on the order of arguments, so both sides of the bridge are
+/// computing the same lane identifier. Reserved lane identifiers are never returned.
+///
+/// Only 4 bytes of hash are used, so different applications may still get the same lane.
+/// That's why the lane must be checked for collisions (e.g. in the lane registry) before
+/// it is opened.
+pub fn derive_lane_id(app_id: &[u8], counterpart: &[u8]) -> LaneId {
+	let (first, second) = if app_id <= counterpart {
+		(app_id, counterpart)
+	} else {
+		(counterpart, app_id)
+	};
+
+	let mut salt: u32 = 0;
+	loop {
+		let hash = (LANE_ID_DERIVATION_PREFIX, first, second, salt).using_encoded(frame_support::Blake2_256::hash);
+		let lane = LaneId::Legacy([hash[0], hash[1], hash[2], hash[3]]);
+		if !RESERVED_LANE_IDS.contains(&lane) {
+			return lane;
+		}
+
+		salt += 1;
+	}
+}
 
 /// Message nonce. Valid messages will never have 0 nonce.
 pub type MessageNonce = u64;
@@ -80,8 +272,34 @@ pub type MessageId = (LaneId, MessageNonce);
 /// Opaque message payload. We only decode this payload when it is dispatched.
 pub type MessagePayload = Vec<u8>;
 
-/// Message key (unique message identifier) as it is stored in the storage.
+/// Message priority.
+///
+/// Priority doesn't change order of messages within the lane. It may only be used by relayers to
+/// decide which lanes need to be served first.
+pub type MessagePriority = u8;
+
+/// Priority of messages that are sent without explicit priority.
+pub const DEFAULT_MESSAGE_PRIORITY: MessagePriority = 0;
+
+/// Identifier of the application that is sending messages over shared lanes.
+pub type AppId = [u8; 2];
+
+/// Message payload, tagged with the identifier of the application that has sent it.
+///
+/// Payloads of lanes, that are shared by multiple applications, are not dispatched as calls.
+/// Instead, they're passed to the handler of the application with given id. The payload
+/// format is defined by the application and is identified by the schema hash in the
+/// application registry at the target chain.
 #[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct AppPayload {
+	/// Identifier of the application that has sent the message.
+	pub app_id: AppId,
+	/// Application-specific payload.
+	pub payload: Vec<u8>,
+}
+
+/// Message key (unique message identifier) as it is stored in the storage.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug, MaxEncodedLen)]
 pub struct MessageKey {
 	/// ID of the message lane.
 	pub lane_id: LaneId,
@@ -96,6 +314,39 @@ pub struct MessageData<Fee> {
 	pub payload: MessagePayload,
 	/// Message delivery and dispatch fee, paid by the submitter.
 	pub fee: Fee,
+	/// Message priority.
+	pub priority: MessagePriority,
+}
+
+impl<Fee: Decode> MessageData<Fee> {
+	/// Decode message data, encoded either by the current runtime, or by the runtime that has been
+	/// using `MessageDataV0` encoding.
+	///
+	/// The whole input must be consumed, so encodings of different versions can't be confused.
+	pub fn decode_any_version(encoded: &[u8]) -> Result<Self, codec::Error> {
+		MessageData::decode_all(encoded).or_else(|_| MessageDataV0::decode_all(encoded).map(Into::into))
+	}
+}
+
+/// Message data as it has been stored before the message priority has been added.
+///
+/// Chains that are not yet upgraded are still storing (and proving) messages using this encoding.
+#[derive(Encode, Decode, Clone, PartialEq, Eq, RuntimeDebug)]
+pub struct MessageDataV0<Fee> {
+	/// Message payload.
+	pub payload: MessagePayload,
+	/// Message delivery and dispatch fee, paid by the submitter.
+	pub fee: Fee,
+}
+
+impl<Fee> From<MessageDataV0<Fee>> for MessageData<Fee> {
+	fn from(data: MessageDataV0<Fee>) -> Self {
+		MessageData {
+			payload: data.payload,
+			fee: data.fee,
+			priority: DEFAULT_MESSAGE_PRIORITY,
+		}
+	}
 }
 
 /// Message as it is stored in the storage.
@@ -108,6 +359,10 @@ pub struct Message<Fee> {
 }
 
 /// Inbound lane data.
+///
+/// Unlike the outbound lane data, it doesn't implement `MaxEncodedLen`, because the size of
+/// the `relayers` field is bounded by the messages pallet configuration. Use `encoded_size_hint`
+/// to compute its size for given bounds.
 #[derive(Encode, Decode, Clone, RuntimeDebug, PartialEq, Eq)]
 pub struct InboundLaneData<RelayerId> {
 	/// Identifiers of relayers and messages that they have delivered to this lane (ordered by message nonce).
@@ -171,6 +426,30 @@ impl<RelayerId> InboundLaneData<RelayerId> {
 			.map(|entry| entry.messages.end)
 			.unwrap_or(self.last_confirmed_nonce)
 	}
+
+	/// Verify that the lane data is consistent.
+	///
+	/// Relayer entries must be ordered by nonce and must cover all delivered, but not yet confirmed
+	/// messages without gaps. Entries that have already been confirmed may still be in the vector,
+	/// because they're pruned lazily.
+	pub fn check_invariants(&self) -> Result<(), &'static str> {
+		for entry in &self.relayers {
+			entry.messages.check_invariants()?;
+		}
+		if find_nonce_gap(&self.relayers).is_some() {
+			return Err("Relayer entries of inbound lane are not contiguous");
+		}
+		if let Some(front) = self.relayers.front() {
+			if front.messages.begin > self.last_confirmed_nonce.saturating_add(1) {
+				return Err("Some unconfirmed messages of inbound lane are missing from relayer entries");
+			}
+		}
+		if self.last_delivered_nonce() < self.last_confirmed_nonce {
+			return Err("Inbound lane has confirmed messages that have never been delivered");
+		}
+
+		Ok(())
+	}
 }
 
 /// Message details, returned by runtime APIs.
@@ -186,6 +465,73 @@ pub struct MessageDetails<OutboundMessageFee> {
 	pub delivery_and_dispatch_fee: OutboundMessageFee,
 	/// Where the fee for dispatching message is paid?
 	pub dispatch_fee_payment: DispatchFeePayment,
+	/// Message priority.
+	pub priority: MessagePriority,
+}
+
+/// Message details, returned by runtime APIs of chains that have not yet added the message priority.
+#[derive(Clone, Encode, Decode, RuntimeDebug, PartialEq, Eq)]
+pub struct MessageDetailsV0<OutboundMessageFee> {
+	/// Nonce assigned to the message.
+	pub nonce: MessageNonce,
+	/// Message dispatch weight, declared by the submitter.
+	pub dispatch_weight: Weight,
+	/// Size of the encoded message.
+	pub size: u32,
+	/// Delivery+dispatch fee paid by the message submitter at the source chain.
+	pub delivery_and_dispatch_fee: OutboundMessageFee,
+	/// Where the fee for dispatching message is paid?
+	pub dispatch_fee_payment: DispatchFeePayment,
+}
+
+impl<OutboundMessageFee> From<MessageDetailsV0<OutboundMessageFee>> for MessageDetails<OutboundMessageFee> {
+	fn from(details: MessageDetailsV0<OutboundMessageFee>) -> Self {
+		MessageDetails {
+			nonce: details.nonce,
+			dispatch_weight: details.dispatch_weight,
+			size: details.size,
+			delivery_and_dispatch_fee: details.delivery_and_dispatch_fee,
+			dispatch_fee_payment: details.dispatch_fee_payment,
+			priority: DEFAULT_MESSAGE_PRIORITY,
+		}
+	}
+}
+
+/// Estimated rewards for delivering messages of the lane, returned by runtime APIs.
+#[derive(Clone, Default, Encode, Decode, RuntimeDebug, PartialEq, Eq)]
+pub struct RelayerRewardsEstimation<OutboundMessageFee> {
+	/// Number of messages that are waiting for delivery at the lane.
+	pub backlog: MessageNonce,
+	/// Number of messages that are covered by this estimation.
+	pub messages: MessageNonce,
+	/// Total dispatch weight of covered messages, declared by their submitters.
+	pub total_dispatch_weight: Weight,
+	/// Total size of covered messages.
+	pub total_size: u32,
+	/// Total delivery+dispatch fee paid by submitters of covered messages. This is the reward
+	/// that is shared by relayers that deliver messages and confirm their delivery.
+	pub total_reward: OutboundMessageFee,
+}
+
+/// Cumulative statistics of the lane, returned by runtime APIs.
+///
+/// Counters are never decreased, except for the `total_fees_collected`, which is decreased when
+/// part of the message fee is refunded to the submitter.
+#[derive(Clone, Default, Encode, Decode, RuntimeDebug, PartialEq, Eq)]
+pub struct LaneStatistics<OutboundMessageFee> {
+	/// Number of messages that have been sent over the lane.
+	pub messages_sent: MessageNonce,
+	/// Number of messages that have been delivered to this chain over the lane.
+	pub messages_delivered: MessageNonce,
+	/// Number of sent messages, which delivery has been confirmed by the bridged chain.
+	pub messages_confirmed: MessageNonce,
+	/// Total size of payloads of sent messages.
+	pub total_payload_size: u64,
+	/// Total delivery and dispatch fee, paid by submitters of sent messages.
+	pub total_fees_collected: OutboundMessageFee,
+	/// Total reward that has been paid to relayers for delivering sent messages and confirming
+	/// their delivery.
+	pub total_rewards_paid: OutboundMessageFee,
 }
 
 /// Bit vector of message dispatch results.
@@ -246,6 +592,18 @@ impl DeliveredMessages {
 		(self.begin..=self.end).contains(&nonce)
 	}
 
+	/// Verify that the messages range is not empty and there's dispatch result for every message.
+	pub fn check_invariants(&self) -> Result<(), &'static str> {
+		if self.begin > self.end {
+			return Err("Delivered messages range is empty");
+		}
+		if self.dispatch_results.len() as MessageNonce != self.total_messages() {
+			return Err("Number of dispatch results doesn't match number of delivered messages");
+		}
+
+		Ok(())
+	}
+
 	/// Get dispatch result flag by message nonce.
 	///
 	/// Dispatch result flag must be interpreted using the knowledge of dispatch mechanism
@@ -263,7 +621,7 @@ impl DeliveredMessages {
 }
 
 /// Gist of `InboundLaneData::relayers` field used by runtime APIs.
-#[derive(Clone, Default, Encode, Decode, RuntimeDebug, PartialEq, Eq)]
+#[derive(Clone, Default, Encode, Decode, RuntimeDebug, PartialEq, Eq, MaxEncodedLen)]
 pub struct UnrewardedRelayersState {
 	/// Number of entries in the `InboundLaneData::relayers` set.
 	pub unrewarded_relayer_entries: MessageNonce,
@@ -275,7 +633,7 @@ pub struct UnrewardedRelayersState {
 }
 
 /// Outbound lane data.
-#[derive(Encode, Decode, Clone, RuntimeDebug, PartialEq, Eq)]
+#[derive(Encode, Decode, Clone, RuntimeDebug, PartialEq, Eq, MaxEncodedLen)]
 pub struct OutboundLaneData {
 	/// Nonce of the oldest message that we haven't yet pruned. May point to not-yet-generated message if
 	/// all sent messages are already pruned.
@@ -286,6 +644,23 @@ pub struct OutboundLaneData {
 	pub latest_generated_nonce: MessageNonce,
 }
 
+impl OutboundLaneData {
+	/// Verify that the lane data is consistent.
+	///
+	/// The bridged chain can't receive messages that we haven't generated and we only prune
+	/// messages, which delivery has been confirmed.
+	pub fn check_invariants(&self) -> Result<(), &'static str> {
+		if self.latest_received_nonce > self.latest_generated_nonce {
+			return Err("Outbound lane has received messages that have never been generated");
+		}
+		if self.oldest_unpruned_nonce > self.latest_received_nonce.saturating_add(1) {
+			return Err("Outbound lane has pruned messages that are not yet received");
+		}
+
+		Ok(())
+	}
+}
+
 impl Default for OutboundLaneData {
 	fn default() -> Self {
 		OutboundLaneData {
@@ -297,6 +672,23 @@ impl Default for OutboundLaneData {
 	}
 }
 
+/// Information about the bridge (messages pallet instance), deployed at the chain.
+#[derive(Encode, Decode, Clone, RuntimeDebug, PartialEq, Eq)]
+pub struct BridgeInfo {
+	/// Identifier of the bridged chain.
+	pub bridged_chain_id: ChainId,
+	/// Name of the messages pallet instance in the runtime.
+	pub messages_pallet_name: Vec<u8>,
+	/// Name of the bridged chain finality pallet instance in the runtime.
+	pub finality_pallet_name: Vec<u8>,
+	/// Current operating mode of the messages pallet.
+	pub operating_mode: OperatingMode,
+	/// True if the bridged chain finality pallet is halted.
+	pub is_finality_halted: bool,
+	/// Lanes that are configured for the bridge.
+	pub lanes: Vec<LaneId>,
+}
+
 /// Returns total number of messages in the `InboundLaneData::relayers` vector.
 ///
 /// Returns `None` if there are more messages that `MessageNonce` may fit (i.e. `MessageNonce + 1`).
@@ -313,10 +705,190 @@ pub fn total_unrewarded_messages<RelayerId>(relayers: &VecDeque<UnrewardedRelaye
 	}
 }
 
+/// Returns the first gap between nonces of consecutive entries of the `InboundLaneData::relayers`
+/// vector.
+///
+/// The gap is returned as a pair of the expected and the actual first nonce of the entry. Returns
+/// `None` if every entry starts right after the previous one.
+pub fn find_nonce_gap<RelayerId>(
+	relayers: &VecDeque<UnrewardedRelayer<RelayerId>>,
+) -> Option<(MessageNonce, MessageNonce)> {
+	relayers
+		.iter()
+		.zip(relayers.iter().skip(1))
+		.map(|(previous, next)| (previous.messages.end.saturating_add(1), next.messages.begin))
+		.find(|(expected_begin, actual_begin)| expected_begin != actual_begin)
+}
+
+sp_api::decl_runtime_apis! {
+	/// API for introspecting bridges that are deployed at the chain.
+	///
+	/// This API is implemented by runtimes and is supposed to be used by generic tooling that
+	/// has no chain-specific knowledge.
+	pub trait BridgesInfoApi {
+		/// Returns information about all bridges that are deployed at the chain.
+		fn bridges() -> Vec<BridgeInfo>;
+	}
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
 
+	#[test]
+	fn derived_lane_id_does_not_depend_on_arguments_order() {
+		assert_eq!(
+			derive_lane_id(b"app", b"counterpart"),
+			derive_lane_id(b"counterpart", b"app")
+		);
+		assert_ne!(
+			derive_lane_id(b"app", b"counterpart"),
+			derive_lane_id(b"app", b"other-counterpart")
+		);
+	}
+
+	#[test]
+	fn fixed_size_lane_data_has_max_encoded_len() {
+		assert_eq!(
+			OutboundLaneData::max_encoded_len(),
+			OutboundLaneData::default().encode().len()
+		);
+		assert_eq!(
+			UnrewardedRelayersState::max_encoded_len(),
+			UnrewardedRelayersState::default().encode().len()
+		);
+	}
+
+	#[test]
+	fn derived_lane_id_is_never_reserved() {
+		for i in 0u32..1024 {
+			assert!(!RESERVED_LANE_IDS.contains(&derive_lane_id(&i.to_le_bytes(), b"counterpart")));
+		}
+	}
+
+	#[test]
+	fn outbound_lane_data_invariants_are_checked() {
+		assert_eq!(OutboundLaneData::default().check_invariants(), Ok(()));
+		assert_eq!(
+			OutboundLaneData {
+				oldest_unpruned_nonce: 6,
+				latest_received_nonce: 5,
+				latest_generated_nonce: 10,
+			}
+			.check_invariants(),
+			Ok(()),
+		);
+		assert!(OutboundLaneData {
+			oldest_unpruned_nonce: 1,
+			latest_received_nonce: 11,
+			latest_generated_nonce: 10,
+		}
+		.check_invariants()
+		.is_err());
+		assert!(OutboundLaneData {
+			oldest_unpruned_nonce: 7,
+			latest_received_nonce: 5,
+			latest_generated_nonce: 10,
+		}
+		.check_invariants()
+		.is_err());
+	}
+
+	fn unrewarded_relayer(begin: MessageNonce, end: MessageNonce) -> UnrewardedRelayer<u8> {
+		UnrewardedRelayer {
+			relayer: 1,
+			messages: DeliveredMessages {
+				begin,
+				end,
+				dispatch_results: bitvec![Msb0, u8; 1; end.saturating_sub(begin).saturating_add(1) as _],
+			},
+		}
+	}
+
+	#[test]
+	fn inbound_lane_data_invariants_are_checked() {
+		let lane_data = |relayers: Vec<UnrewardedRelayer<u8>>, last_confirmed_nonce| InboundLaneData {
+			relayers: relayers.into_iter().collect(),
+			last_confirmed_nonce,
+		};
+
+		// empty lane and lane with confirmed (but not yet pruned) entries are valid
+		assert_eq!(lane_data(vec![], 0).check_invariants(), Ok(()));
+		assert_eq!(
+			lane_data(vec![unrewarded_relayer(1, 2), unrewarded_relayer(3, 5)], 4).check_invariants(),
+			Ok(()),
+		);
+		// gap between entries
+		assert!(lane_data(vec![unrewarded_relayer(1, 2), unrewarded_relayer(4, 5)], 0)
+			.check_invariants()
+			.is_err());
+		// overlapping entries
+		assert!(lane_data(vec![unrewarded_relayer(1, 3), unrewarded_relayer(3, 5)], 0)
+			.check_invariants()
+			.is_err());
+		// unconfirmed messages are missing
+		assert!(lane_data(vec![unrewarded_relayer(3, 5)], 1).check_invariants().is_err());
+		// confirmed messages have never been delivered
+		assert!(lane_data(vec![unrewarded_relayer(1, 5)], 6).check_invariants().is_err());
+		// empty entry
+		assert!(lane_data(vec![unrewarded_relayer(2, 1)], 0).check_invariants().is_err());
+		// missing dispatch results
+		let mut entry = unrewarded_relayer(1, 5);
+		entry.messages.dispatch_results.pop();
+		assert!(lane_data(vec![entry], 0).check_invariants().is_err());
+	}
+
+	#[test]
+	fn nonce_gap_is_found() {
+		let relayers = |entries: Vec<UnrewardedRelayer<u8>>| entries.into_iter().collect::<VecDeque<_>>();
+
+		assert_eq!(find_nonce_gap(&relayers(vec![])), None);
+		assert_eq!(find_nonce_gap(&relayers(vec![unrewarded_relayer(1, 2)])), None);
+		assert_eq!(
+			find_nonce_gap(&relayers(vec![unrewarded_relayer(1, 2), unrewarded_relayer(3, 3)])),
+			None,
+		);
+		assert_eq!(
+			find_nonce_gap(&relayers(vec![
+				unrewarded_relayer(1, 2),
+				unrewarded_relayer(3, 3),
+				unrewarded_relayer(5, 6),
+			])),
+			Some((4, 5)),
+		);
+	}
+
+	#[test]
+	fn legacy_lane_id_is_encoded_as_raw_identifier() {
+		let lane = LaneId::Legacy(*b"test");
+		assert_eq!(lane.encode(), b"test".encode());
+		assert_eq!(LaneId::decode(&mut &b"test"[..]).unwrap(), lane);
+		assert_eq!(lane, *b"test");
+	}
+
+	#[test]
+	fn hashed_lane_id_is_encoded_and_decoded() {
+		let lane = LaneId::new((*b"rlto", 1u32), (*b"mlau", 2u32));
+		let encoded = lane.encode();
+		assert_eq!(&encoded[..4], &HASHED_LANE_ID_MARKER[..]);
+		assert_eq!(encoded.len(), LaneId::max_encoded_len());
+		assert_eq!(lane.size_hint(), encoded.len());
+		assert_eq!(LaneId::decode(&mut &encoded[..]).unwrap(), lane);
+		assert!(!lane.is_legacy());
+	}
+
+	#[test]
+	fn hashed_lane_id_does_not_depend_on_endpoints_order() {
+		assert_eq!(
+			LaneId::new((*b"rlto", 1u32), (*b"mlau", 2u32)),
+			LaneId::new((*b"mlau", 2u32), (*b"rlto", 1u32)),
+		);
+		assert_ne!(
+			LaneId::new((*b"rlto", 1u32), (*b"mlau", 2u32)),
+			LaneId::new((*b"rlto", 1u32), (*b"mlau", 3u32)),
+		);
+	}
+
 	#[test]
 	fn total_unrewarded_messages_does_not_overflow() {
 		assert_eq!(
@@ -396,4 +968,39 @@ mod tests {
 
 		assert!(delivered_messages.message_dispatch_result(125));
 	}
+
+	#[test]
+	fn message_data_of_any_version_is_decoded() {
+		let message_data = MessageData {
+			payload: vec![42],
+			fee: 100u64,
+			priority: 7,
+		};
+		assert_eq!(
+			MessageData::<u64>::decode_any_version(&message_data.encode()),
+			Ok(message_data),
+		);
+
+		let message_data_v0 = MessageDataV0 {
+			payload: vec![42],
+			fee: 100u64,
+		};
+		assert_eq!(
+			MessageData::<u64>::decode_any_version(&message_data_v0.encode()),
+			Ok(MessageData {
+				payload: vec![42],
+				fee: 100u64,
+				priority: DEFAULT_MESSAGE_PRIORITY,
+			}),
+		);
+
+		let mut encoded_with_extra_byte = MessageData {
+			payload: vec![42],
+			fee: 100u64,
+			priority: 7,
+		}
+		.encode();
+		encoded_with_extra_byte.push(0);
+		assert!(MessageData::<u64>::decode_any_version(&encoded_with_extra_byte).is_err());
+	}
 }
@@ -89,6 +89,12 @@ pub trait LaneMessageVerifier<Submitter, Payload, Fee> {
 		outbound_data: &OutboundLaneData,
 		payload: &Payload,
 	) -> Result<(), Self::Error>;
+
+	/// Weight of the `verify_message` call, that is not included in the message sending weight of
+	/// the messages pallet. It is zero for verifiers that don't access the runtime storage.
+	fn verify_message_weight() -> Weight {
+		0
+	}
 }
 
 /// Message delivery payment. It is called as a part of submit-message transaction. Transaction
@@ -116,15 +122,26 @@ pub trait MessageDeliveryAndDispatchPayment<AccountId, Balance> {
 		relayer_fund_account: &AccountId,
 	) -> Result<(), Self::Error>;
 
+	/// Refund (part of) delivery_and_dispatch_fee from some relayers-fund account back to the
+	/// given account.
+	fn refund_delivery_and_dispatch_fee(
+		refund_to: &Sender<AccountId>,
+		fee: &Balance,
+		relayer_fund_account: &AccountId,
+	) -> Result<(), Self::Error>;
+
 	/// Pay rewards for delivering messages to the given relayers.
 	///
 	/// The implementation may also choose to pay reward to the `confirmation_relayer`, which is
 	/// a relayer that has submitted delivery confirmation transaction.
+	///
+	/// Returns the total reward that has actually been paid to relayers. It may be less than the sum
+	/// of `relayers_rewards` if some part of fees is e.g. sent to the treasury, or if some payments fail.
 	fn pay_relayers_rewards(
 		confirmation_relayer: &AccountId,
 		relayers_rewards: RelayersRewards<AccountId, Balance>,
 		relayer_fund_account: &AccountId,
-	);
+	) -> Balance;
 
 	/// Perform some initialization in externalities-provided environment.
 	///
@@ -218,7 +235,7 @@ impl<Submitter, Payload, Fee> LaneMessageVerifier<Submitter, Payload, Fee> for F
 	}
 }
 
-impl<AccountId, Balance> MessageDeliveryAndDispatchPayment<AccountId, Balance> for ForbidOutboundMessages {
+impl<AccountId, Balance: Default> MessageDeliveryAndDispatchPayment<AccountId, Balance> for ForbidOutboundMessages {
 	type Error = &'static str;
 
 	fn pay_delivery_and_dispatch_fee(
@@ -229,10 +246,19 @@ impl<AccountId, Balance> MessageDeliveryAndDispatchPayment<AccountId, Balance> f
 		Err(ALL_OUTBOUND_MESSAGES_REJECTED)
 	}
 
+	fn refund_delivery_and_dispatch_fee(
+		_refund_to: &Sender<AccountId>,
+		_fee: &Balance,
+		_relayer_fund_account: &AccountId,
+	) -> Result<(), Self::Error> {
+		Err(ALL_OUTBOUND_MESSAGES_REJECTED)
+	}
+
 	fn pay_relayers_rewards(
 		_confirmation_relayer: &AccountId,
 		_relayers_rewards: RelayersRewards<AccountId, Balance>,
 		_relayer_fund_account: &AccountId,
-	) {
+	) -> Balance {
+		Balance::default()
 	}
 }
@@ -18,7 +18,7 @@
 
 use crate::{LaneId, Message, MessageData, MessageKey, OutboundLaneData};
 
-use bp_runtime::{messages::MessageDispatchResult, Size};
+use bp_runtime::{messages::MessageDispatchResult, Size, StorageProofStats};
 use codec::{Decode, Encode, Error as CodecError};
 use frame_support::{weights::Weight, Parameter, RuntimeDebug};
 use sp_std::{collections::btree_map::BTreeMap, fmt::Debug, prelude::*};
@@ -35,11 +35,20 @@ pub struct ProvedLaneMessages<Message> {
 	pub messages: Vec<Message>,
 }
 
+/// Dispatch payload that we have failed to decode.
+#[derive(RuntimeDebug)]
+pub struct UndecodablePayload {
+	/// Raw (encoded) dispatch payload.
+	pub payload: Vec<u8>,
+	/// Decode error.
+	pub error: CodecError,
+}
+
 /// Message data with decoded dispatch payload.
 #[derive(RuntimeDebug)]
 pub struct DispatchMessageData<DispatchPayload, Fee> {
 	/// Result of dispatch payload decoding.
-	pub payload: Result<DispatchPayload, CodecError>,
+	pub payload: Result<DispatchPayload, UndecodablePayload>,
 	/// Message delivery and dispatch fee, paid by the submitter.
 	pub fee: Fee,
 }
@@ -66,10 +75,20 @@ pub trait SourceHeaderChain<Fee> {
 	/// of corresponding outbound lane states.
 	type MessagesProof: Parameter + Size;
 
+	/// Return number of lanes that are covered by the messages proof.
+	///
+	/// This is used when computing the delivery transaction weight, so implementations
+	/// that support multi-lane proofs must override it. The default implementation
+	/// assumes that every proof covers a single lane.
+	fn proof_lanes_count(_proof: &Self::MessagesProof) -> u32 {
+		1
+	}
+
 	/// Verify messages proof and return proved messages.
 	///
 	/// Returns error if either proof is incorrect, or the number of messages in the proof
-	/// is not matching the `messages_count`.
+	/// is not matching the `messages_count`. If proof covers several lanes, the `messages_count`
+	/// is the total number of messages at all lanes.
 	///
 	/// Messages vector is required to be sorted by nonce within each lane. Out-of-order
 	/// messages will be rejected.
@@ -81,6 +100,17 @@ pub trait SourceHeaderChain<Fee> {
 		proof: Self::MessagesProof,
 		messages_count: u32,
 	) -> Result<ProvedMessages<Message<Fee>>, Self::Error>;
+
+	/// Verify messages proof and return proved messages along with the storage proof statistics.
+	///
+	/// The statistics are used to refund the part of the delivery transaction weight. The default
+	/// implementation returns no statistics, so nothing is refunded.
+	fn verify_messages_proof_with_stats(
+		proof: Self::MessagesProof,
+		messages_count: u32,
+	) -> Result<(ProvedMessages<Message<Fee>>, Option<StorageProofStats>), Self::Error> {
+		Self::verify_messages_proof(proof, messages_count).map(|messages| (messages, None))
+	}
 }
 
 /// Called when inbound message is received.
@@ -96,6 +126,15 @@ pub trait MessageDispatch<AccountId, Fee> {
 	/// of dispatch weight.
 	fn dispatch_weight(message: &DispatchMessage<Self::DispatchPayload, Fee>) -> Weight;
 
+	/// Weight of the work that the dispatcher does for every delivered message, regardless of
+	/// its payload (e.g. recording dispatched messages).
+	///
+	/// This weight is not declared by the relayer and is added to the weight of the delivery
+	/// transaction instead.
+	fn dispatch_overhead_weight() -> Weight {
+		0
+	}
+
 	/// Called when inbound message is received.
 	///
 	/// It is up to the implementers of this trait to determine whether the message
@@ -130,7 +169,10 @@ impl<DispatchPayload: Decode, Fee> From<Message<Fee>> for DispatchMessage<Dispat
 impl<DispatchPayload: Decode, Fee> From<MessageData<Fee>> for DispatchMessageData<DispatchPayload, Fee> {
 	fn from(data: MessageData<Fee>) -> Self {
 		DispatchMessageData {
-			payload: DispatchPayload::decode(&mut &data.payload[..]),
+			payload: DispatchPayload::decode(&mut &data.payload[..]).map_err(|error| UndecodablePayload {
+				payload: data.payload,
+				error,
+			}),
 			fee: data.fee,
 		}
 	}
@@ -16,6 +16,7 @@
 
 #![cfg_attr(not(feature = "std"), no_std)]
 
+use bp_header_chain::ChainWithGrandpa;
 use bp_messages::MessageNonce;
 use bp_runtime::Chain;
 use frame_support::{
@@ -292,6 +293,16 @@ impl Chain for PolkadotLike {
 	type Header = Header;
 }
 
+impl ChainWithGrandpa for PolkadotLike {
+	// the actual number of validators at Polkadot-like chains is lower, but let's reserve some
+	// space for future growth
+	const MAX_AUTHORITIES_COUNT: u32 = 2048;
+	const REASONABLE_HEADERS_IN_JUSTIFICATON_ANCESTRY: u32 = 8;
+	// authority set change digest item takes `MAX_AUTHORITIES_COUNT * 40` bytes and other
+	// digest items (BABE pre-digest and seal, ...) and header fields fit into 4KB
+	const MAX_HEADER_SIZE: u32 = 4096 + Self::MAX_AUTHORITIES_COUNT * 40;
+}
+
 /// Convert a 256-bit hash into an AccountId.
 pub struct AccountIdConverter;
 
@@ -0,0 +1,139 @@
+// Copyright 2019-2021 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Structured error codes of bridge pallets.
+//!
+//! Every bridge pallet maps its errors into one of `BridgeError` codes. The relay is then able to
+//! report pallet errors (in logs and metrics) without knowing indices of pallets and their errors
+//! in the concrete runtime.
+
+use codec::{Decode, Encode};
+use frame_support::RuntimeDebug;
+use sp_std::convert::TryFrom;
+
+/// Error code that is shared by all bridge pallets and the relay.
+///
+/// Codes are never reused or renumbered, so new codes must only be appended.
+#[derive(Encode, Decode, Clone, Copy, RuntimeDebug, PartialEq, Eq)]
+pub enum BridgeError {
+	/// All pallet operations are halted.
+	Halted = 1,
+	/// The pallet has not been initialized yet.
+	NotInitialized = 2,
+	/// The pallet has already been initialized.
+	AlreadyInitialized = 3,
+	/// There are too many requests for the current window to handle.
+	TooManyRequests = 4,
+	/// Some configured limit (number of items, size of value, ...) has been exceeded.
+	LimitExceeded = 5,
+	/// The bridged chain header (or parachain head) is not known to the pallet.
+	UnknownHeader = 6,
+	/// The bridged chain header (or commitment) is older than the best known one.
+	ObsoleteHeader = 7,
+	/// The finality proof (justification, commitment signatures, ...) is invalid.
+	InvalidFinalityProof = 8,
+	/// The authorities (validators) set or its change is invalid.
+	InvalidAuthoritySet = 9,
+	/// The storage proof is invalid.
+	InvalidStorageProof = 10,
+	/// The messages or messages delivery proof is invalid.
+	InvalidMessagesProof = 11,
+	/// The message has been rejected by the verifier.
+	MessageRejected = 12,
+	/// Failed to withdraw, transfer or refund funds.
+	PaymentFailed = 13,
+	/// The caller is not allowed to perform the operation.
+	NotAllowed = 14,
+	/// The operation is not allowed in the current state.
+	InvalidState = 15,
+	/// The referenced entity (lane, schedule, call, ...) is not known to the pallet.
+	UnknownEntity = 16,
+	/// The call argument is invalid.
+	InvalidArgument = 17,
+}
+
+impl BridgeError {
+	/// Returns numeric code of the error.
+	pub fn code(&self) -> u8 {
+		*self as u8
+	}
+
+	/// Returns name of the error. It may be used as a label in relay metrics.
+	pub fn name(&self) -> &'static str {
+		match *self {
+			BridgeError::Halted => "halted",
+			BridgeError::NotInitialized => "not_initialized",
+			BridgeError::AlreadyInitialized => "already_initialized",
+			BridgeError::TooManyRequests => "too_many_requests",
+			BridgeError::LimitExceeded => "limit_exceeded",
+			BridgeError::UnknownHeader => "unknown_header",
+			BridgeError::ObsoleteHeader => "obsolete_header",
+			BridgeError::InvalidFinalityProof => "invalid_finality_proof",
+			BridgeError::InvalidAuthoritySet => "invalid_authority_set",
+			BridgeError::InvalidStorageProof => "invalid_storage_proof",
+			BridgeError::InvalidMessagesProof => "invalid_messages_proof",
+			BridgeError::MessageRejected => "message_rejected",
+			BridgeError::PaymentFailed => "payment_failed",
+			BridgeError::NotAllowed => "not_allowed",
+			BridgeError::InvalidState => "invalid_state",
+			BridgeError::UnknownEntity => "unknown_entity",
+			BridgeError::InvalidArgument => "invalid_argument",
+		}
+	}
+}
+
+impl TryFrom<u8> for BridgeError {
+	type Error = u8;
+
+	fn try_from(code: u8) -> Result<Self, Self::Error> {
+		match code {
+			1 => Ok(BridgeError::Halted),
+			2 => Ok(BridgeError::NotInitialized),
+			3 => Ok(BridgeError::AlreadyInitialized),
+			4 => Ok(BridgeError::TooManyRequests),
+			5 => Ok(BridgeError::LimitExceeded),
+			6 => Ok(BridgeError::UnknownHeader),
+			7 => Ok(BridgeError::ObsoleteHeader),
+			8 => Ok(BridgeError::InvalidFinalityProof),
+			9 => Ok(BridgeError::InvalidAuthoritySet),
+			10 => Ok(BridgeError::InvalidStorageProof),
+			11 => Ok(BridgeError::InvalidMessagesProof),
+			12 => Ok(BridgeError::MessageRejected),
+			13 => Ok(BridgeError::PaymentFailed),
+			14 => Ok(BridgeError::NotAllowed),
+			15 => Ok(BridgeError::InvalidState),
+			16 => Ok(BridgeError::UnknownEntity),
+			17 => Ok(BridgeError::InvalidArgument),
+			_ => Err(code),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn bridge_error_code_roundtrips() {
+		for code in 0..=u8::MAX {
+			if let Ok(error) = BridgeError::try_from(code) {
+				assert_eq!(error.code(), code);
+			}
+		}
+		assert_eq!(BridgeError::try_from(0), Err(0));
+		assert_eq!(BridgeError::try_from(17).map(|e| e.name()), Ok("invalid_argument"));
+	}
+}
@@ -18,13 +18,19 @@
 
 #![cfg_attr(not(feature = "std"), no_std)]
 
-use codec::Encode;
-use sp_core::hash::H256;
+use codec::{Decode, Encode};
+use frame_support::{RuntimeDebug, StorageHasher};
+use sp_core::{hash::H256, storage::StorageKey};
 use sp_io::hashing::blake2_256;
-use sp_std::convert::TryFrom;
+use sp_std::{convert::TryFrom, vec::Vec};
 
+pub use bp_chain_registry::{
+	ChainId, KUSAMA_CHAIN_ID, MILLAU_CHAIN_ID, NO_INSTANCE_ID, POLKADOT_CHAIN_ID, RIALTO_CHAIN_ID, ROCOCO_CHAIN_ID,
+	WESTEND_CHAIN_ID, WOCOCO_CHAIN_ID,
+};
 pub use chain::{BlockNumberOf, Chain, HashOf, HasherOf, HeaderOf};
-pub use storage_proof::{Error as StorageProofError, StorageProofChecker};
+pub use error::BridgeError;
+pub use storage_proof::{Error as StorageProofError, StorageProofChecker, StorageProofStats};
 
 #[cfg(feature = "std")]
 pub use storage_proof::craft_valid_storage_proof;
@@ -32,28 +38,16 @@ pub use storage_proof::craft_valid_storage_proof;
 pub mod messages;
 
 mod chain;
+mod error;
+mod macros;
 mod storage_proof;
 
-/// Use this when something must be shared among all instances.
-pub const NO_INSTANCE_ID: ChainId = [0, 0, 0, 0];
-
-/// Bridge-with-Rialto instance id.
-pub const RIALTO_CHAIN_ID: ChainId = *b"rlto";
-
-/// Bridge-with-Millau instance id.
-pub const MILLAU_CHAIN_ID: ChainId = *b"mlau";
-
-/// Bridge-with-Polkadot instance id.
-pub const POLKADOT_CHAIN_ID: ChainId = *b"pdot";
-
-/// Bridge-with-Kusama instance id.
-pub const KUSAMA_CHAIN_ID: ChainId = *b"ksma";
-
-/// Bridge-with-Rococo instance id.
-pub const ROCOCO_CHAIN_ID: ChainId = *b"roco";
-
-/// Bridge-with-Wococo instance id.
-pub const WOCOCO_CHAIN_ID: ChainId = *b"woco";
+#[doc(hidden)]
+pub mod __private {
+	pub use frame_support;
+	pub use sp_core;
+	pub use sp_runtime;
+}
 
 /// Call-dispatch module prefix.
 pub const CALL_DISPATCH_MODULE_PREFIX: &[u8] = b"pallet-bridge/dispatch";
@@ -64,15 +58,8 @@ pub const ACCOUNT_DERIVATION_PREFIX: &[u8] = b"pallet-bridge/account-derivation/
 /// A unique prefix for entropy when generating a cross-chain account ID for the Root account.
 pub const ROOT_ACCOUNT_DERIVATION_PREFIX: &[u8] = b"pallet-bridge/account-derivation/root";
 
-/// Unique identifier of the chain.
-///
-/// In addition to its main function (identifying the chain), this type may also be used to
-/// identify module instance. We have a bunch of pallets that may be used in different bridges. E.g.
-/// messages pallet may be deployed twice in the same runtime to bridge ThisChain with Chain1 and Chain2.
-/// Sometimes we need to be able to identify deployed instance dynamically. This type may be used for that.
-pub type ChainId = [u8; 4];
-
 /// Type of accounts on the source chain.
+#[derive(RuntimeDebug, Encode, Decode, Clone, PartialEq, Eq)]
 pub enum SourceAccount<T> {
 	/// An account that belongs to Root (privileged origin).
 	Root,
@@ -115,6 +102,34 @@ pub fn derive_relayer_fund_account_id(bridge_id: ChainId) -> H256 {
 	("relayer-fund-account", bridge_id).using_encoded(blake2_256).into()
 }
 
+/// Returns storage key of the runtime storage value, declared by the pallet with given prefix.
+///
+/// This is a copypaste of the `frame_support::storage::generator::StorageValue::storage_value_final_key`,
+/// that works with dynamic (known at runtime) pallet prefix.
+pub fn storage_value_key(pallet_prefix: &str, value_name: &str) -> StorageKey {
+	let pallet_prefix_hashed = frame_support::Twox128::hash(pallet_prefix.as_bytes());
+	let storage_prefix_hashed = frame_support::Twox128::hash(value_name.as_bytes());
+
+	let mut final_key = Vec::with_capacity(pallet_prefix_hashed.len() + storage_prefix_hashed.len());
+
+	final_key.extend_from_slice(&pallet_prefix_hashed[..]);
+	final_key.extend_from_slice(&storage_prefix_hashed[..]);
+
+	StorageKey(final_key)
+}
+
+/// Returns storage key of the runtime storage map item, declared by the pallet with given prefix.
+///
+/// This is a copypaste of the `frame_support::storage::generator::StorageMap::storage_map_final_key`,
+/// that works with dynamic (known at runtime) pallet prefix. The `key` is the encoded map key.
+pub fn storage_map_final_key<H: StorageHasher>(pallet_prefix: &str, map_name: &str, key: &[u8]) -> StorageKey {
+	let key_hashed = H::hash(key);
+	let mut final_key = storage_value_key(pallet_prefix, map_name).0;
+	final_key.extend_from_slice(key_hashed.as_ref());
+
+	StorageKey(final_key)
+}
+
 /// Anything that has size.
 pub trait Size {
 	/// Return approximate size of this object (in bytes).
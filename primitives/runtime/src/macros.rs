@@ -0,0 +1,121 @@
+// Copyright 2019-2021 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Macros that are used to declare bridged chain primitives.
+
+/// Declare chain type and all common chain constants.
+///
+/// The macro generates:
+///
+/// - the chain type itself, implementing the `bp_runtime::Chain` trait;
+/// - `AccountIdConverter` that converts 256-bit hash into chain `AccountId`;
+/// - `BlockLength` and `BlockWeights` parameters, computed from the given block limits;
+/// - `max_extrinsic_weight()` and `max_extrinsic_size()` functions.
+///
+/// The crate that is using this macro must depend on `frame-system`.
+///
+/// Example:
+///
+/// ```ignore
+/// bp_runtime::decl_bridge_chain! {
+/// 	/// Millau chain.
+/// 	pub struct Millau {
+/// 		BlockNumber = BlockNumber,
+/// 		Hash = Hash,
+/// 		Hasher = Hasher,
+/// 		Header = Header,
+/// 		AccountId = AccountId,
+/// 		MaxBlockSize = 2 * 1024 * 1024,
+/// 		MaxBlockWeight = MAXIMUM_BLOCK_WEIGHT,
+/// 		NormalDispatchRatio = NORMAL_DISPATCH_RATIO,
+/// 		AverageOnInitializeRatio = AVERAGE_ON_INITIALIZE_RATIO,
+/// 	}
+/// }
+/// ```
+#[macro_export]
+macro_rules! decl_bridge_chain {
+	(
+		$(#[$attr:meta])*
+		pub struct $chain:ident {
+			BlockNumber = $block_number:ty,
+			Hash = $hash:ty,
+			Hasher = $hasher:ty,
+			Header = $header:ty,
+			AccountId = $account_id:ty,
+			MaxBlockSize = $max_block_size:expr,
+			MaxBlockWeight = $max_block_weight:expr,
+			NormalDispatchRatio = $normal_dispatch_ratio:expr,
+			AverageOnInitializeRatio = $average_on_initialize_ratio:expr $(,)?
+		}
+	) => {
+		$(#[$attr])*
+		#[derive($crate::__private::frame_support::RuntimeDebug)]
+		pub struct $chain;
+
+		impl $crate::Chain for $chain {
+			type BlockNumber = $block_number;
+			type Hash = $hash;
+			type Hasher = $hasher;
+			type Header = $header;
+		}
+
+		/// Convert a 256-bit hash into an AccountId.
+		pub struct AccountIdConverter;
+
+		impl $crate::__private::sp_runtime::traits::Convert<$crate::__private::sp_core::H256, $account_id>
+			for AccountIdConverter
+		{
+			fn convert(hash: $crate::__private::sp_core::H256) -> $account_id {
+				hash.to_fixed_bytes().into()
+			}
+		}
+
+		$crate::__private::frame_support::parameter_types! {
+			pub BlockLength: frame_system::limits::BlockLength =
+				frame_system::limits::BlockLength::max_with_normal_ratio($max_block_size, $normal_dispatch_ratio);
+			pub BlockWeights: frame_system::limits::BlockWeights = frame_system::limits::BlockWeights::builder()
+				// Allowance for Normal class
+				.for_class($crate::__private::frame_support::weights::DispatchClass::Normal, |weights| {
+					weights.max_total = Some($normal_dispatch_ratio * $max_block_weight);
+				})
+				// Allowance for Operational class
+				.for_class($crate::__private::frame_support::weights::DispatchClass::Operational, |weights| {
+					weights.max_total = Some($max_block_weight);
+					// Extra reserved space for Operational class
+					weights.reserved = Some($max_block_weight - $normal_dispatch_ratio * $max_block_weight);
+				})
+				// By default Mandatory class is not limited at all.
+				// This parameter is used to derive maximal size of a single extrinsic.
+				.avg_block_initialization($average_on_initialize_ratio)
+				.build_or_panic();
+		}
+
+		/// Get the maximum weight (compute time) that a Normal extrinsic on the chain can use.
+		pub fn max_extrinsic_weight() -> $crate::__private::frame_support::weights::Weight {
+			BlockWeights::get()
+				.get($crate::__private::frame_support::weights::DispatchClass::Normal)
+				.max_extrinsic
+				.unwrap_or($crate::__private::frame_support::weights::Weight::MAX)
+		}
+
+		/// Get the maximum length in bytes that a Normal extrinsic on the chain requires.
+		pub fn max_extrinsic_size() -> u32 {
+			*BlockLength::get()
+				.max
+				.get($crate::__private::frame_support::weights::DispatchClass::Normal)
+		}
+	};
+}
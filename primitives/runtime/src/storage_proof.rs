@@ -15,21 +15,51 @@
 // along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
 
 //! Logic for checking Substrate storage proofs.
+//!
+//! Storage proofs are submitted by relayers, so the checker is also guarding against proofs that
+//! have been padded with duplicate or unrelated trie nodes. Such proofs are valid, but the
+//! submitter pays less than the actual cost of their verification.
 
-use hash_db::{HashDB, Hasher, EMPTY_PREFIX};
+use hash_db::{HashDB, HashDBRef, Hasher, Prefix, EMPTY_PREFIX};
 use sp_runtime::RuntimeDebug;
-use sp_std::vec::Vec;
+use sp_std::{
+	cell::{Cell, RefCell},
+	collections::{btree_map::BTreeMap, btree_set::BTreeSet},
+	vec::Vec,
+};
 use sp_trie::{read_trie_value, Layout, MemoryDB, StorageProof};
 
 /// This struct is used to read storage values from a subset of a Merklized database. The "proof"
 /// is a subset of the nodes in the Merkle structure of the database, so that it provides
 /// authentication against a known Merkle root as well as the values in the database themselves.
+///
+/// Values that have been read once are cached, so repeated reads of the same key are not
+/// traversing the trie again. The checker also records trie nodes that have been accessed, so
+/// that the proof may be checked for unused nodes after all required values have been read.
 pub struct StorageProofChecker<H>
 where
 	H: Hasher,
 {
 	root: H::Out,
 	db: MemoryDB<H>,
+	proof_nodes_count: usize,
+	proof_size: usize,
+	accessed_nodes: RefCell<BTreeSet<Vec<u8>>>,
+	cache: RefCell<BTreeMap<Vec<u8>, Option<Vec<u8>>>>,
+	duplicate_reads: Cell<u32>,
+}
+
+/// Storage proof usage statistics.
+#[derive(Clone, Copy, Default, RuntimeDebug, PartialEq, Eq)]
+pub struct StorageProofStats {
+	/// Number of trie nodes in the proof.
+	pub proof_nodes: u32,
+	/// Total size (in bytes) of trie nodes in the proof.
+	pub proof_size: u32,
+	/// Number of proof trie nodes that haven't been accessed by any read.
+	pub unused_nodes: u32,
+	/// Number of reads of keys that have already been read before.
+	pub duplicate_reads: u32,
 }
 
 impl<H> StorageProofChecker<H>
@@ -38,27 +68,109 @@ where
 {
 	/// Constructs a new storage proof checker.
 	///
-	/// This returns an error if the given proof is invalid with respect to the given root.
+	/// This returns an error if the given proof is invalid with respect to the given root, or
+	/// if the proof contains duplicate trie nodes.
 	pub fn new(root: H::Out, proof: StorageProof) -> Result<Self, Error> {
-		let db = proof.into_memory_db();
-		if !db.contains(&root, EMPTY_PREFIX) {
+		let mut proof_nodes_count = 0;
+		let mut proof_size = 0usize;
+		let mut unique_nodes = BTreeSet::new();
+		for node in proof.iter_nodes() {
+			proof_nodes_count += 1;
+			proof_size = proof_size.saturating_add(node.len());
+			if !unique_nodes.insert(node) {
+				return Err(Error::DuplicateNodesInProof);
+			}
+		}
+
+		let db = StorageProof::new(unique_nodes.into_iter().collect()).into_memory_db();
+		if !HashDB::contains(&db, &root, EMPTY_PREFIX) {
 			return Err(Error::StorageRootMismatch);
 		}
 
-		let checker = StorageProofChecker { root, db };
+		let checker = StorageProofChecker {
+			root,
+			db,
+			proof_nodes_count,
+			proof_size,
+			accessed_nodes: RefCell::new(BTreeSet::new()),
+			cache: RefCell::new(BTreeMap::new()),
+			duplicate_reads: Cell::new(0),
+		};
 		Ok(checker)
 	}
 
 	/// Reads a value from the available subset of storage. If the value cannot be read due to an
 	/// incomplete or otherwise invalid proof, this returns an error.
 	pub fn read_value(&self, key: &[u8]) -> Result<Option<Vec<u8>>, Error> {
-		read_trie_value::<Layout<H>, _>(&self.db, &self.root, key).map_err(|_| Error::StorageValueUnavailable)
+		if let Some(value) = self.cache.borrow().get(key) {
+			self.duplicate_reads.set(self.duplicate_reads.get() + 1);
+			return Ok(value.clone());
+		}
+
+		let recorder = AccessRecorder {
+			db: &self.db,
+			accessed_nodes: &self.accessed_nodes,
+		};
+		let value =
+			read_trie_value::<Layout<H>, _>(&recorder, &self.root, key).map_err(|_| Error::StorageValueUnavailable)?;
+		self.cache.borrow_mut().insert(key.to_vec(), value.clone());
+		Ok(value)
+	}
+
+	/// Returns statistics of the proof usage so far.
+	pub fn stats(&self) -> StorageProofStats {
+		let accessed_nodes_count = self.accessed_nodes.borrow().len();
+		StorageProofStats {
+			proof_nodes: self.proof_nodes_count as u32,
+			proof_size: self.proof_size as u32,
+			unused_nodes: self.proof_nodes_count.saturating_sub(accessed_nodes_count) as u32,
+			duplicate_reads: self.duplicate_reads.get(),
+		}
+	}
+
+	/// Checks that all proof nodes have been accessed by previous reads and returns the proof
+	/// usage statistics.
+	///
+	/// Must be called after all required values have been read from the proof.
+	pub fn ensure_no_unused_nodes(self) -> Result<StorageProofStats, Error> {
+		let stats = self.stats();
+		if stats.unused_nodes != 0 {
+			return Err(Error::UnusedNodesInTheProof);
+		}
+
+		Ok(stats)
+	}
+}
+
+/// Trie database wrapper that records keys of all accessed nodes.
+struct AccessRecorder<'a, H: Hasher> {
+	db: &'a MemoryDB<H>,
+	accessed_nodes: &'a RefCell<BTreeSet<Vec<u8>>>,
+}
+
+impl<'a, H: Hasher> HashDBRef<H, Vec<u8>> for AccessRecorder<'a, H> {
+	fn get(&self, key: &H::Out, prefix: Prefix) -> Option<Vec<u8>> {
+		let node = HashDB::get(self.db, key, prefix);
+		if node.is_some() {
+			self.accessed_nodes.borrow_mut().insert(key.as_ref().to_vec());
+		}
+		node
+	}
+
+	fn contains(&self, key: &H::Out, prefix: Prefix) -> bool {
+		HashDB::contains(self.db, key, prefix)
 	}
 }
 
 #[derive(RuntimeDebug, PartialEq)]
 pub enum Error {
+	/// The proof contains the same trie node several times.
+	DuplicateNodesInProof,
+	/// The proof contains trie nodes that haven't been accessed by any read.
+	UnusedNodesInTheProof,
+	/// The proof doesn't contain the storage root node.
 	StorageRootMismatch,
+	/// The value can't be read from the proof.
 	StorageValueUnavailable,
 }
 
@@ -109,4 +221,56 @@ pub mod tests {
 			Some(Error::StorageRootMismatch)
 		);
 	}
+
+	#[test]
+	fn proof_with_duplicate_nodes_is_rejected() {
+		let (root, proof) = craft_valid_storage_proof();
+		let mut nodes: Vec<_> = proof.iter_nodes().collect();
+		nodes.push(nodes[0].clone());
+
+		assert_eq!(
+			<StorageProofChecker<sp_core::Blake2Hasher>>::new(root, StorageProof::new(nodes)).err(),
+			Some(Error::DuplicateNodesInProof)
+		);
+	}
+
+	#[test]
+	fn repeated_reads_are_served_from_cache() {
+		let (root, proof) = craft_valid_storage_proof();
+
+		let checker = <StorageProofChecker<sp_core::Blake2Hasher>>::new(root, proof).unwrap();
+		assert_eq!(checker.read_value(b"key1"), Ok(Some(b"value1".to_vec())));
+		assert_eq!(checker.read_value(b"key1"), Ok(Some(b"value1".to_vec())));
+		assert_eq!(checker.read_value(b"key22"), Ok(None));
+		assert_eq!(checker.read_value(b"key22"), Ok(None));
+		assert_eq!(checker.stats().duplicate_reads, 2);
+	}
+
+	#[test]
+	fn proof_with_unused_nodes_is_rejected() {
+		let (root, proof) = craft_valid_storage_proof();
+
+		// only `key2` is read, so the node that is required to read `key1` is unused
+		let checker = <StorageProofChecker<sp_core::Blake2Hasher>>::new(root, proof.clone()).unwrap();
+		assert_eq!(checker.read_value(b"key2"), Ok(Some(b"value2".to_vec())));
+		assert_ne!(checker.stats().unused_nodes, 0);
+		assert_eq!(checker.ensure_no_unused_nodes(), Err(Error::UnusedNodesInTheProof));
+
+		// all nodes are used when all proved keys are read
+		let checker = <StorageProofChecker<sp_core::Blake2Hasher>>::new(root, proof.clone()).unwrap();
+		assert_eq!(checker.read_value(b"key1"), Ok(Some(b"value1".to_vec())));
+		assert_eq!(checker.read_value(b"key2"), Ok(Some(b"value2".to_vec())));
+		assert_eq!(checker.read_value(b"key22"), Ok(None));
+		let proof_nodes = proof.iter_nodes().count() as u32;
+		let proof_size = proof.iter_nodes().map(|node| node.len() as u32).sum();
+		assert_eq!(
+			checker.ensure_no_unused_nodes(),
+			Ok(StorageProofStats {
+				proof_nodes,
+				proof_size,
+				unused_nodes: 0,
+				duplicate_reads: 0,
+			})
+		);
+	}
 }
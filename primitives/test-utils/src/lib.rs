@@ -210,7 +210,8 @@ pub fn signed_precommit<H: HeaderT>(
 
 /// Get a header for testing.
 ///
-/// The correct parent hash will be used if given a non-zero header.
+/// The correct parent hash will be used if given a non-zero header, so headers, returned by this
+/// function, are forming a single chain.
 pub fn test_header<H: HeaderT>(number: H::Number) -> H {
 	let default = |num| {
 		H::new(
@@ -222,10 +223,11 @@ pub fn test_header<H: HeaderT>(number: H::Number) -> H {
 		)
 	};
 
-	let mut header = default(number);
-	if number != Zero::zero() {
-		let parent_hash = default(number - One::one()).hash();
-		header.set_parent_hash(parent_hash);
+	let mut header = default(Zero::zero());
+	while *header.number() != number {
+		let mut child = default(*header.number() + One::one());
+		child.set_parent_hash(header.hash());
+		header = child;
 	}
 
 	header
@@ -0,0 +1,168 @@
+// Copyright 2019-2021 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Detection of bridged chain resets.
+//!
+//! Test chains are relaunched from time to time and GRANDPA authorities set of the bridged chain
+//! may be reset by its governance. After that, the GRANDPA pallet at the target chain is unable
+//! to verify any new finality proofs. Without this check, the relay would keep submitting failing
+//! transactions until someone notices that.
+
+use bp_header_chain::AuthoritySet;
+use num_traits::Zero;
+use relay_substrate_client::{Chain, Client, Error as SubstrateError, HashOf};
+use relay_utils::alerts::AlertKind;
+use sp_finality_grandpa::SetId;
+use std::fmt::Debug;
+
+/// Name of the GRANDPA pallet at the bridged chain.
+const BRIDGED_GRANDPA_PALLET_NAME: &str = "Grandpa";
+/// Name of the GRANDPA pallet storage value with the current authorities set id.
+const BRIDGED_GRANDPA_CURRENT_SET_ID_VALUE_NAME: &str = "CurrentSetId";
+/// Name of the bridge GRANDPA pallet storage value with the bridged chain genesis hash.
+const GRANDPA_BRIDGED_GENESIS_HASH_VALUE_NAME: &str = "BridgedGenesisHash";
+/// Name of the bridge GRANDPA pallet storage value with the current authorities set.
+const GRANDPA_CURRENT_AUTHORITY_SET_VALUE_NAME: &str = "CurrentAuthoritySet";
+/// Bridged chain reset check interval (in bridged chain blocks).
+const CHECK_INTERVAL_IN_BLOCKS: u32 = 10;
+
+/// Wait until reset of the bridged chain is detected.
+///
+/// When the reset is detected, the alert is fired and description of the reset is returned.
+/// Errors that occur during the check are only logged, because the relay loop is responsible for
+/// handling connection issues.
+pub(crate) async fn wait_for_bridged_chain_reset<SC: Chain, TC: Chain>(
+	source_client: Client<SC>,
+	target_client: Client<TC>,
+	target_grandpa_pallet_name: &'static str,
+) -> String {
+	loop {
+		async_std::task::sleep(SC::AVERAGE_BLOCK_INTERVAL * CHECK_INTERVAL_IN_BLOCKS).await;
+
+		match check_bridged_chain_reset(&source_client, &target_client, target_grandpa_pallet_name).await {
+			Ok(Some(reset)) => {
+				let message = format!(
+					"{}. {} GRANDPA pallet {} must be re-initialized. Stopping {} headers relay",
+					reset,
+					TC::NAME,
+					target_grandpa_pallet_name,
+					SC::NAME,
+				);
+				relay_utils::alerts::fire_and_wait(AlertKind::BridgedChainReset, SC::NAME, message.clone()).await;
+				return message;
+			}
+			Ok(None) => (),
+			Err(error) => log::debug!(
+				target: "bridge",
+				"Failed to check whether {} has been reset: {:?}",
+				SC::NAME,
+				error,
+			),
+		}
+	}
+}
+
+/// Read the bridged chain state, known to the GRANDPA pallet and the actual bridged chain state.
+/// Returns description of the reset, if it is detected.
+async fn check_bridged_chain_reset<SC: Chain, TC: Chain>(
+	source_client: &Client<SC>,
+	target_client: &Client<TC>,
+	target_grandpa_pallet_name: &str,
+) -> Result<Option<String>, SubstrateError> {
+	let actual_genesis_hash = source_client.block_hash_by_number(Zero::zero()).await?;
+	let actual_set_id: Option<SetId> = source_client
+		.storage_value(bp_runtime::storage_value_key(
+			BRIDGED_GRANDPA_PALLET_NAME,
+			BRIDGED_GRANDPA_CURRENT_SET_ID_VALUE_NAME,
+		))
+		.await?;
+	let known_genesis_hash: Option<HashOf<SC>> = target_client
+		.storage_value(bp_runtime::storage_value_key(
+			target_grandpa_pallet_name,
+			GRANDPA_BRIDGED_GENESIS_HASH_VALUE_NAME,
+		))
+		.await?;
+	let known_authority_set: Option<AuthoritySet> = target_client
+		.storage_value(bp_runtime::storage_value_key(
+			target_grandpa_pallet_name,
+			GRANDPA_CURRENT_AUTHORITY_SET_VALUE_NAME,
+		))
+		.await?;
+
+	Ok(detect_bridged_chain_reset(
+		SC::NAME,
+		known_genesis_hash,
+		actual_genesis_hash,
+		known_authority_set.map(|set| set.set_id),
+		actual_set_id,
+	))
+}
+
+/// Compare the bridged chain state, known to the GRANDPA pallet, with the actual bridged chain
+/// state. Returns description of the reset, if it is detected.
+///
+/// Unknown values (e.g. pallets that have been initialized without the genesis hash) never
+/// signal the reset.
+fn detect_bridged_chain_reset<Hash: Debug + PartialEq>(
+	chain_name: &str,
+	known_genesis_hash: Option<Hash>,
+	actual_genesis_hash: Hash,
+	known_set_id: Option<SetId>,
+	actual_set_id: Option<SetId>,
+) -> Option<String> {
+	if let Some(known_genesis_hash) = known_genesis_hash {
+		if known_genesis_hash != actual_genesis_hash {
+			return Some(format!(
+				"{} genesis hash has changed from {:?} to {:?}",
+				chain_name, known_genesis_hash, actual_genesis_hash,
+			));
+		}
+	}
+
+	if let (Some(known_set_id), Some(actual_set_id)) = (known_set_id, actual_set_id) {
+		if actual_set_id < known_set_id {
+			return Some(format!(
+				"{} GRANDPA authorities set id has gone backwards from {} to {}",
+				chain_name, known_set_id, actual_set_id,
+			));
+		}
+	}
+
+	None
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn reset_is_not_detected_if_chain_is_progressing() {
+		assert_eq!(detect_bridged_chain_reset("Test", Some(1), 1, Some(5), Some(5)), None);
+		assert_eq!(detect_bridged_chain_reset("Test", Some(1), 1, Some(5), Some(6)), None);
+		assert_eq!(detect_bridged_chain_reset("Test", None, 1, None, Some(0)), None);
+	}
+
+	#[test]
+	fn reset_is_detected_if_genesis_hash_has_changed() {
+		assert!(detect_bridged_chain_reset("Test", Some(1), 2, Some(5), Some(5)).is_some());
+	}
+
+	#[test]
+	fn reset_is_detected_if_set_id_has_gone_backwards() {
+		assert!(detect_bridged_chain_reset("Test", Some(1), 1, Some(5), Some(0)).is_some());
+		assert!(detect_bridged_chain_reset("Test", None, 1, Some(5), Some(4)).is_some());
+	}
+}
@@ -18,7 +18,7 @@
 
 use crate::cli::{
 	bridge,
-	encode_call::{self, Call, CliEncodeCall},
+	encode_call::{self, Call, CliEncodeCall, TransferCall},
 	encode_message,
 	send_message::{self, DispatchFeePayment},
 	CliChain,
@@ -40,9 +40,22 @@ impl CliEncodeCall for Millau {
 			Call::Remark { remark_payload, .. } => millau_runtime::Call::System(millau_runtime::SystemCall::remark(
 				remark_payload.as_ref().map(|x| x.0.clone()).unwrap_or_default(),
 			)),
-			Call::Transfer { recipient, amount } => millau_runtime::Call::Balances(
-				millau_runtime::BalancesCall::transfer(recipient.raw_id(), amount.cast()),
-			),
+			Call::Transfer {
+				recipient,
+				amount,
+				keep_alive,
+				all,
+			} => millau_runtime::Call::Balances(match encode_call::transfer_call(amount, *keep_alive, *all)? {
+				TransferCall::Transfer(amount) => {
+					millau_runtime::BalancesCall::transfer(recipient.raw_id(), amount.cast())
+				}
+				TransferCall::TransferKeepAlive(amount) => {
+					millau_runtime::BalancesCall::transfer_keep_alive(recipient.raw_id(), amount.cast())
+				}
+				TransferCall::TransferAll { keep_alive } => {
+					millau_runtime::BalancesCall::transfer_all(recipient.raw_id(), keep_alive)
+				}
+			}),
 			Call::BridgeSendMessage {
 				lane,
 				payload,
@@ -62,16 +75,67 @@ impl CliEncodeCall for Millau {
 					bridge_instance_index
 				),
 			},
+			Call::SubmitCallChunk {
+				call_hash,
+				chunks_count,
+				chunk_index,
+				chunk,
+				call_weight,
+			} => millau_runtime::Call::BridgeChunkedDispatch(millau_runtime::ChunkedDispatchCall::submit_chunk(
+				Decode::decode(&mut &*call_hash.0)?,
+				*chunks_count,
+				*chunk_index,
+				chunk.0.clone(),
+				*call_weight,
+			)),
+			Call::SetConversionRate {
+				rate,
+				bridge_instance_index,
+			} => match *bridge_instance_index {
+				bridge::MILLAU_TO_RIALTO_INDEX => {
+					millau_runtime::Call::BridgeRialtoMessages(millau_runtime::MessagesCall::update_pallet_parameter(
+						millau_runtime::rialto_messages::MillauToRialtoMessagesParameter::RialtoToMillauConversionRate(
+							rate.0,
+						),
+					))
+				}
+				_ => anyhow::bail!(
+					"Unsupported target bridge pallet with instance index: {}",
+					bridge_instance_index
+				),
+			},
+			Call::BridgeIncreaseMessageFee {
+				lane,
+				nonce,
+				additional_fee,
+				bridge_instance_index,
+			} => match *bridge_instance_index {
+				bridge::MILLAU_TO_RIALTO_INDEX => millau_runtime::Call::BridgeRialtoMessages(
+					millau_runtime::MessagesCall::increase_message_fee(lane.0, *nonce, additional_fee.cast()),
+				),
+				_ => anyhow::bail!(
+					"Unsupported target bridge pallet with instance index: {}",
+					bridge_instance_index
+				),
+			},
 		})
 	}
 
 	fn get_dispatch_info(call: &millau_runtime::Call) -> anyhow::Result<DispatchInfo> {
 		Ok(call.get_dispatch_info())
 	}
+
+	fn encode_governance_proposal(call: Self::Call) -> anyhow::Result<Self::Call> {
+		// there's no democracy pallet at Millau - the sudo key is the chain governance
+		Ok(millau_runtime::Call::Sudo(millau_runtime::SudoCall::sudo(Box::new(
+			call,
+		))))
+	}
 }
 
 impl CliChain for Millau {
 	const RUNTIME_VERSION: RuntimeVersion = millau_runtime::VERSION;
+	const CHAIN_INFO: bp_chain_registry::ChainInfo = bp_chain_registry::MILLAU;
 
 	type KeyPair = sp_core::sr25519::Pair;
 	type MessagePayload = MessagePayload<bp_millau::AccountId, bp_rialto::AccountSigner, bp_rialto::Signature, Vec<u8>>;
@@ -30,7 +30,9 @@ pub(crate) type MillauFinalityToRialto = SubstrateFinalityToSubstrate<Millau, Ri
 
 impl SubstrateFinalitySyncPipeline for MillauFinalityToRialto {
 	const BEST_FINALIZED_SOURCE_HEADER_ID_AT_TARGET: &'static str = bp_millau::BEST_FINALIZED_MILLAU_HEADER_METHOD;
+	const VERIFY_FINALITY_PROOF_METHOD: Option<&'static str> = Some(bp_millau::VERIFY_MILLAU_FINALITY_PROOF_METHOD);
 
+	type SourceChain = Millau;
 	type TargetChain = Rialto;
 
 	fn transactions_author(&self) -> bp_rialto::AccountId {
@@ -17,15 +17,14 @@
 //! Millau-to-Rialto messages sync entrypoint.
 
 use crate::messages_lane::{
-	select_delivery_transaction_limits, MessagesRelayParams, StandaloneMessagesMetrics, SubstrateMessageLane,
-	SubstrateMessageLaneToSubstrate,
+	read_inbound_lane_limits, select_delivery_transaction_limits, InboundLaneLimits, MessagesRelayParams,
+	StandaloneMessagesMetrics, SubstrateMessageLane, SubstrateMessageLaneToSubstrate,
 };
-use crate::messages_source::SubstrateMessagesSource;
+use crate::messages_source::{AdditionalLanes, SubstrateMessagesSource};
 use crate::messages_target::SubstrateMessagesTarget;
 
 use bp_messages::MessageNonce;
 use bp_runtime::{MILLAU_CHAIN_ID, RIALTO_CHAIN_ID};
-use bridge_runtime_common::messages::target::FromBridgedChainMessagesProof;
 use codec::Encode;
 use frame_support::dispatch::GetDispatchInfo;
 use messages_relay::message_lane::MessageLane;
@@ -96,12 +95,7 @@ impl SubstrateMessageLane for MillauMessagesToRialto {
 		proof: <Self as MessageLane>::MessagesProof,
 	) -> Bytes {
 		let (dispatch_weight, proof) = proof;
-		let FromBridgedChainMessagesProof {
-			ref nonces_start,
-			ref nonces_end,
-			..
-		} = proof;
-		let messages_count = nonces_end - nonces_start + 1;
+		let messages_count = proof.messages_count();
 		let call: rialto_runtime::Call = rialto_runtime::MessagesCall::receive_messages_proof(
 			self.relayer_id_at_source.clone(),
 			proof,
@@ -137,6 +131,8 @@ pub async fn run(
 	params: MessagesRelayParams<Millau, MillauSigningParams, Rialto, RialtoSigningParams>,
 ) -> anyhow::Result<()> {
 	let stall_timeout = Duration::from_secs(5 * 60);
+	let idle_timeout = Duration::from_secs(5 * 60);
+	let idle_tick = Duration::from_secs(60);
 	let relayer_id_at_millau = (*params.source_sign.public().as_array_ref()).into();
 
 	let lane_id = params.lane_id;
@@ -149,13 +145,24 @@ pub async fn run(
 		relayer_id_at_source: relayer_id_at_millau,
 	};
 
+	// limits may be changed by the runtime upgrade, so prefer values from the target runtime
+	let inbound_lane_limits = read_inbound_lane_limits(
+		&params.target_client,
+		bp_rialto::WITH_MILLAU_MESSAGES_PALLET_NAME,
+		InboundLaneLimits {
+			max_unrewarded_relayer_entries: bp_rialto::MAX_UNREWARDED_RELAYER_ENTRIES_AT_INBOUND_LANE,
+			max_unconfirmed_messages: bp_rialto::MAX_UNCONFIRMED_MESSAGES_AT_INBOUND_LANE,
+		},
+	)
+	.await;
+
 	// 2/3 is reserved for proofs and tx overhead
 	let max_messages_size_in_single_batch = bp_rialto::max_extrinsic_size() / 3;
 	// TODO: use Millau weights after https://github.com/paritytech/parity-bridges-common/issues/390
 	let (max_messages_in_single_batch, max_messages_weight_in_single_batch) =
 		select_delivery_transaction_limits::<pallet_bridge_messages::weights::RialtoWeight<millau_runtime::Runtime>>(
 			bp_rialto::max_extrinsic_weight(),
-			bp_rialto::MAX_UNREWARDED_RELAYER_ENTRIES_AT_INBOUND_LANE,
+			inbound_lane_limits.max_unrewarded_relayer_entries,
 		);
 
 	log::info!(
@@ -179,6 +186,7 @@ pub async fn run(
 		>(&lane_id)),
 		params.metrics_params,
 		source_client.clone(),
+		params.target_client.clone(),
 	)?;
 	messages_relay::message_lane_loop::run(
 		messages_relay::message_lane_loop::Params {
@@ -187,9 +195,11 @@ pub async fn run(
 			target_tick: Rialto::AVERAGE_BLOCK_INTERVAL,
 			reconnect_delay: relay_utils::relay_loop::RECONNECT_DELAY,
 			stall_timeout,
+			idle_timeout,
+			idle_tick,
 			delivery_params: messages_relay::message_lane_loop::MessageDeliveryParams {
-				max_unrewarded_relayer_entries_at_target: bp_rialto::MAX_UNREWARDED_RELAYER_ENTRIES_AT_INBOUND_LANE,
-				max_unconfirmed_nonces_at_target: bp_rialto::MAX_UNCONFIRMED_MESSAGES_AT_INBOUND_LANE,
+				max_unrewarded_relayer_entries_at_target: inbound_lane_limits.max_unrewarded_relayer_entries,
+				max_unconfirmed_nonces_at_target: inbound_lane_limits.max_unconfirmed_messages,
 				max_messages_in_single_batch,
 				max_messages_weight_in_single_batch,
 				max_messages_size_in_single_batch,
@@ -202,7 +212,15 @@ pub async fn run(
 			lane_id,
 			RIALTO_CHAIN_ID,
 			params.target_to_source_headers_relay,
-		),
+		)
+		.with_additional_lanes(AdditionalLanes {
+			target_client: params.target_client.clone(),
+			lanes: params.additional_lanes,
+			max_messages_in_single_batch,
+			max_messages_weight_in_single_batch,
+			max_messages_size_in_single_batch,
+			max_unconfirmed_messages_at_target: inbound_lane_limits.max_unconfirmed_messages,
+		}),
 		RialtoTargetClient::new(
 			params.target_client,
 			lane,
@@ -222,11 +240,13 @@ pub(crate) fn add_standalone_metrics(
 	metrics_prefix: Option<String>,
 	metrics_params: MetricsParams,
 	source_client: Client<Millau>,
+	target_client: Client<Rialto>,
 ) -> anyhow::Result<(MetricsParams, StandaloneMessagesMetrics)> {
 	crate::messages_lane::add_standalone_metrics::<MillauMessagesToRialto>(
 		metrics_prefix,
 		metrics_params,
 		source_client,
+		target_client,
 		Some(crate::chains::MILLAU_ASSOCIATED_TOKEN_ID),
 		Some(crate::chains::RIALTO_ASSOCIATED_TOKEN_ID),
 		Some((
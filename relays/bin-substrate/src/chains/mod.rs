@@ -79,7 +79,7 @@ pub(crate) fn token_price_metric(
 
 #[cfg(test)]
 mod tests {
-	use crate::cli::{encode_call, send_message};
+	use crate::cli::send_message;
 	use bp_messages::source_chain::TargetHeaderChain;
 	use codec::Encode;
 	use frame_support::dispatch::GetDispatchInfo;
@@ -135,7 +135,7 @@ mod tests {
 	fn maximal_rialto_to_millau_message_arguments_size_is_computed_correctly() {
 		use rialto_runtime::millau_messages::Millau;
 
-		let maximal_remark_size = encode_call::compute_maximal_message_arguments_size(
+		let maximal_remark_size = bridge_runtime_common::messages::source::maximal_message_arguments_size(
 			bp_rialto::max_extrinsic_size(),
 			bp_millau::max_extrinsic_size(),
 		);
@@ -18,7 +18,7 @@
 
 use crate::cli::{
 	bridge,
-	encode_call::{self, Call, CliEncodeCall},
+	encode_call::{self, Call, CliEncodeCall, TransferCall},
 	encode_message,
 	send_message::{self, DispatchFeePayment},
 	CliChain,
@@ -40,9 +40,20 @@ impl CliEncodeCall for Rialto {
 			Call::Remark { remark_payload, .. } => rialto_runtime::Call::System(rialto_runtime::SystemCall::remark(
 				remark_payload.as_ref().map(|x| x.0.clone()).unwrap_or_default(),
 			)),
-			Call::Transfer { recipient, amount } => {
-				rialto_runtime::Call::Balances(rialto_runtime::BalancesCall::transfer(recipient.raw_id(), amount.0))
-			}
+			Call::Transfer {
+				recipient,
+				amount,
+				keep_alive,
+				all,
+			} => rialto_runtime::Call::Balances(match encode_call::transfer_call(amount, *keep_alive, *all)? {
+				TransferCall::Transfer(amount) => rialto_runtime::BalancesCall::transfer(recipient.raw_id(), amount.0),
+				TransferCall::TransferKeepAlive(amount) => {
+					rialto_runtime::BalancesCall::transfer_keep_alive(recipient.raw_id(), amount.0)
+				}
+				TransferCall::TransferAll { keep_alive } => {
+					rialto_runtime::BalancesCall::transfer_all(recipient.raw_id(), keep_alive)
+				}
+			}),
 			Call::BridgeSendMessage {
 				lane,
 				payload,
@@ -60,16 +71,67 @@ impl CliEncodeCall for Rialto {
 					bridge_instance_index
 				),
 			},
+			Call::SubmitCallChunk {
+				call_hash,
+				chunks_count,
+				chunk_index,
+				chunk,
+				call_weight,
+			} => rialto_runtime::Call::BridgeChunkedDispatch(rialto_runtime::ChunkedDispatchCall::submit_chunk(
+				Decode::decode(&mut &*call_hash.0)?,
+				*chunks_count,
+				*chunk_index,
+				chunk.0.clone(),
+				*call_weight,
+			)),
+			Call::SetConversionRate {
+				rate,
+				bridge_instance_index,
+			} => match *bridge_instance_index {
+				bridge::RIALTO_TO_MILLAU_INDEX => {
+					rialto_runtime::Call::BridgeMillauMessages(rialto_runtime::MessagesCall::update_pallet_parameter(
+						rialto_runtime::millau_messages::RialtoToMillauMessagesParameter::MillauToRialtoConversionRate(
+							rate.0,
+						),
+					))
+				}
+				_ => anyhow::bail!(
+					"Unsupported target bridge pallet with instance index: {}",
+					bridge_instance_index
+				),
+			},
+			Call::BridgeIncreaseMessageFee {
+				lane,
+				nonce,
+				additional_fee,
+				bridge_instance_index,
+			} => match *bridge_instance_index {
+				bridge::RIALTO_TO_MILLAU_INDEX => rialto_runtime::Call::BridgeMillauMessages(
+					rialto_runtime::MessagesCall::increase_message_fee(lane.0, *nonce, additional_fee.cast()),
+				),
+				_ => anyhow::bail!(
+					"Unsupported target bridge pallet with instance index: {}",
+					bridge_instance_index
+				),
+			},
 		})
 	}
 
 	fn get_dispatch_info(call: &rialto_runtime::Call) -> anyhow::Result<DispatchInfo> {
 		Ok(call.get_dispatch_info())
 	}
+
+	fn encode_governance_proposal(call: Self::Call) -> anyhow::Result<Self::Call> {
+		// there's no democracy pallet at Rialto - the sudo key is the chain governance
+		Ok(rialto_runtime::Call::Sudo(rialto_runtime::SudoCall::sudo(Box::new(
+			call,
+		))))
+	}
 }
 
 impl CliChain for Rialto {
 	const RUNTIME_VERSION: RuntimeVersion = rialto_runtime::VERSION;
+	const CHAIN_INFO: bp_chain_registry::ChainInfo = bp_chain_registry::RIALTO;
 
 	type KeyPair = sp_core::sr25519::Pair;
 	type MessagePayload = MessagePayload<bp_rialto::AccountId, bp_millau::AccountSigner, bp_millau::Signature, Vec<u8>>;
@@ -30,7 +30,9 @@ pub(crate) type RialtoFinalityToMillau = SubstrateFinalityToSubstrate<Rialto, Mi
 
 impl SubstrateFinalitySyncPipeline for RialtoFinalityToMillau {
 	const BEST_FINALIZED_SOURCE_HEADER_ID_AT_TARGET: &'static str = bp_rialto::BEST_FINALIZED_RIALTO_HEADER_METHOD;
+	const VERIFY_FINALITY_PROOF_METHOD: Option<&'static str> = Some(bp_rialto::VERIFY_RIALTO_FINALITY_PROOF_METHOD);
 
+	type SourceChain = Rialto;
 	type TargetChain = Millau;
 
 	fn transactions_author(&self) -> bp_millau::AccountId {
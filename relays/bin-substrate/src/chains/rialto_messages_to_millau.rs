@@ -17,15 +17,14 @@
 //! Rialto-to-Millau messages sync entrypoint.
 
 use crate::messages_lane::{
-	select_delivery_transaction_limits, MessagesRelayParams, StandaloneMessagesMetrics, SubstrateMessageLane,
-	SubstrateMessageLaneToSubstrate,
+	read_inbound_lane_limits, select_delivery_transaction_limits, InboundLaneLimits, MessagesRelayParams,
+	StandaloneMessagesMetrics, SubstrateMessageLane, SubstrateMessageLaneToSubstrate,
 };
-use crate::messages_source::SubstrateMessagesSource;
+use crate::messages_source::{AdditionalLanes, SubstrateMessagesSource};
 use crate::messages_target::SubstrateMessagesTarget;
 
 use bp_messages::MessageNonce;
 use bp_runtime::{MILLAU_CHAIN_ID, RIALTO_CHAIN_ID};
-use bridge_runtime_common::messages::target::FromBridgedChainMessagesProof;
 use codec::Encode;
 use frame_support::dispatch::GetDispatchInfo;
 use messages_relay::message_lane::MessageLane;
@@ -96,12 +95,7 @@ impl SubstrateMessageLane for RialtoMessagesToMillau {
 		proof: <Self as MessageLane>::MessagesProof,
 	) -> Bytes {
 		let (dispatch_weight, proof) = proof;
-		let FromBridgedChainMessagesProof {
-			ref nonces_start,
-			ref nonces_end,
-			..
-		} = proof;
-		let messages_count = nonces_end - nonces_start + 1;
+		let messages_count = proof.messages_count();
 		let call: millau_runtime::Call = millau_runtime::MessagesCall::receive_messages_proof(
 			self.relayer_id_at_source.clone(),
 			proof,
@@ -137,6 +131,8 @@ pub async fn run(
 	params: MessagesRelayParams<Rialto, RialtoSigningParams, Millau, MillauSigningParams>,
 ) -> anyhow::Result<()> {
 	let stall_timeout = Duration::from_secs(5 * 60);
+	let idle_timeout = Duration::from_secs(5 * 60);
+	let idle_tick = Duration::from_secs(60);
 	let relayer_id_at_rialto = (*params.source_sign.public().as_array_ref()).into();
 
 	let lane_id = params.lane_id;
@@ -149,12 +145,23 @@ pub async fn run(
 		relayer_id_at_source: relayer_id_at_rialto,
 	};
 
+	// limits may be changed by the runtime upgrade, so prefer values from the target runtime
+	let inbound_lane_limits = read_inbound_lane_limits(
+		&params.target_client,
+		bp_millau::WITH_RIALTO_MESSAGES_PALLET_NAME,
+		InboundLaneLimits {
+			max_unrewarded_relayer_entries: bp_millau::MAX_UNREWARDED_RELAYER_ENTRIES_AT_INBOUND_LANE,
+			max_unconfirmed_messages: bp_millau::MAX_UNCONFIRMED_MESSAGES_AT_INBOUND_LANE,
+		},
+	)
+	.await;
+
 	// 2/3 is reserved for proofs and tx overhead
 	let max_messages_size_in_single_batch = bp_millau::max_extrinsic_size() / 3;
 	let (max_messages_in_single_batch, max_messages_weight_in_single_batch) =
 		select_delivery_transaction_limits::<pallet_bridge_messages::weights::RialtoWeight<rialto_runtime::Runtime>>(
 			bp_millau::max_extrinsic_weight(),
-			bp_millau::MAX_UNREWARDED_RELAYER_ENTRIES_AT_INBOUND_LANE,
+			inbound_lane_limits.max_unrewarded_relayer_entries,
 		);
 
 	log::info!(
@@ -178,6 +185,7 @@ pub async fn run(
 		>(&lane_id)),
 		params.metrics_params,
 		source_client.clone(),
+		params.target_client.clone(),
 	)?;
 	messages_relay::message_lane_loop::run(
 		messages_relay::message_lane_loop::Params {
@@ -186,9 +194,11 @@ pub async fn run(
 			target_tick: Millau::AVERAGE_BLOCK_INTERVAL,
 			reconnect_delay: relay_utils::relay_loop::RECONNECT_DELAY,
 			stall_timeout,
+			idle_timeout,
+			idle_tick,
 			delivery_params: messages_relay::message_lane_loop::MessageDeliveryParams {
-				max_unrewarded_relayer_entries_at_target: bp_millau::MAX_UNREWARDED_RELAYER_ENTRIES_AT_INBOUND_LANE,
-				max_unconfirmed_nonces_at_target: bp_millau::MAX_UNCONFIRMED_MESSAGES_AT_INBOUND_LANE,
+				max_unrewarded_relayer_entries_at_target: inbound_lane_limits.max_unrewarded_relayer_entries,
+				max_unconfirmed_nonces_at_target: inbound_lane_limits.max_unconfirmed_messages,
 				max_messages_in_single_batch,
 				max_messages_weight_in_single_batch,
 				max_messages_size_in_single_batch,
@@ -201,7 +211,15 @@ pub async fn run(
 			lane_id,
 			MILLAU_CHAIN_ID,
 			params.target_to_source_headers_relay,
-		),
+		)
+		.with_additional_lanes(AdditionalLanes {
+			target_client: params.target_client.clone(),
+			lanes: params.additional_lanes,
+			max_messages_in_single_batch,
+			max_messages_weight_in_single_batch,
+			max_messages_size_in_single_batch,
+			max_unconfirmed_messages_at_target: inbound_lane_limits.max_unconfirmed_messages,
+		}),
 		MillauTargetClient::new(
 			params.target_client,
 			lane,
@@ -221,11 +239,13 @@ pub(crate) fn add_standalone_metrics(
 	metrics_prefix: Option<String>,
 	metrics_params: MetricsParams,
 	source_client: Client<Rialto>,
+	target_client: Client<Millau>,
 ) -> anyhow::Result<(MetricsParams, StandaloneMessagesMetrics)> {
 	crate::messages_lane::add_standalone_metrics::<RialtoMessagesToMillau>(
 		metrics_prefix,
 		metrics_params,
 		source_client,
+		target_client,
 		Some(crate::chains::RIALTO_ASSOCIATED_TOKEN_ID),
 		Some(crate::chains::MILLAU_ASSOCIATED_TOKEN_ID),
 		Some((
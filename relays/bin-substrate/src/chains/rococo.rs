@@ -76,16 +76,21 @@ impl CliEncodeCall for Rococo {
 			_ => anyhow::bail!("Unsupported Rococo call: {:?}", call),
 		}
 	}
+
+	fn encode_governance_proposal(_call: Self::Call) -> anyhow::Result<Self::Call> {
+		anyhow::bail!("Governance proposals are not supported at Rococo")
+	}
 }
 
 impl CliChain for Rococo {
 	const RUNTIME_VERSION: RuntimeVersion = bp_rococo::VERSION;
+	const CHAIN_INFO: bp_chain_registry::ChainInfo = bp_chain_registry::ROCOCO;
 
 	type KeyPair = sp_core::sr25519::Pair;
 	type MessagePayload = ();
 
 	fn ss58_format() -> u16 {
-		42
+		bp_chain_registry::ROCOCO.ss58_prefix
 	}
 
 	fn max_extrinsic_weight() -> Weight {
@@ -33,6 +33,7 @@ pub(crate) type RococoFinalityToWococo = SubstrateFinalityToSubstrate<Rococo, Wo
 impl SubstrateFinalitySyncPipeline for RococoFinalityToWococo {
 	const BEST_FINALIZED_SOURCE_HEADER_ID_AT_TARGET: &'static str = bp_rococo::BEST_FINALIZED_ROCOCO_HEADER_METHOD;
 
+	type SourceChain = Rococo;
 	type TargetChain = Wococo;
 
 	fn customize_metrics(params: MetricsParams) -> anyhow::Result<MetricsParams> {
@@ -23,12 +23,13 @@ use sp_version::RuntimeVersion;
 
 impl CliChain for Westend {
 	const RUNTIME_VERSION: RuntimeVersion = bp_westend::VERSION;
+	const CHAIN_INFO: bp_chain_registry::ChainInfo = bp_chain_registry::WESTEND;
 
 	type KeyPair = sp_core::sr25519::Pair;
 	type MessagePayload = ();
 
 	fn ss58_format() -> u16 {
-		42
+		bp_chain_registry::WESTEND.ss58_prefix
 	}
 
 	fn max_extrinsic_weight() -> Weight {
@@ -31,7 +31,9 @@ pub(crate) type WestendFinalityToMillau = SubstrateFinalityToSubstrate<Westend,
 
 impl SubstrateFinalitySyncPipeline for WestendFinalityToMillau {
 	const BEST_FINALIZED_SOURCE_HEADER_ID_AT_TARGET: &'static str = bp_westend::BEST_FINALIZED_WESTEND_HEADER_METHOD;
+	const VERIFY_FINALITY_PROOF_METHOD: Option<&'static str> = Some(bp_westend::VERIFY_WESTEND_FINALITY_PROOF_METHOD);
 
+	type SourceChain = Westend;
 	type TargetChain = Millau;
 
 	fn customize_metrics(params: MetricsParams) -> anyhow::Result<MetricsParams> {
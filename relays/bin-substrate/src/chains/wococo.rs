@@ -70,16 +70,21 @@ impl CliEncodeCall for Wococo {
 			_ => anyhow::bail!("Unsupported Rococo call: {:?}", call),
 		}
 	}
+
+	fn encode_governance_proposal(_call: Self::Call) -> anyhow::Result<Self::Call> {
+		anyhow::bail!("Governance proposals are not supported at Wococo")
+	}
 }
 
 impl CliChain for Wococo {
 	const RUNTIME_VERSION: RuntimeVersion = bp_wococo::VERSION;
+	const CHAIN_INFO: bp_chain_registry::ChainInfo = bp_chain_registry::WOCOCO;
 
 	type KeyPair = sp_core::sr25519::Pair;
 	type MessagePayload = ();
 
 	fn ss58_format() -> u16 {
-		42
+		bp_chain_registry::WOCOCO.ss58_prefix
 	}
 
 	fn max_extrinsic_weight() -> Weight {
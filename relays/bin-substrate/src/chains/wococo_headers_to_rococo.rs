@@ -39,6 +39,7 @@ pub(crate) type WococoFinalityToRococo = SubstrateFinalityToSubstrate<Wococo, Ro
 impl SubstrateFinalitySyncPipeline for WococoFinalityToRococo {
 	const BEST_FINALIZED_SOURCE_HEADER_ID_AT_TARGET: &'static str = bp_wococo::BEST_FINALIZED_WOCOCO_HEADER_METHOD;
 
+	type SourceChain = Wococo;
 	type TargetChain = Rococo;
 
 	fn customize_metrics(params: MetricsParams) -> anyhow::Result<MetricsParams> {
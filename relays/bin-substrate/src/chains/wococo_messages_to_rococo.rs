@@ -17,15 +17,14 @@
 //! Wococo-to-Rococo messages sync entrypoint.
 
 use crate::messages_lane::{
-	select_delivery_transaction_limits, MessagesRelayParams, StandaloneMessagesMetrics, SubstrateMessageLane,
-	SubstrateMessageLaneToSubstrate,
+	read_inbound_lane_limits, select_delivery_transaction_limits, InboundLaneLimits, MessagesRelayParams,
+	StandaloneMessagesMetrics, SubstrateMessageLane, SubstrateMessageLaneToSubstrate,
 };
-use crate::messages_source::SubstrateMessagesSource;
+use crate::messages_source::{AdditionalLanes, SubstrateMessagesSource};
 use crate::messages_target::SubstrateMessagesTarget;
 
 use bp_messages::MessageNonce;
 use bp_runtime::{ROCOCO_CHAIN_ID, WOCOCO_CHAIN_ID};
-use bridge_runtime_common::messages::target::FromBridgedChainMessagesProof;
 use codec::Encode;
 use messages_relay::message_lane::MessageLane;
 use relay_rococo_client::{HeaderId as RococoHeaderId, Rococo, SigningParams as RococoSigningParams};
@@ -97,12 +96,7 @@ impl SubstrateMessageLane for WococoMessagesToRococo {
 		proof: <Self as MessageLane>::MessagesProof,
 	) -> Bytes {
 		let (dispatch_weight, proof) = proof;
-		let FromBridgedChainMessagesProof {
-			ref nonces_start,
-			ref nonces_end,
-			..
-		} = proof;
-		let messages_count = nonces_end - nonces_start + 1;
+		let messages_count = proof.messages_count();
 
 		let call = relay_rococo_client::runtime::Call::BridgeMessagesWococo(
 			relay_rococo_client::runtime::BridgeMessagesWococoCall::receive_messages_proof(
@@ -146,6 +140,8 @@ pub async fn run(
 	params: MessagesRelayParams<Wococo, WococoSigningParams, Rococo, RococoSigningParams>,
 ) -> anyhow::Result<()> {
 	let stall_timeout = Duration::from_secs(5 * 60);
+	let idle_timeout = Duration::from_secs(5 * 60);
+	let idle_tick = Duration::from_secs(60);
 	let relayer_id_at_wococo = (*params.source_sign.public().as_array_ref()).into();
 
 	let lane_id = params.lane_id;
@@ -158,6 +154,17 @@ pub async fn run(
 		relayer_id_at_source: relayer_id_at_wococo,
 	};
 
+	// limits may be changed by the runtime upgrade, so prefer values from the target runtime
+	let inbound_lane_limits = read_inbound_lane_limits(
+		&params.target_client,
+		bp_rococo::WITH_WOCOCO_MESSAGES_PALLET_NAME,
+		InboundLaneLimits {
+			max_unrewarded_relayer_entries: bp_rococo::MAX_UNREWARDED_RELAYER_ENTRIES_AT_INBOUND_LANE,
+			max_unconfirmed_messages: bp_rococo::MAX_UNCONFIRMED_MESSAGES_AT_INBOUND_LANE,
+		},
+	)
+	.await;
+
 	// 2/3 is reserved for proofs and tx overhead
 	let max_messages_size_in_single_batch = bp_rococo::max_extrinsic_size() / 3;
 	// we don't know exact weights of the Rococo runtime. So to guess weights we'll be using
@@ -165,7 +172,7 @@ pub async fn run(
 	let (max_messages_in_single_batch, max_messages_weight_in_single_batch) =
 		select_delivery_transaction_limits::<pallet_bridge_messages::weights::RialtoWeight<rialto_runtime::Runtime>>(
 			bp_rococo::max_extrinsic_weight(),
-			bp_rococo::MAX_UNREWARDED_RELAYER_ENTRIES_AT_INBOUND_LANE,
+			inbound_lane_limits.max_unrewarded_relayer_entries,
 		);
 	let (max_messages_in_single_batch, max_messages_weight_in_single_batch) = (
 		max_messages_in_single_batch / 2,
@@ -193,6 +200,7 @@ pub async fn run(
 		>(&lane_id)),
 		params.metrics_params,
 		source_client.clone(),
+		params.target_client.clone(),
 	)?;
 	messages_relay::message_lane_loop::run(
 		messages_relay::message_lane_loop::Params {
@@ -201,9 +209,11 @@ pub async fn run(
 			target_tick: Rococo::AVERAGE_BLOCK_INTERVAL,
 			reconnect_delay: relay_utils::relay_loop::RECONNECT_DELAY,
 			stall_timeout,
+			idle_timeout,
+			idle_tick,
 			delivery_params: messages_relay::message_lane_loop::MessageDeliveryParams {
-				max_unrewarded_relayer_entries_at_target: bp_rococo::MAX_UNREWARDED_RELAYER_ENTRIES_AT_INBOUND_LANE,
-				max_unconfirmed_nonces_at_target: bp_rococo::MAX_UNCONFIRMED_MESSAGES_AT_INBOUND_LANE,
+				max_unrewarded_relayer_entries_at_target: inbound_lane_limits.max_unrewarded_relayer_entries,
+				max_unconfirmed_nonces_at_target: inbound_lane_limits.max_unconfirmed_messages,
 				max_messages_in_single_batch,
 				max_messages_weight_in_single_batch,
 				max_messages_size_in_single_batch,
@@ -216,7 +226,15 @@ pub async fn run(
 			lane_id,
 			ROCOCO_CHAIN_ID,
 			params.target_to_source_headers_relay,
-		),
+		)
+		.with_additional_lanes(AdditionalLanes {
+			target_client: params.target_client.clone(),
+			lanes: params.additional_lanes,
+			max_messages_in_single_batch,
+			max_messages_weight_in_single_batch,
+			max_messages_size_in_single_batch,
+			max_unconfirmed_messages_at_target: inbound_lane_limits.max_unconfirmed_messages,
+		}),
 		RococoTargetClient::new(
 			params.target_client,
 			lane,
@@ -236,11 +254,13 @@ pub(crate) fn add_standalone_metrics(
 	metrics_prefix: Option<String>,
 	metrics_params: MetricsParams,
 	source_client: Client<Wococo>,
+	target_client: Client<Rococo>,
 ) -> anyhow::Result<(MetricsParams, StandaloneMessagesMetrics)> {
 	crate::messages_lane::add_standalone_metrics::<WococoMessagesToRococo>(
 		metrics_prefix,
 		metrics_params,
 		source_client,
+		target_client,
 		None,
 		None,
 		None,
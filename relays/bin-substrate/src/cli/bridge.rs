@@ -36,6 +36,16 @@ impl FullBridge {
 			Self::WococoToRococo => WOCOCO_TO_ROCOCO_INDEX,
 		}
 	}
+
+	/// Return instance index of the bridge pallet in target runtime.
+	pub fn reverse_bridge_instance_index(&self) -> u8 {
+		match self {
+			Self::MillauToRialto => RIALTO_TO_MILLAU_INDEX,
+			Self::RialtoToMillau => MILLAU_TO_RIALTO_INDEX,
+			Self::RococoToWococo => WOCOCO_TO_ROCOCO_INDEX,
+			Self::WococoToRococo => ROCOCO_TO_WOCOCO_INDEX,
+		}
+	}
 }
 
 pub const RIALTO_TO_MILLAU_INDEX: u8 = 0;
@@ -67,9 +77,64 @@ macro_rules! select_full_bridge {
 				// Send-message / Estimate-fee
 				#[allow(unused_imports)]
 				use bp_rialto::TO_RIALTO_ESTIMATE_MESSAGE_FEE_METHOD as ESTIMATE_MESSAGE_FEE_METHOD;
+				// Estimate-relayer-rewards
+				#[allow(dead_code)]
+				const ESTIMATE_RELAYER_REWARDS_METHOD: Option<&str> =
+					Some(bp_rialto::TO_RIALTO_ESTIMATE_RELAYER_REWARDS_METHOD);
 				// Send-message
 				#[allow(unused_imports)]
 				use millau_runtime::millau_to_rialto_account_ownership_digest as account_ownership_digest;
+				#[allow(dead_code)]
+				const DRY_RUN_MESSAGE_DISPATCH_METHOD: Option<&str> =
+					Some(bp_millau::FROM_MILLAU_DRY_RUN_MESSAGE_DISPATCH_METHOD);
+
+				// Preflight
+				#[allow(dead_code)]
+				const SOURCE_GRANDPA_PALLET_NAME: &str = bp_millau::WITH_RIALTO_GRANDPA_PALLET_NAME;
+				#[allow(dead_code)]
+				const TARGET_GRANDPA_PALLET_NAME: &str = bp_rialto::WITH_MILLAU_GRANDPA_PALLET_NAME;
+				#[allow(dead_code)]
+				const SOURCE_LANE_REGISTRY_PALLET_NAME: Option<&str> = Some(bp_millau::LANE_REGISTRY_PALLET_NAME);
+				#[allow(dead_code)]
+				const TARGET_LANE_REGISTRY_PALLET_NAME: Option<&str> = Some(bp_rialto::LANE_REGISTRY_PALLET_NAME);
+				#[allow(unused_imports)]
+				use millau_runtime::WithRialtoMessagesInstance as SourceMessagesInstance;
+				#[allow(unused_imports)]
+				use rialto_runtime::WithMillauMessagesInstance as TargetMessagesInstance;
+
+				// Verify-chain-compatibility
+				#[allow(dead_code)]
+				const SOURCE_MESSAGES_PALLET_NAME: &str = bp_millau::WITH_RIALTO_MESSAGES_PALLET_NAME;
+				#[allow(dead_code)]
+				const TARGET_MESSAGES_PALLET_NAME: &str = bp_rialto::WITH_MILLAU_MESSAGES_PALLET_NAME;
+				#[allow(dead_code)]
+				const SOURCE_INBOUND_LANE_LIMITS: (bp_messages::MessageNonce, bp_messages::MessageNonce) = (
+					bp_millau::MAX_UNREWARDED_RELAYER_ENTRIES_AT_INBOUND_LANE,
+					bp_millau::MAX_UNCONFIRMED_MESSAGES_AT_INBOUND_LANE,
+				);
+				#[allow(dead_code)]
+				const TARGET_INBOUND_LANE_LIMITS: (bp_messages::MessageNonce, bp_messages::MessageNonce) = (
+					bp_rialto::MAX_UNREWARDED_RELAYER_ENTRIES_AT_INBOUND_LANE,
+					bp_rialto::MAX_UNCONFIRMED_MESSAGES_AT_INBOUND_LANE,
+				);
+				#[allow(dead_code)]
+				fn source_conversion_rate_params() -> Option<(sp_core::storage::StorageKey, sp_runtime::FixedU128)> {
+					Some((
+						sp_core::storage::StorageKey(
+							millau_runtime::rialto_messages::RialtoToMillauConversionRate::key().to_vec(),
+						),
+						millau_runtime::rialto_messages::INITIAL_RIALTO_TO_MILLAU_CONVERSION_RATE,
+					))
+				}
+				#[allow(dead_code)]
+				fn target_conversion_rate_params() -> Option<(sp_core::storage::StorageKey, sp_runtime::FixedU128)> {
+					Some((
+						sp_core::storage::StorageKey(
+							rialto_runtime::millau_messages::MillauToRialtoConversionRate::key().to_vec(),
+						),
+						rialto_runtime::millau_messages::INITIAL_MILLAU_TO_RIALTO_CONVERSION_RATE,
+					))
+				}
 
 				$generic
 			}
@@ -89,10 +154,65 @@ macro_rules! select_full_bridge {
 				// Send-message / Estimate-fee
 				#[allow(unused_imports)]
 				use bp_millau::TO_MILLAU_ESTIMATE_MESSAGE_FEE_METHOD as ESTIMATE_MESSAGE_FEE_METHOD;
+				// Estimate-relayer-rewards
+				#[allow(dead_code)]
+				const ESTIMATE_RELAYER_REWARDS_METHOD: Option<&str> =
+					Some(bp_millau::TO_MILLAU_ESTIMATE_RELAYER_REWARDS_METHOD);
 
 				// Send-message
 				#[allow(unused_imports)]
 				use rialto_runtime::rialto_to_millau_account_ownership_digest as account_ownership_digest;
+				#[allow(dead_code)]
+				const DRY_RUN_MESSAGE_DISPATCH_METHOD: Option<&str> =
+					Some(bp_rialto::FROM_RIALTO_DRY_RUN_MESSAGE_DISPATCH_METHOD);
+
+				// Preflight
+				#[allow(dead_code)]
+				const SOURCE_GRANDPA_PALLET_NAME: &str = bp_rialto::WITH_MILLAU_GRANDPA_PALLET_NAME;
+				#[allow(dead_code)]
+				const TARGET_GRANDPA_PALLET_NAME: &str = bp_millau::WITH_RIALTO_GRANDPA_PALLET_NAME;
+				#[allow(dead_code)]
+				const SOURCE_LANE_REGISTRY_PALLET_NAME: Option<&str> = Some(bp_rialto::LANE_REGISTRY_PALLET_NAME);
+				#[allow(dead_code)]
+				const TARGET_LANE_REGISTRY_PALLET_NAME: Option<&str> = Some(bp_millau::LANE_REGISTRY_PALLET_NAME);
+				#[allow(unused_imports)]
+				use millau_runtime::WithRialtoMessagesInstance as TargetMessagesInstance;
+				#[allow(unused_imports)]
+				use rialto_runtime::WithMillauMessagesInstance as SourceMessagesInstance;
+
+				// Verify-chain-compatibility
+				#[allow(dead_code)]
+				const SOURCE_MESSAGES_PALLET_NAME: &str = bp_rialto::WITH_MILLAU_MESSAGES_PALLET_NAME;
+				#[allow(dead_code)]
+				const TARGET_MESSAGES_PALLET_NAME: &str = bp_millau::WITH_RIALTO_MESSAGES_PALLET_NAME;
+				#[allow(dead_code)]
+				const SOURCE_INBOUND_LANE_LIMITS: (bp_messages::MessageNonce, bp_messages::MessageNonce) = (
+					bp_rialto::MAX_UNREWARDED_RELAYER_ENTRIES_AT_INBOUND_LANE,
+					bp_rialto::MAX_UNCONFIRMED_MESSAGES_AT_INBOUND_LANE,
+				);
+				#[allow(dead_code)]
+				const TARGET_INBOUND_LANE_LIMITS: (bp_messages::MessageNonce, bp_messages::MessageNonce) = (
+					bp_millau::MAX_UNREWARDED_RELAYER_ENTRIES_AT_INBOUND_LANE,
+					bp_millau::MAX_UNCONFIRMED_MESSAGES_AT_INBOUND_LANE,
+				);
+				#[allow(dead_code)]
+				fn source_conversion_rate_params() -> Option<(sp_core::storage::StorageKey, sp_runtime::FixedU128)> {
+					Some((
+						sp_core::storage::StorageKey(
+							rialto_runtime::millau_messages::MillauToRialtoConversionRate::key().to_vec(),
+						),
+						rialto_runtime::millau_messages::INITIAL_MILLAU_TO_RIALTO_CONVERSION_RATE,
+					))
+				}
+				#[allow(dead_code)]
+				fn target_conversion_rate_params() -> Option<(sp_core::storage::StorageKey, sp_runtime::FixedU128)> {
+					Some((
+						sp_core::storage::StorageKey(
+							millau_runtime::rialto_messages::RialtoToMillauConversionRate::key().to_vec(),
+						),
+						millau_runtime::rialto_messages::INITIAL_RIALTO_TO_MILLAU_CONVERSION_RATE,
+					))
+				}
 
 				$generic
 			}
@@ -112,9 +232,52 @@ macro_rules! select_full_bridge {
 				// Send-message / Estimate-fee
 				#[allow(unused_imports)]
 				use bp_wococo::TO_WOCOCO_ESTIMATE_MESSAGE_FEE_METHOD as ESTIMATE_MESSAGE_FEE_METHOD;
+				// Estimate-relayer-rewards
+				#[allow(dead_code)]
+				const ESTIMATE_RELAYER_REWARDS_METHOD: Option<&str> = None;
 				// Send-message
 				#[allow(unused_imports)]
 				use relay_rococo_client::runtime::rococo_to_wococo_account_ownership_digest as account_ownership_digest;
+				#[allow(dead_code)]
+				const DRY_RUN_MESSAGE_DISPATCH_METHOD: Option<&str> = None;
+
+				// Preflight
+				#[allow(dead_code)]
+				const SOURCE_GRANDPA_PALLET_NAME: &str = bp_rococo::WITH_WOCOCO_GRANDPA_PALLET_NAME;
+				#[allow(dead_code)]
+				const TARGET_GRANDPA_PALLET_NAME: &str = bp_wococo::WITH_ROCOCO_GRANDPA_PALLET_NAME;
+				#[allow(dead_code)]
+				const SOURCE_LANE_REGISTRY_PALLET_NAME: Option<&str> = None;
+				#[allow(dead_code)]
+				const TARGET_LANE_REGISTRY_PALLET_NAME: Option<&str> = None;
+				#[allow(unused_imports)]
+				use relay_rococo_client::runtime::WithWococoMessagesInstance as SourceMessagesInstance;
+				#[allow(unused_imports)]
+				use relay_wococo_client::runtime::WithRococoMessagesInstance as TargetMessagesInstance;
+
+				// Verify-chain-compatibility
+				#[allow(dead_code)]
+				const SOURCE_MESSAGES_PALLET_NAME: &str = bp_rococo::WITH_WOCOCO_MESSAGES_PALLET_NAME;
+				#[allow(dead_code)]
+				const TARGET_MESSAGES_PALLET_NAME: &str = bp_wococo::WITH_ROCOCO_MESSAGES_PALLET_NAME;
+				#[allow(dead_code)]
+				const SOURCE_INBOUND_LANE_LIMITS: (bp_messages::MessageNonce, bp_messages::MessageNonce) = (
+					bp_rococo::MAX_UNREWARDED_RELAYER_ENTRIES_AT_INBOUND_LANE,
+					bp_rococo::MAX_UNCONFIRMED_MESSAGES_AT_INBOUND_LANE,
+				);
+				#[allow(dead_code)]
+				const TARGET_INBOUND_LANE_LIMITS: (bp_messages::MessageNonce, bp_messages::MessageNonce) = (
+					bp_wococo::MAX_UNREWARDED_RELAYER_ENTRIES_AT_INBOUND_LANE,
+					bp_wococo::MAX_UNCONFIRMED_MESSAGES_AT_INBOUND_LANE,
+				);
+				#[allow(dead_code)]
+				fn source_conversion_rate_params() -> Option<(sp_core::storage::StorageKey, sp_runtime::FixedU128)> {
+					None
+				}
+				#[allow(dead_code)]
+				fn target_conversion_rate_params() -> Option<(sp_core::storage::StorageKey, sp_runtime::FixedU128)> {
+					None
+				}
 
 				$generic
 			}
@@ -134,9 +297,52 @@ macro_rules! select_full_bridge {
 				// Send-message / Estimate-fee
 				#[allow(unused_imports)]
 				use bp_rococo::TO_ROCOCO_ESTIMATE_MESSAGE_FEE_METHOD as ESTIMATE_MESSAGE_FEE_METHOD;
+				// Estimate-relayer-rewards
+				#[allow(dead_code)]
+				const ESTIMATE_RELAYER_REWARDS_METHOD: Option<&str> = None;
 				// Send-message
 				#[allow(unused_imports)]
 				use relay_wococo_client::runtime::wococo_to_rococo_account_ownership_digest as account_ownership_digest;
+				#[allow(dead_code)]
+				const DRY_RUN_MESSAGE_DISPATCH_METHOD: Option<&str> = None;
+
+				// Preflight
+				#[allow(dead_code)]
+				const SOURCE_GRANDPA_PALLET_NAME: &str = bp_wococo::WITH_ROCOCO_GRANDPA_PALLET_NAME;
+				#[allow(dead_code)]
+				const TARGET_GRANDPA_PALLET_NAME: &str = bp_rococo::WITH_WOCOCO_GRANDPA_PALLET_NAME;
+				#[allow(dead_code)]
+				const SOURCE_LANE_REGISTRY_PALLET_NAME: Option<&str> = None;
+				#[allow(dead_code)]
+				const TARGET_LANE_REGISTRY_PALLET_NAME: Option<&str> = None;
+				#[allow(unused_imports)]
+				use relay_rococo_client::runtime::WithWococoMessagesInstance as TargetMessagesInstance;
+				#[allow(unused_imports)]
+				use relay_wococo_client::runtime::WithRococoMessagesInstance as SourceMessagesInstance;
+
+				// Verify-chain-compatibility
+				#[allow(dead_code)]
+				const SOURCE_MESSAGES_PALLET_NAME: &str = bp_wococo::WITH_ROCOCO_MESSAGES_PALLET_NAME;
+				#[allow(dead_code)]
+				const TARGET_MESSAGES_PALLET_NAME: &str = bp_rococo::WITH_WOCOCO_MESSAGES_PALLET_NAME;
+				#[allow(dead_code)]
+				const SOURCE_INBOUND_LANE_LIMITS: (bp_messages::MessageNonce, bp_messages::MessageNonce) = (
+					bp_wococo::MAX_UNREWARDED_RELAYER_ENTRIES_AT_INBOUND_LANE,
+					bp_wococo::MAX_UNCONFIRMED_MESSAGES_AT_INBOUND_LANE,
+				);
+				#[allow(dead_code)]
+				const TARGET_INBOUND_LANE_LIMITS: (bp_messages::MessageNonce, bp_messages::MessageNonce) = (
+					bp_rococo::MAX_UNREWARDED_RELAYER_ENTRIES_AT_INBOUND_LANE,
+					bp_rococo::MAX_UNCONFIRMED_MESSAGES_AT_INBOUND_LANE,
+				);
+				#[allow(dead_code)]
+				fn source_conversion_rate_params() -> Option<(sp_core::storage::StorageKey, sp_runtime::FixedU128)> {
+					None
+				}
+				#[allow(dead_code)]
+				fn target_conversion_rate_params() -> Option<(sp_core::storage::StorageKey, sp_runtime::FixedU128)> {
+					None
+				}
 
 				$generic
 			}
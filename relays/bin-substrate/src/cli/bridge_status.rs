@@ -0,0 +1,612 @@
+// Copyright 2019-2021 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Bridge status service.
+//!
+//! Wallets and dApp frontends need to know whether the bridge is working, how far finality of
+//! one chain is lagging behind at the other chain, how many messages are queued at the lane
+//! and how much the message delivery costs. The service polls both chains and serves the
+//! snapshot of this state as JSON, so that every frontend doesn't need to read (and decode)
+//! the runtime storage of both chains.
+
+use crate::cli::bridge::FullBridge;
+use crate::cli::encode_call::Call;
+use crate::cli::encode_message::MessagePayload;
+use crate::cli::estimate_fee::estimate_message_delivery_and_dispatch_fee;
+use crate::cli::preflight::{
+	best_finalized_bridged_header_hash, is_grandpa_pallet_halted, messages_pallet_operating_mode,
+};
+use crate::cli::{AccountId, CliChain, HexBytes, HexLaneId, SourceConnectionParams, TargetConnectionParams};
+use crate::select_full_bridge;
+use async_std::net::{TcpListener, TcpStream};
+use async_std::prelude::*;
+use bp_messages::{InboundLaneData, LaneId, MessageNonce, OperatingMode, OutboundLaneData};
+use codec::Encode;
+use frame_support::traits::Instance;
+use relay_substrate_client::{Chain, Client};
+use serde::Serialize;
+use sp_runtime::traits::{Header as HeaderT, UniqueSaturatedInto};
+use sp_runtime::DeserializeOwned;
+use std::{
+	sync::{Arc, Mutex},
+	time::{Duration, SystemTime, UNIX_EPOCH},
+};
+use structopt::StructOpt;
+use strum::VariantNames;
+
+/// Version of the served JSON format. It is increased whenever existing field is changed or
+/// removed.
+const STATUS_FORMAT_VERSION: u32 = 1;
+/// Maximal size of the HTTP request head that we're reading.
+const MAX_REQUEST_HEAD_SIZE: usize = 4096;
+/// Maximal time we're waiting for the HTTP request head.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Serve status of the bridge to wallets and dApp frontends.
+#[derive(StructOpt)]
+pub struct BridgeStatus {
+	/// A bridge instance to serve status of.
+	#[structopt(possible_values = FullBridge::VARIANTS, case_insensitive = true)]
+	bridge: FullBridge,
+	#[structopt(flatten)]
+	source: SourceConnectionParams,
+	#[structopt(flatten)]
+	target: TargetConnectionParams,
+	/// Hex-encoded identifiers of lanes to serve status of.
+	#[structopt(long, default_value = "00000000")]
+	lane: Vec<HexLaneId>,
+	/// Serve bridge status at given interface.
+	#[structopt(long, default_value = "127.0.0.1")]
+	status_host: String,
+	/// Serve bridge status at given port.
+	#[structopt(long, default_value = "9617")]
+	status_port: u16,
+	/// Interval (in seconds) between two consecutive reads of chains state.
+	#[structopt(long, default_value = "30")]
+	poll_interval: u64,
+}
+
+/// Snapshot of the bridge status, served by the service.
+#[derive(Debug, PartialEq, Serialize)]
+struct BridgeStatusSnapshot {
+	/// Version of the format.
+	version: u32,
+	/// Unix timestamp (in seconds) of the moment when the snapshot has been read.
+	updated_at: u64,
+	/// Status of the source chain.
+	source: ChainStatus,
+	/// Status of the target chain.
+	target: ChainStatus,
+	/// Status of lanes.
+	lanes: Vec<LaneStatus>,
+}
+
+/// Status of the chain and of the bridge pallets, deployed at this chain.
+#[derive(Debug, PartialEq, Serialize)]
+struct ChainStatus {
+	/// Name of the chain.
+	name: &'static str,
+	/// Number of the best finalized header of the chain.
+	best_finalized_header: u64,
+	/// Number of the best finalized header of the bridged chain, known to the GRANDPA pallet
+	/// at this chain. `None` if the pallet is not initialized.
+	best_finalized_bridged_header: Option<u64>,
+	/// Number of finalized bridged chain headers that are not yet known to the GRANDPA pallet
+	/// at this chain.
+	bridged_headers_lag: Option<u64>,
+	/// Whether the GRANDPA pallet at this chain is halted.
+	grandpa_pallet_halted: bool,
+	/// Operating mode of the messages pallet at this chain.
+	messages_pallet_operating_mode: OperatingMode,
+}
+
+/// Status of the lane.
+#[derive(Debug, PartialEq, Serialize)]
+struct LaneStatus {
+	/// Hex-encoded lane identifier.
+	lane: String,
+	/// Nonce of the latest message, sent by the source chain.
+	latest_generated_nonce: MessageNonce,
+	/// Nonce of the latest message, delivered to the target chain.
+	latest_delivered_nonce: MessageNonce,
+	/// Nonce of the latest message, which delivery has been confirmed at the source chain.
+	latest_confirmed_nonce: MessageNonce,
+	/// Number of messages that are not yet delivered to the target chain.
+	undelivered_messages: MessageNonce,
+	/// Number of delivered messages, which delivery is not yet confirmed at the source chain.
+	unconfirmed_messages: MessageNonce,
+	/// Estimated delivery and dispatch fee of the small message, in the source chain base
+	/// currency units. It is a string, because the fee may be larger than the maximal safe
+	/// JSON integer. `None` if the fee can't be estimated.
+	message_fee: Option<String>,
+}
+
+/// The latest serialized bridge status snapshot.
+type SharedSnapshot = Arc<Mutex<Option<String>>>;
+
+/// Everything required to read the bridge status.
+struct StatusParams<SC: Chain, TC: Chain, P> {
+	source_client: Client<SC>,
+	source_grandpa_pallet_name: &'static str,
+	target_client: Client<TC>,
+	target_grandpa_pallet_name: &'static str,
+	lanes: Vec<LaneId>,
+	estimate_message_fee_method: &'static str,
+	reference_message: Option<P>,
+}
+
+impl BridgeStatus {
+	/// Run the command.
+	pub async fn run(self) -> anyhow::Result<()> {
+		let address = format!("{}:{}", self.status_host, self.status_port);
+		let poll_interval = Duration::from_secs(self.poll_interval);
+		let lanes = self.lane.into_iter().map(Into::into).collect::<Vec<LaneId>>();
+
+		select_full_bridge!(self.bridge, {
+			let reference_message = match Source::encode_message(reference_message_payload::<Source>()) {
+				Ok(reference_message) => Some(reference_message),
+				Err(error) => {
+					log::warn!(
+						target: "bridge",
+						"Unable to encode {} -> {} message. Fees won't be estimated: {}",
+						Source::NAME,
+						Target::NAME,
+						error,
+					);
+					None
+				}
+			};
+
+			let params = StatusParams {
+				source_client: self.source.to_client::<Source>().await?,
+				source_grandpa_pallet_name: SOURCE_GRANDPA_PALLET_NAME,
+				target_client: self.target.to_client::<Target>().await?,
+				target_grandpa_pallet_name: TARGET_GRANDPA_PALLET_NAME,
+				lanes,
+				estimate_message_fee_method: ESTIMATE_MESSAGE_FEE_METHOD,
+				reference_message,
+			};
+
+			let snapshot = SharedSnapshot::default();
+			async_std::task::spawn(poll_status::<_, _, SourceMessagesInstance, TargetMessagesInstance, _>(
+				params,
+				poll_interval,
+				snapshot.clone(),
+			));
+			serve_status(address, snapshot).await
+		})
+	}
+}
+
+/// Payload of the small message, which fee is reported by the service.
+fn reference_message_payload<C: CliChain>() -> MessagePayload {
+	MessagePayload::Call {
+		call: Call::Remark {
+			remark_payload: Some(HexBytes(Vec::new())),
+			remark_size: None,
+		},
+		sender: AccountId::from_raw::<C>(sp_runtime::AccountId32::new([0u8; 32])),
+	}
+}
+
+/// Periodically read the bridge status and update the shared snapshot.
+///
+/// If the read fails, the previous snapshot is served. Clients may use the `updated_at` field
+/// to detect that the snapshot is outdated.
+async fn poll_status<SC, TC, SI, TI, P>(
+	params: StatusParams<SC, TC, P>,
+	poll_interval: Duration,
+	snapshot: SharedSnapshot,
+) where
+	SC: Chain,
+	SC::Header: DeserializeOwned,
+	TC: Chain,
+	TC::Header: DeserializeOwned,
+	SI: Instance,
+	TI: Instance,
+	P: Encode,
+{
+	loop {
+		match read_status::<SC, TC, SI, TI, P>(&params).await {
+			Ok(status) => match serde_json::to_string(&status) {
+				Ok(serialized_status) => {
+					*snapshot.lock().expect("snapshot mutex is never poisoned; qed") = Some(serialized_status);
+				}
+				Err(error) => log::error!(target: "bridge", "Failed to serialize bridge status: {:?}", error),
+			},
+			Err(error) => log::warn!(
+				target: "bridge",
+				"Failed to read {} -> {} bridge status: {:?}",
+				SC::NAME,
+				TC::NAME,
+				error,
+			),
+		}
+
+		async_std::task::sleep(poll_interval).await;
+	}
+}
+
+/// Read the bridge status from both chains.
+async fn read_status<SC, TC, SI, TI, P>(params: &StatusParams<SC, TC, P>) -> anyhow::Result<BridgeStatusSnapshot>
+where
+	SC: Chain,
+	SC::Header: DeserializeOwned,
+	TC: Chain,
+	TC::Header: DeserializeOwned,
+	SI: Instance,
+	TI: Instance,
+	P: Encode,
+{
+	let source_client = &params.source_client;
+	let target_client = &params.target_client;
+
+	let source_best_finalized_header = best_finalized_header_number(source_client).await?;
+	let target_best_finalized_header = best_finalized_header_number(target_client).await?;
+	let source_best_finalized_bridged_header =
+		best_finalized_bridged_header_number(source_client, params.source_grandpa_pallet_name, target_client).await?;
+	let target_best_finalized_bridged_header =
+		best_finalized_bridged_header_number(target_client, params.target_grandpa_pallet_name, source_client).await?;
+
+	let mut lanes = Vec::with_capacity(params.lanes.len());
+	for lane in &params.lanes {
+		let outbound_lane_data: OutboundLaneData = source_client
+			.storage_value(pallet_bridge_messages::storage_keys::outbound_lane_data_key::<SI>(lane))
+			.await?
+			.unwrap_or_default();
+		let inbound_lane_data: InboundLaneData<SC::AccountId> = target_client
+			.storage_value(pallet_bridge_messages::storage_keys::inbound_lane_data_key::<TI>(lane))
+			.await?
+			.unwrap_or_default();
+		let message_fee = match params.reference_message {
+			Some(ref reference_message) => estimate_message_fee(
+				source_client,
+				params.estimate_message_fee_method,
+				*lane,
+				reference_message,
+			)
+			.await
+			.map(|fee| fee.to_string()),
+			None => None,
+		};
+
+		lanes.push(lane_status(
+			*lane,
+			&outbound_lane_data,
+			inbound_lane_data.last_delivered_nonce(),
+			message_fee,
+		));
+	}
+
+	Ok(BridgeStatusSnapshot {
+		version: STATUS_FORMAT_VERSION,
+		updated_at: SystemTime::now()
+			.duration_since(UNIX_EPOCH)
+			.map(|since_epoch| since_epoch.as_secs())
+			.unwrap_or_default(),
+		source: ChainStatus {
+			name: SC::NAME,
+			best_finalized_header: source_best_finalized_header,
+			best_finalized_bridged_header: source_best_finalized_bridged_header,
+			bridged_headers_lag: headers_lag(target_best_finalized_header, source_best_finalized_bridged_header),
+			grandpa_pallet_halted: is_grandpa_pallet_halted(source_client, params.source_grandpa_pallet_name).await?,
+			messages_pallet_operating_mode: messages_pallet_operating_mode::<SC, SI>(source_client).await?,
+		},
+		target: ChainStatus {
+			name: TC::NAME,
+			best_finalized_header: target_best_finalized_header,
+			best_finalized_bridged_header: target_best_finalized_bridged_header,
+			bridged_headers_lag: headers_lag(source_best_finalized_header, target_best_finalized_bridged_header),
+			grandpa_pallet_halted: is_grandpa_pallet_halted(target_client, params.target_grandpa_pallet_name).await?,
+			messages_pallet_operating_mode: messages_pallet_operating_mode::<TC, TI>(target_client).await?,
+		},
+		lanes,
+	})
+}
+
+/// Read number of the best finalized header of the chain.
+async fn best_finalized_header_number<C: Chain>(client: &Client<C>) -> anyhow::Result<u64>
+where
+	C::Header: DeserializeOwned,
+{
+	let best_finalized_header_hash = client.best_finalized_header_hash().await?;
+	let best_finalized_header = client.header_by_hash(best_finalized_header_hash).await?;
+	Ok((*best_finalized_header.number()).unique_saturated_into())
+}
+
+/// Read number of the best finalized bridged chain header, known to the GRANDPA pallet.
+async fn best_finalized_bridged_header_number<C: Chain, BC: Chain>(
+	client: &Client<C>,
+	grandpa_pallet_name: &str,
+	bridged_client: &Client<BC>,
+) -> anyhow::Result<Option<u64>>
+where
+	BC::Header: DeserializeOwned,
+{
+	let best_finalized_bridged_header_hash =
+		match best_finalized_bridged_header_hash::<C, BC>(client, grandpa_pallet_name).await? {
+			Some(best_finalized_bridged_header_hash) => best_finalized_bridged_header_hash,
+			None => return Ok(None),
+		};
+	let best_finalized_bridged_header = bridged_client
+		.header_by_hash(best_finalized_bridged_header_hash)
+		.await?;
+	Ok(Some((*best_finalized_bridged_header.number()).unique_saturated_into()))
+}
+
+/// Estimate delivery and dispatch fee of the message. Returns `None` if the fee can't be estimated.
+async fn estimate_message_fee<C: Chain, P: Encode>(
+	client: &Client<C>,
+	estimate_message_fee_method: &str,
+	lane: LaneId,
+	payload: &P,
+) -> Option<u128> {
+	match estimate_message_delivery_and_dispatch_fee::<C::Balance, C, _>(
+		client,
+		estimate_message_fee_method,
+		lane,
+		payload,
+	)
+	.await
+	{
+		Ok(fee) => Some(fee.unique_saturated_into()),
+		Err(error) => {
+			log::debug!(
+				target: "bridge",
+				"Failed to estimate {} message fee at lane {}: {:?}",
+				C::NAME,
+				hex::encode(lane),
+				error,
+			);
+			None
+		}
+	}
+}
+
+/// Returns number of finalized headers that are not yet known to the bridged chain.
+fn headers_lag(best_finalized_header: u64, best_finalized_header_at_bridged_chain: Option<u64>) -> Option<u64> {
+	best_finalized_header_at_bridged_chain.map(|known_header| best_finalized_header.saturating_sub(known_header))
+}
+
+/// Compute lane status from lane states at both chains.
+fn lane_status(
+	lane: LaneId,
+	outbound_lane_data: &OutboundLaneData,
+	latest_delivered_nonce: MessageNonce,
+	message_fee: Option<String>,
+) -> LaneStatus {
+	let latest_generated_nonce = outbound_lane_data.latest_generated_nonce;
+	let latest_confirmed_nonce = outbound_lane_data.latest_received_nonce;
+	LaneStatus {
+		lane: hex::encode(lane),
+		latest_generated_nonce,
+		latest_delivered_nonce,
+		latest_confirmed_nonce,
+		undelivered_messages: latest_generated_nonce.saturating_sub(latest_delivered_nonce),
+		unconfirmed_messages: latest_delivered_nonce.saturating_sub(latest_confirmed_nonce),
+		message_fee,
+	}
+}
+
+/// Serve the latest bridge status snapshot over HTTP.
+async fn serve_status(address: String, snapshot: SharedSnapshot) -> anyhow::Result<()> {
+	let listener = TcpListener::bind(&address)
+		.await
+		.map_err(|e| anyhow::format_err!("Failed to bind bridge status endpoint to {}: {}", address, e))?;
+	log::info!(target: "bridge", "Serving bridge status at http://{}/status", address);
+
+	let mut incoming = listener.incoming();
+	while let Some(stream) = incoming.next().await {
+		let stream = match stream {
+			Ok(stream) => stream,
+			Err(error) => {
+				log::debug!(target: "bridge", "Failed to accept bridge status connection: {:?}", error);
+				continue;
+			}
+		};
+
+		let snapshot = snapshot.clone();
+		async_std::task::spawn(async move {
+			if let Err(error) = async_std::io::timeout(REQUEST_TIMEOUT, handle_request(stream, snapshot)).await {
+				log::debug!(target: "bridge", "Failed to serve bridge status request: {:?}", error);
+			}
+		});
+	}
+
+	Ok(())
+}
+
+/// Read HTTP request and write response to the stream.
+async fn handle_request(mut stream: TcpStream, snapshot: SharedSnapshot) -> std::io::Result<()> {
+	let mut request_head = [0u8; MAX_REQUEST_HEAD_SIZE];
+	let mut request_head_size = 0;
+	while request_head_size < request_head.len() {
+		let read = stream.read(&mut request_head[request_head_size..]).await?;
+		if read == 0 {
+			break;
+		}
+
+		request_head_size += read;
+		if request_head[..request_head_size]
+			.windows(4)
+			.any(|window| window == b"\r\n\r\n")
+		{
+			break;
+		}
+	}
+
+	let snapshot = snapshot.lock().expect("snapshot mutex is never poisoned; qed").clone();
+	let response = http_response(
+		&String::from_utf8_lossy(&request_head[..request_head_size]),
+		snapshot.as_deref(),
+	);
+	stream.write_all(response.as_bytes()).await
+}
+
+/// Returns HTTP response to the request with given head.
+fn http_response(request_head: &str, snapshot: Option<&str>) -> String {
+	let mut request_line = request_head.lines().next().unwrap_or_default().split_whitespace();
+	let method = request_line.next();
+	let path = request_line.next().and_then(|path| path.split('?').next());
+	match (method, path) {
+		(Some("GET"), Some("/")) | (Some("GET"), Some("/status")) => match snapshot {
+			Some(snapshot) => http_response_with_body("200 OK", "application/json", snapshot),
+			None => http_response_with_body(
+				"503 Service Unavailable",
+				"text/plain",
+				"Bridge status has not been read yet",
+			),
+		},
+		(Some("GET"), _) => http_response_with_body("404 Not Found", "text/plain", "Not found"),
+		_ => http_response_with_body("405 Method Not Allowed", "text/plain", "Method not allowed"),
+	}
+}
+
+/// Returns HTTP response with given status and body.
+fn http_response_with_body(status: &str, content_type: &str, body: &str) -> String {
+	format!(
+		"HTTP/1.1 {}\r\n\
+		Content-Type: {}\r\n\
+		Content-Length: {}\r\n\
+		Access-Control-Allow-Origin: *\r\n\
+		Cache-Control: no-cache\r\n\
+		Connection: close\r\n\
+		\r\n\
+		{}",
+		status,
+		content_type,
+		body.len(),
+		body,
+	)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn should_parse_cli_options() {
+		let bridge_status = BridgeStatus::from_iter(vec![
+			"bridge-status",
+			"millau-to-rialto",
+			"--source-port",
+			"1234",
+			"--target-port",
+			"5678",
+			"--lane",
+			"00000000",
+			"--lane",
+			"00000001",
+		]);
+
+		assert_eq!(bridge_status.bridge, FullBridge::MillauToRialto);
+		assert_eq!(
+			bridge_status.lane,
+			vec![
+				HexLaneId(LaneId::Legacy([0, 0, 0, 0])),
+				HexLaneId(LaneId::Legacy([0, 0, 0, 1]))
+			],
+		);
+		assert_eq!(bridge_status.status_port, 9617);
+		assert_eq!(bridge_status.poll_interval, 30);
+	}
+
+	#[test]
+	fn lane_status_is_computed_from_lane_states() {
+		let outbound_lane_data = OutboundLaneData {
+			oldest_unpruned_nonce: 5,
+			latest_received_nonce: 10,
+			latest_generated_nonce: 20,
+		};
+
+		assert_eq!(
+			lane_status(LaneId::Legacy(*b"test"), &outbound_lane_data, 15, Some("100".into())),
+			LaneStatus {
+				lane: "74657374".into(),
+				latest_generated_nonce: 20,
+				latest_delivered_nonce: 15,
+				latest_confirmed_nonce: 10,
+				undelivered_messages: 5,
+				unconfirmed_messages: 5,
+				message_fee: Some("100".into()),
+			},
+		);
+	}
+
+	#[test]
+	fn headers_lag_is_unknown_if_pallet_is_not_initialized() {
+		assert_eq!(headers_lag(100, Some(90)), Some(10));
+		assert_eq!(headers_lag(100, Some(100)), Some(0));
+		assert_eq!(headers_lag(100, None), None);
+	}
+
+	#[test]
+	fn snapshot_is_served_at_status_path() {
+		let response = http_response("GET /status HTTP/1.1\r\nHost: localhost\r\n\r\n", Some("{}"));
+		assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+		assert!(response.contains("Content-Length: 2\r\n"));
+		assert!(response.ends_with("\r\n\r\n{}"));
+
+		let response = http_response("GET /?pretty HTTP/1.1\r\n\r\n", Some("{}"));
+		assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+
+		let response = http_response("GET /status HTTP/1.1\r\n\r\n", None);
+		assert!(response.starts_with("HTTP/1.1 503 Service Unavailable\r\n"));
+	}
+
+	#[test]
+	fn unknown_requests_are_rejected() {
+		assert!(http_response("GET /metrics HTTP/1.1\r\n\r\n", Some("{}")).starts_with("HTTP/1.1 404 Not Found\r\n"));
+		assert!(http_response("POST /status HTTP/1.1\r\n\r\n", Some("{}"))
+			.starts_with("HTTP/1.1 405 Method Not Allowed\r\n"));
+		assert!(http_response("", Some("{}")).starts_with("HTTP/1.1 405 Method Not Allowed\r\n"));
+	}
+
+	#[test]
+	fn snapshot_is_serialized_to_stable_json() {
+		let snapshot = BridgeStatusSnapshot {
+			version: STATUS_FORMAT_VERSION,
+			updated_at: 42,
+			source: ChainStatus {
+				name: "Millau",
+				best_finalized_header: 100,
+				best_finalized_bridged_header: Some(50),
+				bridged_headers_lag: Some(2),
+				grandpa_pallet_halted: false,
+				messages_pallet_operating_mode: OperatingMode::Normal,
+			},
+			target: ChainStatus {
+				name: "Rialto",
+				best_finalized_header: 52,
+				best_finalized_bridged_header: None,
+				bridged_headers_lag: None,
+				grandpa_pallet_halted: true,
+				messages_pallet_operating_mode: OperatingMode::Halted,
+			},
+			lanes: vec![],
+		};
+
+		assert_eq!(
+			serde_json::to_string(&snapshot).unwrap(),
+			"{\"version\":1,\"updated_at\":42,\
+			\"source\":{\"name\":\"Millau\",\"best_finalized_header\":100,\"best_finalized_bridged_header\":50,\
+			\"bridged_headers_lag\":2,\"grandpa_pallet_halted\":false,\"messages_pallet_operating_mode\":\"Normal\"},\
+			\"target\":{\"name\":\"Rialto\",\"best_finalized_header\":52,\"best_finalized_bridged_header\":null,\
+			\"bridged_headers_lag\":null,\"grandpa_pallet_halted\":true,\"messages_pallet_operating_mode\":\"Halted\"},\
+			\"lanes\":[]}",
+		);
+	}
+}
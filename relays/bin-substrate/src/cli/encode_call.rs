@@ -15,9 +15,10 @@
 // along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
 
 use crate::cli::bridge::FullBridge;
-use crate::cli::{AccountId, Balance, CliChain, ExplicitOrMaximal, HexBytes, HexLaneId};
+use crate::cli::{AccountId, Balance, CliChain, ConversionRate, ExplicitOrMaximal, HexBytes, HexLaneId};
 use crate::select_full_bridge;
-use frame_support::weights::DispatchInfo;
+use bp_messages::MessageNonce;
+use frame_support::weights::{DispatchInfo, Weight};
 use relay_substrate_client::Chain;
 use structopt::StructOpt;
 use strum::VariantNames;
@@ -58,8 +59,15 @@ pub enum Call {
 		#[structopt(long)]
 		recipient: AccountId,
 		/// Amount of target tokens to send in target chain base currency units.
+		#[structopt(long, required_unless("all"), conflicts_with("all"))]
+		amount: Option<Balance>,
+		/// Never kill the sender account (use `transfer_keep_alive` or `transfer_all` with
+		/// `keep_alive` set to `true`).
 		#[structopt(long)]
-		amount: Balance,
+		keep_alive: bool,
+		/// Transfer all transferable balance of the sender account (use `transfer_all`).
+		#[structopt(long)]
+		all: bool,
 	},
 	/// A call to the specific Bridge Messages pallet to queue message to be sent over a bridge.
 	BridgeSendMessage {
@@ -78,6 +86,50 @@ pub enum Call {
 		#[structopt(long)]
 		fee: Balance,
 	},
+	/// A call to the Bridge Chunked Dispatch pallet to submit chunk of the large call.
+	SubmitCallChunk {
+		/// Hex-encoded hash of the whole encoded call.
+		#[structopt(long)]
+		call_hash: HexBytes,
+		/// Number of chunks the call has been split into.
+		#[structopt(long)]
+		chunks_count: u32,
+		/// Index of this chunk, starting from `0`.
+		#[structopt(long)]
+		chunk_index: u32,
+		/// Hex-encoded chunk of the call.
+		#[structopt(long)]
+		chunk: HexBytes,
+		/// Declared weight of the whole call.
+		#[structopt(long)]
+		call_weight: Weight,
+	},
+	/// A call to the specific Bridge Messages pallet to update the bridged-to-this chain tokens
+	/// conversion rate.
+	SetConversionRate {
+		/// An index of the bridge instance which represents the expected target chain.
+		#[structopt(skip = 255)]
+		bridge_instance_index: u8,
+		/// New conversion rate (e.g. `1.5`) - i.e. how many this chain tokens one bridged chain
+		/// token costs.
+		#[structopt(long)]
+		rate: ConversionRate,
+	},
+	/// A call to the specific Bridge Messages pallet to pay additional fee for the undelivered message.
+	BridgeIncreaseMessageFee {
+		/// An index of the bridge instance which represents the expected target chain.
+		#[structopt(skip = 255)]
+		bridge_instance_index: u8,
+		/// Hex-encoded lane id of the message. Defaults to `00000000`.
+		#[structopt(long, default_value = "00000000")]
+		lane: HexLaneId,
+		/// Nonce of the message.
+		#[structopt(long)]
+		nonce: MessageNonce,
+		/// Additional delivery and dispatch fee in base source-chain currency units.
+		#[structopt(long)]
+		additional_fee: Balance,
+	},
 }
 
 pub trait CliEncodeCall: Chain {
@@ -89,6 +141,9 @@ pub trait CliEncodeCall: Chain {
 
 	/// Get dispatch info for the call.
 	fn get_dispatch_info(call: &Self::Call) -> anyhow::Result<DispatchInfo>;
+
+	/// Wrap the call into the call of the chain governance pallet.
+	fn encode_governance_proposal(call: Self::Call) -> anyhow::Result<Self::Call>;
 }
 
 impl EncodeCall {
@@ -134,7 +189,10 @@ pub(crate) fn preprocess_call<Source: CliEncodeCall + CliChain, Target: CliEncod
 			if remark_payload.is_none() {
 				*remark_payload = Some(HexBytes(generate_remark_payload(
 					remark_size,
-					compute_maximal_message_arguments_size(Source::max_extrinsic_size(), Target::max_extrinsic_size()),
+					bridge_runtime_common::messages::source::maximal_message_arguments_size(
+						Source::max_extrinsic_size(),
+						Target::max_extrinsic_size(),
+					),
 				)));
 			}
 		}
@@ -144,12 +202,49 @@ pub(crate) fn preprocess_call<Source: CliEncodeCall + CliChain, Target: CliEncod
 		Call::BridgeSendMessage {
 			ref mut bridge_instance_index,
 			..
+		}
+		| Call::SetConversionRate {
+			ref mut bridge_instance_index,
+			..
+		}
+		| Call::BridgeIncreaseMessageFee {
+			ref mut bridge_instance_index,
+			..
 		} => {
 			*bridge_instance_index = bridge_instance;
 		}
+		Call::SubmitCallChunk { .. } => {}
 	};
 }
 
+/// Balances pallet call that is selected by options of the [`Call::Transfer`].
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) enum TransferCall {
+	/// Plain `transfer` of the given amount.
+	Transfer(Balance),
+	/// `transfer_keep_alive` of the given amount.
+	TransferKeepAlive(Balance),
+	/// `transfer_all` with given `keep_alive` argument.
+	TransferAll {
+		/// Whether the sender account must be kept alive.
+		keep_alive: bool,
+	},
+}
+
+/// Select balances pallet call for given [`Call::Transfer`] options.
+///
+/// This function is supposed to be used by all [`CliEncodeCall`] implementations, so that
+/// transfers are encoded consistently across bridges.
+pub(crate) fn transfer_call(amount: &Option<Balance>, keep_alive: bool, all: bool) -> anyhow::Result<TransferCall> {
+	match (amount, all) {
+		(None, true) => Ok(TransferCall::TransferAll { keep_alive }),
+		(Some(_), true) => anyhow::bail!("Transfer amount can't be specified when transferring all balance"),
+		(Some(amount), false) if keep_alive => Ok(TransferCall::TransferKeepAlive(*amount)),
+		(Some(amount), false) => Ok(TransferCall::Transfer(*amount)),
+		(None, false) => anyhow::bail!("Transfer amount must be specified"),
+	}
+}
+
 fn generate_remark_payload(remark_size: &Option<ExplicitOrMaximal<usize>>, maximal_allowed_size: u32) -> Vec<u8> {
 	match remark_size {
 		Some(ExplicitOrMaximal::Explicit(remark_size)) => vec![0; *remark_size],
@@ -166,26 +261,6 @@ fn generate_remark_payload(remark_size: &Option<ExplicitOrMaximal<usize>>, maxim
 	}
 }
 
-pub(crate) fn compute_maximal_message_arguments_size(
-	maximal_source_extrinsic_size: u32,
-	maximal_target_extrinsic_size: u32,
-) -> u32 {
-	// assume that both signed extensions and other arguments fit 1KB
-	let service_tx_bytes_on_source_chain = 1024;
-	let maximal_source_extrinsic_size = maximal_source_extrinsic_size - service_tx_bytes_on_source_chain;
-	let maximal_call_size =
-		bridge_runtime_common::messages::target::maximal_incoming_message_size(maximal_target_extrinsic_size);
-	let maximal_call_size = if maximal_call_size > maximal_source_extrinsic_size {
-		maximal_source_extrinsic_size
-	} else {
-		maximal_call_size
-	};
-
-	// bytes in Call encoding that are used to encode everything except arguments
-	let service_bytes = 1 + 1 + 4;
-	maximal_call_size - service_bytes
-}
-
 #[cfg(test)]
 mod tests {
 	use super::*;
@@ -213,6 +288,68 @@ mod tests {
 		);
 	}
 
+	#[test]
+	fn should_encode_transfer_keep_alive_call() {
+		// given
+		let mut encode_call = EncodeCall::from_iter(vec![
+			"encode-call",
+			"rialto-to-millau",
+			"transfer",
+			"--amount",
+			"12345",
+			"--keep-alive",
+			"--recipient",
+			"5sauUXUfPjmwxSgmb3tZ5d6yx24eZX4wWJ2JtVUBaQqFbvEU",
+		]);
+
+		// when
+		let hex = encode_call.encode().unwrap();
+
+		// then
+		assert_eq!(
+			format!("{:?}", hex),
+			"0x0403d43593c715fdd31c61141abd04a99fd6822c8558854ccde39a5684e7a56da27de5c0"
+		);
+	}
+
+	#[test]
+	fn should_encode_transfer_all_call() {
+		// given
+		let mut encode_call = EncodeCall::from_iter(vec![
+			"encode-call",
+			"rialto-to-millau",
+			"transfer",
+			"--all",
+			"--recipient",
+			"5sauUXUfPjmwxSgmb3tZ5d6yx24eZX4wWJ2JtVUBaQqFbvEU",
+		]);
+
+		// when
+		let hex = encode_call.encode().unwrap();
+
+		// then
+		assert_eq!(
+			format!("{:?}", hex),
+			"0x0404d43593c715fdd31c61141abd04a99fd6822c8558854ccde39a5684e7a56da27d00"
+		);
+	}
+
+	#[test]
+	fn should_reject_transfer_without_amount() {
+		// when
+		let err = EncodeCall::from_iter_safe(vec![
+			"encode-call",
+			"rialto-to-millau",
+			"transfer",
+			"--recipient",
+			"5sauUXUfPjmwxSgmb3tZ5d6yx24eZX4wWJ2JtVUBaQqFbvEU",
+		])
+		.unwrap_err();
+
+		// then
+		assert_eq!(err.kind, structopt::clap::ErrorKind::MissingRequiredArgument);
+	}
+
 	#[test]
 	fn should_encode_remark_with_default_payload() {
 		// given
@@ -256,6 +393,72 @@ mod tests {
 		assert_eq!(format!("{:?}", hex), "0x000130000000000000000000000000");
 	}
 
+	#[test]
+	fn should_encode_set_conversion_rate_call() {
+		// given
+		let mut encode_call = EncodeCall::from_iter(vec![
+			"encode-call",
+			"rialto-to-millau",
+			"set-conversion-rate",
+			"--rate",
+			"1.5",
+		]);
+
+		// when
+		let hex = encode_call.encode().unwrap();
+
+		// then
+		let call: rialto_runtime::Call = codec::Decode::decode(&mut &*hex.0).unwrap();
+		assert_eq!(
+			call,
+			rialto_runtime::Call::BridgeMillauMessages(rialto_runtime::MessagesCall::update_pallet_parameter(
+				rialto_runtime::millau_messages::RialtoToMillauMessagesParameter::MillauToRialtoConversionRate(
+					sp_runtime::FixedU128::from_float(1.5)
+				),
+			)),
+		);
+	}
+
+	#[test]
+	fn should_encode_increase_message_fee_call() {
+		// given
+		let mut encode_call = EncodeCall::from_iter(vec![
+			"encode-call",
+			"rialto-to-millau",
+			"bridge-increase-message-fee",
+			"--nonce",
+			"42",
+			"--additional-fee",
+			"1000",
+		]);
+
+		// when
+		let hex = encode_call.encode().unwrap();
+
+		// then
+		let call: rialto_runtime::Call = codec::Decode::decode(&mut &*hex.0).unwrap();
+		assert_eq!(
+			call,
+			rialto_runtime::Call::BridgeMillauMessages(rialto_runtime::MessagesCall::increase_message_fee(
+				bp_messages::LaneId::Legacy([0, 0, 0, 0]),
+				42,
+				1000,
+			)),
+		);
+	}
+
+	#[test]
+	fn should_reject_non_positive_conversion_rate() {
+		assert!(EncodeCall::from_iter_safe(vec![
+			"encode-call",
+			"rialto-to-millau",
+			"set-conversion-rate",
+			"--rate",
+			"0",
+		])
+		.is_err());
+	}
+
 	#[test]
 	fn should_disallow_both_payload_and_size() {
 		// when
@@ -276,4 +479,39 @@ mod tests {
 		let info = err.info.unwrap();
 		assert!(info.contains(&"remark-payload".to_string()) | info.contains(&"remark-size".to_string()))
 	}
+
+	#[test]
+	fn should_reject_call_to_unknown_bridge_instance() {
+		// given
+		let call = Call::SetConversionRate {
+			bridge_instance_index: 42,
+			rate: ConversionRate(sp_runtime::FixedU128::from_float(1.5)),
+		};
+
+		// when
+		let millau_result = relay_millau_client::Millau::encode_call(&call);
+		let rialto_result = relay_rialto_client::Rialto::encode_call(&call);
+
+		// then
+		assert!(millau_result.is_err());
+		assert!(rialto_result.is_err());
+	}
+
+	#[test]
+	fn should_use_bridge_instance_index_of_the_source_chain() {
+		// given
+		let mut call = Call::SetConversionRate {
+			bridge_instance_index: 255,
+			rate: ConversionRate(sp_runtime::FixedU128::from_float(1.5)),
+		};
+
+		// when
+		preprocess_call::<relay_millau_client::Millau, relay_rialto_client::Rialto>(
+			&mut call,
+			FullBridge::MillauToRialto.bridge_instance_index(),
+		);
+
+		// then
+		assert!(relay_millau_client::Millau::encode_call(&call).is_ok());
+	}
 }
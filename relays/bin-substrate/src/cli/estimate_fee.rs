@@ -15,14 +15,22 @@
 // along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
 
 use crate::cli::bridge::FullBridge;
+use crate::cli::preflight::ensure_messages_pallet_accepts_outbound_messages;
 use crate::cli::{Balance, CliChain, HexBytes, HexLaneId, SourceConnectionParams};
 use crate::select_full_bridge;
 use codec::{Decode, Encode};
-use relay_substrate_client::Chain;
+use relay_substrate_client::{Chain, Client};
+use serde::{Serialize, Serializer};
+use sp_core::storage::StorageKey;
+use sp_runtime::{traits::UniqueSaturatedInto, FixedPointNumber, FixedU128};
 use structopt::StructOpt;
 use strum::VariantNames;
 
 /// Estimate Delivery & Dispatch Fee command.
+///
+/// Prints the minimal fee that the source chain accepts for delivering and dispatching given
+/// message. The fee is printed in source chain plancks and tokens. If the bridge is using
+/// conversion rate of target chain tokens, the fee is also converted to target chain tokens.
 #[derive(StructOpt, Debug, PartialEq, Eq)]
 pub struct EstimateFee {
 	/// A bridge instance to encode call for.
@@ -33,6 +41,9 @@ pub struct EstimateFee {
 	/// Hex-encoded id of lane that will be delivering the message.
 	#[structopt(long, default_value = "00000000")]
 	lane: HexLaneId,
+	/// Print estimation as JSON object.
+	#[structopt(long)]
+	json: bool,
 	/// Payload to send over the bridge.
 	#[structopt(flatten)]
 	payload: crate::cli::encode_message::MessagePayload,
@@ -45,11 +56,14 @@ impl EstimateFee {
 			source,
 			bridge,
 			lane,
+			json,
 			payload,
 		} = self;
 
 		select_full_bridge!(bridge, {
 			let source_client = source.to_client::<Source>().await?;
+			ensure_messages_pallet_accepts_outbound_messages::<Source, SourceMessagesInstance>(&source_client).await?;
+
 			let lane = lane.into();
 			let payload = Source::encode_message(payload).map_err(|e| anyhow::format_err!("{:?}", e))?;
 
@@ -57,15 +71,131 @@ impl EstimateFee {
 				estimate_message_delivery_and_dispatch_fee(&source_client, ESTIMATE_MESSAGE_FEE_METHOD, lane, payload)
 					.await?;
 
-			log::info!(target: "bridge", "Fee: {:?}", Balance(fee as _));
-			println!("{}", fee);
+			let conversion_rate = read_conversion_rate(&source_client, source_conversion_rate_params()).await?;
+
+			let estimate = FeeEstimate::new::<Source, Target>(fee.unique_saturated_into(), conversion_rate);
+			log::info!(target: "bridge", "Fee: {:?}", Balance(estimate.fee));
+			if json {
+				println!("{}", serde_json::to_string_pretty(&estimate)?);
+			} else {
+				println!("{}", estimate);
+			}
 			Ok(())
 		})
 	}
 }
 
+/// Estimated message delivery and dispatch fee.
+#[derive(Debug, PartialEq, Serialize)]
+struct FeeEstimate {
+	/// Fee in source chain plancks.
+	#[serde(serialize_with = "serialize_as_string")]
+	fee: u128,
+	/// Fee in source chain tokens.
+	fee_in_tokens: String,
+	/// Symbol of the source chain token.
+	token_symbol: &'static str,
+	/// Target to source chain tokens conversion rate, used by the source chain runtime.
+	conversion_rate: Option<f64>,
+	/// Fee in target chain plancks, computed using the conversion rate.
+	#[serde(serialize_with = "serialize_option_as_string")]
+	target_fee: Option<u128>,
+	/// Fee in target chain tokens, computed using the conversion rate.
+	target_fee_in_tokens: Option<String>,
+	/// Symbol of the target chain token.
+	target_token_symbol: &'static str,
+}
+
+impl FeeEstimate {
+	/// Create fee estimate from the fee in source chain plancks.
+	fn new<Source: CliChain, Target: CliChain>(fee: u128, conversion_rate: Option<FixedU128>) -> Self {
+		let target_fee = conversion_rate
+			.and_then(|conversion_rate| conversion_rate.reciprocal())
+			.map(|inverse_conversion_rate| inverse_conversion_rate.saturating_mul_int(fee));
+		FeeEstimate {
+			fee,
+			fee_in_tokens: format_token_amount(fee, Source::CHAIN_INFO.token_decimals),
+			token_symbol: Source::CHAIN_INFO.token_symbol,
+			conversion_rate: conversion_rate.map(|conversion_rate| conversion_rate.to_float()),
+			target_fee,
+			target_fee_in_tokens: target_fee
+				.map(|target_fee| format_token_amount(target_fee, Target::CHAIN_INFO.token_decimals)),
+			target_token_symbol: Target::CHAIN_INFO.token_symbol,
+		}
+	}
+}
+
+impl std::fmt::Display for FeeEstimate {
+	fn fmt(&self, fmt: &mut std::fmt::Formatter) -> std::fmt::Result {
+		write!(
+			fmt,
+			"Fee: {} plancks ({} {})",
+			Balance(self.fee),
+			self.fee_in_tokens,
+			self.token_symbol
+		)?;
+		if let (Some(conversion_rate), Some(target_fee), Some(target_fee_in_tokens)) =
+			(self.conversion_rate, self.target_fee, &self.target_fee_in_tokens)
+		{
+			write!(
+				fmt,
+				"\nFee in {} tokens: {} plancks ({} {}), conversion rate: {}",
+				self.target_token_symbol,
+				Balance(target_fee),
+				target_fee_in_tokens,
+				self.target_token_symbol,
+				conversion_rate,
+			)?;
+		}
+		Ok(())
+	}
+}
+
+/// Serialize balance as a string, because it may exceed the maximal safe JSON integer.
+fn serialize_as_string<S: Serializer>(value: &u128, serializer: S) -> Result<S::Ok, S::Error> {
+	serializer.serialize_str(&value.to_string())
+}
+
+/// Serialize optional balance as a string, because it may exceed the maximal safe JSON integer.
+fn serialize_option_as_string<S: Serializer>(value: &Option<u128>, serializer: S) -> Result<S::Ok, S::Error> {
+	match value {
+		Some(value) => serialize_as_string(value, serializer),
+		None => serializer.serialize_none(),
+	}
+}
+
+/// Format amount of plancks as a decimal number of tokens.
+fn format_token_amount(amount: u128, decimals: u8) -> String {
+	let planck_per_token = 10u128.saturating_pow(decimals as u32);
+	let integer_part = amount / planck_per_token;
+	let fractional_part = amount % planck_per_token;
+	if fractional_part == 0 {
+		return integer_part.to_string();
+	}
+
+	let fractional_part = format!("{:0width$}", fractional_part, width = decimals as usize);
+	format!("{}.{}", integer_part, fractional_part.trim_end_matches('0'))
+}
+
+/// Read conversion rate from the chain storage.
+///
+/// Returns `None` if conversion rate is not used by the bridge. Returns initial value of the
+/// conversion rate if it has never been updated.
+pub(crate) async fn read_conversion_rate<C: Chain>(
+	client: &Client<C>,
+	conversion_rate_params: Option<(StorageKey, FixedU128)>,
+) -> anyhow::Result<Option<FixedU128>> {
+	let (conversion_rate_storage_key, initial_conversion_rate) = match conversion_rate_params {
+		Some(conversion_rate_params) => conversion_rate_params,
+		None => return Ok(None),
+	};
+
+	let conversion_rate: Option<FixedU128> = client.storage_value(conversion_rate_storage_key).await?;
+	Ok(Some(conversion_rate.unwrap_or(initial_conversion_rate)))
+}
+
 pub(crate) async fn estimate_message_delivery_and_dispatch_fee<Fee: Decode, C: Chain, P: Encode>(
-	client: &relay_substrate_client::Client<C>,
+	client: &Client<C>,
 	estimate_fee_method: &str,
 	lane: bp_messages::LaneId,
 	payload: P,
@@ -110,11 +240,13 @@ mod tests {
 			res,
 			EstimateFee {
 				bridge: FullBridge::RialtoToMillau,
-				lane: HexLaneId([0, 0, 0, 0]),
+				lane: HexLaneId(bp_messages::LaneId::Legacy([0, 0, 0, 0])),
+				json: false,
 				source: SourceConnectionParams {
 					source_host: "127.0.0.1".into(),
 					source_port: 1234,
 					source_secure: false,
+					source_max_daily_rpc_requests: None,
 				},
 				payload: crate::cli::encode_message::MessagePayload::Call {
 					sender: alice.parse().unwrap(),
@@ -126,4 +258,74 @@ mod tests {
 			}
 		);
 	}
+
+	#[test]
+	fn should_parse_json_flag() {
+		// when
+		let res = EstimateFee::from_iter(vec![
+			"estimate_fee",
+			"rialto-to-millau",
+			"--source-port",
+			"1234",
+			"--json",
+			"raw",
+			"dead",
+		]);
+
+		// then
+		assert!(res.json);
+	}
+
+	#[test]
+	fn token_amount_is_formatted() {
+		assert_eq!(format_token_amount(0, 9), "0");
+		assert_eq!(format_token_amount(1, 9), "0.000000001");
+		assert_eq!(format_token_amount(2_500_000_000, 9), "2.5");
+		assert_eq!(format_token_amount(3_000_000_000, 9), "3");
+		assert_eq!(format_token_amount(12_345, 0), "12345");
+		assert_eq!(
+			format_token_amount(u128::MAX, 12),
+			"340282366920938463463374607.431768211455"
+		);
+	}
+
+	#[test]
+	fn fee_is_converted_to_target_tokens() {
+		type Source = relay_millau_client::Millau;
+		type Target = relay_rialto_client::Rialto;
+
+		let estimate = FeeEstimate::new::<Source, Target>(2_500_000_000, Some(FixedU128::from_float(2.0)));
+		assert_eq!(estimate.fee_in_tokens, "2.5");
+		assert_eq!(estimate.token_symbol, "MLAU");
+		assert_eq!(estimate.target_fee, Some(1_250_000_000));
+		assert_eq!(estimate.target_fee_in_tokens, Some("1.25".into()));
+		assert_eq!(estimate.target_token_symbol, "RLT");
+
+		let estimate = FeeEstimate::new::<Source, Target>(2_500_000_000, Some(FixedU128::from_float(0.0)));
+		assert_eq!(estimate.target_fee, None);
+
+		let estimate = FeeEstimate::new::<Source, Target>(2_500_000_000, None);
+		assert_eq!(estimate.target_fee, None);
+		assert_eq!(estimate.to_string(), "Fee: 2,500,000,000 plancks (2.5 MLAU)");
+	}
+
+	#[test]
+	fn fee_estimate_is_serialized_to_json() {
+		type Source = relay_millau_client::Millau;
+		type Target = relay_rialto_client::Rialto;
+
+		let estimate = FeeEstimate::new::<Source, Target>(u128::MAX, None);
+		assert_eq!(
+			serde_json::to_value(&estimate).unwrap(),
+			serde_json::json!({
+				"fee": "340282366920938463463374607431768211455",
+				"fee_in_tokens": "340282366920938463463374607431.768211455",
+				"token_symbol": "MLAU",
+				"conversion_rate": null,
+				"target_fee": null,
+				"target_fee_in_tokens": null,
+				"target_token_symbol": "RLT",
+			})
+		);
+	}
 }
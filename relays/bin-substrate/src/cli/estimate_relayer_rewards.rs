@@ -0,0 +1,141 @@
+// Copyright 2019-2021 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+use crate::cli::bridge::FullBridge;
+use crate::cli::{Balance, HexBytes, HexLaneId, SourceConnectionParams};
+use crate::select_full_bridge;
+use bp_messages::{LaneId, MessageNonce, RelayerRewardsEstimation};
+use codec::{Decode, Encode};
+use relay_substrate_client::Chain;
+use sp_runtime::traits::UniqueSaturatedInto;
+use structopt::StructOpt;
+use strum::VariantNames;
+
+/// Estimate rewards for delivering next messages of the lane.
+///
+/// The estimation is based on fees that have been paid by submitters of undelivered messages.
+/// Costs of delivery and confirmation transactions are not included.
+#[derive(StructOpt, Debug, PartialEq, Eq)]
+pub struct EstimateRelayerRewards {
+	/// A bridge instance to estimate rewards at.
+	#[structopt(possible_values = FullBridge::VARIANTS, case_insensitive = true)]
+	bridge: FullBridge,
+	#[structopt(flatten)]
+	source: SourceConnectionParams,
+	/// Hex-encoded id of lane with undelivered messages. Defaults to `00000000`.
+	#[structopt(long, default_value = "00000000")]
+	lane: HexLaneId,
+	/// Maximal number of next undelivered messages to estimate rewards for.
+	#[structopt(long, default_value = "128")]
+	messages: MessageNonce,
+}
+
+impl EstimateRelayerRewards {
+	/// Run the command.
+	pub async fn run(self) -> anyhow::Result<()> {
+		let Self {
+			bridge,
+			source,
+			lane,
+			messages,
+		} = self;
+
+		select_full_bridge!(bridge, {
+			let estimate_relayer_rewards_method = ESTIMATE_RELAYER_REWARDS_METHOD.ok_or_else(|| {
+				anyhow::format_err!("{} runtime doesn't support relayer rewards estimation", Source::NAME)
+			})?;
+			let source_client = source.to_client::<Source>().await?;
+
+			let lane: LaneId = lane.into();
+			let estimation: RelayerRewardsEstimation<<Source as Chain>::Balance> =
+				estimate_relayer_rewards(&source_client, estimate_relayer_rewards_method, lane, messages).await?;
+
+			log::info!(
+				target: "bridge",
+				"Estimated rewards for delivering {} of {} undelivered messages at {} lane {}: {:?}",
+				estimation.messages,
+				estimation.backlog,
+				Source::NAME,
+				hex::encode(lane),
+				Balance(estimation.total_reward.unique_saturated_into()),
+			);
+			println!("Undelivered messages: {}", estimation.backlog);
+			println!("Estimated messages: {}", estimation.messages);
+			println!("Total dispatch weight: {}", estimation.total_dispatch_weight);
+			println!("Total size: {}", estimation.total_size);
+			println!("Total reward: {}", estimation.total_reward);
+			Ok(())
+		})
+	}
+}
+
+pub(crate) async fn estimate_relayer_rewards<Fee: Decode, C: Chain>(
+	client: &relay_substrate_client::Client<C>,
+	estimate_relayer_rewards_method: &str,
+	lane: LaneId,
+	max_messages: MessageNonce,
+) -> anyhow::Result<RelayerRewardsEstimation<Fee>> {
+	let encoded_response = client
+		.state_call(
+			estimate_relayer_rewards_method.into(),
+			(lane, max_messages).encode().into(),
+			None,
+		)
+		.await?;
+	Decode::decode(&mut &encoded_response.0[..]).map_err(|e| {
+		anyhow::format_err!(
+			"Unable to decode relayer rewards estimation from {:?}: {:?}",
+			HexBytes(encoded_response.to_vec()),
+			e,
+		)
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn should_parse_cli_options() {
+		// when
+		let res = EstimateRelayerRewards::from_iter(vec![
+			"estimate-relayer-rewards",
+			"millau-to-rialto",
+			"--source-port",
+			"1234",
+			"--lane",
+			"00000001",
+			"--messages",
+			"16",
+		]);
+
+		// then
+		assert_eq!(
+			res,
+			EstimateRelayerRewards {
+				bridge: FullBridge::MillauToRialto,
+				source: SourceConnectionParams {
+					source_host: "127.0.0.1".into(),
+					source_port: 1234,
+					source_secure: false,
+					source_max_daily_rpc_requests: None,
+				},
+				lane: HexLaneId(LaneId::Legacy([0, 0, 0, 1])),
+				messages: 16,
+			}
+		);
+	}
+}
@@ -0,0 +1,97 @@
+// Copyright 2019-2021 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+use crate::cli::bridge::FullBridge;
+use crate::cli::encode_call::{self, Call, CliEncodeCall};
+use crate::cli::HexBytes;
+use crate::select_full_bridge;
+use relay_substrate_client::Chain;
+use structopt::StructOpt;
+use strum::VariantNames;
+
+/// Encode source chain runtime call, wrapped into the governance proposal.
+#[derive(StructOpt, Debug)]
+pub struct GovernanceProposal {
+	/// A bridge instance to encode proposal for.
+	#[structopt(possible_values = FullBridge::VARIANTS, case_insensitive = true)]
+	bridge: FullBridge,
+	#[structopt(flatten)]
+	call: Call,
+}
+
+impl GovernanceProposal {
+	fn encode(&mut self) -> anyhow::Result<HexBytes> {
+		select_full_bridge!(self.bridge, {
+			encode_call::preprocess_call::<Source, Target>(&mut self.call, self.bridge.bridge_instance_index());
+			let call = Source::encode_call(&self.call)?;
+			let proposal = Source::encode_governance_proposal(call)?;
+
+			let encoded = HexBytes::encode(&proposal);
+
+			log::info!(target: "bridge", "Generated {} proposal: {:#?}", Source::NAME, proposal);
+			log::info!(target: "bridge", "Encoded {} proposal: {:?}", Source::NAME, encoded);
+
+			Ok(encoded)
+		})
+	}
+
+	/// Run the command.
+	pub async fn run(mut self) -> anyhow::Result<()> {
+		println!("{:?}", self.encode()?);
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn should_wrap_set_conversion_rate_call_into_sudo() {
+		// given
+		let mut governance_proposal = GovernanceProposal::from_iter(vec![
+			"governance-proposal",
+			"millau-to-rialto",
+			"set-conversion-rate",
+			"--rate",
+			"2",
+		]);
+
+		// when
+		let hex = governance_proposal.encode().unwrap();
+
+		// then
+		let call: millau_runtime::Call = codec::Decode::decode(&mut &*hex.0).unwrap();
+		assert_eq!(
+			call,
+			millau_runtime::Call::Sudo(millau_runtime::SudoCall::sudo(Box::new(
+				millau_runtime::Call::BridgeRialtoMessages(millau_runtime::MessagesCall::update_pallet_parameter(
+					millau_runtime::rialto_messages::MillauToRialtoMessagesParameter::RialtoToMillauConversionRate(
+						sp_runtime::FixedU128::from_float(2.0)
+					),
+				))
+			))),
+		);
+	}
+
+	#[test]
+	fn should_reject_proposals_at_chains_without_governance() {
+		let mut governance_proposal =
+			GovernanceProposal::from_iter(vec!["governance-proposal", "rococo-to-wococo", "remark"]);
+
+		assert!(governance_proposal.encode().is_err());
+	}
+}
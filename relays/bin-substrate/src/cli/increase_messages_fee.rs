@@ -0,0 +1,239 @@
+// Copyright 2019-2021 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+use crate::cli::bridge::FullBridge;
+use crate::cli::encode_call::{self, CliEncodeCall};
+use crate::cli::preflight::ensure_messages_pallet_not_halted;
+use crate::cli::{Balance, CliChain, HexLaneId, SourceConnectionParams, SourceSigningParams};
+use crate::select_full_bridge;
+use bp_messages::{LaneId, MessageData, MessageNonce, OutboundLaneData};
+use codec::Encode;
+use frame_support::traits::Instance;
+use relay_substrate_client::{Chain, ChainWithBalances, Client, TransactionSignScheme};
+use sp_core::{Bytes, Pair};
+use sp_runtime::traits::{Saturating, UniqueSaturatedInto};
+use structopt::StructOpt;
+use strum::VariantNames;
+
+/// Maximal number of source chain blocks we're waiting for the batch of transactions to be mined.
+const MAX_BLOCKS_TO_WAIT_FOR_BATCH: u32 = 16;
+
+/// Increase fees of undelivered messages, so that every message pays at least given fee.
+///
+/// Fees are re-read from the chain before every batch of transactions, and the command refuses to
+/// run while there are pending transactions of the signer account. So it may be safely restarted
+/// if it has failed in the middle - messages won't be paid for twice.
+#[derive(StructOpt)]
+pub struct IncreaseMessagesFee {
+	/// A bridge instance to increase messages fee at.
+	#[structopt(possible_values = FullBridge::VARIANTS, case_insensitive = true)]
+	bridge: FullBridge,
+	/// Hex-encoded id of lane with undelivered messages. Defaults to `00000000`.
+	#[structopt(long, default_value = "00000000")]
+	lane: HexLaneId,
+	/// Minimal fee of undelivered message in source chain base currency units. Fee of every
+	/// undelivered message that pays less is increased up to this value.
+	#[structopt(long)]
+	min_fee: Balance,
+	/// Maximal number of transactions that are submitted before waiting for them to be mined.
+	/// If not passed, it is the number of `increase_message_fee` calls that fit into a single block.
+	#[structopt(long)]
+	batch_size: Option<usize>,
+	/// Only print messages that pay less than the minimal fee, without submitting any transactions.
+	#[structopt(long)]
+	dry_run: bool,
+	#[structopt(flatten)]
+	source: SourceConnectionParams,
+	#[structopt(flatten)]
+	source_sign: SourceSigningParams,
+}
+
+impl IncreaseMessagesFee {
+	/// Run the command.
+	pub async fn run(self) -> anyhow::Result<()> {
+		select_full_bridge!(self.bridge, {
+			let source_client = self.source.to_client::<Source>().await?;
+			ensure_messages_pallet_not_halted::<Source, SourceMessagesInstance>(&source_client).await?;
+
+			let lane: LaneId = self.lane.clone().into();
+			let min_fee: <Source as Chain>::Balance = self.min_fee.0.unique_saturated_into();
+			let source_sign = self.source_sign.to_keypair::<Source>()?;
+			let source_account: <Source as Chain>::AccountId = source_sign.public().into();
+			let source_genesis_hash = *source_client.genesis_hash();
+			let encode_increase_fee_call = |nonce: MessageNonce, additional_fee: <Source as Chain>::Balance| {
+				Source::encode_call(&encode_call::Call::BridgeIncreaseMessageFee {
+					bridge_instance_index: self.bridge.bridge_instance_index(),
+					lane: self.lane.clone(),
+					nonce,
+					additional_fee: Balance(additional_fee.unique_saturated_into()),
+				})
+			};
+
+			let batch_size = match self.batch_size {
+				Some(batch_size) => batch_size,
+				None => {
+					let call_weight = Source::get_dispatch_info(&encode_increase_fee_call(0, min_fee)?)?.weight;
+					(Source::max_extrinsic_weight() / std::cmp::max(call_weight, 1)) as usize
+				}
+			};
+			let batch_size = std::cmp::max(batch_size, 1);
+
+			let mut increased_fees = 0;
+			let mut previous_batch = Vec::new();
+			loop {
+				if !self.dry_run {
+					ensure_no_pending_transactions(&source_client, source_account.clone()).await?;
+				}
+
+				let underpaid_messages =
+					read_underpaid_messages::<Source, SourceMessagesInstance>(&source_client, lane, min_fee).await?;
+				if self.dry_run {
+					for (nonce, fee) in &underpaid_messages {
+						println!("{}: {:?}", nonce, fee);
+					}
+					println!("{} messages pay less than {}", underpaid_messages.len(), self.min_fee);
+					return Ok(());
+				}
+				if underpaid_messages.is_empty() {
+					break;
+				}
+				if let Some((nonce, _)) = underpaid_messages
+					.iter()
+					.find(|(nonce, _)| previous_batch.contains(nonce))
+				{
+					return Err(anyhow::format_err!(
+						"Failed to increase fee of message {} at {} lane {}",
+						nonce,
+						Source::NAME,
+						hex::encode(lane),
+					));
+				}
+
+				let batch = underpaid_messages.into_iter().take(batch_size).collect::<Vec<_>>();
+				let batch_len = batch.len();
+				previous_batch = batch.iter().map(|(nonce, _)| *nonce).collect();
+				for (nonce, fee) in batch {
+					let increase_fee_call = encode_increase_fee_call(nonce, min_fee.saturating_sub(fee))?;
+					let source_sign = source_sign.clone();
+					source_client
+						.submit_signed_extrinsic(source_account.clone(), move |transaction_nonce| {
+							Bytes(
+								Source::sign_transaction(
+									source_genesis_hash,
+									&source_sign,
+									transaction_nonce,
+									increase_fee_call,
+								)
+								.encode(),
+							)
+						})
+						.await?;
+				}
+
+				log::info!(
+					target: "bridge",
+					"Submitted {} {} transactions, increasing fees of messages at lane {}",
+					batch_len,
+					Source::NAME,
+					hex::encode(lane),
+				);
+
+				wait_pending_transactions(&source_client, source_account.clone()).await?;
+				increased_fees += batch_len;
+			}
+
+			log::info!(
+				target: "bridge",
+				"Increased fees of {} messages at {} lane {}",
+				increased_fees,
+				Source::NAME,
+				hex::encode(lane),
+			);
+
+			Ok(())
+		})
+	}
+}
+
+/// Read nonces and fees of all undelivered messages that pay less than `min_fee`.
+async fn read_underpaid_messages<C: Chain, I: Instance>(
+	client: &Client<C>,
+	lane: LaneId,
+	min_fee: C::Balance,
+) -> anyhow::Result<Vec<(MessageNonce, C::Balance)>> {
+	let lane_data: OutboundLaneData = client
+		.storage_value(pallet_bridge_messages::storage_keys::outbound_lane_data_key::<I>(&lane))
+		.await?
+		.unwrap_or_default();
+
+	let mut underpaid_messages = Vec::new();
+	for nonce in lane_data.latest_received_nonce + 1..=lane_data.latest_generated_nonce {
+		let message_data: Option<MessageData<C::Balance>> = client
+			.storage_value(pallet_bridge_messages::storage_keys::message_key::<I>(&lane, nonce))
+			.await?;
+		match message_data {
+			Some(message_data) if message_data.fee < min_fee => underpaid_messages.push((nonce, message_data.fee)),
+			_ => (),
+		}
+	}
+
+	Ok(underpaid_messages)
+}
+
+/// Ensure that there are no transactions of the account in the transaction pool.
+///
+/// Fees of messages are read from the runtime storage, so if there are pending transactions that
+/// are increasing these fees, we could pay for the same messages twice.
+async fn ensure_no_pending_transactions<C: ChainWithBalances>(
+	client: &Client<C>,
+	account: C::AccountId,
+) -> anyhow::Result<()> {
+	let account_nonce = client.account_nonce(account.clone()).await?;
+	let next_account_index = client.next_account_index(account).await?;
+	if account_nonce != next_account_index {
+		return Err(anyhow::format_err!(
+			"There are pending {} transactions of the signer account (nonce: {}, next index: {}). \
+			Wait until they are mined and retry",
+			C::NAME,
+			account_nonce,
+			next_account_index,
+		));
+	}
+
+	Ok(())
+}
+
+/// Wait until all pending transactions of the account are mined.
+async fn wait_pending_transactions<C: ChainWithBalances>(
+	client: &Client<C>,
+	account: C::AccountId,
+) -> anyhow::Result<()> {
+	for _ in 0..MAX_BLOCKS_TO_WAIT_FOR_BATCH {
+		async_std::task::sleep(C::AVERAGE_BLOCK_INTERVAL).await;
+
+		let account_nonce = client.account_nonce(account.clone()).await?;
+		let next_account_index = client.next_account_index(account.clone()).await?;
+		if account_nonce == next_account_index {
+			return Ok(());
+		}
+	}
+
+	Err(anyhow::format_err!(
+		"{} transactions have not been mined in {} blocks. Restart the command once they're mined",
+		C::NAME,
+		MAX_BLOCKS_TO_WAIT_FOR_BATCH,
+	))
+}
@@ -14,6 +14,7 @@
 // You should have received a copy of the GNU General Public License
 // along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
 
+use crate::cli::preflight::ensure_grandpa_pallet_not_initialized;
 use crate::cli::{SourceConnectionParams, TargetConnectionParams, TargetSigningParams};
 use bp_header_chain::InitializationData;
 use bp_runtime::Chain as ChainBase;
@@ -54,6 +55,7 @@ macro_rules! select_bridge {
 			InitBridgeName::MillauToRialto => {
 				type Source = relay_millau_client::Millau;
 				type Target = relay_rialto_client::Rialto;
+				const TARGET_GRANDPA_PALLET_NAME: &str = bp_rialto::WITH_MILLAU_GRANDPA_PALLET_NAME;
 
 				fn encode_init_bridge(
 					init_data: InitializationData<<Source as ChainBase>::Header>,
@@ -69,6 +71,7 @@ macro_rules! select_bridge {
 			InitBridgeName::RialtoToMillau => {
 				type Source = relay_rialto_client::Rialto;
 				type Target = relay_millau_client::Millau;
+				const TARGET_GRANDPA_PALLET_NAME: &str = bp_millau::WITH_RIALTO_GRANDPA_PALLET_NAME;
 
 				fn encode_init_bridge(
 					init_data: InitializationData<<Source as ChainBase>::Header>,
@@ -85,6 +88,7 @@ macro_rules! select_bridge {
 			InitBridgeName::WestendToMillau => {
 				type Source = relay_westend_client::Westend;
 				type Target = relay_millau_client::Millau;
+				const TARGET_GRANDPA_PALLET_NAME: &str = bp_millau::WITH_WESTEND_GRANDPA_PALLET_NAME;
 
 				fn encode_init_bridge(
 					init_data: InitializationData<<Source as ChainBase>::Header>,
@@ -104,6 +108,7 @@ macro_rules! select_bridge {
 			InitBridgeName::RococoToWococo => {
 				type Source = relay_rococo_client::Rococo;
 				type Target = relay_wococo_client::Wococo;
+				const TARGET_GRANDPA_PALLET_NAME: &str = bp_wococo::WITH_ROCOCO_GRANDPA_PALLET_NAME;
 
 				fn encode_init_bridge(
 					init_data: InitializationData<<Source as ChainBase>::Header>,
@@ -118,6 +123,7 @@ macro_rules! select_bridge {
 			InitBridgeName::WococoToRococo => {
 				type Source = relay_wococo_client::Wococo;
 				type Target = relay_rococo_client::Rococo;
+				const TARGET_GRANDPA_PALLET_NAME: &str = bp_rococo::WITH_WOCOCO_GRANDPA_PALLET_NAME;
 
 				fn encode_init_bridge(
 					init_data: InitializationData<<Source as ChainBase>::Header>,
@@ -139,6 +145,8 @@ impl InitBridge {
 		select_bridge!(self.bridge, {
 			let source_client = self.source.to_client::<Source>().await?;
 			let target_client = self.target.to_client::<Target>().await?;
+			ensure_grandpa_pallet_not_initialized(&target_client, TARGET_GRANDPA_PALLET_NAME).await?;
+
 			let target_sign = self.target_sign.to_keypair::<Target>()?;
 
 			crate::headers_initialize::initialize(
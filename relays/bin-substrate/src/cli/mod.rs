@@ -30,15 +30,29 @@ pub(crate) mod encode_message;
 pub(crate) mod estimate_fee;
 pub(crate) mod send_message;
 
+mod bridge_status;
 mod derive_account;
+mod estimate_relayer_rewards;
+mod governance_proposal;
+mod increase_messages_fee;
 mod init_bridge;
+mod open_lane;
+mod preflight;
 mod relay_headers;
 mod relay_headers_and_messages;
 mod relay_messages;
+mod sign_payload;
+mod simulate_bridge;
+mod verify_chain_compatibility;
 
 /// Parse relay CLI args.
 pub fn parse_args() -> Command {
-	Command::from_args()
+	match relay_headers_and_messages::expand_config_file_args(std::env::args_os().collect()) {
+		Ok(args) => Command::from_iter(args),
+		Err(e) => {
+			structopt::clap::Error::with_description(&e.to_string(), structopt::clap::ErrorKind::InvalidValue).exit()
+		}
+	}
 }
 
 /// Substrate-to-Substrate bridge utilities.
@@ -72,6 +86,11 @@ pub enum Command {
 	/// The message is being sent to the source chain, delivered to the target chain and dispatched
 	/// there.
 	SendMessage(send_message::SendMessage),
+	/// Sign message payload with the target account key.
+	///
+	/// The signed `MessagePayload` authorizes dispatch of the call by the target account. It can be
+	/// produced at the offline machine and then sent with `send-message --presigned-payload`.
+	SignPayload(sign_payload::SignPayload),
 	/// Generate SCALE-encoded `Call` for choosen network.
 	///
 	/// The call can be used either as message payload or can be wrapped into a transaction
@@ -86,6 +105,37 @@ pub enum Command {
 	EstimateFee(estimate_fee::EstimateFee),
 	/// Given a source chain `AccountId`, derive the corresponding `AccountId` for the target chain.
 	DeriveAccount(derive_account::DeriveAccount),
+	/// Generate SCALE-encoded governance proposal for choosen network.
+	///
+	/// The call (e.g. `set-conversion-rate`) is wrapped into the call of the chain governance
+	/// pallet, so it may be submitted without any additional processing.
+	GovernanceProposal(governance_proposal::GovernanceProposal),
+	/// Derive lane identifier for the application and open (register) this lane on both chains.
+	///
+	/// The lane is only opened if it isn't yet registered at any chain.
+	OpenLane(open_lane::OpenLane),
+	/// Increase fees of undelivered messages at the lane.
+	///
+	/// Fee of every undelivered message that pays less than given minimal fee is increased up to
+	/// this fee. It is useful when messages are stuck at the lane after the conversion rate change.
+	IncreaseMessagesFee(increase_messages_fee::IncreaseMessagesFee),
+	/// Estimate rewards for delivering next messages of the lane.
+	///
+	/// Helps relayer operators to decide whether serving the lane is economically viable.
+	EstimateRelayerRewards(estimate_relayer_rewards::EstimateRelayerRewards),
+	/// Simulate message lane throughput, backlog and relayer costs without any chains.
+	///
+	/// Helps to choose lane limits and fee schedules before the bridge is deployed.
+	SimulateBridge(simulate_bridge::SimulateBridge),
+	/// Verify that both chains are compatible with the relay and with each other.
+	///
+	/// Prints a report with results of all checks and fails if any of them has failed.
+	VerifyChainCompatibility(verify_chain_compatibility::VerifyChainCompatibility),
+	/// Serve status of the bridge as JSON.
+	///
+	/// Polls both chains and serves best finalized headers, lane backlogs, halted flags and
+	/// estimated message fees to wallets and dApp frontends.
+	BridgeStatus(bridge_status::BridgeStatus),
 }
 
 impl Command {
@@ -94,7 +144,11 @@ impl Command {
 		use relay_utils::initialize::{initialize_logger, initialize_relay};
 
 		match self {
-			Self::RelayHeaders(_) | Self::RelayMessages(_) | Self::RelayHeadersAndMessages(_) | Self::InitBridge(_) => {
+			Self::RelayHeaders(_)
+			| Self::RelayMessages(_)
+			| Self::RelayHeadersAndMessages(_)
+			| Self::InitBridge(_)
+			| Self::BridgeStatus(_) => {
 				initialize_relay();
 			}
 			_ => {
@@ -112,10 +166,18 @@ impl Command {
 			Self::RelayHeadersAndMessages(arg) => arg.run().await?,
 			Self::InitBridge(arg) => arg.run().await?,
 			Self::SendMessage(arg) => arg.run().await?,
+			Self::SignPayload(arg) => arg.run().await?,
 			Self::EncodeCall(arg) => arg.run().await?,
 			Self::EncodeMessage(arg) => arg.run().await?,
 			Self::EstimateFee(arg) => arg.run().await?,
 			Self::DeriveAccount(arg) => arg.run().await?,
+			Self::GovernanceProposal(arg) => arg.run().await?,
+			Self::OpenLane(arg) => arg.run().await?,
+			Self::IncreaseMessagesFee(arg) => arg.run().await?,
+			Self::EstimateRelayerRewards(arg) => arg.run().await?,
+			Self::SimulateBridge(arg) => arg.run().await?,
+			Self::VerifyChainCompatibility(arg) => arg.run().await?,
+			Self::BridgeStatus(arg) => arg.run().await?,
 		}
 		Ok(())
 	}
@@ -159,6 +221,25 @@ impl Balance {
 	}
 }
 
+/// Conversion rate between two chain tokens, parsed from a decimal number.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ConversionRate(pub sp_runtime::FixedU128);
+
+impl std::str::FromStr for ConversionRate {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let rate: f64 = s
+			.parse()
+			.map_err(|e| format!("Invalid conversion rate {}: {:?}", s, e))?;
+		if !rate.is_finite() || rate <= 0.0 {
+			return Err(format!("Conversion rate must be a positive number. Got: {}", s));
+		}
+
+		Ok(Self(sp_runtime::FixedU128::from_float(rate)))
+	}
+}
+
 /// Generic account id with custom parser.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct AccountId {
@@ -227,6 +308,8 @@ impl AccountId {
 pub trait CliChain: relay_substrate_client::Chain {
 	/// Chain's current version of the runtime.
 	const RUNTIME_VERSION: sp_version::RuntimeVersion;
+	/// Chain information from the registry of known chains.
+	const CHAIN_INFO: bp_chain_registry::ChainInfo;
 
 	/// Crypto keypair type used to send messages.
 	///
@@ -249,6 +332,9 @@ pub trait CliChain: relay_substrate_client::Chain {
 }
 
 /// Lane id.
+///
+/// Legacy lane id is parsed from 4 hex-encoded bytes and hashed lane id is parsed from 32
+/// hex-encoded bytes.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct HexLaneId(pub LaneId);
 
@@ -262,9 +348,16 @@ impl std::str::FromStr for HexLaneId {
 	type Err = hex::FromHexError;
 
 	fn from_str(s: &str) -> Result<Self, Self::Err> {
-		let mut lane_id = LaneId::default();
-		hex::decode_to_slice(s, &mut lane_id)?;
-		Ok(HexLaneId(lane_id))
+		let bytes = hex::decode(s)?;
+		match bytes.len() {
+			4 => {
+				let mut lane_id = [0u8; 4];
+				lane_id.copy_from_slice(&bytes);
+				Ok(HexLaneId(LaneId::Legacy(lane_id)))
+			}
+			32 => Ok(HexLaneId(LaneId::Hashed(sp_core::H256::from_slice(&bytes)))),
+			_ => Err(hex::FromHexError::InvalidStringLength),
+		}
 	}
 }
 
@@ -327,6 +420,27 @@ impl From<PrometheusParams> for relay_utils::metrics::MetricsParams {
 	}
 }
 
+/// Alert sink parameters.
+#[derive(StructOpt)]
+pub struct AlertParams {
+	/// URL of the HTTP webhook that is notified about critical conditions, detected by the relay.
+	#[structopt(long)]
+	pub alert_webhook: Option<String>,
+	/// Format of the alert webhook payload: generic, slack, discord or alertmanager.
+	#[structopt(long, default_value = "generic")]
+	pub alert_webhook_format: relay_utils::alerts::AlertFormat,
+}
+
+impl AlertParams {
+	/// Install alert sink, if webhook is configured.
+	pub fn install(self) -> anyhow::Result<()> {
+		match self.alert_webhook {
+			Some(url) => relay_utils::alerts::AlertSink::new(url, self.alert_webhook_format).install(),
+			None => Ok(()),
+		}
+	}
+}
+
 /// Either explicit or maximal allowed value.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum ExplicitOrMaximal<V> {
@@ -371,23 +485,26 @@ macro_rules! declare_chain_options {
 				#[doc = "Use secure websocket connection."]
 				#[structopt(long)]
 				pub [<$chain_prefix _secure>]: bool,
+				#[doc = "Soft limit of " $chain " node RPC requests per day. When the limit is nearly reached, the relay switches to the reduced-polling mode."]
+				#[structopt(long)]
+				pub [<$chain_prefix _max_daily_rpc_requests>]: Option<u64>,
 			}
 
 			#[doc = $chain " signing params."]
 			#[derive(StructOpt, Debug, PartialEq, Eq, Clone)]
 			pub struct [<$chain SigningParams>] {
 				#[doc = "The SURI of secret key to use when transactions are submitted to the " $chain " node."]
-				#[structopt(long)]
+				#[structopt(long, env, hide_env_values = true)]
 				pub [<$chain_prefix _signer>]: Option<String>,
 				#[doc = "The password for the SURI of secret key to use when transactions are submitted to the " $chain " node."]
-				#[structopt(long)]
+				#[structopt(long, env, hide_env_values = true)]
 				pub [<$chain_prefix _signer_password>]: Option<String>,
 
-				#[doc = "Path to the file, that contains SURI of secret key to use when transactions are submitted to the " $chain " node. Can be overridden with " $chain_prefix "_signer option."]
-				#[structopt(long)]
+				#[doc = "Path to the file, that contains SURI of secret key to use when transactions are submitted to the " $chain " node. The file may also be the Substrate keystore file with JSON-encoded SURI. Can be overridden with " $chain_prefix "_signer option."]
+				#[structopt(long, env)]
 				pub [<$chain_prefix _signer_file>]: Option<std::path::PathBuf>,
 				#[doc = "Path to the file, that password for the SURI of secret key to use when transactions are submitted to the " $chain " node. Can be overridden with " $chain_prefix "_signer_password option."]
-				#[structopt(long)]
+				#[structopt(long, env)]
 				pub [<$chain_prefix _signer_password_file>]: Option<std::path::PathBuf>,
 			}
 
@@ -396,12 +513,7 @@ macro_rules! declare_chain_options {
 				pub fn to_keypair<Chain: CliChain>(&self) -> anyhow::Result<Chain::KeyPair> {
 					let suri = match (self.[<$chain_prefix _signer>].as_ref(), self.[<$chain_prefix _signer_file>].as_ref()) {
 						(Some(suri), _) => suri.to_owned(),
-						(None, Some(suri_file)) => std::fs::read_to_string(suri_file)
-							.map_err(|err| anyhow::format_err!(
-								"Failed to read SURI from file {:?}: {}",
-								suri_file,
-								err,
-							))?,
+						(None, Some(suri_file)) => crate::cli::read_suri_file(suri_file)?,
 						(None, None) => return Err(anyhow::format_err!(
 							"One of options must be specified: '{}' or '{}'",
 							stringify!([<$chain_prefix _signer>]),
@@ -414,13 +526,7 @@ macro_rules! declare_chain_options {
 						self.[<$chain_prefix _signer_password_file>].as_ref(),
 					) {
 						(Some(suri_password), _) => Some(suri_password.to_owned()),
-						(None, Some(suri_password_file)) => std::fs::read_to_string(suri_password_file)
-							.map(Some)
-							.map_err(|err| anyhow::format_err!(
-								"Failed to read SURI password from file {:?}: {}",
-								suri_password_file,
-								err,
-							))?,
+						(None, Some(suri_password_file)) => Some(crate::cli::read_suri_password_file(suri_password_file)?),
 						_ => None,
 					};
 
@@ -442,6 +548,7 @@ macro_rules! declare_chain_options {
 						host: self.[<$chain_prefix _host>].clone(),
 						port: self.[<$chain_prefix _port>],
 						secure: self.[<$chain_prefix _secure>],
+						max_daily_rpc_requests: self.[<$chain_prefix _max_daily_rpc_requests>],
 					})
 					.await
 					)
@@ -451,6 +558,34 @@ macro_rules! declare_chain_options {
 	};
 }
 
+/// Read SURI from the signer file.
+///
+/// The file may either contain the SURI itself (e.g. secret phrase or hex-encoded raw seed), or
+/// be the Substrate keystore file that contains JSON-encoded SURI.
+pub(crate) fn read_suri_file(suri_file: &std::path::Path) -> anyhow::Result<String> {
+	let suri = std::fs::read_to_string(suri_file)
+		.map_err(|err| anyhow::format_err!("Failed to read SURI from file {:?}: {}", suri_file, err))?;
+	let suri = suri.trim();
+	if suri.starts_with('"') {
+		return serde_json::from_str(suri)
+			.map_err(|err| anyhow::format_err!("Failed to decode SURI from keystore file {:?}: {}", suri_file, err));
+	}
+
+	Ok(suri.into())
+}
+
+/// Read SURI password from the file, ignoring trailing line break.
+pub(crate) fn read_suri_password_file(suri_password_file: &std::path::Path) -> anyhow::Result<String> {
+	let suri_password = std::fs::read_to_string(suri_password_file).map_err(|err| {
+		anyhow::format_err!(
+			"Failed to read SURI password from file {:?}: {}",
+			suri_password_file,
+			err
+		)
+	})?;
+	Ok(suri_password.trim_end_matches(&['\r', '\n'][..]).into())
+}
+
 declare_chain_options!(Source, source);
 declare_chain_options!(Target, target);
 
@@ -574,4 +709,72 @@ mod tests {
 			Ok(alice.public()),
 		);
 	}
+
+	#[test]
+	fn reads_suri_from_keystore_file() {
+		const PHRASE: &str = "bottom drive obey lake curtain smoke basket hold race lonely fit walk";
+		const PASSWORD: &str = "password";
+
+		let expected = sp_core::sr25519::Pair::from_string(PHRASE, Some(PASSWORD)).unwrap();
+
+		let temp_dir = tempdir::TempDir::new("reads_suri_from_keystore_file").unwrap();
+		let keystore_file_path = temp_dir
+			.path()
+			.join("73723235d43593c715fdd31c61141abd04a99fd6822c8558854ccde39a5684e7a56da27d");
+		let password_file_path = temp_dir.path().join("password");
+		std::fs::write(&keystore_file_path, format!("\"{}\"", PHRASE)).unwrap();
+		std::fs::write(&password_file_path, format!("{}\n", PASSWORD)).unwrap();
+
+		// when SURI is read from the keystore file and password has trailing line break
+		assert_eq!(
+			TargetSigningParams {
+				target_signer: None,
+				target_signer_password: None,
+
+				target_signer_file: Some(keystore_file_path.clone()),
+				target_signer_password_file: Some(password_file_path),
+			}
+			.to_keypair::<relay_rialto_client::Rialto>()
+			.map(|p| p.public())
+			.map_err(drop),
+			Ok(expected.public()),
+		);
+
+		// when keystore file is malformed
+		std::fs::write(&keystore_file_path, format!("\"{}", PHRASE)).unwrap();
+		assert!(TargetSigningParams {
+			target_signer: None,
+			target_signer_password: Some(PASSWORD.into()),
+
+			target_signer_file: Some(keystore_file_path),
+			target_signer_password_file: None,
+		}
+		.to_keypair::<relay_rialto_client::Rialto>()
+		.is_err());
+	}
+
+	#[test]
+	fn reads_raw_seed_with_trailing_line_break_from_file() {
+		const SEED: &str = "0xe5be9a5092b81bca64be81d212e7f2f9eba183bb7a90954f7b76361f6edb5c0a";
+
+		let expected = sp_core::sr25519::Pair::from_string(SEED, None).unwrap();
+
+		let temp_dir = tempdir::TempDir::new("reads_raw_seed_from_file").unwrap();
+		let seed_file_path = temp_dir.path().join("seed");
+		std::fs::write(&seed_file_path, format!("{}\n", SEED)).unwrap();
+
+		assert_eq!(
+			SourceSigningParams {
+				source_signer: None,
+				source_signer_password: None,
+
+				source_signer_file: Some(seed_file_path),
+				source_signer_password_file: None,
+			}
+			.to_keypair::<relay_millau_client::Millau>()
+			.map(|p| p.public())
+			.map_err(drop),
+			Ok(expected.public()),
+		);
+	}
 }
@@ -0,0 +1,251 @@
+// Copyright 2019-2021 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+use crate::cli::bridge::FullBridge;
+use crate::cli::{Balance, SourceConnectionParams, SourceSigningParams, TargetConnectionParams, TargetSigningParams};
+use codec::Encode;
+use pallet_bridge_lane_registry::{AllowedSenders, LaneInfo};
+use relay_substrate_client::{Chain, Client, TransactionSignScheme};
+use sp_core::{storage::StorageKey, Bytes, Pair};
+use std::convert::TryInto;
+use structopt::StructOpt;
+use strum::VariantNames;
+
+/// Derive lane identifier for the application and open this lane on both chains.
+#[derive(StructOpt)]
+pub struct OpenLane {
+	/// A bridge instance to open lane at.
+	#[structopt(possible_values = FullBridge::VARIANTS, case_insensitive = true)]
+	bridge: FullBridge,
+	/// Identifier of the application at the source chain.
+	#[structopt(long)]
+	app_id: String,
+	/// Identifier of the counterpart application at the target chain.
+	#[structopt(long)]
+	counterpart: String,
+	/// Minimal fee of messages, sent over the lane at the source chain.
+	#[structopt(long, default_value = "0")]
+	source_minimal_fee: Balance,
+	/// Minimal fee of messages, sent over the lane at the target chain.
+	#[structopt(long, default_value = "0")]
+	target_minimal_fee: Balance,
+	/// Only print the derived lane identifier, without opening the lane.
+	#[structopt(long)]
+	dry_run: bool,
+	#[structopt(flatten)]
+	source: SourceConnectionParams,
+	#[structopt(flatten)]
+	source_sign: SourceSigningParams,
+	#[structopt(flatten)]
+	target: TargetConnectionParams,
+	#[structopt(flatten)]
+	target_sign: TargetSigningParams,
+}
+
+macro_rules! select_bridge {
+	($bridge: expr, $generic: tt) => {
+		match $bridge {
+			FullBridge::MillauToRialto => {
+				type Source = relay_millau_client::Millau;
+				type Target = relay_rialto_client::Rialto;
+
+				fn source_lanes_key(lane: bp_messages::LaneId) -> StorageKey {
+					StorageKey(pallet_bridge_lane_registry::Lanes::<millau_runtime::Runtime>::hashed_key_for(lane))
+				}
+
+				fn target_lanes_key(lane: bp_messages::LaneId) -> StorageKey {
+					StorageKey(pallet_bridge_lane_registry::Lanes::<rialto_runtime::Runtime>::hashed_key_for(lane))
+				}
+
+				fn encode_source_register_lane(
+					lane: bp_messages::LaneId,
+					lane_info: LaneInfo<bp_millau::AccountId, bp_millau::Balance>,
+				) -> <Source as Chain>::Call {
+					millau_runtime::SudoCall::sudo(Box::new(
+						pallet_bridge_lane_registry::Call::<millau_runtime::Runtime>::register_lane(lane, lane_info)
+							.into(),
+					))
+					.into()
+				}
+
+				fn encode_target_register_lane(
+					lane: bp_messages::LaneId,
+					lane_info: LaneInfo<bp_rialto::AccountId, bp_rialto::Balance>,
+				) -> <Target as Chain>::Call {
+					rialto_runtime::SudoCall::sudo(Box::new(
+						pallet_bridge_lane_registry::Call::<rialto_runtime::Runtime>::register_lane(lane, lane_info)
+							.into(),
+					))
+					.into()
+				}
+
+				$generic
+			}
+			FullBridge::RialtoToMillau => {
+				type Source = relay_rialto_client::Rialto;
+				type Target = relay_millau_client::Millau;
+
+				fn source_lanes_key(lane: bp_messages::LaneId) -> StorageKey {
+					StorageKey(pallet_bridge_lane_registry::Lanes::<rialto_runtime::Runtime>::hashed_key_for(lane))
+				}
+
+				fn target_lanes_key(lane: bp_messages::LaneId) -> StorageKey {
+					StorageKey(pallet_bridge_lane_registry::Lanes::<millau_runtime::Runtime>::hashed_key_for(lane))
+				}
+
+				fn encode_source_register_lane(
+					lane: bp_messages::LaneId,
+					lane_info: LaneInfo<bp_rialto::AccountId, bp_rialto::Balance>,
+				) -> <Source as Chain>::Call {
+					rialto_runtime::SudoCall::sudo(Box::new(
+						pallet_bridge_lane_registry::Call::<rialto_runtime::Runtime>::register_lane(lane, lane_info)
+							.into(),
+					))
+					.into()
+				}
+
+				fn encode_target_register_lane(
+					lane: bp_messages::LaneId,
+					lane_info: LaneInfo<bp_millau::AccountId, bp_millau::Balance>,
+				) -> <Target as Chain>::Call {
+					millau_runtime::SudoCall::sudo(Box::new(
+						pallet_bridge_lane_registry::Call::<millau_runtime::Runtime>::register_lane(lane, lane_info)
+							.into(),
+					))
+					.into()
+				}
+
+				$generic
+			}
+			FullBridge::RococoToWococo | FullBridge::WococoToRococo => {
+				anyhow::bail!("Lane registry is only deployed at Millau and Rialto chains")
+			}
+		}
+	};
+}
+
+impl OpenLane {
+	/// Run the command.
+	pub async fn run(self) -> anyhow::Result<()> {
+		let lane = bp_messages::derive_lane_id(self.app_id.as_bytes(), self.counterpart.as_bytes());
+		println!("{}", hex::encode(lane));
+		if self.dry_run {
+			return Ok(());
+		}
+
+		select_bridge!(self.bridge, {
+			let source_client = self.source.to_client::<Source>().await?;
+			let target_client = self.target.to_client::<Target>().await?;
+
+			// collision checks go first, so that we won't open the lane on one side only
+			ensure_lane_is_not_registered(&source_client, source_lanes_key(lane), lane).await?;
+			ensure_lane_is_not_registered(&target_client, target_lanes_key(lane), lane).await?;
+
+			let source_sign = self.source_sign.to_keypair::<Source>()?;
+			let source_lane_info = LaneInfo {
+				owner: source_sign.public().into(),
+				allowed_senders: AllowedSenders::Any,
+				minimal_fee: self
+					.source_minimal_fee
+					.0
+					.try_into()
+					.map_err(|_| anyhow::format_err!("Source minimal fee is too large"))?,
+			};
+			let source_genesis_hash = *source_client.genesis_hash();
+			source_client
+				.submit_signed_extrinsic(source_sign.public().into(), move |transaction_nonce| {
+					Bytes(
+						Source::sign_transaction(
+							source_genesis_hash,
+							&source_sign,
+							transaction_nonce,
+							encode_source_register_lane(lane, source_lane_info),
+						)
+						.encode(),
+					)
+				})
+				.await?;
+
+			let target_sign = self.target_sign.to_keypair::<Target>()?;
+			let target_lane_info = LaneInfo {
+				owner: target_sign.public().into(),
+				allowed_senders: AllowedSenders::Any,
+				minimal_fee: self
+					.target_minimal_fee
+					.0
+					.try_into()
+					.map_err(|_| anyhow::format_err!("Target minimal fee is too large"))?,
+			};
+			let target_genesis_hash = *target_client.genesis_hash();
+			target_client
+				.submit_signed_extrinsic(target_sign.public().into(), move |transaction_nonce| {
+					Bytes(
+						Target::sign_transaction(
+							target_genesis_hash,
+							&target_sign,
+							transaction_nonce,
+							encode_target_register_lane(lane, target_lane_info),
+						)
+						.encode(),
+					)
+				})
+				.await?;
+
+			log::info!(
+				target: "bridge",
+				"Submitted registration of lane {} at {} and {}",
+				hex::encode(lane),
+				Source::NAME,
+				Target::NAME,
+			);
+
+			Ok(())
+		})
+	}
+}
+
+/// Ensure that the lane is not yet registered at the chain.
+async fn ensure_lane_is_not_registered<C: Chain>(
+	client: &Client<C>,
+	lanes_key: StorageKey,
+	lane: bp_messages::LaneId,
+) -> anyhow::Result<()> {
+	let lane_info: Option<LaneInfo<C::AccountId, C::Balance>> = client.storage_value(lanes_key).await?;
+	if let Some(lane_info) = lane_info {
+		return Err(anyhow::format_err!(
+			"Lane {} is already registered at {} (owner: {:?}). Choose another application identifier",
+			hex::encode(lane),
+			C::NAME,
+			lane_info.owner,
+		));
+	}
+
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn lane_storage_keys_match_runtime_keys() {
+		let lane = bp_messages::derive_lane_id(b"app", b"counterpart");
+		assert_eq!(
+			pallet_bridge_lane_registry::Lanes::<millau_runtime::Runtime>::hashed_key_for(lane)[..32],
+			bp_runtime::storage_value_key("BridgeLaneRegistry", "Lanes").0[..],
+		);
+	}
+}
@@ -0,0 +1,369 @@
+// Copyright 2019-2021 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Checks that are performed before running CLI commands.
+//!
+//! Every command that is talking to bridge pallets verifies that these pallets are initialized
+//! and not halted, before doing anything else. Otherwise the same problem would only be detected
+//! deep inside relay loops, with much less helpful error messages.
+//!
+//! Bridge pallets also store the genesis hash of the bridged chain, so commands are checking
+//! that the relay is connected to the same chain that the pallets are expecting.
+
+use bp_messages::{LaneId, OperatingMode};
+use codec::Encode;
+use frame_support::{traits::Instance, Blake2_128Concat, StorageHasher};
+use pallet_bridge_lane_registry::LaneInfo;
+use relay_substrate_client::{Chain, Client, HashOf};
+use relay_utils::alerts::AlertKind;
+use sp_core::storage::StorageKey;
+
+/// Name of the GRANDPA pallet storage value with the hash of the best finalized header.
+const GRANDPA_BEST_FINALIZED_VALUE_NAME: &str = "BestFinalized";
+/// Name of the GRANDPA pallet storage value with the halted flag.
+const GRANDPA_IS_HALTED_VALUE_NAME: &str = "IsHalted";
+/// Name of the GRANDPA pallet storage value with the bridged chain genesis hash.
+const GRANDPA_BRIDGED_GENESIS_HASH_VALUE_NAME: &str = "BridgedGenesisHash";
+/// Name of the lane registry pallet storage map with registered lanes.
+const LANE_REGISTRY_LANES_MAP_NAME: &str = "Lanes";
+
+/// Configuration errors, found by preflight checks.
+///
+/// Errors are collected instead of failing on the first one, so that the user could fix the
+/// whole configuration at once.
+#[derive(Debug, Default)]
+pub(crate) struct ConfigurationErrors(Vec<anyhow::Error>);
+
+impl ConfigurationErrors {
+	/// Remember the check error, if any.
+	pub fn check(&mut self, result: anyhow::Result<()>) {
+		if let Err(error) = result {
+			self.0.push(error);
+		}
+	}
+
+	/// Returns error, describing all found configuration errors.
+	pub fn into_result(mut self) -> anyhow::Result<()> {
+		match self.0.len() {
+			0 => Ok(()),
+			1 => Err(self.0.remove(0)),
+			errors_count => Err(anyhow::format_err!(
+				"Found {} configuration errors:\n{}",
+				errors_count,
+				self.0
+					.iter()
+					.map(|error| format!("  - {}", error))
+					.collect::<Vec<_>>()
+					.join("\n"),
+			)),
+		}
+	}
+}
+
+/// Ensure that the GRANDPA pallet is deployed, initialized and not halted.
+pub(crate) async fn ensure_grandpa_pallet_ready<C: Chain>(client: &Client<C>, pallet_name: &str) -> anyhow::Result<()> {
+	if !is_grandpa_pallet_initialized(client, pallet_name).await? {
+		return Err(anyhow::format_err!(
+			"{} GRANDPA pallet {} is either missing from the runtime, or not initialized; run init-bridge",
+			C::NAME,
+			pallet_name,
+		));
+	}
+
+	if is_grandpa_pallet_halted(client, pallet_name).await? {
+		return Err(halted_pallet_error(
+			C::NAME,
+			format!(
+				"GRANDPA pallet {} is halted; it must be resumed by the pallet owner",
+				pallet_name
+			),
+		)
+		.await);
+	}
+
+	Ok(())
+}
+
+/// Ensure that the GRANDPA pallet at `client` is tracking the chain that `bridged_client` is
+/// connected to.
+///
+/// The check passes if the pallet has been initialized without the bridged chain genesis hash.
+pub(crate) async fn ensure_grandpa_pallet_bridged_with<C: Chain, BC: Chain>(
+	client: &Client<C>,
+	pallet_name: &str,
+	bridged_client: &Client<BC>,
+) -> anyhow::Result<()> {
+	let expected_genesis_hash: Option<HashOf<BC>> = client
+		.storage_value(bp_runtime::storage_value_key(
+			pallet_name,
+			GRANDPA_BRIDGED_GENESIS_HASH_VALUE_NAME,
+		))
+		.await?;
+	ensure_genesis_hash_matches::<C, BC>(
+		&format!("GRANDPA pallet {}", pallet_name),
+		expected_genesis_hash,
+		bridged_client,
+	)
+}
+
+/// Ensure that the GRANDPA pallet is not yet initialized.
+pub(crate) async fn ensure_grandpa_pallet_not_initialized<C: Chain>(
+	client: &Client<C>,
+	pallet_name: &str,
+) -> anyhow::Result<()> {
+	if is_grandpa_pallet_initialized(client, pallet_name).await? {
+		return Err(anyhow::format_err!(
+			"{} GRANDPA pallet {} is already initialized",
+			C::NAME,
+			pallet_name,
+		));
+	}
+
+	Ok(())
+}
+
+/// Ensure that the messages pallet is not halted.
+pub(crate) async fn ensure_messages_pallet_not_halted<C: Chain, I: Instance>(client: &Client<C>) -> anyhow::Result<()> {
+	if messages_pallet_operating_mode::<C, I>(client).await? == OperatingMode::Halted {
+		return Err(halted_pallet_error(
+			C::NAME,
+			format!(
+				"messages pallet {} is halted; it must be resumed by the pallet owner",
+				I::PREFIX
+			),
+		)
+		.await);
+	}
+
+	Ok(())
+}
+
+/// Ensure that the messages pallet at `client` is bridged with the chain that `bridged_client`
+/// is connected to.
+///
+/// The check passes if the bridged chain genesis hash is not set at the pallet.
+pub(crate) async fn ensure_messages_pallet_bridged_with<C: Chain, BC: Chain, I: Instance>(
+	client: &Client<C>,
+	bridged_client: &Client<BC>,
+) -> anyhow::Result<()> {
+	let expected_genesis_hash: Option<HashOf<BC>> = client
+		.storage_value(pallet_bridge_messages::storage_keys::bridged_chain_genesis_hash_key::<I>())
+		.await?;
+	ensure_genesis_hash_matches::<C, BC>(
+		&format!("messages pallet {}", I::PREFIX),
+		expected_genesis_hash,
+		bridged_client,
+	)
+}
+
+/// Ensure that the messages pallet is accepting outbound messages.
+pub(crate) async fn ensure_messages_pallet_accepts_outbound_messages<C: Chain, I: Instance>(
+	client: &Client<C>,
+) -> anyhow::Result<()> {
+	match messages_pallet_operating_mode::<C, I>(client).await? {
+		OperatingMode::Normal => Ok(()),
+		OperatingMode::RejectingOutboundMessages => Err(anyhow::format_err!(
+			"{} messages pallet {} is rejecting outbound messages",
+			C::NAME,
+			I::PREFIX,
+		)),
+		OperatingMode::Halted => Err(halted_pallet_error(
+			C::NAME,
+			format!(
+				"messages pallet {} is halted; it must be resumed by the pallet owner",
+				I::PREFIX
+			),
+		)
+		.await),
+	}
+}
+
+/// Ensure that the lane is registered at the chain.
+///
+/// If `lane_registry_pallet_name` is `None`, the lane registry is not deployed at the chain and
+/// every lane is considered open.
+pub(crate) async fn ensure_lane_registered<C: Chain>(
+	client: &Client<C>,
+	lane_registry_pallet_name: Option<&str>,
+	lane: LaneId,
+) -> anyhow::Result<()> {
+	let lane_registry_pallet_name = match lane_registry_pallet_name {
+		Some(lane_registry_pallet_name) => lane_registry_pallet_name,
+		None => return Ok(()),
+	};
+
+	let lane_info: Option<LaneInfo<C::AccountId, C::Balance>> = client
+		.storage_value(lane_info_key(lane_registry_pallet_name, lane))
+		.await?;
+	if lane_info.is_none() {
+		return Err(anyhow::format_err!(
+			"Lane {} is not registered at {}; run open-lane",
+			hex::encode(lane),
+			C::NAME,
+		));
+	}
+
+	Ok(())
+}
+
+/// Ensure that all pallets, required to relay messages between two chains, are ready and
+/// that all lanes are registered at both chains.
+///
+/// Messages relay requires GRANDPA pallets at both chains (to verify messages and delivery
+/// confirmations proofs) and messages pallets at both chains. All found errors are reported
+/// at once.
+pub(crate) async fn ensure_messages_relay_ready<SC: Chain, TC: Chain, SI: Instance, TI: Instance>(
+	source_client: &Client<SC>,
+	source_grandpa_pallet_name: &str,
+	source_lane_registry_pallet_name: Option<&str>,
+	target_client: &Client<TC>,
+	target_grandpa_pallet_name: &str,
+	target_lane_registry_pallet_name: Option<&str>,
+	lanes: &[LaneId],
+) -> anyhow::Result<()> {
+	let mut errors = ConfigurationErrors::default();
+	errors.check(ensure_grandpa_pallet_ready(source_client, source_grandpa_pallet_name).await);
+	errors.check(ensure_grandpa_pallet_ready(target_client, target_grandpa_pallet_name).await);
+	errors.check(ensure_messages_pallet_not_halted::<SC, SI>(source_client).await);
+	errors.check(ensure_messages_pallet_not_halted::<TC, TI>(target_client).await);
+	errors.check(ensure_grandpa_pallet_bridged_with(source_client, source_grandpa_pallet_name, target_client).await);
+	errors.check(ensure_grandpa_pallet_bridged_with(target_client, target_grandpa_pallet_name, source_client).await);
+	errors.check(ensure_messages_pallet_bridged_with::<SC, TC, SI>(source_client, target_client).await);
+	errors.check(ensure_messages_pallet_bridged_with::<TC, SC, TI>(target_client, source_client).await);
+	for lane in lanes {
+		errors.check(ensure_lane_registered(source_client, source_lane_registry_pallet_name, *lane).await);
+		errors.check(ensure_lane_registered(target_client, target_lane_registry_pallet_name, *lane).await);
+	}
+	errors.into_result()
+}
+
+/// Returns true if the GRANDPA pallet has been initialized.
+///
+/// We can't use the `best_finalized` runtime API here, because it panics if pallet is not
+/// initialized. So we're reading the storage value directly.
+async fn is_grandpa_pallet_initialized<C: Chain>(client: &Client<C>, pallet_name: &str) -> anyhow::Result<bool> {
+	let best_finalized: Option<HashOf<C>> = client
+		.storage_value(bp_runtime::storage_value_key(
+			pallet_name,
+			GRANDPA_BEST_FINALIZED_VALUE_NAME,
+		))
+		.await?;
+	Ok(best_finalized.is_some())
+}
+
+/// Returns true if the GRANDPA pallet is halted.
+pub(crate) async fn is_grandpa_pallet_halted<C: Chain>(client: &Client<C>, pallet_name: &str) -> anyhow::Result<bool> {
+	let is_halted: Option<bool> = client
+		.storage_value(bp_runtime::storage_value_key(pallet_name, GRANDPA_IS_HALTED_VALUE_NAME))
+		.await?;
+	Ok(is_halted.unwrap_or(false))
+}
+
+/// Read hash of the best finalized bridged chain header, known to the GRANDPA pallet.
+///
+/// Returns `None` if the pallet is not initialized.
+pub(crate) async fn best_finalized_bridged_header_hash<C: Chain, BC: Chain>(
+	client: &Client<C>,
+	pallet_name: &str,
+) -> anyhow::Result<Option<HashOf<BC>>> {
+	Ok(client
+		.storage_value(bp_runtime::storage_value_key(
+			pallet_name,
+			GRANDPA_BEST_FINALIZED_VALUE_NAME,
+		))
+		.await?)
+}
+
+/// Compare genesis hash, stored by the bridge pallet, with the genesis hash of the connected
+/// bridged chain.
+fn ensure_genesis_hash_matches<C: Chain, BC: Chain>(
+	pallet: &str,
+	expected_genesis_hash: Option<HashOf<BC>>,
+	bridged_client: &Client<BC>,
+) -> anyhow::Result<()> {
+	match expected_genesis_hash {
+		Some(expected_genesis_hash) if expected_genesis_hash != *bridged_client.genesis_hash() => {
+			Err(anyhow::format_err!(
+				"{} {} expects {} genesis hash {:?}, but the relay is connected to the chain with genesis hash {:?}",
+				C::NAME,
+				pallet,
+				BC::NAME,
+				expected_genesis_hash,
+				bridged_client.genesis_hash(),
+			))
+		}
+		_ => Ok(()),
+	}
+}
+
+/// Storage key of the lane registry entry.
+fn lane_info_key(lane_registry_pallet_name: &str, lane: LaneId) -> StorageKey {
+	let mut key = bp_runtime::storage_value_key(lane_registry_pallet_name, LANE_REGISTRY_LANES_MAP_NAME).0;
+	key.extend(Blake2_128Concat::hash(&lane.encode()));
+	StorageKey(key)
+}
+
+/// Read operating mode of the messages pallet.
+pub(crate) async fn messages_pallet_operating_mode<C: Chain, I: Instance>(
+	client: &Client<C>,
+) -> anyhow::Result<OperatingMode> {
+	let operating_mode: Option<OperatingMode> = client
+		.storage_value(pallet_bridge_messages::storage_keys::operating_mode_key::<I>())
+		.await?;
+	Ok(operating_mode.unwrap_or(OperatingMode::Normal))
+}
+
+/// Fire the halted pallet alert and return error with the same message.
+///
+/// We're waiting until the alert is sent, because the relay is going to exit right after that.
+async fn halted_pallet_error(chain_name: &str, message: String) -> anyhow::Error {
+	relay_utils::alerts::fire_and_wait(AlertKind::PalletHalted, chain_name, message.clone()).await;
+	anyhow::format_err!("{} {}", chain_name, message)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn lane_info_key_matches_runtime_key() {
+		let lane = bp_messages::derive_lane_id(b"app", b"counterpart");
+		assert_eq!(
+			lane_info_key(bp_millau::LANE_REGISTRY_PALLET_NAME, lane).0,
+			pallet_bridge_lane_registry::Lanes::<millau_runtime::Runtime>::hashed_key_for(lane),
+		);
+	}
+
+	#[test]
+	fn configuration_errors_are_reported_at_once() {
+		let mut errors = ConfigurationErrors::default();
+		errors.check(Ok(()));
+		assert!(errors.into_result().is_ok());
+
+		let mut errors = ConfigurationErrors::default();
+		errors.check(Err(anyhow::format_err!("first")));
+		assert_eq!(errors.into_result().unwrap_err().to_string(), "first");
+
+		let mut errors = ConfigurationErrors::default();
+		errors.check(Err(anyhow::format_err!("first")));
+		errors.check(Ok(()));
+		errors.check(Err(anyhow::format_err!("second")));
+		assert_eq!(
+			errors.into_result().unwrap_err().to_string(),
+			"Found 2 configuration errors:\n  - first\n  - second",
+		);
+	}
+}
@@ -14,7 +14,8 @@
 // You should have received a copy of the GNU General Public License
 // along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
 
-use crate::cli::{PrometheusParams, SourceConnectionParams, TargetConnectionParams, TargetSigningParams};
+use crate::cli::preflight::{ensure_grandpa_pallet_bridged_with, ensure_grandpa_pallet_ready};
+use crate::cli::{AlertParams, PrometheusParams, SourceConnectionParams, TargetConnectionParams, TargetSigningParams};
 use crate::finality_pipeline::SubstrateFinalitySyncPipeline;
 use structopt::StructOpt;
 use strum::{EnumString, EnumVariantNames, VariantNames};
@@ -28,6 +29,10 @@ pub struct RelayHeaders {
 	/// If passed, only mandatory headers (headers that are changing the GRANDPA authorities set) are relayed.
 	#[structopt(long)]
 	only_mandatory_headers: bool,
+	/// If passed and the source chain reset (relaunch or GRANDPA authorities set reset) is detected,
+	/// the relay prepares and logs data that is required to re-initialize the bridge pallet.
+	#[structopt(long)]
+	prepare_reinitialization: bool,
 	#[structopt(flatten)]
 	source: SourceConnectionParams,
 	#[structopt(flatten)]
@@ -36,6 +41,8 @@ pub struct RelayHeaders {
 	target_sign: TargetSigningParams,
 	#[structopt(flatten)]
 	prometheus_params: PrometheusParams,
+	#[structopt(flatten)]
+	alert_params: AlertParams,
 }
 
 #[derive(Debug, EnumString, EnumVariantNames)]
@@ -56,6 +63,7 @@ macro_rules! select_bridge {
 				type Source = relay_millau_client::Millau;
 				type Target = relay_rialto_client::Rialto;
 				type Finality = crate::chains::millau_headers_to_rialto::MillauFinalityToRialto;
+				const TARGET_GRANDPA_PALLET_NAME: &str = bp_rialto::WITH_MILLAU_GRANDPA_PALLET_NAME;
 
 				$generic
 			}
@@ -63,6 +71,7 @@ macro_rules! select_bridge {
 				type Source = relay_rialto_client::Rialto;
 				type Target = relay_millau_client::Millau;
 				type Finality = crate::chains::rialto_headers_to_millau::RialtoFinalityToMillau;
+				const TARGET_GRANDPA_PALLET_NAME: &str = bp_millau::WITH_RIALTO_GRANDPA_PALLET_NAME;
 
 				$generic
 			}
@@ -70,6 +79,7 @@ macro_rules! select_bridge {
 				type Source = relay_westend_client::Westend;
 				type Target = relay_millau_client::Millau;
 				type Finality = crate::chains::westend_headers_to_millau::WestendFinalityToMillau;
+				const TARGET_GRANDPA_PALLET_NAME: &str = bp_millau::WITH_WESTEND_GRANDPA_PALLET_NAME;
 
 				$generic
 			}
@@ -77,6 +87,7 @@ macro_rules! select_bridge {
 				type Source = relay_rococo_client::Rococo;
 				type Target = relay_wococo_client::Wococo;
 				type Finality = crate::chains::rococo_headers_to_wococo::RococoFinalityToWococo;
+				const TARGET_GRANDPA_PALLET_NAME: &str = bp_wococo::WITH_ROCOCO_GRANDPA_PALLET_NAME;
 
 				$generic
 			}
@@ -84,6 +95,7 @@ macro_rules! select_bridge {
 				type Source = relay_wococo_client::Wococo;
 				type Target = relay_rococo_client::Rococo;
 				type Finality = crate::chains::wococo_headers_to_rococo::WococoFinalityToRococo;
+				const TARGET_GRANDPA_PALLET_NAME: &str = bp_rococo::WITH_WOCOCO_GRANDPA_PALLET_NAME;
 
 				$generic
 			}
@@ -94,9 +106,14 @@ macro_rules! select_bridge {
 impl RelayHeaders {
 	/// Run the command.
 	pub async fn run(self) -> anyhow::Result<()> {
+		self.alert_params.install()?;
+
 		select_bridge!(self.bridge, {
 			let source_client = self.source.to_client::<Source>().await?;
 			let target_client = self.target.to_client::<Target>().await?;
+			ensure_grandpa_pallet_ready(&target_client, TARGET_GRANDPA_PALLET_NAME).await?;
+			ensure_grandpa_pallet_bridged_with(&target_client, TARGET_GRANDPA_PALLET_NAME, &source_client).await?;
+
 			let target_sign = self.target_sign.to_keypair::<Target>()?;
 			let metrics_params = Finality::customize_metrics(self.prometheus_params.into())?;
 			let finality = Finality::new(target_client.clone(), target_sign);
@@ -106,7 +123,9 @@ impl RelayHeaders {
 				finality,
 				source_client,
 				target_client,
+				TARGET_GRANDPA_PALLET_NAME,
 				self.only_mandatory_headers,
+				self.prepare_reinitialization,
 				metrics_params,
 			)
 			.await
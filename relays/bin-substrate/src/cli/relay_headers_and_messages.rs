@@ -22,16 +22,23 @@
 //! 2) add `declare_bridge_options!(...)` for the bridge;
 //! 3) add bridge support to the `select_bridge! { ... }` macro.
 
-use crate::cli::{relay_messages::RelayerMode, CliChain, HexLaneId, PrometheusParams};
+use crate::cli::preflight::ensure_messages_relay_ready;
+use crate::cli::{relay_messages::RelayerMode, AlertParams, CliChain, HexLaneId, PrometheusParams};
 use crate::declare_chain_options;
 use crate::messages_lane::MessagesRelayParams;
 use crate::on_demand_headers::OnDemandHeadersRelay;
 
 use futures::{FutureExt, TryFutureExt};
 use relay_utils::metrics::MetricsParams;
+use std::{ffi::OsString, path::PathBuf};
 use structopt::StructOpt;
 use strum::VariantNames;
 
+/// Name of the `relay-headers-and-messages` subcommand.
+const RELAY_HEADERS_AND_MESSAGES_COMMAND: &str = "relay-headers-and-messages";
+/// Name of the option that points to the relay configuration file.
+const CONFIG_OPTION: &str = "--config";
+
 /// Start headers+messages relayer process.
 #[derive(StructOpt)]
 pub enum RelayHeadersAndMessages {
@@ -45,10 +52,24 @@ pub struct HeadersAndMessagesSharedParams {
 	/// Hex-encoded lane identifiers that should be served by the complex relay.
 	#[structopt(long, default_value = "00000000")]
 	lane: Vec<HexLaneId>,
+	/// Deliver messages of all served lanes in the same transactions, if they fit. Messages of
+	/// other lanes are bundled into delivery transactions of the first lane.
+	#[structopt(long)]
+	bundle_lanes: bool,
 	#[structopt(long, possible_values = RelayerMode::VARIANTS, case_insensitive = true, default_value = "rational")]
 	relayer_mode: RelayerMode,
 	#[structopt(flatten)]
 	prometheus_params: PrometheusParams,
+	#[structopt(flatten)]
+	alert_params: AlertParams,
+	/// Path to the TOML file with relay options.
+	///
+	/// Every option of this command may be set in the file. Top-level keys are option names
+	/// (e.g. `relayer_mode = "altruistic"`) and keys of tables are option names without the table
+	/// name prefix (e.g. `port = 9944` in the `[millau]` table sets the `--millau-port` option).
+	/// Options, passed in the command line, override options from the file.
+	#[structopt(long)]
+	config: Option<PathBuf>,
 }
 
 // The reason behind this macro is that 'normal' relays are using source and target chains terminology,
@@ -103,6 +124,13 @@ macro_rules! select_bridge {
 				const MAX_MISSING_LEFT_HEADERS_AT_RIGHT: bp_millau::BlockNumber = bp_millau::SESSION_LENGTH;
 				const MAX_MISSING_RIGHT_HEADERS_AT_LEFT: bp_rialto::BlockNumber = bp_rialto::SESSION_LENGTH;
 
+				const LEFT_GRANDPA_PALLET_NAME: &str = bp_millau::WITH_RIALTO_GRANDPA_PALLET_NAME;
+				const RIGHT_GRANDPA_PALLET_NAME: &str = bp_rialto::WITH_MILLAU_GRANDPA_PALLET_NAME;
+				const LEFT_LANE_REGISTRY_PALLET_NAME: Option<&str> = Some(bp_millau::LANE_REGISTRY_PALLET_NAME);
+				const RIGHT_LANE_REGISTRY_PALLET_NAME: Option<&str> = Some(bp_rialto::LANE_REGISTRY_PALLET_NAME);
+				use millau_runtime::WithRialtoMessagesInstance as LeftMessagesInstance;
+				use rialto_runtime::WithMillauMessagesInstance as RightMessagesInstance;
+
 				use crate::chains::millau_messages_to_rialto::{
 					add_standalone_metrics as add_left_to_right_standalone_metrics, run as left_to_right_messages,
 				};
@@ -127,6 +155,13 @@ macro_rules! select_bridge {
 				const MAX_MISSING_LEFT_HEADERS_AT_RIGHT: bp_rococo::BlockNumber = bp_rococo::SESSION_LENGTH;
 				const MAX_MISSING_RIGHT_HEADERS_AT_LEFT: bp_wococo::BlockNumber = bp_wococo::SESSION_LENGTH;
 
+				const LEFT_GRANDPA_PALLET_NAME: &str = bp_rococo::WITH_WOCOCO_GRANDPA_PALLET_NAME;
+				const RIGHT_GRANDPA_PALLET_NAME: &str = bp_wococo::WITH_ROCOCO_GRANDPA_PALLET_NAME;
+				const LEFT_LANE_REGISTRY_PALLET_NAME: Option<&str> = None;
+				const RIGHT_LANE_REGISTRY_PALLET_NAME: Option<&str> = None;
+				use relay_rococo_client::runtime::WithWococoMessagesInstance as LeftMessagesInstance;
+				use relay_wococo_client::runtime::WithRococoMessagesInstance as RightMessagesInstance;
+
 				use crate::chains::rococo_messages_to_wococo::{
 					add_standalone_metrics as add_left_to_right_standalone_metrics, run as left_to_right_messages,
 				};
@@ -154,37 +189,60 @@ impl RelayHeadersAndMessages {
 	pub async fn run(self) -> anyhow::Result<()> {
 		select_bridge!(self, {
 			let params: Params = self.into();
+			params.shared.alert_params.install()?;
+			if let Some(ref config) = params.shared.config {
+				log::info!(target: "bridge", "Relay options are read from {:?}", config);
+			}
 
-			let left_client = params.left.to_client::<Left>().await?;
 			let left_sign = params.left_sign.to_keypair::<Left>()?;
-			let right_client = params.right.to_client::<Right>().await?;
 			let right_sign = params.right_sign.to_keypair::<Right>()?;
+			let left_client = params.left.to_client::<Left>().await?;
+			let right_client = params.right.to_client::<Right>().await?;
 
 			let lanes = params.shared.lane;
+			ensure_messages_relay_ready::<Left, Right, LeftMessagesInstance, RightMessagesInstance>(
+				&left_client,
+				LEFT_GRANDPA_PALLET_NAME,
+				LEFT_LANE_REGISTRY_PALLET_NAME,
+				&right_client,
+				RIGHT_GRANDPA_PALLET_NAME,
+				RIGHT_LANE_REGISTRY_PALLET_NAME,
+				&lanes.iter().map(|lane| lane.0).collect::<Vec<_>>(),
+			)
+			.await?;
 			let relayer_mode = params.shared.relayer_mode.into();
 
 			let metrics_params: MetricsParams = params.shared.prometheus_params.into();
 			let metrics_params = relay_utils::relay_metrics(None, metrics_params).into_params();
-			let (metrics_params, _) = add_left_to_right_standalone_metrics(None, metrics_params, left_client.clone())?;
-			let (metrics_params, _) = add_right_to_left_standalone_metrics(None, metrics_params, right_client.clone())?;
+			let (metrics_params, _) =
+				add_left_to_right_standalone_metrics(None, metrics_params, left_client.clone(), right_client.clone())?;
+			let (metrics_params, _) =
+				add_right_to_left_standalone_metrics(None, metrics_params, right_client.clone(), left_client.clone())?;
 
 			let left_to_right_on_demand_headers = OnDemandHeadersRelay::new(
 				left_client.clone(),
 				right_client.clone(),
 				LeftToRightFinality::new(right_client.clone(), right_sign.clone()),
 				MAX_MISSING_LEFT_HEADERS_AT_RIGHT,
+				metrics_params.clone().disable(),
 			);
 			let right_to_left_on_demand_headers = OnDemandHeadersRelay::new(
 				right_client.clone(),
 				left_client.clone(),
 				RightToLeftFinality::new(left_client.clone(), left_sign.clone()),
 				MAX_MISSING_RIGHT_HEADERS_AT_LEFT,
+				metrics_params.clone().disable(),
 			);
 
 			// Need 2x capacity since we consider both directions for each lane
 			let mut message_relays = Vec::with_capacity(lanes.len() * 2);
-			for lane in lanes {
-				let lane = lane.into();
+			let lanes = lanes.into_iter().map(Into::into).collect::<Vec<bp_messages::LaneId>>();
+			for (lane_index, lane) in lanes.iter().cloned().enumerate() {
+				let additional_lanes = if params.shared.bundle_lanes && lane_index == 0 {
+					lanes[1..].to_vec()
+				} else {
+					Vec::new()
+				};
 				let left_to_right_messages = left_to_right_messages(MessagesRelayParams {
 					source_client: left_client.clone(),
 					source_sign: left_sign.clone(),
@@ -193,6 +251,7 @@ impl RelayHeadersAndMessages {
 					source_to_target_headers_relay: Some(left_to_right_on_demand_headers.clone()),
 					target_to_source_headers_relay: Some(right_to_left_on_demand_headers.clone()),
 					lane_id: lane,
+					additional_lanes: additional_lanes.clone(),
 					relayer_mode,
 					metrics_params: metrics_params.clone().disable().metrics_prefix(
 						messages_relay::message_lane_loop::metrics_prefix::<LeftToRightMessages>(&lane),
@@ -208,6 +267,7 @@ impl RelayHeadersAndMessages {
 					source_to_target_headers_relay: Some(right_to_left_on_demand_headers.clone()),
 					target_to_source_headers_relay: Some(left_to_right_on_demand_headers.clone()),
 					lane_id: lane,
+					additional_lanes,
 					relayer_mode,
 					metrics_params: metrics_params.clone().disable().metrics_prefix(
 						messages_relay::message_lane_loop::metrics_prefix::<RightToLeftMessages>(&lane),
@@ -229,3 +289,221 @@ impl RelayHeadersAndMessages {
 		})
 	}
 }
+
+/// Expand options from the configuration file of the `relay-headers-and-messages` command.
+///
+/// If the command line has no `--config` option, it is returned unchanged. Otherwise options from
+/// the configuration file that are not set in the command line are appended to it.
+pub(crate) fn expand_config_file_args(args: Vec<OsString>) -> anyhow::Result<Vec<OsString>> {
+	if args.get(1).and_then(|arg| arg.to_str()) != Some(RELAY_HEADERS_AND_MESSAGES_COMMAND) {
+		return Ok(args);
+	}
+
+	let config_path = match config_path(&args) {
+		Some(config_path) => config_path,
+		None => return Ok(args),
+	};
+	let config = std::fs::read_to_string(&config_path)
+		.map_err(|e| anyhow::format_err!("Failed to read relay configuration file {:?}: {}", config_path, e))?;
+	let config_args = config_file_args(&config)
+		.map_err(|e| anyhow::format_err!("Invalid relay configuration file {:?}: {}", config_path, e))?;
+
+	let mut args = args;
+	for (name, value) in config_args {
+		if is_option_set(&args, &name) {
+			continue;
+		}
+
+		args.push(name.into());
+		if let Some(value) = value {
+			args.push(value.into());
+		}
+	}
+	Ok(args)
+}
+
+/// Returns path to the configuration file, if it is set in the command line.
+fn config_path(args: &[OsString]) -> Option<PathBuf> {
+	let config_option_value = format!("{}=", CONFIG_OPTION);
+	let mut args = args.iter().filter_map(|arg| arg.to_str());
+	while let Some(arg) = args.next() {
+		if arg == CONFIG_OPTION {
+			return args.next().map(Into::into);
+		}
+		if let Some(path) = arg.strip_prefix(&config_option_value) {
+			return Some(path.into());
+		}
+	}
+	None
+}
+
+/// Returns true if option with given name is set in the command line.
+fn is_option_set(args: &[OsString], name: &str) -> bool {
+	let name_with_value = format!("{}=", name);
+	args.iter()
+		.filter_map(|arg| arg.to_str())
+		.any(|arg| arg == name || arg.starts_with(&name_with_value))
+}
+
+/// Convert configuration file contents into the list of command line options and their values.
+fn config_file_args(config: &str) -> anyhow::Result<Vec<(String, Option<String>)>> {
+	let config: toml::value::Table = toml::from_str(config)?;
+	let mut args = Vec::new();
+	for (key, value) in config {
+		match value {
+			toml::Value::Table(table) => {
+				for (table_key, value) in table {
+					push_config_arg(&mut args, &format!("{}_{}", key, table_key), value)?;
+				}
+			}
+			value => push_config_arg(&mut args, &key, value)?,
+		}
+	}
+	Ok(args)
+}
+
+/// Convert single configuration file value into command line option(s).
+fn push_config_arg(args: &mut Vec<(String, Option<String>)>, key: &str, value: toml::Value) -> anyhow::Result<()> {
+	let name = format!("--{}", key.replace('_', "-"));
+	match value {
+		toml::Value::Boolean(true) => args.push((name, None)),
+		toml::Value::Boolean(false) => (),
+		toml::Value::Array(values) => {
+			for value in values {
+				args.push((name.clone(), Some(config_value(key, value)?)));
+			}
+		}
+		value => args.push((name, Some(config_value(key, value)?))),
+	}
+	Ok(())
+}
+
+/// Convert configuration file value into command line option value.
+fn config_value(key: &str, value: toml::Value) -> anyhow::Result<String> {
+	match value {
+		toml::Value::String(value) => Ok(value),
+		toml::Value::Integer(value) => Ok(value.to_string()),
+		toml::Value::Float(value) => Ok(value.to_string()),
+		_ => Err(anyhow::format_err!("Unsupported value of `{}` option: {}", key, value)),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const CONFIG: &str = r#"
+		lane = ["00000000", "73776170"]
+		relayer_mode = "altruistic"
+		no_prometheus = false
+
+		[millau]
+		host = "millau-node-alice"
+		port = 9944
+		signer = "//Charlie"
+
+		[rialto]
+		host = "rialto-node-alice"
+		port = 9945
+		secure = true
+		signer_file = "/run/secrets/rialto-signer"
+
+		[prometheus]
+		host = "0.0.0.0"
+		port = 9616
+	"#;
+
+	fn args(args: &[&str]) -> Vec<OsString> {
+		args.iter().map(OsString::from).collect()
+	}
+
+	fn args_with_config(config: &str, cli_args: &[&str]) -> Vec<OsString> {
+		let dir = tempdir::TempDir::new("relay-config").unwrap();
+		let config_path = dir.path().join("relay.toml");
+		std::fs::write(&config_path, config).unwrap();
+
+		let mut args = args(&["substrate-relay", RELAY_HEADERS_AND_MESSAGES_COMMAND, "millau-rialto"]);
+		args.push(format!("--config={}", config_path.display()).into());
+		args.extend(cli_args.iter().map(OsString::from));
+		expand_config_file_args(args).unwrap()
+	}
+
+	#[test]
+	fn config_file_is_converted_to_args() {
+		let args = config_file_args(CONFIG).unwrap();
+		assert!(args.contains(&("--lane".into(), Some("00000000".into()))));
+		assert!(args.contains(&("--lane".into(), Some("73776170".into()))));
+		assert!(args.contains(&("--relayer-mode".into(), Some("altruistic".into()))));
+		assert!(args.contains(&("--millau-port".into(), Some("9944".into()))));
+		assert!(args.contains(&("--rialto-secure".into(), None)));
+		assert!(args.contains(&("--rialto-signer-file".into(), Some("/run/secrets/rialto-signer".into()))));
+		assert!(args.contains(&("--prometheus-host".into(), Some("0.0.0.0".into()))));
+		assert!(!args.iter().any(|(name, _)| name == "--no-prometheus"));
+	}
+
+	#[test]
+	fn config_file_with_unsupported_value_is_rejected() {
+		assert!(config_file_args("[millau]\nport = { value = 9944 }").is_err());
+		assert!(config_file_args("lane = [[\"00000000\"]]").is_err());
+		assert!(config_file_args("not a toml").is_err());
+	}
+
+	#[test]
+	fn args_without_config_are_not_changed() {
+		let cli_args = args(&[
+			"substrate-relay",
+			RELAY_HEADERS_AND_MESSAGES_COMMAND,
+			"millau-rialto",
+			"--millau-port",
+			"9944",
+		]);
+		assert_eq!(expand_config_file_args(cli_args.clone()).unwrap(), cli_args);
+
+		let cli_args = args(&["substrate-relay", "relay-headers", "--config", "relay.toml"]);
+		assert_eq!(expand_config_file_args(cli_args.clone()).unwrap(), cli_args);
+	}
+
+	#[test]
+	fn missing_config_file_is_reported() {
+		let cli_args = args(&[
+			"substrate-relay",
+			RELAY_HEADERS_AND_MESSAGES_COMMAND,
+			"millau-rialto",
+			"--config",
+			"/non/existing/relay.toml",
+		]);
+		assert!(expand_config_file_args(cli_args).is_err());
+	}
+
+	#[test]
+	fn command_is_parsed_from_config_file() {
+		let args = args_with_config(CONFIG, &[]);
+		let params: MillauRialtoHeadersAndMessages = RelayHeadersAndMessages::from_iter(&args[1..]).into();
+
+		assert_eq!(params.left.millau_host, "millau-node-alice");
+		assert_eq!(params.left.millau_port, 9944);
+		assert_eq!(params.left_sign.millau_signer, Some("//Charlie".into()));
+		assert_eq!(params.right.rialto_port, 9945);
+		assert!(params.right.rialto_secure);
+		assert_eq!(
+			params.right_sign.rialto_signer_file,
+			Some("/run/secrets/rialto-signer".into())
+		);
+		assert_eq!(params.shared.lane.len(), 2);
+		assert_eq!(params.shared.relayer_mode, RelayerMode::Altruistic);
+		assert_eq!(params.shared.prometheus_params.prometheus_host, "0.0.0.0");
+	}
+
+	#[test]
+	fn command_line_options_override_config_file() {
+		let args = args_with_config(CONFIG, &["--millau-port", "19944", "--lane=00000001"]);
+		let params: MillauRialtoHeadersAndMessages = RelayHeadersAndMessages::from_iter(&args[1..]).into();
+
+		assert_eq!(params.left.millau_port, 19944);
+		assert_eq!(params.right.rialto_port, 9945);
+		assert_eq!(
+			params.shared.lane,
+			vec![HexLaneId(bp_messages::LaneId::Legacy([0, 0, 0, 1]))]
+		);
+	}
+}
@@ -15,8 +15,9 @@
 // along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
 
 use crate::cli::bridge::FullBridge;
+use crate::cli::preflight::ensure_messages_relay_ready;
 use crate::cli::{
-	HexLaneId, PrometheusParams, SourceConnectionParams, SourceSigningParams, TargetConnectionParams,
+	AlertParams, HexLaneId, PrometheusParams, SourceConnectionParams, SourceSigningParams, TargetConnectionParams,
 	TargetSigningParams,
 };
 use crate::messages_lane::MessagesRelayParams;
@@ -52,6 +53,10 @@ pub struct RelayMessages {
 	/// Hex-encoded lane id that should be served by the relay. Defaults to `00000000`.
 	#[structopt(long, default_value = "00000000")]
 	lane: HexLaneId,
+	/// Hex-encoded identifiers of lanes, which messages are delivered in the same transactions
+	/// with messages of the `lane`, if they fit.
+	#[structopt(long)]
+	additional_lane: Vec<HexLaneId>,
 	#[structopt(long, possible_values = RelayerMode::VARIANTS, case_insensitive = true, default_value = "rational")]
 	relayer_mode: RelayerMode,
 	#[structopt(flatten)]
@@ -64,16 +69,34 @@ pub struct RelayMessages {
 	target_sign: TargetSigningParams,
 	#[structopt(flatten)]
 	prometheus_params: PrometheusParams,
+	#[structopt(flatten)]
+	alert_params: AlertParams,
 }
 
 impl RelayMessages {
 	/// Run the command.
 	pub async fn run(self) -> anyhow::Result<()> {
+		self.alert_params.install()?;
+
 		select_full_bridge!(self.bridge, {
-			let source_client = self.source.to_client::<Source>().await?;
 			let source_sign = self.source_sign.to_keypair::<Source>()?;
-			let target_client = self.target.to_client::<Target>().await?;
 			let target_sign = self.target_sign.to_keypair::<Target>()?;
+			let source_client = self.source.to_client::<Source>().await?;
+			let target_client = self.target.to_client::<Target>().await?;
+
+			ensure_messages_relay_ready::<Source, Target, SourceMessagesInstance, TargetMessagesInstance>(
+				&source_client,
+				SOURCE_GRANDPA_PALLET_NAME,
+				SOURCE_LANE_REGISTRY_PALLET_NAME,
+				&target_client,
+				TARGET_GRANDPA_PALLET_NAME,
+				TARGET_LANE_REGISTRY_PALLET_NAME,
+				&std::iter::once(self.lane.clone())
+					.chain(self.additional_lane.iter().cloned())
+					.map(Into::into)
+					.collect::<Vec<bp_messages::LaneId>>(),
+			)
+			.await?;
 
 			relay_messages(MessagesRelayParams {
 				source_client,
@@ -83,6 +106,7 @@ impl RelayMessages {
 				source_to_target_headers_relay: None,
 				target_to_source_headers_relay: None,
 				lane_id: self.lane.into(),
+				additional_lanes: self.additional_lane.into_iter().map(Into::into).collect(),
 				relayer_mode: self.relayer_mode.into(),
 				metrics_params: self.prometheus_params.into(),
 			})
@@ -17,16 +17,22 @@
 use crate::cli::bridge::FullBridge;
 use crate::cli::encode_call::{self, CliEncodeCall};
 use crate::cli::estimate_fee::estimate_message_delivery_and_dispatch_fee;
+use crate::cli::preflight::ensure_messages_pallet_accepts_outbound_messages;
 use crate::cli::{
 	Balance, CliChain, ExplicitOrMaximal, HexBytes, HexLaneId, Origins, SourceConnectionParams, SourceSigningParams,
-	TargetSigningParams,
+	TargetConnectionParams, TargetSigningParams,
 };
 use bp_message_dispatch::{CallOrigin, MessagePayload};
-use codec::Encode;
+use bp_messages::LaneId;
+use bp_runtime::{messages::MessageDispatchResult, HasherOf};
+use codec::{Decode, Encode};
 use frame_support::weights::Weight;
-use relay_substrate_client::{Chain, TransactionSignScheme};
+use relay_substrate_client::{Chain, Client, TransactionSignScheme};
 use sp_core::{Bytes, Pair};
-use sp_runtime::{traits::IdentifyAccount, AccountId32, MultiSignature, MultiSigner};
+use sp_runtime::{
+	traits::{Hash, IdentifyAccount, Verify},
+	AccountId32, MultiSignature, MultiSigner,
+};
 use std::fmt::Debug;
 use structopt::StructOpt;
 use strum::{EnumString, EnumVariantNames, VariantNames};
@@ -79,139 +85,283 @@ pub struct SendMessage {
 	/// Delivery and dispatch fee in source chain base currency units. If not passed, determined automatically.
 	#[structopt(long)]
 	fee: Option<Balance>,
+	/// Split the encoded call into chunks and send every chunk in a separate message. Chunks are
+	/// reassembled and dispatched by the chunked dispatch pallet at the target chain. This allows
+	/// sending calls that are larger than the maximal message size.
+	#[structopt(long)]
+	chunked: bool,
+	/// Maximal size of single chunk in bytes. If not passed, determined automatically.
+	#[structopt(long, requires = "chunked")]
+	chunk_size: Option<u32>,
+	/// Hex-encoded SCALE-encoded message payload, signed by the target account key with the
+	/// `sign-payload` command. The payload is sent as is, so the message call must not be specified.
+	#[structopt(long, conflicts_with_all = &["chunked", "dispatch-weight"])]
+	presigned_payload: Option<HexBytes>,
 	/// Message type.
 	#[structopt(subcommand)]
-	message: crate::cli::encode_call::Call,
+	message: Option<crate::cli::encode_call::Call>,
 	/// The origin to use when dispatching the message on the target chain. Defaults to
 	/// `SourceAccount`.
 	#[structopt(long, possible_values = &Origins::variants(), default_value = "Source")]
 	origin: Origins,
+	/// Dispatch messages at the target chain instead of sending them. Reports whether the dispatch
+	/// succeeds and what weight it consumes. Changes made by the dispatch are not saved.
+	#[structopt(long, requires = "target-port")]
+	dry_run: bool,
+	/// Connect to Target node at given host. Only used in the dry-run mode.
+	#[structopt(long, default_value = "127.0.0.1")]
+	target_host: String,
+	/// Connect to Target node websocket server at given port. Only used in the dry-run mode.
+	#[structopt(long)]
+	target_port: Option<u16>,
+	/// Use secure websocket connection to Target node. Only used in the dry-run mode.
+	#[structopt(long)]
+	target_secure: bool,
 }
 
 impl SendMessage {
-	pub fn encode_payload(
+	pub fn encode_payloads(
 		&mut self,
-	) -> anyhow::Result<MessagePayload<AccountId32, MultiSigner, MultiSignature, Vec<u8>>> {
+	) -> anyhow::Result<Vec<MessagePayload<AccountId32, MultiSigner, MultiSignature, Vec<u8>>>> {
 		crate::select_full_bridge!(self.bridge, {
 			let SendMessage {
 				source_sign,
 				target_sign,
+				presigned_payload,
 				ref mut message,
 				dispatch_fee_payment,
 				dispatch_weight,
+				chunked,
+				chunk_size,
 				origin,
 				bridge,
 				..
 			} = self;
 
 			let source_sign = source_sign.to_keypair::<Source>()?;
+			let source_sender_public: MultiSigner = source_sign.public().into();
+			let source_account_id = source_sender_public.into_account();
+
+			if let Some(presigned_payload) = presigned_payload {
+				if message.is_some() {
+					anyhow::bail!("Message call can not be specified along with the presigned payload");
+				}
+
+				return Ok(vec![decode_presigned_payload::<Target>(
+					presigned_payload,
+					&source_account_id,
+					|call, source_account_id, spec_version| {
+						account_ownership_digest(&call, source_account_id, spec_version)
+					},
+				)?]);
+			}
 
+			let message = message
+				.as_mut()
+				.ok_or_else(|| anyhow::format_err!("Either message call or presigned payload must be specified"))?;
 			encode_call::preprocess_call::<Source, Target>(message, bridge.bridge_instance_index());
 			let target_call = Target::encode_call(message)?;
-
-			let payload = {
-				let target_call_weight = prepare_call_dispatch_weight(
-					dispatch_weight,
-					ExplicitOrMaximal::Explicit(Target::get_dispatch_info(&target_call)?.weight),
-					compute_maximal_message_dispatch_weight(Target::max_extrinsic_weight()),
-				);
-				let source_sender_public: MultiSigner = source_sign.public().into();
-				let source_account_id = source_sender_public.into_account();
-
-				message_payload(
-					Target::RUNTIME_VERSION.spec_version,
-					target_call_weight,
-					match origin {
-						Origins::Source => CallOrigin::SourceAccount(source_account_id),
-						Origins::Target => {
-							let target_sign = target_sign.to_keypair::<Target>()?;
-							let digest = account_ownership_digest(
-								&target_call,
-								source_account_id.clone(),
-								Target::RUNTIME_VERSION.spec_version,
-							);
-							let target_origin_public = target_sign.public();
-							let digest_signature = target_sign.sign(&digest);
-							CallOrigin::TargetAccount(
-								source_account_id,
-								target_origin_public.into(),
-								digest_signature.into(),
-							)
-						}
-					},
-					&target_call,
-					*dispatch_fee_payment,
-				)
+			let target_call_weight = prepare_call_dispatch_weight(
+				dispatch_weight,
+				ExplicitOrMaximal::Explicit(Target::get_dispatch_info(&target_call)?.weight),
+				compute_maximal_message_dispatch_weight(Target::max_extrinsic_weight()),
+			);
+
+			let target_calls = if *chunked {
+				let chunk_size = chunk_size.unwrap_or_else(|| {
+					compute_maximal_chunk_size(Source::max_extrinsic_size(), Target::max_extrinsic_size())
+				});
+				split_call_into_chunks::<Target>(&target_call, target_call_weight, chunk_size)?
+			} else {
+				vec![(target_call, target_call_weight)]
 			};
-			Ok(payload)
+
+			target_calls
+				.into_iter()
+				.map(|(target_call, target_call_weight)| -> anyhow::Result<_> {
+					Ok(message_payload(
+						Target::RUNTIME_VERSION.spec_version,
+						target_call_weight,
+						match origin {
+							Origins::Source => CallOrigin::SourceAccount(source_account_id.clone()),
+							Origins::Target => {
+								let target_sign = target_sign.to_keypair::<Target>()?;
+								let digest = account_ownership_digest(
+									&target_call,
+									source_account_id.clone(),
+									Target::RUNTIME_VERSION.spec_version,
+								);
+								target_account_origin(&target_sign, source_account_id.clone(), &digest)
+							}
+						},
+						&target_call,
+						*dispatch_fee_payment,
+					))
+				})
+				.collect()
 		})
 	}
 
 	/// Run the command.
 	pub async fn run(mut self) -> anyhow::Result<()> {
 		crate::select_full_bridge!(self.bridge, {
-			let payload = self.encode_payload()?;
+			let payloads = self.encode_payloads()?;
+
+			if self.dry_run {
+				let dry_run_method = DRY_RUN_MESSAGE_DISPATCH_METHOD.ok_or_else(|| {
+					anyhow::format_err!("Dry run of message dispatch is not supported by {}", Target::NAME)
+				})?;
+				let target_client = TargetConnectionParams {
+					target_host: self.target_host.clone(),
+					target_port: self
+						.target_port
+						.ok_or_else(|| anyhow::format_err!("Target port is required in the dry-run mode"))?,
+					target_secure: self.target_secure,
+					target_max_daily_rpc_requests: None,
+				}
+				.to_client::<Target>()
+				.await?;
+				return dry_run_messages_dispatch(&target_client, dry_run_method, self.lane.clone().into(), payloads)
+					.await;
+			}
 
 			let source_client = self.source.to_client::<Source>().await?;
+			ensure_messages_pallet_accepts_outbound_messages::<Source, SourceMessagesInstance>(&source_client).await?;
+
 			let source_sign = self.source_sign.to_keypair::<Source>()?;
+			let source_genesis_hash = *source_client.genesis_hash();
 
 			let lane = self.lane.clone().into();
-			let fee = match self.fee {
-				Some(fee) => fee,
-				None => Balance(
-					estimate_message_delivery_and_dispatch_fee::<<Source as Chain>::Balance, _, _>(
-						&source_client,
-						ESTIMATE_MESSAGE_FEE_METHOD,
-						lane,
-						payload.clone(),
-					)
-					.await? as _,
-				),
-			};
-			let dispatch_weight = payload.weight;
-			let send_message_call = Source::encode_call(&encode_call::Call::BridgeSendMessage {
-				bridge_instance_index: self.bridge.bridge_instance_index(),
-				lane: self.lane,
-				payload: HexBytes::encode(&payload),
-				fee,
-			})?;
-
-			let source_genesis_hash = *source_client.genesis_hash();
-			source_client
-				.submit_signed_extrinsic(source_sign.public().into(), move |transaction_nonce| {
-					let signed_source_call = Source::sign_transaction(
-						source_genesis_hash,
-						&source_sign,
-						transaction_nonce,
-						send_message_call,
-					)
-					.encode();
-
-					log::info!(
-						target: "bridge",
-						"Sending message to {}. Size: {}. Dispatch weight: {}. Fee: {}",
-						Target::NAME,
-						signed_source_call.len(),
-						dispatch_weight,
-						fee,
-					);
-					log::info!(
-						target: "bridge",
-						"Signed {} Call: {:?}",
-						Source::NAME,
-						HexBytes::encode(&signed_source_call)
-					);
-
-					Bytes(signed_source_call)
-				})
-				.await?;
+			let messages_count = payloads.len();
+			for (message_index, payload) in payloads.into_iter().enumerate() {
+				let fee = match self.fee {
+					Some(fee) => fee,
+					None => Balance(
+						estimate_message_delivery_and_dispatch_fee::<<Source as Chain>::Balance, _, _>(
+							&source_client,
+							ESTIMATE_MESSAGE_FEE_METHOD,
+							lane,
+							payload.clone(),
+						)
+						.await? as _,
+					),
+				};
+				let dispatch_weight = payload.weight;
+				let send_message_call = Source::encode_call(&encode_call::Call::BridgeSendMessage {
+					bridge_instance_index: self.bridge.bridge_instance_index(),
+					lane: self.lane.clone(),
+					payload: HexBytes::encode(&payload),
+					fee,
+				})?;
+
+				let source_sign = source_sign.clone();
+				source_client
+					.submit_signed_extrinsic(source_sign.public().into(), move |transaction_nonce| {
+						let signed_source_call = Source::sign_transaction(
+							source_genesis_hash,
+							&source_sign,
+							transaction_nonce,
+							send_message_call,
+						)
+						.encode();
+
+						log::info!(
+							target: "bridge",
+							"Sending message {} of {} to {}. Size: {}. Dispatch weight: {}. Fee: {}",
+							message_index + 1,
+							messages_count,
+							Target::NAME,
+							signed_source_call.len(),
+							dispatch_weight,
+							fee,
+						);
+						log::info!(
+							target: "bridge",
+							"Signed {} Call: {:?}",
+							Source::NAME,
+							HexBytes::encode(&signed_source_call)
+						);
+
+						Bytes(signed_source_call)
+					})
+					.await?;
+			}
 		});
 
 		Ok(())
 	}
 }
 
-fn prepare_call_dispatch_weight(
+/// Dispatch messages at the target chain without saving dispatch results.
+///
+/// Returns error if dispatch of any message fails.
+async fn dry_run_messages_dispatch<C: Chain, P: Encode>(
+	client: &Client<C>,
+	dry_run_method: &str,
+	lane: LaneId,
+	payloads: Vec<MessagePayload<AccountId32, MultiSigner, MultiSignature, P>>,
+) -> anyhow::Result<()> {
+	let messages_count = payloads.len();
+	let mut failed_messages = 0;
+	for (message_index, payload) in payloads.into_iter().enumerate() {
+		let declared_weight = payload.weight;
+		let encoded_response = client
+			.state_call(dry_run_method.into(), (lane, payload.encode()).encode().into(), None)
+			.await?;
+		let dispatch_result: MessageDispatchResult =
+			Decode::decode(&mut &encoded_response.0[..]).map_err(relay_substrate_client::Error::ResponseParseFailed)?;
+		if !dispatch_result.dispatch_result {
+			failed_messages += 1;
+		}
+
+		println!(
+			"{}",
+			dry_run_report(
+				message_index,
+				messages_count,
+				C::NAME,
+				declared_weight,
+				&dispatch_result
+			),
+		);
+	}
+
+	if failed_messages != 0 {
+		anyhow::bail!(
+			"Dispatch of {} of {} messages has failed at {}",
+			failed_messages,
+			messages_count,
+			C::NAME
+		);
+	}
+
+	Ok(())
+}
+
+/// Format dry-run result of the single message dispatch.
+fn dry_run_report(
+	message_index: usize,
+	messages_count: usize,
+	target_chain: &str,
+	declared_weight: Weight,
+	dispatch_result: &MessageDispatchResult,
+) -> String {
+	format!(
+		"Message {} of {}: dispatch at {} has {}. Declared dispatch weight: {}. Actual dispatch weight: {}",
+		message_index + 1,
+		messages_count,
+		target_chain,
+		if dispatch_result.dispatch_result {
+			"succeeded"
+		} else {
+			"failed"
+		},
+		declared_weight,
+		declared_weight.saturating_sub(dispatch_result.unspent_weight),
+	)
+}
+
+pub(crate) fn prepare_call_dispatch_weight(
 	user_specified_dispatch_weight: &Option<ExplicitOrMaximal<Weight>>,
 	weight_from_pre_dispatch_call: ExplicitOrMaximal<Weight>,
 	maximal_allowed_weight: Weight,
@@ -225,6 +375,91 @@ fn prepare_call_dispatch_weight(
 	}
 }
 
+/// Sign the account ownership digest with the target account key and return `TargetAccount`
+/// call origin.
+pub(crate) fn target_account_origin<TPair: Pair>(
+	target_sign: &TPair,
+	source_account_id: AccountId32,
+	digest: &[u8],
+) -> CallOrigin<AccountId32, MultiSigner, MultiSignature>
+where
+	TPair::Public: Into<MultiSigner>,
+	TPair::Signature: Into<MultiSignature>,
+{
+	CallOrigin::TargetAccount(
+		source_account_id,
+		target_sign.public().into(),
+		target_sign.sign(digest).into(),
+	)
+}
+
+/// Decode payload, signed by the target account key, and check that it may be sent by the
+/// `source_account_id`.
+///
+/// The `account_ownership_digest` is called with the encoded target call, source account id and
+/// target runtime spec version.
+fn decode_presigned_payload<Target: CliChain>(
+	presigned_payload: &HexBytes,
+	source_account_id: &AccountId32,
+	account_ownership_digest: impl Fn(EncodedCall, AccountId32, u32) -> Vec<u8>,
+) -> anyhow::Result<MessagePayload<AccountId32, MultiSigner, MultiSignature, Vec<u8>>> {
+	let payload: MessagePayload<AccountId32, MultiSigner, MultiSignature, Vec<u8>> =
+		Decode::decode(&mut &presigned_payload.0[..])
+			.map_err(|e| anyhow::format_err!("Failed to decode presigned message payload: {:?}", e))?;
+
+	let (payload_source_account_id, target_origin_public, digest_signature) = match payload.origin {
+		CallOrigin::TargetAccount(ref source_account_id, ref public, ref signature) => {
+			(source_account_id, public, signature)
+		}
+		ref origin => anyhow::bail!(
+			"Presigned message payload must have TargetAccount origin. Actual origin: {:?}",
+			origin,
+		),
+	};
+	if payload_source_account_id != source_account_id {
+		anyhow::bail!(
+			"Presigned message payload may only be sent by {}. Source signer account: {}",
+			payload_source_account_id,
+			source_account_id,
+		);
+	}
+	if payload.spec_version != Target::RUNTIME_VERSION.spec_version {
+		log::warn!(
+			target: "bridge",
+			"Presigned message payload is signed for {} runtime version {}. Current version: {}",
+			Target::NAME,
+			payload.spec_version,
+			Target::RUNTIME_VERSION.spec_version,
+		);
+	}
+
+	let digest = account_ownership_digest(
+		EncodedCall(&payload.call),
+		source_account_id.clone(),
+		payload.spec_version,
+	);
+	if !digest_signature.verify(&digest[..], &target_origin_public.clone().into_account()) {
+		anyhow::bail!("Presigned message payload has invalid signature");
+	}
+
+	log::info!(target: "bridge", "Decoded presigned Message Payload: {:#?}", payload);
+
+	Ok(payload)
+}
+
+/// Already encoded call that is encoded as is, without length prefix.
+struct EncodedCall<'a>(&'a [u8]);
+
+impl Encode for EncodedCall<'_> {
+	fn size_hint(&self) -> usize {
+		self.0.len()
+	}
+
+	fn encode_to<T: codec::Output + ?Sized>(&self, dest: &mut T) {
+		dest.write(self.0)
+	}
+}
+
 pub(crate) fn message_payload<SAccountId, TPublic, TSignature>(
 	spec_version: u32,
 	weight: Weight,
@@ -266,6 +501,61 @@ where
 	}
 }
 
+/// Split the encoded call into chunks and wrap every chunk into the `submit_chunk` call of the
+/// chunked dispatch pallet.
+///
+/// Returns target chain calls along with their dispatch weights. The weight of the last chunk
+/// call includes the `call_weight`.
+fn split_call_into_chunks<Target: CliEncodeCall>(
+	call: &Target::Call,
+	call_weight: Weight,
+	chunk_size: u32,
+) -> anyhow::Result<Vec<(Target::Call, Weight)>> {
+	if chunk_size == 0 {
+		anyhow::bail!("Chunk size must be positive");
+	}
+
+	let encoded_call = call.encode();
+	let call_hash = HasherOf::<Target>::hash(&encoded_call);
+	let chunks = encoded_call.chunks(chunk_size as usize).collect::<Vec<_>>();
+	let chunks_count = chunks.len() as u32;
+
+	log::info!(
+		target: "bridge",
+		"Splitting {} call of {} bytes into {} chunks. Call hash: {:?}",
+		Target::NAME,
+		encoded_call.len(),
+		chunks_count,
+		call_hash,
+	);
+
+	chunks
+		.into_iter()
+		.enumerate()
+		.map(|(chunk_index, chunk)| -> anyhow::Result<_> {
+			let chunk_call = Target::encode_call(&encode_call::Call::SubmitCallChunk {
+				call_hash: HexBytes::encode(&call_hash),
+				chunks_count,
+				chunk_index: chunk_index as u32,
+				chunk: HexBytes(chunk.to_vec()),
+				call_weight,
+			})?;
+			let chunk_call_weight = Target::get_dispatch_info(&chunk_call)?.weight;
+			Ok((chunk_call, chunk_call_weight))
+		})
+		.collect()
+}
+
+fn compute_maximal_chunk_size(maximal_source_extrinsic_size: u32, maximal_target_extrinsic_size: u32) -> u32 {
+	// assume that the message payload fields and `submit_chunk` arguments (except the chunk itself) fit 1KB
+	let service_chunk_bytes = 1024;
+	bridge_runtime_common::messages::source::maximal_message_arguments_size(
+		maximal_source_extrinsic_size,
+		maximal_target_extrinsic_size,
+	)
+	.saturating_sub(service_chunk_bytes)
+}
+
 pub(crate) fn compute_maximal_message_dispatch_weight(maximal_extrinsic_weight: Weight) -> Weight {
 	bridge_runtime_common::messages::target::maximal_incoming_message_dispatch_weight(maximal_extrinsic_weight)
 }
@@ -291,7 +581,9 @@ mod tests {
 		]);
 
 		// when
-		let payload = send_message.encode_payload().unwrap();
+		let mut payloads = send_message.encode_payloads().unwrap();
+		assert_eq!(payloads.len(), 1);
+		let payload = payloads.remove(0);
 
 		// then
 		assert_eq!(
@@ -326,7 +618,9 @@ mod tests {
 		]);
 
 		// when
-		let payload = send_message.encode_payload().unwrap();
+		let mut payloads = send_message.encode_payloads().unwrap();
+		assert_eq!(payloads.len(), 1);
+		let payload = payloads.remove(0);
 
 		// then
 		// Since signatures are randomized we extract it from here and only check the rest.
@@ -386,7 +680,9 @@ mod tests {
 		]);
 
 		// when
-		let payload = send_message.encode_payload().unwrap();
+		let mut payloads = send_message.encode_payloads().unwrap();
+		assert_eq!(payloads.len(), 1);
+		let payload = payloads.remove(0);
 
 		// then
 		assert_eq!(
@@ -394,4 +690,148 @@ mod tests {
 			bp_runtime::messages::DispatchFeePayment::AtTargetChain
 		);
 	}
+
+	#[test]
+	fn send_chunked_remark_rialto_to_millau() {
+		// given
+		let mut send_message = SendMessage::from_iter(vec![
+			"send-message",
+			"rialto-to-millau",
+			"--source-port",
+			"1234",
+			"--source-signer",
+			"//Alice",
+			"--chunked",
+			"--chunk-size",
+			"256",
+			"remark",
+			"--remark-size",
+			"1000",
+		]);
+
+		// when
+		let payloads = send_message.encode_payloads().unwrap();
+
+		// then
+		let remark_call = millau_runtime::Call::System(millau_runtime::SystemCall::remark(vec![0; 1000])).encode();
+		assert_eq!(payloads.len(), 4);
+
+		let mut reassembled_call = Vec::new();
+		for (index, payload) in payloads.iter().enumerate() {
+			match millau_runtime::Call::decode(&mut &payload.call[..]).unwrap() {
+				millau_runtime::Call::BridgeChunkedDispatch(millau_runtime::ChunkedDispatchCall::submit_chunk(
+					call_hash,
+					chunks_count,
+					chunk_index,
+					chunk,
+					_,
+				)) => {
+					assert_eq!(call_hash, sp_runtime::traits::BlakeTwo256::hash(&remark_call));
+					assert_eq!(chunks_count, 4);
+					assert_eq!(chunk_index, index as u32);
+					reassembled_call.extend(chunk);
+				}
+				call => panic!("Unexpected call: {:?}", call),
+			}
+		}
+		assert_eq!(reassembled_call, remark_call);
+		assert!(payloads[3].weight > payloads[0].weight);
+	}
+
+	#[test]
+	fn rejects_chunk_size_without_chunked_flag() {
+		// when
+		let err = SendMessage::from_iter_safe(vec![
+			"send-message",
+			"rialto-to-millau",
+			"--source-port",
+			"1234",
+			"--source-signer",
+			"//Alice",
+			"--chunk-size",
+			"256",
+			"remark",
+		]);
+
+		// then
+		assert_eq!(
+			err.err().map(|e| e.kind),
+			Some(structopt::clap::ErrorKind::MissingRequiredArgument)
+		);
+	}
+
+	#[test]
+	fn rejects_dry_run_without_target_port() {
+		// when
+		let err = SendMessage::from_iter_safe(vec![
+			"send-message",
+			"rialto-to-millau",
+			"--source-port",
+			"1234",
+			"--source-signer",
+			"//Alice",
+			"--dry-run",
+			"remark",
+		]);
+
+		// then
+		assert_eq!(
+			err.err().map(|e| e.kind),
+			Some(structopt::clap::ErrorKind::MissingRequiredArgument)
+		);
+	}
+
+	#[test]
+	fn accepts_dry_run_with_target_port() {
+		// when
+		let send_message = SendMessage::from_iter(vec![
+			"send-message",
+			"rialto-to-millau",
+			"--source-port",
+			"1234",
+			"--source-signer",
+			"//Alice",
+			"--dry-run",
+			"--target-port",
+			"4321",
+			"remark",
+		]);
+
+		// then
+		assert!(send_message.dry_run);
+		assert_eq!(send_message.target_host, "127.0.0.1");
+		assert_eq!(send_message.target_port, Some(4321));
+	}
+
+	#[test]
+	fn dry_run_report_contains_actual_dispatch_weight() {
+		assert_eq!(
+			dry_run_report(
+				0,
+				2,
+				"Millau",
+				1000,
+				&MessageDispatchResult {
+					dispatch_result: true,
+					unspent_weight: 400,
+					dispatch_fee_paid_during_dispatch: false,
+				},
+			),
+			"Message 1 of 2: dispatch at Millau has succeeded. Declared dispatch weight: 1000. Actual dispatch weight: 600",
+		);
+		assert_eq!(
+			dry_run_report(
+				1,
+				2,
+				"Millau",
+				1000,
+				&MessageDispatchResult {
+					dispatch_result: false,
+					unspent_weight: 1000,
+					dispatch_fee_paid_during_dispatch: false,
+				},
+			),
+			"Message 2 of 2: dispatch at Millau has failed. Declared dispatch weight: 1000. Actual dispatch weight: 0",
+		);
+	}
 }
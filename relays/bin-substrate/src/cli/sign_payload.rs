@@ -0,0 +1,198 @@
+// Copyright 2019-2021 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+use crate::cli::bridge::FullBridge;
+use crate::cli::encode_call::{self, CliEncodeCall};
+use crate::cli::send_message::{
+	compute_maximal_message_dispatch_weight, message_payload, prepare_call_dispatch_weight, target_account_origin,
+	DispatchFeePayment,
+};
+use crate::cli::{AccountId, CliChain, ExplicitOrMaximal, HexBytes, TargetSigningParams};
+use crate::select_full_bridge;
+use frame_support::weights::Weight;
+use structopt::StructOpt;
+use strum::VariantNames;
+
+/// Sign message payload with the target account key.
+///
+/// The command doesn't connect to any node, so it may be used at the offline machine. The resulting
+/// payload may then be sent by the source account with `send-message --presigned-payload`.
+#[derive(StructOpt)]
+pub struct SignPayload {
+	/// A bridge instance to sign payload for.
+	#[structopt(possible_values = FullBridge::VARIANTS, case_insensitive = true)]
+	bridge: FullBridge,
+	#[structopt(flatten)]
+	target_sign: TargetSigningParams,
+	/// SS58 encoded Source account that will send the message.
+	#[structopt(long)]
+	sender: AccountId,
+	/// Where dispatch fee is paid?
+	#[structopt(
+		long,
+		possible_values = DispatchFeePayment::VARIANTS,
+		case_insensitive = true,
+		default_value = "at-source-chain",
+	)]
+	dispatch_fee_payment: DispatchFeePayment,
+	/// Dispatch weight of the message. If not passed, determined automatically.
+	#[structopt(long)]
+	dispatch_weight: Option<ExplicitOrMaximal<Weight>>,
+	/// Message type.
+	#[structopt(subcommand)]
+	message: crate::cli::encode_call::Call,
+}
+
+impl SignPayload {
+	/// Encode and sign message payload.
+	pub fn encode(&mut self) -> anyhow::Result<HexBytes> {
+		select_full_bridge!(self.bridge, {
+			let target_sign = self.target_sign.to_keypair::<Target>()?;
+
+			self.sender.enforce_chain::<Source>();
+			let source_account_id = self.sender.raw_id();
+
+			encode_call::preprocess_call::<Source, Target>(&mut self.message, self.bridge.bridge_instance_index());
+			let target_call = Target::encode_call(&self.message)?;
+			let target_call_weight = prepare_call_dispatch_weight(
+				&self.dispatch_weight,
+				ExplicitOrMaximal::Explicit(Target::get_dispatch_info(&target_call)?.weight),
+				compute_maximal_message_dispatch_weight(Target::max_extrinsic_weight()),
+			);
+
+			let digest = account_ownership_digest(
+				&target_call,
+				source_account_id.clone(),
+				Target::RUNTIME_VERSION.spec_version,
+			);
+			let payload = message_payload(
+				Target::RUNTIME_VERSION.spec_version,
+				target_call_weight,
+				target_account_origin(&target_sign, source_account_id, &digest),
+				&target_call,
+				self.dispatch_fee_payment,
+			);
+
+			Ok(HexBytes::encode(&payload))
+		})
+	}
+
+	/// Run the command.
+	pub async fn run(mut self) -> anyhow::Result<()> {
+		let payload = self.encode()?;
+		println!("{:?}", payload);
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::cli::send_message::SendMessage;
+	use bp_message_dispatch::{CallOrigin, MessagePayload};
+	use codec::Decode;
+	use sp_core::crypto::Ss58Codec;
+	use sp_runtime::{AccountId32, MultiSignature, MultiSigner};
+
+	fn sign_payload(sender: sp_keyring::AccountKeyring) -> HexBytes {
+		let sender = sender.to_account_id().to_ss58check();
+		let mut sign_payload = SignPayload::from_iter(vec![
+			"sign-payload",
+			"millau-to-rialto",
+			"--sender",
+			&sender,
+			"--target-signer",
+			"//Bob",
+			"remark",
+			"--remark-payload",
+			"1234",
+		]);
+		sign_payload.encode().unwrap()
+	}
+
+	fn send_presigned_payload(
+		payload: &HexBytes,
+	) -> anyhow::Result<Vec<MessagePayload<AccountId32, MultiSigner, MultiSignature, Vec<u8>>>> {
+		let payload = format!("{:?}", payload);
+		let mut send_message = SendMessage::from_iter(vec![
+			"send-message",
+			"millau-to-rialto",
+			"--source-port",
+			"1234",
+			"--source-signer",
+			"//Alice",
+			"--presigned-payload",
+			&payload,
+		]);
+		send_message.encode_payloads()
+	}
+
+	#[test]
+	fn signed_payload_has_target_account_origin() {
+		// when
+		let payload = sign_payload(sp_keyring::AccountKeyring::Alice);
+
+		// then
+		let payload: MessagePayload<AccountId32, MultiSigner, MultiSignature, Vec<u8>> =
+			Decode::decode(&mut &payload.0[..]).unwrap();
+		match payload.origin {
+			CallOrigin::TargetAccount(source_account_id, target_origin_public, _) => {
+				assert_eq!(source_account_id, sp_keyring::AccountKeyring::Alice.to_account_id());
+				assert_eq!(target_origin_public, sp_keyring::AccountKeyring::Bob.into());
+			}
+			origin => panic!("Unexpected `CallOrigin`: {:?}", origin),
+		}
+	}
+
+	#[test]
+	fn presigned_payload_is_sent_as_is() {
+		// given
+		let payload = sign_payload(sp_keyring::AccountKeyring::Alice);
+
+		// when
+		let payloads = send_presigned_payload(&payload).unwrap();
+
+		// then
+		assert_eq!(payloads.len(), 1);
+		assert_eq!(HexBytes::encode(&payloads[0]).0, payload.0);
+	}
+
+	#[test]
+	fn presigned_payload_is_rejected_if_signed_for_other_sender() {
+		// given
+		let payload = sign_payload(sp_keyring::AccountKeyring::Charlie);
+
+		// when
+		let result = send_presigned_payload(&payload);
+
+		// then
+		assert!(result.is_err());
+	}
+
+	#[test]
+	fn presigned_payload_is_rejected_if_signature_is_invalid() {
+		// given
+		let mut payload = sign_payload(sp_keyring::AccountKeyring::Alice);
+		let last_byte = payload.0.len() - 1;
+		payload.0[last_byte] ^= 1;
+
+		// when
+		let result = send_presigned_payload(&payload);
+
+		// then
+		assert!(result.is_err());
+	}
+}
@@ -0,0 +1,369 @@
+// Copyright 2019-2021 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+use crate::cli::Balance;
+use crate::messages_lane::select_delivery_transaction_limits;
+
+use bp_messages::{MessageNonce, UnrewardedRelayersState};
+use bp_runtime::PreComputedSize;
+use frame_support::{
+	traits::Get,
+	weights::{constants::RocksDbWeight, Weight},
+};
+use pallet_bridge_messages::WeightInfoExt;
+use std::{collections::VecDeque, io::Write, path::PathBuf};
+use structopt::StructOpt;
+use strum::{EnumString, EnumVariantNames, VariantNames};
+
+// TODO: use chain-specific weights after https://github.com/paritytech/parity-bridges-common/issues/390
+type SimulationWeights = pallet_bridge_messages::weights::RialtoWeight<rialto_runtime::Runtime>;
+
+/// Distribution of the number of messages, sent in every source block.
+#[derive(Debug, EnumString, EnumVariantNames, Clone, Copy, PartialEq, Eq)]
+#[strum(serialize_all = "kebab_case")]
+pub enum RateDistribution {
+	/// Exactly `messages-per-block` messages are sent in every block.
+	Constant,
+	/// From zero to `2 * messages-per-block` messages are sent in every block.
+	Uniform,
+	/// `messages-per-block` messages are sent in every block on average.
+	Poisson,
+}
+
+/// Simulate message lane throughput, backlog and relayer costs without any chains.
+///
+/// The simulation uses messages pallet weights and prints CSV with the lane state
+/// at every target chain block.
+#[derive(StructOpt, Debug, PartialEq)]
+pub struct SimulateBridge {
+	/// Number of seconds to simulate.
+	#[structopt(long, default_value = "86400")]
+	duration: u64,
+	/// Source chain block time in seconds.
+	#[structopt(long, default_value = "6")]
+	source_block_time: u64,
+	/// Target chain block time in seconds.
+	#[structopt(long, default_value = "6")]
+	target_block_time: u64,
+	/// Delay (in seconds) between the block production and its finalization at the bridged chain.
+	#[structopt(long, default_value = "30")]
+	finality_lag: u64,
+	/// Average number of messages, sent in every source block.
+	#[structopt(long, default_value = "1")]
+	messages_per_block: f64,
+	/// Distribution of the number of messages, sent in every source block.
+	#[structopt(long, possible_values = RateDistribution::VARIANTS, case_insensitive = true, default_value = "poisson")]
+	rate_distribution: RateDistribution,
+	/// Minimal size of the message payload.
+	#[structopt(long, default_value = "128")]
+	min_message_size: u32,
+	/// Maximal size of the message payload.
+	#[structopt(long, default_value = "1024")]
+	max_message_size: u32,
+	/// Dispatch weight of every message.
+	#[structopt(long, default_value = "0")]
+	dispatch_weight: Weight,
+	/// Maximal number of unconfirmed messages at the inbound lane.
+	#[structopt(long, default_value = "128")]
+	max_unconfirmed_messages: MessageNonce,
+	/// Maximal weight of the delivery transaction.
+	#[structopt(long)]
+	max_extrinsic_weight: Option<Weight>,
+	/// Fee that is paid for single unit of weight at the target chain.
+	#[structopt(long, default_value = "1")]
+	target_fee_per_weight: Balance,
+	/// Fee that is paid for single unit of weight at the source chain.
+	#[structopt(long, default_value = "1")]
+	source_fee_per_weight: Balance,
+	/// Seed of the random number generator.
+	#[structopt(long, default_value = "42")]
+	seed: u64,
+	/// Path to the output CSV file. The CSV is printed to stdout if not specified.
+	#[structopt(long)]
+	output: Option<PathBuf>,
+}
+
+/// State of the lane at the end of the target block.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+struct SimulationRow {
+	/// Seconds since simulation start.
+	time: u64,
+	/// Total number of messages, sent at the source chain.
+	generated: MessageNonce,
+	/// Total number of messages, delivered to the target chain.
+	delivered: MessageNonce,
+	/// Total number of messages, which delivery has been confirmed at the source chain.
+	confirmed: MessageNonce,
+	/// Number of messages, waiting for delivery.
+	backlog: MessageNonce,
+	/// Number of delivered, but not yet confirmed messages.
+	unconfirmed: MessageNonce,
+	/// Total number of delivery transactions.
+	delivery_transactions: u64,
+	/// Total number of confirmation transactions.
+	confirmation_transactions: u64,
+	/// Total cost of all delivery and confirmation transactions.
+	relayer_cost: u128,
+}
+
+impl SimulateBridge {
+	/// Run the command.
+	pub async fn run(self) -> anyhow::Result<()> {
+		if self.source_block_time == 0 || self.target_block_time == 0 {
+			anyhow::bail!("Block time must be positive");
+		}
+		if self.min_message_size > self.max_message_size {
+			anyhow::bail!("Minimal message size must not exceed maximal message size");
+		}
+
+		let rows = self.simulate::<SimulationWeights>();
+		let mut output: Box<dyn Write> = match self.output {
+			Some(ref path) => Box::new(std::fs::File::create(path)?),
+			None => Box::new(std::io::stdout()),
+		};
+		writeln!(
+			output,
+			"time,generated,delivered,confirmed,backlog,unconfirmed,delivery_transactions,confirmation_transactions,relayer_cost",
+		)?;
+		for row in rows {
+			writeln!(
+				output,
+				"{},{},{},{},{},{},{},{},{}",
+				row.time,
+				row.generated,
+				row.delivered,
+				row.confirmed,
+				row.backlog,
+				row.unconfirmed,
+				row.delivery_transactions,
+				row.confirmation_transactions,
+				row.relayer_cost,
+			)?;
+		}
+
+		Ok(())
+	}
+
+	/// Simulate the lane and return its state at every target block.
+	fn simulate<W: WeightInfoExt>(&self) -> Vec<SimulationRow> {
+		let max_extrinsic_weight = self
+			.max_extrinsic_weight
+			.unwrap_or_else(bp_rialto::max_extrinsic_weight);
+		let (max_messages_in_batch, max_dispatch_weight_in_batch) =
+			select_delivery_transaction_limits::<W>(max_extrinsic_weight, self.max_unconfirmed_messages);
+
+		let mut rng = XorShiftRng(self.seed.max(1));
+		let mut row = SimulationRow::default();
+		let mut rows = Vec::new();
+		// (sent at, message size)
+		let mut backlog = VecDeque::new();
+		// (delivered at, number of messages)
+		let mut unconfirmed = VecDeque::new();
+
+		for time in 0..self.duration {
+			if time % self.source_block_time == 0 {
+				// new messages are sent
+				let messages = self.messages_in_block(&mut rng);
+				for _ in 0..messages {
+					let size = rng.next_in_range(self.min_message_size as u64, self.max_message_size as u64) as u32;
+					backlog.push_back((time, size));
+				}
+				row.generated += messages;
+
+				// delivery of all finalized target blocks is confirmed by the single transaction
+				let mut confirmed_messages = 0;
+				while let Some((delivered_at, messages)) = unconfirmed.front().cloned() {
+					if delivered_at + self.finality_lag > time {
+						break;
+					}
+					confirmed_messages += messages;
+					unconfirmed.pop_front();
+				}
+				if confirmed_messages != 0 {
+					let relayers_state = UnrewardedRelayersState {
+						unrewarded_relayer_entries: 1,
+						messages_in_oldest_entry: confirmed_messages,
+						total_messages: confirmed_messages,
+					};
+					let weight = W::receive_messages_delivery_proof_weight(
+						&PreComputedSize(W::expected_extra_storage_proof_size() as usize),
+						&relayers_state,
+						RocksDbWeight::get(),
+					);
+					row.confirmed += confirmed_messages;
+					row.unconfirmed -= confirmed_messages;
+					row.confirmation_transactions += 1;
+					row.relayer_cost = row
+						.relayer_cost
+						.saturating_add((weight as u128).saturating_mul(self.source_fee_per_weight.0));
+				}
+			}
+
+			if time % self.target_block_time == 0 {
+				// single delivery transaction brings all finalized messages that fit the limits
+				let free_slots = self.max_unconfirmed_messages.saturating_sub(row.unconfirmed);
+				let mut messages = 0;
+				let mut proof_size = W::expected_extra_storage_proof_size();
+				let mut dispatch_weight: Weight = 0;
+				while let Some((sent_at, size)) = backlog.front().cloned() {
+					let limits_reached = messages >= std::cmp::min(max_messages_in_batch, free_slots)
+						|| dispatch_weight.saturating_add(self.dispatch_weight) > max_dispatch_weight_in_batch;
+					if sent_at + self.finality_lag > time || limits_reached {
+						break;
+					}
+					messages += 1;
+					proof_size = proof_size.saturating_add(size);
+					dispatch_weight = dispatch_weight.saturating_add(self.dispatch_weight);
+					backlog.pop_front();
+				}
+				if messages != 0 {
+					let weight = W::receive_messages_proof_weight(proof_size, 1, messages as u32, dispatch_weight);
+					unconfirmed.push_back((time, messages));
+					row.delivered += messages;
+					row.unconfirmed += messages;
+					row.delivery_transactions += 1;
+					row.relayer_cost = row
+						.relayer_cost
+						.saturating_add((weight as u128).saturating_mul(self.target_fee_per_weight.0));
+				}
+
+				row.time = time;
+				row.backlog = backlog.len() as MessageNonce;
+				rows.push(row.clone());
+			}
+		}
+
+		rows
+	}
+
+	/// Returns number of messages, sent in the source block.
+	fn messages_in_block(&self, rng: &mut XorShiftRng) -> MessageNonce {
+		let mean = self.messages_per_block.max(0.0);
+		match self.rate_distribution {
+			RateDistribution::Constant => mean.round() as MessageNonce,
+			RateDistribution::Uniform => rng.next_in_range(0, (2.0 * mean).round() as u64),
+			RateDistribution::Poisson => {
+				// Knuth's algorithm is fine for the small mean values we're expecting here
+				let limit = (-mean).exp();
+				let mut messages = 0;
+				let mut product = rng.next_f64();
+				while product > limit {
+					messages += 1;
+					product *= rng.next_f64();
+				}
+				messages
+			}
+		}
+	}
+}
+
+/// Simple pseudo-random numbers generator, so that simulation results are reproducible.
+struct XorShiftRng(u64);
+
+impl XorShiftRng {
+	/// Returns next pseudo-random number.
+	fn next_u64(&mut self) -> u64 {
+		self.0 ^= self.0 << 13;
+		self.0 ^= self.0 >> 7;
+		self.0 ^= self.0 << 17;
+		self.0
+	}
+
+	/// Returns next pseudo-random number in `[0; 1)` range.
+	fn next_f64(&mut self) -> f64 {
+		(self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+	}
+
+	/// Returns next pseudo-random number in `[min; max]` range.
+	fn next_in_range(&mut self, min: u64, max: u64) -> u64 {
+		min + self.next_u64() % (max - min + 1)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn simulate(args: Vec<&str>) -> Vec<SimulationRow> {
+		SimulateBridge::from_iter(vec!["simulate-bridge"].into_iter().chain(args)).simulate::<SimulationWeights>()
+	}
+
+	#[test]
+	fn should_parse_cli_options() {
+		// when
+		let res = SimulateBridge::from_iter(vec![
+			"simulate-bridge",
+			"--duration",
+			"3600",
+			"--messages-per-block",
+			"2.5",
+			"--rate-distribution",
+			"uniform",
+			"--dispatch-weight",
+			"1000",
+		]);
+
+		// then
+		assert_eq!(res.duration, 3600);
+		assert!((res.messages_per_block - 2.5).abs() < f64::EPSILON);
+		assert_eq!(res.rate_distribution, RateDistribution::Uniform);
+		assert_eq!(res.dispatch_weight, 1000);
+		assert_eq!(res.max_extrinsic_weight, None);
+		assert_eq!(res.output, None);
+	}
+
+	#[test]
+	fn lane_keeps_up_with_low_message_rate() {
+		let rows = simulate(vec!["--duration", "3600", "--rate-distribution", "constant"]);
+
+		// row is produced for every target block
+		assert_eq!(rows.len(), 600);
+		// all messages are delivered after finality lag
+		let last_row = rows.last().unwrap();
+		assert_eq!(last_row.generated, 600);
+		assert!(last_row.backlog <= 6);
+		assert_eq!(last_row.delivered + last_row.backlog, last_row.generated);
+		assert_eq!(last_row.confirmed + last_row.unconfirmed, last_row.delivered);
+		assert!(last_row.relayer_cost > 0);
+	}
+
+	#[test]
+	fn backlog_grows_if_lane_is_overloaded() {
+		let rows = simulate(vec![
+			"--duration",
+			"3600",
+			"--rate-distribution",
+			"constant",
+			"--messages-per-block",
+			"64",
+			"--max-unconfirmed-messages",
+			"16",
+		]);
+
+		let last_row = rows.last().unwrap();
+		assert!(last_row.backlog > 1000);
+		assert!(last_row.unconfirmed <= 16);
+	}
+
+	#[test]
+	fn simulation_is_reproducible() {
+		assert_eq!(simulate(vec!["--duration", "600"]), simulate(vec!["--duration", "600"]));
+		assert_ne!(
+			simulate(vec!["--duration", "600"]),
+			simulate(vec!["--duration", "600", "--seed", "7"])
+		);
+	}
+}
@@ -0,0 +1,445 @@
+// Copyright 2019-2021 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+use crate::cli::bridge::FullBridge;
+use crate::cli::encode_call::{self, CliEncodeCall};
+use crate::cli::preflight::{self, ConfigurationErrors};
+use crate::cli::{Balance, CliChain, HexBytes, HexLaneId, SourceConnectionParams, TargetConnectionParams};
+use crate::select_full_bridge;
+use bp_messages::MessageNonce;
+use codec::Encode;
+use relay_substrate_client::{Chain, Client};
+use sp_core::storage::StorageKey;
+use sp_runtime::{traits::Zero, FixedU128};
+use structopt::StructOpt;
+use strum::VariantNames;
+
+/// Name of the messages pallet constant with maximal number of unrewarded relayer entries.
+const MAX_UNREWARDED_RELAYER_ENTRIES_CONSTANT_NAME: &str = "MaxUnrewardedRelayerEntriesAtInboundLane";
+/// Name of the messages pallet constant with maximal number of unconfirmed messages.
+const MAX_UNCONFIRMED_MESSAGES_CONSTANT_NAME: &str = "MaxUnconfirmedMessagesAtInboundLane";
+
+/// Connect to both chains and verify that their runtimes are compatible with the relay and
+/// with each other.
+#[derive(StructOpt, Debug, PartialEq, Eq)]
+pub struct VerifyChainCompatibility {
+	/// A bridge instance to verify.
+	#[structopt(possible_values = FullBridge::VARIANTS, case_insensitive = true)]
+	bridge: FullBridge,
+	/// Hex-encoded identifiers of lanes that must be registered at both chains.
+	#[structopt(long)]
+	lane: Vec<HexLaneId>,
+	#[structopt(flatten)]
+	source: SourceConnectionParams,
+	#[structopt(flatten)]
+	target: TargetConnectionParams,
+}
+
+impl VerifyChainCompatibility {
+	/// Run the command.
+	pub async fn run(self) -> anyhow::Result<()> {
+		select_full_bridge!(self.bridge, {
+			let source_client = self.source.to_client::<Source>().await?;
+			let target_client = self.target.to_client::<Target>().await?;
+
+			let mut report = CompatibilityReport::default();
+			report.check(
+				format!("{} runtime version", Source::NAME),
+				verify_runtime_version(&source_client).await,
+			);
+			report.check(
+				format!("{} runtime version", Target::NAME),
+				verify_runtime_version(&target_client).await,
+			);
+			report.check(
+				format!("{} messages pallet call index", Source::NAME),
+				verify_messages_pallet_call_index(
+					&source_client,
+					SOURCE_MESSAGES_PALLET_NAME,
+					self.bridge.bridge_instance_index(),
+				)
+				.await,
+			);
+			report.check(
+				format!("{} messages pallet call index", Target::NAME),
+				verify_messages_pallet_call_index(
+					&target_client,
+					TARGET_MESSAGES_PALLET_NAME,
+					self.bridge.reverse_bridge_instance_index(),
+				)
+				.await,
+			);
+			report.check(
+				format!("{} inbound lane limits", Source::NAME),
+				verify_inbound_lane_limits(&source_client, SOURCE_MESSAGES_PALLET_NAME, SOURCE_INBOUND_LANE_LIMITS)
+					.await,
+			);
+			report.check(
+				format!("{} inbound lane limits", Target::NAME),
+				verify_inbound_lane_limits(&target_client, TARGET_MESSAGES_PALLET_NAME, TARGET_INBOUND_LANE_LIMITS)
+					.await,
+			);
+			report.check(
+				format!("{} conversion rate", Source::NAME),
+				verify_conversion_rate(&source_client, source_conversion_rate_params()).await,
+			);
+			report.check(
+				format!("{} conversion rate", Target::NAME),
+				verify_conversion_rate(&target_client, target_conversion_rate_params()).await,
+			);
+			report.check(
+				format!("{} GRANDPA pallet", Source::NAME),
+				passed(preflight::ensure_grandpa_pallet_ready(&source_client, SOURCE_GRANDPA_PALLET_NAME).await),
+			);
+			report.check(
+				format!("{} GRANDPA pallet", Target::NAME),
+				passed(preflight::ensure_grandpa_pallet_ready(&target_client, TARGET_GRANDPA_PALLET_NAME).await),
+			);
+			report.check(
+				format!("{} GRANDPA pallet bridged chain", Source::NAME),
+				passed(
+					preflight::ensure_grandpa_pallet_bridged_with(
+						&source_client,
+						SOURCE_GRANDPA_PALLET_NAME,
+						&target_client,
+					)
+					.await,
+				),
+			);
+			report.check(
+				format!("{} GRANDPA pallet bridged chain", Target::NAME),
+				passed(
+					preflight::ensure_grandpa_pallet_bridged_with(
+						&target_client,
+						TARGET_GRANDPA_PALLET_NAME,
+						&source_client,
+					)
+					.await,
+				),
+			);
+			report.check(
+				format!("{} messages pallet", Source::NAME),
+				passed(
+					preflight::ensure_messages_pallet_not_halted::<Source, SourceMessagesInstance>(&source_client)
+						.await,
+				),
+			);
+			report.check(
+				format!("{} messages pallet", Target::NAME),
+				passed(
+					preflight::ensure_messages_pallet_not_halted::<Target, TargetMessagesInstance>(&target_client)
+						.await,
+				),
+			);
+			report.check(
+				format!("{} messages pallet bridged chain", Source::NAME),
+				passed(
+					preflight::ensure_messages_pallet_bridged_with::<Source, Target, SourceMessagesInstance>(
+						&source_client,
+						&target_client,
+					)
+					.await,
+				),
+			);
+			report.check(
+				format!("{} messages pallet bridged chain", Target::NAME),
+				passed(
+					preflight::ensure_messages_pallet_bridged_with::<Target, Source, TargetMessagesInstance>(
+						&target_client,
+						&source_client,
+					)
+					.await,
+				),
+			);
+			for lane in &self.lane {
+				report.check(
+					format!("{} lane {}", Source::NAME, hex::encode(lane.0)),
+					passed(
+						preflight::ensure_lane_registered(&source_client, SOURCE_LANE_REGISTRY_PALLET_NAME, lane.0)
+							.await,
+					),
+				);
+				report.check(
+					format!("{} lane {}", Target::NAME, hex::encode(lane.0)),
+					passed(
+						preflight::ensure_lane_registered(&target_client, TARGET_LANE_REGISTRY_PALLET_NAME, lane.0)
+							.await,
+					),
+				);
+			}
+
+			println!("{}", report.render());
+			report.into_result()
+		})
+	}
+}
+
+/// Results of all compatibility checks.
+///
+/// Every check either succeeds with (possibly empty) details, or fails with an error.
+#[derive(Debug, Default)]
+struct CompatibilityReport {
+	checks: Vec<(String, anyhow::Result<String>)>,
+}
+
+impl CompatibilityReport {
+	/// Remember result of the check.
+	fn check(&mut self, name: String, result: anyhow::Result<String>) {
+		self.checks.push((name, result));
+	}
+
+	/// Render the report, one line per check.
+	fn render(&self) -> String {
+		self.checks
+			.iter()
+			.map(|(name, result)| match result {
+				Ok(details) if details.is_empty() => format!("[ OK ] {}", name),
+				Ok(details) => format!("[ OK ] {}: {}", name, details),
+				Err(error) => format!("[FAIL] {}: {}", name, error),
+			})
+			.collect::<Vec<_>>()
+			.join("\n")
+	}
+
+	/// Returns error, describing all failed checks.
+	fn into_result(self) -> anyhow::Result<()> {
+		let mut errors = ConfigurationErrors::default();
+		for (_, result) in self.checks {
+			errors.check(result.map(drop));
+		}
+		errors.into_result()
+	}
+}
+
+/// Convert result of the preflight check into the result of the compatibility check.
+fn passed(result: anyhow::Result<()>) -> anyhow::Result<String> {
+	result.map(|_| String::new())
+}
+
+/// Ensure that the chain runs the runtime version that the relay has been compiled with.
+async fn verify_runtime_version<C: CliChain>(client: &Client<C>) -> anyhow::Result<String> {
+	let actual = client.runtime_version().await?;
+	let expected = C::RUNTIME_VERSION;
+	if actual.spec_version != expected.spec_version || actual.transaction_version != expected.transaction_version {
+		return Err(anyhow::format_err!(
+			"{} runtime has spec_version {} and transaction_version {}, but the relay is compiled with {} and {}",
+			C::NAME,
+			actual.spec_version,
+			actual.transaction_version,
+			expected.spec_version,
+			expected.transaction_version,
+		));
+	}
+
+	Ok(format!(
+		"spec_version {}, transaction_version {}",
+		actual.spec_version, actual.transaction_version,
+	))
+}
+
+/// Ensure that the messages pallet has the same index in the chain runtime and in the runtime
+/// that the relay has been compiled with.
+///
+/// The relay encodes calls of the pallet itself, so any mismatch means that its transactions
+/// would be dispatched by the wrong pallet.
+async fn verify_messages_pallet_call_index<C: CliEncodeCall>(
+	client: &Client<C>,
+	pallet_name: &str,
+	bridge_instance_index: u8,
+) -> anyhow::Result<String>
+where
+	C::Call: Encode,
+{
+	let call = C::encode_call(&encode_call::Call::BridgeSendMessage {
+		bridge_instance_index,
+		lane: HexLaneId(Default::default()),
+		payload: HexBytes(dummy_message_payload()),
+		fee: Balance(0),
+	})?;
+	let expected = call.encode()[0];
+	let actual = client.pallet_index(pallet_name).await?;
+	if actual != expected {
+		return Err(anyhow::format_err!(
+			"{} messages pallet {} has index {}, but the relay is compiled with {}",
+			C::NAME,
+			pallet_name,
+			actual,
+			expected,
+		));
+	}
+
+	Ok(format!("{}", actual))
+}
+
+/// Ensure that inbound lane limits of the messages pallet match limits that the bridged chain
+/// (and the relay) have been compiled with.
+async fn verify_inbound_lane_limits<C: Chain>(
+	client: &Client<C>,
+	pallet_name: &str,
+	(expected_max_unrewarded_relayer_entries, expected_max_unconfirmed_messages): (MessageNonce, MessageNonce),
+) -> anyhow::Result<String> {
+	let max_unrewarded_relayer_entries: MessageNonce = client
+		.pallet_constant(pallet_name, MAX_UNREWARDED_RELAYER_ENTRIES_CONSTANT_NAME)
+		.await?;
+	let max_unconfirmed_messages: MessageNonce = client
+		.pallet_constant(pallet_name, MAX_UNCONFIRMED_MESSAGES_CONSTANT_NAME)
+		.await?;
+	if max_unrewarded_relayer_entries != expected_max_unrewarded_relayer_entries
+		|| max_unconfirmed_messages != expected_max_unconfirmed_messages
+	{
+		return Err(anyhow::format_err!(
+			"{} messages pallet {} allows {} unrewarded relayer entries and {} unconfirmed messages at inbound lane, \
+			but the bridged chain and the relay are compiled with {} and {}",
+			C::NAME,
+			pallet_name,
+			max_unrewarded_relayer_entries,
+			max_unconfirmed_messages,
+			expected_max_unrewarded_relayer_entries,
+			expected_max_unconfirmed_messages,
+		));
+	}
+
+	Ok(format!(
+		"{} unrewarded relayer entries, {} unconfirmed messages",
+		max_unrewarded_relayer_entries, max_unconfirmed_messages,
+	))
+}
+
+/// Ensure that the bridged token conversion rate is usable.
+///
+/// If the rate has never been updated, the runtime is using its initial value.
+async fn verify_conversion_rate<C: Chain>(
+	client: &Client<C>,
+	conversion_rate_params: Option<(StorageKey, FixedU128)>,
+) -> anyhow::Result<String> {
+	let (conversion_rate_storage_key, initial_conversion_rate) = match conversion_rate_params {
+		Some(conversion_rate_params) => conversion_rate_params,
+		None => return Ok("not used by the bridge".into()),
+	};
+
+	let conversion_rate: Option<FixedU128> = client.storage_value(conversion_rate_storage_key).await?;
+	match conversion_rate {
+		Some(conversion_rate) if conversion_rate.is_zero() => {
+			Err(anyhow::format_err!("{} bridged token conversion rate is zero", C::NAME))
+		}
+		Some(conversion_rate) => Ok(format!("{:?}", conversion_rate)),
+		None => Ok(format!("{:?} (initial value)", initial_conversion_rate)),
+	}
+}
+
+/// Encoded message payload that is only used to encode the `send_message` call.
+fn dummy_message_payload() -> Vec<u8> {
+	bp_message_dispatch::MessagePayload::<(), (), (), Vec<u8>> {
+		spec_version: 0,
+		weight: 0,
+		origin: bp_message_dispatch::CallOrigin::SourceRoot,
+		dispatch_fee_payment: bp_runtime::messages::DispatchFeePayment::AtSourceChain,
+		call: Vec::new(),
+	}
+	.encode()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn should_parse_cli_options() {
+		// when
+		let res = VerifyChainCompatibility::from_iter(vec![
+			"verify-chain-compatibility",
+			"rialto-to-millau",
+			"--lane",
+			"00000000",
+			"--lane",
+			"00000001",
+			"--source-port",
+			"1234",
+			"--target-port",
+			"5678",
+		]);
+
+		// then
+		assert_eq!(
+			res,
+			VerifyChainCompatibility {
+				bridge: FullBridge::RialtoToMillau,
+				lane: vec![
+					HexLaneId(bp_messages::LaneId::Legacy([0, 0, 0, 0])),
+					HexLaneId(bp_messages::LaneId::Legacy([0, 0, 0, 1]))
+				],
+				source: SourceConnectionParams {
+					source_host: "127.0.0.1".into(),
+					source_port: 1234,
+					source_secure: false,
+					source_max_daily_rpc_requests: None,
+				},
+				target: TargetConnectionParams {
+					target_host: "127.0.0.1".into(),
+					target_port: 5678,
+					target_secure: false,
+					target_max_daily_rpc_requests: None,
+				},
+			}
+		);
+	}
+
+	#[test]
+	fn report_lists_all_checks() {
+		let mut report = CompatibilityReport::default();
+		report.check("first".into(), Ok(String::new()));
+		report.check("second".into(), Ok("details".into()));
+		report.check("third".into(), Err(anyhow::format_err!("error")));
+
+		assert_eq!(
+			report.render(),
+			"[ OK ] first\n[ OK ] second: details\n[FAIL] third: error",
+		);
+	}
+
+	#[test]
+	fn report_fails_if_any_check_has_failed() {
+		let mut report = CompatibilityReport::default();
+		report.check("first".into(), Ok(String::new()));
+		assert!(report.into_result().is_ok());
+
+		let mut report = CompatibilityReport::default();
+		report.check("first".into(), Ok(String::new()));
+		report.check("second".into(), Err(anyhow::format_err!("error")));
+		assert_eq!(report.into_result().unwrap_err().to_string(), "error");
+	}
+
+	#[test]
+	fn dummy_message_payload_is_accepted_by_all_chains() {
+		let call = encode_call::Call::BridgeSendMessage {
+			bridge_instance_index: 0,
+			lane: HexLaneId(Default::default()),
+			payload: HexBytes(dummy_message_payload()),
+			fee: Balance(0),
+		};
+
+		assert_eq!(
+			relay_millau_client::Millau::encode_call(&call).unwrap().encode()[0],
+			millau_runtime::Call::BridgeRialtoMessages(millau_runtime::MessagesCall::propose_owner(None)).encode()[0],
+		);
+		assert_eq!(
+			relay_rialto_client::Rialto::encode_call(&call).unwrap().encode()[0],
+			rialto_runtime::Call::BridgeMillauMessages(rialto_runtime::MessagesCall::propose_owner(None)).encode()[0],
+		);
+		assert!(relay_rococo_client::Rococo::encode_call(&call).is_ok());
+		assert!(relay_wococo_client::Wococo::encode_call(&call).is_ok());
+	}
+}
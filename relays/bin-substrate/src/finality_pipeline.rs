@@ -18,9 +18,13 @@
 
 use crate::finality_target::SubstrateFinalityTarget;
 
-use bp_header_chain::justification::GrandpaJustification;
+use bp_header_chain::{justification::GrandpaJustification, ChainWithGrandpa};
+use codec::Encode;
 use finality_relay::{FinalitySyncParams, FinalitySyncPipeline};
-use relay_substrate_client::{finality_source::FinalitySource, BlockNumberOf, Chain, Client, HashOf, SyncHeader};
+use futures::FutureExt;
+use relay_substrate_client::{
+	finality_source::FinalitySource, metrics::RpcUsageMetric, BlockNumberOf, Chain, Client, HashOf, SyncHeader,
+};
 use relay_utils::{metrics::MetricsParams, BlockNumberBase};
 use sp_core::Bytes;
 use std::{fmt::Debug, marker::PhantomData, time::Duration};
@@ -37,7 +41,14 @@ pub(crate) const RECENT_FINALITY_PROOFS_LIMIT: usize = 4096;
 pub trait SubstrateFinalitySyncPipeline: FinalitySyncPipeline {
 	/// Name of the runtime method that returns id of best finalized source header at target chain.
 	const BEST_FINALIZED_SOURCE_HEADER_ID_AT_TARGET: &'static str;
+	/// Name of the runtime method that verifies finality proof at target chain, without importing
+	/// the header.
+	///
+	/// If `None`, finality proofs are submitted without pre-validation.
+	const VERIFY_FINALITY_PROOF_METHOD: Option<&'static str> = None;
 
+	/// Chain with GRANDPA finality, which headers are synced.
+	type SourceChain: Chain + ChainWithGrandpa;
 	/// Chain with GRANDPA bridge pallet.
 	type TargetChain: Chain;
 
@@ -115,11 +126,18 @@ where
 }
 
 /// Run Substrate-to-Substrate finality sync.
+///
+/// The sync is stopped with an error if the source chain reset is detected (see
+/// [`crate::bridged_chain_reset`]). If `prepare_reinitialization` is true, the initialization
+/// data for the target chain GRANDPA pallet is read from the reset source chain and logged, so
+/// that the pallet owner could re-initialize the pallet.
 pub async fn run<SourceChain, TargetChain, P>(
 	pipeline: P,
 	source_client: Client<SourceChain>,
 	target_client: Client<TargetChain>,
+	target_grandpa_pallet_name: &'static str,
 	only_mandatory_headers: bool,
+	prepare_reinitialization: bool,
 	metrics_params: MetricsParams,
 ) -> anyhow::Result<()>
 where
@@ -128,9 +146,10 @@ where
 		Number = BlockNumberOf<SourceChain>,
 		Header = SyncHeader<SourceChain::Header>,
 		FinalityProof = GrandpaJustification<SourceChain::Header>,
+		SourceChain = SourceChain,
 		TargetChain = TargetChain,
 	>,
-	SourceChain: Clone + Chain,
+	SourceChain: Clone + Chain + ChainWithGrandpa,
 	BlockNumberOf<SourceChain>: BlockNumberBase,
 	TargetChain: Clone + Chain,
 {
@@ -141,18 +160,58 @@ where
 		TargetChain::NAME,
 	);
 
+	let bridged_chain_reset = crate::bridged_chain_reset::wait_for_bridged_chain_reset(
+		source_client.clone(),
+		target_client.clone(),
+		target_grandpa_pallet_name,
+	)
+	.shared();
+
+	let metrics_params = relay_utils::relay_metrics(Some(finality_relay::metrics_prefix::<P>()), metrics_params)
+		.standalone_metric(|registry, prefix| RpcUsageMetric::new(registry, prefix, source_client.clone()))?
+		.standalone_metric(|registry, prefix| RpcUsageMetric::new(registry, prefix, target_client.clone()))?
+		.into_params();
+
 	finality_relay::run(
-		FinalitySource::new(source_client, None),
+		FinalitySource::new(source_client.clone(), None),
 		SubstrateFinalityTarget::new(target_client, pipeline),
 		FinalitySyncParams {
 			tick: std::cmp::max(SourceChain::AVERAGE_BLOCK_INTERVAL, TargetChain::AVERAGE_BLOCK_INTERVAL),
 			recent_finality_proofs_limit: RECENT_FINALITY_PROOFS_LIMIT,
 			stall_timeout: STALL_TIMEOUT,
 			only_mandatory_headers,
+			on_demand: false,
 		},
 		metrics_params,
-		futures::future::pending(),
+		bridged_chain_reset.clone().map(drop),
 	)
 	.await
-	.map_err(|e| anyhow::format_err!("{}", e))
+	.map_err(|e| anyhow::format_err!("{}", e))?;
+
+	let reset = match bridged_chain_reset.now_or_never() {
+		Some(reset) => reset,
+		None => return Ok(()),
+	};
+
+	if prepare_reinitialization {
+		match crate::headers_initialize::prepare_initialization_data(source_client).await {
+			Ok(initialization_data) => log::info!(
+				target: "bridge",
+				"Prepared data to re-initialize {} GRANDPA pallet {}: {:?}. SCALE-encoded: {}",
+				TargetChain::NAME,
+				target_grandpa_pallet_name,
+				initialization_data,
+				hex::encode(initialization_data.encode()),
+			),
+			Err(error) => log::error!(
+				target: "bridge",
+				"Failed to prepare data to re-initialize {} GRANDPA pallet {}: {}",
+				TargetChain::NAME,
+				target_grandpa_pallet_name,
+				error,
+			),
+		}
+	}
+
+	Err(anyhow::format_err!("{}", reset))
 }
@@ -21,10 +21,14 @@
 use crate::finality_pipeline::SubstrateFinalitySyncPipeline;
 
 use async_trait::async_trait;
-use codec::Decode;
-use finality_relay::TargetClient;
-use relay_substrate_client::{Chain, Client, Error as SubstrateError};
+use bp_header_chain::{justification::GrandpaJustification, ChainWithGrandpa, FinalityProofError};
+use codec::{Decode, Encode};
+use finality_relay::{FinalitySyncPipeline, SourceHeader, TargetClient};
+use relay_substrate_client::{Chain, Client, Error as SubstrateError, HeaderOf};
 use relay_utils::relay_loop::Client as RelayClient;
+use sp_core::Bytes;
+use sp_runtime::traits::Header as HeaderT;
+use std::ops::Deref;
 
 /// Substrate client as Substrate finality target.
 pub struct SubstrateFinalityTarget<C: Chain, P> {
@@ -63,7 +67,9 @@ where
 	C: Chain,
 	P::Number: Decode,
 	P::Hash: Decode,
+	P::Header: Deref<Target = HeaderOf<P::SourceChain>>,
 	P: SubstrateFinalitySyncPipeline<TargetChain = C>,
+	P: FinalitySyncPipeline<FinalityProof = GrandpaJustification<HeaderOf<P::SourceChain>>>,
 {
 	async fn best_finalized_source_block_number(&self) -> Result<P::Number, SubstrateError> {
 		// we can't continue to relay finality if target node is out of sync, because
@@ -80,6 +86,45 @@ where
 	}
 
 	async fn submit_finality_proof(&self, header: P::Header, proof: P::FinalityProof) -> Result<(), SubstrateError> {
+		warn_if_exceeds_source_chain_limits::<P>(header.deref(), &proof);
+
+		if let Some(verify_method) = P::VERIFY_FINALITY_PROOF_METHOD {
+			// don't pay for the transaction that is going to be rejected by the runtime
+			let encoded_response = self
+				.client
+				.state_call(
+					verify_method.into(),
+					Bytes((header.deref().encode(), proof.encode()).encode()),
+					None,
+				)
+				.await?;
+			let verification_result: Result<(), FinalityProofError> =
+				Decode::decode(&mut &encoded_response.0[..]).map_err(SubstrateError::ResponseParseFailed)?;
+			match verification_result {
+				Ok(()) => (),
+				Err(FinalityProofError::InvalidEncoding) => {
+					return Err(SubstrateError::Custom(format!(
+						"Finality proof of {} header {:?} is rejected by {} runtime: invalid encoding",
+						P::SOURCE_NAME,
+						header.number(),
+						P::TARGET_NAME,
+					)))
+				}
+				Err(FinalityProofError::Rejected(error, reason)) => {
+					return Err(SubstrateError::BridgePallet(
+						error,
+						format!(
+							"Finality proof of {} header {:?} is rejected by {} runtime: {}",
+							P::SOURCE_NAME,
+							header.number(),
+							P::TARGET_NAME,
+							String::from_utf8_lossy(&reason),
+						),
+					))
+				}
+			}
+		}
+
 		let transactions_author = self.pipeline.transactions_author();
 		let pipeline = self.pipeline.clone();
 		self.client
@@ -89,4 +134,53 @@ where
 			.await
 			.map(drop)
 	}
+
+	fn submission_error_label(error: &SubstrateError) -> &'static str {
+		error.bridge_error().map(|error| error.name()).unwrap_or("other")
+	}
+}
+
+/// Log warning if the header or its justification exceeds limits of the source chain.
+///
+/// Such proofs may still be accepted by the target chain, but the submit transaction is going
+/// to be more expensive than expected (or it may even be rejected, if limits are too low).
+fn warn_if_exceeds_source_chain_limits<P: SubstrateFinalitySyncPipeline>(
+	header: &HeaderOf<P::SourceChain>,
+	proof: &GrandpaJustification<HeaderOf<P::SourceChain>>,
+) {
+	let header_size = header.encoded_size();
+	if header_size > P::SourceChain::MAX_HEADER_SIZE as usize {
+		log::warn!(
+			target: "bridge",
+			"Size of {} header {:?} is {} bytes. It is larger than maximal expected size of {} bytes",
+			P::SOURCE_NAME,
+			header.number(),
+			header_size,
+			P::SourceChain::MAX_HEADER_SIZE,
+		);
+	}
+
+	let precommits = proof.commit.precommits.len();
+	if precommits > P::SourceChain::MAX_AUTHORITIES_COUNT as usize {
+		log::warn!(
+			target: "bridge",
+			"Justification of {} header {:?} has {} precommits. It is more than maximal number of authorities {}",
+			P::SOURCE_NAME,
+			header.number(),
+			precommits,
+			P::SourceChain::MAX_AUTHORITIES_COUNT,
+		);
+	}
+
+	let ancestry = proof.votes_ancestries.len();
+	if ancestry > P::SourceChain::REASONABLE_HEADERS_IN_JUSTIFICATON_ANCESTRY as usize {
+		log::warn!(
+			target: "bridge",
+			"Justification of {} header {:?} has {} ancestry headers. It is more than reasonable number {}",
+			P::SOURCE_NAME,
+			header.number(),
+			ancestry,
+			P::SourceChain::REASONABLE_HEADERS_IN_JUSTIFICATON_ANCESTRY,
+		);
+	}
 }
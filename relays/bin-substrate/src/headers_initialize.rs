@@ -97,7 +97,7 @@ async fn do_initialize<SourceChain: Chain, TargetChain: Chain>(
 }
 
 /// Prepare initialization data for the GRANDPA verifier pallet.
-async fn prepare_initialization_data<SourceChain: Chain>(
+pub(crate) async fn prepare_initialization_data<SourceChain: Chain>(
 	source_client: Client<SourceChain>,
 ) -> Result<InitializationData<SourceChain::Header>, String> {
 	// In ideal world we just need to get best finalized header and then to read GRANDPA authorities
@@ -212,6 +212,13 @@ async fn prepare_initialization_data<SourceChain: Chain>(
 		}
 	}
 
+	// The genesis hash is read instead of using the one cached by the client, because the
+	// data may be prepared after the source chain relaunch.
+	let genesis_hash = source_client
+		.block_hash_by_number(Zero::zero())
+		.await
+		.map_err(|err| format!("Failed to read {} genesis hash: {:?}", SourceChain::NAME, err))?;
+
 	Ok(InitializationData {
 		header: initial_header,
 		authority_list: initial_authorities_set,
@@ -221,6 +228,7 @@ async fn prepare_initialization_data<SourceChain: Chain>(
 			initial_authorities_set_id
 		},
 		is_halted: false,
+		genesis_hash,
 	})
 }
 
@@ -18,6 +18,7 @@
 
 #![warn(missing_docs)]
 
+mod bridged_chain_reset;
 mod chains;
 mod cli;
 mod finality_pipeline;
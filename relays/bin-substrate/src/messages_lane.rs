@@ -22,7 +22,7 @@ use bp_messages::{LaneId, MessageNonce};
 use frame_support::weights::Weight;
 use messages_relay::message_lane::{MessageLane, SourceHeaderIdOf, TargetHeaderIdOf};
 use relay_substrate_client::{
-	metrics::{FloatStorageValueMetric, StorageProofOverheadMetric},
+	metrics::{ClockSkewMetric, FloatStorageValueMetric, RpcUsageMetric, StorageProofOverheadMetric},
 	BlockNumberOf, Chain, Client, HashOf,
 };
 use relay_utils::{
@@ -31,7 +31,12 @@ use relay_utils::{
 };
 use sp_core::{storage::StorageKey, Bytes};
 use sp_runtime::FixedU128;
-use std::ops::RangeInclusive;
+use std::{ops::RangeInclusive, time::Duration};
+
+/// Maximal acceptable difference between the relay clock and timestamps of chain blocks.
+///
+/// Mortal transactions and relay latency metrics are unreliable if the relay clock is skewed.
+const MAX_CLOCK_SKEW: Duration = Duration::from_secs(30);
 
 /// Substrate <-> Substrate messages relay parameters.
 pub struct MessagesRelayParams<SC: Chain, SS, TC: Chain, TS> {
@@ -49,6 +54,9 @@ pub struct MessagesRelayParams<SC: Chain, SS, TC: Chain, TS> {
 	pub target_to_source_headers_relay: Option<OnDemandHeadersRelay<TC>>,
 	/// Identifier of lane that needs to be served.
 	pub lane_id: LaneId,
+	/// Identifiers of lanes, which messages are delivered in the same transactions with messages
+	/// of the `lane_id` lane.
+	pub additional_lanes: Vec<LaneId>,
 	/// Relayer operating mode.
 	pub relayer_mode: messages_relay::message_lane_loop::RelayerMode,
 	/// Metrics parameters.
@@ -156,6 +164,50 @@ where
 	type TargetHeaderHash = HashOf<Target>;
 }
 
+/// Limits of inbound lanes at the messages target chain.
+#[derive(Debug, Clone, Copy)]
+pub struct InboundLaneLimits {
+	/// Maximal number of unrewarded relayer entries at inbound lane.
+	pub max_unrewarded_relayer_entries: MessageNonce,
+	/// Maximal number of unconfirmed messages at inbound lane.
+	pub max_unconfirmed_messages: MessageNonce,
+}
+
+/// Read inbound lane limits from the constants of the messages pallet at the target chain.
+///
+/// If the target runtime doesn't expose these constants, the `default_limits` (that are
+/// hardcoded in the relay) are returned.
+pub async fn read_inbound_lane_limits<C: Chain>(
+	target_client: &Client<C>,
+	messages_pallet_name: &str,
+	default_limits: InboundLaneLimits,
+) -> InboundLaneLimits {
+	let limits = async {
+		Ok::<_, relay_substrate_client::Error>(InboundLaneLimits {
+			max_unrewarded_relayer_entries: target_client
+				.pallet_constant(messages_pallet_name, "MaxUnrewardedRelayerEntriesAtInboundLane")
+				.await?,
+			max_unconfirmed_messages: target_client
+				.pallet_constant(messages_pallet_name, "MaxUnconfirmedMessagesAtInboundLane")
+				.await?,
+		})
+	};
+
+	match limits.await {
+		Ok(limits) => limits,
+		Err(error) => {
+			log::warn!(
+				target: "bridge",
+				"Failed to read inbound lane limits from {} runtime: {:?}. Using default limits: {:?}",
+				C::NAME,
+				error,
+				default_limits,
+			);
+			default_limits
+		}
+	}
+}
+
 /// Returns maximal number of messages and their maximal cumulative dispatch weight, based
 /// on given chain parameters.
 pub fn select_delivery_transaction_limits<W: pallet_bridge_messages::WeightInfoExt>(
@@ -217,14 +269,15 @@ pub fn add_standalone_metrics<P: SubstrateMessageLane>(
 	metrics_prefix: Option<String>,
 	metrics_params: MetricsParams,
 	source_client: Client<P::SourceChain>,
+	target_client: Client<P::TargetChain>,
 	source_chain_token_id: Option<&str>,
 	target_chain_token_id: Option<&str>,
 	target_to_source_conversion_rate_params: Option<(StorageKey, FixedU128)>,
 ) -> anyhow::Result<(MetricsParams, StandaloneMessagesMetrics)> {
 	let mut source_to_base_conversion_rate = None;
 	let mut target_to_base_conversion_rate = None;
-	let mut metrics_params =
-		relay_utils::relay_metrics(metrics_prefix, metrics_params).standalone_metric(|registry, prefix| {
+	let mut metrics_params = relay_utils::relay_metrics(metrics_prefix, metrics_params)
+		.standalone_metric(|registry, prefix| {
 			StorageProofOverheadMetric::new(
 				registry,
 				prefix,
@@ -232,7 +285,15 @@ pub fn add_standalone_metrics<P: SubstrateMessageLane>(
 				format!("{}_storage_proof_overhead", P::SourceChain::NAME.to_lowercase()),
 				format!("{} storage proof overhead", P::SourceChain::NAME),
 			)
-		})?;
+		})?
+		.standalone_metric(|registry, prefix| {
+			ClockSkewMetric::new(registry, prefix, source_client.clone(), MAX_CLOCK_SKEW)
+		})?
+		.standalone_metric(|registry, prefix| {
+			ClockSkewMetric::new(registry, prefix, target_client.clone(), MAX_CLOCK_SKEW)
+		})?
+		.standalone_metric(|registry, prefix| RpcUsageMetric::new(registry, prefix, source_client.clone()))?
+		.standalone_metric(|registry, prefix| RpcUsageMetric::new(registry, prefix, target_client))?;
 	if let Some((target_to_source_conversion_rate_storage_key, initial_target_to_source_conversion_rate)) =
 		target_to_source_conversion_rate_params
 	{
@@ -23,13 +23,14 @@ use crate::messages_target::SubstrateMessagesReceivingProof;
 use crate::on_demand_headers::OnDemandHeadersRelay;
 
 use async_trait::async_trait;
-use bp_messages::{LaneId, MessageNonce, UnrewardedRelayersState};
+use bp_messages::{LaneId, MessageNonce, UnrewardedRelayersState, DEFAULT_MESSAGE_PRIORITY};
 use bp_runtime::{messages::DispatchFeePayment, ChainId};
 use bridge_runtime_common::messages::{
 	source::FromBridgedChainMessagesDeliveryProof, target::FromBridgedChainMessagesProof,
 };
-use codec::{Decode, Encode};
+use codec::{Decode, DecodeAll, Encode};
 use frame_support::{traits::Instance, weights::Weight};
+use futures::stream::{BoxStream, StreamExt};
 use messages_relay::{
 	message_lane::{SourceHeaderIdOf, TargetHeaderIdOf},
 	message_lane_loop::{
@@ -48,6 +49,50 @@ use std::{marker::PhantomData, ops::RangeInclusive};
 /// the proof itself.
 pub type SubstrateMessagesProof<C> = (Weight, FromBridgedChainMessagesProof<HashOf<C>>);
 
+/// Lanes, which messages are delivered in the same transaction with messages of the main lane.
+///
+/// Messages of additional lanes are only delivered if they fit into the space that is left by
+/// messages of the main lane. The main lane loop doesn't take them into account when deciding
+/// whether delivery transaction is profitable.
+pub struct AdditionalLanes<TC: Chain> {
+	/// Client of the messages target chain. It is used to read state of inbound lanes.
+	pub target_client: Client<TC>,
+	/// Identifiers of additional lanes.
+	pub lanes: Vec<LaneId>,
+	/// Maximal number of messages in single delivery transaction.
+	pub max_messages_in_single_batch: MessageNonce,
+	/// Maximal cumulative dispatch weight of messages in single delivery transaction.
+	pub max_messages_weight_in_single_batch: Weight,
+	/// Maximal cumulative size of messages in single delivery transaction.
+	pub max_messages_size_in_single_batch: u32,
+	/// Maximal number of unconfirmed messages at the target chain inbound lane.
+	pub max_unconfirmed_messages_at_target: MessageNonce,
+}
+
+impl<TC: Chain> Clone for AdditionalLanes<TC> {
+	fn clone(&self) -> Self {
+		Self {
+			target_client: self.target_client.clone(),
+			lanes: self.lanes.clone(),
+			max_messages_in_single_batch: self.max_messages_in_single_batch,
+			max_messages_weight_in_single_batch: self.max_messages_weight_in_single_batch,
+			max_messages_size_in_single_batch: self.max_messages_size_in_single_batch,
+			max_unconfirmed_messages_at_target: self.max_unconfirmed_messages_at_target,
+		}
+	}
+}
+
+/// Limits that are left for messages of additional lanes in the delivery transaction.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct AdditionalLanesLimits {
+	/// Number of messages.
+	messages: MessageNonce,
+	/// Cumulative dispatch weight of messages.
+	weight: Weight,
+	/// Cumulative size of messages.
+	size: u32,
+}
+
 /// Substrate client as Substrate messages source.
 pub struct SubstrateMessagesSource<SC: Chain, TC: Chain, P: SubstrateMessageLane, I> {
 	client: Client<SC>,
@@ -55,6 +100,7 @@ pub struct SubstrateMessagesSource<SC: Chain, TC: Chain, P: SubstrateMessageLane
 	lane_id: LaneId,
 	instance: ChainId,
 	target_to_source_headers_relay: Option<OnDemandHeadersRelay<TC>>,
+	additional_lanes: Option<AdditionalLanes<TC>>,
 	_phantom: PhantomData<I>,
 }
 
@@ -73,9 +119,20 @@ impl<SC: Chain, TC: Chain, P: SubstrateMessageLane, I> SubstrateMessagesSource<S
 			lane_id,
 			instance,
 			target_to_source_headers_relay,
+			additional_lanes: None,
 			_phantom: Default::default(),
 		}
 	}
+
+	/// Deliver messages of additional lanes in the same transactions with messages of the main lane.
+	pub fn with_additional_lanes(mut self, additional_lanes: AdditionalLanes<TC>) -> Self {
+		self.additional_lanes = if additional_lanes.lanes.is_empty() {
+			None
+		} else {
+			Some(additional_lanes)
+		};
+		self
+	}
 }
 
 impl<SC: Chain, TC: Chain, P: SubstrateMessageLane, I> Clone for SubstrateMessagesSource<SC, TC, P, I> {
@@ -86,6 +143,7 @@ impl<SC: Chain, TC: Chain, P: SubstrateMessageLane, I> Clone for SubstrateMessag
 			lane_id: self.lane_id,
 			instance: self.instance,
 			target_to_source_headers_relay: self.target_to_source_headers_relay.clone(),
+			additional_lanes: self.additional_lanes.clone(),
 			_phantom: Default::default(),
 		}
 	}
@@ -187,10 +245,7 @@ where
 			)
 			.await?;
 
-		make_message_details_map::<SC>(
-			Decode::decode(&mut &encoded_response.0[..]).map_err(SubstrateError::ResponseParseFailed)?,
-			nonces,
-		)
+		make_message_details_map::<SC>(decode_message_details::<SC>(&encoded_response.0)?, nonces)
 	}
 
 	async fn prove_messages(
@@ -212,6 +267,38 @@ where
 			));
 		}
 
+		let (additional_lanes, additional_lanes_dispatch_weight) = match self.additional_lanes {
+			Some(ref additional_lanes) => {
+				let main_lane_messages = self.generated_message_details(id, nonces.clone()).await?;
+				let limits = AdditionalLanesLimits {
+					messages: additional_lanes
+						.max_messages_in_single_batch
+						.saturating_sub(main_lane_messages.len() as MessageNonce),
+					weight: additional_lanes
+						.max_messages_weight_in_single_batch
+						.saturating_sub(proof_parameters.dispatch_weight),
+					size: additional_lanes.max_messages_size_in_single_batch.saturating_sub(
+						main_lane_messages
+							.values()
+							.fold(0u32, |size, details| size.saturating_add(details.size)),
+					),
+				};
+				let pending_messages =
+					read_additional_lanes_messages::<SC, TC, P>(&self.client, additional_lanes, id.1, limits.messages)
+						.await?;
+				select_additional_lanes_messages(limits, pending_messages)
+			}
+			None => (Vec::new(), 0),
+		};
+		for (lane_id, nonces_start, nonces_end) in &additional_lanes {
+			for message_nonce in *nonces_start..=*nonces_end {
+				storage_keys.push(pallet_bridge_messages::storage_keys::message_key::<I>(
+					lane_id,
+					message_nonce,
+				));
+			}
+		}
+
 		let proof = self
 			.client
 			.prove_storage(storage_keys, id.1)
@@ -224,8 +311,12 @@ where
 			lane: self.lane_id,
 			nonces_start: *nonces.start(),
 			nonces_end: *nonces.end(),
+			additional_lanes,
 		};
-		Ok((id, nonces, (proof_parameters.dispatch_weight, proof)))
+		let dispatch_weight = proof_parameters
+			.dispatch_weight
+			.saturating_add(additional_lanes_dispatch_weight);
+		Ok((id, nonces, (dispatch_weight, proof)))
 	}
 
 	async fn submit_messages_receiving_proof(
@@ -258,6 +349,16 @@ where
 			.await
 			.unwrap_or_else(|_| SC::Balance::max_value())
 	}
+
+	async fn subscribe_outbound_lane_updates(&self) -> Result<BoxStream<'static, ()>, SubstrateError> {
+		Ok(self
+			.client
+			.subscribe_storage_changes(vec![pallet_bridge_messages::storage_keys::outbound_lane_data_key::<I>(
+				&self.lane_id,
+			)])
+			.await?
+			.boxed())
+	}
 }
 
 /// Prepare 'dummy' messages delivery proof that will compose the delivery confirmation transaction.
@@ -279,6 +380,7 @@ fn prepare_dummy_messages_delivery_proof<SC: Chain, TC: Chain>() -> SubstrateMes
 			bridged_header_hash: Default::default(),
 			storage_proof: vec![vec![0; proof_size as usize]],
 			lane: Default::default(),
+			relayer_entries: None,
 		},
 	)
 }
@@ -326,6 +428,156 @@ where
 	})
 }
 
+/// Read details of messages, that are queued at the source chain and may be delivered to the target
+/// chain at additional lanes.
+///
+/// At most `max_messages` messages are read for every lane.
+async fn read_additional_lanes_messages<SC, TC, P>(
+	source_client: &Client<SC>,
+	additional_lanes: &AdditionalLanes<TC>,
+	at_source_block: SC::Hash,
+	max_messages: MessageNonce,
+) -> Result<Vec<(LaneId, MessageDetailsMap<SC::Balance>)>, SubstrateError>
+where
+	SC: Chain,
+	SC::Balance: Decode,
+	TC: Chain,
+	P: SubstrateMessageLane,
+{
+	let read_nonce = |encoded_response: Bytes| -> Result<MessageNonce, SubstrateError> {
+		Decode::decode(&mut &encoded_response.0[..]).map_err(SubstrateError::ResponseParseFailed)
+	};
+
+	let mut lanes_messages = Vec::with_capacity(additional_lanes.lanes.len());
+	if max_messages == 0 {
+		return Ok(lanes_messages);
+	}
+
+	for lane_id in &additional_lanes.lanes {
+		let latest_generated_nonce = read_nonce(
+			source_client
+				.state_call(
+					P::OUTBOUND_LANE_LATEST_GENERATED_NONCE_METHOD.into(),
+					Bytes(lane_id.encode()),
+					Some(at_source_block),
+				)
+				.await?,
+		)?;
+		let latest_received_nonce = read_nonce(
+			additional_lanes
+				.target_client
+				.state_call(
+					P::INBOUND_LANE_LATEST_RECEIVED_NONCE_METHOD.into(),
+					Bytes(lane_id.encode()),
+					None,
+				)
+				.await?,
+		)?;
+		let latest_confirmed_nonce = read_nonce(
+			additional_lanes
+				.target_client
+				.state_call(
+					P::INBOUND_LANE_LATEST_CONFIRMED_NONCE_METHOD.into(),
+					Bytes(lane_id.encode()),
+					None,
+				)
+				.await?,
+		)?;
+
+		let nonces_start = latest_received_nonce.saturating_add(1);
+		let nonces_end = std::cmp::min(
+			latest_generated_nonce,
+			std::cmp::min(
+				latest_confirmed_nonce.saturating_add(additional_lanes.max_unconfirmed_messages_at_target),
+				latest_received_nonce.saturating_add(max_messages),
+			),
+		);
+		if nonces_end < nonces_start {
+			continue;
+		}
+
+		let encoded_response = source_client
+			.state_call(
+				P::OUTBOUND_LANE_MESSAGE_DETAILS_METHOD.into(),
+				Bytes((lane_id, nonces_start, nonces_end).encode()),
+				Some(at_source_block),
+			)
+			.await?;
+		let messages = make_message_details_map::<SC>(
+			decode_message_details::<SC>(&encoded_response.0)?,
+			nonces_start..=nonces_end,
+		)?;
+		// if some messages are missing from the source node, the target node is likely out of sync
+		// and the lane will be served by its own loop
+		if messages.keys().next() == Some(&nonces_start) {
+			lanes_messages.push((*lane_id, messages));
+		}
+	}
+
+	Ok(lanes_messages)
+}
+
+/// Select messages of additional lanes that fit into the given limits.
+///
+/// Lanes with higher priority messages are served first. Lanes with the same priority are served
+/// in the given order. Messages of every lane are selected in order, starting from the first
+/// pending message. Returns (inclusive) nonces ranges of selected messages and their cumulative
+/// dispatch weight.
+fn select_additional_lanes_messages<Balance>(
+	mut limits: AdditionalLanesLimits,
+	mut lanes_messages: Vec<(LaneId, MessageDetailsMap<Balance>)>,
+) -> (Vec<(LaneId, MessageNonce, MessageNonce)>, Weight) {
+	// messages are delivered in order, so the lane priority is the priority of its most important
+	// pending message
+	lanes_messages.sort_by_key(|(_, messages)| {
+		std::cmp::Reverse(
+			messages
+				.values()
+				.map(|details| details.priority)
+				.max()
+				.unwrap_or(DEFAULT_MESSAGE_PRIORITY),
+		)
+	});
+
+	let mut selected_lanes = Vec::new();
+	let mut selected_dispatch_weight: Weight = 0;
+	for (lane_id, messages) in lanes_messages {
+		let mut selected_range: Option<(MessageNonce, MessageNonce)> = None;
+		for (nonce, details) in &messages {
+			if limits.messages == 0 || details.dispatch_weight > limits.weight || details.size > limits.size {
+				break;
+			}
+
+			limits.messages -= 1;
+			limits.weight -= details.dispatch_weight;
+			limits.size -= details.size;
+			selected_dispatch_weight = selected_dispatch_weight.saturating_add(details.dispatch_weight);
+			selected_range = Some((selected_range.map(|(start, _)| start).unwrap_or(*nonce), *nonce));
+		}
+
+		if let Some((nonces_start, nonces_end)) = selected_range {
+			selected_lanes.push((lane_id, nonces_start, nonces_end));
+		}
+	}
+
+	(selected_lanes, selected_dispatch_weight)
+}
+
+/// Decode message details, returned by the source chain runtime.
+///
+/// The runtime, that hasn't been upgraded yet, returns message details without the message
+/// priority.
+fn decode_message_details<C: Chain>(
+	encoded_details: &[u8],
+) -> Result<Vec<bp_messages::MessageDetails<C::Balance>>, SubstrateError> {
+	Vec::<bp_messages::MessageDetails<C::Balance>>::decode_all(encoded_details)
+		.or_else(|_| {
+			Vec::<bp_messages::MessageDetailsV0<C::Balance>>::decode_all(encoded_details)
+				.map(|details| details.into_iter().map(Into::into).collect())
+		})
+		.map_err(SubstrateError::ResponseParseFailed)
+}
+
 fn make_message_details_map<C: Chain>(
 	weights: Vec<bp_messages::MessageDetails<C::Balance>>,
 	nonces: RangeInclusive<MessageNonce>,
@@ -385,6 +637,7 @@ fn make_message_details_map<C: Chain>(
 				size: details.size as _,
 				reward: details.delivery_and_dispatch_fee,
 				dispatch_fee_payment: DispatchFeePayment::AtSourceChain,
+				priority: details.priority,
 			},
 		);
 		expected_nonce = details.nonce + 1;
@@ -412,6 +665,7 @@ mod tests {
 				size: 0,
 				delivery_and_dispatch_fee: 0,
 				dispatch_fee_payment: DispatchFeePayment::AtSourceChain,
+				priority: DEFAULT_MESSAGE_PRIORITY,
 			})
 			.collect()
 	}
@@ -428,6 +682,7 @@ mod tests {
 						size: 0,
 						reward: 0,
 						dispatch_fee_payment: DispatchFeePayment::AtSourceChain,
+						priority: DEFAULT_MESSAGE_PRIORITY,
 					}
 				),
 				(
@@ -437,6 +692,7 @@ mod tests {
 						size: 0,
 						reward: 0,
 						dispatch_fee_payment: DispatchFeePayment::AtSourceChain,
+						priority: DEFAULT_MESSAGE_PRIORITY,
 					}
 				),
 				(
@@ -446,6 +702,7 @@ mod tests {
 						size: 0,
 						reward: 0,
 						dispatch_fee_payment: DispatchFeePayment::AtSourceChain,
+						priority: DEFAULT_MESSAGE_PRIORITY,
 					}
 				),
 			]
@@ -466,6 +723,7 @@ mod tests {
 						size: 0,
 						reward: 0,
 						dispatch_fee_payment: DispatchFeePayment::AtSourceChain,
+						priority: DEFAULT_MESSAGE_PRIORITY,
 					}
 				),
 				(
@@ -475,6 +733,7 @@ mod tests {
 						size: 0,
 						reward: 0,
 						dispatch_fee_payment: DispatchFeePayment::AtSourceChain,
+						priority: DEFAULT_MESSAGE_PRIORITY,
 					}
 				),
 			]
@@ -509,6 +768,180 @@ mod tests {
 		));
 	}
 
+	#[test]
+	fn message_details_of_any_version_are_decoded() {
+		let details = message_details_from_rpc(1..=3);
+		assert_eq!(
+			decode_message_details::<relay_rialto_client::Rialto>(&details.encode()).unwrap(),
+			details,
+		);
+
+		let details_v0 = details
+			.iter()
+			.map(|details| bp_messages::MessageDetailsV0 {
+				nonce: details.nonce,
+				dispatch_weight: details.dispatch_weight,
+				size: details.size,
+				delivery_and_dispatch_fee: details.delivery_and_dispatch_fee,
+				dispatch_fee_payment: details.dispatch_fee_payment,
+			})
+			.collect::<Vec<_>>();
+		assert_eq!(
+			decode_message_details::<relay_rialto_client::Rialto>(&details_v0.encode()).unwrap(),
+			details,
+		);
+	}
+
+	fn pending_lane_messages(
+		nonces: RangeInclusive<MessageNonce>,
+		dispatch_weight: Weight,
+		size: u32,
+	) -> MessageDetailsMap<bp_rialto::Balance> {
+		nonces
+			.map(|nonce| {
+				(
+					nonce,
+					MessageDetails {
+						dispatch_weight,
+						size,
+						reward: 0,
+						dispatch_fee_payment: DispatchFeePayment::AtSourceChain,
+						priority: DEFAULT_MESSAGE_PRIORITY,
+					},
+				)
+			})
+			.collect()
+	}
+
+	#[test]
+	fn select_additional_lanes_messages_selects_all_messages_that_fit_into_limits() {
+		assert_eq!(
+			select_additional_lanes_messages(
+				AdditionalLanesLimits {
+					messages: 10,
+					weight: 1000,
+					size: 1000,
+				},
+				vec![
+					(LaneId::Legacy([0, 0, 0, 1]), pending_lane_messages(1..=3, 10, 10)),
+					(LaneId::Legacy([0, 0, 0, 2]), pending_lane_messages(5..=6, 20, 20)),
+				],
+			),
+			(
+				vec![
+					(LaneId::Legacy([0, 0, 0, 1]), 1, 3),
+					(LaneId::Legacy([0, 0, 0, 2]), 5, 6)
+				],
+				70
+			),
+		);
+	}
+
+	#[test]
+	fn select_additional_lanes_messages_respects_messages_count_limit() {
+		assert_eq!(
+			select_additional_lanes_messages(
+				AdditionalLanesLimits {
+					messages: 4,
+					weight: 1000,
+					size: 1000,
+				},
+				vec![
+					(LaneId::Legacy([0, 0, 0, 1]), pending_lane_messages(1..=3, 10, 10)),
+					(LaneId::Legacy([0, 0, 0, 2]), pending_lane_messages(5..=6, 20, 20)),
+				],
+			),
+			(
+				vec![
+					(LaneId::Legacy([0, 0, 0, 1]), 1, 3),
+					(LaneId::Legacy([0, 0, 0, 2]), 5, 5)
+				],
+				50
+			),
+		);
+	}
+
+	#[test]
+	fn select_additional_lanes_messages_respects_weight_and_size_limits() {
+		assert_eq!(
+			select_additional_lanes_messages(
+				AdditionalLanesLimits {
+					messages: 10,
+					weight: 25,
+					size: 1000,
+				},
+				vec![(LaneId::Legacy([0, 0, 0, 1]), pending_lane_messages(1..=3, 10, 10))],
+			),
+			(vec![(LaneId::Legacy([0, 0, 0, 1]), 1, 2)], 20),
+		);
+		assert_eq!(
+			select_additional_lanes_messages(
+				AdditionalLanesLimits {
+					messages: 10,
+					weight: 1000,
+					size: 15,
+				},
+				vec![(LaneId::Legacy([0, 0, 0, 1]), pending_lane_messages(1..=3, 10, 10))],
+			),
+			(vec![(LaneId::Legacy([0, 0, 0, 1]), 1, 1)], 10),
+		);
+	}
+
+	#[test]
+	fn select_additional_lanes_messages_never_skips_messages_of_the_lane() {
+		let mut lane_messages = pending_lane_messages(1..=3, 10, 10);
+		lane_messages.get_mut(&2).unwrap().dispatch_weight = 100;
+		assert_eq!(
+			select_additional_lanes_messages(
+				AdditionalLanesLimits {
+					messages: 10,
+					weight: 50,
+					size: 1000,
+				},
+				vec![
+					(LaneId::Legacy([0, 0, 0, 1]), lane_messages),
+					(LaneId::Legacy([0, 0, 0, 2]), pending_lane_messages(1..=1, 10, 10)),
+				],
+			),
+			(
+				vec![
+					(LaneId::Legacy([0, 0, 0, 1]), 1, 1),
+					(LaneId::Legacy([0, 0, 0, 2]), 1, 1)
+				],
+				20
+			),
+		);
+	}
+
+	#[test]
+	fn select_additional_lanes_messages_prefers_lanes_with_higher_priority_messages() {
+		let mut high_priority_lane_messages = pending_lane_messages(1..=3, 10, 10);
+		high_priority_lane_messages.get_mut(&3).unwrap().priority = 7;
+		let mut medium_priority_lane_messages = pending_lane_messages(1..=3, 10, 10);
+		medium_priority_lane_messages.get_mut(&1).unwrap().priority = 3;
+		assert_eq!(
+			select_additional_lanes_messages(
+				AdditionalLanesLimits {
+					messages: 5,
+					weight: 1000,
+					size: 1000,
+				},
+				vec![
+					(LaneId::Legacy([0, 0, 0, 1]), pending_lane_messages(1..=3, 10, 10)),
+					(LaneId::Legacy([0, 0, 0, 2]), medium_priority_lane_messages),
+					(LaneId::Legacy([0, 0, 0, 3]), high_priority_lane_messages),
+				],
+			),
+			(
+				vec![
+					(LaneId::Legacy([0, 0, 0, 3]), 1, 3),
+					(LaneId::Legacy([0, 0, 0, 2]), 1, 2)
+				],
+				50
+			),
+		);
+	}
+
 	#[test]
 	fn prepare_dummy_messages_delivery_proof_works() {
 		let expected_minimal_size = Rialto::MAXIMAL_ENCODED_ACCOUNT_ID_SIZE + Millau::STORAGE_PROOF_OVERHEAD;
@@ -23,7 +23,9 @@ use crate::messages_source::{read_client_state, SubstrateMessagesProof};
 use crate::on_demand_headers::OnDemandHeadersRelay;
 
 use async_trait::async_trait;
-use bp_messages::{LaneId, MessageNonce, UnrewardedRelayersState};
+use bp_messages::{
+	total_unrewarded_messages, InboundLaneData, LaneId, MessageNonce, UnrewardedRelayer, UnrewardedRelayersState,
+};
 use bp_runtime::ChainId;
 use bridge_runtime_common::messages::{
 	source::FromBridgedChainMessagesDeliveryProof, target::FromBridgedChainMessagesProof,
@@ -39,7 +41,7 @@ use relay_substrate_client::{Chain, Client, Error as SubstrateError, HashOf};
 use relay_utils::{relay_loop::Client as RelayClient, BlockNumberBase, HeaderId};
 use sp_core::Bytes;
 use sp_runtime::{traits::Header as HeaderT, DeserializeOwned, FixedPointNumber, FixedU128};
-use std::{convert::TryFrom, marker::PhantomData, ops::RangeInclusive};
+use std::{collections::VecDeque, convert::TryFrom, marker::PhantomData, ops::RangeInclusive};
 
 /// Message receiving proof returned by the target Substrate node.
 pub type SubstrateMessagesReceivingProof<C> = (
@@ -80,6 +82,79 @@ impl<SC: Chain, TC: Chain, P: SubstrateMessageLane, I> SubstrateMessagesTarget<S
 	}
 }
 
+impl<SC: Chain, TC: Chain, P: SubstrateMessageLane, I: Instance> SubstrateMessagesTarget<SC, TC, P, I> {
+	/// Prepare compact proof of unrewarded relayer entries that are not yet confirmed at the source chain.
+	///
+	/// Returns `None` if there are no such entries, or if some of them are missing from the
+	/// `InboundLaneRelayers` storage (i.e. they have been created before this storage has been introduced).
+	/// Then the whole inbound lane state must be proved.
+	async fn prove_unconfirmed_relayer_entries(
+		&self,
+		at_block: TC::Hash,
+	) -> Result<Option<SubstrateMessagesReceivingProof<TC>>, SubstrateError> {
+		let inbound_lane_data: Option<InboundLaneData<SC::AccountId>> = self
+			.client
+			.storage_value_at(
+				pallet_bridge_messages::storage_keys::inbound_lane_data_key::<I>(&self.lane_id),
+				Some(at_block),
+			)
+			.await?;
+		let inbound_lane_data = match inbound_lane_data {
+			Some(inbound_lane_data) => inbound_lane_data,
+			None => return Ok(None),
+		};
+
+		// entries that are confirmed at the target chain, are also confirmed at the source chain
+		let last_confirmed_nonce = inbound_lane_data.last_confirmed_nonce;
+		let unconfirmed_entries = inbound_lane_data
+			.relayers
+			.into_iter()
+			.filter(|entry| entry.messages.end > last_confirmed_nonce)
+			.collect::<VecDeque<_>>();
+		if unconfirmed_entries.is_empty() {
+			return Ok(None);
+		}
+
+		let mut entry_keys = Vec::with_capacity(unconfirmed_entries.len());
+		for entry in &unconfirmed_entries {
+			let entry_key = pallet_bridge_messages::storage_keys::inbound_lane_relayer_entry_key::<I>(
+				&self.lane_id,
+				entry.messages.begin,
+			);
+			let stored_entry: Option<UnrewardedRelayer<SC::AccountId>> =
+				self.client.storage_value_at(entry_key.clone(), Some(at_block)).await?;
+			if stored_entry.as_ref() != Some(entry) {
+				return Ok(None);
+			}
+
+			entry_keys.push(entry_key);
+		}
+
+		let storage_proof = self
+			.client
+			.prove_storage(entry_keys, at_block)
+			.await?
+			.iter_nodes()
+			.collect();
+		let relayers_state = UnrewardedRelayersState {
+			unrewarded_relayer_entries: unconfirmed_entries.len() as _,
+			messages_in_oldest_entry: unconfirmed_entries
+				.front()
+				.map(|entry| entry.messages.end - entry.messages.begin + 1)
+				.unwrap_or(0),
+			total_messages: total_unrewarded_messages(&unconfirmed_entries).unwrap_or(MessageNonce::MAX),
+		};
+		let proof = FromBridgedChainMessagesDeliveryProof {
+			bridged_header_hash: at_block,
+			storage_proof,
+			lane: self.lane_id,
+			relayer_entries: Some(unconfirmed_entries.iter().map(|entry| entry.messages.begin).collect()),
+		};
+
+		Ok(Some((relayers_state, proof)))
+	}
+}
+
 impl<SC: Chain, TC: Chain, P: SubstrateMessageLane, I> Clone for SubstrateMessagesTarget<SC, TC, P, I> {
 	fn clone(&self) -> Self {
 		Self {
@@ -197,6 +272,10 @@ where
 		&self,
 		id: TargetHeaderIdOf<P>,
 	) -> Result<(TargetHeaderIdOf<P>, P::MessagesReceivingProof), SubstrateError> {
+		if let Some(proof) = self.prove_unconfirmed_relayer_entries(id.1).await? {
+			return Ok((id, proof));
+		}
+
 		let (id, relayers_state) = self.unrewarded_relayers_state(id).await?;
 		let inbound_data_key = pallet_bridge_messages::storage_keys::inbound_lane_data_key::<I>(&self.lane_id);
 		let proof = self
@@ -209,6 +288,7 @@ where
 			bridged_header_hash: id.1,
 			storage_proof: proof,
 			lane: self.lane_id,
+			relayer_entries: None,
 		};
 		Ok((id, (relayers_state, proof)))
 	}
@@ -272,12 +352,17 @@ where
 				.unwrap_or_else(|_| TC::Balance::max_value()),
 		))
 	}
+
+	fn is_exhausts_resources_error(error: &SubstrateError) -> bool {
+		error.is_exhausts_resources_error()
+	}
 }
 
 /// Prepare 'dummy' messages proof that will compose the delivery transaction.
 ///
 /// We don't care about proof actually being the valid proof, because its validity doesn't
-/// affect the call weight - we only care about its size.
+/// affect the call weight - we only care about its size. Messages of additional lanes are not
+/// included, because only messages of the main lane are checked for profitability.
 fn prepare_dummy_messages_proof<SC: Chain>(
 	nonces: RangeInclusive<MessageNonce>,
 	total_dispatch_weight: Weight,
@@ -291,6 +376,7 @@ fn prepare_dummy_messages_proof<SC: Chain>(
 			lane: Default::default(),
 			nonces_start: *nonces.start(),
 			nonces_end: *nonces.end(),
+			additional_lanes: Vec::new(),
 		},
 	)
 }
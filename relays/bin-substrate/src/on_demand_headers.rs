@@ -57,6 +57,7 @@ impl<SourceChain: Chain> OnDemandHeadersRelay<SourceChain> {
 		target_client: Client<TargetChain>,
 		pipeline: SubstrateFinalityToSubstrate<SourceChain, TargetChain, TargetSign>,
 		maximal_headers_difference: SourceChain::BlockNumber,
+		metrics_params: MetricsParams,
 	) -> Self
 	where
 		SourceChain: Chain + Debug,
@@ -86,6 +87,7 @@ impl<SourceChain: Chain> OnDemandHeadersRelay<SourceChain> {
 				pipeline,
 				maximal_headers_difference,
 				required_header_number,
+				metrics_params,
 			)
 			.await;
 		});
@@ -117,6 +119,7 @@ async fn background_task<SourceChain, TargetChain, TargetSign>(
 	pipeline: SubstrateFinalityToSubstrate<SourceChain, TargetChain, TargetSign>,
 	maximal_headers_difference: SourceChain::BlockNumber,
 	required_header_number: RequiredHeaderNumberRef<SourceChain>,
+	metrics_params: MetricsParams,
 ) where
 	SourceChain: Chain + Debug,
 	SourceChain::BlockNumber: BlockNumberBase,
@@ -134,6 +137,9 @@ async fn background_task<SourceChain, TargetChain, TargetSign>(
 		FinalityTargetClient<SubstrateFinalityToSubstrate<SourceChain, TargetChain, TargetSign>>,
 {
 	let relay_task_name = on_demand_headers_relay_name::<SourceChain, TargetChain>();
+	let metrics_params = metrics_params.metrics_prefix(finality_relay::metrics_prefix::<
+		SubstrateFinalityToSubstrate<SourceChain, TargetChain, TargetSign>,
+	>());
 	let mut finality_source = SubstrateFinalitySource::<
 		_,
 		SubstrateFinalityToSubstrate<SourceChain, TargetChain, TargetSign>,
@@ -236,8 +242,9 @@ async fn background_task<SourceChain, TargetChain, TargetSign>(
 						recent_finality_proofs_limit: RECENT_FINALITY_PROOFS_LIMIT,
 						stall_timeout: STALL_TIMEOUT,
 						only_mandatory_headers: false,
+						on_demand: true,
 					},
-					MetricsParams::disabled(),
+					metrics_params.clone(),
 					futures::future::pending(),
 				)
 				.fuse(),
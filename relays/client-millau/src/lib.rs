@@ -16,6 +16,7 @@
 
 //! Types used to connect to the Millau-Substrate chain.
 
+use bp_header_chain::ChainWithGrandpa;
 use codec::Encode;
 use relay_substrate_client::{Chain, ChainBase, ChainWithBalances, TransactionSignScheme};
 use sp_core::{storage::StorageKey, Pair};
@@ -36,6 +37,13 @@ impl ChainBase for Millau {
 	type Header = millau_runtime::Header;
 }
 
+impl ChainWithGrandpa for Millau {
+	const MAX_AUTHORITIES_COUNT: u32 = <bp_millau::Millau as ChainWithGrandpa>::MAX_AUTHORITIES_COUNT;
+	const REASONABLE_HEADERS_IN_JUSTIFICATON_ANCESTRY: u32 =
+		<bp_millau::Millau as ChainWithGrandpa>::REASONABLE_HEADERS_IN_JUSTIFICATON_ANCESTRY;
+	const MAX_HEADER_SIZE: u32 = <bp_millau::Millau as ChainWithGrandpa>::MAX_HEADER_SIZE;
+}
+
 impl Chain for Millau {
 	const NAME: &'static str = "Millau";
 	const AVERAGE_BLOCK_INTERVAL: Duration = Duration::from_secs(5);
@@ -79,6 +87,7 @@ impl TransactionSignScheme for Millau {
 				frame_system::CheckNonce::<millau_runtime::Runtime>::from(signer_nonce),
 				frame_system::CheckWeight::<millau_runtime::Runtime>::new(),
 				pallet_transaction_payment::ChargeTransactionPayment::<millau_runtime::Runtime>::from(0),
+				millau_runtime::BridgeRejectObsoleteHeadersAndMessages,
 			),
 			(
 				millau_runtime::VERSION.spec_version,
@@ -88,6 +97,7 @@ impl TransactionSignScheme for Millau {
 				(),
 				(),
 				(),
+				(),
 			),
 		);
 		let signature = raw_payload.using_encoded(|payload| signer.sign(payload));
@@ -16,6 +16,7 @@
 
 //! Types used to connect to the Rococo-Substrate chain.
 
+use bp_header_chain::ChainWithGrandpa;
 use codec::Encode;
 use relay_substrate_client::{Chain, ChainBase, ChainWithBalances, TransactionSignScheme};
 use sp_core::{storage::StorageKey, Pair};
@@ -41,6 +42,13 @@ impl ChainBase for Rococo {
 	type Header = bp_rococo::Header;
 }
 
+impl ChainWithGrandpa for Rococo {
+	const MAX_AUTHORITIES_COUNT: u32 = <bp_rococo::Rococo as ChainWithGrandpa>::MAX_AUTHORITIES_COUNT;
+	const REASONABLE_HEADERS_IN_JUSTIFICATON_ANCESTRY: u32 =
+		<bp_rococo::Rococo as ChainWithGrandpa>::REASONABLE_HEADERS_IN_JUSTIFICATON_ANCESTRY;
+	const MAX_HEADER_SIZE: u32 = <bp_rococo::Rococo as ChainWithGrandpa>::MAX_HEADER_SIZE;
+}
+
 impl Chain for Rococo {
 	const NAME: &'static str = "Rococo";
 	const AVERAGE_BLOCK_INTERVAL: Duration = Duration::from_secs(6);
@@ -18,19 +18,26 @@
 
 use crate::chain::{Chain, ChainWithBalances};
 use crate::rpc::Substrate;
+use crate::rpc_capabilities::{self, RpcCapabilities};
+use crate::rpc_usage::{RpcUsage, RpcUsageStats};
+use crate::signed_extensions::SignedExtensionSchema;
 use crate::{ConnectionParams, Error, Result};
 
 use async_std::sync::{Arc, Mutex};
 use codec::Decode;
 use frame_system::AccountInfo;
 use futures::{SinkExt, StreamExt};
-use jsonrpsee_ws_client::{traits::SubscriptionClient, v2::params::JsonRpcParams, DeserializeOwned};
+use jsonrpsee_ws_client::{traits::SubscriptionClient, v2::params::JsonRpcParams, DeserializeOwned, JsonValue};
 use jsonrpsee_ws_client::{WsClient as RpcClient, WsClientBuilder as RpcClientBuilder};
 use num_traits::{Bounded, Zero};
 use pallet_balances::AccountData;
 use pallet_transaction_payment::InclusionFee;
 use relay_utils::relay_loop::RECONNECT_DELAY;
-use sp_core::{storage::StorageKey, Bytes};
+use sp_core::{
+	hexdisplay::HexDisplay,
+	storage::{StorageChangeSet, StorageData, StorageKey},
+	Bytes,
+};
 use sp_trie::StorageProof;
 use sp_version::RuntimeVersion;
 use std::{convert::TryFrom, future::Future};
@@ -41,6 +48,12 @@ const MAX_SUBSCRIPTION_CAPACITY: usize = 4096;
 /// Opaque justifications subscription type.
 pub struct JustificationsSubscription(Mutex<futures::channel::mpsc::Receiver<Option<Bytes>>>);
 
+/// Storage changes subscription type.
+///
+/// The stream yields an item every time when value of any of subscribed keys is changed. It ends
+/// when the underlying subscription is lost.
+pub type StorageChangesSubscription = futures::channel::mpsc::Receiver<()>;
+
 /// Opaque GRANDPA authorities set.
 pub type OpaqueGrandpaAuthoritiesSet = Vec<u8>;
 
@@ -56,6 +69,10 @@ pub struct Client<C: Chain> {
 	client: Arc<RpcClient>,
 	/// Genesis block hash.
 	genesis_hash: C::Hash,
+	/// Storage-related RPC methods, supported by the node.
+	rpc_capabilities: RpcCapabilities,
+	/// RPC usage of this client and all its clones. It is preserved across reconnects.
+	rpc_usage: Arc<RpcUsage>,
 	/// If several tasks are submitting their transactions simultaneously using `submit_signed_extrinsic`
 	/// method, they may get the same transaction nonce. So one of transactions will be rejected
 	/// from the pool. This lock is here to prevent situations like that.
@@ -69,6 +86,8 @@ impl<C: Chain> Clone for Client<C> {
 			params: self.params.clone(),
 			client: self.client.clone(),
 			genesis_hash: self.genesis_hash,
+			rpc_capabilities: self.rpc_capabilities,
+			rpc_usage: self.rpc_usage.clone(),
 			submit_signed_extrinsic_lock: self.submit_signed_extrinsic_lock.clone(),
 		}
 	}
@@ -114,12 +133,16 @@ impl<C: Chain> Client<C> {
 		let genesis_hash = tokio
 			.spawn(async move { Substrate::<C>::chain_get_block_hash(&*genesis_hash_client, number).await })
 			.await??;
+		let rpc_capabilities = Self::detect_rpc_capabilities(&tokio, client.clone()).await?;
+		let rpc_usage = Arc::new(RpcUsage::new(C::NAME, params.max_daily_rpc_requests));
 
 		Ok(Self {
 			tokio,
 			params,
 			client,
 			genesis_hash,
+			rpc_capabilities,
+			rpc_usage,
 			submit_signed_extrinsic_lock: Arc::new(Mutex::new(())),
 		})
 	}
@@ -127,11 +150,34 @@ impl<C: Chain> Client<C> {
 	/// Reopen client connection.
 	pub async fn reconnect(&mut self) -> Result<()> {
 		let (tokio, client) = Self::build_client(self.params.clone()).await?;
+		self.rpc_capabilities = Self::detect_rpc_capabilities(&tokio, client.clone()).await?;
 		self.tokio = tokio;
 		self.client = client;
 		Ok(())
 	}
 
+	/// Detect storage-related RPC methods that are supported by the node.
+	async fn detect_rpc_capabilities(
+		tokio: &tokio::runtime::Runtime,
+		client: Arc<RpcClient>,
+	) -> Result<RpcCapabilities> {
+		let rpc_methods = tokio
+			.spawn(async move { Substrate::<C>::rpc_methods(&*client).await })
+			.await?;
+		let rpc_capabilities = rpc_methods
+			.ok()
+			.and_then(|rpc_methods| RpcCapabilities::from_rpc_methods(&rpc_methods))
+			.unwrap_or_else(RpcCapabilities::legacy);
+		log::debug!(
+			target: "bridge",
+			"Detected {} node RPC capabilities: {:?}",
+			C::NAME,
+			rpc_capabilities,
+		);
+
+		Ok(rpc_capabilities)
+	}
+
 	/// Build client to use in connection.
 	async fn build_client(params: ConnectionParams) -> Result<(Arc<tokio::runtime::Runtime>, Arc<RpcClient>)> {
 		let tokio = tokio::runtime::Runtime::new()?;
@@ -174,6 +220,16 @@ impl<C: Chain> Client<C> {
 		&self.genesis_hash
 	}
 
+	/// Return storage-related RPC methods, supported by the node.
+	pub fn rpc_capabilities(&self) -> RpcCapabilities {
+		self.rpc_capabilities
+	}
+
+	/// Return RPC usage statistics of this client and all its clones.
+	pub fn rpc_usage_stats(&self) -> RpcUsageStats {
+		self.rpc_usage.stats()
+	}
+
 	/// Return hash of the best finalized block.
 	pub async fn best_finalized_header_hash(&self) -> Result<C::Hash> {
 		self.jsonrpsee_execute(|client| async move { Ok(Substrate::<C>::chain_get_finalized_head(&*client).await?) })
@@ -232,15 +288,92 @@ impl<C: Chain> Client<C> {
 			.await
 	}
 
+	/// Return signed extensions schema of the current runtime.
+	pub async fn signed_extension_schema(&self) -> Result<SignedExtensionSchema> {
+		let metadata = self.runtime_metadata().await?;
+		SignedExtensionSchema::from_metadata(&metadata.0)
+	}
+
+	/// Read constant of the runtime pallet from the current runtime metadata.
+	pub async fn pallet_constant<T: Send + Decode + 'static>(
+		&self,
+		pallet_name: &str,
+		constant_name: &str,
+	) -> Result<T> {
+		let metadata = self.runtime_metadata().await?;
+		let encoded_value = crate::metadata::pallet_constant_value(&metadata.0, pallet_name, constant_name)?;
+		T::decode(&mut &encoded_value[..]).map_err(Error::ResponseParseFailed)
+	}
+
+	/// Read index of the runtime pallet from the current runtime metadata.
+	pub async fn pallet_index(&self, pallet_name: &str) -> Result<u8> {
+		let metadata = self.runtime_metadata().await?;
+		crate::metadata::pallet_index(&metadata.0, pallet_name)
+	}
+
+	/// Read encoded metadata of the current runtime.
+	async fn runtime_metadata(&self) -> Result<Bytes> {
+		let metadata = self
+			.jsonrpsee_execute(move |client| async move { Ok(Substrate::<C>::state_get_metadata(&*client).await?) })
+			.await?;
+		self.rpc_usage.note_bytes(0, metadata.0.len());
+		Ok(metadata)
+	}
+
 	/// Read value from runtime storage.
 	pub async fn storage_value<T: Send + Decode + 'static>(&self, storage_key: StorageKey) -> Result<Option<T>> {
-		self.jsonrpsee_execute(move |client| async move {
-			Substrate::<C>::state_get_storage(&*client, storage_key)
-				.await?
-				.map(|encoded_value| T::decode(&mut &encoded_value.0[..]).map_err(Error::ResponseParseFailed))
-				.transpose()
-		})
-		.await
+		self.storage_value_at(storage_key, None).await
+	}
+
+	/// Read value from runtime storage at given block.
+	pub async fn storage_value_at<T: Send + Decode + 'static>(
+		&self,
+		storage_key: StorageKey,
+		at_block: Option<C::Hash>,
+	) -> Result<Option<T>> {
+		let storage_key_len = storage_key.0.len();
+		if self.rpc_capabilities.state_get_storage {
+			let encoded_value = self
+				.jsonrpsee_execute(move |client| async move {
+					Ok(Substrate::<C>::state_get_storage(&*client, storage_key, at_block).await?)
+				})
+				.await?;
+			return self.decode_storage_value(storage_key_len, encoded_value);
+		}
+		if !self.rpc_capabilities.archive_storage {
+			return Err(Error::RpcMethodUnavailable(rpc_capabilities::STATE_GET_STORAGE_METHOD));
+		}
+
+		let at_block = match at_block {
+			Some(at_block) => at_block,
+			None => self.best_finalized_header_hash().await?,
+		};
+		let encoded_value = self
+			.jsonrpsee_execute(move |client| async move {
+				let items = rpc_capabilities::archive_storage_items(&storage_key);
+				let response = Substrate::<C>::archive_storage(&*client, at_block, items, None).await?;
+				rpc_capabilities::archive_storage_value(&response, &storage_key)
+			})
+			.await?;
+		self.decode_storage_value(storage_key_len, encoded_value)
+	}
+
+	/// Note storage value read and decode the value.
+	fn decode_storage_value<T: Decode>(
+		&self,
+		storage_key_len: usize,
+		encoded_value: Option<StorageData>,
+	) -> Result<Option<T>> {
+		self.rpc_usage.note_bytes(
+			storage_key_len,
+			encoded_value
+				.as_ref()
+				.map(|encoded_value| encoded_value.0.len())
+				.unwrap_or(0),
+		);
+		encoded_value
+			.map(|encoded_value| T::decode(&mut &encoded_value.0[..]).map_err(Error::ResponseParseFailed))
+			.transpose()
 	}
 
 	/// Return native tokens balance of the account.
@@ -248,17 +381,25 @@ impl<C: Chain> Client<C> {
 	where
 		C: ChainWithBalances,
 	{
-		self.jsonrpsee_execute(move |client| async move {
-			let storage_key = C::account_info_storage_key(&account);
-			let encoded_account_data = Substrate::<C>::state_get_storage(&*client, storage_key)
-				.await?
-				.ok_or(Error::AccountDoesNotExist)?;
-			let decoded_account_data =
-				AccountInfo::<C::Index, AccountData<C::Balance>>::decode(&mut &encoded_account_data.0[..])
-					.map_err(Error::ResponseParseFailed)?;
-			Ok(decoded_account_data.data.free)
-		})
-		.await
+		let storage_key = C::account_info_storage_key(&account);
+		let account_data: AccountInfo<C::Index, AccountData<C::Balance>> = self
+			.storage_value(storage_key)
+			.await?
+			.ok_or(Error::AccountDoesNotExist)?;
+		Ok(account_data.data.free)
+	}
+
+	/// Return nonce of the account, stored in the runtime storage.
+	///
+	/// Unlike `next_account_index`, it ignores transactions that are still in the transaction pool.
+	pub async fn account_nonce(&self, account: C::AccountId) -> Result<C::Index>
+	where
+		C: ChainWithBalances,
+	{
+		let storage_key = C::account_info_storage_key(&account);
+		let account_info: Option<AccountInfo<C::Index, AccountData<C::Balance>>> =
+			self.storage_value(storage_key).await?;
+		Ok(account_info.map(|account_info| account_info.nonce).unwrap_or_default())
 	}
 
 	/// Get the nonce of the given Substrate account.
@@ -275,6 +416,7 @@ impl<C: Chain> Client<C> {
 	///
 	/// Note: The given transaction needs to be SCALE encoded beforehand.
 	pub async fn submit_unsigned_extrinsic(&self, transaction: Bytes) -> Result<C::Hash> {
+		self.rpc_usage.note_bytes(transaction.0.len(), 0);
 		self.jsonrpsee_execute(move |client| async move {
 			let tx_hash = Substrate::<C>::author_submit_extrinsic(&*client, transaction).await?;
 			log::trace!(target: "bridge", "Sent transaction to Substrate node: {:?}", tx_hash);
@@ -297,8 +439,10 @@ impl<C: Chain> Client<C> {
 	) -> Result<C::Hash> {
 		let _guard = self.submit_signed_extrinsic_lock.lock().await;
 		let transaction_nonce = self.next_account_index(extrinsic_signer).await?;
+		let rpc_usage = self.rpc_usage.clone();
 		self.jsonrpsee_execute(move |client| async move {
 			let extrinsic = prepare_extrinsic(transaction_nonce);
+			rpc_usage.note_bytes(extrinsic.0.len(), 0);
 			let tx_hash = Substrate::<C>::author_submit_extrinsic(&*client, extrinsic).await?;
 			log::trace!(target: "bridge", "Sent transaction to {} node: {:?}", C::NAME, tx_hash);
 			Ok(tx_hash)
@@ -308,6 +452,7 @@ impl<C: Chain> Client<C> {
 
 	/// Estimate fee that will be spent on given extrinsic.
 	pub async fn estimate_extrinsic_fee(&self, transaction: Bytes) -> Result<C::Balance> {
+		self.rpc_usage.note_bytes(transaction.0.len(), 0);
 		self.jsonrpsee_execute(move |client| async move {
 			let fee_details = Substrate::<C>::payment_query_fee_details(&*client, transaction, None).await?;
 			let inclusion_fee = fee_details
@@ -331,37 +476,46 @@ impl<C: Chain> Client<C> {
 
 	/// Get the GRANDPA authority set at given block.
 	pub async fn grandpa_authorities_set(&self, block: C::Hash) -> Result<OpaqueGrandpaAuthoritiesSet> {
-		self.jsonrpsee_execute(move |client| async move {
-			let call = SUB_API_GRANDPA_AUTHORITIES.to_string();
-			let data = Bytes(Vec::new());
-
-			let encoded_response = Substrate::<C>::state_call(&*client, call, data, Some(block)).await?;
-			let authority_list = encoded_response.0;
-
-			Ok(authority_list)
-		})
-		.await
+		let encoded_response = self
+			.state_call(SUB_API_GRANDPA_AUTHORITIES.to_string(), Bytes(Vec::new()), Some(block))
+			.await?;
+		Ok(encoded_response.0)
 	}
 
 	/// Execute runtime call at given block.
 	pub async fn state_call(&self, method: String, data: Bytes, at_block: Option<C::Hash>) -> Result<Bytes> {
-		self.jsonrpsee_execute(move |client| async move {
-			Substrate::<C>::state_call(&*client, method, data, at_block)
-				.await
-				.map_err(Into::into)
-		})
-		.await
+		let data_len = data.0.len();
+		let encoded_response = self
+			.jsonrpsee_execute(move |client| async move {
+				Substrate::<C>::state_call(&*client, method, data, at_block)
+					.await
+					.map_err(Into::into)
+			})
+			.await?;
+		self.rpc_usage.note_bytes(data_len, encoded_response.0.len());
+		Ok(encoded_response)
 	}
 
 	/// Returns storage proof of given storage keys.
 	pub async fn prove_storage(&self, keys: Vec<StorageKey>, at_block: C::Hash) -> Result<StorageProof> {
-		self.jsonrpsee_execute(move |client| async move {
-			Substrate::<C>::state_prove_storage(&*client, keys, Some(at_block))
-				.await
-				.map(|proof| StorageProof::new(proof.proof.into_iter().map(|b| b.0).collect()))
-				.map_err(Into::into)
-		})
-		.await
+		if !self.rpc_capabilities.state_get_read_proof {
+			return Err(Error::RpcMethodUnavailable(
+				rpc_capabilities::STATE_GET_READ_PROOF_METHOD,
+			));
+		}
+
+		let keys_len = keys.iter().map(|key| key.0.len()).sum();
+		let proof_nodes: Vec<Vec<u8>> = self
+			.jsonrpsee_execute(move |client| async move {
+				Substrate::<C>::state_prove_storage(&*client, keys, Some(at_block))
+					.await
+					.map(|proof| proof.proof.into_iter().map(|b| b.0).collect())
+					.map_err(Into::into)
+			})
+			.await?;
+		self.rpc_usage
+			.note_bytes(keys_len, proof_nodes.iter().map(|node| node.len()).sum());
+		Ok(StorageProof::new(proof_nodes))
 	}
 
 	/// Return new justifications stream.
@@ -411,13 +565,69 @@ impl<C: Chain> Client<C> {
 		Ok(JustificationsSubscription(Mutex::new(receiver)))
 	}
 
+	/// Return new storage changes stream for given keys.
+	pub async fn subscribe_storage_changes(&self, keys: Vec<StorageKey>) -> Result<StorageChangesSubscription> {
+		let mut subscription = self
+			.jsonrpsee_execute(move |client| async move {
+				let keys = keys
+					.into_iter()
+					.map(|key| JsonValue::String(format!("0x{}", HexDisplay::from(&key.0))))
+					.collect();
+				Ok(client
+					.subscribe::<StorageChangeSet<C::Hash>>(
+						"state_subscribeStorage",
+						JsonRpcParams::Array(vec![JsonValue::Array(keys)]),
+						"state_unsubscribeStorage",
+					)
+					.await?)
+			})
+			.await?;
+		let (mut sender, receiver) = futures::channel::mpsc::channel(MAX_SUBSCRIPTION_CAPACITY);
+		self.tokio.spawn(async move {
+			loop {
+				match subscription.next().await {
+					Ok(Some(_)) => {
+						if sender.send(()).await.is_err() {
+							break;
+						}
+					}
+					Ok(None) => {
+						log::trace!(
+							target: "bridge",
+							"{} storage changes subscription stream has returned None. Stream needs to be restarted.",
+							C::NAME,
+						);
+						break;
+					}
+					Err(e) => {
+						log::trace!(
+							target: "bridge",
+							"{} storage changes subscription stream has returned '{:?}'. Stream needs to be restarted.",
+							C::NAME,
+							e,
+						);
+						break;
+					}
+				}
+			}
+		});
+		Ok(receiver)
+	}
+
 	/// Execute jsonrpsee future in tokio context.
+	///
+	/// Every call is accounted as a single RPC request. If the client is in the reduced-polling
+	/// mode, the request is delayed.
 	async fn jsonrpsee_execute<MF, F, T>(&self, make_jsonrpsee_future: MF) -> Result<T>
 	where
 		MF: FnOnce(Arc<RpcClient>) -> F + Send + 'static,
 		F: Future<Output = Result<T>> + Send,
 		T: Send + 'static,
 	{
+		if let Some(delay) = self.rpc_usage.note_request() {
+			async_std::task::sleep(delay).await;
+		}
+
 		let client = self.client.clone();
 		self.tokio
 			.spawn(async move { make_jsonrpsee_future(client).await })
@@ -16,10 +16,15 @@
 
 //! Substrate node RPC errors.
 
+use bp_runtime::BridgeError;
 use jsonrpsee_ws_client::Error as RpcError;
 use relay_utils::MaybeConnectionError;
 use sc_rpc_api::system::Health;
 
+/// Part of the transaction pool error message, returned when the transaction would exhaust
+/// block resources (`InvalidTransaction::ExhaustsResources`).
+const EXHAUSTS_RESOURCES_ERROR_MESSAGE: &str = "exhaust the block limits";
+
 /// Result type used by Substrate client.
 pub type Result<T> = std::result::Result<T, Error>;
 
@@ -44,6 +49,12 @@ pub enum Error {
 	ClientNotSynced(Health),
 	/// An error has happened when we have tried to parse storage proof.
 	StorageProofError(bp_runtime::StorageProofError),
+	/// The runtime metadata version is not supported by the client.
+	UnsupportedRuntimeMetadata,
+	/// The node doesn't support required RPC method and there's no supported alternative.
+	RpcMethodUnavailable(&'static str),
+	/// The call has been rejected by the bridge pallet with given error.
+	BridgePallet(BridgeError, String),
 	/// Custom logic error.
 	Custom(String),
 }
@@ -59,11 +70,36 @@ impl std::error::Error for Error {
 			Self::MissingMandatoryCodeEntry => None,
 			Self::ClientNotSynced(_) => None,
 			Self::StorageProofError(_) => None,
+			Self::UnsupportedRuntimeMetadata => None,
+			Self::RpcMethodUnavailable(_) => None,
+			Self::BridgePallet(_, _) => None,
 			Self::Custom(_) => None,
 		}
 	}
 }
 
+impl Error {
+	/// Returns bridge pallet error code, if the error has been caused by the bridge pallet.
+	pub fn bridge_error(&self) -> Option<BridgeError> {
+		match *self {
+			Self::BridgePallet(error, _) => Some(error),
+			_ => None,
+		}
+	}
+
+	/// Returns true if the transaction has been rejected, because it would exhaust block
+	/// resources (weight or size).
+	///
+	/// The node only reports this error in the stringified form, so we're looking for the
+	/// error message here.
+	pub fn is_exhausts_resources_error(&self) -> bool {
+		match *self {
+			Self::RpcError(ref e) => e.to_string().contains(EXHAUSTS_RESOURCES_ERROR_MESSAGE),
+			_ => false,
+		}
+	}
+}
+
 impl From<RpcError> for Error {
 	fn from(error: RpcError) -> Self {
 		Error::RpcError(error)
@@ -104,7 +140,13 @@ impl std::fmt::Display for Error {
 			Self::AccountDoesNotExist => "Account does not exist on the chain".into(),
 			Self::MissingMandatoryCodeEntry => "Mandatory :code: entry is missing from runtime storage".into(),
 			Self::StorageProofError(e) => format!("Error when parsing storage proof: {:?}", e),
+			Self::UnsupportedRuntimeMetadata => "Runtime metadata version is not supported".into(),
+			Self::RpcMethodUnavailable(method) => format!(
+				"Node doesn't support {} RPC method and there's no supported alternative",
+				method
+			),
 			Self::ClientNotSynced(health) => format!("Substrate client is not synced: {}", health),
+			Self::BridgePallet(error, reason) => format!("Bridge pallet error ({}): {}", error.name(), reason),
 			Self::Custom(e) => e.clone(),
 		};
 
@@ -23,11 +23,13 @@ use crate::sync_header::SyncHeader;
 
 use async_std::sync::{Arc, Mutex};
 use async_trait::async_trait;
-use bp_header_chain::justification::GrandpaJustification;
-use codec::Decode;
+use bp_header_chain::justification::{optimize, GrandpaJustification};
+use codec::{Decode, Encode};
+use finality_grandpa::voter_set::VoterSet;
 use finality_relay::{FinalitySyncPipeline, SourceClient, SourceHeader};
 use futures::stream::{unfold, Stream, StreamExt};
 use relay_utils::relay_loop::Client as RelayClient;
+use sp_finality_grandpa::AuthorityList;
 use sp_runtime::traits::Header as HeaderT;
 use std::{marker::PhantomData, pin::Pin};
 
@@ -125,14 +127,24 @@ where
 			.map(|raw_justification| GrandpaJustification::<C::Header>::decode(&mut raw_justification.as_slice()))
 			.transpose()
 			.map_err(Error::ResponseParseFailed)?;
+		let header = signed_block.header();
+		let justification = match justification {
+			Some(justification) => {
+				Some(optimize_justification(&self.client, *header.parent_hash(), justification).await)
+			}
+			None => None,
+		};
 
-		Ok((signed_block.header().into(), justification))
+		Ok((header.into(), justification))
 	}
 
 	async fn finality_proofs(&self) -> Result<Self::FinalityProofsStream, Error> {
 		Ok(unfold(
-			self.client.clone().subscribe_justifications().await?,
-			move |subscription| async move {
+			(
+				self.client.clone(),
+				self.client.clone().subscribe_justifications().await?,
+			),
+			move |(client, subscription)| async move {
 				loop {
 					let log_error = |err| {
 						log::error!(
@@ -159,10 +171,80 @@ where
 						}
 					};
 
-					return Some((justification, subscription));
+					let parent_hash = match client.header_by_hash(justification.commit.target_hash).await {
+						Ok(header) => *header.parent_hash(),
+						Err(err) => {
+							log_error(format!("failed to read justification target header: {:?}", err));
+							continue;
+						}
+					};
+					let justification = optimize_justification(&client, parent_hash, justification).await;
+
+					return Some((justification, (client, subscription)));
 				}
 			},
 		)
 		.boxed())
 	}
 }
+
+/// Remove redundant precommits and ancestry headers from the justification before it is
+/// submitted to the target chain.
+///
+/// GRANDPA authorities set is read at the parent of the justification target, because headers
+/// that are enacting authorities set change are still finalized by the previous set. If
+/// justification can't be optimized, it is returned unchanged.
+async fn optimize_justification<C: Chain>(
+	client: &Client<C>,
+	parent_hash: C::Hash,
+	justification: GrandpaJustification<C::Header>,
+) -> GrandpaJustification<C::Header> {
+	let authorities_set = match client
+		.grandpa_authorities_set(parent_hash)
+		.await
+		.and_then(|raw_authorities_set| {
+			AuthorityList::decode(&mut &raw_authorities_set[..]).map_err(Error::ResponseParseFailed)
+		}) {
+		Ok(authorities_set) => authorities_set,
+		Err(err) => {
+			log::debug!(
+				target: "bridge",
+				"Failed to read {} GRANDPA authorities set at {:?}. Justification is not optimized: {:?}",
+				C::NAME,
+				parent_hash,
+				err,
+			);
+			return justification;
+		}
+	};
+	let authorities_set = match VoterSet::new(authorities_set) {
+		Some(authorities_set) => authorities_set,
+		None => return justification,
+	};
+
+	let target = (justification.commit.target_hash, justification.commit.target_number);
+	let original_size = justification.encoded_size();
+	match optimize(target, &authorities_set, justification.clone()) {
+		Ok(optimized_justification) => {
+			log::trace!(
+				target: "bridge",
+				"Optimized {} justification of header {:?}: {} -> {} bytes",
+				C::NAME,
+				target.1,
+				original_size,
+				optimized_justification.encoded_size(),
+			);
+			optimized_justification
+		}
+		Err(err) => {
+			log::debug!(
+				target: "bridge",
+				"Failed to optimize {} justification of header {:?}: {:?}",
+				C::NAME,
+				target.1,
+				err,
+			);
+			justification
+		}
+	}
+}
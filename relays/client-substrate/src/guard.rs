@@ -21,6 +21,7 @@ use crate::{Chain, ChainWithBalances, Client};
 
 use async_trait::async_trait;
 use num_traits::CheckedSub;
+use relay_utils::alerts::AlertKind;
 use sp_version::RuntimeVersion;
 use std::{
 	collections::VecDeque,
@@ -115,15 +116,18 @@ pub fn abort_when_account_balance_decreased<C: ChainWithBalances>(
 						balances.front().expect("pushed to queue couple of lines above; qed");
 					let balances_difference = oldest_balance.checked_sub(&current_balance);
 					if balances_difference > Some(maximal_decrease) {
-						log::error!(
-							target: "bridge-guard",
-							"Balance of {} account {:?} has decreased from {:?} to {:?} in {} minutes. Aborting relay",
+						relay_utils::alerts::fire_and_wait(
+							AlertKind::BalanceExhaustion,
 							C::NAME,
-							account_id,
-							oldest_balance,
-							current_balance,
-							current_time.duration_since(*oldest_time).as_secs() / 60,
-						);
+							format!(
+								"Balance of account {:?} has decreased from {:?} to {:?} in {} minutes. Aborting relay",
+								account_id,
+								oldest_balance,
+								current_balance,
+								current_time.duration_since(*oldest_time).as_secs() / 60,
+							),
+						)
+						.await;
 
 						env.abort().await;
 					}
@@ -21,17 +21,23 @@
 mod chain;
 mod client;
 mod error;
+mod metadata;
 mod rpc;
+mod rpc_capabilities;
+mod rpc_usage;
 mod sync_header;
 
 pub mod finality_source;
 pub mod guard;
 pub mod headers_source;
 pub mod metrics;
+pub mod signed_extensions;
 
 pub use crate::chain::{BlockWithJustification, Chain, ChainWithBalances, TransactionSignScheme};
-pub use crate::client::{Client, JustificationsSubscription, OpaqueGrandpaAuthoritiesSet};
+pub use crate::client::{Client, JustificationsSubscription, OpaqueGrandpaAuthoritiesSet, StorageChangesSubscription};
 pub use crate::error::{Error, Result};
+pub use crate::rpc_capabilities::RpcCapabilities;
+pub use crate::rpc_usage::RpcUsageStats;
 pub use crate::sync_header::SyncHeader;
 pub use bp_runtime::{BlockNumberOf, Chain as ChainBase, HashOf, HeaderOf};
 
@@ -47,6 +53,9 @@ pub struct ConnectionParams {
 	pub port: u16,
 	/// Use secure websocket connection.
 	pub secure: bool,
+	/// Soft limit of RPC requests per day. When it is nearly reached, the client switches to the
+	/// reduced-polling mode.
+	pub max_daily_rpc_requests: Option<u64>,
 }
 
 impl Default for ConnectionParams {
@@ -55,6 +64,7 @@ impl Default for ConnectionParams {
 			host: "localhost".into(),
 			port: 9944,
 			secure: false,
+			max_daily_rpc_requests: None,
 		}
 	}
 }
@@ -0,0 +1,75 @@
+// Copyright 2019-2021 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Helpers to read values from the runtime metadata.
+
+use crate::error::{Error, Result};
+
+use codec::Decode;
+use frame_metadata::{DecodeDifferent, RuntimeMetadata, RuntimeMetadataPrefixed};
+
+/// Read SCALE-encoded value of the pallet constant from the SCALE-encoded runtime metadata.
+pub(crate) fn pallet_constant_value(
+	encoded_metadata: &[u8],
+	pallet_name: &str,
+	constant_name: &str,
+) -> Result<Vec<u8>> {
+	let metadata = RuntimeMetadataPrefixed::decode(&mut &encoded_metadata[..]).map_err(Error::ResponseParseFailed)?;
+	let modules = match metadata.1 {
+		RuntimeMetadata::V13(metadata) => decoded(metadata.modules)?,
+		_ => return Err(Error::UnsupportedRuntimeMetadata),
+	};
+
+	let module = modules
+		.into_iter()
+		.find(|module| matches!(module.name, DecodeDifferent::Decoded(ref name) if name == pallet_name))
+		.ok_or_else(|| Error::Custom(format!("Unknown pallet: {}", pallet_name)))?;
+	let constant = decoded(module.constants)?
+		.into_iter()
+		.find(|constant| matches!(constant.name, DecodeDifferent::Decoded(ref name) if name == constant_name))
+		.ok_or_else(|| Error::Custom(format!("Unknown constant: {}::{}", pallet_name, constant_name)))?;
+
+	decoded(constant.value)
+}
+
+/// Read index of the runtime pallet from the SCALE-encoded runtime metadata.
+///
+/// The index is the first byte of every encoded call of the pallet.
+pub(crate) fn pallet_index(encoded_metadata: &[u8], pallet_name: &str) -> Result<u8> {
+	let metadata = RuntimeMetadataPrefixed::decode(&mut &encoded_metadata[..]).map_err(Error::ResponseParseFailed)?;
+	let modules = match metadata.1 {
+		RuntimeMetadata::V13(metadata) => decoded(metadata.modules)?,
+		_ => return Err(Error::UnsupportedRuntimeMetadata),
+	};
+
+	modules
+		.into_iter()
+		.find(|module| matches!(module.name, DecodeDifferent::Decoded(ref name) if name == pallet_name))
+		.map(|module| module.index)
+		.ok_or_else(|| Error::Custom(format!("Unknown pallet: {}", pallet_name)))
+}
+
+/// Returns decoded value. Decoded metadata never contains values that are only encodable.
+fn decoded<B, O>(value: DecodeDifferent<B, O>) -> Result<O>
+where
+	B: 'static,
+	O: 'static,
+{
+	match value {
+		DecodeDifferent::Decoded(value) => Ok(value),
+		DecodeDifferent::Encode(_) => Err(Error::UnsupportedRuntimeMetadata),
+	}
+}
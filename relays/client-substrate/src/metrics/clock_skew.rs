@@ -0,0 +1,160 @@
+// Copyright 2019-2021 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+use crate::chain::Chain;
+use crate::client::Client;
+use crate::error::Error;
+
+use async_trait::async_trait;
+use relay_utils::metrics::{metric_name, register, Gauge, PrometheusError, Registry, StandaloneMetrics, F64};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Clock skew update interval (in blocks).
+const UPDATE_INTERVAL_IN_BLOCKS: u32 = 10;
+/// Name of the timestamp pallet.
+const TIMESTAMP_PALLET_NAME: &str = "Timestamp";
+/// Name of the timestamp pallet storage value with the timestamp of the current block.
+const TIMESTAMP_NOW_VALUE_NAME: &str = "Now";
+
+/// Metric that represents difference between the relay clock and the timestamp of the best
+/// chain block (in seconds) as floating point gauge.
+///
+/// The relay clock is used to build mortal transactions and to measure relay latencies. If the
+/// relay host clock is wrong, both are silently broken. So if the difference is too large, the
+/// warning is logged.
+///
+/// The timestamp of the best block is normally behind the relay clock by at most one block
+/// interval. So the acceptable difference is `[-max_clock_skew; block_interval + max_clock_skew]`.
+#[derive(Debug)]
+pub struct ClockSkewMetric<C: Chain> {
+	client: Client<C>,
+	max_clock_skew: Duration,
+	metric: Gauge<F64>,
+}
+
+impl<C: Chain> Clone for ClockSkewMetric<C> {
+	fn clone(&self) -> Self {
+		ClockSkewMetric {
+			client: self.client.clone(),
+			max_clock_skew: self.max_clock_skew,
+			metric: self.metric.clone(),
+		}
+	}
+}
+
+impl<C: Chain> ClockSkewMetric<C> {
+	/// Create new metric instance.
+	pub fn new(
+		registry: &Registry,
+		prefix: Option<&str>,
+		client: Client<C>,
+		max_clock_skew: Duration,
+	) -> Result<Self, PrometheusError> {
+		Ok(ClockSkewMetric {
+			client,
+			max_clock_skew,
+			metric: register(
+				Gauge::new(
+					metric_name(prefix, &format!("{}_clock_skew", C::NAME.to_lowercase())),
+					format!(
+						"Difference between the relay clock and the {} best block timestamp (in seconds)",
+						C::NAME
+					),
+				)?,
+				registry,
+			)?,
+		})
+	}
+
+	/// Read the best block timestamp and return difference between the relay clock and this
+	/// timestamp.
+	async fn read_clock_skew(&self) -> Result<f64, Error> {
+		let chain_timestamp: u64 = self
+			.client
+			.storage_value(bp_runtime::storage_value_key(
+				TIMESTAMP_PALLET_NAME,
+				TIMESTAMP_NOW_VALUE_NAME,
+			))
+			.await?
+			.ok_or_else(|| Error::Custom(format!("{} best block timestamp is missing", C::NAME)))?;
+		let local_timestamp = SystemTime::now()
+			.duration_since(UNIX_EPOCH)
+			.map_err(|e| Error::Custom(format!("Relay clock is set before the UNIX epoch: {}", e)))?
+			.as_millis();
+
+		Ok(clock_skew(local_timestamp, chain_timestamp as u128))
+	}
+}
+
+#[async_trait]
+impl<C: Chain> StandaloneMetrics for ClockSkewMetric<C> {
+	fn update_interval(&self) -> Duration {
+		C::AVERAGE_BLOCK_INTERVAL * UPDATE_INTERVAL_IN_BLOCKS
+	}
+
+	async fn update(&self) {
+		let clock_skew = self.read_clock_skew().await;
+		if let Ok(clock_skew) = clock_skew {
+			if !is_clock_skew_acceptable(clock_skew, C::AVERAGE_BLOCK_INTERVAL, self.max_clock_skew) {
+				log::warn!(
+					target: "bridge",
+					"Relay clock differs from the {} best block timestamp by {:.1}s. Mortal transactions and \
+					latency metrics are unreliable. Check the relay host clock and whether {} is producing blocks",
+					C::NAME,
+					clock_skew,
+					C::NAME,
+				);
+			}
+		}
+
+		relay_utils::metrics::set_gauge_value(&self.metric, clock_skew.map(Some));
+	}
+}
+
+/// Returns difference (in seconds) between local and chain timestamps (in milliseconds).
+fn clock_skew(local_timestamp: u128, chain_timestamp: u128) -> f64 {
+	(local_timestamp as f64 - chain_timestamp as f64) / 1000.0
+}
+
+/// Returns true if the clock skew (in seconds) is within acceptable range.
+fn is_clock_skew_acceptable(clock_skew: f64, block_interval: Duration, max_clock_skew: Duration) -> bool {
+	let max_clock_skew = max_clock_skew.as_secs_f64();
+	clock_skew >= -max_clock_skew && clock_skew <= block_interval.as_secs_f64() + max_clock_skew
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn clock_skew_is_computed_in_seconds() {
+		assert_eq!(clock_skew(10_500, 4_000), 6.5);
+		assert_eq!(clock_skew(4_000, 10_500), -6.5);
+	}
+
+	#[test]
+	fn clock_skew_is_acceptable_within_block_interval() {
+		let block_interval = Duration::from_secs(6);
+		let max_clock_skew = Duration::from_secs(10);
+
+		assert!(is_clock_skew_acceptable(0.0, block_interval, max_clock_skew));
+		assert!(is_clock_skew_acceptable(-10.0, block_interval, max_clock_skew));
+		assert!(is_clock_skew_acceptable(16.0, block_interval, max_clock_skew));
+
+		assert!(!is_clock_skew_acceptable(-10.5, block_interval, max_clock_skew));
+		assert!(!is_clock_skew_acceptable(16.5, block_interval, max_clock_skew));
+	}
+}
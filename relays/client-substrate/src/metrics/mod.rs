@@ -16,8 +16,12 @@
 
 //! Contains several Substrate-specific metrics that may be exposed by relay.
 
+pub use clock_skew::ClockSkewMetric;
 pub use float_storage_value::FloatStorageValueMetric;
+pub use rpc_usage::RpcUsageMetric;
 pub use storage_proof_overhead::StorageProofOverheadMetric;
 
+mod clock_skew;
 mod float_storage_value;
+mod rpc_usage;
 mod storage_proof_overhead;
@@ -0,0 +1,88 @@
+// Copyright 2019-2021 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+use crate::chain::Chain;
+use crate::client::Client;
+
+use async_trait::async_trait;
+use relay_utils::metrics::{metric_name, register, GaugeVec, Opts, PrometheusError, Registry, StandaloneMetrics, U64};
+use std::time::Duration;
+
+/// RPC usage update interval (in blocks).
+const UPDATE_INTERVAL_IN_BLOCKS: u32 = 5;
+
+/// Metric that represents RPC usage of the client: number of requests, number of transferred
+/// payload bytes and the budget state.
+///
+/// The client (and all its clones) is normally shared by all loops of the relay process. So if
+/// several loops are exposing this metric, all of them are showing the same values.
+#[derive(Debug)]
+pub struct RpcUsageMetric<C: Chain> {
+	client: Client<C>,
+	metric: GaugeVec<U64>,
+}
+
+impl<C: Chain> Clone for RpcUsageMetric<C> {
+	fn clone(&self) -> Self {
+		RpcUsageMetric {
+			client: self.client.clone(),
+			metric: self.metric.clone(),
+		}
+	}
+}
+
+impl<C: Chain> RpcUsageMetric<C> {
+	/// Create new metric instance.
+	pub fn new(registry: &Registry, prefix: Option<&str>, client: Client<C>) -> Result<Self, PrometheusError> {
+		Ok(RpcUsageMetric {
+			client,
+			metric: register(
+				GaugeVec::new(
+					Opts::new(
+						metric_name(prefix, &format!("{}_rpc_usage", C::NAME.to_lowercase())),
+						format!("{} node RPC usage", C::NAME),
+					),
+					&["type"],
+				)?,
+				registry,
+			)?,
+		})
+	}
+}
+
+#[async_trait]
+impl<C: Chain> StandaloneMetrics for RpcUsageMetric<C> {
+	fn update_interval(&self) -> Duration {
+		C::AVERAGE_BLOCK_INTERVAL * UPDATE_INTERVAL_IN_BLOCKS
+	}
+
+	async fn update(&self) {
+		let stats = self.client.rpc_usage_stats();
+		self.metric.with_label_values(&["requests"]).set(stats.total_requests);
+		self.metric
+			.with_label_values(&["bytes_sent"])
+			.set(stats.total_bytes_sent);
+		self.metric
+			.with_label_values(&["bytes_received"])
+			.set(stats.total_bytes_received);
+		self.metric
+			.with_label_values(&["budget_window_requests"])
+			.set(stats.window_requests);
+		self.metric
+			.with_label_values(&["reduced_polling"])
+			.set(stats.is_reduced_polling as u64);
+	}
+}
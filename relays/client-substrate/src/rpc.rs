@@ -18,6 +18,7 @@
 
 use crate::chain::Chain;
 
+use jsonrpsee_ws_client::JsonValue;
 use pallet_transaction_payment_rpc_runtime_api::FeeDetails;
 use sc_rpc_api::{state::ReadProof, system::Health};
 use sp_core::{
@@ -29,6 +30,8 @@ use sp_version::RuntimeVersion;
 
 jsonrpsee_proc_macros::rpc_client_api! {
 	pub(crate) Substrate<C: Chain> {
+		#[rpc(method = "rpc_methods", positional_params)]
+		fn rpc_methods() -> JsonValue;
 		#[rpc(method = "system_health", positional_params)]
 		fn system_health() -> Health;
 		#[rpc(method = "chain_getHeader", positional_params)]
@@ -46,11 +49,15 @@ jsonrpsee_proc_macros::rpc_client_api! {
 		#[rpc(method = "state_call", positional_params)]
 		fn state_call(method: String, data: Bytes, at_block: Option<C::Hash>) -> Bytes;
 		#[rpc(method = "state_getStorage", positional_params)]
-		fn state_get_storage(key: StorageKey) -> Option<StorageData>;
+		fn state_get_storage(key: StorageKey, at_block: Option<C::Hash>) -> Option<StorageData>;
 		#[rpc(method = "state_getReadProof", positional_params)]
 		fn state_prove_storage(keys: Vec<StorageKey>, hash: Option<C::Hash>) -> ReadProof<C::Hash>;
+		#[rpc(method = "archive_unstable_storage", positional_params)]
+		fn archive_storage(hash: C::Hash, items: JsonValue, child_trie: Option<Bytes>) -> JsonValue;
 		#[rpc(method = "state_getRuntimeVersion", positional_params)]
 		fn state_runtime_version() -> RuntimeVersion;
+		#[rpc(method = "state_getMetadata", positional_params)]
+		fn state_get_metadata() -> Bytes;
 		#[rpc(method = "payment_queryFeeDetails", positional_params)]
 		fn payment_query_fee_details(extrinsic: Bytes, at_block: Option<C::Hash>) -> FeeDetails<NumberOrHex>;
 	}
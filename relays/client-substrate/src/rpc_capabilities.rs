@@ -0,0 +1,159 @@
+// Copyright 2019-2021 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Detection of storage-related RPC methods that are supported by the node.
+//!
+//! Node providers are deprecating legacy `state_*` methods in favor of the new JSON-RPC spec.
+//! When the legacy `state_getStorage` is unavailable, storage is read using the `archive_*`
+//! methods of the new spec. There's no equivalent of the `state_getReadProof` in the new
+//! spec, so proofs may only be generated by nodes that are still supporting it.
+
+use crate::{Error, Result};
+
+use jsonrpsee_ws_client::JsonValue;
+use sp_core::storage::{StorageData, StorageKey};
+
+/// Name of the legacy RPC method that is used to read storage values.
+pub const STATE_GET_STORAGE_METHOD: &str = "state_getStorage";
+/// Name of the legacy RPC method that is used to generate storage proofs.
+pub const STATE_GET_READ_PROOF_METHOD: &str = "state_getReadProof";
+/// Name of the new JSON-RPC spec method that is used to read storage values.
+pub const ARCHIVE_STORAGE_METHOD: &str = "archive_unstable_storage";
+
+/// Storage-related RPC methods, supported by the node.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RpcCapabilities {
+	/// Node supports `state_getStorage` method.
+	pub state_get_storage: bool,
+	/// Node supports `state_getReadProof` method.
+	pub state_get_read_proof: bool,
+	/// Node supports `archive_unstable_storage` method.
+	pub archive_storage: bool,
+}
+
+impl RpcCapabilities {
+	/// Capabilities of the node that doesn't list its methods (i.e. doesn't support `rpc_methods`).
+	///
+	/// Such nodes are old enough to support all legacy methods.
+	pub fn legacy() -> Self {
+		RpcCapabilities {
+			state_get_storage: true,
+			state_get_read_proof: true,
+			archive_storage: false,
+		}
+	}
+
+	/// Parse capabilities from the `rpc_methods` response.
+	///
+	/// Returns `None` if response has unexpected format.
+	pub fn from_rpc_methods(response: &JsonValue) -> Option<Self> {
+		let methods = response.get("methods")?.as_array()?;
+		let has_method = |name: &str| methods.iter().any(|method| method.as_str() == Some(name));
+		Some(RpcCapabilities {
+			state_get_storage: has_method(STATE_GET_STORAGE_METHOD),
+			state_get_read_proof: has_method(STATE_GET_READ_PROOF_METHOD),
+			archive_storage: has_method(ARCHIVE_STORAGE_METHOD),
+		})
+	}
+}
+
+/// Prepare items argument of the `archive_unstable_storage` call that reads value of single key.
+pub(crate) fn archive_storage_items(key: &StorageKey) -> JsonValue {
+	JsonValue::Array(vec![JsonValue::Object(
+		vec![
+			(
+				"key".to_owned(),
+				JsonValue::String(sp_core::bytes::to_hex(&key.0, false)),
+			),
+			("type".to_owned(), JsonValue::String("value".to_owned())),
+		]
+		.into_iter()
+		.collect(),
+	)])
+}
+
+/// Read value of given key from the `archive_unstable_storage` response.
+pub(crate) fn archive_storage_value(response: &JsonValue, key: &StorageKey) -> Result<Option<StorageData>> {
+	let invalid_response = || Error::Custom(format!("Invalid {} response: {}", ARCHIVE_STORAGE_METHOD, response));
+	let items = response
+		.get("result")
+		.and_then(|items| items.as_array())
+		.ok_or_else(invalid_response)?;
+	for item in items {
+		let item_key = item
+			.get("key")
+			.and_then(|key| key.as_str())
+			.and_then(|key| sp_core::bytes::from_hex(key).ok())
+			.ok_or_else(invalid_response)?;
+		if item_key != key.0 {
+			continue;
+		}
+
+		return match item.get("value").and_then(|value| value.as_str()) {
+			Some(value) => sp_core::bytes::from_hex(value)
+				.map(|value| Some(StorageData(value)))
+				.map_err(|_| invalid_response()),
+			None => Ok(None),
+		};
+	}
+
+	Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn json(s: &str) -> JsonValue {
+		s.parse().unwrap()
+	}
+
+	#[test]
+	fn capabilities_are_parsed_from_rpc_methods_response() {
+		assert_eq!(
+			RpcCapabilities::from_rpc_methods(&json(
+				r#"{"version": 1, "methods": ["state_getStorage", "archive_unstable_storage"]}"#
+			)),
+			Some(RpcCapabilities {
+				state_get_storage: true,
+				state_get_read_proof: false,
+				archive_storage: true,
+			}),
+		);
+		assert_eq!(RpcCapabilities::from_rpc_methods(&json(r#"{"version": 1}"#)), None);
+	}
+
+	#[test]
+	fn archive_storage_value_is_parsed() {
+		let key = StorageKey(vec![1, 2]);
+		let items = archive_storage_items(&key);
+		assert_eq!(items, json(r#"[{"key": "0x0102", "type": "value"}]"#));
+
+		assert_eq!(
+			archive_storage_value(
+				&json(r#"{"result": [{"key": "0x0102", "value": "0x2a"}], "discardedItems": 0}"#),
+				&key
+			)
+			.unwrap(),
+			Some(StorageData(vec![42])),
+		);
+		assert_eq!(
+			archive_storage_value(&json(r#"{"result": [], "discardedItems": 0}"#), &key).unwrap(),
+			None,
+		);
+		assert!(archive_storage_value(&json(r#"{"error": "oops"}"#), &key).is_err());
+	}
+}
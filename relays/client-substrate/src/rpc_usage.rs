@@ -0,0 +1,232 @@
+// Copyright 2019-2021 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+//! RPC usage accounting and budgeting.
+//!
+//! Many RPC providers are metered - requests above the plan quota are either billed, or lead to
+//! the relayer ban. So the client counts RPC requests and payload bytes. If the daily requests
+//! budget is configured, the client switches to the reduced-polling mode when the budget is nearly
+//! exhausted. In this mode every request is delayed, so that the rest of the budget is spread over
+//! the rest of the day. The budget is soft - requests are never rejected.
+
+use std::{
+	sync::Mutex,
+	time::{Duration, Instant},
+};
+
+/// Share of the daily budget (in percents), after which the client switches to the reduced-polling
+/// mode.
+const REDUCED_POLLING_THRESHOLD_PERCENT: u64 = 80;
+/// Maximal delay of the single request in the reduced-polling mode.
+const MAX_REDUCED_POLLING_DELAY: Duration = Duration::from_secs(60);
+/// Duration of the budget window.
+const BUDGET_WINDOW: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// RPC usage statistics.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct RpcUsageStats {
+	/// Total number of RPC requests, made since the client has been started.
+	pub total_requests: u64,
+	/// Total number of payload bytes (SCALE-encoded extrinsics, call arguments, storage keys),
+	/// sent since the client has been started.
+	pub total_bytes_sent: u64,
+	/// Total number of payload bytes (SCALE-encoded storage values, proofs, call results and
+	/// metadata), received since the client has been started.
+	pub total_bytes_received: u64,
+	/// Number of RPC requests, made within the current budget window.
+	pub window_requests: u64,
+	/// Whether the client is in the reduced-polling mode.
+	pub is_reduced_polling: bool,
+}
+
+/// RPC usage of the single client (and all its clones).
+#[derive(Debug)]
+pub(crate) struct RpcUsage {
+	chain_name: &'static str,
+	max_daily_requests: Option<u64>,
+	state: Mutex<RpcUsageState>,
+}
+
+#[derive(Debug)]
+struct RpcUsageState {
+	window_start: Instant,
+	stats: RpcUsageStats,
+}
+
+impl RpcUsage {
+	/// Create new RPC usage tracker.
+	pub fn new(chain_name: &'static str, max_daily_requests: Option<u64>) -> Self {
+		RpcUsage {
+			chain_name,
+			max_daily_requests,
+			state: Mutex::new(RpcUsageState {
+				window_start: Instant::now(),
+				stats: Default::default(),
+			}),
+		}
+	}
+
+	/// Returns current usage statistics.
+	pub fn stats(&self) -> RpcUsageStats {
+		self.state.lock().expect("RPC usage mutex is never poisoned; qed").stats
+	}
+
+	/// Note new RPC request. Returns delay that must be applied before making the request.
+	pub fn note_request(&self) -> Option<Duration> {
+		self.note_request_at(Instant::now())
+	}
+
+	/// Note payload bytes, transferred by the RPC request.
+	pub fn note_bytes(&self, sent: usize, received: usize) {
+		let mut state = self.state.lock().expect("RPC usage mutex is never poisoned; qed");
+		state.stats.total_bytes_sent = state.stats.total_bytes_sent.saturating_add(sent as u64);
+		state.stats.total_bytes_received = state.stats.total_bytes_received.saturating_add(received as u64);
+	}
+
+	fn note_request_at(&self, now: Instant) -> Option<Duration> {
+		let mut state = self.state.lock().expect("RPC usage mutex is never poisoned; qed");
+		let mut time_in_window = now.saturating_duration_since(state.window_start);
+		if time_in_window >= BUDGET_WINDOW {
+			state.window_start = now;
+			state.stats.window_requests = 0;
+			time_in_window = Duration::from_secs(0);
+		}
+
+		state.stats.total_requests = state.stats.total_requests.saturating_add(1);
+		state.stats.window_requests = state.stats.window_requests.saturating_add(1);
+
+		let delay = self.max_daily_requests.and_then(|max_daily_requests| {
+			reduced_polling_delay(
+				max_daily_requests,
+				state.stats.window_requests,
+				BUDGET_WINDOW - time_in_window,
+			)
+		});
+		let is_reduced_polling = delay.is_some();
+		if is_reduced_polling != state.stats.is_reduced_polling {
+			state.stats.is_reduced_polling = is_reduced_polling;
+			if is_reduced_polling {
+				log::warn!(
+					target: "bridge",
+					"{} RPC requests budget is nearly exhausted ({} of {} daily requests are made). \
+					Switching to reduced-polling mode",
+					self.chain_name,
+					state.stats.window_requests,
+					self.max_daily_requests.unwrap_or_default(),
+				);
+			} else {
+				log::info!(
+					target: "bridge",
+					"{} RPC requests budget has been renewed. Leaving reduced-polling mode",
+					self.chain_name,
+				);
+			}
+		}
+
+		delay
+	}
+}
+
+/// Returns delay of the next request if the client needs to be in the reduced-polling mode.
+fn reduced_polling_delay(max_daily_requests: u64, window_requests: u64, time_left: Duration) -> Option<Duration> {
+	let threshold = max_daily_requests.saturating_mul(REDUCED_POLLING_THRESHOLD_PERCENT) / 100;
+	if window_requests < threshold {
+		return None;
+	}
+
+	// when the budget is exhausted, we keep making requests at the lowest rate
+	let requests_left = std::cmp::max(max_daily_requests.saturating_sub(window_requests), 1);
+	Some(std::cmp::min(
+		Duration::from_secs_f64(time_left.as_secs_f64() / requests_left as f64),
+		MAX_REDUCED_POLLING_DELAY,
+	))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn requests_are_not_delayed_until_threshold() {
+		assert_eq!(reduced_polling_delay(1000, 0, BUDGET_WINDOW), None);
+		assert_eq!(reduced_polling_delay(1000, 799, BUDGET_WINDOW), None);
+	}
+
+	#[test]
+	fn rest_of_budget_is_spread_over_rest_of_window() {
+		assert_eq!(
+			reduced_polling_delay(1000, 800, Duration::from_secs(2000)),
+			Some(Duration::from_secs(10)),
+		);
+		assert_eq!(
+			reduced_polling_delay(1000, 900, Duration::from_secs(1000)),
+			Some(Duration::from_secs(10)),
+		);
+	}
+
+	#[test]
+	fn delay_is_limited_when_budget_is_exhausted() {
+		assert_eq!(
+			reduced_polling_delay(1000, 1000, BUDGET_WINDOW),
+			Some(MAX_REDUCED_POLLING_DELAY),
+		);
+		assert_eq!(
+			reduced_polling_delay(1000, 5000, BUDGET_WINDOW),
+			Some(MAX_REDUCED_POLLING_DELAY),
+		);
+	}
+
+	#[test]
+	fn requests_are_counted_and_budget_window_is_renewed() {
+		let usage = RpcUsage::new("Test", Some(10));
+		let start = usage.state.lock().unwrap().window_start;
+
+		for _ in 0..7 {
+			assert_eq!(usage.note_request_at(start), None);
+		}
+		assert!(usage.note_request_at(start).is_some());
+		assert!(usage.stats().is_reduced_polling);
+
+		assert_eq!(usage.note_request_at(start + BUDGET_WINDOW), None);
+		assert_eq!(
+			usage.stats(),
+			RpcUsageStats {
+				total_requests: 9,
+				window_requests: 1,
+				..Default::default()
+			},
+		);
+	}
+
+	#[test]
+	fn requests_are_never_delayed_without_budget() {
+		let usage = RpcUsage::new("Test", None);
+		for _ in 0..100 {
+			assert_eq!(usage.note_request(), None);
+		}
+		usage.note_bytes(10, 20);
+		assert_eq!(
+			usage.stats(),
+			RpcUsageStats {
+				total_requests: 100,
+				total_bytes_sent: 10,
+				total_bytes_received: 20,
+				window_requests: 100,
+				is_reduced_polling: false,
+			},
+		);
+	}
+}
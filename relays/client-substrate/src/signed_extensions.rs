@@ -0,0 +1,363 @@
+// Copyright 2019-2021 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Declarative description of chain signed extensions.
+//!
+//! Every chain client crate has its own hard-coded set of signed extensions. The schema from this
+//! module allows to build signed transactions for chains that have extensions that are not known
+//! in advance. It is constructed from the runtime metadata, and may be adjusted by overriding
+//! encoding of separate extensions.
+
+use crate::chain::Chain;
+use crate::error::{Error, Result};
+
+use codec::{Compact, Decode, Encode};
+use frame_metadata::{DecodeDifferent, RuntimeMetadata, RuntimeMetadataPrefixed};
+use sp_runtime::{
+	generic::Era,
+	traits::{Dispatchable, SignedExtension, UniqueSaturatedInto},
+	transaction_validity::TransactionValidityError,
+};
+use std::marker::PhantomData;
+
+/// Signed extension, as it is known to the relay.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SignedExtensionKind {
+	/// The `frame_system::CheckSpecVersion` extension.
+	CheckSpecVersion,
+	/// The `frame_system::CheckTxVersion` extension.
+	CheckTxVersion,
+	/// The `frame_system::CheckGenesis` extension.
+	CheckGenesis,
+	/// The `frame_system::CheckMortality` (or `frame_system::CheckEra`) extension.
+	CheckMortality,
+	/// The `frame_system::CheckNonce` extension.
+	CheckNonce,
+	/// The `frame_system::CheckWeight` extension.
+	CheckWeight,
+	/// The `pallet_transaction_payment::ChargeTransactionPayment` extension.
+	ChargeTransactionPayment,
+	/// The `ChargeAssetTxPayment` extension. The fee is always paid in native tokens.
+	ChargeAssetTxPayment,
+	/// Extension with pre-encoded data.
+	///
+	/// Extensions that aren't known to the relay are treated as `Custom` extensions with empty
+	/// data. If that is not true, the encoding must be overridden.
+	Custom {
+		/// Data that is included in the transaction.
+		extra: Vec<u8>,
+		/// Data that is only included in the signed payload.
+		additional_signed: Vec<u8>,
+	},
+}
+
+impl SignedExtensionKind {
+	/// Select extension kind by its identifier.
+	pub fn from_identifier(identifier: &str) -> Self {
+		match identifier {
+			"CheckSpecVersion" => SignedExtensionKind::CheckSpecVersion,
+			"CheckTxVersion" => SignedExtensionKind::CheckTxVersion,
+			"CheckGenesis" => SignedExtensionKind::CheckGenesis,
+			"CheckMortality" | "CheckEra" => SignedExtensionKind::CheckMortality,
+			"CheckNonce" => SignedExtensionKind::CheckNonce,
+			"CheckWeight" => SignedExtensionKind::CheckWeight,
+			"ChargeTransactionPayment" => SignedExtensionKind::ChargeTransactionPayment,
+			"ChargeAssetTxPayment" => SignedExtensionKind::ChargeAssetTxPayment,
+			_ => SignedExtensionKind::Custom {
+				extra: Vec::new(),
+				additional_signed: Vec::new(),
+			},
+		}
+	}
+
+	/// Encode data that is included in the transaction.
+	fn encode_extra_to<C: Chain>(&self, params: &SignedExtensionParams<C>, dest: &mut Vec<u8>) {
+		match *self {
+			SignedExtensionKind::CheckMortality => params.era.encode_to(dest),
+			SignedExtensionKind::CheckNonce => Compact::<u64>(params.nonce.unique_saturated_into()).encode_to(dest),
+			SignedExtensionKind::ChargeTransactionPayment => {
+				Compact::<u128>(params.tip.unique_saturated_into()).encode_to(dest)
+			}
+			SignedExtensionKind::ChargeAssetTxPayment => {
+				Compact::<u128>(params.tip.unique_saturated_into()).encode_to(dest);
+				// no asset id => fee is paid in native tokens
+				Option::<()>::None.encode_to(dest);
+			}
+			SignedExtensionKind::Custom { ref extra, .. } => dest.extend_from_slice(extra),
+			SignedExtensionKind::CheckSpecVersion
+			| SignedExtensionKind::CheckTxVersion
+			| SignedExtensionKind::CheckGenesis
+			| SignedExtensionKind::CheckWeight => (),
+		}
+	}
+
+	/// Encode data that is only included in the signed payload.
+	fn encode_additional_signed_to<C: Chain>(&self, params: &SignedExtensionParams<C>, dest: &mut Vec<u8>) {
+		match *self {
+			SignedExtensionKind::CheckSpecVersion => params.spec_version.encode_to(dest),
+			SignedExtensionKind::CheckTxVersion => params.transaction_version.encode_to(dest),
+			SignedExtensionKind::CheckGenesis => params.genesis_hash.encode_to(dest),
+			SignedExtensionKind::CheckMortality => params.era_block_hash.encode_to(dest),
+			SignedExtensionKind::Custom {
+				ref additional_signed, ..
+			} => dest.extend_from_slice(additional_signed),
+			SignedExtensionKind::CheckNonce
+			| SignedExtensionKind::CheckWeight
+			| SignedExtensionKind::ChargeTransactionPayment
+			| SignedExtensionKind::ChargeAssetTxPayment => (),
+		}
+	}
+}
+
+/// Parameters that are used to encode signed extensions.
+#[derive(Clone, Debug)]
+pub struct SignedExtensionParams<C: Chain> {
+	/// Runtime spec version.
+	pub spec_version: u32,
+	/// Runtime transaction version.
+	pub transaction_version: u32,
+	/// Genesis block hash.
+	pub genesis_hash: C::Hash,
+	/// Transaction era.
+	pub era: Era,
+	/// Hash of the block, where transaction era starts. Genesis hash for immortal transactions.
+	pub era_block_hash: C::Hash,
+	/// Transaction nonce.
+	pub nonce: C::Index,
+	/// Transaction tip.
+	pub tip: C::Balance,
+}
+
+/// Ordered list of chain signed extensions.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SignedExtensionSchema {
+	extensions: Vec<(String, SignedExtensionKind)>,
+}
+
+impl SignedExtensionSchema {
+	/// Create schema from the ordered list of signed extension identifiers.
+	pub fn new(identifiers: impl IntoIterator<Item = String>) -> Self {
+		SignedExtensionSchema {
+			extensions: identifiers
+				.into_iter()
+				.map(|identifier| {
+					let kind = SignedExtensionKind::from_identifier(&identifier);
+					(identifier, kind)
+				})
+				.collect(),
+		}
+	}
+
+	/// Create schema from the SCALE-encoded runtime metadata.
+	pub fn from_metadata(encoded_metadata: &[u8]) -> Result<Self> {
+		let metadata =
+			RuntimeMetadataPrefixed::decode(&mut &encoded_metadata[..]).map_err(Error::ResponseParseFailed)?;
+		let signed_extensions = match metadata.1 {
+			RuntimeMetadata::V13(metadata) => metadata.extrinsic.signed_extensions,
+			_ => return Err(Error::UnsupportedRuntimeMetadata),
+		};
+
+		Ok(Self::new(signed_extensions.into_iter().map(
+			|identifier| match identifier {
+				DecodeDifferent::Encode(identifier) => identifier.into(),
+				DecodeDifferent::Decoded(identifier) => identifier,
+			},
+		)))
+	}
+
+	/// Override kind of the extension with given identifier.
+	///
+	/// Returns error if there's no such extension in the schema.
+	pub fn with_override(mut self, identifier: &str, kind: SignedExtensionKind) -> Result<Self> {
+		let extension = self
+			.extensions
+			.iter_mut()
+			.find(|(extension_identifier, _)| extension_identifier == identifier)
+			.ok_or_else(|| Error::Custom(format!("Unknown signed extension: {}", identifier)))?;
+		extension.1 = kind;
+		Ok(self)
+	}
+
+	/// Returns identifiers of all extensions in the schema.
+	pub fn identifiers(&self) -> impl Iterator<Item = &str> {
+		self.extensions.iter().map(|(identifier, _)| identifier.as_str())
+	}
+
+	/// Encode signed extensions using given parameters.
+	pub fn encode<C: Chain>(
+		&self,
+		params: &SignedExtensionParams<C>,
+	) -> EncodedSignedExtensions<C::AccountId, C::Call> {
+		let mut extra = Vec::new();
+		let mut additional_signed = Vec::new();
+		for (_, kind) in &self.extensions {
+			kind.encode_extra_to(params, &mut extra);
+			kind.encode_additional_signed_to(params, &mut additional_signed);
+		}
+
+		EncodedSignedExtensions {
+			extra: RawEncoded(extra),
+			additional_signed: RawEncoded(additional_signed),
+			_phantom: Default::default(),
+		}
+	}
+}
+
+/// Bytes that are already SCALE-encoded, so they're written as is.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RawEncoded(pub Vec<u8>);
+
+impl Encode for RawEncoded {
+	fn size_hint(&self) -> usize {
+		self.0.len()
+	}
+
+	fn encode_to<T: codec::Output + ?Sized>(&self, dest: &mut T) {
+		dest.write(&self.0)
+	}
+}
+
+/// Signed extensions, encoded using the `SignedExtensionSchema`.
+///
+/// May be used as `Extra` of `sp_runtime::generic::UncheckedExtrinsic` and
+/// `sp_runtime::generic::SignedPayload`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EncodedSignedExtensions<AccountId, Call> {
+	extra: RawEncoded,
+	additional_signed: RawEncoded,
+	_phantom: PhantomData<(AccountId, Call)>,
+}
+
+impl<AccountId, Call> Encode for EncodedSignedExtensions<AccountId, Call> {
+	fn size_hint(&self) -> usize {
+		self.extra.size_hint()
+	}
+
+	fn encode_to<T: codec::Output + ?Sized>(&self, dest: &mut T) {
+		self.extra.encode_to(dest)
+	}
+}
+
+impl<AccountId, Call> Decode for EncodedSignedExtensions<AccountId, Call> {
+	fn decode<I: codec::Input>(_input: &mut I) -> std::result::Result<Self, codec::Error> {
+		Err("Encoded signed extensions are never meant to be decoded".into())
+	}
+}
+
+impl<AccountId, Call> SignedExtension for EncodedSignedExtensions<AccountId, Call>
+where
+	AccountId: std::fmt::Debug + Clone + Eq + Send + Sync,
+	Call: Dispatchable + std::fmt::Debug + Clone + Eq + Send + Sync,
+{
+	const IDENTIFIER: &'static str = "Not needed.";
+
+	type AccountId = AccountId;
+	type Call = Call;
+	type AdditionalSigned = RawEncoded;
+	type Pre = ();
+
+	fn additional_signed(&self) -> std::result::Result<Self::AdditionalSigned, TransactionValidityError> {
+		Ok(self.additional_signed.clone())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn schema() -> SignedExtensionSchema {
+		SignedExtensionSchema::new(
+			vec![
+				"CheckSpecVersion",
+				"CheckTxVersion",
+				"CheckGenesis",
+				"CheckMortality",
+				"CheckNonce",
+				"CheckWeight",
+				"ChargeTransactionPayment",
+				"PrevalidateAttests",
+			]
+			.into_iter()
+			.map(Into::into),
+		)
+	}
+
+	#[test]
+	fn unknown_extensions_are_treated_as_empty() {
+		assert_eq!(
+			SignedExtensionKind::from_identifier("PrevalidateAttests"),
+			SignedExtensionKind::Custom {
+				extra: Vec::new(),
+				additional_signed: Vec::new(),
+			},
+		);
+		assert_eq!(
+			SignedExtensionKind::from_identifier("CheckEra"),
+			SignedExtensionKind::CheckMortality,
+		);
+	}
+
+	#[test]
+	fn override_is_rejected_for_unknown_extension() {
+		assert!(schema()
+			.with_override(
+				"CheckBridgedHeaders",
+				SignedExtensionKind::Custom {
+					extra: vec![42],
+					additional_signed: Vec::new(),
+				},
+			)
+			.is_err());
+	}
+
+	#[test]
+	fn overridden_extension_is_encoded() {
+		let schema = schema()
+			.with_override(
+				"PrevalidateAttests",
+				SignedExtensionKind::Custom {
+					extra: vec![42],
+					additional_signed: vec![43],
+				},
+			)
+			.unwrap();
+		assert_eq!(
+			schema.identifiers().collect::<Vec<_>>(),
+			vec![
+				"CheckSpecVersion",
+				"CheckTxVersion",
+				"CheckGenesis",
+				"CheckMortality",
+				"CheckNonce",
+				"CheckWeight",
+				"ChargeTransactionPayment",
+				"PrevalidateAttests",
+			],
+		);
+		assert_eq!(
+			schema.extensions.last().map(|(_, kind)| kind.clone()),
+			Some(SignedExtensionKind::Custom {
+				extra: vec![42],
+				additional_signed: vec![43],
+			}),
+		);
+	}
+
+	#[test]
+	fn raw_encoded_is_not_prefixed_with_length() {
+		assert_eq!(RawEncoded(vec![1, 2, 3]).encode(), vec![1, 2, 3]);
+	}
+}
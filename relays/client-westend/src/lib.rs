@@ -16,6 +16,7 @@
 
 //! Types used to connect to the Westend chain.
 
+use bp_header_chain::ChainWithGrandpa;
 use codec::Encode;
 use relay_substrate_client::{Chain, ChainBase, ChainWithBalances, TransactionSignScheme};
 use sp_core::{storage::StorageKey, Pair};
@@ -39,6 +40,13 @@ impl ChainBase for Westend {
 	type Header = bp_westend::Header;
 }
 
+impl ChainWithGrandpa for Westend {
+	const MAX_AUTHORITIES_COUNT: u32 = <bp_westend::Westend as ChainWithGrandpa>::MAX_AUTHORITIES_COUNT;
+	const REASONABLE_HEADERS_IN_JUSTIFICATON_ANCESTRY: u32 =
+		<bp_westend::Westend as ChainWithGrandpa>::REASONABLE_HEADERS_IN_JUSTIFICATON_ANCESTRY;
+	const MAX_HEADER_SIZE: u32 = <bp_westend::Westend as ChainWithGrandpa>::MAX_HEADER_SIZE;
+}
+
 impl Chain for Westend {
 	const NAME: &'static str = "Westend";
 	const AVERAGE_BLOCK_INTERVAL: Duration = Duration::from_secs(6);
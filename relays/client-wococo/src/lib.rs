@@ -16,6 +16,7 @@
 
 //! Types used to connect to the Wococo-Substrate chain.
 
+use bp_header_chain::ChainWithGrandpa;
 use codec::Encode;
 use relay_substrate_client::{Chain, ChainBase, ChainWithBalances, TransactionSignScheme};
 use sp_core::{storage::StorageKey, Pair};
@@ -41,6 +42,13 @@ impl ChainBase for Wococo {
 	type Header = bp_wococo::Header;
 }
 
+impl ChainWithGrandpa for Wococo {
+	const MAX_AUTHORITIES_COUNT: u32 = <bp_wococo::Wococo as ChainWithGrandpa>::MAX_AUTHORITIES_COUNT;
+	const REASONABLE_HEADERS_IN_JUSTIFICATON_ANCESTRY: u32 =
+		<bp_wococo::Wococo as ChainWithGrandpa>::REASONABLE_HEADERS_IN_JUSTIFICATON_ANCESTRY;
+	const MAX_HEADER_SIZE: u32 = <bp_wococo::Wococo as ChainWithGrandpa>::MAX_HEADER_SIZE;
+}
+
 impl Chain for Wococo {
 	const NAME: &'static str = "Wococo";
 	const AVERAGE_BLOCK_INTERVAL: Duration = Duration::from_secs(6);
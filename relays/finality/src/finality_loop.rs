@@ -19,14 +19,14 @@
 //! is the mandatory headers, which we always submit to the target node. For such headers, we
 //! assume that the persistent proof either exists, or will eventually become available.
 
-use crate::{FinalityProof, FinalitySyncPipeline, SourceHeader};
+use crate::{FinalityProof, FinalitySyncLoopMetrics, FinalitySyncPipeline, SourceHeader};
 
 use async_trait::async_trait;
 use backoff::backoff::Backoff;
 use futures::{select, Future, FutureExt, Stream, StreamExt};
-use headers_relay::sync_loop_metrics::SyncLoopMetrics;
 use num_traits::{One, Saturating};
 use relay_utils::{
+	alerts::{self, AlertKind},
 	metrics::{GlobalMetrics, MetricsParams},
 	relay_loop::Client as RelayClient,
 	retry_backoff, FailedClient, MaybeConnectionError,
@@ -36,6 +36,9 @@ use std::{
 	time::{Duration, Instant},
 };
 
+/// Number of consecutive loop iteration failures, after which the alert is fired.
+const MAX_CONSECUTIVE_FAILURES_BEFORE_ALERT: u32 = 5;
+
 /// Finality proof synchronization loop parameters.
 #[derive(Debug, Clone)]
 pub struct FinalitySyncParams {
@@ -60,6 +63,31 @@ pub struct FinalitySyncParams {
 	pub stall_timeout: Duration,
 	/// If true, only mandatory headers are relayed.
 	pub only_mandatory_headers: bool,
+	/// If true, the loop is driven by the on-demand headers relay. All non-mandatory headers that
+	/// we submit are then required by some other relay (e.g. messages relay).
+	pub on_demand: bool,
+}
+
+/// Kind of header that is submitted to the target node.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SubmittedHeaderKind {
+	/// Mandatory header (e.g. header that enacts new GRANDPA authorities set).
+	Mandatory,
+	/// Non-mandatory header that is required by some other relay (e.g. messages relay).
+	OnDemand,
+	/// Non-mandatory header that is submitted by the regular finality relay.
+	Periodic,
+}
+
+impl SubmittedHeaderKind {
+	/// Returns name of the header kind that is used in logs and metrics.
+	pub fn as_str(&self) -> &'static str {
+		match *self {
+			SubmittedHeaderKind::Mandatory => "mandatory",
+			SubmittedHeaderKind::OnDemand => "on_demand",
+			SubmittedHeaderKind::Periodic => "periodic",
+		}
+	}
 }
 
 /// Source client used in finality synchronization loop.
@@ -90,6 +118,11 @@ pub trait TargetClient<P: FinalitySyncPipeline>: RelayClient {
 
 	/// Submit header finality proof.
 	async fn submit_finality_proof(&self, header: P::Header, proof: P::FinalityProof) -> Result<(), Self::Error>;
+
+	/// Returns label of the finality proof submission error, that is used in metrics.
+	fn submission_error_label(_error: &Self::Error) -> &'static str {
+		"other"
+	}
 }
 
 /// Return prefix that will be used by default to expose Prometheus metrics of the finality proofs sync loop.
@@ -108,7 +141,7 @@ pub async fn run<P: FinalitySyncPipeline>(
 	let exit_signal = exit_signal.shared();
 	relay_utils::relay_loop(source_client, target_client)
 		.with_metrics(Some(metrics_prefix::<P>()), metrics_params)
-		.loop_metric(|registry, prefix| SyncLoopMetrics::new(registry, prefix))?
+		.loop_metric(|registry, prefix| FinalitySyncLoopMetrics::new(registry, prefix))?
 		.standalone_metric(|registry, prefix| GlobalMetrics::new(registry, prefix))?
 		.expose()
 		.await?
@@ -209,7 +242,7 @@ async fn run_until_connection_lost<P: FinalitySyncPipeline>(
 	source_client: impl SourceClient<P>,
 	target_client: impl TargetClient<P>,
 	sync_params: FinalitySyncParams,
-	metrics_sync: Option<SyncLoopMetrics>,
+	metrics_sync: Option<FinalitySyncLoopMetrics>,
 	exit_signal: impl Future<Output = ()>,
 ) -> Result<(), FailedClient> {
 	let restart_finality_proofs_stream = || async {
@@ -237,6 +270,7 @@ async fn run_until_connection_lost<P: FinalitySyncPipeline>(
 	let mut progress = (Instant::now(), None);
 	let mut retry_backoff = retry_backoff();
 	let mut last_transaction = None;
+	let mut consecutive_failures = 0u32;
 
 	loop {
 		// run loop iteration
@@ -259,10 +293,22 @@ async fn run_until_connection_lost<P: FinalitySyncPipeline>(
 			Ok(updated_last_transaction) => {
 				last_transaction = updated_last_transaction;
 				retry_backoff.reset();
+				consecutive_failures = 0;
 				sync_params.tick
 			}
 			Err(error) => {
 				log::error!(target: "bridge", "Finality sync loop iteration has failed with error: {:?}", error);
+				consecutive_failures += 1;
+				if consecutive_failures == MAX_CONSECUTIVE_FAILURES_BEFORE_ALERT {
+					alerts::fire(
+						AlertKind::RepeatedSubmissionFailures,
+						format!("{} -> {} finality relay", P::SOURCE_NAME, P::TARGET_NAME),
+						format!(
+							"Finality sync loop iteration has failed {} times in a row. Last error: {:?}",
+							consecutive_failures, error,
+						),
+					);
+				}
 				error.fail_if_connection_error()?;
 				retry_backoff
 					.next_backoff()
@@ -289,7 +335,7 @@ async fn run_loop_iteration<P, SC, TC>(
 	target_client: &TC,
 	state: FinalityLoopState<'_, P, SC::FinalityProofsStream>,
 	sync_params: &FinalitySyncParams,
-	metrics_sync: &Option<SyncLoopMetrics>,
+	metrics_sync: &Option<FinalitySyncLoopMetrics>,
 ) -> Result<Option<Transaction<P::Number>>, Error<P, SC::Error, TC::Error>>
 where
 	P: FinalitySyncPipeline,
@@ -311,6 +357,22 @@ where
 	}
 	*state.progress = print_sync_progress::<P>(*state.progress, best_number_at_source, best_number_at_target);
 
+	// target chain can't know finalized source headers that are not finalized at the source chain
+	if best_number_at_target > best_number_at_source {
+		alerts::fire(
+			AlertKind::FinalityDivergence,
+			format!("{} -> {} finality relay", P::SOURCE_NAME, P::TARGET_NAME),
+			format!(
+				"Best finalized {} header #{:?}, known to {}, is ahead of best finalized header #{:?} at {} node",
+				P::SOURCE_NAME,
+				best_number_at_target,
+				P::TARGET_NAME,
+				best_number_at_source,
+				P::SOURCE_NAME,
+			),
+		);
+	}
+
 	// if we have already submitted header, then we just need to wait for it
 	// if we're waiting too much, then we believe our transaction has been lost and restart sync
 	if let Some(last_transaction) = state.last_transaction {
@@ -342,7 +404,7 @@ where
 	)
 	.await?
 	{
-		Some((header, justification)) => {
+		Some((header, justification, kind)) => {
 			let new_transaction = Transaction {
 				time: Instant::now(),
 				submitted_header_number: header.number(),
@@ -350,16 +412,21 @@ where
 
 			log::debug!(
 				target: "bridge",
-				"Going to submit finality proof of {} header #{:?} to {}",
+				"Going to submit finality proof of {} header #{:?} ({}) to {}",
 				P::SOURCE_NAME,
 				new_transaction.submitted_header_number,
+				kind.as_str(),
 				P::TARGET_NAME,
 			);
 
-			target_client
-				.submit_finality_proof(header, justification)
-				.await
-				.map_err(Error::Target)?;
+			let submit_result = target_client.submit_finality_proof(header, justification).await;
+			if let Some(ref metrics_sync) = *metrics_sync {
+				match submit_result {
+					Ok(_) => metrics_sync.note_submitted_header(kind),
+					Err(ref error) => metrics_sync.note_failed_submission(TC::submission_error_label(error)),
+				}
+			}
+			submit_result.map_err(Error::Target)?;
 			Ok(Some(new_transaction))
 		}
 		None => Ok(None),
@@ -374,7 +441,7 @@ pub(crate) async fn select_header_to_submit<P, SC, TC>(
 	best_number_at_source: P::Number,
 	best_number_at_target: P::Number,
 	sync_params: &FinalitySyncParams,
-) -> Result<Option<(P::Header, P::FinalityProof)>, Error<P, SC::Error, TC::Error>>
+) -> Result<Option<(P::Header, P::FinalityProof, SubmittedHeaderKind)>, Error<P, SC::Error, TC::Error>>
 where
 	P: FinalitySyncPipeline,
 	SC: SourceClient<P>,
@@ -398,7 +465,9 @@ where
 	)
 	.await?;
 	let (mut unjustified_headers, mut selected_finality_proof) = match selected_finality_proof {
-		SelectedFinalityProof::Mandatory(header, finality_proof) => return Ok(Some((header, finality_proof))),
+		SelectedFinalityProof::Mandatory(header, finality_proof) => {
+			return Ok(Some((header, finality_proof, SubmittedHeaderKind::Mandatory)))
+		}
 		_ if sync_params.only_mandatory_headers => {
 			// we are not reading finality proofs from the stream, so eventually it'll break
 			// but we don't care about transient proofs at all, so it is acceptable
@@ -431,7 +500,12 @@ where
 		sync_params.recent_finality_proofs_limit,
 	);
 
-	Ok(selected_finality_proof)
+	let kind = if sync_params.on_demand {
+		SubmittedHeaderKind::OnDemand
+	} else {
+		SubmittedHeaderKind::Periodic
+	};
+	Ok(selected_finality_proof.map(|(header, finality_proof)| (header, finality_proof, kind)))
 }
 
 /// Finality proof that has been selected by the `read_missing_headers` function.
@@ -21,7 +21,7 @@
 use crate::finality_loop::{
 	prune_recent_finality_proofs, read_finality_proofs_from_stream, run, select_better_recent_finality_proof,
 	select_header_to_submit, FinalityProofs, FinalitySyncParams, RestartableFinalityProofsStream, SourceClient,
-	TargetClient,
+	SubmittedHeaderKind, TargetClient,
 };
 use crate::{FinalityProof, FinalitySyncPipeline, SourceHeader};
 
@@ -216,6 +216,7 @@ fn run_sync_loop(state_function: impl Fn(&mut ClientsData) -> bool + Send + Sync
 		recent_finality_proofs_limit: 1024,
 		stall_timeout: Duration::from_secs(1),
 		only_mandatory_headers: false,
+		on_demand: false,
 	};
 
 	let clients_data = source_client.data.clone();
@@ -279,7 +280,8 @@ fn finality_sync_loop_works() {
 fn run_only_mandatory_headers_mode_test(
 	only_mandatory_headers: bool,
 	has_mandatory_headers: bool,
-) -> Option<(TestSourceHeader, TestFinalityProof)> {
+	on_demand: bool,
+) -> Option<(TestSourceHeader, TestFinalityProof, SubmittedHeaderKind)> {
 	let (exit_sender, _) = futures::channel::mpsc::unbounded();
 	let (source_client, target_client) = prepare_test_clients(
 		exit_sender,
@@ -309,6 +311,7 @@ fn run_only_mandatory_headers_mode_test(
 			recent_finality_proofs_limit: 0,
 			stall_timeout: Duration::from_secs(0),
 			only_mandatory_headers,
+			on_demand,
 		},
 	))
 	.unwrap()
@@ -316,22 +319,54 @@ fn run_only_mandatory_headers_mode_test(
 
 #[test]
 fn select_header_to_submit_skips_non_mandatory_headers_when_only_mandatory_headers_are_required() {
-	assert_eq!(run_only_mandatory_headers_mode_test(true, false), None);
+	assert_eq!(run_only_mandatory_headers_mode_test(true, false, false), None);
 	assert_eq!(
-		run_only_mandatory_headers_mode_test(false, false),
-		Some((TestSourceHeader(false, 10), TestFinalityProof(10))),
+		run_only_mandatory_headers_mode_test(false, false, false),
+		Some((
+			TestSourceHeader(false, 10),
+			TestFinalityProof(10),
+			SubmittedHeaderKind::Periodic
+		)),
 	);
 }
 
 #[test]
 fn select_header_to_submit_selects_mandatory_headers_when_only_mandatory_headers_are_required() {
 	assert_eq!(
-		run_only_mandatory_headers_mode_test(true, true),
-		Some((TestSourceHeader(true, 8), TestFinalityProof(8))),
+		run_only_mandatory_headers_mode_test(true, true, false),
+		Some((
+			TestSourceHeader(true, 8),
+			TestFinalityProof(8),
+			SubmittedHeaderKind::Mandatory
+		)),
 	);
 	assert_eq!(
-		run_only_mandatory_headers_mode_test(false, true),
-		Some((TestSourceHeader(true, 8), TestFinalityProof(8))),
+		run_only_mandatory_headers_mode_test(false, true, false),
+		Some((
+			TestSourceHeader(true, 8),
+			TestFinalityProof(8),
+			SubmittedHeaderKind::Mandatory
+		)),
+	);
+}
+
+#[test]
+fn select_header_to_submit_reports_kind_of_selected_header() {
+	assert_eq!(
+		run_only_mandatory_headers_mode_test(false, false, true),
+		Some((
+			TestSourceHeader(false, 10),
+			TestFinalityProof(10),
+			SubmittedHeaderKind::OnDemand
+		)),
+	);
+	assert_eq!(
+		run_only_mandatory_headers_mode_test(false, true, true),
+		Some((
+			TestSourceHeader(true, 8),
+			TestFinalityProof(8),
+			SubmittedHeaderKind::Mandatory
+		)),
 	);
 }
 
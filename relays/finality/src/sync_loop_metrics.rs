@@ -0,0 +1,84 @@
+// Copyright 2019-2021 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Metrics for finality proofs synchronization loop.
+
+use crate::finality_loop::SubmittedHeaderKind;
+
+use headers_relay::sync_loop_metrics::SyncLoopMetrics;
+use relay_utils::metrics::{metric_name, register, CounterVec, Opts, PrometheusError, Registry, U64};
+
+/// Finality proofs sync metrics.
+#[derive(Clone)]
+pub struct FinalitySyncLoopMetrics {
+	/// Generic headers sync metrics (best block numbers at source and target nodes).
+	sync: SyncLoopMetrics,
+	/// Number of submitted headers of given kind (see `SubmittedHeaderKind`).
+	submitted_headers: CounterVec<U64>,
+	/// Number of failed finality proof submissions with given error label.
+	failed_submissions: CounterVec<U64>,
+}
+
+impl FinalitySyncLoopMetrics {
+	/// Create and register finality loop metrics.
+	pub fn new(registry: &Registry, prefix: Option<&str>) -> Result<Self, PrometheusError> {
+		Ok(FinalitySyncLoopMetrics {
+			sync: SyncLoopMetrics::new(registry, prefix)?,
+			submitted_headers: register(
+				CounterVec::new(
+					Opts::new(
+						metric_name(prefix, "submitted_headers"),
+						"Total number of submitted headers of given kind",
+					),
+					&["kind"],
+				)?,
+				registry,
+			)?,
+			failed_submissions: register(
+				CounterVec::new(
+					Opts::new(
+						metric_name(prefix, "failed_submissions"),
+						"Total number of failed finality proof submissions with given error",
+					),
+					&["error"],
+				)?,
+				registry,
+			)?,
+		})
+	}
+}
+
+impl FinalitySyncLoopMetrics {
+	/// Update best block number at source.
+	pub fn update_best_block_at_source<Number: Into<u64>>(&self, source_best_number: Number) {
+		self.sync.update_best_block_at_source(source_best_number);
+	}
+
+	/// Update best block number at target.
+	pub fn update_best_block_at_target<Number: Into<u64>>(&self, target_best_number: Number) {
+		self.sync.update_best_block_at_target(target_best_number);
+	}
+
+	/// Note that header of given kind has been submitted to the target node.
+	pub fn note_submitted_header(&self, kind: SubmittedHeaderKind) {
+		self.submitted_headers.with_label_values(&[kind.as_str()]).inc();
+	}
+
+	/// Note that finality proof submission has failed with given error.
+	pub fn note_failed_submission(&self, error_label: &str) {
+		self.failed_submissions.with_label_values(&[error_label]).inc();
+	}
+}
@@ -30,17 +30,27 @@ use crate::message_race_receiving::run as run_message_receiving_race;
 use crate::metrics::MessageLaneLoopMetrics;
 
 use async_trait::async_trait;
-use bp_messages::{LaneId, MessageNonce, UnrewardedRelayersState, Weight};
+use bp_messages::{LaneId, MessageNonce, MessagePriority, UnrewardedRelayersState, Weight};
 use bp_runtime::messages::DispatchFeePayment;
-use futures::{channel::mpsc::unbounded, future::FutureExt, stream::StreamExt};
+use futures::{
+	channel::mpsc::unbounded,
+	future::FutureExt,
+	stream::{BoxStream, StreamExt},
+};
 use relay_utils::{
 	interval,
 	metrics::{GlobalMetrics, MetricsParams},
 	process_future_result,
 	relay_loop::Client as RelayClient,
-	retry_backoff, FailedClient,
+	retry_backoff, FailedClient, MaybeConnectionError,
+};
+use std::{
+	collections::BTreeMap,
+	fmt::Debug,
+	future::Future,
+	ops::RangeInclusive,
+	time::{Duration, Instant},
 };
-use std::{collections::BTreeMap, fmt::Debug, future::Future, ops::RangeInclusive, time::Duration};
 
 /// Message lane loop configuration params.
 #[derive(Debug, Clone)]
@@ -55,6 +65,10 @@ pub struct Params {
 	pub reconnect_delay: Duration,
 	/// The loop will auto-restart if there has been no updates during this period.
 	pub stall_timeout: Duration,
+	/// The loop will switch to idle mode if there has been no outbound lane updates during this period.
+	pub idle_timeout: Duration,
+	/// Interval at which we ask both nodes about their updates when the lane is idle.
+	pub idle_tick: Duration,
 	/// Message delivery race parameters.
 	pub delivery_params: MessageDeliveryParams,
 }
@@ -100,6 +114,10 @@ pub struct MessageDetails<SourceChainBalance> {
 	pub reward: SourceChainBalance,
 	/// Where the fee for dispatching message is paid?
 	pub dispatch_fee_payment: DispatchFeePayment,
+	/// Message priority. Messages are always delivered in order, so it doesn't affect order of
+	/// messages within the lane. When messages of several lanes are delivered in the same
+	/// transaction, lanes with higher priority messages are served first.
+	pub priority: MessagePriority,
 }
 
 /// Messages details map.
@@ -161,6 +179,13 @@ pub trait SourceClient<P: MessageLane>: RelayClient {
 
 	/// Estimate cost of single message confirmation transaction in source chain tokens.
 	async fn estimate_confirmation_transaction(&self) -> P::SourceChainBalance;
+
+	/// Subscribe to outbound lane updates.
+	///
+	/// The returned stream must yield an item every time the outbound lane state is changed (e.g.
+	/// when new message is sent). Spurious items are allowed. The stream ends when the subscription
+	/// is lost.
+	async fn subscribe_outbound_lane_updates(&self) -> Result<BoxStream<'static, ()>, Self::Error>;
 }
 
 /// Target client trait.
@@ -213,6 +238,12 @@ pub trait TargetClient<P: MessageLane>: RelayClient {
 		total_dispatch_weight: Weight,
 		total_size: u32,
 	) -> Result<P::SourceChainBalance, Self::Error>;
+
+	/// Returns true if the error, returned by `submit_messages_proof`, means that the delivery
+	/// transaction exhausts block resources (weight or size) at the target chain.
+	fn is_exhausts_resources_error(_error: &Self::Error) -> bool {
+		false
+	}
 }
 
 /// State of the client.
@@ -304,6 +335,24 @@ async fn run_until_connection_lost<P: MessageLane, SC: SourceClient<P>, TC: Targ
 	let target_go_offline_future = futures::future::Fuse::terminated();
 	let target_tick_stream = interval(params.target_tick).fuse();
 
+	// when lane is idle, we're polling nodes less frequently and wake up when new message is sent
+	let mut is_lane_idle = false;
+	let mut last_lane_update = Instant::now();
+	let (is_idle_mode_supported, outbound_lane_updates) = match source_client.subscribe_outbound_lane_updates().await {
+		Ok(outbound_lane_updates) => (true, outbound_lane_updates.fuse()),
+		Err(error) if error.is_connection_error() => return Err(FailedClient::Source),
+		Err(error) => {
+			log::warn!(
+				target: "bridge",
+				"Failed to subscribe to {} lane {:?} updates: {:?}. Idle mode is disabled",
+				P::SOURCE_NAME,
+				params.lane,
+				error,
+			);
+			(false, futures::stream::pending().boxed().fuse())
+		}
+	};
+
 	let (
 		(delivery_source_state_sender, delivery_source_state_receiver),
 		(delivery_target_state_sender, delivery_target_state_receiver),
@@ -342,6 +391,7 @@ async fn run_until_connection_lost<P: MessageLane, SC: SourceClient<P>, TC: Targ
 		target_state,
 		target_go_offline_future,
 		target_tick_stream,
+		outbound_lane_updates,
 		delivery_race_loop,
 		receiving_race_loop,
 		exit_signal
@@ -411,6 +461,34 @@ async fn run_until_connection_lost<P: MessageLane, SC: SourceClient<P>, TC: Targ
 			_ = target_tick_stream.next() => {
 				target_state_required = true;
 			},
+			outbound_lane_update = outbound_lane_updates.next() => {
+				if outbound_lane_update.is_none() {
+					log::warn!(
+						target: "bridge",
+						"{} lane {:?} updates subscription has ended",
+						P::SOURCE_NAME,
+						params.lane,
+					);
+					return Err(FailedClient::Source);
+				}
+
+				last_lane_update = Instant::now();
+				if is_lane_idle {
+					log::debug!(
+						target: "bridge",
+						"{} -> {} lane {:?} has been updated. Leaving idle mode",
+						P::SOURCE_NAME,
+						P::TARGET_NAME,
+						params.lane,
+					);
+
+					is_lane_idle = false;
+					source_state_required = true;
+					target_state_required = true;
+					source_tick_stream.set(interval(params.source_tick).fuse());
+					target_tick_stream.set(interval(params.target_tick).fuse());
+				}
+			},
 
 			delivery_error = delivery_race_loop => {
 				match delivery_error {
@@ -430,6 +508,21 @@ async fn run_until_connection_lost<P: MessageLane, SC: SourceClient<P>, TC: Targ
 			}
 		}
 
+		if is_idle_mode_supported && !is_lane_idle && last_lane_update.elapsed() > params.idle_timeout {
+			log::debug!(
+				target: "bridge",
+				"{} -> {} lane {:?} has not been updated for {:?}. Entering idle mode",
+				P::SOURCE_NAME,
+				P::TARGET_NAME,
+				params.lane,
+				params.idle_timeout,
+			);
+
+			is_lane_idle = true;
+			source_tick_stream.set(interval(params.idle_tick).fuse());
+			target_tick_stream.set(interval(params.idle_tick).fuse());
+		}
+
 		if source_client_is_online && source_state_required {
 			log::debug!(target: "bridge", "Asking {} node about its state", P::SOURCE_NAME);
 			source_state.set(source_client.state().fuse());
@@ -447,7 +540,8 @@ async fn run_until_connection_lost<P: MessageLane, SC: SourceClient<P>, TC: Targ
 #[cfg(test)]
 pub(crate) mod tests {
 	use super::*;
-	use futures::stream::StreamExt;
+	use bp_messages::DEFAULT_MESSAGE_PRIORITY;
+	use futures::{channel::mpsc::UnboundedReceiver, stream::StreamExt};
 	use parking_lot::Mutex;
 	use relay_utils::{HeaderId, MaybeConnectionError};
 	use std::sync::Arc;
@@ -503,6 +597,7 @@ pub(crate) mod tests {
 	pub struct TestClientData {
 		is_source_fails: bool,
 		is_source_reconnected: bool,
+		source_state_requests: usize,
 		source_state: SourceClientState<TestMessageLane>,
 		source_latest_generated_nonce: MessageNonce,
 		source_latest_confirmed_received_nonce: MessageNonce,
@@ -523,6 +618,7 @@ pub(crate) mod tests {
 	pub struct TestSourceClient {
 		data: Arc<Mutex<TestClientData>>,
 		tick: Arc<dyn Fn(&mut TestClientData) + Send + Sync>,
+		outbound_lane_updates: Arc<Mutex<Option<UnboundedReceiver<()>>>>,
 	}
 
 	impl Default for TestSourceClient {
@@ -530,6 +626,7 @@ pub(crate) mod tests {
 			TestSourceClient {
 				data: Arc::new(Mutex::new(TestClientData::default())),
 				tick: Arc::new(|_| {}),
+				outbound_lane_updates: Arc::new(Mutex::new(None)),
 			}
 		}
 	}
@@ -553,6 +650,7 @@ pub(crate) mod tests {
 		async fn state(&self) -> Result<SourceClientState<TestMessageLane>, TestError> {
 			let mut data = self.data.lock();
 			(self.tick)(&mut *data);
+			data.source_state_requests += 1;
 			if data.is_source_fails {
 				return Err(TestError);
 			}
@@ -594,6 +692,7 @@ pub(crate) mod tests {
 							size: 1,
 							reward: 1,
 							dispatch_fee_payment: DispatchFeePayment::AtSourceChain,
+							priority: DEFAULT_MESSAGE_PRIORITY,
 						},
 					)
 				})
@@ -654,6 +753,13 @@ pub(crate) mod tests {
 		async fn estimate_confirmation_transaction(&self) -> TestSourceChainBalance {
 			CONFIRMATION_TRANSACTION_COST
 		}
+
+		async fn subscribe_outbound_lane_updates(&self) -> Result<BoxStream<'static, ()>, TestError> {
+			Ok(match self.outbound_lane_updates.lock().take() {
+				Some(outbound_lane_updates) => outbound_lane_updates.boxed(),
+				None => futures::stream::pending().boxed(),
+			})
+		}
 	}
 
 	#[derive(Clone)]
@@ -784,39 +890,62 @@ pub(crate) mod tests {
 		}
 	}
 
+	fn test_params() -> Params {
+		Params {
+			lane: LaneId::Legacy([0, 0, 0, 0]),
+			source_tick: Duration::from_millis(100),
+			target_tick: Duration::from_millis(100),
+			reconnect_delay: Duration::from_millis(0),
+			stall_timeout: Duration::from_millis(60 * 1000),
+			idle_timeout: Duration::from_millis(60 * 1000),
+			idle_tick: Duration::from_millis(100),
+			delivery_params: MessageDeliveryParams {
+				max_unrewarded_relayer_entries_at_target: 4,
+				max_unconfirmed_nonces_at_target: 4,
+				max_messages_in_single_batch: 4,
+				max_messages_weight_in_single_batch: 4,
+				max_messages_size_in_single_batch: 4,
+				relayer_mode: RelayerMode::Altruistic,
+			},
+		}
+	}
+
 	fn run_loop_test(
 		data: TestClientData,
 		source_tick: Arc<dyn Fn(&mut TestClientData) + Send + Sync>,
 		target_tick: Arc<dyn Fn(&mut TestClientData) + Send + Sync>,
 		exit_signal: impl Future<Output = ()> + 'static + Send,
 	) -> TestClientData {
-		async_std::task::block_on(async {
-			let data = Arc::new(Mutex::new(data));
+		run_loop_test_with_params(
+			Arc::new(Mutex::new(data)),
+			test_params(),
+			None,
+			source_tick,
+			target_tick,
+			exit_signal,
+		)
+	}
 
+	fn run_loop_test_with_params(
+		data: Arc<Mutex<TestClientData>>,
+		params: Params,
+		outbound_lane_updates: Option<UnboundedReceiver<()>>,
+		source_tick: Arc<dyn Fn(&mut TestClientData) + Send + Sync>,
+		target_tick: Arc<dyn Fn(&mut TestClientData) + Send + Sync>,
+		exit_signal: impl Future<Output = ()> + 'static + Send,
+	) -> TestClientData {
+		async_std::task::block_on(async {
 			let source_client = TestSourceClient {
 				data: data.clone(),
 				tick: source_tick,
+				outbound_lane_updates: Arc::new(Mutex::new(outbound_lane_updates)),
 			};
 			let target_client = TestTargetClient {
 				data: data.clone(),
 				tick: target_tick,
 			};
 			let _ = run(
-				Params {
-					lane: [0, 0, 0, 0],
-					source_tick: Duration::from_millis(100),
-					target_tick: Duration::from_millis(100),
-					reconnect_delay: Duration::from_millis(0),
-					stall_timeout: Duration::from_millis(60 * 1000),
-					delivery_params: MessageDeliveryParams {
-						max_unrewarded_relayer_entries_at_target: 4,
-						max_unconfirmed_nonces_at_target: 4,
-						max_messages_in_single_batch: 4,
-						max_messages_weight_in_single_batch: 4,
-						max_messages_size_in_single_batch: 4,
-						relayer_mode: RelayerMode::Altruistic,
-					},
-				},
+				params,
 				source_client,
 				target_client,
 				MetricsParams::disabled(),
@@ -950,4 +1079,76 @@ pub(crate) mod tests {
 		assert!(!result.target_to_source_header_requirements.is_empty());
 		assert!(!result.source_to_target_header_requirements.is_empty());
 	}
+
+	#[test]
+	fn idle_message_lane_loop_wakes_up_when_outbound_lane_is_updated() {
+		let (exit_sender, exit_receiver) = unbounded();
+		let (outbound_lane_updates_sender, outbound_lane_updates_receiver) = unbounded();
+		let data = Arc::new(Mutex::new(TestClientData {
+			source_state: ClientState {
+				best_self: HeaderId(10, 10),
+				best_finalized_self: HeaderId(10, 10),
+				best_finalized_peer_at_best_self: HeaderId(0, 0),
+			},
+			target_state: ClientState {
+				best_self: HeaderId(0, 0),
+				best_finalized_self: HeaderId(0, 0),
+				best_finalized_peer_at_best_self: HeaderId(10, 10),
+			},
+			..Default::default()
+		}));
+
+		// when the lane is idle, remember number of source state requests, then send a message
+		let idle_source_state_requests = Arc::new(Mutex::new(None));
+		let send_message_data = data.clone();
+		let send_message_idle_source_state_requests = idle_source_state_requests.clone();
+		async_std::task::spawn(async move {
+			async_std::task::sleep(Duration::from_millis(2000)).await;
+			let requests_before = send_message_data.lock().source_state_requests;
+			async_std::task::sleep(Duration::from_millis(500)).await;
+			let requests_after = send_message_data.lock().source_state_requests;
+			*send_message_idle_source_state_requests.lock() = Some((requests_before, requests_after));
+
+			send_message_data.lock().source_latest_generated_nonce = 1;
+			outbound_lane_updates_sender.unbounded_send(()).unwrap();
+		});
+
+		let result = run_loop_test_with_params(
+			data,
+			Params {
+				idle_timeout: Duration::from_millis(1000),
+				idle_tick: Duration::from_secs(60 * 60),
+				..test_params()
+			},
+			Some(outbound_lane_updates_receiver),
+			Arc::new(|data: &mut TestClientData| {
+				// blocks are produced on every tick
+				data.source_state.best_self =
+					HeaderId(data.source_state.best_self.0 + 1, data.source_state.best_self.1 + 1);
+				data.source_state.best_finalized_self = data.source_state.best_self;
+			}),
+			Arc::new(move |data: &mut TestClientData| {
+				// blocks are produced on every tick and source headers are relayed immediately
+				data.target_state.best_self =
+					HeaderId(data.target_state.best_self.0 + 1, data.target_state.best_self.1 + 1);
+				data.target_state.best_finalized_self = data.target_state.best_self;
+				data.target_state.best_finalized_peer_at_best_self = data.source_state.best_finalized_self;
+				// if message has been delivered => stop
+				if !data.submitted_messages_proofs.is_empty() {
+					exit_sender.unbounded_send(()).unwrap();
+				}
+			}),
+			futures::future::select(
+				exit_receiver.into_future().map(|(_, _)| ()),
+				async_std::task::sleep(Duration::from_secs(10)).boxed(),
+			)
+			.map(|_| ()),
+		);
+
+		// nodes haven't been polled while the lane has been idle
+		let (requests_before, requests_after) = idle_source_state_requests.lock().unwrap();
+		assert_eq!(requests_before, requests_after);
+		// the message has been delivered after lane has been woken up
+		assert_eq!(result.submitted_messages_proofs[0].0, 1..=1);
+	}
 }
@@ -214,6 +214,10 @@ where
 			.submit_messages_proof(generated_at_block, nonces, proof)
 			.await
 	}
+
+	fn is_exhausts_resources_error(error: &Self::Error) -> bool {
+		C::is_exhausts_resources_error(error)
+	}
 }
 
 /// Additional nonces data from the target client used by message delivery race.
@@ -535,6 +539,35 @@ where
 			},
 		))
 	}
+
+	fn proof_exhausts_resources(
+		&mut self,
+		nonces: RangeInclusive<MessageNonce>,
+		race_state: &mut RaceState<SourceHeaderIdOf<P>, TargetHeaderIdOf<P>, P::MessagesProof>,
+	) -> bool {
+		let rejected_messages = nonces.end().saturating_sub(*nonces.start()).saturating_add(1);
+		if rejected_messages <= 1 {
+			return false;
+		}
+
+		// the weight and size limits are computed using estimations, which may drift from actual values
+		// => let's remember that we can't deliver that many messages in a single transaction
+		let max_messages_in_single_batch = std::cmp::min(self.max_messages_in_single_batch, rejected_messages / 2);
+		log::warn!(
+			target: "bridge",
+			"{} -> {} delivery transaction with {} messages has exhausted block resources. Limiting number \
+			of messages in a single transaction to {}. Please check the relay calibration",
+			MessageDeliveryRace::<P>::source_name(),
+			MessageDeliveryRace::<P>::target_name(),
+			rejected_messages,
+			max_messages_in_single_batch,
+		);
+
+		self.max_messages_in_single_batch = max_messages_in_single_batch;
+		self.strategy.clear_source_queue();
+		race_state.nonces_to_submit = None;
+		true
+	}
 }
 
 /// From given set of source nonces, that are ready to be delivered, select nonces
@@ -783,6 +816,7 @@ mod tests {
 		},
 		MessageDetails,
 	};
+	use bp_messages::DEFAULT_MESSAGE_PRIORITY;
 	use bp_runtime::messages::DispatchFeePayment::*;
 
 	const DEFAULT_DISPATCH_WEIGHT: Weight = 1;
@@ -812,6 +846,7 @@ mod tests {
 							size: DEFAULT_SIZE,
 							reward,
 							dispatch_fee_payment,
+							priority: DEFAULT_MESSAGE_PRIORITY,
 						},
 					)
 				})
@@ -892,6 +927,7 @@ mod tests {
 							size: idx as _,
 							reward: idx as _,
 							dispatch_fee_payment: AtSourceChain,
+							priority: DEFAULT_MESSAGE_PRIORITY,
 						},
 					)
 				})
@@ -1267,4 +1303,32 @@ mod tests {
 			Some(((20..=24), proof_parameters(false, 5)))
 		);
 	}
+
+	#[async_std::test]
+	async fn message_delivery_strategy_selects_smaller_batch_if_transaction_exhausts_resources() {
+		let (mut state, mut strategy) = prepare_strategy();
+
+		// all queued messages are selected, but the delivery transaction is rejected
+		assert_eq!(
+			strategy.select_nonces_to_deliver(state.clone()).await,
+			Some(((20..=23), proof_parameters(false, 4)))
+		);
+		state.nonces_to_submit = Some((header_id(1), 20..=23, (20..=23, None)));
+		assert!(strategy.proof_exhausts_resources(20..=23, &mut state));
+		assert_eq!(state.nonces_to_submit, None);
+		assert_eq!(strategy.max_messages_in_single_batch, 2);
+		assert!(strategy.is_empty());
+
+		// when nonces are read from the source again, only half of them is selected
+		strategy.source_nonces_updated(header_id(1), source_nonces(20..=23, 19, DEFAULT_REWARD, AtSourceChain));
+		assert_eq!(
+			strategy.select_nonces_to_deliver(state.clone()).await,
+			Some(((20..=21), proof_parameters(false, 2)))
+		);
+
+		// single message can't be split
+		state.nonces_to_submit = Some((header_id(1), 20..=20, (20..=20, None)));
+		assert!(!strategy.proof_exhausts_resources(20..=20, &mut state));
+		assert_eq!(strategy.max_messages_in_single_batch, 2);
+	}
 }
@@ -140,6 +140,12 @@ pub trait TargetClient<P: MessageRace> {
 		nonces: RangeInclusive<MessageNonce>,
 		proof: P::Proof,
 	) -> Result<RangeInclusive<MessageNonce>, Self::Error>;
+
+	/// Returns true if the proof submission error means that the transaction exhausts block
+	/// resources (weight or size) at the target node.
+	fn is_exhausts_resources_error(_error: &Self::Error) -> bool {
+		false
+	}
 }
 
 /// Race strategy.
@@ -187,6 +193,17 @@ pub trait RaceStrategy<SourceHeaderId, TargetHeaderId, Proof>: Debug {
 		&mut self,
 		race_state: RaceState<SourceHeaderId, TargetHeaderId, Proof>,
 	) -> Option<(RangeInclusive<MessageNonce>, Self::ProofParameters)>;
+	/// Called when proof of `nonces` has been rejected by the target node, because the transaction
+	/// exhausts block resources.
+	///
+	/// If the strategy is able to select smaller batches, it should forget selected and queued
+	/// nonces (they'll be read from the source node again) and return true. Otherwise the same
+	/// proof is resubmitted later.
+	fn proof_exhausts_resources(
+		&mut self,
+		nonces: RangeInclusive<MessageNonce>,
+		race_state: &mut RaceState<SourceHeaderId, TargetHeaderId, Proof>,
+	) -> bool;
 }
 
 /// State of the race.
@@ -388,7 +405,11 @@ pub async fn run<P: MessageRace, SC: SourceClient<P>, TC: TargetClient<P>>(
 				).fail_if_connection_error(FailedClient::Source)?;
 			},
 			proof_submit_result = target_submit_proof => {
-				target_client_is_online = process_future_result(
+				let exhausts_resources = matches!(
+					proof_submit_result,
+					Err(ref error) if TC::is_exhausts_resources_error(error)
+				);
+				let proof_submitted = process_future_result(
 					proof_submit_result,
 					&mut target_retry_backoff,
 					|nonces_range| {
@@ -407,6 +428,29 @@ pub async fn run<P: MessageRace, SC: SourceClient<P>, TC: TargetClient<P>>(
 					async_std::task::sleep,
 					|| format!("Error submitting proof {}", P::target_name()),
 				).fail_if_connection_error(FailedClient::Target)?;
+				target_client_is_online = proof_submitted;
+
+				// if the proof has been rejected because the transaction exhausts block resources,
+				// let's try to deliver smaller batch. Otherwise, it may be because the source header
+				// it has been generated at is pruned already => regenerate it at the newer header
+				// if possible
+				if !proof_submitted && exhausts_resources && reduce_rejected_proof(&mut race_state, &mut strategy) {
+					source_nonces_required = true;
+					log::debug!(
+						target: "bridge",
+						"Going to deliver smaller batch of {} -> {} nonces",
+						P::source_name(),
+						P::target_name(),
+					);
+				} else if !proof_submitted && reanchor_rejected_proof(&mut race_state) {
+					log::debug!(
+						target: "bridge",
+						"Going to regenerate rejected {} -> {} proof at {:?}",
+						P::source_name(),
+						P::target_name(),
+						race_state.best_finalized_source_header_id_at_best_target,
+					);
+				}
 			},
 
 			// when we're ready to retry request
@@ -555,6 +599,47 @@ where
 	now_time
 }
 
+/// Forget proof that has been rejected by the target node, if it has been generated at the source
+/// header that is different from the best finalized source header known to the target node. Then
+/// the proof is regenerated at the latter header.
+///
+/// Returns true if the proof has been forgotten.
+fn reanchor_rejected_proof<SourceHeaderId: PartialEq, TargetHeaderId, Proof>(
+	race_state: &mut RaceState<SourceHeaderId, TargetHeaderId, Proof>,
+) -> bool {
+	let is_proof_outdated = match (
+		race_state.nonces_to_submit.as_ref(),
+		race_state.best_finalized_source_header_id_at_best_target.as_ref(),
+	) {
+		(Some((at_block, _, _)), Some(best_at_target)) => at_block != best_at_target,
+		_ => false,
+	};
+	if is_proof_outdated {
+		race_state.nonces_to_submit = None;
+	}
+
+	is_proof_outdated
+}
+
+/// Ask strategy to select smaller batch instead of the proof that has been rejected by the
+/// target node, because the transaction exhausts block resources.
+///
+/// Returns true if the proof has been forgotten.
+fn reduce_rejected_proof<SourceHeaderId, TargetHeaderId, Proof, Strategy>(
+	race_state: &mut RaceState<SourceHeaderId, TargetHeaderId, Proof>,
+	strategy: &mut Strategy,
+) -> bool
+where
+	Strategy: RaceStrategy<SourceHeaderId, TargetHeaderId, Proof>,
+{
+	let rejected_nonces = match race_state.nonces_to_submit.as_ref() {
+		Some((_, nonces, _)) => nonces.clone(),
+		None => return false,
+	};
+
+	strategy.proof_exhausts_resources(rejected_nonces, race_state)
+}
+
 async fn select_nonces_to_deliver<SourceHeaderId, TargetHeaderId, Proof, Strategy>(
 	race_state: RaceState<SourceHeaderId, TargetHeaderId, Proof>,
 	strategy: &mut Strategy,
@@ -623,4 +708,33 @@ mod tests {
 			Some((HeaderId(BEST_AT_TARGET, BEST_AT_TARGET), 6..=10, (),))
 		);
 	}
+	#[test]
+	fn rejected_proof_is_regenerated_at_newer_header() {
+		let mut race_state = RaceState::<_, (), ()> {
+			best_finalized_source_header_id_at_source: Some(HeaderId(10, 10)),
+			best_finalized_source_header_id_at_best_target: Some(HeaderId(8, 8)),
+			best_target_header_id: None,
+			best_finalized_target_header_id: None,
+			nonces_to_submit: Some((HeaderId(6, 6), 1..=5, ())),
+			nonces_submitted: None,
+		};
+
+		assert!(reanchor_rejected_proof(&mut race_state));
+		assert_eq!(race_state.nonces_to_submit, None);
+	}
+
+	#[test]
+	fn rejected_proof_is_kept_if_no_newer_header_is_known() {
+		let mut race_state = RaceState::<_, (), ()> {
+			best_finalized_source_header_id_at_source: Some(HeaderId(10, 10)),
+			best_finalized_source_header_id_at_best_target: Some(HeaderId(8, 8)),
+			best_target_header_id: None,
+			best_finalized_target_header_id: None,
+			nonces_to_submit: Some((HeaderId(8, 8), 1..=5, ())),
+			nonces_submitted: None,
+		};
+
+		assert!(!reanchor_rejected_proof(&mut race_state));
+		assert_eq!(race_state.nonces_to_submit, Some((HeaderId(8, 8), 1..=5, ())));
+	}
 }
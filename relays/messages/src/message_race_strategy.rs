@@ -119,6 +119,14 @@ where
 			.last()
 	}
 
+	/// Remove all nonces from the source queue.
+	///
+	/// Nonces that are greater than the best nonce at target node are read from the source
+	/// node again, when the race asks for source nonces.
+	pub fn clear_source_queue(&mut self) {
+		self.source_queue.clear();
+	}
+
 	/// Remove all nonces that are less than or equal to given nonce from the source queue.
 	pub fn remove_le_nonces_from_source_queue(&mut self, nonce: MessageNonce) {
 		while let Some((queued_at, queued_range)) = self.source_queue.pop_front() {
@@ -273,6 +281,19 @@ where
 		self.remove_le_nonces_from_source_queue(range_end);
 		Some((range_begin..=range_end, ()))
 	}
+
+	fn proof_exhausts_resources(
+		&mut self,
+		_nonces: RangeInclusive<MessageNonce>,
+		_race_state: &mut RaceState<
+			HeaderId<SourceHeaderHash, SourceHeaderNumber>,
+			HeaderId<TargetHeaderHash, TargetHeaderNumber>,
+			Proof,
+		>,
+	) -> bool {
+		// basic strategy doesn't limit number of nonces in the batch, so it can't select smaller batch
+		false
+	}
 }
 
 #[cfg(test)]
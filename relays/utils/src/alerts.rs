@@ -0,0 +1,296 @@
+// Copyright 2019-2021 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Optional alert sink, that notifies relay operators about critical conditions.
+//!
+//! Alerts are sent to the HTTP webhook, configured by the operator. The payload format is
+//! compatible with generic webhooks, Slack and Discord incoming webhooks and the Alertmanager
+//! `/api/v1/alerts` endpoint. This complements Prometheus metrics for teams without a full
+//! monitoring stack.
+
+use once_cell::sync::OnceCell;
+use serde_json::{json, Value as JsonValue};
+use std::{
+	collections::HashMap,
+	str::FromStr,
+	sync::Mutex,
+	time::{Duration, Instant},
+};
+
+/// Minimal interval between two alerts of the same kind, fired by the same source.
+pub const MIN_REPEAT_INTERVAL: Duration = Duration::from_secs(10 * 60);
+
+/// Installed alert sink.
+static ALERT_SINK: OnceCell<AlertSink> = OnceCell::new();
+
+/// Critical condition that is detected by the relay.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AlertKind {
+	/// Bridge pallet is halted.
+	PalletHalted,
+	/// Relay is repeatedly failing to submit transactions.
+	RepeatedSubmissionFailures,
+	/// Relay account balance is (almost) exhausted.
+	BalanceExhaustion,
+	/// Finality of the source chain, known to the target chain, has diverged from the actual one.
+	FinalityDivergence,
+	/// Bridged chain has been relaunched or its GRANDPA authorities set has been reset.
+	BridgedChainReset,
+}
+
+impl AlertKind {
+	/// Return alert name.
+	pub fn name(&self) -> &'static str {
+		match *self {
+			AlertKind::PalletHalted => "PalletHalted",
+			AlertKind::RepeatedSubmissionFailures => "RepeatedSubmissionFailures",
+			AlertKind::BalanceExhaustion => "BalanceExhaustion",
+			AlertKind::FinalityDivergence => "FinalityDivergence",
+			AlertKind::BridgedChainReset => "BridgedChainReset",
+		}
+	}
+}
+
+/// Alert that is sent to the sink.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Alert {
+	/// Alert kind.
+	pub kind: AlertKind,
+	/// Component that has detected the condition (e.g. relay loop name or chain name).
+	pub source: String,
+	/// Human-readable description of the condition.
+	pub message: String,
+}
+
+/// Format of the alert webhook payload.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AlertFormat {
+	/// Generic JSON object with `alert`, `source` and `message` fields.
+	Generic,
+	/// Slack incoming webhook.
+	Slack,
+	/// Discord webhook.
+	Discord,
+	/// Alertmanager `/api/v1/alerts` endpoint.
+	Alertmanager,
+}
+
+impl FromStr for AlertFormat {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s.to_lowercase().as_str() {
+			"generic" => Ok(AlertFormat::Generic),
+			"slack" => Ok(AlertFormat::Slack),
+			"discord" => Ok(AlertFormat::Discord),
+			"alertmanager" => Ok(AlertFormat::Alertmanager),
+			_ => Err(format!(
+				"Unknown alert format: {}. Supported formats are: generic, slack, discord, alertmanager",
+				s
+			)),
+		}
+	}
+}
+
+/// HTTP webhook alert sink.
+#[derive(Debug)]
+pub struct AlertSink {
+	url: String,
+	format: AlertFormat,
+	last_fired: Mutex<HashMap<(AlertKind, String), Instant>>,
+}
+
+impl AlertSink {
+	/// Create new alert sink.
+	pub fn new(url: String, format: AlertFormat) -> Self {
+		AlertSink {
+			url,
+			format,
+			last_fired: Mutex::new(HashMap::new()),
+		}
+	}
+
+	/// Install this sink as the process-wide alert sink.
+	///
+	/// Returns error if the sink has already been installed.
+	pub fn install(self) -> anyhow::Result<()> {
+		ALERT_SINK
+			.set(self)
+			.map_err(|_| anyhow::format_err!("Alert sink is already installed"))
+	}
+
+	/// Returns true if the alert should be sent to the webhook.
+	///
+	/// The same alert, fired by the same source is only sent once per `MIN_REPEAT_INTERVAL`.
+	fn should_send(&self, alert: &Alert, now: Instant) -> bool {
+		let mut last_fired = self.last_fired.lock().expect("alert sink mutex is never poisoned; qed");
+		let key = (alert.kind, alert.source.clone());
+		match last_fired.get(&key) {
+			Some(last_fired_at) if now.duration_since(*last_fired_at) < MIN_REPEAT_INTERVAL => false,
+			_ => {
+				last_fired.insert(key, now);
+				true
+			}
+		}
+	}
+
+	/// Send alert to the webhook.
+	async fn send(url: String, payload: JsonValue) -> Result<(), String> {
+		use isahc::{AsyncReadResponseExt, HttpClient, Request};
+
+		fn map_isahc_err(err: impl std::fmt::Display) -> String {
+			format!("Failed to send alert to the webhook: {}", err)
+		}
+
+		let request = Request::post(&url)
+			.header("Content-Type", "application/json")
+			.body(payload.to_string())
+			.map_err(map_isahc_err)?;
+		let mut response = HttpClient::new()
+			.map_err(map_isahc_err)?
+			.send_async(request)
+			.await
+			.map_err(map_isahc_err)?;
+		if !response.status().is_success() {
+			let response_text = response.text().await.unwrap_or_default();
+			return Err(format!(
+				"Failed to send alert to the webhook: status {}. Response: {:?}",
+				response.status(),
+				response_text,
+			));
+		}
+
+		Ok(())
+	}
+}
+
+/// Fire the alert.
+///
+/// The alert is always logged. If alert sink has been installed, it is also sent to the webhook in
+/// the background.
+pub fn fire(kind: AlertKind, source: impl Into<String>, message: impl Into<String>) {
+	if let Some((url, payload)) = prepare_alert(kind, source.into(), message.into()) {
+		async_std::task::spawn(send_alert(url, payload));
+	}
+}
+
+/// Fire the alert and wait until it is sent to the webhook.
+///
+/// This should be used when the process is going to be aborted right after firing the alert.
+pub async fn fire_and_wait(kind: AlertKind, source: impl Into<String>, message: impl Into<String>) {
+	if let Some((url, payload)) = prepare_alert(kind, source.into(), message.into()) {
+		send_alert(url, payload).await;
+	}
+}
+
+/// Log the alert and prepare webhook URL and payload, if it needs to be sent.
+fn prepare_alert(kind: AlertKind, source: String, message: String) -> Option<(String, JsonValue)> {
+	let alert = Alert { kind, source, message };
+
+	log::error!(
+		target: "bridge",
+		"Alert {} from {}: {}",
+		alert.kind.name(),
+		alert.source,
+		alert.message,
+	);
+
+	let sink = ALERT_SINK.get()?;
+	if !sink.should_send(&alert, Instant::now()) {
+		return None;
+	}
+
+	Some((sink.url.clone(), alert_payload(sink.format, &alert)))
+}
+
+/// Send alert to the webhook, logging error if we have failed.
+async fn send_alert(url: String, payload: JsonValue) {
+	if let Err(error) = AlertSink::send(url, payload).await {
+		log::warn!(target: "bridge", "{}", error);
+	}
+}
+
+/// Prepare webhook payload for the alert.
+fn alert_payload(format: AlertFormat, alert: &Alert) -> JsonValue {
+	let text = format!("[{}] {}: {}", alert.kind.name(), alert.source, alert.message);
+	match format {
+		AlertFormat::Generic => json!({
+			"alert": alert.kind.name(),
+			"source": alert.source,
+			"message": alert.message,
+		}),
+		AlertFormat::Slack => json!({ "text": text }),
+		AlertFormat::Discord => json!({ "content": text }),
+		AlertFormat::Alertmanager => json!([{
+			"labels": {
+				"alertname": alert.kind.name(),
+				"source": alert.source,
+				"severity": "critical",
+			},
+			"annotations": {
+				"description": alert.message,
+			},
+		}]),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn test_alert() -> Alert {
+		Alert {
+			kind: AlertKind::PalletHalted,
+			source: "Millau".into(),
+			message: "GRANDPA pallet is halted".into(),
+		}
+	}
+
+	#[test]
+	fn alert_format_is_parsed() {
+		assert_eq!("Slack".parse(), Ok(AlertFormat::Slack));
+		assert_eq!("alertmanager".parse(), Ok(AlertFormat::Alertmanager));
+		assert!("email".parse::<AlertFormat>().is_err());
+	}
+
+	#[test]
+	fn alert_payload_is_formatted() {
+		assert_eq!(
+			alert_payload(AlertFormat::Slack, &test_alert()),
+			json!({ "text": "[PalletHalted] Millau: GRANDPA pallet is halted" }),
+		);
+		assert_eq!(
+			alert_payload(AlertFormat::Alertmanager, &test_alert())[0]["labels"]["alertname"],
+			json!("PalletHalted"),
+		);
+	}
+
+	#[test]
+	fn same_alert_is_not_sent_too_often() {
+		let sink = AlertSink::new("http://localhost".into(), AlertFormat::Generic);
+		let now = Instant::now();
+		assert!(sink.should_send(&test_alert(), now));
+		assert!(!sink.should_send(&test_alert(), now + MIN_REPEAT_INTERVAL / 2));
+		assert!(sink.should_send(
+			&Alert {
+				source: "Rialto".into(),
+				..test_alert()
+			},
+			now
+		));
+		assert!(sink.should_send(&test_alert(), now + MIN_REPEAT_INTERVAL));
+	}
+}
@@ -29,6 +29,7 @@ pub const MAX_BACKOFF_INTERVAL: Duration = Duration::from_secs(60);
 /// reconnection again.
 pub const CONNECTION_ERROR_DELAY: Duration = Duration::from_secs(10);
 
+pub mod alerts;
 pub mod initialize;
 pub mod metrics;
 pub mod relay_loop;